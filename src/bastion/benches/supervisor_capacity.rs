@@ -0,0 +1,82 @@
+#![feature(test)]
+
+extern crate test;
+
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use test::Bencher;
+
+const SUPERVISORS: usize = 512;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_benchs {
+    use super::*;
+    #[bench]
+    fn launch_supervisor_with_hint(b: &mut Bencher) {
+        tokio_test::block_on(async { _launch_supervisor(b, Some(SUPERVISORS)) });
+    }
+    #[bench]
+    fn launch_supervisor_without_hint(b: &mut Bencher) {
+        tokio_test::block_on(async { _launch_supervisor(b, None) });
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod no_tokio_benchs {
+    use super::*;
+    #[bench]
+    fn launch_supervisor_with_hint(b: &mut Bencher) {
+        _launch_supervisor(b, Some(SUPERVISORS));
+    }
+    #[bench]
+    fn launch_supervisor_without_hint(b: &mut Bencher) {
+        _launch_supervisor(b, None);
+    }
+}
+
+// Launches a supervisor of `SUPERVISORS` supervisors, each of which
+// supervises a children group, and waits for all of them to be
+// started.
+fn launch(hint: Option<usize>) {
+    let started = Arc::new(AtomicUsize::new(0));
+
+    let started_ref = started.clone();
+    let sp = Bastion::supervisor(move |sp| {
+        let sp = match hint {
+            Some(hint) => sp.with_capacity_hint(hint),
+            None => sp,
+        };
+
+        (0..SUPERVISORS).fold(sp, |sp, _| {
+            let started = started_ref.clone();
+            sp.supervisor(move |sp| {
+                sp.children(move |children| {
+                    children.with_exec(move |ctx: BastionContext| {
+                        started.fetch_add(1, Ordering::SeqCst);
+                        async move {
+                            ctx.recv().await?;
+                            Ok(())
+                        }
+                    })
+                })
+            })
+        })
+    })
+    .expect("Couldn't create the supervisor.");
+
+    while started.load(Ordering::SeqCst) < SUPERVISORS {
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    sp.stop().expect("Couldn't stop the supervisor.");
+}
+
+fn _launch_supervisor(b: &mut Bencher, hint: Option<usize>) {
+    Bastion::init();
+    Bastion::start();
+
+    b.iter(|| launch(hint));
+}
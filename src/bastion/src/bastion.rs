@@ -1,23 +1,40 @@
 use crate::broadcast::{Broadcast, Parent};
+use crate::child::Child;
+use crate::child_ref::ChildRef;
 use crate::children::Children;
 use crate::children_ref::ChildrenRef;
 use crate::config::Config;
 use crate::context::{BastionContext, BastionId};
-use crate::envelope::Envelope;
+use crate::dead_letters::DEAD_LETTERS;
+use crate::dependencies::DEPENDENCIES;
+use crate::dispatcher::{Dispatcher, DispatcherType};
+use crate::envelope::{Envelope, SignedMessage};
+use crate::errors::{SpecError, StartError};
+use crate::events::{EventStream, EVENTS, EVENT_STREAM_CAPACITY};
+use crate::health::{Health, Liveness, HEALTH};
+use crate::interceptor::{Interceptor, INTERCEPTORS};
 use crate::message::{BastionMessage, Message};
-use crate::path::BastionPathElement;
+use crate::path::{BastionPath, BastionPathElement};
+use crate::pipeline::{Pipeline, PipelineRef};
+use crate::quiesce::{QuiesceGuard, QUIESCE};
+use crate::rate_limit::{OverloadPolicy, GLOBAL_RATE_LIMIT};
+use crate::shutdown::{ShutdownReport, SHUTDOWN};
+use crate::spec::{TreeSpec, EXECS};
 use crate::supervisor::{Supervisor, SupervisorRef};
-use crate::system::SYSTEM;
+use crate::system::{CONFIG, SYSTEM};
+use crate::topology::{TopologySnapshot, TOPOLOGY};
 
 use core::future::Future;
-use tracing::{debug, trace};
+use futures::channel::oneshot;
+use futures::future;
+use tracing::{debug, error, trace};
 
 use std::fmt::{self, Debug, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 distributed_api! {
-    use std::sync::Arc;
     use crate::distributed::*;
-    use artillery_core::cluster::ap::*;
 }
 
 /// A `struct` allowing to access the system's API to initialize it,
@@ -257,22 +274,861 @@ impl Bastion {
             debug!("Bastion: Hiding backtraces.");
             std::panic::set_hook(Box::new(|_| ()));
         }
+        if config.captures_backtraces() {
+            debug!("Bastion: Capturing the backtraces of children.");
+            Child::install_panic_hook();
+        }
+
+        // NOTE: the system is only initialized once, by the first call.
+        if CONFIG.set(config).is_err() {
+            debug!("Bastion: Already initialized, ignoring the config.");
+        } else if let Some((max_entries, max_age)) = CONFIG.get().and_then(Config::dead_letters) {
+            Bastion::configure_dead_letters(max_entries, max_age);
+        }
+
+        let _ = &SYSTEM;
+    }
+
+    /// Creates a new [`Supervisor`], passes it through the specified
+    /// `init` closure and then sends it to the system for it to
+    /// start supervising children.
+    ///
+    /// This method returns a [`SupervisorRef`] referencing the newly
+    /// created supervisor if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - The closure taking the new [`Supervisor`] as an
+    ///     argument and returning it once configured.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// let sp_ref: SupervisorRef = Bastion::supervisor(|sp| {
+    ///     // Configure the supervisor...
+    ///     sp.with_strategy(SupervisionStrategy::OneForOne)
+    ///     // ...and return it.
+    /// }).expect("Couldn't create the supervisor.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn supervisor<S>(init: S) -> Result<SupervisorRef, ()>
+    where
+        S: FnOnce(Supervisor) -> Supervisor,
+    {
+        debug!("Bastion: Creating supervisor.");
+        let parent = Parent::system();
+        let bcast = Broadcast::new(parent, BastionPathElement::Supervisor(BastionId::new()));
+
+        debug!("Bastion: Initializing Supervisor({}).", bcast.id());
+        let supervisor = Supervisor::new(bcast);
+        let supervisor = init(supervisor);
+        debug!("Supervisor({}): Initialized.", supervisor.id());
+        let supervisor_ref = supervisor.as_ref();
+
+        debug!("Bastion: Deploying Supervisor({}).", supervisor.id());
+        let msg = BastionMessage::deploy_supervisor(supervisor);
+        let envelope = Envelope::new(msg, SYSTEM.path().clone(), SYSTEM.sender().clone());
+        trace!("Bastion: Sending envelope: {:?}", envelope);
+        SYSTEM.sender().unbounded_send(envelope).map_err(|_| ())?;
+
+        Ok(supervisor_ref)
+    }
+
+    /// Creates a new [`Children`], passes it through the specified
+    /// `init` closure and then sends it to the system's default
+    /// supervisor for it to start supervising it.
+    ///
+    /// This methods returns a [`ChildrenRef`] referencing the newly
+    /// created children group it it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// Note that the "system supervisor" is a supervisor created
+    /// by the system at startup.
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - The closure taking the new [`Children`] as an
+    ///     argument and returning it once configured.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// let children_ref: ChildrenRef = Bastion::children(|children| {
+    ///     // Configure the children group...
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // Send and receive messages...
+    ///             let opt_msg: Option<SignedMessage> = ctx.try_recv().await;
+    ///             // ...and return `Ok(())` or `Err(())` when you are done...
+    ///             Ok(())
+    ///
+    ///             // Note that if `Err(())` was returned, the supervisor would
+    ///             // restart the children group.
+    ///         }
+    ///     })
+    ///     // ...and return it.
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn children<C>(init: C) -> Result<ChildrenRef, ()>
+    where
+        C: FnOnce(Children) -> Children,
+    {
+        debug!("Bastion: Creating children group.");
+        SYSTEM.supervisor().children(init)
+    }
+
+    /// Launches the stages of the given [`Pipeline`] as children
+    /// groups supervised by the system's default supervisor, each of
+    /// them forwarding its output to the next one through a
+    /// dispatcher.
+    ///
+    /// This method returns a [`PipelineRef`] allowing to push items
+    /// to the pipeline if it succeeded, or `Err(())` otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `pipeline` - The pipeline to launch.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// let pipeline = Bastion::pipeline(
+    ///     Pipeline::new(Stage::new(|line: &String| line.len()).with_redundancy(4))
+    ///         .then(Stage::new(|len: &usize| println!("{}", len))),
+    /// ).expect("Couldn't launch the pipeline.");
+    ///
+    /// pipeline.push("Hello, world!".to_string());
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Pipeline`]: crate::pipeline::Pipeline
+    /// [`PipelineRef`]: crate::pipeline::PipelineRef
+    pub fn pipeline<I, O>(pipeline: Pipeline<I, O>) -> Result<PipelineRef<I>, ()>
+    where
+        I: Message,
+        O: Message,
+    {
+        debug!("Bastion: Launching pipeline.");
+        SYSTEM.supervisor().pipeline(pipeline)
+    }
+
+    /// Creates a new [`Children`] which will have the given closure
+    /// as action and then sends it to the system's default supervisor.
+    ///
+    /// This method returns a [`ChildrenRef`] referencing the newly created children
+    /// if the creation was successful, otherwise returns an `Err(())`.
+    ///
+    /// Internally this method uses the [`Bastion::children`] and [`Children::with_exec`] methods
+    /// to create a new children.
+    ///
+    /// # Arguments
+    /// * `action` - The closure which gets executed by the child.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// let children_ref: ChildrenRef = Bastion::spawn(|ctx: BastionContext| {
+    ///     async move {
+    ///         // ...
+    ///         Ok(())
+    ///     }
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn spawn<I, F>(action: I) -> Result<ChildrenRef, ()>
+    where
+        I: Fn(BastionContext) -> F + Send + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        Bastion::children(|ch| ch.with_redundancy(1).with_exec(action))
+    }
+
+    /// Runs the future returned by the given closure as a supervised
+    /// root task, blocks until it resolves and returns its output.
+    ///
+    /// The system is started before running the task and stopped once
+    /// it resolved, which makes this method convenient for short-lived
+    /// programs and tests. The system needs to be initialized with
+    /// [`Bastion::init`] or [`Bastion::init_with`] first.
+    ///
+    /// This method returns `Err(())` if the root task couldn't be
+    /// created or if it faulted before resolving.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The closure returning the future to run.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// Bastion::init();
+    ///
+    /// let answer = Bastion::run(|| async { 6 * 7 }).expect("The root task faulted.");
+    /// assert_eq!(answer, 42);
+    ///
+    /// // The system is now stopped.
+    /// # }
+    /// ```
+    pub fn run<I, F, T>(action: I) -> Result<T, ()>
+    where
+        I: FnOnce() -> F + Send + 'static,
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        debug!("Bastion: Running a root task.");
+        let (sender, receiver) = oneshot::channel();
+        // NOTE: the root task only runs once, a restarted element
+        //      finds it taken and stops right away.
+        let task = Mutex::new(Some((action, sender)));
+        let spawned = Bastion::spawn(move |_: BastionContext| {
+            // FIXME: panics?
+            let task = task.lock().unwrap().take();
+            async move {
+                if let Some((action, sender)) = task {
+                    sender.send(action().await).ok();
+                }
+
+                Ok(())
+            }
+        });
+
+        let output = if spawned.is_ok() {
+            Bastion::start();
+            // NOTE: the sender is dropped without sending if the
+            //      root task faulted.
+            crate::executor::run(receiver).map_err(|_| ())
+        } else {
+            Err(())
+        };
+
+        Bastion::stop();
+        Bastion::block_until_stopped();
+        output
+    }
+    distributed_api! {
+        // FIXME!
+        #[allow(missing_docs)]
+        pub fn distributed<C, I, F>(cluster_config: C, action: I) -> Result<ChildrenRef, ()>
+        where
+            C: Into<ClusterConfig>,
+            I: Fn(Arc<DistributedContext>) -> F + Send + Sync + 'static,
+            F: Future<Output = Result<(), ()>> + Send + 'static,
+        {
+            cluster_actor(cluster_config.into(), action)
+        }
+    }
+
+    /// Returns a stream of all the [`SystemEvent`]s emitted by the
+    /// system after this call (supervisors being launched, children
+    /// faulting or being restarted, dead letters, ...).
+    ///
+    /// Every call returns a new, independent stream which receives
+    /// every event. Streams that aren't polled fast enough drop their
+    /// oldest events instead of slowing down the system.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use futures::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    ///     # Bastion::init();
+    ///     #
+    /// let mut events = Bastion::event_stream();
+    ///
+    /// Bastion::spawn(|ctx: BastionContext| async move {
+    ///     Err(())
+    /// }).expect("Couldn't create the children group.");
+    ///
+    ///     # Bastion::start();
+    /// run!(async {
+    ///     while let Some(event) = events.next().await {
+    ///         if let SystemEvent::ChildFaulted { id, .. } = event {
+    ///             println!("Child({}) faulted.", id);
+    ///             break;
+    ///         }
+    ///     }
+    /// });
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`SystemEvent`]: crate::events::SystemEvent
+    pub fn event_stream() -> EventStream {
+        debug!("Bastion: Subscribing to the system's events.");
+        EVENTS.subscribe(EVENT_STREAM_CAPACITY)
+    }
+
+    /// Returns a snapshot of the current shape of the supervision
+    /// tree: every launched supervisor with the supervisors and
+    /// children groups it supervises (their names, redundancy, amount
+    /// of launched elements and attached dispatchers).
+    ///
+    /// The snapshot is plain data that can be serialized (e.g. to
+    /// JSON) for tooling.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    /// let topology: TopologySnapshot = Bastion::topology();
+    /// for supervisor in topology.supervisors.iter() {
+    ///     println!("Supervisor({})", supervisor.id);
+    /// }
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn topology() -> TopologySnapshot {
+        debug!("Bastion: Taking a snapshot of the topology.");
+        TOPOLOGY.snapshot()
+    }
+
+    /// Suspends the delivery of messages to every element of the
+    /// system until the returned guard is dropped, e.g. to take a
+    /// consistent snapshot of the system (see [`Bastion::topology`]).
+    ///
+    /// The messages currently being handled are handled until the
+    /// elements try to receive another one, while the messages sent
+    /// meanwhile are kept in the mailboxes of their receivers, which
+    /// receive them in order once every guard returned by this method
+    /// was dropped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    /// let guard: QuiesceGuard = Bastion::quiesce();
+    /// // No element receives a message until the guard is dropped...
+    /// let topology = Bastion::topology();
+    /// drop(guard);
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn quiesce() -> QuiesceGuard {
+        debug!("Bastion: Quiescing.");
+        QUIESCE.guard()
+    }
+
+    /// Registers the given exec closure with the given name, allowing
+    /// the children groups of the specs built with
+    /// [`Bastion::from_spec`] to reference it.
+    ///
+    /// Registering another closure with the same name replaces the
+    /// previous one for the groups built afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name the children specs reference the closure
+    ///     with.
+    /// * `init` - The closure the elements of the groups referencing
+    ///     it are created with (see [`Children::with_exec`]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    /// Bastion::register_exec("echo", |ctx: BastionContext| async move {
+    ///     loop {
+    ///         let msg = ctx.recv().await?;
+    ///         println!("{:?}", msg);
+    ///     }
+    /// });
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn register_exec<I, F>(name: impl Into<String>, init: I)
+    where
+        I: Fn(BastionContext) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        let name = name.into();
+        debug!("Bastion: Registering exec closure: {}", name);
+        EXECS.register(name, init);
+    }
+
+    /// Builds the supervision tree described by the given spec, whose
+    /// children groups reference exec closures registered with
+    /// [`Bastion::register_exec`], and sends its top-level supervisors
+    /// to the system.
+    ///
+    /// This method returns the [`SupervisorRef`]s referencing the
+    /// top-level supervisors, in the order of the spec, or an error if
+    /// the spec references an exec closure that wasn't registered (in
+    /// which case nothing is built) or a supervisor couldn't be
+    /// launched.
+    ///
+    /// The tree of a running system can be described by a spec with
+    /// [`TopologySnapshot::to_spec`].
+    ///
+    /// # Arguments
+    ///
+    /// * `spec` - The spec describing the supervision tree to build.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    /// Bastion::register_exec("echo", |ctx: BastionContext| async move {
+    ///     loop {
+    ///         let msg = ctx.recv().await?;
+    ///         println!("{:?}", msg);
+    ///     }
+    /// });
+    ///
+    /// let spec: TreeSpec = serde_json::from_str(r#"{
+    ///     "supervisors": [{
+    ///         "strategy": "OneForAll",
+    ///         "children": [{ "name": "echoes", "exec": "echo", "redundancy": 2 }]
+    ///     }]
+    /// }"#).expect("Couldn't parse the spec.");
+    ///
+    /// let supervisors = Bastion::from_spec(spec).expect("Couldn't build the spec.");
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`TopologySnapshot::to_spec`]: crate::topology::TopologySnapshot::to_spec
+    pub fn from_spec(spec: TreeSpec) -> Result<Vec<SupervisorRef>, SpecError> {
+        debug!("Bastion: Building spec: {:?}", spec);
+        spec.validate()?;
+
+        spec.supervisors
+            .iter()
+            .map(|supervisor| {
+                Bastion::supervisor(|sp| supervisor.build(sp)).map_err(|_| SpecError::Launch)
+            })
+            .collect()
+    }
+
+    /// Returns the current health of the system, meant to answer
+    /// liveness and readiness probes (e.g. from Kubernetes):
+    /// * the system is alive until it is stopped or killed.
+    /// * the system is ready while every children group is running
+    ///   at least as many elements as it was configured with, which
+    ///   isn't the case before it is started or while elements that
+    ///   faulted are waiting to be restarted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    /// let health: Health = Bastion::health();
+    /// if !health.is_ready() {
+    ///     println!("Not ready yet: {:?}", health.readiness);
+    /// }
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn health() -> Health {
+        let liveness = if SYSTEM.is_running() {
+            Liveness::Alive
+        } else {
+            Liveness::Dead
+        };
+        let readiness = HEALTH.readiness();
+        trace!(
+            "Bastion: Health: liveness={:?}, readiness={:?}",
+            liveness,
+            readiness
+        );
+
+        Health {
+            liveness,
+            readiness,
+        }
+    }
+
+    /// Sets how many of the messages received by the dead letters
+    /// are kept and for how long (1024 messages kept for a minute by
+    /// default).
+    ///
+    /// Once more than `max_entries` dead letters are kept, the oldest
+    /// ones are dropped, as are the ones older than `max_age`, which
+    /// is counted by [`Bastion::dropped_dead_letters`].
+    ///
+    /// # Arguments
+    ///
+    /// * `max_entries` - The maximum amount of dead letters kept.
+    /// * `max_age` - How long a dead letter is kept.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    /// Bastion::configure_dead_letters(100, Duration::from_secs(10));
+    ///
+    /// // Later...
+    /// for dead_letter in Bastion::drain_dead_letters() {
+    ///     println!("{} couldn't deliver {:?}", dead_letter.sender(), dead_letter);
+    /// }
+    /// println!("{} dead letters were dropped", Bastion::dropped_dead_letters());
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn configure_dead_letters(max_entries: usize, max_age: Duration) {
+        debug!(
+            "Bastion: Configuring dead letters: max_entries={}, max_age={:?}",
+            max_entries, max_age
+        );
+        DEAD_LETTERS.configure(max_entries, max_age);
+    }
+
+    /// Removes and returns the dead letters kept (see
+    /// [`Bastion::configure_dead_letters`]), oldest first.
+    ///
+    /// The dead letters are returned in the exact order they were
+    /// received by the dead letters, so that replaying them in this
+    /// order is deterministic. The dead letters received while
+    /// draining are kept for the next drain.
+    pub fn drain_dead_letters() -> Vec<SignedMessage> {
+        DEAD_LETTERS.drain()
+    }
+
+    /// Returns the amount of dead letters currently kept (see
+    /// [`Bastion::configure_dead_letters`]).
+    pub fn dead_letters_count() -> usize {
+        DEAD_LETTERS.len()
+    }
+
+    /// Returns the amount of dead letters dropped because there were
+    /// too many of them or because they were too old (see
+    /// [`Bastion::configure_dead_letters`]).
+    pub fn dropped_dead_letters() -> u64 {
+        DEAD_LETTERS.dropped()
+    }
+
+    /// Caps the rate at which the elements of the whole system receive
+    /// messages to `per_second` messages per second, allowing bursts
+    /// of up to `per_second` messages.
+    ///
+    /// The elements wait for the rate limit to let the messages
+    /// through (see [`OverloadPolicy::Backpressure`]); use
+    /// [`Bastion::set_global_rate_limit_with`] to shed them instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `per_second` - The amount of messages the elements of the
+    ///     system can receive every second.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    /// Bastion::set_global_rate_limit(1_000);
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`OverloadPolicy::Backpressure`]: crate::prelude::OverloadPolicy::Backpressure
+    pub fn set_global_rate_limit(per_second: u32) {
+        Bastion::set_global_rate_limit_with(per_second, OverloadPolicy::Backpressure)
+    }
+
+    /// Caps the rate at which the elements of the whole system receive
+    /// messages to `per_second` messages per second, allowing bursts
+    /// of up to `per_second` messages, and handling the messages above
+    /// it as set by `overload`.
+    ///
+    /// # Arguments
+    ///
+    /// * `per_second` - The amount of messages the elements of the
+    ///     system can receive every second.
+    /// * `overload` - Whether the messages above the rate limit wait
+    ///     for it or are dropped and kept with the dead letters.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    /// Bastion::set_global_rate_limit_with(1_000, OverloadPolicy::Shed);
+    ///
+    /// // Later...
+    /// for shed in Bastion::drain_dead_letters() {
+    ///     println!("{:?} was shed", shed);
+    /// }
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn set_global_rate_limit_with(per_second: u32, overload: OverloadPolicy) {
+        debug!(
+            "Bastion: Setting the global rate limit: per_second={}, overload={:?}",
+            per_second, overload
+        );
+        GLOBAL_RATE_LIMIT.set(per_second, overload);
+    }
 
-        let _ = &SYSTEM;
+    /// Removes the cap set by [`Bastion::set_global_rate_limit`] on
+    /// the rate at which the elements of the system receive messages.
+    pub fn clear_global_rate_limit() {
+        debug!("Bastion: Clearing the global rate limit.");
+        GLOBAL_RATE_LIMIT.clear();
     }
 
-    /// Creates a new [`Supervisor`], passes it through the specified
-    /// `init` closure and then sends it to the system for it to
-    /// start supervising children.
+    /// Returns a reference to the child living at the given path, or
+    /// `None` if no live child matches it (e.g. because it stopped or
+    /// because the path doesn't lead to a child).
     ///
-    /// This method returns a [`SupervisorRef`] referencing the newly
-    /// created supervisor if it succeeded, or `Err(())`
-    /// otherwise.
+    /// A child that restarted keeps its path and is resolved again
+    /// once relaunched.
     ///
     /// # Arguments
     ///
-    /// * `init` - The closure taking the new [`Supervisor`] as an
-    ///     argument and returning it once configured.
+    /// * `path` - The path of the child to resolve.
     ///
     /// # Example
     ///
@@ -291,57 +1147,44 @@ impl Bastion {
     /// # }
     /// #
     /// # fn run() {
-    /// # Bastion::init();
-    /// #
-    /// let sp_ref: SupervisorRef = Bastion::supervisor(|sp| {
-    ///     // Configure the supervisor...
-    ///     sp.with_strategy(SupervisionStrategy::OneForOne)
-    ///     // ...and return it.
-    /// }).expect("Couldn't create the supervisor.");
-    /// #
-    /// # Bastion::start();
-    /// # Bastion::stop();
-    /// # Bastion::block_until_stopped();
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // ...
+    ///             # ctx.recv().await?;
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// let path = children_ref.elems()[0].path().clone();
+    /// if let Some(child_ref) = Bastion::resolve(&path) {
+    ///     child_ref.tell_anonymously("A message").ok();
+    /// }
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
-    pub fn supervisor<S>(init: S) -> Result<SupervisorRef, ()>
-    where
-        S: FnOnce(Supervisor) -> Supervisor,
-    {
-        debug!("Bastion: Creating supervisor.");
-        let parent = Parent::system();
-        let bcast = Broadcast::new(parent, BastionPathElement::Supervisor(BastionId::new()));
-
-        debug!("Bastion: Initializing Supervisor({}).", bcast.id());
-        let supervisor = Supervisor::new(bcast);
-        let supervisor = init(supervisor);
-        debug!("Supervisor({}): Initialized.", supervisor.id());
-        let supervisor_ref = supervisor.as_ref();
-
-        debug!("Bastion: Deploying Supervisor({}).", supervisor.id());
-        let msg = BastionMessage::deploy_supervisor(supervisor);
-        let envelope = Envelope::new(msg, SYSTEM.path().clone(), SYSTEM.sender().clone());
-        trace!("Bastion: Sending envelope: {:?}", envelope);
-        SYSTEM.sender().unbounded_send(envelope).map_err(|_| ())?;
-
-        Ok(supervisor_ref)
+    pub fn resolve(path: &BastionPath) -> Option<ChildRef> {
+        debug!("Bastion: Resolving the child at {}.", path);
+        SYSTEM.dispatcher().resolve(path)
     }
 
-    /// Creates a new [`Children`], passes it through the specified
-    /// `init` closure and then sends it to the system's default
-    /// supervisor for it to start supervising it.
-    ///
-    /// This methods returns a [`ChildrenRef`] referencing the newly
-    /// created children group it it succeeded, or `Err(())`
-    /// otherwise.
+    /// Adds an interceptor at the end of the system's chain of
+    /// interceptors, which see every message before it is delivered
+    /// to the mailbox of an element and can let it through,
+    /// transform it or drop it.
     ///
-    /// Note that the "system supervisor" is a supervisor created
-    /// by the system at startup.
+    /// Interceptors run in the order they were added, a message
+    /// dropped by an interceptor isn't seen by the next ones.
     ///
     /// # Arguments
     ///
-    /// * `init` - The closure taking the new [`Children`] as an
-    ///     argument and returning it once configured.
+    /// * `interceptor` - The interceptor to add.
     ///
     /// # Example
     ///
@@ -360,48 +1203,39 @@ impl Bastion {
     /// # }
     /// #
     /// # fn run() {
-    /// # Bastion::init();
-    /// #
-    /// let children_ref: ChildrenRef = Bastion::children(|children| {
-    ///     // Configure the children group...
-    ///     children.with_exec(|ctx: BastionContext| {
-    ///         async move {
-    ///             // Send and receive messages...
-    ///             let opt_msg: Option<SignedMessage> = ctx.try_recv().await;
-    ///             // ...and return `Ok(())` or `Err(())` when you are done...
-    ///             Ok(())
-    ///
-    ///             // Note that if `Err(())` was returned, the supervisor would
-    ///             // restart the children group.
-    ///         }
-    ///     })
-    ///     // ...and return it.
-    /// }).expect("Couldn't create the children group.");
-    /// #
-    /// # Bastion::start();
-    /// # Bastion::stop();
-    /// # Bastion::block_until_stopped();
+    ///     # Bastion::init();
+    /// // Drops the messages sent anonymously.
+    /// Bastion::add_interceptor(|msg: SignedMessage, _: &BastionPath| {
+    ///     if msg.signature().path().is_dead_letters() {
+    ///         None
+    ///     } else {
+    ///         Some(msg)
+    ///     }
+    /// });
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
-    pub fn children<C>(init: C) -> Result<ChildrenRef, ()>
-    where
-        C: FnOnce(Children) -> Children,
-    {
-        debug!("Bastion: Creating children group.");
-        SYSTEM.supervisor().children(init)
+    pub fn add_interceptor<I: Interceptor>(interceptor: I) {
+        debug!("Bastion: Adding an interceptor.");
+        INTERCEPTORS.add(Box::new(interceptor));
     }
 
-    /// Creates a new [`Children`] which will have the given closure
-    /// as action and then sends it to the system's default supervisor.
+    /// Registers a dispatcher at runtime, allowing to broadcast
+    /// messages to its group without rebuilding the supervision tree.
     ///
-    /// This method returns a [`ChildrenRef`] referencing the newly created children
-    /// if the creation was successful, otherwise returns an `Err(())`.
+    /// The children groups declaring a dispatcher of the same type
+    /// (using [`Children::with_dispatcher`]) that are launched
+    /// afterwards join it.
     ///
-    /// Internally this method uses the [`Bastion::children`] and [`Children::with_exec`] methods
-    /// to create a new children.
+    /// This method returns `Err(())` if a dispatcher of the same type
+    /// was already registered.
     ///
     /// # Arguments
-    /// * `action` - The closure which gets executed by the child.
+    ///
+    /// * `dispatcher` - The dispatcher to register.
     ///
     /// # Example
     ///
@@ -420,36 +1254,61 @@ impl Bastion {
     /// # }
     /// #
     /// # fn run() {
-    /// # Bastion::init();
-    /// #
-    /// let children_ref: ChildrenRef = Bastion::spawn(|ctx: BastionContext| {
-    ///     async move {
-    ///         // ...
-    ///         Ok(())
-    ///     }
-    /// }).expect("Couldn't create the children group.");
-    /// #
-    /// # Bastion::start();
-    /// # Bastion::stop();
-    /// # Bastion::block_until_stopped();
+    ///     # Bastion::init();
+    ///     # Bastion::start();
+    ///     #
+    /// let dispatcher_type = DispatcherType::Named("workers".to_string());
+    /// Bastion::register_dispatcher(Dispatcher::with_type(dispatcher_type.clone()))
+    ///     .expect("Couldn't register the dispatcher.");
+    ///
+    /// // Broadcast messages to the group...
+    ///
+    /// Bastion::remove_dispatcher(&dispatcher_type)
+    ///     .expect("Couldn't remove the dispatcher.");
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
-    pub fn spawn<I, F>(action: I) -> Result<ChildrenRef, ()>
-    where
-        I: Fn(BastionContext) -> F + Send + 'static,
-        F: Future<Output = Result<(), ()>> + Send + 'static,
-    {
-        Bastion::children(|ch| ch.with_redundancy(1).with_exec(action))
+    ///
+    /// [`Children::with_dispatcher`]: crate::children::Children::with_dispatcher
+    pub fn register_dispatcher(dispatcher: Dispatcher) -> Result<(), ()> {
+        let dispatcher_type = dispatcher.dispatcher_type();
+        debug!("Bastion: Registering dispatcher: {:?}", dispatcher_type);
+        let global_dispatcher = SYSTEM.dispatcher();
+        if global_dispatcher.dispatchers.contains_key(&dispatcher_type) {
+            debug!(
+                "Bastion: Dispatcher({:?}) is already registered.",
+                dispatcher_type
+            );
+            return Err(());
+        }
+
+        global_dispatcher
+            .register_dispatcher(&Arc::new(Box::new(dispatcher)))
+            .map_err(|_| ())
     }
-    distributed_api! {
-        // FIXME!
-        #[allow(missing_docs)]
-        pub fn distributed<I, F>(cluster_config: &'static ArtilleryAPClusterConfig, action: I) -> Result<ChildrenRef, ()>
-        where
-            I: Fn(Arc<DistributedContext>) -> F + Send + Sync + 'static,
-            F: Future<Output = Result<(), ()>> + Send + 'static,
-        {
-            cluster_actor(cluster_config, action)
+
+    /// Removes a dispatcher, which was either registered using
+    /// [`Bastion::register_dispatcher`] or declared by a children
+    /// group.
+    ///
+    /// The messages broadcasted to its group afterwards are routed
+    /// to the dead letters.
+    ///
+    /// This method returns `Err(())` if no dispatcher of this type
+    /// was registered.
+    ///
+    /// # Arguments
+    ///
+    /// * `dispatcher_type` - The type of the dispatcher to remove.
+    ///
+    /// See [`Bastion::register_dispatcher`] for an example.
+    pub fn remove_dispatcher(dispatcher_type: &DispatcherType) -> Result<(), ()> {
+        debug!("Bastion: Removing dispatcher: {:?}", dispatcher_type);
+        match SYSTEM.dispatcher().remove_dispatcher_type(dispatcher_type) {
+            Ok(true) => Ok(()),
+            _ => Err(()),
         }
     }
 
@@ -457,6 +1316,9 @@ impl Bastion {
     /// the root-level supervisors and their supervised children and
     /// supervisors, etc.
     ///
+    /// Every element receives the messages broadcasted by the same
+    /// caller in the order they were broadcasted.
+    ///
     /// This method returns `()` if it succeeded, or `Err(msg)`
     /// otherwise.
     ///
@@ -524,6 +1386,10 @@ impl Bastion {
     /// Sends a message to the system to tell it to start
     /// handling messages and running children.
     ///
+    /// The system isn't started if the dependencies declared with
+    /// [`SupervisorRef::depends_on`] form a cycle, which is logged;
+    /// use [`Bastion::try_start`] to get the error instead.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -555,17 +1421,75 @@ impl Bastion {
     /// # }
     /// ```
     pub fn start() {
+        if let Err(err) = Bastion::try_start() {
+            error!("Bastion: Couldn't start: {}", err);
+        }
+    }
+
+    /// Checks that the dependencies declared between supervisors with
+    /// [`SupervisorRef::depends_on`] don't form a cycle, which would
+    /// make stopping them in order impossible, then sends a message to
+    /// the system to tell it to start handling messages and running
+    /// children.
+    ///
+    /// This method returns `()` if it succeeded, or a
+    /// [`StartError::DependencyCycle`] naming the supervisors forming
+    /// the cycle otherwise, in which case the system isn't started.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    ///
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// Bastion::init();
+    ///
+    /// let storage = Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
+    /// let api = Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
+    /// api.depends_on(&storage);
+    ///
+    /// Bastion::try_start().expect("Couldn't start the system.");
+    /// #
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`StartError::DependencyCycle`]: crate::errors::StartError::DependencyCycle
+    pub fn try_start() -> Result<(), StartError> {
+        if let Some(cycle) = DEPENDENCIES.find_cycle() {
+            let cycle = cycle.iter().map(|path| (**path).clone()).collect();
+            return Err(StartError::DependencyCycle(cycle));
+        }
+
         debug!("Bastion: Starting.");
         let msg = BastionMessage::start();
         let envelope = Envelope::from_dead_letters(msg);
         trace!("Bastion: Sending envelope: {:?}", envelope);
         // FIXME: Err(Error)
         SYSTEM.sender().unbounded_send(envelope).ok();
+
+        Ok(())
     }
 
     /// Sends a message to the system to tell it to stop
     /// every running children groups and supervisors.
     ///
+    /// Supervisors stop their supervised elements one at a time,
+    /// in the reverse of the order they were added in, so that an
+    /// element is only stopped once the ones added after it are.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -606,8 +1530,79 @@ impl Bastion {
         SYSTEM.sender().unbounded_send(envelope).ok();
     }
 
+    /// Sends a message to the system to tell it to stop every
+    /// running children groups and supervisors, like [`Bastion::stop`],
+    /// then waits up to `timeout` for their elements to stop and
+    /// returns a report of which ones did, keyed by their path.
+    ///
+    /// An element that is still running once `timeout` elapsed (e.g.
+    /// because it is blocking its thread) is reported as
+    /// [`ShutdownStatus::TimedOut`], while one that faulted or
+    /// panicked meanwhile is reported as [`ShutdownStatus::Errored`].
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The maximum amount of time to wait for the
+    ///     elements to stop.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// Bastion::init();
+    ///
+    /// // Use bastion, spawn children and supervisors...
+    ///
+    /// Bastion::start();
+    ///
+    /// let report = Bastion::stop_with_timeout(Duration::from_secs(5));
+    /// for path in report.timed_out() {
+    ///     println!("{} didn't stop in time", path);
+    /// }
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ShutdownStatus::TimedOut`]: crate::shutdown::ShutdownStatus::TimedOut
+    /// [`ShutdownStatus::Errored`]: crate::shutdown::ShutdownStatus::Errored
+    pub fn stop_with_timeout(timeout: Duration) -> ShutdownReport {
+        debug!("Bastion: Stopping (timeout={:?}).", timeout);
+        let deadline = Instant::now() + timeout;
+        SHUTDOWN.begin();
+        Bastion::stop();
+
+        SHUTDOWN.report(deadline)
+    }
+
     /// Sends a message to the system to tell it to kill every
-    /// running children groups and supervisors
+    /// running children groups and supervisors, cancelling their
+    /// tasks without waiting for them to stop, and marks the system
+    /// as stopped right away.
+    ///
+    /// Unlike [`Bastion::stop`], the elements aren't given the chance
+    /// to stop on their own, so that a stuck element can't make the
+    /// shutdown hang, and a stop which is already in progress is cut
+    /// short. An element's task is cancelled once it yields, so an
+    /// element blocking its thread without ever yielding can't be
+    /// killed.
+    ///
+    /// This can be called from any thread, including from the
+    /// system's own tasks, and calling it again once the system was
+    /// killed does nothing.
     ///
     /// # Example
     ///
@@ -646,13 +1641,9 @@ impl Bastion {
         // FIXME: Err(Error)
         SYSTEM.sender().unbounded_send(envelope).ok();
 
-        let handle = SYSTEM.handle();
-        let system = crate::executor::run(async { handle.lock().await.take() });
-        if let Some(system) = system {
-            debug!("Bastion: Cancelling system handle.");
-            system.cancel();
-        }
-
+        // NOTE: the system cancels the supervisors' tasks on its own,
+        //      and isn't cancelled itself, since it wouldn't get to
+        //      handle the message otherwise.
         SYSTEM.notify_stopped();
     }
 
@@ -695,6 +1686,46 @@ impl Bastion {
         debug!("Bastion: Blocking until system is stopped.");
         SYSTEM.wait_until_stopped();
     }
+
+    /// Returns a future resolving once the system is stopped (either
+    /// by calling [`Bastion::stop`] or [`Bastion::kill`]), after the
+    /// same teardown [`Bastion::block_until_stopped`] waits for.
+    ///
+    /// Unlike [`Bastion::block_until_stopped`], this doesn't block
+    /// the current thread, so that it can be awaited from within an
+    /// asynchronous context.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();
+    /// # }
+    /// #
+    /// # fn run() {
+    /// use bastion::prelude::*;
+    ///
+    /// Bastion::init();
+    /// Bastion::start();
+    ///
+    /// # Bastion::stop();
+    /// run!(async {
+    ///     Bastion::stopped().await;
+    ///     // The system is now stopped...
+    /// });
+    /// # }
+    /// ```
+    pub fn stopped() -> impl Future<Output = ()> {
+        debug!("Bastion: Waiting until system is stopped.");
+        future::poll_fn(|cx| SYSTEM.poll_stopped(cx))
+    }
 }
 
 impl Debug for Bastion {
@@ -3,7 +3,7 @@ use crate::context::BastionId;
 use crate::envelope::Envelope;
 use crate::message::BastionMessage;
 use crate::path::{BastionPath, BastionPathElement};
-use crate::supervisor::SupervisorRef;
+use crate::supervisor::{FaultReason, SupervisorRef};
 use crate::system::SYSTEM;
 use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use futures::prelude::*;
@@ -111,6 +111,26 @@ impl Broadcast {
         self.children.remove(id);
     }
 
+    /// Registers the child with the given identifier and sender, which
+    /// was handed over by another parent.
+    pub(crate) fn adopt(&mut self, id: BastionId, sender: Sender) {
+        self.children.insert(id, sender);
+    }
+
+    /// Unregisters the child with the given identifier, returning its
+    /// sender so that it can be handed over to another parent.
+    pub(crate) fn take_child(&mut self, id: &BastionId) -> Option<Sender> {
+        self.children.remove(id)
+    }
+
+    /// Sets the parent the stopped, faulted and restart messages are
+    /// sent to.
+    ///
+    /// The path doesn't change, and keeps naming the previous parent.
+    pub(crate) fn set_parent(&mut self, parent: Parent) {
+        self.parent = parent;
+    }
+
     pub(crate) fn clear_children(&mut self) {
         self.children.clear();
     }
@@ -156,10 +176,10 @@ impl Broadcast {
         self.send_parent(env).ok();
     }
 
-    pub(crate) fn faulted(&mut self) {
+    pub(crate) fn faulted(&mut self, reason: FaultReason) {
         self.kill_children();
 
-        let msg = BastionMessage::faulted(self.id().clone());
+        let msg = BastionMessage::faulted(self.id().clone(), reason);
         let env = Envelope::new(msg, self.path.clone(), self.sender.clone());
         // FIXME: Err(msg)
         self.send_parent(env).ok();
@@ -187,6 +207,22 @@ impl Broadcast {
         }
     }
 
+    // Sends the envelope to every child, returning the identifiers
+    // of the ones whose mailbox it was enqueued in.
+    pub(crate) fn send_children_receipted(&self, env: Envelope) -> Vec<BastionId> {
+        let mut delivered = Vec::with_capacity(self.children.len());
+        for (id, child) in self.children.iter() {
+            // FIXME: Err(Error) if None
+            if let Some(env) = env.try_clone() {
+                if child.unbounded_send(env).is_ok() {
+                    delivered.push(id.clone());
+                }
+            }
+        }
+
+        delivered
+    }
+
     pub(crate) fn send_self(&self, env: Envelope) {
         // FIXME: handle errors
         self.sender.unbounded_send(env).ok();
@@ -0,0 +1,108 @@
+//!
+//! A semaphore capping how many elements of a children group can be
+//! executing at once.
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+#[derive(Debug)]
+/// Holds up to `max_concurrency` permits, which the elements of a
+/// group acquire before polling their future and release right after.
+pub(crate) struct Bulkhead {
+    state: Mutex<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    available: usize,
+    // The tasks waiting for a permit, which are all woken up when
+    // one is released.
+    waiters: Vec<Waker>,
+}
+
+/// A permit of a [`Bulkhead`], released once dropped.
+pub(crate) struct Permit(Arc<Bulkhead>);
+
+/// A future resolving to a [`Permit`] once one is available.
+pub(crate) struct Acquire(Arc<Bulkhead>);
+
+impl Bulkhead {
+    pub(crate) fn new(max_concurrency: usize) -> Self {
+        assert!(
+            max_concurrency > 0,
+            "The bulkhead must allow some concurrency."
+        );
+
+        Bulkhead {
+            state: Mutex::new(State {
+                available: max_concurrency,
+                waiters: Vec::new(),
+            }),
+        }
+    }
+
+    /// Returns a future resolving to a permit once one is available.
+    pub(crate) fn acquire(self: &Arc<Self>) -> Acquire {
+        Acquire(self.clone())
+    }
+
+    fn release(&self) {
+        // FIXME: panics?
+        let mut state = self.state.lock().unwrap();
+        state.available += 1;
+        for waker in state.waiters.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+impl Future for Acquire {
+    type Output = Permit;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        // FIXME: panics?
+        let mut state = self.0.state.lock().unwrap();
+        if state.available > 0 {
+            state.available -= 1;
+            return Poll::Ready(Permit(self.0.clone()));
+        }
+
+        if !state
+            .waiters
+            .iter()
+            .any(|waker| waker.will_wake(cx.waker()))
+        {
+            state.waiters.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.0.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{executor, poll};
+
+    #[test]
+    fn permits_are_capped_and_released() {
+        let bulkhead = Arc::new(Bulkhead::new(2));
+
+        executor::block_on(async {
+            let first = poll!(bulkhead.acquire());
+            let second = poll!(bulkhead.acquire());
+            assert!(first.is_ready());
+            assert!(second.is_ready());
+            assert!(poll!(bulkhead.acquire()).is_pending());
+
+            drop(first);
+            assert!(poll!(bulkhead.acquire()).is_ready());
+        });
+    }
+}
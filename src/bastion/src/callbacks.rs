@@ -1,3 +1,5 @@
+use crate::context::BastionId;
+use crate::supervisor::FaultReason;
 use std::fmt::{self, Debug, Formatter};
 use std::sync::Arc;
 
@@ -65,6 +67,7 @@ pub struct Callbacks {
     before_restart: Option<Arc<dyn Fn() + Send + Sync>>,
     after_restart: Option<Arc<dyn Fn() + Send + Sync>>,
     after_stop: Option<Arc<dyn Fn() + Send + Sync>>,
+    on_fault: Option<Arc<dyn Fn(&BastionId, &FaultReason) + Send + Sync>>,
 }
 
 impl Callbacks {
@@ -485,6 +488,59 @@ impl Callbacks {
         self
     }
 
+    /// Sets the method that will get called by a [`Supervisor`] when
+    /// an element of one of its children groups faulted, with the
+    /// identifier of the element and the reason why it faulted,
+    /// before deciding whether to restart it.
+    ///
+    /// # Arguments
+    ///
+    /// * `on_fault` - The method to call.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::supervisor(|sp| {
+    ///     let callbacks = Callbacks::new().with_on_fault(|id, reason| match reason {
+    ///         FaultReason::Panicked(msg) => println!("Child({}) panicked: {}", id, msg),
+    ///         _ => println!("Child({}) faulted: {:?}", id, reason),
+    ///     });
+    ///
+    ///     sp.with_callbacks(callbacks)
+    /// }).expect("Couldn't create the supervisor.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Supervisor`]: crate::supervisor::Supervisor
+    pub fn with_on_fault<C>(mut self, on_fault: C) -> Self
+    where
+        C: Fn(&BastionId, &FaultReason) + Send + Sync + 'static,
+    {
+        let on_fault = Arc::new(on_fault);
+        self.on_fault = Some(on_fault);
+        self
+    }
+
     /// Returns whether a callback was defined using [`with_before_start`].
     ///
     /// # Example
@@ -557,6 +613,24 @@ impl Callbacks {
         self.after_stop.is_some()
     }
 
+    /// Returns whether a callback was defined using [`with_on_fault`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// let callbacks = Callbacks::new()
+    ///     .with_on_fault(|id, reason| println!("Child({}) faulted: {:?}", id, reason));
+    ///
+    /// assert!(callbacks.has_on_fault());
+    /// ```
+    ///
+    /// [`with_on_fault`]: Self::with_on_fault
+    pub fn has_on_fault(&self) -> bool {
+        self.on_fault.is_some()
+    }
+
     pub(crate) fn before_start(&self) {
         if let Some(before_start) = &self.before_start {
             before_start()
@@ -590,6 +664,12 @@ impl Callbacks {
             after_stop()
         }
     }
+
+    pub(crate) fn on_fault(&self, id: &BastionId, reason: &FaultReason) {
+        if let Some(on_fault) = &self.on_fault {
+            on_fault(id, reason)
+        }
+    }
 }
 
 impl Debug for Callbacks {
@@ -599,6 +679,7 @@ impl Debug for Callbacks {
             .field("before_restart", &self.before_start.is_some())
             .field("after_restart", &self.before_start.is_some())
             .field("after_stop", &self.before_start.is_some())
+            .field("on_fault", &self.on_fault.is_some())
             .finish()
     }
 }
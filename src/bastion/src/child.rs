@@ -1,30 +1,50 @@
 //!
 //! Child is a element of Children group executing user-defined computation
 use crate::broadcast::Broadcast;
+use crate::bulkhead::Bulkhead;
 use crate::callbacks::{CallbackType, Callbacks};
 use crate::child_ref::ChildRef;
-use crate::context::{BastionContext, BastionId, ContextState};
-use crate::envelope::Envelope;
-use crate::message::BastionMessage;
+use crate::context::{BastionContext, BastionId, ContextState, CANCELLATION_TIMEOUT};
+use crate::envelope::{Envelope, RefAddr, SignedMessage};
+use crate::executor::{self, spawner, Spawner};
+use crate::health::HEALTH;
+use crate::interceptor::INTERCEPTORS;
+use crate::message::{BastionMessage, Msg};
 use crate::prelude::ChildrenRef;
 #[cfg(feature = "scaling")]
 use crate::resizer::ActorGroupStats;
+use crate::shutdown::{ShutdownGuard, SHUTDOWN};
+use crate::supervisor::FaultReason;
 use crate::system::SYSTEM;
+use crate::watch::{DownReason, WATCHES};
 use anyhow::Result as AnyResult;
 
-use bastion_executor::pool;
 use futures::pending;
 use futures::poll;
 use futures::prelude::*;
+use futures_timer::Delay;
 use lightproc::prelude::*;
 use lightproc::proc_state::EmptyProcState;
+use std::any::Any;
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::cell::RefCell;
 use std::fmt::{self, Debug, Formatter};
 use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, Once};
 use std::task::{Context, Poll};
+use std::time::Duration;
 use tracing::{debug, error, trace, warn};
 
+static PANIC_HOOK: Once = Once::new();
+
+thread_local! {
+    // The backtrace of the last panic of this thread, if backtraces
+    // are enabled.
+    static BACKTRACE: RefCell<Option<String>> = RefCell::new(None);
+}
+
 pub(crate) struct Init(pub(crate) Box<dyn Fn(BastionContext) -> Exec + Send>);
 pub(crate) struct Exec(pub(crate) Pin<Box<dyn Future<Output = Result<(), ()>> + Send>>);
 
@@ -48,6 +68,35 @@ pub(crate) struct Child {
     // A shortcut for accessing to this actor by others.
     child_ref: ChildRef,
     started: bool,
+    // The drain timeout of the child once it was told to stop
+    // gracefully, in which case it won't accept new messages and
+    // will stop once the messages of its mailbox were handled.
+    draining: Option<Delay>,
+    // The cancellation timeout of the child once it was told to
+    // stop while its future was waiting to be cancelled, in which
+    // case it will stop once its future returned.
+    cancelling: Option<Delay>,
+    // The bulkhead shared by the elements of the group, whose
+    // permit is needed to poll the future, if any.
+    bulkhead: Option<Arc<Bulkhead>>,
+    // The launch timeout of the child, until its future reaches its
+    // running state, if its group has one.
+    launching: Option<Delay>,
+    // The last panic of the child's future, which is taken when
+    // reporting the fault to the parent.
+    panic: Arc<Mutex<Option<CaughtPanic>>>,
+    // Reports to the shutdown tracker when the child exits,
+    // whether it returned or was cancelled.
+    shutdown: ShutdownGuard,
+}
+
+#[derive(Debug)]
+// A panic of a child's future, caught when polling it.
+struct CaughtPanic {
+    message: String,
+    // Only captured if the system captures them and backtraces are
+    // enabled (see `Config::capture_backtraces`).
+    backtrace: Option<String>,
 }
 
 impl Init {
@@ -78,6 +127,12 @@ impl Child {
         debug!("Child({}): Initializing.", bcast.id());
         let pre_start_msgs = Vec::new();
         let started = false;
+        let draining = None;
+        let cancelling = None;
+        let bulkhead = None;
+        let launching = None;
+        let panic = Arc::new(Mutex::new(None));
+        let shutdown = SHUTDOWN.register(bcast.id().clone(), bcast.path().clone());
 
         Child {
             bcast,
@@ -87,9 +142,25 @@ impl Child {
             pre_start_msgs,
             child_ref,
             started,
+            draining,
+            cancelling,
+            bulkhead,
+            launching,
+            panic,
+            shutdown,
         }
     }
 
+    pub(crate) fn with_bulkhead(mut self, bulkhead: Option<Arc<Bulkhead>>) -> Self {
+        self.bulkhead = bulkhead;
+        self
+    }
+
+    pub(crate) fn with_launch_timeout(mut self, launch_timeout: Option<Duration>) -> Self {
+        self.launching = launch_timeout.map(Delay::new);
+        self
+    }
+
     fn stack(&self) -> ProcStack {
         trace!("Child({}): Creating ProcStack.", self.id());
         let id = self.bcast.id().clone();
@@ -100,10 +171,26 @@ impl Child {
 
         let parent_inner = self.bcast.parent().clone().into_children();
         let child_ref_inner = self.child_ref.clone();
+        let panic_inner = self.panic.clone();
 
         // FIXME: with_pid
         ProcStack::default().with_after_panic(move |_state: &mut EmptyProcState| {
-            warn!("Child({}): Panicked.", id);
+            // FIXME: panics?
+            let caught = panic_inner.lock().unwrap().take();
+            let message = match caught {
+                Some(CaughtPanic { message, backtrace }) => {
+                    error!("Child({}): Panicked at {}: {}", id, path, message);
+                    if let Some(backtrace) = backtrace {
+                        error!("Child({}): Panic backtrace:\n{}", id, backtrace);
+                    }
+
+                    message
+                }
+                None => {
+                    warn!("Child({}): Panicked.", id);
+                    String::new()
+                }
+            };
 
             if let Some(parent) = &parent_inner {
                 Self::remove_from_dispatchers(parent, &child_ref_inner);
@@ -114,9 +201,14 @@ impl Child {
                     )
                 });
             }
+            SYSTEM.dispatcher().remove_child(&id);
+            HEALTH.child_down(&id);
+
+            let reason = FaultReason::Panicked(message);
+            WATCHES.down(&id, &path, DownReason::Faulted(reason.clone()));
 
             let id = id.clone();
-            let msg = BastionMessage::restart_required(id, parent.id().clone());
+            let msg = BastionMessage::restart_required(id, parent.id().clone(), reason);
             let env = Envelope::new(msg, path.clone(), sender.clone());
             // TODO: handle errors
             parent.send(env).ok();
@@ -133,31 +225,111 @@ impl Child {
 
         Self::remove_from_dispatchers(&parent, &self.child_ref);
         let _ = Self::remove_from_distributors(&parent, &self.child_ref);
+        let _ = SYSTEM.dispatcher().unsubscribe_all(&self.child_ref);
+        SYSTEM.dispatcher().remove_child(self.id());
+        HEALTH.child_down(self.id());
+        WATCHES.down(self.id(), self.bcast.path(), DownReason::Stopped);
         self.bcast.stopped();
     }
 
+    // NOTE: the group ignores the restart request when it is the one
+    //      killing its elements, because it is stopping or faulting.
+    fn killed(&mut self) {
+        debug!("Child({}): Killed.", self.id());
+        self.down_with(FaultReason::Killed, DownReason::Killed);
+    }
+
     fn faulted(&mut self) {
+        let reason = if self.state.is_escalated() {
+            FaultReason::Escalated
+        } else {
+            FaultReason::Returned
+        };
+
+        self.faulted_with(reason);
+    }
+
+    fn faulted_with(&mut self, reason: FaultReason) {
         debug!("Child({}): Faulted.", self.id());
+        self.shutdown.errored();
+        let down = DownReason::Faulted(reason.clone());
+        self.down_with(reason, down);
+    }
+
+    // Tells the group that the child terminated abnormally, so that it
+    // decides whether to restart it.
+    fn down_with(&mut self, reason: FaultReason, down: DownReason) {
         let parent = self.bcast.parent().clone().into_children().unwrap();
 
         Self::remove_from_dispatchers(&parent, &self.child_ref);
         let _ = Self::remove_from_distributors(&parent, &self.child_ref);
+        let _ = SYSTEM.dispatcher().unsubscribe_all(&self.child_ref);
+        SYSTEM.dispatcher().remove_child(self.id());
+        HEALTH.child_down(self.id());
+        WATCHES.down(self.id(), self.bcast.path(), down);
 
         let path = self.bcast.path().clone();
         let sender = self.bcast.sender().clone();
 
-        let msg = BastionMessage::restart_required(self.id().clone(), parent.id().clone());
+        let msg = BastionMessage::restart_required(self.id().clone(), parent.id().clone(), reason);
         let env = Envelope::new(msg, path, sender);
         // TODO: handle errors
         parent.send(env).ok();
     }
 
+    // Pushes a message into the mailbox, or routes it to the dead
+    // letters if the child is draining.
+    fn receive(&mut self, msg: Msg, sign: RefAddr) {
+        if self.draining.is_some() {
+            debug!(
+                "Child({}): Routing a message to the dead letters: draining.",
+                self.id()
+            );
+            let msg = BastionMessage::Message(msg);
+            SYSTEM
+                .dead_letters_of(self.bcast.path())
+                .send(Envelope { msg, sign })
+                .ok();
+            return;
+        }
+
+        debug!("Child({}): Received a message: {:?}", self.id(), msg);
+        let smsg = SignedMessage::new(msg, sign);
+        let (msg, sign) = match INTERCEPTORS.intercept(smsg, self.bcast.path()) {
+            Some(smsg) => smsg.extract(),
+            None => {
+                debug!(
+                    "Child({}): A message was dropped by an interceptor.",
+                    self.id()
+                );
+                return;
+            }
+        };
+        // NOTE: messages are pushed in the order they were received (including
+        //      the ones replayed after starting), which keeps the FIFO ordering
+        //      documented on `BastionContext::tell`.
+        if let Some((msg, sign)) = self.state.make_room(msg, sign, self.bcast.path()) {
+            self.state.push_message(msg, sign);
+        }
+    }
+
     async fn handle(&mut self, env: Envelope) -> Result<(), ()> {
         match env {
             Envelope {
                 msg: BastionMessage::Start,
                 ..
             } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Stop,
+                ..
+            } if self.cancelling.is_none() && self.state.is_cancellation_watched() => {
+                debug!(
+                    "Child({}): Cancelling its future before stopping.",
+                    self.id()
+                );
+                self.state.cancel();
+                self.cancelling = Some(Delay::new(CANCELLATION_TIMEOUT));
+            }
             Envelope {
                 msg: BastionMessage::Stop,
                 ..
@@ -170,11 +342,22 @@ impl Child {
                 self.callbacks.after_stop();
                 return Err(());
             }
+            Envelope {
+                msg: BastionMessage::GracefulStop { drain_timeout },
+                ..
+            } => {
+                debug!(
+                    "Child({}): Draining its mailbox before stopping (drain_timeout={:?}).",
+                    self.id(),
+                    drain_timeout
+                );
+                self.draining = Some(Delay::new(drain_timeout));
+            }
             Envelope {
                 msg: BastionMessage::Kill,
                 ..
             } => {
-                self.stopped();
+                self.killed();
 
                 #[cfg(feature = "scaling")]
                 self.cleanup_actors_stats().await;
@@ -208,9 +391,22 @@ impl Child {
             Envelope {
                 msg: BastionMessage::Message(msg),
                 sign,
+            } => self.receive(msg, sign),
+            // NOTE: a batch holds this child's share of the messages broadcasted
+            //      together, each signed by its sender.
+            Envelope {
+                msg: BastionMessage::Batch(messages),
+                ..
             } => {
-                debug!("Child({}): Received a message: {:?}", self.id(), msg);
-                self.state.push_message(msg, sign);
+                debug!(
+                    "Child({}): Received a batch of {} messages.",
+                    self.id(),
+                    messages.len()
+                );
+                for message in messages {
+                    let sign = message.signature().clone();
+                    self.receive(Msg::tell(message), sign);
+                }
             }
             Envelope {
                 msg: BastionMessage::RestartRequired { .. },
@@ -253,6 +449,46 @@ impl Child {
                 msg: BastionMessage::Heartbeat,
                 ..
             } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Pause,
+                ..
+            } => {
+                debug!("Child({}): Pausing.", self.id());
+                self.state.set_paused(true);
+            }
+            Envelope {
+                msg: BastionMessage::Resume,
+                ..
+            } => {
+                debug!("Child({}): Resuming.", self.id());
+                self.state.set_paused(false);
+            }
+            Envelope {
+                msg: BastionMessage::Hold,
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Release,
+                ..
+            } => unreachable!(),
+            // NOTE: receiving the message is enough for the future to be
+            //      polled again and receive the messages held meanwhile.
+            Envelope {
+                msg: BastionMessage::Thaw,
+                ..
+            } => debug!("Child({}): Thawed.", self.id()),
+            Envelope {
+                msg: BastionMessage::Handover { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Adopt(_),
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Reparent(_),
+                ..
+            } => unreachable!(),
         }
 
         Ok(())
@@ -322,6 +558,10 @@ impl Child {
             error!("couldn't add actor to the distributors: {}", e);
             return;
         };
+        if let Err(e) = SYSTEM.dispatcher().register_child(&self.child_ref) {
+            error!("couldn't add actor to the resolvable children: {}", e);
+            return;
+        };
 
         self.callbacks.after_start();
 
@@ -381,28 +621,167 @@ impl Child {
                 continue;
             }
 
-            match poll!(&mut self.exec) {
+            // NOTE: the permit is only held while the future is polled.
+            let permit = match &self.bulkhead {
+                Some(bulkhead) => match poll!(bulkhead.acquire()) {
+                    Poll::Ready(permit) => Some(permit),
+                    Poll::Pending => {
+                        trace!("Child({}): Waiting for the bulkhead.", self.id());
+                        pending!();
+
+                        continue;
+                    }
+                },
+                None => None,
+            };
+            let polled = poll!(Self::catch_panic(&mut self.exec, &self.panic));
+            drop(permit);
+
+            match polled {
+                Poll::Ready(result) if self.cancelling.is_some() => {
+                    debug!(
+                        "Child({}): The future returned after being cancelled: {:?}",
+                        self.id(),
+                        result
+                    );
+                    self.stopped();
+
+                    #[cfg(feature = "scaling")]
+                    self.cleanup_actors_stats().await;
+
+                    self.callbacks.after_stop();
+                    return;
+                }
                 Poll::Ready(Ok(())) => {
                     debug!(
                         "Child({}): The future finished executing successfully.",
                         self.id()
                     );
+                    self.state.complete_in_flight();
+                    return self.stopped();
+                }
+                Poll::Ready(Err(())) if self.state.is_stopped_on_panic() => {
+                    debug!(
+                        "Child({}): The future returned after panicking while handling a message.",
+                        self.id()
+                    );
+                    self.state.complete_in_flight();
                     return self.stopped();
                 }
                 Poll::Ready(Err(())) => {
                     warn!("Child({}): The future returned an error.", self.id());
                     return self.faulted();
                 }
-                Poll::Pending => (),
+                // NOTE: the future yielded, letting the other elements
+                //      run, so it can receive messages again.
+                Poll::Pending => self.state.restore_budget(),
+            }
+
+            if self.state.is_running() {
+                self.launching = None;
+            } else if let Some(launch_timeout) = &mut self.launching {
+                if poll!(launch_timeout).is_ready() {
+                    error!(
+                        "Child({}): Didn't reach its running state within its launch timeout.",
+                        self.id()
+                    );
+                    return self.faulted_with(FaultReason::LaunchTimedOut);
+                }
+            }
+
+            if let Some(cancellation_timeout) = &mut self.cancelling {
+                if poll!(cancellation_timeout).is_ready() {
+                    warn!("Child({}): Cancellation timeout elapsed.", self.id());
+                    self.stopped();
+
+                    #[cfg(feature = "scaling")]
+                    self.cleanup_actors_stats().await;
+
+                    self.callbacks.after_stop();
+                    return;
+                }
+            }
+
+            if let Some(drain_timeout) = &mut self.draining {
+                let timed_out = poll!(drain_timeout).is_ready();
+                if timed_out {
+                    warn!("Child({}): Drain timeout elapsed.", self.id());
+                }
+
+                if timed_out || self.state.is_drained() {
+                    debug!("Child({}): Drained its mailbox.", self.id());
+                    self.stopped();
+
+                    #[cfg(feature = "scaling")]
+                    self.cleanup_actors_stats().await;
+
+                    self.callbacks.after_stop();
+                    return;
+                }
             }
 
             pending!();
         }
     }
 
+    /// Polls the child's future once, keeping its panic message and
+    /// backtrace, if it panics, before resuming unwinding.
+    fn catch_panic<'a>(
+        exec: &'a mut Exec,
+        slot: &'a Mutex<Option<CaughtPanic>>,
+    ) -> impl Future<Output = Result<(), ()>> + 'a {
+        future::poll_fn(move |cx| {
+            match panic::catch_unwind(AssertUnwindSafe(|| {
+                executor::polling_child(|| Pin::new(&mut *exec).poll(cx))
+            })) {
+                Ok(poll) => poll,
+                Err(payload) => {
+                    let caught = CaughtPanic {
+                        message: Self::panic_message(&*payload),
+                        backtrace: BACKTRACE.with(|backtrace| backtrace.borrow_mut().take()),
+                    };
+                    // FIXME: panics?
+                    *slot.lock().unwrap() = Some(caught);
+                    panic::resume_unwind(payload)
+                }
+            }
+        })
+    }
+
+    /// Chains a panic hook keeping the backtrace of the panics of the
+    /// current thread, which is only captured if backtraces are
+    /// enabled (see [`Config::capture_backtraces`]).
+    ///
+    /// [`Config::capture_backtraces`]: crate::config::Config::capture_backtraces
+    pub(crate) fn install_panic_hook() {
+        PANIC_HOOK.call_once(|| {
+            let previous = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                let backtrace = Backtrace::capture();
+                let backtrace = match backtrace.status() {
+                    BacktraceStatus::Captured => Some(backtrace.to_string()),
+                    _ => None,
+                };
+                BACKTRACE.with(|slot| *slot.borrow_mut() = backtrace);
+
+                previous(info);
+            }));
+        });
+    }
+
+    pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+        if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "Box<dyn Any>".to_string()
+        }
+    }
+
     pub(crate) fn launch(self) -> RecoverableHandle<()> {
         let stack = self.stack();
-        pool::spawn(self.run(), stack)
+        spawner().spawn(self.run(), stack)
     }
 
     /// Adds the actor into each registry declared in the parent node.
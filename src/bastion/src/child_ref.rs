@@ -1,15 +1,24 @@
 //!
 //! Allows users to communicate with Child through the mailboxes.
 use crate::context::BastionId;
-use crate::envelope::{Envelope, RefAddr};
-use crate::message::{Answer, BastionMessage, Message};
+use crate::envelope::{Envelope, RefAddr, SignedMessage};
+use crate::errors::AskError;
+use crate::executor;
+use crate::message::{check_message_size, Answer, BastionMessage, Message, Msg};
 use crate::path::BastionPath;
+use crate::system::SYSTEM;
 use crate::{broadcast::Sender, prelude::SendError};
+use futures::future::{self, Either};
+use futures_timer::Delay;
+use std::any::type_name;
 use std::cmp::{Eq, PartialEq};
 use std::fmt::Debug;
+use std::future::Future;
 use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use tracing::{debug, trace};
+use std::time::Duration;
+use tracing::{debug, trace, warn};
 
 #[derive(Debug, Clone)]
 /// A "reference" to an element of a children group, allowing to
@@ -23,6 +32,15 @@ pub struct ChildRef {
     // use `ChildRef::new_internal` to set it to false, for internal use children,
     // such as the heartbeat children for example
     is_public: bool,
+    // The mailbox depth of the child and the high-watermark above
+    // which asking it is refused, if any.
+    watermark: Option<Watermark>,
+}
+
+#[derive(Debug, Clone)]
+struct Watermark {
+    depth: Arc<AtomicUsize>,
+    high: usize,
 }
 
 impl ChildRef {
@@ -38,6 +56,7 @@ impl ChildRef {
             name,
             path,
             is_public: false,
+            watermark: None,
         }
     }
 
@@ -53,6 +72,29 @@ impl ChildRef {
             name,
             path,
             is_public: true,
+            watermark: None,
+        }
+    }
+
+    pub(crate) fn with_high_watermark(mut self, depth: Arc<AtomicUsize>, high: usize) -> Self {
+        self.watermark = Some(Watermark { depth, high });
+        self
+    }
+
+    // Whether the child's mailbox is above its high-watermark.
+    pub(crate) fn is_overloaded(&self) -> bool {
+        match &self.watermark {
+            Some(watermark) => watermark.depth.load(Ordering::SeqCst) >= watermark.high,
+            None => false,
+        }
+    }
+
+    // Whether the child's mailbox can take `count` more messages
+    // without going above its high-watermark.
+    pub(crate) fn admits(&self, count: usize) -> bool {
+        match &self.watermark {
+            Some(watermark) => watermark.depth.load(Ordering::SeqCst) + count <= watermark.high,
+            None => true,
         }
     }
 
@@ -145,6 +187,47 @@ impl ChildRef {
         self.is_public
     }
 
+    /// Returns whether the child this `ChildRef` is referencing is
+    /// currently running, as tracked by the system.
+    ///
+    /// A child which faulted isn't alive until its supervisor
+    /// restarted it (keeping its identifier), while a child which
+    /// stopped or was killed is never alive again.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// let children_ref = Bastion::children(|children| children).unwrap();
+    /// let child_ref = &children_ref.elems()[0];
+    /// if child_ref.is_alive() {
+    ///     child_ref.tell_anonymously("A message containing data.").ok();
+    /// }
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn is_alive(&self) -> bool {
+        SYSTEM.dispatcher().is_child_alive(&self.id)
+    }
+
     /// Sends a message to the child this `ChildRef` is referencing.
     /// This message is intended to be used outside of Bastion context when
     /// there is no way for receiver to identify message sender
@@ -209,12 +292,56 @@ impl ChildRef {
     /// ```
     pub fn tell_anonymously<M: Message>(&self, msg: M) -> Result<(), M> {
         debug!("ChildRef({}): Telling message: {:?}", self.id(), msg);
+        if let Err((size, max)) = check_message_size(&msg) {
+            debug!(
+                "ChildRef({}): Refusing to tell: the message is {} bytes long, above {}.",
+                self.id(),
+                size,
+                max
+            );
+            return Err(msg);
+        }
+
         let msg = BastionMessage::tell(msg);
         let env = Envelope::from_dead_letters(msg);
         // FIXME: panics?
         self.send(env).map_err(|env| env.into_msg().unwrap())
     }
 
+    /// Forwards a message broadcasted through a dispatcher to the
+    /// child this `ChildRef` is referencing, signed by the element
+    /// which broadcasted it.
+    pub(crate) fn forward(&self, message: &Arc<SignedMessage>) -> Result<(), Arc<SignedMessage>> {
+        debug!("ChildRef({}): Forwarding message: {:?}", self.id(), message);
+        let msg = BastionMessage::tell(message.clone());
+        let env = Envelope::new_with_sign(msg, message.signature().clone());
+        // FIXME: panics?
+        self.send(env).map_err(|env| env.into_msg().unwrap())
+    }
+
+    /// Forwards messages broadcasted together through a dispatcher to
+    /// the child this `ChildRef` is referencing, as a single envelope.
+    /// Each message stays signed by the element which broadcasted it.
+    pub(crate) fn forward_batch(
+        &self,
+        messages: Vec<Arc<SignedMessage>>,
+    ) -> Result<(), Vec<Arc<SignedMessage>>> {
+        debug!(
+            "ChildRef({}): Forwarding a batch of {} messages.",
+            self.id(),
+            messages.len()
+        );
+        let sign = match messages.first() {
+            Some(message) => message.signature().clone(),
+            None => return Ok(()),
+        };
+        let env = Envelope::new_with_sign(BastionMessage::batch(messages), sign);
+        self.send(env).map_err(|env| match env.msg {
+            BastionMessage::Batch(messages) => messages,
+            _ => unreachable!(),
+        })
+    }
+
     /// Try to send a message to the child this `ChildRef` is referencing.
     /// This message is intended to be used outside of Bastion context when
     /// there is no way for receiver to identify message sender
@@ -279,6 +406,20 @@ impl ChildRef {
     /// ```
     pub fn try_tell_anonymously<M: Message>(&self, msg: M) -> Result<(), SendError> {
         debug!("ChildRef({}): Try Telling message: {:?}", self.id(), msg);
+        if let Err((size, max)) = check_message_size(&msg) {
+            debug!(
+                "ChildRef({}): Refusing to tell: the message is {} bytes long, above {}.",
+                self.id(),
+                size,
+                max
+            );
+            return Err(SendError::MessageTooLarge {
+                msg: Msg::tell(msg),
+                size,
+                max,
+            });
+        }
+
         let msg = BastionMessage::tell(msg);
         let env = Envelope::from_dead_letters(msg);
         self.try_send(env).map_err(Into::into)
@@ -290,7 +431,8 @@ impl ChildRef {
     /// there is no way for receiver to identify message sender
     ///
     /// This method returns [`Answer`](../message/struct.Answer.html) if it succeeded, or `Err(msg)`
-    /// otherwise.
+    /// otherwise, which is also the case when the child's mailbox is
+    /// above the high-watermark of its group.
     ///
     /// # Argument
     ///
@@ -373,6 +515,20 @@ impl ChildRef {
     /// ```
     pub fn ask_anonymously<M: Message>(&self, msg: M) -> Result<Answer, M> {
         debug!("ChildRef({}): Asking message: {:?}", self.id(), msg);
+        if self.is_overloaded() {
+            debug!("ChildRef({}): Refusing to ask: overloaded.", self.id());
+            return Err(msg);
+        }
+        if let Err((size, max)) = check_message_size(&msg) {
+            debug!(
+                "ChildRef({}): Refusing to ask: the message is {} bytes long, above {}.",
+                self.id(),
+                size,
+                max
+            );
+            return Err(msg);
+        }
+
         let (msg, answer) = BastionMessage::ask(msg, self.addr());
         let env = Envelope::from_dead_letters(msg);
         // FIXME: panics?
@@ -387,7 +543,8 @@ impl ChildRef {
     /// there is no way for receiver to identify message sender
     ///
     /// This method returns [`Answer`](../message/struct.Answer.html) if it succeeded, or a `SendError`(../child_ref/enum.SendError.html)
-    /// otherwise.
+    /// otherwise. If the child's mailbox is above the high-watermark of
+    /// its group, `SendError::Overloaded` is returned right away.
     ///
     /// # Argument
     ///
@@ -470,11 +627,224 @@ impl ChildRef {
     /// ```
     pub fn try_ask_anonymously<M: Message>(&self, msg: M) -> Result<Answer, SendError> {
         debug!("ChildRef({}): Try Asking message: {:?}", self.id(), msg);
+        if self.is_overloaded() {
+            debug!("ChildRef({}): Refusing to ask: overloaded.", self.id());
+            return Err(SendError::Overloaded(Msg::tell(msg)));
+        }
+        if let Err((size, max)) = check_message_size(&msg) {
+            debug!(
+                "ChildRef({}): Refusing to ask: the message is {} bytes long, above {}.",
+                self.id(),
+                size,
+                max
+            );
+            return Err(SendError::MessageTooLarge {
+                msg: Msg::tell(msg),
+                size,
+                max,
+            });
+        }
+
         let (msg, answer) = BastionMessage::ask(msg, self.addr());
         let env = Envelope::from_dead_letters(msg);
         self.try_send(env).map(|_| answer)
     }
 
+    /// Sends a question to the child this `ChildRef` is referencing,
+    /// returning a future resolving to its answer once downcasted to
+    /// the expected type.
+    ///
+    /// The future resolves to [`AskError::Decode`] (holding the
+    /// answer) if the child answered with a message of another type,
+    /// to [`AskError::Dropped`] if it didn't answer, and to
+    /// [`AskError::Send`] if the question couldn't be sent (see
+    /// [`ChildRef::try_ask_anonymously`]).
+    ///
+    /// # Argument
+    ///
+    /// * `req` - The question to send.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    ///     # Bastion::init();
+    ///     # let children_ref =
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             msg! { ctx.recv().await?,
+    ///                 number: u64 =!> {
+    ///                     answer!(ctx, number * 2).unwrap();
+    ///                 };
+    ///                 _: _ => ();
+    ///             }
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    ///     # Bastion::children(|children| {
+    ///         # children.with_exec(move |ctx: BastionContext| {
+    ///             # let child_ref = children_ref.elems()[0].clone();
+    ///             # async move {
+    /// let doubled: u64 = child_ref
+    ///     .ask::<u64, u64>(21)
+    ///     .await
+    ///     .expect("Couldn't receive the answer.");
+    /// assert_eq!(doubled, 42);
+    ///                 #
+    ///                 # Ok(())
+    ///             # }
+    ///         # })
+    ///     # }).unwrap();
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`AskError::Decode`]: crate::errors::AskError::Decode
+    /// [`AskError::Dropped`]: crate::errors::AskError::Dropped
+    /// [`AskError::Send`]: crate::errors::AskError::Send
+    pub fn ask<Req: Message, Resp: Message>(
+        &self,
+        req: Req,
+    ) -> impl Future<Output = Result<Resp, AskError>> {
+        let answer = self.try_ask_anonymously(req);
+        let id = self.id().clone();
+
+        async move {
+            let answer = answer.map_err(AskError::Send)?;
+            let answer = answer.await.map_err(|()| AskError::Dropped)?;
+            answer.msg.downcast().map_err(|msg| {
+                debug!(
+                    "ChildRef({}): Couldn't decode the answer as {}: {:?}",
+                    id,
+                    type_name::<Resp>(),
+                    msg
+                );
+                AskError::Decode(msg)
+            })
+        }
+    }
+
+    /// Sends a question to the child this `ChildRef` is referencing and
+    /// blocks the current thread until it answers or the timeout
+    /// elapses, returning its answer once downcasted to the expected
+    /// type.
+    ///
+    /// This is meant to be called from synchronous code running outside
+    /// of bastion (e.g. a `main` function or a thread of a synchronous
+    /// library); from within a child, use [`ChildRef::ask`] instead.
+    /// Blocking one of the executor's threads would stall the other
+    /// children it runs (and possibly the asked child itself), so this
+    /// method returns [`AskError::Blocking`] without sending the
+    /// question when called from the future of a child. Futures given
+    /// to [`spawn!`] aren't detected and must not call it either.
+    ///
+    /// Besides the errors of [`ChildRef::ask`], this method returns
+    /// [`AskError::Timeout`] if the child didn't answer on time.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The question to send.
+    /// * `timeout` - How long to wait for the answer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    ///     # Bastion::init();
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             loop {
+    ///                 msg! { ctx.recv().await?,
+    ///                     number: u64 =!> {
+    ///                         answer!(ctx, number * 2).unwrap();
+    ///                     };
+    ///                     _: _ => ();
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     # Bastion::start();
+    ///
+    /// let child_ref = &children_ref.elems()[0];
+    /// let doubled: u64 = child_ref
+    ///     .ask_sync::<u64, u64>(21, Duration::from_secs(1))
+    ///     .expect("Couldn't receive the answer.");
+    /// assert_eq!(doubled, 42);
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`spawn!`]: crate::spawn
+    /// [`AskError::Blocking`]: crate::errors::AskError::Blocking
+    /// [`AskError::Timeout`]: crate::errors::AskError::Timeout
+    pub fn ask_sync<Req: Message, Resp: Message>(
+        &self,
+        req: Req,
+        timeout: Duration,
+    ) -> Result<Resp, AskError> {
+        if executor::is_polling_child() {
+            warn!(
+                "ChildRef({}): Refusing to block on a question from within a child.",
+                self.id()
+            );
+            return Err(AskError::Blocking);
+        }
+
+        let answer = Box::pin(self.ask::<Req, Resp>(req));
+        let timed_out = Delay::new(timeout);
+        let id = self.id().clone();
+        executor::run(async move {
+            match future::select(answer, timed_out).await {
+                Either::Left((answer, _)) => answer,
+                Either::Right(_) => {
+                    debug!(
+                        "ChildRef({}): No answer was received after {:?}.",
+                        id, timeout
+                    );
+                    Err(AskError::Timeout(timeout))
+                }
+            }
+        })
+    }
+
     /// Sends a message to the child this `ChildRef` is referencing
     /// to tell it to stop its execution.
     ///
@@ -537,6 +907,12 @@ impl ChildRef {
     /// Sends a message to the child this `ChildRef` is referencing
     /// to tell it to suicide.
     ///
+    /// Its group then handles it as a fault, with
+    /// [`FaultReason::Killed`] as reason, and restarts it according to
+    /// the restart strategy of its supervisor.
+    ///
+    /// [`FaultReason::Killed`]: crate::supervisor::FaultReason::Killed
+    ///
     /// This method returns `()` if it succeeded, or `Err(())`
     /// otherwise.
     ///
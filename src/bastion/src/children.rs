@@ -1,39 +1,69 @@
 //!
 //! Children are a group of child supervised under a supervisor
+use crate::bulkhead::Bulkhead;
 use crate::callbacks::{CallbackType, Callbacks};
 use crate::child::{Child, Init};
 use crate::child_ref::ChildRef;
 use crate::children_ref::ChildrenRef;
-use crate::context::{BastionContext, BastionId, ContextState};
-use crate::dispatcher::Dispatcher;
-use crate::envelope::Envelope;
-use crate::message::BastionMessage;
+use crate::config::Config;
+use crate::context::{BastionContext, BastionId, ContextState, OutboundTransforms};
+use crate::dispatcher::{Dispatcher, DispatcherType};
+use crate::envelope::{Envelope, RefAddr, SignedMessage};
+use crate::events::{SystemEvent, EVENTS};
+use crate::executor::{spawner, Spawner};
+use crate::health::HEALTH;
+#[cfg(feature = "metrics")]
+use crate::latency::LATENCIES;
+use crate::message::{AnswerSender, BastionMessage, Message, Msg, TypedHandlers};
+use crate::overflow::DropPriorities;
+#[cfg(feature = "json-logs")]
+use crate::path::BastionPath;
 use crate::path::BastionPathElement;
+use crate::persistence::Persistence;
 #[cfg(feature = "scaling")]
-use crate::resizer::{ActorGroupStats, OptimalSizeExploringResizer, ScalingRule};
-use crate::system::SYSTEM;
+use crate::resizer::{
+    ActorGroupStats, OptimalSizeExploringResizer, Resizer, ScalingRule, UtilizationResizer,
+};
+use crate::spec::EXECS;
+#[cfg(feature = "json-logs")]
+use crate::supervision_log::{self, SupervisionEvent};
+use crate::supervisor::{FaultReason, SupervisionStrategy};
+use crate::system::{CONFIG, SYSTEM};
+use crate::topology::{ChildrenNode, TOPOLOGY};
 use crate::{
     broadcast::{Broadcast, Parent, Sender},
     distributor::Distributor,
 };
 use anyhow::Result as AnyResult;
 
-use bastion_executor::pool;
 use futures::pending;
 use futures::poll;
 use futures::prelude::*;
 use futures::stream::FuturesOrdered;
 use futures_timer::Delay;
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet};
 use lightproc::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::future::Future;
+use std::path::Path;
 use std::pin::Pin;
+use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
 use std::task::Poll;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
 use tracing::{debug, trace, warn};
 
+// The interval between two heartbeats of a children group, unless
+// the system or the group was configured with another one.
+const DEFAULT_HEARTBEAT_TICK: Duration = Duration::from_secs(60);
+// The amount of messages an element receives in a row before yielding
+// to the other elements, unless the system or the group was
+// configured with another one.
+const DEFAULT_FAIRNESS_BUDGET: usize = 128;
+
 #[derive(Debug)]
 /// A children group that will contain a defined number of
 /// elements (set with [`with_redundancy`] or `1` by default)
@@ -98,6 +128,12 @@ pub struct Children {
     // The closure returning the future that will be used by
     // every element of the group.
     init: Init,
+    // The name the exec closure was registered with, if it was
+    // built from a spec (see `Bastion::from_spec`).
+    exec_name: Option<String>,
+    // The handlers of the message types the elements receive, driving
+    // their message loop if any was registered instead of `init`.
+    handlers: TypedHandlers,
     redundancy: usize,
     // The callbacks called at the group's different lifecycle
     // events.
@@ -109,20 +145,246 @@ pub struct Children {
     started: bool,
     // List of dispatchers attached to each actor in the group.
     dispatchers: Vec<Arc<Box<Dispatcher>>>,
+    // The dispatchers shared with the other groups of the supervisor,
+    // which are left registered when the group stops.
+    shared_dispatchers: Vec<DispatcherType>,
     distributors: Vec<Distributor>,
     // The name of children
     name: Option<String>,
     #[cfg(feature = "scaling")]
     // Resizer for dynamic actor group scaling up/down.
     resizer: Box<OptimalSizeExploringResizer>,
+    #[cfg(feature = "scaling")]
+    // Resizer keeping the utilization of the mailboxes of the group
+    // within a band, used instead of `resizer` if set.
+    utilization_resizer: Option<UtilizationResizer>,
     // Defines how often do heartbeat checks. By default checks will
-    // be done each 60 seconds.
+    // be done each 60 seconds, unless the system was configured with
+    // another interval.
     hearbeat_tick: Duration,
+    // The amount of messages the elements of the group receive in a
+    // row before yielding to the other elements.
+    fairness_budget: usize,
     // Special kind for actors that not going to be visible for others
     // parts of the cluster, but required for extra behaviour for the
     // Children instance. For example for heartsbeat checks, collecting
     // stats, etc.
     helper_actors: FxHashMap<BastionId, (Sender, RecoverableHandle<()>)>,
+    // The circuit breaker configuration of the group, if any.
+    circuit_breaker: Option<CircuitBreaker>,
+    // The state of the circuit of every element of the group that
+    // faulted at least once while a circuit breaker is configured.
+    circuits: FxHashMap<BastionId, CircuitState>,
+    // The bulkhead capping how many elements of the group can be
+    // executing at once, if any.
+    bulkhead: Option<Arc<Bulkhead>>,
+    // How long an element has to reach its running state once
+    // launched before faulting, if any.
+    launch_timeout: Option<Duration>,
+    // The mailbox depth above which asking an element of the group
+    // is refused, if any.
+    high_watermark: Option<usize>,
+    // The mailbox depth of every launched element of the group.
+    depths: FxHashMap<BastionId, Arc<AtomicUsize>>,
+    // The ordinal of every launched element of the group within
+    // it, see `BastionContext::index`.
+    indices: FxHashMap<BastionId, usize>,
+    // Whether the elements of the group were paused, in which
+    // case the launched ones are paused too.
+    paused: bool,
+    // Whether the delivery of messages is held while several
+    // elements of the group are restarted together.
+    held: bool,
+    // The elements which faulted and weren't restarted yet.
+    down: FxHashSet<BastionId>,
+    // The messages dead-lettered while elements of the group were
+    // down, replayed to them once restarted, if enabled.
+    replay: Option<DeadLetterReplay>,
+    // The log the messages received by the elements are persisted
+    // to, if enabled.
+    persistence: Option<Arc<Persistence>>,
+    // What happens to the mailbox of the elements when they are
+    // restarted.
+    handover: MailboxHandover,
+    // The amount of messages the mailbox of the elements holds
+    // before evicting the ones of the lowest drop priority, if
+    // bounded.
+    overflow: Option<(usize, Arc<DropPriorities>)>,
+    // What happens to the elements when they panic while handling a
+    // message received with `BastionContext::recv_with`.
+    panic_policy: PanicPolicy,
+    // The transforms applied to the messages the elements send.
+    outbound: OutboundTransforms,
+    // How many asks each element can have waiting for their answer
+    // at once, if bounded.
+    max_outstanding_asks: Option<usize>,
+    // The strategy the supervisor uses when the elements of this group
+    // fault, instead of its own, if set.
+    strategy: Option<SupervisionStrategy>,
+    // Whether a `SystemEvent::BroadcastDelivered` is emitted for every
+    // element a broadcasted message is enqueued for.
+    delivery_receipts: bool,
+    // How long the elements can go without proving they aren't hung
+    // before being restarted, if they are watched.
+    watchdog: Option<Duration>,
+    // The states of the watched elements, telling when they last
+    // proved they aren't hung.
+    heartbeats: FxHashMap<BastionId, Arc<Pin<Box<ContextState>>>>,
+    // The amount of messages broadcasted to the elements, numbering
+    // the broadcasts the receipts are about.
+    broadcasts: u64,
+    // Why the faulted elements faulted, logged once they are
+    // restarted.
+    #[cfg(feature = "json-logs")]
+    fault_reasons: FxHashMap<BastionId, FaultReason>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// What happens to the mailbox of an element of a children group
+/// when it is restarted (see [`Children::with_mailbox_handover`]).
+///
+/// The default handover is `Full`.
+pub enum MailboxHandover {
+    /// The messages waiting in the mailbox are handed over to the
+    /// restarted element, and so is the message that was being
+    /// handled if it can be replayed, that is if the group persists
+    /// its messages (see [`Children::with_persistence`]).
+    Full,
+    /// The messages waiting in the mailbox are handed over to the
+    /// restarted element, but the message that was being handled is
+    /// dropped, because it might have caused the fault.
+    SkipInFlight,
+    /// The messages waiting in the mailbox are routed to the dead
+    /// letters and the message that was being handled is dropped,
+    /// so that the restarted element starts from an empty mailbox.
+    Discard,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// What happens to an element of a children group when it panics
+/// while handling a message received with
+/// [`BastionContext::recv_with`] (see [`Children::with_panic_policy`]).
+///
+/// The panics happening outside of `recv_with` always make the
+/// element fault, as its future can't be resumed after them.
+///
+/// The default policy is `Restart`.
+///
+/// [`BastionContext::recv_with`]: crate::context::BastionContext::recv_with
+pub enum PanicPolicy {
+    /// The element faults and is restarted by its supervisor,
+    /// losing its state.
+    Restart,
+    /// The message is dropped and the element keeps handling the
+    /// next ones, keeping its state.
+    Resume,
+    /// The element is stopped for good, without being restarted.
+    Stop,
+}
+
+#[derive(Debug)]
+struct DeadLetterReplay {
+    // How long a dead-lettered message is kept to be replayed.
+    window: Duration,
+    // The messages dead-lettered while elements were down, in the
+    // order they were sent in, with when they were sent and which
+    // element missed them.
+    missed: VecDeque<(Instant, BastionId, Envelope)>,
+}
+
+#[derive(Debug, Clone)]
+struct CircuitBreaker {
+    // The amount of faults within `window` that opens the circuit.
+    threshold: usize,
+    // How long a fault counts towards opening the circuit.
+    window: Duration,
+    // How long the circuit stays open before probing the element
+    // and how long the probe needs to survive to close it again.
+    cooldown: Duration,
+}
+
+#[derive(Debug, Default)]
+struct CircuitState {
+    faults: VecDeque<Instant>,
+    status: CircuitStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CircuitStatus {
+    Closed,
+    Open(Instant),
+    HalfOpen(Instant),
+}
+
+impl Default for CircuitStatus {
+    fn default() -> Self {
+        CircuitStatus::Closed
+    }
+}
+
+impl Default for MailboxHandover {
+    fn default() -> Self {
+        MailboxHandover::Full
+    }
+}
+
+impl Default for PanicPolicy {
+    fn default() -> Self {
+        PanicPolicy::Restart
+    }
+}
+
+impl DeadLetterReplay {
+    fn new(window: Duration) -> Self {
+        DeadLetterReplay {
+            window,
+            missed: VecDeque::new(),
+        }
+    }
+
+    /// Keeps a copy of the message for every element that is down.
+    fn record(&mut self, envelope: &Envelope, down: &FxHashSet<BastionId>, now: Instant) {
+        self.prune(now);
+        for id in down.iter() {
+            if let Some(env) = envelope.try_clone() {
+                self.missed.push_back((now, id.clone(), env));
+            }
+        }
+    }
+
+    /// Returns the messages the restarted element missed within the
+    /// window, in the order they were sent in.
+    fn take(&mut self, id: &BastionId, now: Instant) -> Vec<Envelope> {
+        self.prune(now);
+
+        let mut taken = Vec::new();
+        let mut kept = VecDeque::with_capacity(self.missed.len());
+        for (sent, missed_by, env) in self.missed.drain(..) {
+            if &missed_by == id {
+                taken.push(env);
+            } else {
+                kept.push_back((sent, missed_by, env));
+            }
+        }
+
+        self.missed = kept;
+        taken
+    }
+
+    /// Forgets about an element that won't be restarted.
+    fn forget(&mut self, id: &BastionId) {
+        self.missed.retain(|(_, missed_by, _)| missed_by != id);
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while let Some((sent, _, _)) = self.missed.front() {
+            if now.duration_since(*sent) < self.window {
+                break;
+            }
+
+            self.missed.pop_front();
+        }
+    }
 }
 
 impl Children {
@@ -130,33 +392,97 @@ impl Children {
         debug!("Children({}): Initializing.", bcast.id());
         let launched = FxHashMap::default();
         let init = Init::default();
+        let exec_name = None;
+        let handlers = TypedHandlers::default();
         let redundancy = 1;
         let callbacks = Callbacks::new();
         let pre_start_msgs = Vec::new();
         let started = false;
         let dispatchers = Vec::new();
+        let shared_dispatchers = Vec::new();
         let distributors = Vec::new();
         let name = None;
         #[cfg(feature = "scaling")]
         let resizer = Box::new(OptimalSizeExploringResizer::default());
-        let hearbeat_tick = Duration::from_secs(60);
+        #[cfg(feature = "scaling")]
+        let utilization_resizer = None;
+        let hearbeat_tick = CONFIG
+            .get()
+            .and_then(Config::heartbeat_tick)
+            .unwrap_or(DEFAULT_HEARTBEAT_TICK);
+        let fairness_budget = CONFIG
+            .get()
+            .and_then(Config::fairness_budget)
+            .unwrap_or(DEFAULT_FAIRNESS_BUDGET);
         let helper_actors = FxHashMap::default();
+        let circuit_breaker = None;
+        let circuits = FxHashMap::default();
+        let bulkhead = None;
+        let launch_timeout = None;
+        let high_watermark = None;
+        let depths = FxHashMap::default();
+        let indices = FxHashMap::default();
+        let paused = false;
+        let held = false;
+        let down = FxHashSet::default();
+        let replay = None;
+        let persistence = None;
+        let handover = MailboxHandover::default();
+        let overflow = None;
+        let panic_policy = PanicPolicy::default();
+        let outbound = OutboundTransforms::default();
+        let max_outstanding_asks = None;
+        let strategy = None;
+        let delivery_receipts = false;
+        let watchdog = None;
+        let heartbeats = FxHashMap::default();
+        let broadcasts = 0;
 
         Children {
             bcast,
             launched,
             init,
+            exec_name,
+            handlers,
             redundancy,
             callbacks,
             pre_start_msgs,
             started,
             dispatchers,
+            shared_dispatchers,
             distributors,
             name,
             #[cfg(feature = "scaling")]
             resizer,
+            #[cfg(feature = "scaling")]
+            utilization_resizer,
             hearbeat_tick,
+            fairness_budget,
             helper_actors,
+            circuit_breaker,
+            circuits,
+            bulkhead,
+            launch_timeout,
+            high_watermark,
+            depths,
+            indices,
+            paused,
+            held,
+            down,
+            replay,
+            persistence,
+            handover,
+            overflow,
+            panic_policy,
+            outbound,
+            max_outstanding_asks,
+            strategy,
+            delivery_receipts,
+            watchdog,
+            heartbeats,
+            broadcasts,
+            #[cfg(feature = "json-logs")]
+            fault_reasons: FxHashMap::default(),
         }
     }
 
@@ -213,6 +539,10 @@ impl Children {
         &self.callbacks
     }
 
+    pub(crate) fn strategy(&self) -> Option<&SupervisionStrategy> {
+        self.strategy.as_ref()
+    }
+
     pub(crate) fn name(&self) -> String {
         if let Some(name) = &self.name {
             name.clone()
@@ -237,7 +567,7 @@ impl Children {
             trace!("Children({}): Creating new ChildRef({}).", self.id(), id);
             // TODO: clone or ref?
             let child = ChildRef::new(id.clone(), sender.clone(), self.name(), path.clone());
-            children.push(child);
+            children.push(self.watermarked(child));
         }
 
         let dispatchers = self
@@ -318,9 +648,235 @@ impl Children {
     {
         trace!("Children({}): Setting exec closure.", self.id());
         self.init = Init::new(init);
+        self.exec_name = None;
+        self.handlers = TypedHandlers::default();
+        self
+    }
+
+    /// Registers a handler of the messages of type `T`, which the
+    /// elements of this children group then receive in a loop,
+    /// instead of running the closure set with [`with_exec`].
+    ///
+    /// Every message received is handed to the first handler
+    /// registered for its type (see also [`on_question`]), or to the
+    /// handler set with [`on_fallback`] if there is none, and the
+    /// next message is only received once the handler's future
+    /// completed. The element stops if a handler's future returns
+    /// `Err(())`, like the future of an exec closure would.
+    ///
+    /// Messages are handed to the handlers by value: the broadcasted
+    /// ones (and the ones forwarded by dispatchers) are cloned unless
+    /// the element is their only recipient left, so that every
+    /// element receiving them hands them to the handler of their type.
+    /// Questions can be handled with [`on_question`] instead, as their
+    /// sender is dropped when handled by this handler.
+    ///
+    /// Calling [`with_exec`] afterwards removes the handlers.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The closure taking the context of the element
+    ///     and a message of type `T`, and returning the [`Future`]
+    ///     handling it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// #[derive(Debug, Clone)]
+    /// struct Resize {
+    ///     width: u32,
+    ///     height: u32,
+    /// }
+    ///
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .on(|ctx, msg: &'static str| async move {
+    ///             // Handle the message...
+    ///             # drop((ctx, msg));
+    ///             Ok(())
+    ///         })
+    ///         .on(|ctx, resize: Resize| async move {
+    ///             // Handle the message...
+    ///             # drop((ctx, resize.width, resize.height));
+    ///             Ok(())
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`with_exec`]: Self::with_exec
+    /// [`on_question`]: Self::on_question
+    /// [`on_fallback`]: Self::on_fallback
+    pub fn on<T, H, F>(mut self, handler: H) -> Self
+    where
+        T: Message + Clone,
+        H: Fn(Arc<BastionContext>, T) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        trace!(
+            "Children({}): Adding a handler of {}.",
+            self.id(),
+            std::any::type_name::<T>()
+        );
+        self.handlers.on(handler);
+        self.init = self.handlers.init();
+        self.exec_name = None;
+        self
+    }
+
+    /// Registers a handler of the questions of type `T` (see
+    /// [`BastionContext::ask`]), which is given the [`AnswerSender`]
+    /// to answer them with, like [`on`] does for every message.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The closure taking the context of the element,
+    ///     a question of type `T` and its [`AnswerSender`], and
+    ///     returning the [`Future`] handling it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children.on_question(|_ctx, n: u64, sender| async move {
+    ///         sender.reply(n * 2).map_err(|_| ())
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`on`]: Self::on
+    /// [`BastionContext::ask`]: crate::context::BastionContext::ask
+    pub fn on_question<T, H, F>(mut self, handler: H) -> Self
+    where
+        T: Message,
+        H: Fn(Arc<BastionContext>, T, AnswerSender) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        trace!(
+            "Children({}): Adding a handler of questions of {}.",
+            self.id(),
+            std::any::type_name::<T>()
+        );
+        self.handlers.on_question(handler);
+        self.init = self.handlers.init();
+        self.exec_name = None;
+        self
+    }
+
+    /// Sets the handler of the messages received by the elements of
+    /// this children group which none of the handlers registered with
+    /// [`on`] or [`on_question`] handles, which are dropped otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The closure taking the context of the element
+    ///     and the unhandled message, and returning the [`Future`]
+    ///     handling it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .on(|_ctx, msg: &'static str| async move {
+    ///             // Handle the message...
+    ///             # drop(msg);
+    ///             Ok(())
+    ///         })
+    ///         .on_fallback(|_ctx, msg: SignedMessage| async move {
+    ///             println!("Unexpected message: {:?}", msg);
+    ///             Ok(())
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`on`]: Self::on
+    /// [`on_question`]: Self::on_question
+    pub fn on_fallback<H, F>(mut self, handler: H) -> Self
+    where
+        H: Fn(Arc<BastionContext>, SignedMessage) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        trace!("Children({}): Setting the fallback handler.", self.id());
+        self.handlers.on_fallback(handler);
+        self.init = self.handlers.init();
+        self.exec_name = None;
         self
     }
 
+    /// Sets the exec closure registered with the given name (see
+    /// [`Bastion::register_exec`]), returning `None` if none was.
+    ///
+    /// [`Bastion::register_exec`]: crate::Bastion::register_exec
+    pub(crate) fn with_registered_exec(self, name: &str) -> Option<Self> {
+        let exec = EXECS.get(name)?;
+        let mut children = self.with_exec(move |ctx| exec(ctx));
+        children.exec_name = Some(name.to_string());
+        Some(children)
+    }
+
     /// Sets the number of elements this children group will
     /// contain. Each element will call the closure passed in
     /// [`with_exec`] and run the returned future until it stops,
@@ -385,6 +941,9 @@ impl Children {
     /// Appends each supervised element to the declared dispatcher.
     ///
     /// By default supervised elements aren't added to any of dispatcher.
+    /// This method can be called several times for the group to be
+    /// reachable through each of the dispatchers (see
+    /// [`with_dispatchers`]).
     ///
     /// # Arguments
     ///
@@ -423,11 +982,70 @@ impl Children {
     /// # }
     /// ```
     /// [`DispatcherHandler`]: crate::dispatcher::DispatcherHandler
+    /// [`with_dispatchers`]: Self::with_dispatchers
     pub fn with_dispatcher(mut self, dispatcher: Dispatcher) -> Self {
         self.dispatchers.push(Arc::new(Box::new(dispatcher)));
         self
     }
 
+    /// Appends each supervised element to every declared dispatcher,
+    /// so that a message broadcasted to any of them reaches the group.
+    ///
+    /// # Arguments
+    ///
+    /// * `dispatchers` - The dispatchers the elements are appended to.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children.with_dispatchers(vec![
+    ///         Dispatcher::with_type(DispatcherType::Named("CustomGroup".to_string())),
+    ///         Dispatcher::with_type(DispatcherType::Anonymous),
+    ///     ])
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn with_dispatchers(self, dispatchers: Vec<Dispatcher>) -> Self {
+        dispatchers.into_iter().fold(self, |children, dispatcher| {
+            children.with_dispatcher(dispatcher)
+        })
+    }
+
+    /// Appends each supervised element to the dispatchers shared by
+    /// all the children groups of the supervisor (see
+    /// [`Supervisor::with_dispatcher`]).
+    ///
+    /// [`Supervisor::with_dispatcher`]: crate::supervisor::Supervisor::with_dispatcher
+    pub(crate) fn with_shared_dispatchers(mut self, dispatchers: &[Arc<Box<Dispatcher>>]) -> Self {
+        for dispatcher in dispatchers {
+            self.shared_dispatchers.push(dispatcher.dispatcher_type());
+            self.dispatchers.push(dispatcher.clone());
+        }
+
+        self
+    }
+
     /// Appends a distributor to the children.
     ///
     /// By default supervised elements aren't added to any distributor.
@@ -481,7 +1099,8 @@ impl Children {
     ///
     /// # Arguments
     ///
-    /// * `resizer` - An instance of the [`Resizer`] struct.
+    /// * `resizer` - The resizing policy, either an
+    ///     [`OptimalSizeExploringResizer`] or an [`UtilizationResizer`].
     ///
     /// # Example
     ///
@@ -517,9 +1136,21 @@ impl Children {
     ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
-    pub fn with_resizer(mut self, resizer: OptimalSizeExploringResizer) -> Self {
-        self.redundancy = resizer.lower_bound() as usize;
-        self.resizer = Box::new(resizer);
+    pub fn with_resizer<R: Into<Resizer>>(mut self, resizer: R) -> Self {
+        match resizer.into() {
+            Resizer::OptimalSizeExploring(resizer) => {
+                self.redundancy = resizer.lower_bound() as usize;
+                self.resizer = Box::new(resizer);
+                self.utilization_resizer = None;
+            }
+            Resizer::Utilization(resizer) => {
+                self.redundancy = resizer.min();
+                // The utilization is inspected on every heartbeat.
+                self.hearbeat_tick = resizer.interval();
+                self.utilization_resizer = Some(resizer);
+            }
+        }
+
         self
     }
 
@@ -641,27 +1272,1087 @@ impl Children {
         self
     }
 
-    /// Returns executable code for the actor that will trigger heartbeat
-    fn get_heartbeat_fut(&self) -> Init {
-        let interval = self.hearbeat_tick;
-
-        let exec_fut = move |ctx: BastionContext| async move {
-            let self_path = ctx.current().path();
-            let self_sender = ctx.current().sender();
-
-            loop {
-                Delay::new(interval).await;
-
-                let msg = BastionMessage::heartbeat();
-                let env = Envelope::new(msg, self_path.clone(), self_sender.clone());
-                ctx.parent().send(env).ok();
-            }
-        };
-
-        Init::new(exec_fut)
-    }
-
-    async fn disable_helper_actors(&mut self) {
+    /// Watches the elements of this group, restarting the ones which
+    /// didn't prove they aren't hung (e.g. stuck in a blocking call or
+    /// a deadlock) for `miss_threshold` times `interval`, with
+    /// [`FaultReason::Unresponsive`].
+    ///
+    /// An element proves it isn't hung by returning to its message
+    /// loop (any of the ways to receive a message does), while it
+    /// waits for a message, or by calling
+    /// [`BastionContext::heartbeat`].
+    ///
+    /// The elements are checked on every heartbeat of the group,
+    /// whose interval is lowered to `interval` if it was longer (see
+    /// [`with_heartbeat_tick`]). Note that a future can't be
+    /// interrupted while it blocks its thread, so a hung element is
+    /// only replaced once it yields again.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The interval the elements are expected to prove
+    ///     they aren't hung at.
+    /// * `miss_threshold` - How many intervals an element can go
+    ///     without proving it before being restarted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     // The elements are restarted if they don't get back to their
+    ///     // message loop for 3 seconds.
+    ///     children
+    ///         .with_heartbeat(Duration::from_secs(1), 3)
+    ///         .with_exec(|ctx| async move {
+    ///             loop {
+    ///                 let msg = ctx.recv().await?;
+    ///                 // ...
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`FaultReason::Unresponsive`]: crate::supervisor::FaultReason::Unresponsive
+    /// [`BastionContext::heartbeat`]: crate::context::BastionContext::heartbeat
+    /// [`with_heartbeat_tick`]: Self::with_heartbeat_tick
+    pub fn with_heartbeat(mut self, interval: Duration, miss_threshold: u32) -> Self {
+        trace!(
+            "Children({}): Setting heartbeat: interval={:?} miss_threshold={}",
+            self.id(),
+            interval,
+            miss_threshold
+        );
+        self.watchdog = Some(interval * miss_threshold.max(1));
+        self.hearbeat_tick = self.hearbeat_tick.min(interval);
+        self
+    }
+
+    /// Sets the amount of messages the elements of this group receive
+    /// in a row before yielding to the other elements, so that an
+    /// element with a busy mailbox can't starve the ones sharing its
+    /// threads, bounding their latency.
+    ///
+    /// The default budget is `128` messages, unless the system was
+    /// configured with another one (see
+    /// [`Config::with_fairness_budget`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `budget` - The amount of messages an element receives before
+    ///     yielding (at least `1`).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_fairness_budget(16)
+    ///         .with_exec(|ctx| async move {
+    ///             loop {
+    ///                 let msg = ctx.recv().await?;
+    ///                 // ...
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Config::with_fairness_budget`]: crate::config::Config::with_fairness_budget
+    pub fn with_fairness_budget(mut self, budget: usize) -> Self {
+        trace!(
+            "Children({}): Setting fairness budget: {}",
+            self.id(),
+            budget
+        );
+        self.fairness_budget = budget.max(1);
+        self
+    }
+
+    /// Sets a circuit breaker around every element of this children
+    /// group.
+    ///
+    /// When an element faults `threshold` times within `window`, its
+    /// circuit "opens": the element isn't restarted anymore and the
+    /// messages sent to the group are routed to the dead letters
+    /// instead of being delivered to it, while the other elements
+    /// still receive them.
+    /// Once `cooldown` elapsed, the circuit becomes "half-open" and the
+    /// element is restarted to probe its recovery. If it doesn't fault
+    /// again within `cooldown`, the circuit closes; otherwise it opens
+    /// again.
+    ///
+    /// The transitions of the circuits are emitted as [`SystemEvent`]s
+    /// on [`Bastion::event_stream`].
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - The amount of faults within `window` that
+    ///     opens the circuit of an element.
+    /// * `window` - How long a fault counts towards opening the
+    ///     circuit of an element.
+    /// * `cooldown` - How long a circuit stays open before probing
+    ///     the element again.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    /// children
+    ///     .with_circuit_breaker(3, Duration::from_secs(60), Duration::from_secs(10))
+    ///     .with_exec(|ctx| {
+    ///         // -- Children group started.
+    ///         async move {
+    ///             // ...
+    ///             # Ok(())
+    ///         }
+    ///         // -- Children group stopped.
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`SystemEvent`]: crate::events::SystemEvent
+    /// [`Bastion::event_stream`]: crate::Bastion::event_stream
+    pub fn with_circuit_breaker(
+        mut self,
+        threshold: usize,
+        window: Duration,
+        cooldown: Duration,
+    ) -> Self {
+        trace!(
+            "Children({}): Setting circuit breaker: threshold={}, window={:?}, cooldown={:?}",
+            self.id(),
+            threshold,
+            window,
+            cooldown
+        );
+        self.circuit_breaker = Some(CircuitBreaker {
+            threshold,
+            window,
+            cooldown,
+        });
+        self
+    }
+
+    /// Replays to the elements of this children group the messages
+    /// they missed while they were down, once they are restarted.
+    ///
+    /// The messages broadcasted to the group while one of its elements
+    /// faulted and wasn't restarted yet are routed to the dead letters,
+    /// and those sent within the last `window` are replayed to the
+    /// element once restarted, in the order they were sent in.
+    ///
+    /// This is a best-effort recovery: the messages the element had
+    /// received but not handled yet when it faulted are still lost.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - How long a dead-lettered message is kept to be
+    ///     replayed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    /// children
+    ///     .with_deadletter_replay(Duration::from_secs(30))
+    ///     .with_exec(|ctx| {
+    ///         // -- Children group started.
+    ///         async move {
+    ///             // ...
+    ///             # Ok(())
+    ///         }
+    ///         // -- Children group stopped.
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn with_deadletter_replay(mut self, window: Duration) -> Self {
+        trace!(
+            "Children({}): Setting dead letters replay: window={:?}",
+            self.id(),
+            window
+        );
+        self.replay = Some(DeadLetterReplay::new(window));
+        self
+    }
+
+    /// Persists the messages of type `M` received by the elements of
+    /// the group to a write-ahead log stored in the `path` directory,
+    /// so that the ones they didn't handle yet are replayed to them
+    /// once they are restarted after faulting, or once the group is
+    /// launched again with the same `path` after a crash.
+    ///
+    /// A message is appended to the log once it is received by an
+    /// element, and marked as done once the element asks for the next
+    /// one or its future returns `Ok(())`. The log is split into
+    /// segments, which are removed once all their messages are done.
+    ///
+    /// Only the messages sent using "tells" or broadcasts of type `M`
+    /// are persisted, and the replayed ones are received as "tells"
+    /// from the dead letters. If the log can't be opened, the messages
+    /// aren't persisted.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The directory storing the log, which is created if
+    ///     it doesn't exist.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use serde::{Deserialize, Serialize};
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// #[derive(Debug, Serialize, Deserialize)]
+    /// struct Job(u64);
+    ///
+    /// # fn run() {
+    /// # Bastion::init();
+    /// # let path = std::env::temp_dir().join("bastion-jobs");
+    /// #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_persistence::<Job, _>(path)
+    ///         .with_exec(|ctx| {
+    ///             async move {
+    ///                 loop {
+    ///                     msg! { ctx.recv().await?,
+    ///                         job: Job => {
+    ///                             // Replayed until it was handled...
+    ///                         };
+    ///                         _: _ => ();
+    ///                     }
+    ///                 }
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn with_persistence<M, P>(mut self, path: P) -> Self
+    where
+        M: Message + Serialize + DeserializeOwned,
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        trace!(
+            "Children({}): Persisting messages to: {:?}",
+            self.id(),
+            path
+        );
+        match Persistence::open::<M, _>(path) {
+            Ok(persistence) => self.persistence = Some(Arc::new(persistence)),
+            Err(e) => warn!(
+                "Children({}): Couldn't open the log in {:?}: {}",
+                self.id(),
+                path,
+                e
+            ),
+        }
+
+        self
+    }
+
+    /// Sets what happens to the mailbox of the elements of this group
+    /// when they are restarted: whether the messages waiting in it are
+    /// handed over to the restarted elements, and whether the message
+    /// that was being handled, which might have caused the fault (a
+    /// "poison" message), is too.
+    ///
+    /// The default handover is [`MailboxHandover::Full`].
+    ///
+    /// # Arguments
+    ///
+    /// * `handover` - What happens to the mailbox of a restarted
+    ///     element.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_mailbox_handover(MailboxHandover::SkipInFlight)
+    ///         .with_exec(|ctx| async move {
+    ///             loop {
+    ///                 let msg = ctx.recv().await?;
+    ///                 // A message making the element fault isn't
+    ///                 // received again once it restarted...
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn with_mailbox_handover(mut self, handover: MailboxHandover) -> Self {
+        trace!(
+            "Children({}): Setting mailbox handover: {:?}",
+            self.id(),
+            handover
+        );
+        self.handover = handover;
+        self
+    }
+
+    /// Bounds the mailbox of the elements of this group to the given
+    /// amount of messages, evicting the messages of the lowest drop
+    /// priority (see [`DropPriorities`]) first once it is full.
+    ///
+    /// Among the messages of the lowest priority, including the one
+    /// being pushed, the oldest is evicted and routed to the dead
+    /// letters, so that the messages of a lower priority than all the
+    /// ones waiting in the mailbox are evicted right away.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The amount of messages an element's mailbox
+    ///     holds before evicting some of them.
+    /// * `priorities` - The drop priorities of the message types.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// #[derive(Debug)]
+    /// struct Ping;
+    /// #[derive(Debug)]
+    /// struct Command(String);
+    ///
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_typed_overflow(
+    ///             1_000,
+    ///             DropPriorities::new()
+    ///                 .with_priority::<Ping>(0)
+    ///                 .with_priority::<Command>(10),
+    ///         )
+    ///         .with_exec(|ctx| async move {
+    ///             loop {
+    ///                 let msg = ctx.recv().await?;
+    ///                 // The pings are evicted before the commands
+    ///                 // once the mailbox is full...
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn with_typed_overflow(mut self, capacity: usize, priorities: DropPriorities) -> Self {
+        trace!(
+            "Children({}): Setting typed overflow: capacity={} priorities={:?}",
+            self.id(),
+            capacity,
+            priorities
+        );
+        self.overflow = Some((capacity.max(1), Arc::new(priorities)));
+        self
+    }
+
+    /// Sets what happens to the elements of this group when they
+    /// panic while handling a message received with
+    /// [`BastionContext::recv_with`]: whether they are restarted,
+    /// resumed with their state intact, or stopped for good.
+    ///
+    /// The default policy is [`PanicPolicy::Restart`].
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - What happens to an element when it panics while
+    ///     handling a message.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_panic_policy(PanicPolicy::Resume)
+    ///         .with_exec(|ctx| async move {
+    ///             let mut handled = 0;
+    ///             loop {
+    ///                 // A message making the handler panic is
+    ///                 // dropped, and `handled` is kept...
+    ///                 ctx.recv_with(|msg| handled += 1).await?;
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`BastionContext::recv_with`]: crate::context::BastionContext::recv_with
+    pub fn with_panic_policy(mut self, policy: PanicPolicy) -> Self {
+        trace!(
+            "Children({}): Setting panic policy: {:?}",
+            self.id(),
+            policy
+        );
+        self.panic_policy = policy;
+        self
+    }
+
+    /// Adds a transform rewriting every message of type `M` sent by
+    /// the elements of this children group before it is routed, e.g.
+    /// to stamp a correlation id or a tenant on it.
+    ///
+    /// The transforms apply to the messages sent with
+    /// [`BastionContext::tell`] and its variants, and to the messages
+    /// broadcasted or published with [`BastionContext::broadcast_message`]
+    /// and its variants. They run in the order they were added,
+    /// and don't apply to the messages the elements receive nor to the
+    /// ones sent to them from outside of the group.
+    ///
+    /// # Arguments
+    ///
+    /// * `transform` - The function rewriting the messages.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// #[derive(Debug)]
+    /// struct Request {
+    ///     tenant: Option<&'static str>,
+    ///     body: &'static str,
+    /// }
+    ///
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_outbound_transform(|mut req: Request| {
+    ///             req.tenant = Some("acme");
+    ///             req
+    ///         })
+    ///         .with_exec(|ctx: BastionContext| async move {
+    ///             // The request is sent with `tenant: Some("acme")`.
+    ///             let req = Request { tenant: None, body: "hello" };
+    ///             ctx.broadcast_message(BroadcastTarget::All, req);
+    ///
+    ///             Ok(())
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`BastionContext::tell`]: crate::context::BastionContext::tell
+    /// [`BastionContext::broadcast_message`]: crate::context::BastionContext::broadcast_message
+    pub fn with_outbound_transform<M, F>(mut self, transform: F) -> Self
+    where
+        M: Message,
+        F: Fn(M) -> M + Send + Sync + 'static,
+    {
+        trace!(
+            "Children({}): Adding an outbound transform of {}.",
+            self.id(),
+            std::any::type_name::<M>()
+        );
+        self.outbound.push(transform);
+        self
+    }
+
+    /// Sets how many messages each element of this group can have
+    /// asked (using [`BastionContext::ask`] or [`BastionContext::try_ask`])
+    /// without having received or dropped their [`Answer`] yet.
+    ///
+    /// Once the limit is reached, [`BastionContext::ask`] returns the
+    /// message back and [`BastionContext::try_ask`] returns
+    /// [`SendError::TooManyAsks`], until one of the outstanding asks
+    /// completes. The number of asks is unbounded by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - The maximum number of outstanding asks of each element.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_max_outstanding_asks(64)
+    ///         .with_exec(|ctx: BastionContext| async move {
+    ///             // Ask up to 64 messages at once...
+    ///             Ok(())
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`BastionContext::ask`]: crate::context::BastionContext::ask
+    /// [`BastionContext::try_ask`]: crate::context::BastionContext::try_ask
+    /// [`Answer`]: crate::message::Answer
+    /// [`SendError::TooManyAsks`]: crate::errors::SendError::TooManyAsks
+    pub fn with_max_outstanding_asks(mut self, max: usize) -> Self {
+        trace!(
+            "Children({}): Setting the maximum of outstanding asks to {}.",
+            self.id(),
+            max
+        );
+        self.max_outstanding_asks = Some(max);
+        self
+    }
+
+    /// Makes the group emit a [`SystemEvent::BroadcastDelivered`]
+    /// receipt, observable through [`Bastion::event_stream`], for
+    /// every element a message broadcasted to it (see
+    /// [`ChildrenRef::broadcast`]) is enqueued for.
+    ///
+    /// Receipts are emitted by the system when the message is put in
+    /// the mailbox of an element, without the element having to do
+    /// anything, which allows to reconcile the broadcasts with their
+    /// deliveries and to detect silent drops. All the receipts of a
+    /// broadcast share the same sequence number.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use futures::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// let mut events = Bastion::event_stream();
+    ///
+    /// let children_ref = Bastion::children(|children| {
+    ///     children
+    ///         .with_redundancy(3)
+    ///         .with_delivery_receipts()
+    ///         .with_exec(|ctx| async move {
+    ///             // ...
+    ///             # Ok(())
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    ///
+    /// children_ref.broadcast("A message.").expect("Couldn't send the message.");
+    /// run!(async {
+    ///     while let Some(event) = events.next().await {
+    ///         if let SystemEvent::BroadcastDelivered { path, at, .. } = event {
+    ///             println!("Delivered to {} at {:?}.", path, at);
+    ///             # break;
+    ///         }
+    ///     }
+    /// });
+    /// #
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`SystemEvent::BroadcastDelivered`]: crate::events::SystemEvent::BroadcastDelivered
+    /// [`Bastion::event_stream`]: crate::Bastion::event_stream
+    /// [`ChildrenRef::broadcast`]: crate::children_ref::ChildrenRef::broadcast
+    pub fn with_delivery_receipts(mut self) -> Self {
+        trace!("Children({}): Enabling delivery receipts.", self.id());
+        self.delivery_receipts = true;
+        self
+    }
+
+    /// Sets the strategy the supervisor of this group uses when its
+    /// elements fault, instead of the supervisor's own strategy, so
+    /// that groups with different failure semantics can be supervised
+    /// by the same supervisor.
+    ///
+    /// The strategy only applies to the elements of this group:
+    ///     - [`SupervisionStrategy::OneForOne`] restarts the faulted
+    ///         element.
+    ///     - [`SupervisionStrategy::OneForAll`] restarts all the
+    ///         elements of the group.
+    ///     - [`SupervisionStrategy::RestForOne`] restarts the faulted
+    ///         element and the ones launched after it.
+    ///
+    /// The other groups of the supervisor are never restarted because
+    /// of it. If no strategy is set, the supervisor's one is used (see
+    /// [`Supervisor::with_strategy`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - The strategy to use when an element of this
+    ///     group faults.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::supervisor(|sp| {
+    ///     sp.with_strategy(SupervisionStrategy::OneForOne)
+    ///         // All the elements of this group are restarted when one
+    ///         // of them faults...
+    ///         .children(|children| {
+    ///             children
+    ///                 .with_redundancy(3)
+    ///                 .with_strategy(SupervisionStrategy::OneForAll)
+    ///                 .with_exec(|ctx| async move {
+    ///                     // ...
+    ///                     # Ok(())
+    ///                 })
+    ///         })
+    ///         // ...while only the faulted ones of this group are.
+    ///         .children(|children| {
+    ///             children.with_redundancy(3).with_exec(|ctx| async move {
+    ///                 // ...
+    ///                 # Ok(())
+    ///             })
+    ///         })
+    /// }).expect("Couldn't create the supervisor.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Supervisor::with_strategy`]: crate::supervisor::Supervisor::with_strategy
+    pub fn with_strategy(mut self, strategy: SupervisionStrategy) -> Self {
+        trace!("Children({}): Setting strategy: {:?}", self.id(), strategy);
+        self.strategy = Some(strategy);
+        self
+    }
+
+    /// Isolates the group from the others by capping how many of its
+    /// elements can be executing at once to `max_concurrency`.
+    ///
+    /// The elements still receive messages while waiting for their
+    /// turn, but don't run their future, so that a group busy with
+    /// CPU-intensive or blocking work can't hold more than
+    /// `max_concurrency` threads and starve the other groups.
+    ///
+    /// By default the elements of a group aren't limited.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_concurrency` - The maximum amount of elements of the
+    ///     group executing at once, which must be positive.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    /// children
+    ///     .with_redundancy(8)
+    ///     .with_bulkhead(2)
+    ///     .with_exec(|ctx| {
+    ///         // -- Children group started.
+    ///         async move {
+    ///             // ...
+    ///             # Ok(())
+    ///         }
+    ///         // -- Children group stopped.
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn with_bulkhead(mut self, max_concurrency: usize) -> Self {
+        trace!(
+            "Children({}): Setting bulkhead: max_concurrency={}",
+            self.id(),
+            max_concurrency
+        );
+        self.bulkhead = Some(Arc::new(Bulkhead::new(max_concurrency)));
+        self
+    }
+
+    /// Sets how long an element of the group has to reach its
+    /// running state once it is launched or restarted, after which it
+    /// faults with [`FaultReason::LaunchTimedOut`] and is handled by
+    /// its supervisor like any other fault.
+    ///
+    /// An element reaches its running state once its future waits
+    /// for a message (with [`BastionContext::recv`] or one of its
+    /// variants) or returns, so that an element whose initialization
+    /// never completes doesn't stall silently.
+    ///
+    /// By default elements have no launch timeout.
+    ///
+    /// # Arguments
+    ///
+    /// * `launch_timeout` - How long an element has to reach its
+    ///     running state.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_launch_timeout(Duration::from_secs(5))
+    ///         .with_exec(|ctx| {
+    ///             async move {
+    ///                 // The element has 5 seconds to get there...
+    ///                 ctx.recv().await?;
+    ///                 # Ok(())
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`FaultReason::LaunchTimedOut`]: crate::supervisor::FaultReason::LaunchTimedOut
+    /// [`BastionContext::recv`]: crate::context::BastionContext::recv
+    pub fn with_launch_timeout(mut self, launch_timeout: Duration) -> Self {
+        trace!(
+            "Children({}): Setting launch timeout: {:?}",
+            self.id(),
+            launch_timeout
+        );
+        self.launch_timeout = Some(launch_timeout);
+        self
+    }
+
+    /// Sets the mailbox depth above which asking an element of the
+    /// group fails fast instead of queuing the question behind the
+    /// element's backlog.
+    ///
+    /// Once an element has `high_watermark` messages waiting in its
+    /// mailbox, [`ChildRef::try_ask_anonymously`] returns
+    /// `SendError::Overloaded` right away, allowing callers to shed
+    /// load upstream, until the element catches up.
+    ///
+    /// By default asking an element never fails because of its
+    /// mailbox depth.
+    ///
+    /// # Arguments
+    ///
+    /// * `high_watermark` - The amount of messages waiting in an
+    ///     element's mailbox above which it refuses to be asked.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_mailbox_high_watermark(1_000)
+    ///         .with_exec(|ctx| {
+    ///             // -- Children group started.
+    ///             async move {
+    ///                 // ...
+    ///                 # Ok(())
+    ///             }
+    ///             // -- Children group stopped.
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef::try_ask_anonymously`]: crate::child_ref::ChildRef::try_ask_anonymously
+    pub fn with_mailbox_high_watermark(mut self, high_watermark: usize) -> Self {
+        trace!(
+            "Children({}): Setting mailbox high-watermark: {}",
+            self.id(),
+            high_watermark
+        );
+        self.high_watermark = Some(high_watermark);
+        self
+    }
+
+    // Attaches the mailbox depth of the element to its `ChildRef`
+    // if the group has a high-watermark.
+    fn watermarked(&self, child_ref: ChildRef) -> ChildRef {
+        match (self.high_watermark, self.depths.get(child_ref.id())) {
+            (Some(high), Some(depth)) => child_ref.with_high_watermark(depth.clone(), high),
+            _ => child_ref,
+        }
+    }
+
+    /// Returns executable code for the actor that will trigger heartbeat
+    fn get_heartbeat_fut(&self) -> Init {
+        let interval = self.hearbeat_tick;
+
+        let exec_fut = move |ctx: BastionContext| async move {
+            let self_path = ctx.current().path();
+            let self_sender = ctx.current().sender();
+
+            loop {
+                Delay::new(interval).await;
+
+                let msg = BastionMessage::heartbeat();
+                let env = Envelope::new(msg, self_path.clone(), self_sender.clone());
+                ctx.parent().send(env).ok();
+            }
+        };
+
+        Init::new(exec_fut)
+    }
+
+    async fn disable_helper_actors(&mut self) {
         let mut children = FuturesOrdered::new();
         for (_, (_, launched)) in self.helper_actors.drain() {
             launched.cancel();
@@ -682,6 +2373,8 @@ impl Children {
         self.bcast.kill_children();
 
         let mut children = FuturesOrdered::new();
+        self.depths.clear();
+        self.down.clear();
         for (_, (_, launched)) in self.launched.drain() {
             launched.cancel();
 
@@ -698,6 +2391,10 @@ impl Children {
 
     fn stopped(&mut self) {
         debug!("Children({}): Stopped.", self.id());
+        TOPOLOGY.unregister(self.id());
+        HEALTH.unregister(self.id());
+        #[cfg(feature = "metrics")]
+        LATENCIES.unregister(self.id());
         if let Err(e) = self.remove_dispatchers() {
             warn!("couldn't remove all dispatchers from the registry: {}", e);
         };
@@ -707,15 +2404,19 @@ impl Children {
         self.bcast.stopped();
     }
 
-    fn faulted(&mut self) {
+    fn faulted(&mut self, reason: FaultReason) {
         debug!("Children({}): Faulted.", self.id());
+        TOPOLOGY.unregister(self.id());
+        HEALTH.unregister(self.id());
+        #[cfg(feature = "metrics")]
+        LATENCIES.unregister(self.id());
         if let Err(e) = self.remove_dispatchers() {
             warn!("couldn't remove all dispatchers from the registry: {}", e);
         };
         if let Err(e) = self.remove_distributors() {
             warn!("couldn't remove all distributors from the registry: {}", e);
         };
-        self.bcast.faulted();
+        self.bcast.faulted(reason);
     }
 
     async fn kill_children(&mut self) -> Result<(), ()> {
@@ -732,12 +2433,49 @@ impl Children {
         Err(())
     }
 
+    async fn graceful_stop_children(&mut self, drain_timeout: Duration) -> Result<(), ()> {
+        debug!(
+            "Children({}): Stopping gracefully (drain_timeout={:?}).",
+            self.id(),
+            drain_timeout
+        );
+        self.disable_helper_actors().await;
+
+        let msg = BastionMessage::graceful_stop(drain_timeout);
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        self.bcast.send_children(env);
+        self.bcast.clear_children();
+
+        let mut children = FuturesOrdered::new();
+        self.depths.clear();
+        self.down.clear();
+        for (_, (_, launched)) in self.launched.drain() {
+            children.push(launched);
+        }
+
+        let id = self.id();
+        children
+            .for_each_concurrent(None, |_| async {
+                trace!("Children({}): Child drained its mailbox.", id);
+            })
+            .await;
+
+        self.stopped();
+        Err(())
+    }
+
     async fn handle_stopped_child(&mut self, id: &BastionId) -> Result<(), ()> {
         // FIXME: Err if false?
         if self.launched.contains_key(&id) {
             debug!("Children({}): Child({}) stopped.", self.id(), id);
             self.drop_child(id);
 
+            #[cfg(feature = "json-logs")]
+            supervision_log::log(SupervisionEvent::ChildStopped, &self.child_path(id), None);
+
+            let id = id.clone();
+            EVENTS.emit(SystemEvent::ChildStopped { id });
+
             let msg = BastionMessage::finished_child(id.clone(), self.bcast.id().clone());
             let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
             self.bcast.send_parent(env).ok();
@@ -746,12 +2484,16 @@ impl Children {
         Ok(())
     }
 
-    async fn handle_faulted_child(&mut self, id: &BastionId) -> Result<(), ()> {
+    async fn handle_faulted_child(
+        &mut self,
+        id: &BastionId,
+        reason: FaultReason,
+    ) -> Result<(), ()> {
         // FIXME: Err if false?
         if self.launched.contains_key(id) {
             warn!("Children({}): Child({}) faulted.", self.id(), id);
             self.kill().await;
-            self.faulted();
+            self.faulted(reason);
 
             return Err(());
         }
@@ -759,16 +2501,277 @@ impl Children {
         Ok(())
     }
 
-    fn request_restarting_child(&mut self, id: &BastionId, parent_id: &BastionId) {
-        if parent_id == self.bcast.id() && self.launched.contains_key(id) {
-            let parent_id = self.bcast.id().clone();
-            let msg = BastionMessage::restart_required(id.clone(), parent_id);
-            let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
-            self.bcast.send_parent(env).ok();
+    fn request_restarting_child(
+        &mut self,
+        id: &BastionId,
+        parent_id: &BastionId,
+        reason: FaultReason,
+    ) {
+        if parent_id != self.bcast.id() {
+            return;
+        }
+
+        // NOTE: an element with an open circuit isn't running, so this
+        //      is the request scheduled by `open_circuit` to probe it.
+        if self.is_circuit_open(id) {
+            if self.half_open_circuit(id) {
+                self.send_restart_required(id, reason);
+            }
+
+            return;
+        }
+
+        if self.launched.contains_key(id) {
+            self.remove_from_dispatchers(id);
+            self.down.insert(id.clone());
+            self.update_topology();
+            #[cfg(feature = "json-logs")]
+            {
+                let path = self.child_path(id);
+                supervision_log::log(SupervisionEvent::ChildFaulted, &path, Some(&reason));
+                self.fault_reasons.insert(id.clone(), reason.clone());
+            }
+            EVENTS.emit(SystemEvent::ChildFaulted {
+                id: id.clone(),
+                parent_id: parent_id.clone(),
+                reason: reason.clone(),
+            });
+
+            // NOTE: an escalated fault is never retried, so it
+            //      doesn't count towards opening the circuit.
+            if reason != FaultReason::Escalated && self.record_fault(id) {
+                self.open_circuit(id, reason);
+            } else {
+                self.send_restart_required(id, reason);
+            }
+        }
+    }
+
+    fn send_restart_required(&self, id: &BastionId, reason: FaultReason) {
+        let parent_id = self.bcast.id().clone();
+        let msg = BastionMessage::restart_required(id.clone(), parent_id, reason);
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        self.bcast.send_parent(env).ok();
+    }
+
+    fn is_circuit_open(&self, id: &BastionId) -> bool {
+        match self.circuits.get(id) {
+            Some(circuit) => matches!(circuit.status, CircuitStatus::Open(_)),
+            None => false,
+        }
+    }
+
+    /// Returns the elements which can't receive the messages sent to
+    /// the group, because their circuit is open or because they are
+    /// down while the group replays the messages they miss.
+    fn unreachable_elements(&self) -> Vec<BastionId> {
+        let open = self
+            .circuits
+            .iter()
+            .filter(|(_, circuit)| matches!(circuit.status, CircuitStatus::Open(_)))
+            .map(|(id, _)| id.clone());
+        let down = self
+            .down
+            .iter()
+            .filter(|_| self.replay.is_some())
+            .filter(|id| !self.is_circuit_open(id))
+            .cloned();
+
+        open.chain(down).collect()
+    }
+
+    /// Sends the messages the restarted element missed while it was
+    /// down, if the group replays them.
+    fn replay_dead_letters(&mut self, id: &BastionId) {
+        let missed = match &mut self.replay {
+            Some(replay) => replay.take(id, Instant::now()),
+            None => return,
+        };
+
+        debug!(
+            "Children({}): Replaying {} dead letters to Child({}).",
+            self.id(),
+            missed.len(),
+            id
+        );
+        for env in missed {
+            self.bcast.send_child(id, env);
+        }
+    }
+
+    /// Records a fault of the given element and returns whether its
+    /// circuit needs to be opened.
+    fn record_fault(&mut self, id: &BastionId) -> bool {
+        let breaker = match &self.circuit_breaker {
+            Some(breaker) => breaker,
+            None => return false,
+        };
+
+        let now = Instant::now();
+        let circuit = self.circuits.entry(id.clone()).or_default();
+        match circuit.status {
+            CircuitStatus::Closed => {
+                circuit.faults.push_back(now);
+                while let Some(fault) = circuit.faults.front() {
+                    if now.duration_since(*fault) < breaker.window {
+                        break;
+                    }
+
+                    circuit.faults.pop_front();
+                }
+
+                circuit.faults.len() >= breaker.threshold
+            }
+            // The probe faulted again.
+            CircuitStatus::HalfOpen(_) => true,
+            CircuitStatus::Open(_) => false,
+        }
+    }
+
+    fn open_circuit(&mut self, id: &BastionId, reason: FaultReason) {
+        let cooldown = match &self.circuit_breaker {
+            Some(breaker) => breaker.cooldown,
+            None => return,
+        };
+
+        warn!(
+            "Children({}): Opening the circuit of Child({}).",
+            self.id(),
+            id
+        );
+        let circuit = self.circuits.entry(id.clone()).or_default();
+        circuit.status = CircuitStatus::Open(Instant::now());
+        circuit.faults.clear();
+
+        self.launched.remove(id);
+        self.depths.remove(id);
+        self.bcast.unregister(id);
+        self.update_topology();
+
+        EVENTS.emit(SystemEvent::CircuitOpened { id: id.clone() });
+
+        let msg = BastionMessage::restart_required(id.clone(), self.bcast.id().clone(), reason);
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        self.send_self_after(env, cooldown);
+    }
+
+    /// Moves the circuit of the given element from open to half-open
+    /// if its cooldown elapsed, and returns whether it did.
+    fn half_open_circuit(&mut self, id: &BastionId) -> bool {
+        let cooldown = match &self.circuit_breaker {
+            Some(breaker) => breaker.cooldown,
+            None => return false,
+        };
+
+        let circuit = match self.circuits.get_mut(id) {
+            Some(circuit) => circuit,
+            None => return false,
+        };
+
+        match circuit.status {
+            CircuitStatus::Open(since) if since.elapsed() >= cooldown => {
+                circuit.status = CircuitStatus::HalfOpen(Instant::now());
+            }
+            _ => return false,
+        }
+
+        debug!(
+            "Children({}): Half-opening the circuit of Child({}).",
+            self.id(),
+            id
+        );
+        EVENTS.emit(SystemEvent::CircuitHalfOpened { id: id.clone() });
+
+        // Checks whether the probe survived the cooldown.
+        let msg = BastionMessage::heartbeat();
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        self.send_self_after(env, cooldown);
+
+        true
+    }
+
+    /// Closes the half-open circuits whose probe didn't fault within
+    /// the cooldown.
+    fn close_recovered_circuits(&mut self) {
+        let cooldown = match &self.circuit_breaker {
+            Some(breaker) => breaker.cooldown,
+            None => return,
+        };
+
+        let id = self.bcast.id().clone();
+        for (child_id, circuit) in self.circuits.iter_mut() {
+            let recovered = matches!(
+                circuit.status,
+                CircuitStatus::HalfOpen(since) if since.elapsed() >= cooldown
+            );
+
+            if recovered {
+                debug!(
+                    "Children({}): Closing the circuit of Child({}).",
+                    id, child_id
+                );
+                circuit.status = CircuitStatus::Closed;
+                EVENTS.emit(SystemEvent::CircuitClosed {
+                    id: child_id.clone(),
+                });
+            }
+        }
+    }
+
+    /// Restarts the watched elements which didn't prove they aren't
+    /// hung within the watchdog's timeout.
+    fn restart_unresponsive_children(&mut self) {
+        let timeout = match self.watchdog {
+            Some(timeout) => timeout,
+            None => return,
+        };
+
+        let unresponsive = self
+            .heartbeats
+            .iter()
+            .filter(|(id, _)| !self.down.contains(*id))
+            .filter(|(_, state)| state.is_unresponsive(timeout))
+            .map(|(id, _)| id.clone())
+            .collect::<Vec<_>>();
+
+        let parent_id = self.bcast.id().clone();
+        for id in unresponsive {
+            warn!(
+                "Children({}): Child({}) didn't prove it isn't hung for {:?}.",
+                self.id(),
+                id,
+                timeout
+            );
+            self.request_restarting_child(&id, &parent_id, FaultReason::Unresponsive);
         }
     }
 
-    fn restart_child(&mut self, old_id: &BastionId, old_state: Arc<Pin<Box<ContextState>>>) {
+    fn send_self_after(&self, env: Envelope, delay: Duration) {
+        let sender = self.bcast.sender().clone();
+        spawner().spawn(
+            async move {
+                Delay::new(delay).await;
+                sender.unbounded_send(env).ok();
+            },
+            ProcStack::default(),
+        );
+    }
+
+    async fn restart_child(&mut self, old_id: &BastionId, old_state: Arc<Pin<Box<ContextState>>>) {
+        // NOTE: with the `OneForAll` and `RestForOne` strategies,
+        //      elements that didn't fault are restarted too, so the
+        //      old element has to be stopped before it gets replaced.
+        //      The delivery of messages is held meanwhile (see
+        //      `hold_children`), so that the messages routed to the
+        //      group are only received once all of them are restored.
+        if let Some((_, launched)) = self.launched.remove(old_id) {
+            launched.cancel();
+            launched.await;
+        }
+        // NOTE: a cancelled element doesn't get the chance to remove
+        //      itself from the dispatchers.
+        self.remove_from_dispatchers(old_id);
+
         let parent = Parent::children(self.as_ref());
         let bcast = Broadcast::new(parent, BastionPathElement::Child(old_id.clone()));
 
@@ -776,6 +2779,21 @@ impl Children {
         let sender = bcast.sender().clone();
         let path = bcast.path().clone();
         let child_ref = ChildRef::new(id.clone(), sender.clone(), self.name(), path);
+        self.depths.insert(id.clone(), old_state.depth());
+        let child_ref = self.watermarked(child_ref);
+        match self.handover {
+            MailboxHandover::Full => old_state.replay_in_flight(),
+            MailboxHandover::SkipInFlight => old_state.complete_in_flight(),
+            MailboxHandover::Discard => {
+                old_state.complete_in_flight();
+                old_state.discard_messages(child_ref.path());
+            }
+        }
+        old_state.reset_running();
+        old_state.heartbeat();
+        // NOTE: the old element might have been stopped before
+        //      receiving the last `Pause` or `Resume` message.
+        old_state.set_paused(self.is_paused());
 
         let children = self.as_ref();
         let supervisor = self.bcast.parent().clone().into_supervisor();
@@ -806,7 +2824,9 @@ impl Children {
         debug!("Children({}): Restarting Child({}).", self.id(), bcast.id());
         let callbacks = self.callbacks.clone();
         let state = Arc::new(Box::pin(ContextState::new()));
-        let child = Child::new(exec, callbacks, bcast, state, child_ref);
+        let child = Child::new(exec, callbacks, bcast, state, child_ref)
+            .with_bulkhead(self.bulkhead.clone())
+            .with_launch_timeout(self.launch_timeout);
         debug!(
             "Children({}): Launching faulted Child({}).",
             self.id(),
@@ -814,7 +2834,65 @@ impl Children {
         );
         let id = child.id().clone();
         let launched = child.launch();
-        self.launched.insert(id, (sender, launched));
+        self.launched.insert(id.clone(), (sender, launched));
+        self.down.remove(&id);
+        self.update_topology();
+        self.replay_dead_letters(&id);
+
+        // NOTE: the elements restarted along with a faulted one
+        //      (see `SupervisionStrategy`) don't have a reason.
+        #[cfg(feature = "json-logs")]
+        {
+            let reason = self.fault_reasons.remove(old_id);
+            let path = self.child_path(&id);
+            supervision_log::log(SupervisionEvent::ChildRestarted, &path, reason.as_ref());
+        }
+
+        let old_id = old_id.clone();
+        EVENTS.emit(SystemEvent::ChildRestarted { id, old_id });
+    }
+
+    // The path of the element of the group with the given id.
+    fn child_path(&self, id: &BastionId) -> BastionPath {
+        // NOTE: a children group's path always ends with it, so an
+        //      element can always be appended to it.
+        BastionPath::clone(self.bcast.path())
+            .append(BastionPathElement::Child(id.clone()))
+            .expect("couldn't append an element to its group's path")
+    }
+
+    // Whether the elements of the group are kept from receiving
+    // messages, because it was paused or its delivery is held.
+    fn is_paused(&self) -> bool {
+        self.paused || self.held
+    }
+
+    fn pause_children(&mut self, paused: bool) {
+        debug!("Children({}): Setting paused={}.", self.id(), paused);
+        let was_paused = self.is_paused();
+        self.paused = paused;
+        self.update_paused(was_paused);
+    }
+
+    /// Holds the delivery of messages to the elements of the group
+    /// while its supervisor restarts several of them, or releases
+    /// it once they are all restored, without resuming a group that
+    /// was paused meanwhile.
+    fn hold_children(&mut self, held: bool) {
+        debug!("Children({}): Setting held={}.", self.id(), held);
+        let was_paused = self.is_paused();
+        self.held = held;
+        self.update_paused(was_paused);
+    }
+
+    fn update_paused(&self, was_paused: bool) {
+        let msg = match (was_paused, self.is_paused()) {
+            (false, true) => BastionMessage::pause(),
+            (true, false) => BastionMessage::resume(),
+            _ => return,
+        };
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        self.bcast.send_children(env);
     }
 
     fn drop_child(&mut self, id: &BastionId) {
@@ -824,6 +2902,16 @@ impl Children {
             id,
         );
         self.launched.remove_entry(id);
+        self.depths.remove(id);
+        self.heartbeats.remove(id);
+        self.down.remove(id);
+        #[cfg(feature = "json-logs")]
+        self.fault_reasons.remove(id);
+        if let Some(replay) = &mut self.replay {
+            replay.forget(id);
+        }
+        self.remove_from_dispatchers(id);
+        self.update_topology();
 
         #[cfg(feature = "scaling")]
         self.update_actors_count_stats();
@@ -839,6 +2927,10 @@ impl Children {
                 msg: BastionMessage::Stop,
                 ..
             } => self.stop_children().await?,
+            Envelope {
+                msg: BastionMessage::GracefulStop { drain_timeout },
+                ..
+            } => self.graceful_stop_children(drain_timeout).await?,
             Envelope {
                 msg: BastionMessage::Kill,
                 ..
@@ -875,12 +2967,52 @@ impl Children {
                     self.id(),
                     message
                 );
-                self.bcast.send_children(envelope);
+                // NOTE: only the elements which can't receive the message
+                //      miss it, the other ones receiving it below.
+                for id in self.unreachable_elements() {
+                    if let Some(env) = envelope.try_clone() {
+                        debug!(
+                            "Children({}): Routing a message to the dead letters: Child({}) is down.",
+                            self.id(),
+                            id
+                        );
+                        SYSTEM.dead_letters_of(&self.child_path(&id)).send(env).ok();
+                    }
+                }
+
+                if let Some(replay) = &mut self.replay {
+                    replay.record(&envelope, &self.down, Instant::now());
+                }
+
+                if self.delivery_receipts {
+                    self.broadcasts += 1;
+                    let delivered = self.bcast.send_children_receipted(envelope);
+                    let at = SystemTime::now();
+                    for id in delivered {
+                        EVENTS.emit(SystemEvent::BroadcastDelivered {
+                            group: self.id().clone(),
+                            broadcast: self.broadcasts,
+                            path: Arc::new(self.child_path(&id)),
+                            at,
+                        });
+                    }
+                } else {
+                    self.bcast.send_children(envelope);
+                }
             }
             Envelope {
-                msg: BastionMessage::RestartRequired { id, parent_id },
+                msg: BastionMessage::Batch(_),
                 ..
-            } => self.request_restarting_child(&id, &parent_id),
+            } => unreachable!(),
+            Envelope {
+                msg:
+                    BastionMessage::RestartRequired {
+                        id,
+                        parent_id,
+                        reason,
+                    },
+                ..
+            } => self.request_restarting_child(&id, &parent_id, reason),
             Envelope {
                 msg: BastionMessage::FinishedChild { .. },
                 ..
@@ -892,7 +3024,7 @@ impl Children {
             Envelope {
                 msg: BastionMessage::RestoreChild { id, state },
                 ..
-            } => self.restart_child(&id, state),
+            } => self.restart_child(&id, state).await,
             Envelope {
                 msg: BastionMessage::DropChild { id },
                 ..
@@ -906,13 +3038,62 @@ impl Children {
                 ..
             } => self.handle_stopped_child(&id).await?,
             Envelope {
-                msg: BastionMessage::Faulted { id },
+                msg: BastionMessage::Faulted { id, reason },
                 ..
-            } => self.handle_faulted_child(&id).await?,
+            } => self.handle_faulted_child(&id, reason).await?,
             Envelope {
                 msg: BastionMessage::Heartbeat,
                 ..
-            } => {}
+            } => {
+                self.close_recovered_circuits();
+                self.restart_unresponsive_children();
+            }
+            Envelope {
+                msg: BastionMessage::Pause,
+                ..
+            } => self.pause_children(true),
+            Envelope {
+                msg: BastionMessage::Resume,
+                ..
+            } => self.pause_children(false),
+            Envelope {
+                msg: BastionMessage::Hold,
+                ..
+            } => self.hold_children(true),
+            Envelope {
+                msg: BastionMessage::Release,
+                ..
+            } => self.hold_children(false),
+            Envelope {
+                msg: BastionMessage::Thaw,
+                ..
+            } => {
+                debug!(
+                    "Children({}): Resuming the delivery of messages.",
+                    self.id()
+                );
+                self.bcast.send_children(envelope);
+            }
+            Envelope {
+                msg: BastionMessage::Handover { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Adopt(_),
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Reparent(supervisor),
+                ..
+            } => {
+                debug!(
+                    "Children({}): Supervised by Supervisor({}) from now on.",
+                    self.id(),
+                    supervisor.id()
+                );
+                self.bcast.set_parent(Parent::supervisor(supervisor));
+                self.update_topology();
+            }
         }
 
         Ok(())
@@ -957,6 +3138,11 @@ impl Children {
 
     #[cfg(feature = "scaling")]
     async fn autoresize_group(&mut self) {
+        if self.utilization_resizer.is_some() {
+            self.resize_by_utilization();
+            return;
+        }
+
         match self.resizer.scale(&self.launched).await {
             ScalingRule::Upscale(count) => {
                 for _ in 0..count {
@@ -974,6 +3160,40 @@ impl Children {
         self.update_actors_count_stats();
     }
 
+    #[cfg(feature = "scaling")]
+    fn resize_by_utilization(&mut self) {
+        // NOTE: the group isn't resized until it is started.
+        if !self.started {
+            return;
+        }
+
+        let rule = match &mut self.utilization_resizer {
+            Some(resizer) => resizer.scale(&self.depths, Instant::now()),
+            None => return,
+        };
+
+        match rule {
+            ScalingRule::Upscale(count) => {
+                debug!("Children({}): Launching {} elements.", self.id(), count);
+                for _ in 0..count {
+                    self.launch_child();
+                }
+            }
+            ScalingRule::Downscale(ids) => {
+                for id in ids {
+                    debug!("Children({}): Stopping Child({}).", self.id(), id);
+                    // NOTE: the element is removed from the group once
+                    //      it tells that it stopped.
+                    self.depths.remove(&id);
+                    self.bcast.stop_child(&id);
+                }
+            }
+            ScalingRule::DoNothing => (),
+        }
+
+        self.update_actors_count_stats();
+    }
+
     #[cfg(feature = "scaling")]
     fn init_data_for_scaling(&self, state: &mut ContextState) {
         state.set_stats(self.resizer.stats());
@@ -1035,6 +3255,12 @@ impl Children {
     }
 
     pub(crate) fn launch_child(&mut self) {
+        self.launch_child_with(Vec::new());
+    }
+
+    // Launches an element which first receives the given persisted
+    // messages, recovered from the log.
+    fn launch_child_with(&mut self, recovered: Vec<(u64, Msg)>) {
         let name = self.name();
         let parent = Parent::children(self.as_ref());
         let bcast = Broadcast::new(parent, BastionPathElement::Child(BastionId::new()));
@@ -1048,12 +3274,35 @@ impl Children {
         let children = self.as_ref();
         let supervisor = self.bcast.parent().clone().into_supervisor();
 
-        #[allow(unused_mut)]
         let mut state = ContextState::new();
         #[cfg(feature = "scaling")]
         self.init_data_for_scaling(&mut state);
+        let index = self.next_index();
+        state.set_index(index);
+        state.set_fairness_budget(self.fairness_budget);
+        if let Some((capacity, priorities)) = &self.overflow {
+            state.set_overflow(*capacity, priorities.clone());
+        }
+        state.set_panic_policy(self.panic_policy);
+        state.set_outbound_transforms(self.outbound.clone());
+        if let Some(max) = self.max_outstanding_asks {
+            state.set_max_outstanding_asks(max);
+        }
+        self.indices.insert(id.clone(), index);
+        state.set_paused(self.is_paused());
+        if let Some(persistence) = &self.persistence {
+            state.set_persistence(persistence.clone());
+        }
+        for (seq, msg) in recovered {
+            state.push_persisted(seq, msg, RefAddr::dead_letters());
+        }
 
         let state = Arc::new(Box::pin(state));
+        self.depths.insert(id.clone(), state.depth());
+        if self.watchdog.is_some() {
+            self.heartbeats.insert(id.clone(), state.clone());
+        }
+        let child_ref = self.watermarked(child_ref);
 
         let ctx = BastionContext::new(
             id.clone(),
@@ -1081,11 +3330,29 @@ impl Children {
             bcast.id()
         );
         let callbacks = self.callbacks.clone();
-        let child = Child::new(exec, callbacks, bcast, state, child_ref);
+        let child = Child::new(exec, callbacks, bcast, state, child_ref)
+            .with_bulkhead(self.bulkhead.clone())
+            .with_launch_timeout(self.launch_timeout);
         debug!("Children({}): Launching Child({}).", self.id(), child.id());
         let id = child.id().clone();
         let launched = child.launch();
         self.launched.insert(id, (sender, launched));
+        self.update_topology();
+    }
+
+    // The smallest ordinal which isn't used by a launched element of
+    // the group.
+    fn next_index(&mut self) -> usize {
+        let launched = &self.launched;
+        self.indices.retain(|id, _| launched.contains_key(id));
+
+        let mut used = self.indices.values().copied().collect::<Vec<_>>();
+        used.sort_unstable();
+        used.into_iter()
+            .enumerate()
+            .find(|(expected, index)| expected != index)
+            .map(|(expected, _)| expected)
+            .unwrap_or_else(|| self.indices.len())
     }
 
     pub(crate) fn launch_heartbeat(&mut self) {
@@ -1128,8 +3395,22 @@ impl Children {
 
     pub(crate) fn launch_elems(&mut self) {
         debug!("Children({}): Launching elements.", self.id());
-        for _ in 0..self.redundancy {
-            self.launch_child();
+        // The messages which weren't handled before the group crashed
+        // are distributed among its elements.
+        let recovered = match &self.persistence {
+            Some(persistence) => persistence.recover(),
+            None => Vec::new(),
+        };
+        let mut shares = (0..self.redundancy).map(|_| Vec::new()).collect::<Vec<_>>();
+        let count = shares.len().max(1);
+        for (index, recovered) in recovered.into_iter().enumerate() {
+            if let Some(share) = shares.get_mut(index % count) {
+                share.push(recovered);
+            }
+        }
+
+        for share in shares {
+            self.launch_child_with(share);
         }
 
         self.launch_heartbeat();
@@ -1137,8 +3418,35 @@ impl Children {
 
     pub(crate) fn launch(self) -> RecoverableHandle<Self> {
         debug!("Children({}): Launching.", self.id());
+        self.update_topology();
         let stack = self.stack();
-        pool::spawn(self.run(), stack)
+        spawner().spawn(self.run(), stack)
+    }
+
+    /// Updates the group's node in the supervision tree's topology
+    /// and its health.
+    fn update_topology(&self) {
+        let node = ChildrenNode {
+            id: self.id().to_string(),
+            name: self.name(),
+            exec: self.exec_name.clone(),
+            redundancy: self.redundancy,
+            live: self.launched.len(),
+            dispatchers: self
+                .dispatchers
+                .iter()
+                .map(|dispatcher| dispatcher.dispatcher_type().name())
+                .collect(),
+        };
+
+        TOPOLOGY.register_children(self.id(), self.bcast.parent(), node);
+
+        let running = self
+            .launched
+            .keys()
+            .filter(|id| !self.down.contains(id))
+            .count();
+        HEALTH.register_children(self.id(), self.redundancy, running);
     }
 
     /// Registers all declared local dispatchers in the global dispatcher.
@@ -1151,16 +3459,37 @@ impl Children {
         Ok(())
     }
 
-    /// Removes all declared local dispatchers from the global dispatcher.
+    /// Removes all declared local dispatchers from the global dispatcher,
+    /// except the ones shared with the other groups of the supervisor
+    /// and the anonymous one, which is shared with every group.
     pub(crate) fn remove_dispatchers(&self) -> AnyResult<()> {
         let global_dispatcher = SYSTEM.dispatcher();
 
-        for dispatcher in self.dispatchers.iter() {
+        let local_dispatchers = self.dispatchers.iter().filter(|dispatcher| {
+            let dispatcher_type = dispatcher.dispatcher_type();
+            dispatcher_type != DispatcherType::Anonymous
+                && !self.shared_dispatchers.contains(&dispatcher_type)
+        });
+        for dispatcher in local_dispatchers {
             global_dispatcher.remove_dispatcher(dispatcher)?;
         }
         Ok(())
     }
 
+    /// Removes the given element from all declared local dispatchers, so
+    /// that no message is routed to it until it is restarted.
+    fn remove_from_dispatchers(&self, id: &BastionId) {
+        let global_dispatcher = SYSTEM.dispatcher();
+        let dispatchers = self
+            .dispatchers
+            .iter()
+            .map(|dispatcher| dispatcher.dispatcher_type())
+            .collect::<Vec<_>>();
+
+        global_dispatcher.remove_id(&dispatchers, id);
+        global_dispatcher.remove_child(id);
+    }
+
     /// Registers all declared local distributors in the global dispatcher.
     pub(crate) fn register_distributors(&self) -> AnyResult<()> {
         let global_dispatcher = SYSTEM.dispatcher();
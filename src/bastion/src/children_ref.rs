@@ -4,13 +4,17 @@ use crate::broadcast::Sender;
 use crate::context::BastionId;
 use crate::dispatcher::DispatcherType;
 use crate::envelope::Envelope;
-use crate::message::{BastionMessage, Message};
+use crate::health::HEALTH;
+#[cfg(feature = "metrics")]
+use crate::latency::{Percentiles, LATENCIES};
+use crate::message::{check_message_size, BastionMessage, Message};
 use crate::path::BastionPath;
 use crate::system::SYSTEM;
 use crate::{child_ref::ChildRef, distributor::Distributor};
 use std::cmp::{Eq, PartialEq};
 use std::fmt::Debug;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{debug, trace};
 
 #[derive(Debug, Clone)]
@@ -256,6 +260,16 @@ impl ChildrenRef {
             self.id(),
             msg
         );
+        if let Err((size, max)) = check_message_size(&msg) {
+            debug!(
+                "ChildrenRef({}): Refusing to broadcast: the message is {} bytes long, above {}.",
+                self.id(),
+                size,
+                max
+            );
+            return Err(msg);
+        }
+
         let msg = BastionMessage::broadcast(msg);
         let env = Envelope::from_dead_letters(msg);
         // FIXME: panics?
@@ -303,6 +317,165 @@ impl ChildrenRef {
         self.send(env).map_err(|_| ())
     }
 
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to gracefully stop all of its
+    /// running elements.
+    ///
+    /// Unlike [`stop`], the elements stop accepting new messages
+    /// (which are then routed to the dead letters) but keep handling
+    /// the messages that are already in their mailbox until it is
+    /// empty or until `drain_timeout` elapsed, and only then stop.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `drain_timeout` - The maximum amount of time the elements
+    ///     are given to handle the messages of their mailbox.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// # let children_ref = Bastion::children(|children| children).unwrap();
+    /// children_ref
+    ///     .graceful_stop(Duration::from_secs(5))
+    ///     .expect("Couldn't send the message.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`stop`]: Self::stop
+    pub fn graceful_stop(&self, drain_timeout: Duration) -> Result<(), ()> {
+        debug!(
+            "ChildrenRef({}): Stopping gracefully (drain_timeout={:?}).",
+            self.id(),
+            drain_timeout
+        );
+        let msg = BastionMessage::graceful_stop(drain_timeout);
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to pause all of its elements.
+    ///
+    /// Unlike [`stop`], the elements aren't stopped nor restarted
+    /// and keep their state, but the messages they receive are
+    /// kept in their mailbox instead of being handled, until the
+    /// group is resumed with [`resume`]. The elements launched
+    /// while the group is paused are paused too.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// # let children_ref = Bastion::children(|children| children).unwrap();
+    /// children_ref.pause().expect("Couldn't send the message.");
+    /// // The messages sent to the group are kept in the mailboxes...
+    /// children_ref.resume().expect("Couldn't send the message.");
+    /// // ...and handled in order once it is resumed.
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`stop`]: Self::stop
+    /// [`resume`]: Self::resume
+    pub fn pause(&self) -> Result<(), ()> {
+        debug!("ChildrenRef({}): Pausing.", self.id());
+        let msg = BastionMessage::pause();
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to resume all of its elements
+    /// after it was paused with [`pause`], which then handle the
+    /// messages kept in their mailbox in the order they were
+    /// received.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// # let children_ref = Bastion::children(|children| children).unwrap();
+    /// children_ref.pause().expect("Couldn't send the message.");
+    /// // ...
+    /// children_ref.resume().expect("Couldn't send the message.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`pause`]: Self::pause
+    pub fn resume(&self) -> Result<(), ()> {
+        debug!("ChildrenRef({}): Resuming.", self.id());
+        let msg = BastionMessage::resume();
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())
+    }
+
     /// Sends a message to the children group this `ChildrenRef`
     /// is referencing to tell it to kill all of its running
     /// elements.
@@ -344,6 +517,173 @@ impl ChildrenRef {
         self.send(env).map_err(|_| ())
     }
 
+    /// Returns the percentiles of the processing latency of the
+    /// messages handled by the elements of the children group this
+    /// `ChildrenRef` is referencing.
+    ///
+    /// The latency of a message is measured from the moment it
+    /// reaches the mailbox of an element to the moment the element
+    /// is done handling it, that is when it asks for the next one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// # let children_ref = Bastion::children(|children| children).unwrap();
+    /// let percentiles: Percentiles = children_ref.latency_percentiles();
+    /// println!("p99: {:?} over {} messages", percentiles.p99, percentiles.count);
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    #[cfg(feature = "metrics")]
+    pub fn latency_percentiles(&self) -> Percentiles {
+        LATENCIES.percentiles(self.id())
+    }
+
+    /// Returns a future resolving once all the elements of the
+    /// children group this `ChildrenRef` is referencing entered
+    /// their message loop, that is once each of them started waiting
+    /// for messages with [`BastionContext::recv`] (or one of its
+    /// variants).
+    ///
+    /// Messages sent to the elements once it resolved are handled
+    /// by their message loop right away.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// # Bastion::start();
+    /// #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| async move {
+    ///         loop {
+    ///             msg! { ctx.recv().await?,
+    ///                 _: _ => ();
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// run!(children_ref.await_ready());
+    /// children_ref.broadcast("A message containing data.").expect("Couldn't send the message.");
+    /// #
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`BastionContext::recv`]: crate::context::BastionContext::recv
+    pub async fn await_ready(&self) {
+        debug!(
+            "ChildrenRef({}): Waiting for the elements to be ready.",
+            self.id()
+        );
+        let ids = self
+            .children
+            .iter()
+            .map(|child| child.id().clone())
+            .collect();
+        HEALTH.await_ready(ids).await
+    }
+
+    /// Returns a future resolving once none of the elements of the
+    /// children group this `ChildrenRef` is referencing is being
+    /// restarted by its supervisor, that is once each restarted
+    /// element entered its message loop again (see [`await_ready`]).
+    ///
+    /// With the [`SupervisionStrategy::OneForAll`] strategy, all the
+    /// elements of the group are restarted together when one of them
+    /// faults, and this resolves once the whole group is back up, so
+    /// that messages can be held back until then instead of reaching
+    /// a half-restarted group.
+    ///
+    /// It resolves right away if no element is being restarted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// # Bastion::start();
+    /// #
+    /// let supervisor = Bastion::supervisor(|sp| {
+    ///     sp.with_strategy(SupervisionStrategy::OneForAll)
+    /// }).expect("Couldn't create the supervisor.");
+    ///
+    /// let children_ref = supervisor.children(|children| {
+    ///     children.with_redundancy(3).with_exec(|ctx: BastionContext| async move {
+    ///         loop {
+    ///             msg! { ctx.recv().await?,
+    ///                 _: _ => ();
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// run!(children_ref.restart_completed());
+    /// children_ref.broadcast("A message containing data.").expect("Couldn't send the message.");
+    /// #
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`await_ready`]: Self::await_ready
+    /// [`SupervisionStrategy::OneForAll`]: crate::supervisor::SupervisionStrategy::OneForAll
+    pub async fn restart_completed(&self) {
+        debug!(
+            "ChildrenRef({}): Waiting for the restarted elements to be ready.",
+            self.id()
+        );
+        HEALTH.restart_completed(self.id().clone()).await
+    }
+
     pub(crate) fn send(&self, env: Envelope) -> Result<(), Envelope> {
         trace!("ChildrenRef({}): Sending message: {:?}", self.id(), env);
         self.sender.unbounded_send(env).or_else(|err| {
@@ -0,0 +1,278 @@
+//!
+//! Optional compression of the payloads exchanged between the members
+//! of a cluster.
+//!
+//! Compressed payloads are tagged with the codec that was used to
+//! compress them, so that a member never misinterprets a payload it
+//! can't decompress and untagged payloads (sent by members which don't
+//! compress anything) are still delivered as-is.
+use thiserror::Error;
+
+/// The character every tagged payload starts with.
+const TAG: char = '\u{1}';
+/// The codec tag of uncompressed payloads which would otherwise be
+/// mistaken for tagged ones.
+const RAW_TAG: &str = "raw";
+#[cfg(feature = "compression-lz4")]
+const LZ4_TAG: &str = "lz4";
+#[cfg(feature = "compression-zstd")]
+const ZSTD_TAG: &str = "zstd";
+
+/// The default size (in bytes) under which payloads aren't compressed.
+pub const DEFAULT_MIN_SIZE: usize = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An algorithm used to compress the payloads sent to the other
+/// members of a cluster.
+///
+/// Every codec other than [`Codec::None`] is available behind its own
+/// feature.
+pub enum Codec {
+    /// Doesn't compress the payloads, which are sent as-is.
+    None,
+    #[cfg(feature = "compression-lz4")]
+    /// LZ4, available with the `compression-lz4` feature.
+    Lz4,
+    #[cfg(feature = "compression-zstd")]
+    /// Zstandard with the given compression level, available with
+    /// the `compression-zstd` feature.
+    Zstd(i32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The compression settings of a cluster, set with
+/// [`ClusterConfig::with_compression`].
+///
+/// [`ClusterConfig::with_compression`]: crate::distributed::ClusterConfig::with_compression
+pub struct Compression {
+    codec: Codec,
+    min_size: usize,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub(crate) enum DecodeError {
+    #[error("malformed payload tag")]
+    MalformedTag,
+    #[error("payload compressed with an unsupported codec: {0}")]
+    UnknownCodec(String),
+    #[cfg(any(feature = "compression-lz4", feature = "compression-zstd"))]
+    #[error("couldn't decompress the payload: {0}")]
+    Corrupted(String),
+}
+
+impl Codec {
+    fn tag(&self) -> &'static str {
+        match *self {
+            Codec::None => RAW_TAG,
+            #[cfg(feature = "compression-lz4")]
+            Codec::Lz4 => LZ4_TAG,
+            #[cfg(feature = "compression-zstd")]
+            Codec::Zstd(_) => ZSTD_TAG,
+        }
+    }
+
+    fn compress(&self, payload: &str) -> Option<String> {
+        match *self {
+            Codec::None => None,
+            #[cfg(feature = "compression-lz4")]
+            Codec::Lz4 => {
+                let compressed = lz4_flex::compress_prepend_size(payload.as_bytes());
+                Some(base64::encode(compressed))
+            }
+            #[cfg(feature = "compression-zstd")]
+            Codec::Zstd(level) => {
+                let compressed = zstd::encode_all(payload.as_bytes(), level).ok()?;
+                Some(base64::encode(compressed))
+            }
+        }
+    }
+}
+
+impl Compression {
+    /// Creates new compression settings using the given codec and
+    /// skipping the payloads smaller than [`DEFAULT_MIN_SIZE`].
+    ///
+    /// # Arguments
+    ///
+    /// * `codec` - The codec used to compress the payloads.
+    pub fn new(codec: Codec) -> Self {
+        let min_size = DEFAULT_MIN_SIZE;
+
+        Compression { codec, min_size }
+    }
+
+    /// Sets the size (in bytes) under which payloads are sent
+    /// uncompressed, because compressing them wouldn't be worth
+    /// the overhead.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_size` - The size under which payloads are sent
+    ///     uncompressed.
+    pub fn with_min_size(mut self, min_size: usize) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Returns the codec used to compress the payloads.
+    pub fn codec(&self) -> Codec {
+        self.codec
+    }
+
+    /// Returns the size under which payloads are sent uncompressed.
+    pub fn min_size(&self) -> usize {
+        self.min_size
+    }
+}
+
+/// Returns the on-wire representation of `payload`, compressed if
+/// `compression` is set and it is worth it.
+pub(crate) fn encode(payload: &str, compression: Option<&Compression>) -> String {
+    if let Some(compression) = compression {
+        if payload.len() >= compression.min_size {
+            match compression.codec.compress(payload) {
+                // Some payloads don't compress well enough to make up
+                // for the base64 encoding.
+                Some(compressed) if compressed.len() < payload.len() => {
+                    return tagged(compression.codec.tag(), &compressed);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    if payload.starts_with(TAG) {
+        tagged(RAW_TAG, payload)
+    } else {
+        payload.to_string()
+    }
+}
+
+/// Returns the payload an on-wire representation returned by
+/// [`encode`] was created from.
+pub(crate) fn decode(payload: String) -> Result<String, DecodeError> {
+    let tagged = match payload.strip_prefix(TAG) {
+        Some(tagged) => tagged,
+        None => return Ok(payload),
+    };
+
+    let (tag, body) = tagged.split_once(':').ok_or(DecodeError::MalformedTag)?;
+    match tag {
+        RAW_TAG => Ok(body.to_string()),
+        #[cfg(feature = "compression-lz4")]
+        LZ4_TAG => {
+            let compressed = decode_base64(body)?;
+            let decompressed = lz4_flex::decompress_size_prepended(&compressed)
+                .map_err(|e| DecodeError::Corrupted(e.to_string()))?;
+            into_string(decompressed)
+        }
+        #[cfg(feature = "compression-zstd")]
+        ZSTD_TAG => {
+            let compressed = decode_base64(body)?;
+            let decompressed = zstd::decode_all(compressed.as_slice())
+                .map_err(|e| DecodeError::Corrupted(e.to_string()))?;
+            into_string(decompressed)
+        }
+        tag => Err(DecodeError::UnknownCodec(tag.to_string())),
+    }
+}
+
+pub(crate) fn tagged(tag: &str, body: &str) -> String {
+    format!("{}{}:{}", TAG, tag, body)
+}
+
+/// Returns the body of `payload` if it was tagged with `tag`.
+pub(crate) fn untagged<'a>(tag: &str, payload: &'a str) -> Option<&'a str> {
+    payload
+        .strip_prefix(TAG)?
+        .strip_prefix(tag)?
+        .strip_prefix(':')
+}
+
+#[cfg(any(feature = "compression-lz4", feature = "compression-zstd"))]
+fn decode_base64(body: &str) -> Result<Vec<u8>, DecodeError> {
+    base64::decode(body).map_err(|e| DecodeError::Corrupted(e.to_string()))
+}
+
+#[cfg(any(feature = "compression-lz4", feature = "compression-zstd"))]
+fn into_string(bytes: Vec<u8>) -> Result<String, DecodeError> {
+    String::from_utf8(bytes).map_err(|e| DecodeError::Corrupted(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uncompressed_payloads_are_sent_as_is() {
+        let payload = "a small payload";
+
+        let encoded = encode(payload, None);
+        assert_eq!(encoded, payload);
+        assert_eq!(decode(encoded), Ok(payload.to_string()));
+    }
+
+    #[test]
+    fn payloads_are_sent_as_is_without_codec() {
+        let sender = Compression::new(Codec::None).with_min_size(0);
+        let payload = "a".repeat(DEFAULT_MIN_SIZE);
+
+        let encoded = encode(&payload, Some(&sender));
+        assert_eq!(encoded, payload);
+        assert_eq!(decode(encoded), Ok(payload));
+    }
+
+    #[test]
+    fn payloads_looking_tagged_are_escaped() {
+        let payload = format!("{}lz4:not compressed", TAG);
+
+        let encoded = encode(&payload, None);
+        assert_ne!(encoded, payload);
+        assert_eq!(decode(encoded), Ok(payload));
+    }
+
+    #[test]
+    fn tagged_payloads_are_untagged() {
+        let payload = tagged("meta", "{}");
+
+        assert_eq!(untagged("meta", &payload), Some("{}"));
+        assert_eq!(untagged("met", &payload), None);
+        assert_eq!(untagged("meta", "{}"), None);
+    }
+
+    #[test]
+    fn unknown_codecs_are_rejected() {
+        let payload = tagged("snappy", "AAAA");
+
+        assert_eq!(
+            decode(payload),
+            Err(DecodeError::UnknownCodec("snappy".to_string()))
+        );
+    }
+
+    #[cfg(any(feature = "compression-lz4", feature = "compression-zstd"))]
+    fn assert_compressed(codec: Codec) {
+        let sender = Compression::new(codec);
+        let payload = format!("[{}]", vec!["{\"key\":\"value\"}"; 1024].join(","));
+
+        let encoded = encode(&payload, Some(&sender));
+        assert!(encoded.len() < payload.len() / 10);
+        // The receiver doesn't need to be configured to decompress it.
+        assert_eq!(decode(encoded), Ok(payload));
+
+        let small = "{\"key\":\"value\"}";
+        assert_eq!(encode(small, Some(&sender)), small);
+    }
+
+    #[cfg(feature = "compression-lz4")]
+    #[test]
+    fn lz4_compresses_large_payloads() {
+        assert_compressed(Codec::Lz4);
+    }
+
+    #[cfg(feature = "compression-zstd")]
+    #[test]
+    fn zstd_compresses_large_payloads() {
+        assert_compressed(Codec::Zstd(3));
+    }
+}
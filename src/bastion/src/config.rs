@@ -1,9 +1,30 @@
+#[cfg(feature = "tokio-runtime")]
+use crate::executor::TokioSpawner;
+use std::time::Duration;
+
 #[derive(Default, Debug, Clone)]
 /// The configuration that should be used to initialize the
 /// system using [`Bastion::init_with`].
 ///
 /// The default behaviors are the following:
 /// - All backtraces are shown (see [`Config::show_backtraces`]).
+/// - The backtraces of the panics of children aren't captured (see
+///     [`Config::capture_backtraces`]).
+/// - The system's registries start empty and grow as supervisors
+///     are launched (see [`Config::with_capacity_hint`]).
+/// - The amount of blocking tasks running at once isn't bounded
+///     (see [`Config::with_blocking_pool_size`]).
+/// - Children groups send themselves a heartbeat every 60 seconds
+///     (see [`Config::with_heartbeat_tick`]).
+/// - Up to 1024 dead letters are kept for up to 60 seconds (see
+///     [`Config::with_dead_letters`]).
+/// - The size of the messages sent locally isn't bounded (see
+///     [`Config::with_max_message_size`]).
+/// - Elements receive up to 128 messages in a row before yielding to
+///     the other ones (see [`Config::with_fairness_budget`]).
+/// - The system's tasks are spawned onto `bastion-executor`'s pools,
+///     even with the `tokio-runtime` feature enabled (see
+///     [`Config::with_tokio_spawner`]).
 ///
 /// # Example
 ///
@@ -37,6 +58,15 @@
 /// [`Bastion::init_with`]: crate::Bastion::init_with
 pub struct Config {
     backtraces: Backtraces,
+    capture_backtraces: bool,
+    capacity_hint: Option<usize>,
+    blocking_pool_size: Option<usize>,
+    heartbeat_tick: Option<Duration>,
+    dead_letters: Option<(usize, Duration)>,
+    max_message_size: Option<usize>,
+    fairness_budget: Option<usize>,
+    #[cfg(feature = "tokio-runtime")]
+    tokio_spawner: Option<TokioSpawner>,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -53,6 +83,23 @@ impl Config {
     /// Creates a new configuration with the following default
     /// behaviors:
     /// - All backtraces are shown (see [`Config::show_backtraces`]).
+    /// - The backtraces of the panics of children aren't captured
+    ///     (see [`Config::capture_backtraces`]).
+    /// - The system's registries start empty and grow as supervisors
+    ///     are launched (see [`Config::with_capacity_hint`]).
+    /// - The amount of blocking tasks running at once isn't bounded
+    ///     (see [`Config::with_blocking_pool_size`]).
+    /// - Children groups send themselves a heartbeat every 60 seconds
+    ///     (see [`Config::with_heartbeat_tick`]).
+    /// - Up to 1024 dead letters are kept for up to 60 seconds (see
+    ///     [`Config::with_dead_letters`]).
+    /// - The size of the messages sent locally isn't bounded (see
+    ///     [`Config::with_max_message_size`]).
+    /// - Elements receive up to 128 messages in a row before yielding
+    ///     to the other ones (see [`Config::with_fairness_budget`]).
+    /// - The system's tasks are spawned onto `bastion-executor`'s
+    ///     pools, even with the `tokio-runtime` feature enabled (see
+    ///     [`Config::with_tokio_spawner`]).
     pub fn new() -> Self {
         Config::default()
     }
@@ -136,9 +183,400 @@ impl Config {
         self
     }
 
+    /// Makes Bastion capture the backtrace of the panics of children,
+    /// which is then logged with their panic message.
+    ///
+    /// This chains a panic hook to the one which was set before
+    /// initializing the system, which captures a backtrace for every
+    /// panic of every thread of the process, only if backtraces are
+    /// enabled (see [`std::backtrace`]). A panic hook set afterwards
+    /// replaces it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    ///
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// let config = Config::new().capture_backtraces();
+    ///
+    /// Bastion::init_with(config);
+    ///
+    /// // You can now use bastion and the backtraces of the
+    /// // panics of children will be logged...
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn capture_backtraces(mut self) -> Self {
+        self.capture_backtraces = true;
+        self
+    }
+
+    /// Preallocates the registries of the system and of its
+    /// supervisor for the expected amount of supervisors and
+    /// children groups, so that launching a tree of a known size
+    /// doesn't have them grow incrementally.
+    ///
+    /// This is only a hint: the registries still grow if more
+    /// elements are launched.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The expected amount of supervisors (or
+    ///     children groups) launched by the system.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    ///
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// let config = Config::new().with_capacity_hint(512);
+    ///
+    /// Bastion::init_with(config);
+    ///
+    /// for _ in 0..512 {
+    ///     Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
+    /// }
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn with_capacity_hint(mut self, capacity: usize) -> Self {
+        self.capacity_hint = Some(capacity);
+        self
+    }
+
+    /// Bounds the amount of tasks spawned with [`blocking!`] or
+    /// [`blocking_named!`] that can run at once, the other ones
+    /// waiting for one of them to complete before starting, without
+    /// holding a thread of the blocking pool meanwhile.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The maximum amount of blocking tasks running at
+    ///     once, which must be greater than zero.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    ///
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// let config = Config::new().with_blocking_pool_size(4);
+    ///
+    /// Bastion::init_with(config);
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`blocking!`]: crate::blocking!
+    /// [`blocking_named!`]: crate::blocking_named!
+    pub fn with_blocking_pool_size(mut self, size: usize) -> Self {
+        self.blocking_pool_size = Some(size);
+        self
+    }
+
+    /// Sets the default interval at which children groups send
+    /// themselves a heartbeat, which they can still override with
+    /// [`Children::with_heartbeat_tick`].
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The default interval between two heartbeats
+    ///     of a children group.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// let config = Config::new().with_heartbeat_tick(Duration::from_secs(5));
+    ///
+    /// Bastion::init_with(config);
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_heartbeat_tick`]: crate::children::Children::with_heartbeat_tick
+    pub fn with_heartbeat_tick(mut self, interval: Duration) -> Self {
+        self.heartbeat_tick = Some(interval);
+        self
+    }
+
+    /// Bounds the dead letters kept by the system, the same way
+    /// [`Bastion::configure_dead_letters`] does once it is
+    /// initialized.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_entries` - The maximum amount of dead letters kept,
+    ///     the oldest ones being dropped first.
+    /// * `max_age` - The maximum amount of time a dead letter is
+    ///     kept for.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// let config = Config::new().with_dead_letters(100, Duration::from_secs(60));
+    ///
+    /// Bastion::init_with(config);
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Bastion::configure_dead_letters`]: crate::Bastion::configure_dead_letters
+    pub fn with_dead_letters(mut self, max_entries: usize, max_age: Duration) -> Self {
+        self.dead_letters = Some((max_entries, max_age));
+        self
+    }
+
+    /// Bounds the size of the messages sent locally, so that the
+    /// larger ones are refused instead of being enqueued: sending
+    /// them returns an error ([`SendError::MessageTooLarge`] when
+    /// the error can carry their size) while broadcasting them routes
+    /// them to the dead letters.
+    ///
+    /// The size of [`Bytes`], `Vec<u8>`, `String` and `&str` messages
+    /// is the length of their payload, while the size of the other
+    /// messages is the amount of memory they use in place.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - The maximum size of a message, in bytes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    ///
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// let config = Config::new().with_max_message_size(64 * 1024);
+    ///
+    /// Bastion::init_with(config);
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`SendError::MessageTooLarge`]: crate::errors::SendError::MessageTooLarge
+    /// [`Bytes`]: bytes::Bytes
+    pub fn with_max_message_size(mut self, max: usize) -> Self {
+        self.max_message_size = Some(max);
+        self
+    }
+
+    /// Sets the default amount of messages the elements of children
+    /// groups receive in a row before yielding to the other elements,
+    /// so that an element with a busy mailbox can't starve the ones
+    /// sharing its threads. Groups can still override it with
+    /// [`Children::with_fairness_budget`].
+    ///
+    /// # Arguments
+    ///
+    /// * `budget` - The default amount of messages an element receives
+    ///     before yielding (at least `1`).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    ///
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// let config = Config::new().with_fairness_budget(32);
+    ///
+    /// Bastion::init_with(config);
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_fairness_budget`]: crate::children::Children::with_fairness_budget
+    pub fn with_fairness_budget(mut self, budget: usize) -> Self {
+        self.fairness_budget = Some(budget.max(1));
+        self
+    }
+
+    #[cfg(feature = "tokio-runtime")]
+    #[cfg_attr(feature = "docs", doc(cfg(feature = "tokio-runtime")))]
+    /// Makes the system spawn its tasks (the system, supervisors,
+    /// children groups and their elements, but also the futures given
+    /// to [`spawn!`] and [`blocking!`]) onto the Tokio runtime of the
+    /// given spawner instead of `bastion-executor`'s pools.
+    ///
+    /// Note that the threads of the runtime then run the elements of
+    /// the system, so that blocking one of them (e.g. with
+    /// [`Bastion::block_until_stopped`]) can stall the system, which
+    /// a current-thread runtime can't survive.
+    ///
+    /// # Arguments
+    ///
+    /// * `spawner` - The spawner of the Tokio runtime to spawn the
+    ///     system's tasks onto.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::executor::TokioSpawner;
+    /// use bastion::prelude::*;
+    ///
+    /// # #[tokio::main(flavor = "multi_thread")]
+    /// # async fn main() {
+    /// let spawner = TokioSpawner::new(tokio::runtime::Handle::current());
+    /// let config = Config::new().with_tokio_spawner(spawner);
+    ///
+    /// Bastion::init_with(config);
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # }
+    /// ```
+    ///
+    /// [`spawn!`]: crate::spawn!
+    /// [`blocking!`]: crate::blocking!
+    /// [`Bastion::block_until_stopped`]: crate::Bastion::block_until_stopped
+    pub fn with_tokio_spawner(mut self, spawner: TokioSpawner) -> Self {
+        self.tokio_spawner = Some(spawner);
+        self
+    }
+
     pub(crate) fn backtraces(&self) -> &Backtraces {
         &self.backtraces
     }
+
+    pub(crate) fn captures_backtraces(&self) -> bool {
+        self.capture_backtraces
+    }
+
+    pub(crate) fn capacity_hint(&self) -> usize {
+        self.capacity_hint.unwrap_or_default()
+    }
+
+    pub(crate) fn blocking_pool_size(&self) -> Option<usize> {
+        self.blocking_pool_size
+    }
+
+    pub(crate) fn heartbeat_tick(&self) -> Option<Duration> {
+        self.heartbeat_tick
+    }
+
+    pub(crate) fn dead_letters(&self) -> Option<(usize, Duration)> {
+        self.dead_letters
+    }
+
+    pub(crate) fn max_message_size(&self) -> Option<usize> {
+        self.max_message_size
+    }
+
+    pub(crate) fn fairness_budget(&self) -> Option<usize> {
+        self.fairness_budget
+    }
+
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn tokio_spawner(&self) -> Option<&TokioSpawner> {
+        self.tokio_spawner.as_ref()
+    }
 }
 
 impl Backtraces {
@@ -2,34 +2,77 @@
 //! A context allows a child's future to access its received
 //! messages, parent and supervisor.
 
+use crate::child::Child;
 use crate::child_ref::ChildRef;
+use crate::children::{Children, PanicPolicy};
 use crate::children_ref::ChildrenRef;
-use crate::dispatcher::{BroadcastTarget, DispatcherType, NotificationType};
+use crate::dead_letters::DEAD_LETTERS;
+use crate::dispatcher::{BroadcastOrder, BroadcastTarget, DispatcherType, NotificationType};
 use crate::envelope::{Envelope, RefAddr, SignedMessage};
-use crate::message::{Answer, BastionMessage, Message, Msg};
-use crate::supervisor::SupervisorRef;
+use crate::errors::{RejectedMessage, RejectionReason, SendError};
+use crate::health::HEALTH;
+#[cfg(feature = "metrics")]
+use crate::latency::LATENCIES;
+use crate::message::{
+    check_message_size, AckReport, Answer, AnswerSender, BastionMessage, GroupAnswers, GroupReply,
+    Message, Msg, OutstandingAsks,
+};
+use crate::overflow::DropPriorities;
+use crate::path::BastionPath;
+use crate::persistence::Persistence;
+use crate::quiesce::QUIESCE;
+use crate::rate_limit::{OverloadPolicy, GLOBAL_RATE_LIMIT};
+use crate::retry::RetryPolicy;
+use crate::sources::{ContextEvent, EventSources};
+use crate::supervisor::{SupervisionStrategy, SupervisorRef};
+use crate::watch::WATCHES;
 use crate::{prelude::ReceiveError, system::SYSTEM};
 
 use crossbeam_queue::SegQueue;
 use futures::pending;
-use futures::FutureExt;
+use futures::prelude::*;
 use futures_timer::Delay;
+use fxhash::FxHashMap;
 #[cfg(feature = "scaling")]
 use lever::table::lotable::LOTable;
+use once_cell::sync::Lazy;
+use std::any::{Any, TypeId};
+#[cfg(feature = "testkit")]
+use std::collections::VecDeque;
 use std::fmt::{self, Display, Formatter};
+use std::panic::{self, AssertUnwindSafe};
 use std::pin::Pin;
 #[cfg(feature = "scaling")]
 use std::sync::atomic::AtomicU64;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::task::{Context, Poll};
+use std::time::Instant;
 use std::{sync::Arc, time::Duration};
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
 use uuid::Uuid;
 
 /// Identifier for a root supervisor and dead-letters children.
 pub const NIL_ID: BastionId = BastionId(Uuid::nil());
 
+/// How long an element waiting for [`BastionContext::cancelled`] is
+/// given to return once it was asked to stop.
+pub const CANCELLATION_TIMEOUT: Duration = Duration::from_secs(5);
+
+// How often `BastionContext::tell_async` checks whether the mailbox of
+// an overloaded recipient went back below its high-watermark.
+const OVERLOAD_CHECK_INTERVAL: Duration = Duration::from_millis(5);
+
+type IdGenerator = Box<dyn Fn() -> Uuid + Send + Sync>;
+
+// The function generating the UUIDs of the new identifiers, if one
+// was set with `BastionId::set_generator`.
+static ID_GENERATOR: Lazy<RwLock<Option<IdGenerator>>> = Lazy::new(|| RwLock::new(None));
+
 #[derive(Hash, Eq, PartialEq, Debug, Clone)]
 /// An identifier used by supervisors, children groups and
-/// their elements to identify themselves, using a v4 UUID.
+/// their elements to identify themselves, using a v4 UUID
+/// unless another generator was set with [`set_generator`].
 ///
 /// A `BastionId` is unique to its attached element and is
 /// reset when it is restarted. A special `BastionId` exists
@@ -71,6 +114,8 @@ pub const NIL_ID: BastionId = BastionId(Uuid::nil());
 /// # Bastion::block_until_stopped();
 /// # }
 /// ```
+///
+/// [`set_generator`]: Self::set_generator
 pub struct BastionId(pub(crate) Uuid);
 
 #[derive(Debug)]
@@ -138,23 +183,175 @@ pub struct BastionContext {
     children: ChildrenRef,
     supervisor: Option<SupervisorRef>,
     state: Arc<Pin<Box<ContextState>>>,
+    // Values stored by the child for its current incarnation,
+    // keyed by their type.
+    locals: FxHashMap<TypeId, Box<dyn Any + Send + Sync>>,
+    // The timers and streams waited on alongside the mailbox by
+    // `next_event`.
+    sources: Mutex<EventSources>,
 }
 
 #[derive(Debug)]
 pub(crate) struct ContextState {
     messages: SegQueue<SignedMessage>,
+    // The messages moved out of `messages` to be lent to a snapshot,
+    // which are received before the ones left in `messages`.
+    #[cfg(feature = "testkit")]
+    snapshotted: Mutex<VecDeque<SignedMessage>>,
+    // The log the messages are persisted to, if the group was
+    // configured with one.
+    persistence: Option<Arc<Persistence>>,
+    // The sequence numbers of the messages in the mailbox, in the
+    // same order, if they were persisted.
+    persisted: SegQueue<Option<u64>>,
+    // The sequence number of the persisted message being handled.
+    in_flight: Mutex<Option<u64>>,
+    // The amount of messages waiting in the mailbox, shared with
+    // the `ChildRef`s checking it against a high-watermark.
+    depth: Arc<AtomicUsize>,
+    // The amount of messages the mailbox holds before evicting the
+    // ones of the lowest drop priority, if bounded.
+    overflow: Option<(usize, Arc<DropPriorities>)>,
+    // What happens to the child when it panics while handling a
+    // message received with `BastionContext::recv_with`.
+    panic_policy: PanicPolicy,
+    // The transforms applied to the messages the child sends.
+    outbound: OutboundTransforms,
+    // The asks sent by the child which weren't answered yet.
+    asks: OutstandingAsks,
+    // Whether the child panicked while handling a message and is to
+    // be stopped instead of faulting.
+    stopped_on_panic: AtomicBool,
+    // Whether the messages are kept in the mailbox instead of
+    // being received, because the child was paused.
+    paused: AtomicBool,
+    // Whether the child's future is waiting for a new message.
+    waiting: AtomicBool,
+    // Whether the child's future waited for a message at least
+    // once, reaching its running state.
+    running: AtomicBool,
+    // Whether the child was asked to stop while its future was
+    // waiting for it to be cancelled.
+    cancelled: AtomicBool,
+    // The amount of futures returned by `BastionContext::cancelled`
+    // that are currently waiting for the child to be cancelled.
+    cancellation_watchers: AtomicUsize,
+    // Whether the child escalated its fault to its supervisor.
+    escalated: AtomicBool,
+    // When the child's future last returned to its message loop or
+    // called `BastionContext::heartbeat`.
+    last_heartbeat: Mutex<Instant>,
+    // The ordinal of the child within its group.
+    index: usize,
+    // The amount of messages the child's future receives in a row
+    // before yielding to the other elements, if bounded.
+    fairness_budget: Option<usize>,
+    // The amount of messages the child's future received since it
+    // last yielded.
+    received_in_row: AtomicUsize,
+    // Allows to acknowledge the last received message, if it was
+    // sent with `BastionContext::broadcast_with_ack`.
+    ack: Mutex<Option<AnswerSender>>,
+    // When the messages in the mailbox were pushed to it, in the same
+    // order.
+    #[cfg(feature = "metrics")]
+    enqueued: SegQueue<Instant>,
+    // When the message being handled was pushed to the mailbox.
+    #[cfg(feature = "metrics")]
+    handled_since: Mutex<Option<Instant>>,
     #[cfg(feature = "scaling")]
     stats: Arc<AtomicU64>,
     #[cfg(feature = "scaling")]
     actor_stats: Arc<LOTable<BastionId, u32>>,
 }
 
+#[derive(Default, Clone)]
+// The transforms applied to the messages sent by the elements of a
+// group, each of them erased to rewrite a message of its type taken
+// out of an `Option`, and leave the other messages untouched.
+pub(crate) struct OutboundTransforms(Vec<Arc<dyn Fn(&mut dyn Any) + Send + Sync>>);
+
 impl BastionId {
     pub(crate) fn new() -> Self {
-        let uuid = Uuid::new_v4();
+        // FIXME: panics?
+        let uuid = match &*ID_GENERATOR.read().unwrap() {
+            Some(generator) => generator(),
+            None => Uuid::new_v4(),
+        };
 
         BastionId(uuid)
     }
+
+    /// Sets the function generating the UUIDs of the identifiers
+    /// created from now on, instead of using v4 UUIDs, allowing
+    /// to get deterministic identifiers in tests or sequential
+    /// ones which are easier to trace in logs.
+    ///
+    /// The generated UUIDs need to be unique and shouldn't be nil,
+    /// which is the UUID of [`NIL_ID`].
+    ///
+    /// # Arguments
+    ///
+    /// * `generator` - The function generating the UUIDs.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// use std::sync::atomic::{AtomicU64, Ordering};
+    /// use uuid::Uuid;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// let next = AtomicU64::new(1);
+    /// BastionId::set_generator(move || {
+    ///     Uuid::from_u128(next.fetch_add(1, Ordering::SeqCst).into())
+    /// });
+    ///
+    /// let supervisor = Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
+    /// assert_eq!(supervisor.id().uuid(), &Uuid::from_u128(1));
+    /// #
+    /// # BastionId::reset_generator();
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn set_generator<G>(generator: G)
+    where
+        G: Fn() -> Uuid + Send + Sync + 'static,
+    {
+        debug!("BastionId: Setting a generator.");
+        // FIXME: panics?
+        *ID_GENERATOR.write().unwrap() = Some(Box::new(generator));
+    }
+
+    /// Goes back to generating the identifiers using v4 UUIDs, after
+    /// a generator was set with [`set_generator`].
+    ///
+    /// [`set_generator`]: Self::set_generator
+    pub fn reset_generator() {
+        debug!("BastionId: Resetting the generator.");
+        // FIXME: panics?
+        *ID_GENERATOR.write().unwrap() = None;
+    }
+
+    /// Returns the UUID of this identifier.
+    pub fn uuid(&self) -> &Uuid {
+        &self.0
+    }
 }
 
 impl BastionContext {
@@ -166,12 +363,17 @@ impl BastionContext {
         state: Arc<Pin<Box<ContextState>>>,
     ) -> Self {
         debug!("BastionContext({}): Creating.", id);
+        let locals = FxHashMap::default();
+        let sources = Mutex::new(EventSources::default());
+
         BastionContext {
             id,
             child,
             children,
             supervisor,
             state,
+            locals,
+            sources,
         }
     }
 
@@ -218,6 +420,105 @@ impl BastionContext {
         &self.child
     }
 
+    /// Returns the ordinal of the element linked to this
+    /// `BastionContext` within its children group, between `0` and
+    /// the group's redundancy (excluded, unless the group was
+    /// resized).
+    ///
+    /// Elements keep their ordinal when they are restarted, and the
+    /// ordinal of an element which stopped is reused by the next
+    /// element launched in the group.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_redundancy(5)
+    ///         .with_exec(|ctx: BastionContext| {
+    ///             async move {
+    ///                 let index: usize = ctx.index();
+    ///                 println!("replica #{} started", index);
+    ///
+    ///                 Ok(())
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn index(&self) -> usize {
+        self.state.index()
+    }
+
+    /// Returns the [`BastionPath`] of the element linked to this
+    /// `BastionContext`, which is assigned when the element is
+    /// launched and kept when it is restarted.
+    ///
+    /// This is the same path as the one of [`current`], and the one
+    /// of the signature of the messages this element sends.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             let path: &BastionPath = ctx.path();
+    ///             assert!(path.elem().as_ref().unwrap().is_child());
+    ///             println!("{} (#{}) started", path, ctx.index());
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`current`]: Self::current
+    pub fn path(&self) -> &BastionPath {
+        self.child.path()
+    }
+
     /// Returns a [`ChildrenRef`] referencing the children group
     /// of the element that is linked to this `BastionContext`.
     ///
@@ -328,17 +629,27 @@ impl BastionContext {
         self.supervisor.as_ref()
     }
 
-    /// Tries to retrieve asynchronously a message received by
-    /// the element this `BastionContext` is linked to.
+    /// Hands the children group of the element linked to this
+    /// `BastionContext` over to the given supervisor, which supervises
+    /// it with the given strategy from then on.
     ///
-    /// If you need to wait (always asynchronously) until at
-    /// least one message can be retrieved, use [`recv`] instead.
-    ///    
-    /// If you want to wait for a certain amount of time before bailing out
-    /// use [`try_recv_timeout`] instead.
+    /// The group keeps running while it is handed over: its elements
+    /// aren't restarted and keep their state, but their faults are
+    /// handled by the new supervisor once it adopted them. This allows
+    /// to keep an element spawned for a short while (for example with
+    /// [`Bastion::spawn`]) once it proves worth keeping.
     ///
-    /// This method returns [`SignedMessage`] if a message was available, or
-    /// `None` otherwise.
+    /// The strategy is set on the supervisor, and thus applies to
+    /// everything it supervises. [`supervisor`] keeps returning the
+    /// previous supervisor until the element restarts.
+    ///
+    /// This method returns `Err(())` if the group's supervisor or the
+    /// given one is stopped.
+    ///
+    /// # Arguments
+    ///
+    /// * `supervisor` - The supervisor adopting the children group.
+    /// * `strategy` - The strategy the supervisor uses from then on.
     ///
     /// # Example
     ///
@@ -348,27 +659,30 @@ impl BastionContext {
     /// # #[cfg(feature = "tokio-runtime")]
     /// # #[tokio::main]
     /// # async fn main() {
-    /// #    run();    
+    /// #    run();
     /// # }
     /// #
     /// # #[cfg(not(feature = "tokio-runtime"))]
     /// # fn main() {
-    /// #    run();    
+    /// #    run();
     /// # }
     /// #
     /// # fn run() {
     /// # Bastion::init();
     /// #
-    /// Bastion::children(|children| {
-    ///     children.with_exec(|ctx: BastionContext| {
-    ///         async move {
-    ///             let opt_msg: Option<SignedMessage> = ctx.try_recv().await;
-    ///             // If a message was received by the element, `opt_msg` will
-    ///             // be `Some(Msg)`, otherwise it will be `None`.
+    /// let supervisor = Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
     ///
-    ///             Ok(())
+    /// Bastion::spawn(move |ctx: BastionContext| {
+    ///     let supervisor = supervisor.clone();
+    ///     async move {
+    ///         // The element proved worth keeping...
+    ///         ctx.promote_to(&supervisor, SupervisionStrategy::OneForOne)
+    ///             .expect("Couldn't promote the element.");
+    ///
+    ///         loop {
+    ///             ctx.recv().await?;
     ///         }
-    ///     })
+    ///     }
     /// }).expect("Couldn't create the children group.");
     /// #
     /// # Bastion::start();
@@ -377,37 +691,54 @@ impl BastionContext {
     /// # }
     /// ```
     ///
-    /// [`recv`]: Self::method.recv
-    /// [`try_recv_timeout`]: Self::method.try_recv_timeout
-    pub async fn try_recv(&self) -> Option<SignedMessage> {
-        // We want to let a tick pass
-        // otherwise guard will never contain anything.
-        Delay::new(Duration::from_millis(0)).await;
-
-        trace!("BastionContext({}): Trying to receive message.", self.id);
+    /// [`Bastion::spawn`]: crate::Bastion::spawn
+    /// [`supervisor`]: Self::supervisor
+    pub fn promote_to(
+        &self,
+        supervisor: &SupervisorRef,
+        strategy: SupervisionStrategy,
+    ) -> Result<(), ()> {
+        debug!(
+            "BastionContext({}): Promoting Children({}) to Supervisor({}).",
+            self.id,
+            self.children.id(),
+            supervisor.id()
+        );
+        supervisor.strategy(strategy)?;
 
-        if let Some(msg) = self.state.pop_message() {
-            trace!("BastionContext({}): Received message: {:?}", self.id, msg);
-            Some(msg)
-        } else {
-            trace!("BastionContext({}): Received no message.", self.id);
-            None
+        let parent = self
+            .supervisor
+            .as_ref()
+            .unwrap_or_else(|| SYSTEM.supervisor());
+        if parent.id() == supervisor.id() {
+            return Ok(());
         }
+
+        let msg = BastionMessage::handover(self.children.id().clone(), supervisor.clone());
+        let env = Envelope::from_dead_letters(msg);
+        parent.send(env).map_err(|_| ())
     }
 
-    /// Retrieves asynchronously a message received by the element
-    /// this `BastionContext` is linked to and waits (always
-    /// asynchronously) for one if none has been received yet.
+    /// Creates a new [`Children`], passes it through the specified
+    /// `init` closure and then sends it to the supervisor of the
+    /// element linked to this `BastionContext` (or to the system
+    /// supervisor if it doesn't have one, see [`supervisor`]), for it
+    /// to supervise the new children group alongside the element's
+    /// own group.
     ///
-    /// If you don't need to wait until at least one message
-    /// can be retrieved, use [`try_recv`] instead.
-    ///    
-    /// If you want to wait for a certain amount of time before bailing out
-    /// use [`try_recv_timeout`] instead.
+    /// This allows a running element to spawn helpers which follow
+    /// the supervision strategy of its supervisor and are stopped
+    /// along with it.
     ///
-    /// This method returns [`SignedMessage`] if it succeeded, or `Err(())`
+    /// This method returns a [`ChildrenRef`] referencing the newly
+    /// created children group if it succeeded, or `Err(())`
     /// otherwise.
     ///
+    /// # Arguments
+    ///
+    /// * `init` - The closure taking the new [`Children`] as an
+    ///     argument and returning it once configured.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -416,24 +747,33 @@ impl BastionContext {
     /// # #[cfg(feature = "tokio-runtime")]
     /// # #[tokio::main]
     /// # async fn main() {
-    /// #    run();    
+    /// #    run();
     /// # }
     /// #
     /// # #[cfg(not(feature = "tokio-runtime"))]
     /// # fn main() {
-    /// #    run();    
+    /// #    run();
     /// # }
     /// #
     /// # fn run() {
     /// # Bastion::init();
     /// #
     /// Bastion::children(|children| {
-    ///     children.with_exec(|ctx: BastionContext| {
-    ///         async move {
-    ///             // This will block until a message has been received...
-    ///             let msg: SignedMessage = ctx.recv().await?;
+    ///     children.with_exec(|ctx: BastionContext| async move {
+    ///         let helper = ctx
+    ///             .spawn_child(|children| {
+    ///                 children.with_exec(|ctx: BastionContext| async move {
+    ///                     loop {
+    ///                         ctx.recv().await?;
+    ///                     }
+    ///                 })
+    ///             })
+    ///             .expect("Couldn't spawn the helper.");
     ///
-    ///             Ok(())
+    ///         helper.broadcast("A message containing data.").ok();
+    ///
+    ///         loop {
+    ///             ctx.recv().await?;
     ///         }
     ///     })
     /// }).expect("Couldn't create the children group.");
@@ -444,64 +784,73 @@ impl BastionContext {
     /// # }
     /// ```
     ///
-    /// [`try_recv`]: Self::try_recv
-    /// [`try_recv_timeout`]: Self::try_recv_timeout
-    pub async fn recv(&self) -> Result<SignedMessage, ()> {
-        debug!("BastionContext({}): Waiting to receive message.", self.id);
-        loop {
-            if let Some(msg) = self.state.pop_message() {
-                trace!("BastionContext({}): Received message: {:?}", self.id, msg);
-                return Ok(msg);
-            }
-            pending!();
-        }
+    /// [`supervisor`]: Self::supervisor
+    pub fn spawn_child<C>(&self, init: C) -> Result<ChildrenRef, ()>
+    where
+        C: FnOnce(Children) -> Children,
+    {
+        debug!("BastionContext({}): Spawning a children group.", self.id);
+        self.supervisor
+            .as_ref()
+            .unwrap_or_else(|| SYSTEM.supervisor())
+            .children(init)
     }
 
-    /// Retrieves asynchronously a message received by the element
-    /// this `BastionContext` is linked to and waits until `timeout` (always
-    /// asynchronously) for one if none has been received yet.
+    /// Watches the element referenced by the given [`ChildRef`], so
+    /// that the element linked to this `BastionContext` receives a
+    /// [`Down`] message once it terminates, with its path and the
+    /// reason it terminated.
     ///
-    /// If you want to wait for ever until at least one message
-    /// can be retrieved, use [`recv`] instead.
+    /// The watch is removed once the message is sent, or when the
+    /// element linked to this `BastionContext` terminates itself.
     ///
-    /// If you don't need to wait until at least one message
-    /// can be retrieved, use [`try_recv`] instead.
+    /// # Arguments
     ///
-    /// This method returns [`SignedMessage`] if it succeeded, or `Err(TimeoutError)`
-    /// otherwise.
+    /// * `target` - The element to watch.
     ///
     /// # Example
     ///
     /// ```rust
     /// # use bastion::prelude::*;
-    /// # use std::time::Duration;
     /// #
     /// # #[cfg(feature = "tokio-runtime")]
     /// # #[tokio::main]
     /// # async fn main() {
-    /// #    run();    
+    /// #    run();
     /// # }
     /// #
     /// # #[cfg(not(feature = "tokio-runtime"))]
     /// # fn main() {
-    /// #    run();    
+    /// #    run();
     /// # }
     /// #
     /// # fn run() {
     /// # Bastion::init();
     /// #
-    /// Bastion::children(|children| {
-    ///     children.with_exec(|ctx: BastionContext| {
+    /// let worker = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| async move {
+    ///         loop {
+    ///             ctx.recv().await?;
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// let worker = worker.elems()[0].clone();
+    ///
+    /// Bastion::children(move |children| {
+    ///     let worker = worker.clone();
+    ///     children.with_exec(move |ctx: BastionContext| {
+    ///         let worker = worker.clone();
     ///         async move {
-    ///             // This will block until a message has been received...
-    ///             let timeout = Duration::from_millis(10);
-    ///             let msg: SignedMessage = ctx.try_recv_timeout(timeout).await.map_err(|e| {
-    ///                if let ReceiveError::Timeout(duration) = e {
-    ///                    // Timeout happened      
-    ///                }
-    ///             })?;
+    ///             ctx.watch(&worker);
     ///
-    ///             Ok(())
+    ///             loop {
+    ///                 msg! { ctx.recv().await?,
+    ///                     down: Down => {
+    ///                         println!("{} terminated: {:?}", down.path, down.reason);
+    ///                     };
+    ///                     _: _ => ();
+    ///                 }
+    ///             }
     ///         }
     ///     })
     /// }).expect("Couldn't create the children group.");
@@ -512,26 +861,50 @@ impl BastionContext {
     /// # }
     /// ```
     ///
-    /// [`recv`]: Self::recv
-    /// [`try_recv`]: Self::try_recv
-    /// [`SignedMessage`]: .crate::enveloppe::SignedMessage
-    pub async fn try_recv_timeout(&self, timeout: Duration) -> Result<SignedMessage, ReceiveError> {
+    /// [`Down`]: crate::watch::Down
+    pub fn watch(&self, target: &ChildRef) {
         debug!(
-            "BastionContext({}): Waiting to receive message within {} milliseconds.",
+            "BastionContext({}): Watching Child({}).",
             self.id,
-            timeout.as_millis()
+            target.id()
         );
-        futures::select! {
-            message = self.recv().fuse() => {
-                message.map_err(|_| ReceiveError::Other)
-            },
-            _duration = Delay::new(timeout).fuse() => {
-                Err(ReceiveError::Timeout(timeout))
-            }
-        }
+        WATCHES.watch(target.id(), &self.child);
     }
 
-    /// Returns [`RefAddr`] of the current `BastionContext`
+    /// Stops watching the element referenced by the given
+    /// [`ChildRef`] (see [`watch`]), so that no [`Down`] message is
+    /// received once it terminates.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The element to stop watching.
+    ///
+    /// [`watch`]: Self::watch
+    /// [`Down`]: crate::watch::Down
+    pub fn unwatch(&self, target: &ChildRef) {
+        debug!(
+            "BastionContext({}): Unwatching Child({}).",
+            self.id,
+            target.id()
+        );
+        WATCHES.unwatch(target.id(), &self.id);
+    }
+
+    /// Links the element linked to this `BastionContext` with the
+    /// element referenced by the given [`ChildRef`], so that if either
+    /// of them terminates abnormally (because it faulted or was
+    /// killed), the other one is killed too.
+    ///
+    /// An element which stops normally doesn't terminate the element
+    /// it is linked to, and an element trapping exits (see
+    /// [`trap_exit`]) receives a [`Down`] message instead of being
+    /// killed.
+    ///
+    /// The link is removed once either of the elements terminates.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The element to link with.
     ///
     /// # Example
     ///
@@ -541,49 +914,108 @@ impl BastionContext {
     /// # #[cfg(feature = "tokio-runtime")]
     /// # #[tokio::main]
     /// # async fn main() {
-    /// #    run();    
+    /// #    run();
     /// # }
     /// #
     /// # #[cfg(not(feature = "tokio-runtime"))]
     /// # fn main() {
-    /// #    run();    
+    /// #    run();
     /// # }
     /// #
     /// # fn run() {
     /// # Bastion::init();
     /// #
+    /// let reader = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| async move {
+    ///         loop {
+    ///             ctx.recv().await?;
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// let reader = reader.elems()[0].clone();
     ///
-    /// Bastion::children(|children| {
-    ///     children.with_exec(|ctx: BastionContext| {
+    /// Bastion::children(move |children| {
+    ///     let reader = reader.clone();
+    ///     children.with_exec(move |ctx: BastionContext| {
+    ///         let reader = reader.clone();
     ///         async move {
-    ///             ctx.tell(&ctx.signature(), "Hello to myself");
+    ///             // The writer is killed if the reader faults, and
+    ///             // the other way around.
+    ///             ctx.link(&reader);
     ///
-    ///             # Bastion::stop();
-    ///             Ok(())
+    ///             loop {
+    ///                 ctx.recv().await?;
+    ///             }
     ///         }
     ///     })
     /// }).expect("Couldn't create the children group.");
     /// #
     /// # Bastion::start();
+    /// # Bastion::stop();
     /// # Bastion::block_until_stopped();
     /// # }
     /// ```
     ///
-    // TODO(scrabsha): should we link to Answer or to RefAddr?
-    // [`RefAddr`]: /prelude/struct.Answer.html
-    pub fn signature(&self) -> RefAddr {
-        RefAddr::new(
-            self.current().path().clone(),
-            self.current().sender().clone(),
-        )
+    /// [`trap_exit`]: Self::trap_exit
+    /// [`Down`]: crate::watch::Down
+    pub fn link(&self, other: &ChildRef) {
+        debug!(
+            "BastionContext({}): Linking with Child({}).",
+            self.id,
+            other.id()
+        );
+        WATCHES.link(&self.child, other);
     }
 
-    /// Sends a message to the specified [`RefAddr`]
+    /// Removes the link between the element linked to this
+    /// `BastionContext` and the element referenced by the given
+    /// [`ChildRef`] (see [`link`]).
     ///
     /// # Arguments
     ///
-    /// * `to` – the [`RefAddr`] to send the message to
-    /// * `msg` – The actual message to send
+    /// * `other` - The element to unlink from.
+    ///
+    /// [`link`]: Self::link
+    pub fn unlink(&self, other: &ChildRef) {
+        debug!(
+            "BastionContext({}): Unlinking from Child({}).",
+            self.id,
+            other.id()
+        );
+        WATCHES.unlink(&self.id, other.id());
+    }
+
+    /// Sets whether the element linked to this `BastionContext`
+    /// receives a [`Down`] message instead of being killed when an
+    /// element it is linked to (see [`link`]) terminates abnormally.
+    ///
+    /// Elements don't trap exits by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `trap_exit` - Whether the element traps exits.
+    ///
+    /// [`link`]: Self::link
+    /// [`Down`]: crate::watch::Down
+    pub fn trap_exit(&self, trap_exit: bool) {
+        debug!(
+            "BastionContext({}): Setting trap_exit={}.",
+            self.id, trap_exit
+        );
+        WATCHES.trap_exit(&self.id, trap_exit);
+    }
+
+    /// Tries to retrieve asynchronously a message received by
+    /// the element this `BastionContext` is linked to.
+    ///
+    /// If you need to wait (always asynchronously) until at
+    /// least one message can be retrieved, use [`recv`] instead.
+    ///    
+    /// If you want to wait for a certain amount of time before bailing out
+    /// use [`try_recv_timeout`] instead.
+    ///
+    /// This method returns [`SignedMessage`] if a message was available, or
+    /// `None` otherwise.
     ///
     /// # Example
     ///
@@ -607,12 +1039,10 @@ impl BastionContext {
     /// Bastion::children(|children| {
     ///     children.with_exec(|ctx: BastionContext| {
     ///         async move {
-    ///             // Wait for a message to be received...
-    ///             let smsg: SignedMessage = ctx.recv().await?;
-    ///             // Obtain address of this message sender...
-    ///             let sender_addr = smsg.signature();
-    ///             // And send something back
-    ///             ctx.tell(&sender_addr, "Ack").expect("Unable to acknowledge");
+    ///             let opt_msg: Option<SignedMessage> = ctx.try_recv().await;
+    ///             // If a message was received by the element, `opt_msg` will
+    ///             // be `Some(Msg)`, otherwise it will be `None`.
+    ///
     ///             Ok(())
     ///         }
     ///     })
@@ -623,34 +1053,47 @@ impl BastionContext {
     /// # Bastion::block_until_stopped();
     /// # }
     /// ```
-    pub fn tell<M: Message>(&self, to: &RefAddr, msg: M) -> Result<(), M> {
-        debug!(
-            "{:?}: Telling message: {:?} to: {:?}",
-            self.current().path(),
-            msg,
-            to.path()
-        );
-        let msg = BastionMessage::tell(msg);
-        let env = Envelope::new_with_sign(msg, self.signature());
-        // FIXME: panics?
-        to.sender()
-            .unbounded_send(env)
-            .map_err(|err| err.into_inner().into_msg().unwrap())
+    ///
+    /// [`recv`]: Self::method.recv
+    /// [`try_recv_timeout`]: Self::method.try_recv_timeout
+    pub async fn try_recv(&self) -> Option<SignedMessage> {
+        self.set_running();
+        // We want to let a tick pass
+        // otherwise guard will never contain anything.
+        Delay::new(Duration::from_millis(0)).await;
+
+        trace!("BastionContext({}): Trying to receive message.", self.id);
+
+        // NOTE: finding the mailbox empty means that the messages
+        //      received before were handled, like when `recv` waits.
+        if let Some(msg) = self.pop_governed().await {
+            trace!("BastionContext({}): Received message: {:?}", self.id, msg);
+            self.state.set_waiting(false);
+            Some(msg)
+        } else {
+            trace!("BastionContext({}): Received no message.", self.id);
+            self.state.set_waiting(true);
+            None
+        }
     }
 
-    /// Sends a message from behalf of current context to the addr,
-    /// allowing to addr owner answer.
+    /// Calls the given closure with the messages currently waiting in
+    /// the mailbox of the element this `BastionContext` is linked to,
+    /// in the order they will be received, returning its output.
     ///
-    /// This method returns [`Answer`] if it succeeded, or `Err(msg)`
-    /// otherwise.
+    /// The messages are only lent to the closure, and are still
+    /// received afterwards, allowing tests to assert on the pending
+    /// work of an element without consuming it.
     ///
-    /// # Argument
+    /// This method is only available with the `testkit` feature.
     ///
-    /// * `msg` - The message to send.
+    /// # Arguments
+    ///
+    /// * `f` - The closure called with the queued messages.
     ///
     /// # Example
     ///
-    /// ```
+    /// ```rust
     /// # use bastion::prelude::*;
     /// #
     /// # #[cfg(feature = "tokio-runtime")]
@@ -665,135 +1108,2194 @@ impl BastionContext {
     /// # }
     /// #
     /// # fn run() {
-    ///     # Bastion::init();
-    /// // The message that will be "asked"...
-    /// const ASK_MSG: &'static str = "A message containing data (ask).";
-    /// // The message the will be "answered"...
-    /// const ANSWER_MSG: &'static str = "A message containing data (answer).";
-    ///
-    ///     # let children_ref =
-    /// // Create a new child...
+    /// # Bastion::init();
+    /// #
     /// Bastion::children(|children| {
     ///     children.with_exec(|ctx: BastionContext| {
     ///         async move {
-    ///             // ...which will receive the message asked...
-    ///             msg! { ctx.recv().await?,
-    ///                 msg: &'static str =!> {
-    ///                     assert_eq!(msg, ASK_MSG);
-    ///                     // Handle the message...
-    ///
-    ///                     // ...and eventually answer to it...
-    ///                     answer!(ctx, ANSWER_MSG);
-    ///                 };
-    ///                 // This won't happen because this example
-    ///                 // only "asks" a `&'static str`...
-    ///                 _: _ => ();
+    ///             let pending: Vec<usize> = ctx.with_mailbox_snapshot(|messages| {
+    ///                 messages
+    ///                     .iter()
+    ///                     .filter_map(|msg| msg.peek::<usize>().copied())
+    ///                     .collect()
+    ///             });
+    ///             // The messages are still received afterwards.
+    ///             for _ in 0..pending.len() {
+    ///                 ctx.recv().await?;
     ///             }
     ///
     ///             Ok(())
     ///         }
     ///     })
     /// }).expect("Couldn't create the children group.");
-    ///
-    ///     # Bastion::children(|children| {
-    ///         # children.with_exec(move |ctx: BastionContext| {
-    ///             # let child_ref = children_ref.elems()[0].clone();
-    ///             # async move {
-    /// // Later, the message is "asked" to the child...
-    /// let answer: Answer = ctx.ask(&child_ref.addr(), ASK_MSG).expect("Couldn't send the message.");
-    ///
-    /// // ...and the child's answer is received...
-    /// msg! { answer.await.expect("Couldn't receive the answer."),
-    ///     msg: &'static str => {
-    ///         assert_eq!(msg, ANSWER_MSG);
-    ///         // Handle the answer...
-    ///     };
-    ///     // This won't happen because this example
-    ///     // only answers a `&'static str`...
-    ///     _: _ => ();
-    /// }
-    ///                 #
-    ///                 # Ok(())
-    ///             # }
-    ///         # })
-    ///     # }).unwrap();
-    ///     #
-    ///     # Bastion::start();
-    ///     # Bastion::stop();
-    ///     # Bastion::block_until_stopped();
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
     /// # }
     /// ```
-    pub fn ask<M: Message>(&self, to: &RefAddr, msg: M) -> Result<Answer, M> {
-        debug!(
-            "{:?}: Asking message: {:?} to: {:?}",
-            self.current().path(),
-            msg,
-            to
-        );
-        let (msg, answer) = BastionMessage::ask(msg, self.signature());
-        let env = Envelope::new_with_sign(msg, self.signature());
-        // FIXME: panics?
-        to.sender()
-            .unbounded_send(env)
-            .map_err(|err| err.into_inner().into_msg().unwrap())?;
-
-        Ok(answer)
+    #[cfg(feature = "testkit")]
+    pub fn with_mailbox_snapshot<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&[SignedMessage]) -> R,
+    {
+        trace!("BastionContext({}): Taking a mailbox snapshot.", self.id);
+        self.state.with_snapshot(f)
     }
 
-    /// Sends the notification to each declared dispatcher of the actor.
+    /// Retrieves asynchronously a message received by the element
+    /// this `BastionContext` is linked to and waits (always
+    /// asynchronously) for one if none has been received yet.
     ///
-    /// # Argument
+    /// If you don't need to wait until at least one message
+    /// can be retrieved, use [`try_recv`] instead.
+    ///    
+    /// If you want to wait for a certain amount of time before bailing out
+    /// use [`try_recv_timeout`] instead.
     ///
-    /// * `dispatchers` - Vector of dispatcher names to which need to
-    /// deliver a notification.
-    /// * `notification_type` - The type of the notification to send.
+    /// This method returns [`SignedMessage`] if it succeeded, or `Err(())`
+    /// otherwise.
     ///
-    pub fn notify(&self, dispatchers: &[DispatcherType], notification_type: NotificationType) {
-        let global_dispatcher = SYSTEM.dispatcher();
-        let from_actor = self.current();
-        global_dispatcher.notify(from_actor, dispatchers, notification_type);
-    }
-
-    /// Sends the broadcasted message to the target group(s).
+    /// # Example
     ///
-    /// # Argument
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // This will block until a message has been received...
+    ///             let msg: SignedMessage = ctx.recv().await?;
     ///
-    /// * `target` - Defines the message receivers in according with
-    /// the [`BroadcastTarget`] value.
-    /// * `message` - The broadcasted message.
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
     ///
-    pub fn broadcast_message<M: Message>(&self, target: BroadcastTarget, message: M) {
-        let msg = Arc::new(SignedMessage {
-            msg: Msg::broadcast(message),
-            sign: self.signature(),
-        });
-
-        let global_dispatcher = SYSTEM.dispatcher();
-        global_dispatcher.broadcast_message(target, &msg);
-    }
-}
-
-impl ContextState {
-    pub(crate) fn new() -> Self {
-        ContextState {
-            messages: SegQueue::new(),
-            #[cfg(feature = "scaling")]
-            stats: Arc::new(AtomicU64::new(0)),
-            #[cfg(feature = "scaling")]
-            actor_stats: Arc::new(LOTable::new()),
+    /// [`try_recv`]: Self::try_recv
+    /// [`try_recv_timeout`]: Self::try_recv_timeout
+    pub async fn recv(&self) -> Result<SignedMessage, ()> {
+        debug!("BastionContext({}): Waiting to receive message.", self.id);
+        self.set_running();
+        loop {
+            if let Some(msg) = self.pop_governed().await {
+                trace!("BastionContext({}): Received message: {:?}", self.id, msg);
+                self.state.set_waiting(false);
+                return Ok(msg);
+            }
+            self.state.set_waiting(true);
+            pending!();
         }
     }
 
-    #[cfg(feature = "scaling")]
-    pub(crate) fn set_stats(&mut self, stats: Arc<AtomicU64>) {
-        self.stats = stats;
-    }
-
-    #[cfg(feature = "scaling")]
-    pub(crate) fn set_actor_stats(&mut self, actor_stats: Arc<LOTable<BastionId, u32>>) {
-        self.actor_stats = actor_stats;
-    }
-
+    /// Retrieves asynchronously a message received by the element
+    /// this `BastionContext` is linked to, like [`recv`], and handles
+    /// it with the given closure, catching the panics happening while
+    /// it does.
+    ///
+    /// What happens when the closure panics depends on the panic
+    /// policy of the element's group (see
+    /// [`Children::with_panic_policy`]):
+    /// - with [`PanicPolicy::Restart`], the panic isn't caught and the
+    ///     element faults,
+    /// - with [`PanicPolicy::Resume`], the message is dropped and
+    ///     `Ok(None)` is returned, so that the element keeps handling
+    ///     the next messages with its state intact,
+    /// - with [`PanicPolicy::Stop`], `Err(())` is returned, and the
+    ///     element is stopped for good once its future returns it.
+    ///
+    /// The state the closure updated before panicking is kept as is
+    /// when the element is resumed.
+    ///
+    /// This method returns what the closure returned if it succeeded,
+    /// or `Err(())` otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The closure handling the received message.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_panic_policy(PanicPolicy::Resume)
+    ///         .with_exec(|ctx: BastionContext| {
+    ///             async move {
+    ///                 let mut total = 0;
+    ///                 loop {
+    ///                     let handled = ctx.recv_with(|msg| {
+    ///                         let amount: u64 = msg.extract().0.downcast().unwrap();
+    ///                         total += amount;
+    ///                     }).await?;
+    ///
+    ///                     if handled.is_none() {
+    ///                         // The message wasn't an amount...
+    ///                     }
+    ///                 }
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`recv`]: Self::recv
+    /// [`Children::with_panic_policy`]: crate::children::Children::with_panic_policy
+    pub async fn recv_with<F, R>(&self, handler: F) -> Result<Option<R>, ()>
+    where
+        F: FnOnce(SignedMessage) -> R,
+    {
+        let msg = self.recv().await?;
+        let policy = self.state.panic_policy;
+        if policy == PanicPolicy::Restart {
+            return Ok(Some(handler(msg)));
+        }
+
+        // NOTE: the state the handler mutably borrows might be left
+        //      half-updated, which resuming the element accepts.
+        let payload = match panic::catch_unwind(AssertUnwindSafe(|| handler(msg))) {
+            Ok(output) => return Ok(Some(output)),
+            Err(payload) => payload,
+        };
+
+        let message = Child::panic_message(&*payload);
+        if policy == PanicPolicy::Resume {
+            warn!(
+                "BastionContext({}): Resuming after panicking while handling a message: {}",
+                self.id, message
+            );
+            Ok(None)
+        } else {
+            warn!(
+                "BastionContext({}): Stopping after panicking while handling a message: {}",
+                self.id, message
+            );
+            self.state.stopped_on_panic.store(true, Ordering::SeqCst);
+            Err(())
+        }
+    }
+
+    // Pops the next message of the mailbox once the global rate limit
+    // (see `Bastion::set_global_rate_limit`) lets it through, or sheds
+    // the messages it doesn't let through if it was set to.
+    async fn pop_governed(&self) -> Option<SignedMessage> {
+        // NOTE: returning to the message loop proves the child isn't
+        //      hung (see `Children::with_heartbeat`).
+        self.state.heartbeat();
+        if self.state.is_budget_exhausted() {
+            trace!(
+                "BastionContext({}): Yielding after exhausting its fairness budget.",
+                self.id
+            );
+            yield_now().await;
+        }
+
+        loop {
+            match GLOBAL_RATE_LIMIT.try_acquire() {
+                Ok(()) => {
+                    let msg = self.state.pop_message(self.current().path());
+                    if msg.is_some() {
+                        self.state.spend_budget();
+                    } else {
+                        GLOBAL_RATE_LIMIT.refund();
+                    }
+
+                    return msg;
+                }
+                // NOTE: the message is only popped once it's let
+                //      through, so that it isn't lost if the future is
+                //      dropped in the meantime.
+                Err((OverloadPolicy::Backpressure, wait)) => Delay::new(wait).await,
+                Err((OverloadPolicy::Shed, _)) => {
+                    let msg = self.state.pop_message(self.current().path())?;
+                    debug!(
+                        "BastionContext({}): Shedding message above the global rate limit: {:?}",
+                        self.id, msg
+                    );
+                    DEAD_LETTERS.push(msg);
+                }
+            }
+        }
+    }
+
+    /// Returns a future resolving once the element this
+    /// `BastionContext` is linked to is asked to stop, allowing it
+    /// to race its work against it and to clean up before returning.
+    ///
+    /// While this future is awaited, stopping the element doesn't
+    /// abort its future anymore: the element only stops once its
+    /// future returned, or once it didn't for
+    /// [`CANCELLATION_TIMEOUT`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use futures::future::{self, Either};
+    /// # use futures_timer::Delay;
+    /// # use std::time::Duration;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             let work = Delay::new(Duration::from_secs(60));
+    ///             match future::select(Box::pin(work), Box::pin(ctx.cancelled())).await {
+    ///                 Either::Left(_) => println!("Done working."),
+    ///                 // Cleans up before stopping...
+    ///                 Either::Right(_) => println!("Cancelled."),
+    ///             }
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    pub fn cancelled(&self) -> impl Future<Output = ()> {
+        Cancelled {
+            state: self.state.clone(),
+            watching: false,
+        }
+    }
+
+    /// Turns this `BastionContext` into a [`Stream`] of the messages
+    /// received by the element it is linked to, which ends once the
+    /// element is asked to stop, so that the messages can be handled
+    /// with the stream combinators of the `futures` ecosystem.
+    ///
+    /// Like with [`cancelled`], stopping the element doesn't abort its
+    /// future while it waits for the next message of the stream, but
+    /// ends the stream instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use futures::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             let mut messages = Box::pin(ctx.into_message_stream().take(3));
+    ///             while let Some(msg) = messages.next().await {
+    ///                 msg! { msg,
+    ///                     msg: &'static str => println!("Received: {}", msg);
+    ///                     _: _ => ();
+    ///                 }
+    ///             }
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`cancelled`]: Self::cancelled
+    pub fn into_message_stream(self) -> impl Stream<Item = SignedMessage> {
+        debug!(
+            "BastionContext({}): Turning into a message stream.",
+            self.id
+        );
+        stream::unfold(self, |ctx| async move {
+            let message =
+                match future::select(Box::pin(ctx.recv()), Box::pin(ctx.cancelled())).await {
+                    future::Either::Left((Ok(message), _)) => Some(message),
+                    _ => None,
+                };
+
+            match message {
+                Some(message) => Some((message, ctx)),
+                None => {
+                    debug!("BastionContext({}): The message stream ended.", ctx.id);
+                    None
+                }
+            }
+        })
+    }
+
+    /// Escalates the fault of the element this `BastionContext` is
+    /// linked to, so that once its future returns an error, its
+    /// supervisor doesn't restart it but faults itself instead, with
+    /// [`FaultReason::Escalated`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::supervisor(|sp| {
+    ///     sp.children(|children| {
+    ///         children.with_exec(|ctx: BastionContext| {
+    ///             async move {
+    ///                 // Restarting wouldn't help...
+    ///                 ctx.escalate();
+    ///                 Err(())
+    ///             }
+    ///         })
+    ///     })
+    /// }).expect("Couldn't create the supervisor.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`FaultReason::Escalated`]: crate::supervisor::FaultReason::Escalated
+    pub fn escalate(&self) {
+        debug!("BastionContext({}): Escalating its fault.", self.id);
+        self.state.escalated.store(true, Ordering::SeqCst);
+    }
+
+    /// Proves that the element this `BastionContext` is linked to
+    /// isn't hung, if its group watches it (see
+    /// [`Children::with_heartbeat`]).
+    ///
+    /// Receiving messages already does so, this is only needed while
+    /// doing work that keeps the element from receiving messages for
+    /// longer than the group tolerates.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_heartbeat(Duration::from_secs(1), 3)
+    ///         .with_exec(|ctx: BastionContext| async move {
+    ///             for _chunk in 0..100 {
+    ///                 // ...long work...
+    ///                 ctx.heartbeat();
+    ///             }
+    ///
+    ///             Ok(())
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_heartbeat`]: crate::children::Children::with_heartbeat
+    pub fn heartbeat(&self) {
+        trace!("BastionContext({}): Heartbeat.", self.id);
+        self.state.heartbeat();
+    }
+
+    /// Retrieves asynchronously a message received by the element
+    /// this `BastionContext` is linked to and waits until `timeout` (always
+    /// asynchronously) for one if none has been received yet.
+    ///
+    /// If you want to wait for ever until at least one message
+    /// can be retrieved, use [`recv`] instead.
+    ///
+    /// If you don't need to wait until at least one message
+    /// can be retrieved, use [`try_recv`] instead.
+    ///
+    /// This method returns [`SignedMessage`] if it succeeded, or `Err(TimeoutError)`
+    /// otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // This will block until a message has been received...
+    ///             let timeout = Duration::from_millis(10);
+    ///             let msg: SignedMessage = ctx.try_recv_timeout(timeout).await.map_err(|e| {
+    ///                if let ReceiveError::Timeout(duration) = e {
+    ///                    // Timeout happened      
+    ///                }
+    ///             })?;
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`recv`]: Self::recv
+    /// [`try_recv`]: Self::try_recv
+    /// [`SignedMessage`]: .crate::enveloppe::SignedMessage
+    pub async fn try_recv_timeout(&self, timeout: Duration) -> Result<SignedMessage, ReceiveError> {
+        debug!(
+            "BastionContext({}): Waiting to receive message within {} milliseconds.",
+            self.id,
+            timeout.as_millis()
+        );
+        futures::select! {
+            message = self.recv().fuse() => {
+                message.map_err(|_| ReceiveError::Other)
+            },
+            _duration = Delay::new(timeout).fuse() => {
+                self.state.set_waiting(false);
+                Err(ReceiveError::Timeout(timeout))
+            }
+        }
+    }
+
+    /// Registers a timer ticking every `interval`, whose ticks are
+    /// returned by [`next_event`] as [`ContextEvent::Tick`]s.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the timer, given back with its ticks.
+    /// * `interval` - The interval between two ticks of the timer.
+    ///
+    /// # Example
+    ///
+    /// See [`next_event`].
+    ///
+    /// [`next_event`]: Self::next_event
+    pub fn register_timer<N: Into<String>>(&self, name: N, interval: Duration) {
+        let name = name.into();
+        debug!(
+            "BastionContext({}): Registering timer {} ticking every {:?}.",
+            self.id, name, interval
+        );
+        // FIXME: panics?
+        let mut sources = self.sources.lock().unwrap();
+        sources.register_timer(name, interval);
+    }
+
+    /// Registers a stream, whose items are returned by [`next_event`]
+    /// as [`ContextEvent::Item`]s until it ends.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the stream, given back with its items.
+    /// * `stream` - The stream to wait on alongside the mailbox.
+    ///
+    /// # Example
+    ///
+    /// See [`next_event`].
+    ///
+    /// [`next_event`]: Self::next_event
+    pub fn register_stream<N, S>(&self, name: N, stream: S)
+    where
+        N: Into<String>,
+        S: Stream + Send + 'static,
+        S::Item: Send + 'static,
+    {
+        let name = name.into();
+        debug!("BastionContext({}): Registering stream {}.", self.id, name);
+        // FIXME: panics?
+        let mut sources = self.sources.lock().unwrap();
+        sources.register_stream(name, stream);
+    }
+
+    /// Waits for the next event coming either from the mailbox of the
+    /// element or from one of the timers and streams registered with
+    /// [`register_timer`] and [`register_stream`], so that a single
+    /// loop handles all of them.
+    ///
+    /// This is cancel-safe: the registered sources are kept between
+    /// two calls, so dropping the returned future (e.g. when racing
+    /// it against another one) doesn't lose any message, tick or
+    /// item.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use futures::stream;
+    /// # use std::time::Duration;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             ctx.register_timer("flush", Duration::from_secs(1));
+    ///             ctx.register_stream("numbers", stream::iter(0..10usize));
+    ///
+    ///             loop {
+    ///                 match ctx.next_event().await? {
+    ///                     ContextEvent::Message(msg) => {
+    ///                         msg! { msg,
+    ///                             msg: &'static str => println!("Received: {}", msg);
+    ///                             _: _ => ();
+    ///                         }
+    ///                     }
+    ///                     ContextEvent::Tick(_) => println!("Flushing..."),
+    ///                     ContextEvent::Item(_, item) => {
+    ///                         if let Ok(number) = item.downcast::<usize>() {
+    ///                             println!("Number: {}", number);
+    ///                         }
+    ///                     }
+    ///                     ContextEvent::Ended(_) => (),
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`register_timer`]: Self::register_timer
+    /// [`register_stream`]: Self::register_stream
+    pub async fn next_event(&self) -> Result<ContextEvent, ()> {
+        let sources = future::poll_fn(|cx| {
+            // FIXME: panics?
+            let mut sources = self.sources.lock().unwrap();
+            sources.poll_next_event(cx)
+        });
+
+        futures::select! {
+            message = self.recv().fuse() => message.map(ContextEvent::Message),
+            event = sources.fuse() => {
+                self.state.set_waiting(false);
+                trace!("BastionContext({}): Received event: {:?}", self.id, event);
+                Ok(event)
+            }
+        }
+    }
+
+    // Marks the child as running, and as ready the first time it
+    // waits for a message (see `ChildrenRef::await_ready`).
+    fn set_running(&self) {
+        if self.state.set_running() {
+            debug!("BastionContext({}): Entered the message loop.", self.id);
+            HEALTH.child_ready(&self.id);
+        }
+    }
+
+    /// Returns [`RefAddr`] of the current `BastionContext`
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    ///
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             ctx.tell(&ctx.signature(), "Hello to myself");
+    ///
+    ///             # Bastion::stop();
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    // TODO(scrabsha): should we link to Answer or to RefAddr?
+    // [`RefAddr`]: /prelude/struct.Answer.html
+    pub fn signature(&self) -> RefAddr {
+        RefAddr::new(
+            self.current().path().clone(),
+            self.current().sender().clone(),
+        )
+    }
+
+    /// Sends a message to the specified [`RefAddr`]
+    ///
+    /// The messages sent by an element to another one are received
+    /// in the order they were sent: every element has a single
+    /// mailbox, fed by a single channel, and handles its messages
+    /// in FIFO order. This also holds for the messages sent using
+    /// a [`ChildRef`] and for the ones routed by a dispatcher, but
+    /// messages sent through different paths (eg. one sent with
+    /// this method and one broadcasted with [`Bastion::broadcast`],
+    /// which goes through the supervision tree) aren't ordered
+    /// relatively to each other.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` – the [`RefAddr`] to send the message to
+    /// * `msg` – The actual message to send
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // Wait for a message to be received...
+    ///             let smsg: SignedMessage = ctx.recv().await?;
+    ///             // Obtain address of this message sender...
+    ///             let sender_addr = smsg.signature();
+    ///             // And send something back
+    ///             ctx.tell(&sender_addr, "Ack").expect("Unable to acknowledge");
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Bastion::broadcast`]: crate::Bastion::broadcast
+    pub fn tell<M: Message>(&self, to: &RefAddr, msg: M) -> Result<(), M> {
+        let msg = self.outbound(msg);
+        debug!(
+            "{:?}: Telling message: {:?} to: {:?}",
+            self.current().path(),
+            msg,
+            to.path()
+        );
+        if let Err((size, max)) = check_message_size(&msg) {
+            debug!(
+                "{:?}: Refusing to tell: the message is {} bytes long, above {}.",
+                self.current().path(),
+                size,
+                max
+            );
+            return Err(msg);
+        }
+
+        let msg = BastionMessage::tell(msg);
+        let env = Envelope::new_with_sign(msg, self.signature());
+        // FIXME: panics?
+        to.sender()
+            .unbounded_send(env)
+            .map_err(|err| err.into_inner().into_msg().unwrap())
+    }
+
+    /// Sends a message to the specified child once its mailbox is
+    /// below the high-watermark of its group (see
+    /// [`Children::with_mailbox_high_watermark`]), returning a future
+    /// resolving once the message was enqueued, allowing to await its
+    /// admission instead of overloading the child.
+    ///
+    /// The future resolves to an error if the child stopped.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` – the [`ChildRef`] to send the message to
+    /// * `msg` – The actual message to send
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// let consumers = Bastion::children(|children| {
+    ///     children
+    ///         .with_mailbox_high_watermark(100)
+    ///         .with_exec(|ctx: BastionContext| async move {
+    ///             loop {
+    ///                 ctx.recv().await?;
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// Bastion::children(move |children| {
+    ///     let consumer = consumers.elems()[0].clone();
+    ///     children.with_exec(move |ctx: BastionContext| {
+    ///         let consumer = consumer.clone();
+    ///         async move {
+    ///             for i in 0..1_000usize {
+    ///                 // Waits while the consumer is overloaded...
+    ///                 ctx.tell_async(&consumer, i).await.map_err(|_| ())?;
+    ///             }
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_mailbox_high_watermark`]: crate::children::Children::with_mailbox_high_watermark
+    pub async fn tell_async<M: Message>(&self, to: &ChildRef, msg: M) -> Result<(), SendError> {
+        let msg = self.outbound(msg);
+        debug!(
+            "{:?}: Telling message asynchronously: {:?} to: {:?}",
+            self.current().path(),
+            msg,
+            to.path()
+        );
+        if let Err((size, max)) = check_message_size(&msg) {
+            debug!(
+                "{:?}: Refusing to tell: the message is {} bytes long, above {}.",
+                self.current().path(),
+                size,
+                max
+            );
+            return Err(SendError::MessageTooLarge {
+                msg: Msg::tell(msg),
+                size,
+                max,
+            });
+        }
+
+        let msg = BastionMessage::tell(msg);
+        let env = Envelope::new_with_sign(msg, self.signature());
+
+        // NOTE: the mailbox depth doesn't notify when it decreases.
+        while to.is_overloaded() && !to.sender().is_closed() {
+            trace!(
+                "{:?}: Waiting for {:?} to be below its high-watermark.",
+                self.current().path(),
+                to.path()
+            );
+            Delay::new(OVERLOAD_CHECK_INTERVAL).await;
+        }
+
+        to.try_send(env)
+    }
+
+    /// Sends a batch of messages to the specified children, either
+    /// enqueueing all of them or none, so that the batch is never
+    /// partially applied.
+    ///
+    /// The batch is rejected if any of its messages can't be
+    /// enqueued: if the messages sent to a child would take its
+    /// mailbox above the high-watermark of its group (see
+    /// [`Children::with_mailbox_high_watermark`]), if a child stopped
+    /// or if a message is too large. All the messages are then handed
+    /// back, in order, along with why they couldn't be enqueued.
+    ///
+    /// Note that the mailboxes are checked before any message is
+    /// enqueued, without preventing other elements from sending
+    /// messages to the same children in the meantime.
+    ///
+    /// # Arguments
+    ///
+    /// * `batch` – The children to send the messages to, along with
+    ///     the messages
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// let accounts = Bastion::children(|children| {
+    ///     children
+    ///         .with_redundancy(2)
+    ///         .with_mailbox_high_watermark(100)
+    ///         .with_exec(|ctx: BastionContext| async move {
+    ///             loop {
+    ///                 ctx.recv().await?;
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// Bastion::children(move |children| {
+    ///     let from = accounts.elems()[0].clone();
+    ///     let to = accounts.elems()[1].clone();
+    ///     children.with_exec(move |ctx: BastionContext| {
+    ///         let batch = vec![(from.clone(), -10i64), (to.clone(), 10i64)];
+    ///         async move {
+    ///             if let Err(rejected) = ctx.tell_all_or_nothing(batch) {
+    ///                 // None of the messages was sent...
+    ///                 # drop(rejected);
+    ///             }
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_mailbox_high_watermark`]: crate::children::Children::with_mailbox_high_watermark
+    pub fn tell_all_or_nothing<M: Message>(
+        &self,
+        batch: Vec<(ChildRef, M)>,
+    ) -> Result<(), Vec<RejectedMessage<M>>> {
+        let batch = batch
+            .into_iter()
+            .map(|(to, msg)| (to, self.outbound(msg)))
+            .collect::<Vec<_>>();
+        debug!(
+            "{:?}: Telling a batch of {} messages.",
+            self.current().path(),
+            batch.len()
+        );
+        let mut counts = FxHashMap::default();
+        for (to, _) in batch.iter() {
+            *counts.entry(to.id().clone()).or_insert(0) += 1;
+        }
+
+        let reasons = batch
+            .iter()
+            .map(|(to, msg)| {
+                if let Err((size, max)) = check_message_size(msg) {
+                    Some(RejectionReason::MessageTooLarge { size, max })
+                } else if to.sender().is_closed() {
+                    Some(RejectionReason::Disconnected)
+                } else if !to.admits(counts[to.id()]) {
+                    Some(RejectionReason::Overloaded)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if reasons.iter().any(Option::is_some) {
+            debug!(
+                "{:?}: Rejecting the batch: some of its messages can't be enqueued.",
+                self.current().path()
+            );
+            let rejected = batch
+                .into_iter()
+                .zip(reasons)
+                .map(|((to, msg), reason)| RejectedMessage { to, msg, reason })
+                .collect();
+            return Err(rejected);
+        }
+
+        for (to, msg) in batch {
+            let msg = BastionMessage::tell(msg);
+            let env = Envelope::new_with_sign(msg, self.signature());
+            // NOTE: the recipients were all alive when checked.
+            to.send(env).ok();
+        }
+
+        Ok(())
+    }
+
+    /// Sends a message to the specified [`RefAddr`], which is dropped
+    /// instead of being received if it is still queued in its
+    /// recipient's mailbox once `ttl` elapsed since it was sent.
+    ///
+    /// Expired messages are skipped by [`recv`], [`try_recv`] and
+    /// [`try_recv_timeout`], and routed to the dead letters.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` – the [`RefAddr`] to send the message to
+    /// * `msg` – The actual message to send
+    /// * `ttl` – The time after which the message isn't worth
+    ///     processing anymore
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             let smsg: SignedMessage = ctx.recv().await?;
+    ///             let sender_addr = smsg.signature();
+    ///             // The sender doesn't care about answers received
+    ///             // more than a second from now.
+    ///             ctx.tell_ttl(&sender_addr, "Ack", Duration::from_secs(1))
+    ///                 .expect("Unable to acknowledge");
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`recv`]: Self::recv
+    /// [`try_recv`]: Self::try_recv
+    /// [`try_recv_timeout`]: Self::try_recv_timeout
+    pub fn tell_ttl<M: Message>(&self, to: &RefAddr, msg: M, ttl: Duration) -> Result<(), M> {
+        let msg = self.outbound(msg);
+        debug!(
+            "{:?}: Telling message: {:?} to: {:?} with a TTL of {:?}",
+            self.current().path(),
+            msg,
+            to.path(),
+            ttl
+        );
+        if let Err((size, max)) = check_message_size(&msg) {
+            debug!(
+                "{:?}: Refusing to tell: the message is {} bytes long, above {}.",
+                self.current().path(),
+                size,
+                max
+            );
+            return Err(msg);
+        }
+
+        let msg = BastionMessage::Message(Msg::tell(msg).with_ttl(ttl));
+        let env = Envelope::new_with_sign(msg, self.signature());
+        // FIXME: panics?
+        to.sender()
+            .unbounded_send(env)
+            .map_err(|err| err.into_inner().into_msg().unwrap())
+    }
+
+    /// Sends a message from behalf of current context to the addr,
+    /// allowing to addr owner answer.
+    ///
+    /// This method returns [`Answer`] if it succeeded, or `Err(msg)`
+    /// otherwise.
+    ///
+    /// # Argument
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    ///     # Bastion::init();
+    /// // The message that will be "asked"...
+    /// const ASK_MSG: &'static str = "A message containing data (ask).";
+    /// // The message the will be "answered"...
+    /// const ANSWER_MSG: &'static str = "A message containing data (answer).";
+    ///
+    ///     # let children_ref =
+    /// // Create a new child...
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // ...which will receive the message asked...
+    ///             msg! { ctx.recv().await?,
+    ///                 msg: &'static str =!> {
+    ///                     assert_eq!(msg, ASK_MSG);
+    ///                     // Handle the message...
+    ///
+    ///                     // ...and eventually answer to it...
+    ///                     answer!(ctx, ANSWER_MSG);
+    ///                 };
+    ///                 // This won't happen because this example
+    ///                 // only "asks" a `&'static str`...
+    ///                 _: _ => ();
+    ///             }
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    ///     # Bastion::children(|children| {
+    ///         # children.with_exec(move |ctx: BastionContext| {
+    ///             # let child_ref = children_ref.elems()[0].clone();
+    ///             # async move {
+    /// // Later, the message is "asked" to the child...
+    /// let answer: Answer = ctx.ask(&child_ref.addr(), ASK_MSG).expect("Couldn't send the message.");
+    ///
+    /// // ...and the child's answer is received...
+    /// msg! { answer.await.expect("Couldn't receive the answer."),
+    ///     msg: &'static str => {
+    ///         assert_eq!(msg, ANSWER_MSG);
+    ///         // Handle the answer...
+    ///     };
+    ///     // This won't happen because this example
+    ///     // only answers a `&'static str`...
+    ///     _: _ => ();
+    /// }
+    ///                 #
+    ///                 # Ok(())
+    ///             # }
+    ///         # })
+    ///     # }).unwrap();
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn ask<M: Message>(&self, to: &RefAddr, msg: M) -> Result<Answer, M> {
+        debug!(
+            "{:?}: Asking message: {:?} to: {:?}",
+            self.current().path(),
+            msg,
+            to
+        );
+        if let Err((size, max)) = check_message_size(&msg) {
+            debug!(
+                "{:?}: Refusing to ask: the message is {} bytes long, above {}.",
+                self.current().path(),
+                size,
+                max
+            );
+            return Err(msg);
+        }
+        let slot = match self.state.asks.acquire() {
+            Ok(slot) => slot,
+            Err(max) => {
+                debug!(
+                    "{:?}: Refusing to ask: {} asks are already outstanding.",
+                    self.current().path(),
+                    max
+                );
+                return Err(msg);
+            }
+        };
+
+        let (msg, answer) = BastionMessage::ask(msg, self.signature());
+        let env = Envelope::new_with_sign(msg, self.signature());
+        // FIXME: panics?
+        to.sender()
+            .unbounded_send(env)
+            .map_err(|err| err.into_inner().into_msg().unwrap())?;
+
+        Ok(answer.with_slot(slot))
+    }
+
+    /// Sends a message to the specified [`RefAddr`], allowing it to
+    /// answer, like [`ask`] does, but returning why the message
+    /// couldn't be sent.
+    ///
+    /// If the group of the element linked to this `BastionContext` was
+    /// configured with [`Children::with_max_outstanding_asks`] and as
+    /// many of its asks are still waiting for their answer,
+    /// [`SendError::TooManyAsks`] is returned without sending the
+    /// message. [`SendError::MessageTooLarge`] and
+    /// [`SendError::Disconnected`] are returned if the message is too
+    /// large or if the recipient stopped.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` – The [`RefAddr`] to send the message to.
+    /// * `msg` – The actual message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// let workers = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| async move {
+    ///         loop {
+    ///             msg! { ctx.recv().await?,
+    ///                 n: u64 =!> {
+    ///                     answer!(ctx, n * 2).unwrap();
+    ///                 };
+    ///                 _: _ => ();
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// Bastion::children(move |children| {
+    ///     let worker = workers.elems()[0].clone();
+    ///     children
+    ///         .with_max_outstanding_asks(16)
+    ///         .with_exec(move |ctx: BastionContext| {
+    ///             let worker = worker.clone();
+    ///             async move {
+    ///                 match ctx.try_ask(&worker.addr(), 21u64) {
+    ///                     Ok(answer) => {
+    ///                         let answer = answer.await?;
+    ///                         // Handle the answer...
+    ///                         # drop(answer);
+    ///                     }
+    ///                     Err(SendError::TooManyAsks { max, .. }) => {
+    ///                         // Wait for some answers first...
+    ///                         # drop(max);
+    ///                     }
+    ///                     Err(_) => {
+    ///                         // Handle the other errors...
+    ///                     }
+    ///                 }
+    ///
+    ///                 Ok(())
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ask`]: Self::ask
+    /// [`Children::with_max_outstanding_asks`]: crate::children::Children::with_max_outstanding_asks
+    pub fn try_ask<M: Message>(&self, to: &RefAddr, msg: M) -> Result<Answer, SendError> {
+        debug!(
+            "{:?}: Asking message: {:?} to: {:?}",
+            self.current().path(),
+            msg,
+            to
+        );
+        if let Err((size, max)) = check_message_size(&msg) {
+            let msg = Msg::tell(msg);
+            return Err(SendError::MessageTooLarge { msg, size, max });
+        }
+        let slot = match self.state.asks.acquire() {
+            Ok(slot) => slot,
+            Err(max) => {
+                let msg = Msg::tell(msg);
+                return Err(SendError::TooManyAsks { msg, max });
+            }
+        };
+
+        let (msg, answer) = BastionMessage::ask(msg, self.signature());
+        let env = Envelope::new_with_sign(msg, self.signature());
+        to.sender().unbounded_send(env).map_err(SendError::from)?;
+
+        Ok(answer.with_slot(slot))
+    }
+
+    /// Returns the amount of messages sent by the element linked to
+    /// this `BastionContext` with [`ask`] or [`try_ask`] whose
+    /// [`Answer`] wasn't received nor dropped yet.
+    ///
+    /// [`ask`]: Self::ask
+    /// [`try_ask`]: Self::try_ask
+    pub fn outstanding_asks(&self) -> usize {
+        self.state.asks.len()
+    }
+
+    /// Sends a message to every member of the target group(s),
+    /// expecting an answer from each of them.
+    ///
+    /// This method returns a [`GroupAnswers`] stream yielding the
+    /// reply of every member as soon as it arrives. Members which
+    /// can't be asked, drop the question or don't answer before
+    /// `timeout` elapsed are yielded as [`GroupReply::Missing`]
+    /// instead of being silently dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - Defines the asked members in according with
+    /// the [`BroadcastTarget`] value.
+    /// * `msg` - The message to send to every member.
+    /// * `timeout` - How long to wait for the answers.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use futures::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             let target = BroadcastTarget::Group("workers".to_string());
+    ///             let replies: Vec<GroupReply> = ctx
+    ///                 .ask_group(target, "queue depth?", Duration::from_secs(1))
+    ///                 .collect()
+    ///                 .await;
+    ///
+    ///             for reply in replies {
+    ///                 match reply {
+    ///                     GroupReply::Answered { from, answer } => {
+    ///                         // Handle the answer...
+    ///                     }
+    ///                     GroupReply::Missing { from } => {
+    ///                         // Handle the missing answer...
+    ///                     }
+    ///                 }
+    ///             }
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`GroupReply::Missing`]: crate::message::GroupReply::Missing
+    pub fn ask_group<M: Message + Clone>(
+        &self,
+        target: BroadcastTarget,
+        msg: M,
+        timeout: Duration,
+    ) -> GroupAnswers {
+        debug!(
+            "{:?}: Asking message to group(s): {:?} to: {:?}",
+            self.current().path(),
+            msg,
+            target
+        );
+        let mut answers = GroupAnswers::new(timeout);
+        for member in SYSTEM.dispatcher().members(target) {
+            match self.ask(&member.addr(), msg.clone()) {
+                Ok(answer) => answers.push_answer(member, answer),
+                Err(_) => answers.push_missing(member),
+            }
+        }
+
+        answers
+    }
+
+    /// Sends a message to every member of the target group(s), and
+    /// waits for them to acknowledge it using [`ack`] while they
+    /// handle it.
+    ///
+    /// This method returns a future resolving to an [`AckReport`],
+    /// telling which members acknowledged the message. Members which
+    /// can't be sent the message, handle it without acknowledging it,
+    /// are stopped or don't acknowledge it before `timeout` elapsed
+    /// are reported as missing.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - Defines the members the message is sent to in
+    /// according with the [`BroadcastTarget`] value.
+    /// * `msg` - The message to send to every member.
+    /// * `timeout` - How long to wait for the acknowledgements.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_dispatcher(Dispatcher::with_type(DispatcherType::Named("replicas".to_string())))
+    ///         .with_exec(|ctx: BastionContext| async move {
+    ///             loop {
+    ///                 msg! { ctx.recv().await?,
+    ///                     msg: &'static str => {
+    ///                         // Apply the command...
+    ///                         ctx.ack().expect("Couldn't acknowledge the command.");
+    ///                     };
+    ///                     _: _ => ();
+    ///                 }
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| async move {
+    ///         let target = BroadcastTarget::Group("replicas".to_string());
+    ///         let report = ctx
+    ///             .broadcast_with_ack(target, "apply", Duration::from_secs(1))
+    ///             .await;
+    ///
+    ///         if report.missing.is_empty() {
+    ///             // Every replica applied the command...
+    ///         }
+    ///
+    ///         Ok(())
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ack`]: Self::ack
+    pub fn broadcast_with_ack<M: Message + Clone>(
+        &self,
+        target: BroadcastTarget,
+        msg: M,
+        timeout: Duration,
+    ) -> impl Future<Output = AckReport> {
+        let msg = self.outbound(msg);
+        debug!(
+            "{:?}: Broadcasting message with acknowledgements: {:?} to: {:?}",
+            self.current().path(),
+            msg,
+            target
+        );
+        let mut answers = GroupAnswers::new(timeout);
+        for member in SYSTEM.dispatcher().members(target) {
+            let (msg, answer) = Msg::ack_request(msg.clone(), self.signature());
+            let env = Envelope::new_with_sign(BastionMessage::Message(msg), self.signature());
+            match member.send(env) {
+                Ok(()) => answers.push_answer(member, answer),
+                Err(_) => answers.push_missing(member),
+            }
+        }
+
+        answers.fold(AckReport::default(), |mut report, reply| {
+            match reply {
+                GroupReply::Answered { from, .. } => report.acked.push(from),
+                GroupReply::Missing { from } => report.missing.push(from),
+            }
+
+            future::ready(report)
+        })
+    }
+
+    /// Acknowledges the last received message, if it was sent with
+    /// [`broadcast_with_ack`] and wasn't acknowledged yet.
+    ///
+    /// This method returns `Ok(())` if the message was acknowledged,
+    /// or `Err(())` otherwise.
+    ///
+    /// See [`broadcast_with_ack`] for an example.
+    ///
+    /// [`broadcast_with_ack`]: Self::broadcast_with_ack
+    pub fn ack(&self) -> Result<(), ()> {
+        trace!("BastionContext({}): Acknowledging message.", self.id);
+        match self.state.take_ack() {
+            Some(sender) => sender.reply(()),
+            None => Err(()),
+        }
+    }
+
+    /// Sends the notification to each declared dispatcher of the actor.
+    ///
+    /// # Argument
+    ///
+    /// * `dispatchers` - Vector of dispatcher names to which need to
+    /// deliver a notification.
+    /// * `notification_type` - The type of the notification to send.
+    ///
+    pub fn notify(&self, dispatchers: &[DispatcherType], notification_type: NotificationType) {
+        let global_dispatcher = SYSTEM.dispatcher();
+        let from_actor = self.current();
+        global_dispatcher.notify(from_actor, dispatchers, notification_type);
+    }
+
+    /// Sends the broadcasted message to the target group(s).
+    ///
+    /// # Argument
+    ///
+    /// * `target` - Defines the message receivers in according with
+    /// the [`BroadcastTarget`] value.
+    /// * `message` - The broadcasted message.
+    ///
+    pub fn broadcast_message<M: Message>(&self, target: BroadcastTarget, message: M) {
+        let message = match self.admit_broadcast(message) {
+            Some(message) => message,
+            None => return,
+        };
+        let msg = Arc::new(SignedMessage {
+            msg: Msg::broadcast(message),
+            sign: self.signature(),
+        });
+
+        let global_dispatcher = SYSTEM.dispatcher();
+        global_dispatcher.broadcast_message(target, &msg);
+    }
+
+    /// Sends the broadcasted message to the target group(s), going
+    /// through its receivers in the given order.
+    ///
+    /// The order only applies when the message is sent to each actor
+    /// of the targeted groups, that is with [`BroadcastTarget::Groups`].
+    /// The other targets leave the choice of the receivers to the
+    /// handlers of their dispatchers. [`broadcast_message`] uses
+    /// [`BroadcastOrder::Unordered`].
+    ///
+    /// # Argument
+    ///
+    /// * `target` - Defines the message receivers in according with
+    /// the [`BroadcastTarget`] value.
+    /// * `message` - The broadcasted message.
+    /// * `order` - The order in which the receivers are gone through.
+    ///
+    /// [`broadcast_message`]: Self::broadcast_message
+    pub fn broadcast_message_ordered<M: Message>(
+        &self,
+        target: BroadcastTarget,
+        message: M,
+        order: BroadcastOrder,
+    ) {
+        let message = match self.admit_broadcast(message) {
+            Some(message) => message,
+            None => return,
+        };
+        let msg = Arc::new(SignedMessage {
+            msg: Msg::broadcast(message),
+            sign: self.signature(),
+        });
+
+        let global_dispatcher = SYSTEM.dispatcher();
+        global_dispatcher.broadcast_message_ordered(target, &msg, order);
+    }
+
+    /// Sends the broadcasted messages to the target group(s) as a
+    /// single batch.
+    ///
+    /// Compared to calling [`broadcast_message`] for each message,
+    /// every member of the targeted groups is sent its share of the
+    /// batch as a single envelope, receiving it in the order of
+    /// `messages`.
+    ///
+    /// The groups with a rate limit or a coalescing window, or whose
+    /// handler doesn't override [`DispatcherHandler::broadcast_batch`],
+    /// are sent the messages one by one.
+    ///
+    /// # Argument
+    ///
+    /// * `target` - Defines the message receivers in according with
+    /// the [`BroadcastTarget`] value.
+    /// * `messages` - The broadcasted messages.
+    ///
+    /// [`broadcast_message`]: Self::broadcast_message
+    /// [`DispatcherHandler::broadcast_batch`]: crate::dispatcher::DispatcherHandler::broadcast_batch
+    pub fn broadcast_batch<M: Message>(&self, target: BroadcastTarget, messages: Vec<M>) {
+        let sign = self.signature();
+        let msgs = messages
+            .into_iter()
+            .filter_map(|message| self.admit_broadcast(message))
+            .map(|message| {
+                Arc::new(SignedMessage {
+                    msg: Msg::broadcast(message),
+                    sign: sign.clone(),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let global_dispatcher = SYSTEM.dispatcher();
+        global_dispatcher.broadcast_batch(target, &msgs);
+    }
+
+    // Returns the message, once transformed, if it can be broadcasted,
+    // or routes it to the dead letters if it is larger than the
+    // maximum message size.
+    fn admit_broadcast<M: Message>(&self, message: M) -> Option<M> {
+        let message = self.outbound(message);
+        let (size, max) = match check_message_size(&message) {
+            Ok(()) => return Some(message),
+            Err(oversized) => oversized,
+        };
+
+        debug!(
+            "{:?}: Routing a broadcasted message to the dead letters: it is {} bytes long, above {}.",
+            self.current().path(),
+            size,
+            max
+        );
+        let msg = BastionMessage::Message(Msg::broadcast(message));
+        let env = Envelope::new_with_sign(msg, self.signature());
+        SYSTEM.dead_letters_of(self.current().path()).send(env).ok();
+
+        None
+    }
+
+    // Applies the outbound transforms of the group to a message sent by
+    // the element, see `Children::with_outbound_transform`.
+    fn outbound<M: Message>(&self, msg: M) -> M {
+        self.state.outbound.apply(msg)
+    }
+
+    /// Subscribes the current element to the given topic of the event
+    /// bus, so that it receives every message [`publish`]ed to it
+    /// until it [`unsubscribe`]s from it or dies.
+    ///
+    /// Subscribing more than once to the same topic has no effect.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The name of the topic to subscribe to.
+    ///
+    /// See [`publish`] for an example.
+    ///
+    /// [`publish`]: Self::publish
+    /// [`unsubscribe`]: Self::unsubscribe
+    pub fn subscribe(&self, topic: &str) {
+        debug!("{:?}: Subscribing to: {}", self.current().path(), topic);
+        let global_dispatcher = SYSTEM.dispatcher();
+        let _ = global_dispatcher.subscribe(topic, self.current().clone());
+    }
+
+    /// Unsubscribes the current element from the given topic of the
+    /// event bus, if it was subscribed to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The name of the topic to unsubscribe from.
+    ///
+    /// See [`publish`] for an example.
+    ///
+    /// [`publish`]: Self::publish
+    pub fn unsubscribe(&self, topic: &str) {
+        debug!("{:?}: Unsubscribing from: {}", self.current().path(), topic);
+        let global_dispatcher = SYSTEM.dispatcher();
+        let _ = global_dispatcher.unsubscribe(topic, self.current());
+    }
+
+    /// Publishes a message to the given topic of the event bus, which
+    /// sends it to each of the topic's current [`subscribe`]rs, wherever
+    /// they are in the supervision tree.
+    ///
+    /// Subscribers receive the message like a broadcasted one, so it
+    /// should be matched by reference. If the topic has no subscribers,
+    /// the message is routed to the dead letters.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The name of the topic to publish the message to.
+    /// * `msg` - The published message.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             ctx.subscribe("events");
+    ///             msg! { ctx.recv().await?,
+    ///                 ref event: &'static str => {
+    ///                     // Handle the event...
+    ///                 };
+    ///                 _: _ => ();
+    ///             }
+    ///             ctx.unsubscribe("events");
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the subscribers.");
+    ///
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             ctx.publish("events", "Something happened");
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the publisher.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`subscribe`]: Self::subscribe
+    pub fn publish<M: Message>(&self, topic: &str, msg: M) {
+        let msg = self.outbound(msg);
+        debug!(
+            "{:?}: Publishing message: {:?} to: {}",
+            self.current().path(),
+            msg,
+            topic
+        );
+        let msg = Arc::new(SignedMessage {
+            msg: Msg::broadcast(msg),
+            sign: self.signature(),
+        });
+
+        let global_dispatcher = SYSTEM.dispatcher();
+        let _ = global_dispatcher.publish(topic, &msg);
+    }
+
+    /// Processes a message with the given handler, retrying it
+    /// according to the given policy until it returns `Ok` or the
+    /// policy runs out of attempts.
+    ///
+    /// Returns the last result of the handler, so that returning its
+    /// error from the child's future escalates it as a fault once the
+    /// retries are exhausted. The retries are specific to this call,
+    /// so that every message gets as many attempts.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - How many attempts to make, and how long to wait
+    ///     between them.
+    /// * `handler` - The closure processing the message, called once
+    ///     per attempt.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// # async fn call_downstream(request: &str) -> Result<(), ()> { Ok(()) }
+    /// #
+    /// let policy = RetryPolicy::new(5).with_backoff(ActorRestartStrategy::LinearBackOff {
+    ///     timeout: Duration::from_millis(10),
+    /// });
+    ///
+    /// Bastion::children(|children| {
+    ///     children.with_exec(move |ctx: BastionContext| {
+    ///         let policy = policy.clone();
+    ///         async move {
+    ///             loop {
+    ///                 msg! { ctx.recv().await?,
+    ///                     ref request: &'static str => {
+    ///                         ctx.retry(&policy, || call_downstream(request)).await?;
+    ///                     };
+    ///                     _: _ => ();
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub async fn retry<F, Fut, T, E>(&self, policy: &RetryPolicy, mut handler: F) -> Result<T, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut attempts = 1;
+        loop {
+            let err = match handler().await {
+                Ok(res) => return Ok(res),
+                Err(err) => err,
+            };
+
+            if attempts >= policy.max_attempts() {
+                debug!(
+                    "BastionContext({}): Giving up after {} attempts.",
+                    self.id, attempts
+                );
+                return Err(err);
+            }
+
+            debug!(
+                "BastionContext({}): Attempt {} failed, retrying.",
+                self.id, attempts
+            );
+            if let Some(delay) = policy.delay(attempts - 1) {
+                Delay::new(delay).await;
+            }
+            attempts += 1;
+        }
+    }
+
+    /// Stores a value in this context's local storage, replacing and
+    /// returning the previously stored value of the same type, if any.
+    ///
+    /// The local storage holds at most one value per type and lives as
+    /// long as the current incarnation of the child: it is cleared when
+    /// the child is restarted.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to store.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|mut ctx: BastionContext| {
+    ///         async move {
+    ///             ctx.local_set(0usize);
+    ///
+    ///             loop {
+    ///                 ctx.recv().await?;
+    ///
+    ///                 let received = ctx.local_get::<usize>().copied().unwrap_or_default();
+    ///                 ctx.local_set(received + 1);
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn local_set<T>(&mut self, value: T) -> Option<T>
+    where
+        T: Send + Sync + 'static,
+    {
+        trace!(
+            "BastionContext({}): Setting local value of type {}.",
+            self.id,
+            std::any::type_name::<T>()
+        );
+        self.locals
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|previous| previous.downcast().ok())
+            .map(|previous| *previous)
+    }
+
+    /// Returns a reference to the value of type `T` stored in this
+    /// context's local storage, if any.
+    ///
+    /// See [`local_set`] for more details.
+    ///
+    /// [`local_set`]: Self::local_set
+    pub fn local_get<T>(&self) -> Option<&T>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.locals
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref())
+    }
+
+    /// Returns a mutable reference to the value of type `T` stored in
+    /// this context's local storage, if any.
+    ///
+    /// See [`local_set`] for more details.
+    ///
+    /// [`local_set`]: Self::local_set
+    pub fn local_get_mut<T>(&mut self) -> Option<&mut T>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.locals
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_mut())
+    }
+}
+
+// The future returned by `BastionContext::cancelled`, which is
+// polled by the child's task like the rest of its future.
+struct Cancelled {
+    state: Arc<Pin<Box<ContextState>>>,
+    watching: bool,
+}
+
+impl Future for Cancelled {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _: &mut Context) -> Poll<()> {
+        if self.state.is_cancelled() {
+            return Poll::Ready(());
+        }
+
+        if !self.watching {
+            self.state
+                .cancellation_watchers
+                .fetch_add(1, Ordering::SeqCst);
+            self.watching = true;
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Drop for Cancelled {
+    fn drop(&mut self) {
+        if self.watching {
+            self.state
+                .cancellation_watchers
+                .fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Returns a future yielding to the executor once before resolving,
+/// letting it run the other elements.
+fn yield_now() -> impl Future<Output = ()> {
+    let mut yielded = false;
+    future::poll_fn(move |cx| {
+        if yielded {
+            return Poll::Ready(());
+        }
+
+        yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    })
+}
+
+impl OutboundTransforms {
+    pub(crate) fn push<M, F>(&mut self, transform: F)
+    where
+        M: Message,
+        F: Fn(M) -> M + Send + Sync + 'static,
+    {
+        self.0.push(Arc::new(move |slot: &mut dyn Any| {
+            if let Some(slot) = slot.downcast_mut::<Option<M>>() {
+                *slot = slot.take().map(&transform);
+            }
+        }));
+    }
+
+    /// Runs the message through the transforms of its type, in the
+    /// order they were added.
+    pub(crate) fn apply<M: Message>(&self, msg: M) -> M {
+        if self.0.is_empty() {
+            return msg;
+        }
+
+        let mut slot = Some(msg);
+        for transform in self.0.iter() {
+            transform(&mut slot);
+        }
+
+        // NOTE: the transforms always put a message back.
+        slot.unwrap()
+    }
+}
+
+impl fmt::Debug for OutboundTransforms {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_tuple("OutboundTransforms")
+            .field(&self.0.len())
+            .finish()
+    }
+}
+
+impl ContextState {
+    pub(crate) fn new() -> Self {
+        ContextState {
+            messages: SegQueue::new(),
+            #[cfg(feature = "testkit")]
+            snapshotted: Mutex::new(VecDeque::new()),
+            persistence: None,
+            persisted: SegQueue::new(),
+            in_flight: Mutex::new(None),
+            depth: Arc::new(AtomicUsize::new(0)),
+            overflow: None,
+            panic_policy: PanicPolicy::default(),
+            outbound: OutboundTransforms::default(),
+            asks: OutstandingAsks::default(),
+            stopped_on_panic: AtomicBool::new(false),
+            paused: AtomicBool::new(false),
+            waiting: AtomicBool::new(false),
+            running: AtomicBool::new(false),
+            cancelled: AtomicBool::new(false),
+            cancellation_watchers: AtomicUsize::new(0),
+            escalated: AtomicBool::new(false),
+            last_heartbeat: Mutex::new(Instant::now()),
+            index: 0,
+            fairness_budget: None,
+            received_in_row: AtomicUsize::new(0),
+            ack: Mutex::new(None),
+            #[cfg(feature = "metrics")]
+            enqueued: SegQueue::new(),
+            #[cfg(feature = "metrics")]
+            handled_since: Mutex::new(None),
+            #[cfg(feature = "scaling")]
+            stats: Arc::new(AtomicU64::new(0)),
+            #[cfg(feature = "scaling")]
+            actor_stats: Arc::new(LOTable::new()),
+        }
+    }
+
+    #[cfg(feature = "scaling")]
+    pub(crate) fn set_stats(&mut self, stats: Arc<AtomicU64>) {
+        self.stats = stats;
+    }
+
+    #[cfg(feature = "scaling")]
+    pub(crate) fn set_actor_stats(&mut self, actor_stats: Arc<LOTable<BastionId, u32>>) {
+        self.actor_stats = actor_stats;
+    }
+
     #[cfg(feature = "scaling")]
     pub(crate) fn stats(&self) -> Arc<AtomicU64> {
         self.stats.clone()
@@ -804,17 +3306,427 @@ impl ContextState {
         self.actor_stats.clone()
     }
 
+    pub(crate) fn set_index(&mut self, index: usize) {
+        self.index = index;
+    }
+
+    pub(crate) fn index(&self) -> usize {
+        self.index
+    }
+
+    pub(crate) fn set_fairness_budget(&mut self, budget: usize) {
+        self.fairness_budget = Some(budget);
+    }
+
+    /// Returns whether the child's future received as many messages in
+    /// a row as its fairness budget allows, in which case it should
+    /// yield before receiving the next one.
+    pub(crate) fn is_budget_exhausted(&self) -> bool {
+        match self.fairness_budget {
+            Some(budget) => self.received_in_row.load(Ordering::SeqCst) >= budget,
+            None => false,
+        }
+    }
+
+    /// Counts a message received by the child's future against its
+    /// fairness budget.
+    pub(crate) fn spend_budget(&self) {
+        self.received_in_row.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Restores the fairness budget of the child's future, because it
+    /// yielded.
+    pub(crate) fn restore_budget(&self) {
+        self.received_in_row.store(0, Ordering::SeqCst);
+    }
+
+    pub(crate) fn set_persistence(&mut self, persistence: Arc<Persistence>) {
+        self.persistence = Some(persistence);
+    }
+
+    pub(crate) fn set_panic_policy(&mut self, policy: PanicPolicy) {
+        self.panic_policy = policy;
+    }
+
+    pub(crate) fn set_outbound_transforms(&mut self, outbound: OutboundTransforms) {
+        self.outbound = outbound;
+    }
+
+    pub(crate) fn set_max_outstanding_asks(&mut self, max: usize) {
+        self.asks = OutstandingAsks::new(Some(max));
+    }
+
+    pub(crate) fn is_stopped_on_panic(&self) -> bool {
+        self.stopped_on_panic.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn set_overflow(&mut self, capacity: usize, priorities: Arc<DropPriorities>) {
+        self.overflow = Some((capacity, priorities));
+    }
+
+    /// Makes room for the given message in the mailbox of the element
+    /// at the given path if it is full, by routing the oldest message
+    /// of the lowest drop priority to its dead letters, and returns
+    /// the message unless it was the one evicted.
+    pub(crate) fn make_room(
+        &self,
+        msg: Msg,
+        sign: RefAddr,
+        path: &BastionPath,
+    ) -> Option<(Msg, RefAddr)> {
+        let (capacity, priorities) = match &self.overflow {
+            Some(overflow) => overflow,
+            None => return Some((msg, sign)),
+        };
+        if self.queued() < *capacity {
+            return Some((msg, sign));
+        }
+
+        // NOTE: the messages are only pushed by the element, which
+        //      polls its future (popping them) in between, so that
+        //      the mailbox can be drained and refilled in order.
+        let mut queued = Vec::with_capacity(*capacity);
+        while let Some(smsg) = self.next_message() {
+            queued.push(smsg);
+        }
+        let mut persisted = Vec::with_capacity(queued.len());
+        while let Some(seq) = self.persisted.pop() {
+            persisted.push(seq);
+        }
+        #[cfg(feature = "metrics")]
+        let mut enqueued = Vec::with_capacity(queued.len());
+        #[cfg(feature = "metrics")]
+        while let Some(instant) = self.enqueued.pop() {
+            enqueued.push(instant);
+        }
+
+        // NOTE: `min_by_key` returns the first of the messages of the
+        //      lowest priority, which is the oldest one.
+        let incoming = priorities.priority_of(&msg);
+        let victim = queued
+            .iter()
+            .enumerate()
+            .map(|(i, smsg)| (i, priorities.priority_of(&smsg.msg)))
+            .min_by_key(|(_, priority)| *priority)
+            .filter(|(_, priority)| *priority <= incoming);
+
+        let (evicted, kept) = match victim {
+            Some((i, _)) => {
+                self.depth.fetch_sub(1, Ordering::SeqCst);
+                #[cfg(feature = "metrics")]
+                {
+                    if i < enqueued.len() {
+                        enqueued.remove(i);
+                    }
+                }
+                if i < persisted.len() {
+                    if let (Some(persistence), Some(seq)) = (&self.persistence, persisted.remove(i))
+                    {
+                        // The evicted message won't ever be handled.
+                        persistence.complete(seq);
+                    }
+                }
+
+                (queued.remove(i), Some((msg, sign)))
+            }
+            None => (SignedMessage::new(msg, sign), None),
+        };
+
+        for seq in persisted {
+            self.persisted.push(seq);
+        }
+        #[cfg(feature = "metrics")]
+        for instant in enqueued {
+            self.enqueued.push(instant);
+        }
+        for smsg in queued {
+            self.messages.push(smsg);
+        }
+
+        debug!(
+            "ContextState: Routing an evicted message to the dead letters: {:?}",
+            evicted.msg
+        );
+        let SignedMessage { msg, sign } = evicted;
+        let msg = BastionMessage::Message(msg);
+        SYSTEM
+            .dead_letters_of(path)
+            .send(Envelope { msg, sign })
+            .ok();
+
+        kept
+    }
+
     pub(crate) fn push_message(&self, msg: Msg, sign: RefAddr) {
+        if let Some(persistence) = &self.persistence {
+            // NOTE: the sequence number is pushed first, so that it
+            //      is always there once the message is popped.
+            self.persisted.push(persistence.append(&msg));
+        }
+
+        self.depth.fetch_add(1, Ordering::SeqCst);
+        #[cfg(feature = "metrics")]
+        self.enqueued.push(Instant::now());
         self.messages.push(SignedMessage::new(msg, sign))
     }
 
-    pub(crate) fn pop_message(&self) -> Option<SignedMessage> {
+    /// Pushes a message which was already persisted with the given
+    /// sequence number, while the element isn't running.
+    pub(crate) fn push_persisted(&self, seq: u64, msg: Msg, sign: RefAddr) {
+        self.persisted.push(Some(seq));
+        self.depth.fetch_add(1, Ordering::SeqCst);
+        #[cfg(feature = "metrics")]
+        self.enqueued.push(Instant::now());
+        self.messages.push(SignedMessage::new(msg, sign))
+    }
+
+    /// Pushes the persisted message which was being handled back to
+    /// the mailbox, once the element restarted after faulting.
+    pub(crate) fn replay_in_flight(&self) {
+        let persistence = match &self.persistence {
+            Some(persistence) => persistence,
+            None => return,
+        };
+
+        // FIXME: panics?
+        let seq = match self.in_flight.lock().unwrap().take() {
+            Some(seq) => seq,
+            None => return,
+        };
+
+        // NOTE: the message is received after the ones which were
+        //      already waiting in the mailbox.
+        if let Some(msg) = persistence.replay(seq) {
+            debug!("ContextState: Replaying persisted message #{}.", seq);
+            self.push_persisted(seq, msg, RefAddr::dead_letters());
+        }
+    }
+
+    /// Routes the messages waiting in the mailbox of the element at
+    /// the given path to its dead letters, once it restarted without
+    /// them being handed over.
+    pub(crate) fn discard_messages(&self, path: &BastionPath) {
+        while let Some(SignedMessage { msg, sign }) = self.next_message() {
+            self.depth.fetch_sub(1, Ordering::SeqCst);
+            #[cfg(feature = "metrics")]
+            self.enqueued.pop();
+            if let (Some(persistence), Some(seq)) = (&self.persistence, self.persisted.pop()) {
+                // The discarded message won't ever be handled.
+                if let Some(seq) = seq {
+                    persistence.complete(seq);
+                }
+            }
+
+            debug!(
+                "ContextState: Routing a discarded message to the dead letters: {:?}",
+                msg
+            );
+            let msg = BastionMessage::Message(msg);
+            SYSTEM
+                .dead_letters_of(path)
+                .send(Envelope { msg, sign })
+                .ok();
+        }
+    }
+
+    /// Marks the persisted message which was being handled as done.
+    pub(crate) fn complete_in_flight(&self) {
+        // FIXME: panics?
+        let seq = self.in_flight.lock().unwrap().take();
+        if let (Some(persistence), Some(seq)) = (&self.persistence, seq) {
+            persistence.complete(seq);
+        }
+    }
+
+    // Marks the previously received message as done, as it was
+    // handled once the next one is received, and returns the
+    // sequence number of the popped message, if it was persisted.
+    fn next_persisted(&self) -> Option<u64> {
+        self.persistence.as_ref()?;
+        self.complete_in_flight();
+
+        self.persisted.pop().flatten()
+    }
+
+    /// Pops the next message of the mailbox of the element at the
+    /// given path, routing the expired ones to its dead letters.
+    pub(crate) fn pop_message(&self, path: &BastionPath) -> Option<SignedMessage> {
+        #[cfg(feature = "metrics")]
+        self.record_latency(path);
+
+        if self.paused.load(Ordering::SeqCst) || QUIESCE.is_quiesced() {
+            return None;
+        }
+
+        loop {
+            let SignedMessage { mut msg, sign } = self.next_message()?;
+            self.depth.fetch_sub(1, Ordering::SeqCst);
+            #[cfg(feature = "metrics")]
+            let enqueued = self.enqueued.pop();
+            let seq = self.next_persisted();
+            if !msg.is_expired() {
+                #[cfg(feature = "metrics")]
+                {
+                    // FIXME: panics?
+                    *self.handled_since.lock().unwrap() = enqueued;
+                }
+                // FIXME: panics?
+                *self.in_flight.lock().unwrap() = seq;
+                // NOTE: a message which wasn't acknowledged before the
+                //      next one is received won't ever be.
+                // FIXME: panics?
+                *self.ack.lock().unwrap() = msg.take_ack_request();
+                return Some(SignedMessage::new(msg, sign));
+            }
+
+            if let (Some(persistence), Some(seq)) = (&self.persistence, seq) {
+                // The expired message won't ever be handled.
+                persistence.complete(seq);
+            }
+
+            debug!(
+                "ContextState: Routing an expired message to the dead letters: {:?}",
+                msg
+            );
+            let msg = BastionMessage::Message(msg.without_ttl());
+            SYSTEM
+                .dead_letters_of(path)
+                .send(Envelope { msg, sign })
+                .ok();
+        }
+    }
+
+    // Records the latency of the message which was being handled, as
+    // it was handled once the element asks for the next one.
+    #[cfg(feature = "metrics")]
+    fn record_latency(&self, path: &BastionPath) {
+        // FIXME: panics?
+        let enqueued = match self.handled_since.lock().unwrap().take() {
+            Some(enqueued) => enqueued,
+            None => return,
+        };
+
+        LATENCIES.record(path.group_path().id(), enqueued.elapsed());
+    }
+
+    #[cfg(not(feature = "testkit"))]
+    fn next_message(&self) -> Option<SignedMessage> {
         self.messages.pop()
     }
 
+    #[cfg(feature = "testkit")]
+    fn next_message(&self) -> Option<SignedMessage> {
+        // FIXME: panics?
+        let msg = self.snapshotted.lock().unwrap().pop_front();
+        msg.or_else(|| self.messages.pop())
+    }
+
+    #[cfg(feature = "testkit")]
+    pub(crate) fn with_snapshot<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&[SignedMessage]) -> R,
+    {
+        // FIXME: panics?
+        let mut snapshotted = self.snapshotted.lock().unwrap();
+        // NOTE: messages sent in the meantime are pushed after the
+        //      ones moved here, which keeps them in order.
+        while let Some(msg) = self.messages.pop() {
+            snapshotted.push_back(msg);
+        }
+
+        f(snapshotted.make_contiguous())
+    }
+
+    #[cfg(not(feature = "testkit"))]
+    fn queued(&self) -> usize {
+        self.messages.len()
+    }
+
+    #[cfg(feature = "testkit")]
+    fn queued(&self) -> usize {
+        // FIXME: panics?
+        self.snapshotted.lock().unwrap().len() + self.messages.len()
+    }
+
+    pub(crate) fn depth(&self) -> Arc<AtomicUsize> {
+        self.depth.clone()
+    }
+
+    fn take_ack(&self) -> Option<AnswerSender> {
+        // FIXME: panics?
+        self.ack.lock().unwrap().take()
+    }
+
+    pub(crate) fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::SeqCst);
+    }
+
+    fn set_waiting(&self, waiting: bool) {
+        self.waiting.store(waiting, Ordering::SeqCst);
+    }
+
+    // Returns whether the child's future didn't wait for a message
+    // before.
+    fn set_running(&self) -> bool {
+        !self.running.swap(true, Ordering::SeqCst)
+    }
+
+    /// Resets the running state of a restarted child, which has to
+    /// reach it again.
+    pub(crate) fn reset_running(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Returns whether the child's future waited for a message at
+    /// least once.
+    pub(crate) fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    /// Returns whether the child's future is currently waiting for
+    /// the child to be cancelled.
+    pub(crate) fn is_cancellation_watched(&self) -> bool {
+        self.cancellation_watchers.load(Ordering::SeqCst) > 0
+    }
+
+    pub(crate) fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn is_escalated(&self) -> bool {
+        self.escalated.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn heartbeat(&self) {
+        // FIXME: panics?
+        *self.last_heartbeat.lock().unwrap() = Instant::now();
+    }
+
+    /// Returns whether the child's future neither waits for a new
+    /// message nor proved it isn't hung for longer than `timeout`.
+    pub(crate) fn is_unresponsive(&self, timeout: Duration) -> bool {
+        if self.waiting.load(Ordering::SeqCst) {
+            return false;
+        }
+
+        // FIXME: panics?
+        self.last_heartbeat.lock().unwrap().elapsed() > timeout
+    }
+
+    /// Returns whether all the received messages were handled, which
+    /// is the case once the mailbox is empty and the child's future
+    /// is waiting for a new message.
+    pub(crate) fn is_drained(&self) -> bool {
+        self.queued() == 0 && self.waiting.load(Ordering::SeqCst)
+    }
+
     #[cfg(feature = "scaling")]
     pub(crate) fn mailbox_size(&self) -> u32 {
-        self.messages.len() as _
+        self.queued() as _
     }
 }
 
@@ -0,0 +1,176 @@
+//!
+//! The store keeping the messages received by the dead letters
+//! children group, bounded in size and age (see
+//! [`Bastion::configure_dead_letters`]), and the dead letter handlers
+//! of the supervisors configured with one (see
+//! [`Supervisor::with_dead_letter_handler`]).
+//!
+//! [`Bastion::configure_dead_letters`]: crate::Bastion::configure_dead_letters
+//! [`Supervisor::with_dead_letter_handler`]: crate::supervisor::Supervisor::with_dead_letter_handler
+use crate::children_ref::ChildrenRef;
+use crate::context::BastionId;
+use crate::envelope::SignedMessage;
+use crate::path::BastionPath;
+use fxhash::FxHashMap;
+use once_cell::sync::Lazy;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tracing::trace;
+
+pub(crate) static DEAD_LETTERS: Lazy<DeadLetterStore> = Lazy::new(DeadLetterStore::new);
+
+pub(crate) static DEAD_LETTER_HANDLERS: Lazy<DeadLetterHandlers> =
+    Lazy::new(DeadLetterHandlers::default);
+
+/// The amount of dead letters kept by default.
+pub(crate) const DEFAULT_MAX_ENTRIES: usize = 1024;
+/// How long dead letters are kept by default.
+pub(crate) const DEFAULT_MAX_AGE: Duration = Duration::from_secs(60);
+
+#[derive(Debug)]
+pub(crate) struct DeadLetterStore {
+    inner: Mutex<Store>,
+    // The amount of dead letters dropped because the store was full
+    // or because they were too old.
+    dropped: AtomicU64,
+}
+
+#[derive(Debug, Default)]
+/// The dead letter handlers of the launched supervisors configured
+/// with one, by identifier.
+pub(crate) struct DeadLetterHandlers {
+    handlers: RwLock<FxHashMap<BastionId, ChildrenRef>>,
+}
+
+#[derive(Debug)]
+struct Store {
+    max_entries: usize,
+    max_age: Duration,
+    // The dead letters, in the order they were received (which is
+    // also the order they are evicted and drained in), with when they
+    // were received.
+    entries: VecDeque<(Instant, SignedMessage)>,
+}
+
+impl DeadLetterStore {
+    fn new() -> Self {
+        let store = Store {
+            max_entries: DEFAULT_MAX_ENTRIES,
+            max_age: DEFAULT_MAX_AGE,
+            entries: VecDeque::new(),
+        };
+
+        DeadLetterStore {
+            inner: Mutex::new(store),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Sets the amount of dead letters kept and how long they are
+    /// kept, dropping the ones which don't fit anymore.
+    pub(crate) fn configure(&self, max_entries: usize, max_age: Duration) {
+        // FIXME: panics?
+        let mut store = self.inner.lock().unwrap();
+        store.max_entries = max_entries;
+        store.max_age = max_age;
+
+        let dropped = store.prune(Instant::now());
+        self.dropped.fetch_add(dropped, Ordering::Relaxed);
+    }
+
+    /// Keeps a dead letter, dropping the oldest ones if the store is
+    /// full.
+    pub(crate) fn push(&self, smsg: SignedMessage) {
+        // FIXME: panics?
+        let mut store = self.inner.lock().unwrap();
+        // NOTE: the time is taken while holding the lock, so that the
+        //      entries are also sorted by when they were received and
+        //      pruning them from the front drops all the expired ones.
+        let now = Instant::now();
+        store.entries.push_back((now, smsg));
+
+        let dropped = store.prune(now);
+        if dropped > 0 {
+            trace!("DeadLetters: Dropped {} dead letters.", dropped);
+            self.dropped.fetch_add(dropped, Ordering::Relaxed);
+        }
+    }
+
+    /// Removes and returns the dead letters kept, in the order they
+    /// were received.
+    pub(crate) fn drain(&self) -> Vec<SignedMessage> {
+        // FIXME: panics?
+        let mut store = self.inner.lock().unwrap();
+        let dropped = store.prune(Instant::now());
+        self.dropped.fetch_add(dropped, Ordering::Relaxed);
+
+        store.entries.drain(..).map(|(_, smsg)| smsg).collect()
+    }
+
+    /// Returns the amount of dead letters currently kept.
+    pub(crate) fn len(&self) -> usize {
+        // FIXME: panics?
+        self.inner.lock().unwrap().entries.len()
+    }
+
+    /// Returns the amount of dead letters dropped since the system
+    /// started.
+    pub(crate) fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl DeadLetterHandlers {
+    /// Routes the dead letters of the subtree of the supervisor with
+    /// the given identifier to the given children group.
+    pub(crate) fn register(&self, id: &BastionId, handler: ChildrenRef) {
+        // FIXME: panics?
+        let mut handlers = self.handlers.write().unwrap();
+        handlers.insert(id.clone(), handler);
+    }
+
+    /// Stops routing the dead letters of the subtree of the supervisor
+    /// with the given identifier to its handler.
+    pub(crate) fn unregister(&self, id: &BastionId) {
+        // FIXME: panics?
+        let mut handlers = self.handlers.write().unwrap();
+        handlers.remove(id);
+    }
+
+    /// Returns the handler of the closest supervisor of the element
+    /// at the given path which was configured with one, if any.
+    pub(crate) fn handler_of(&self, path: &BastionPath) -> Option<ChildrenRef> {
+        // FIXME: panics?
+        let handlers = self.handlers.read().unwrap();
+        if handlers.is_empty() {
+            return None;
+        }
+
+        let ids = path.iter().collect::<Vec<_>>();
+        ids.into_iter()
+            .rev()
+            .find_map(|id| handlers.get(id))
+            .cloned()
+    }
+}
+
+impl Store {
+    // Drops the dead letters which are too old or don't fit, returning
+    // how many were dropped.
+    fn prune(&mut self, now: Instant) -> u64 {
+        let mut dropped = 0;
+        while let Some((received, _)) = self.entries.front() {
+            let expired = now.duration_since(*received) >= self.max_age;
+            if !expired && self.entries.len() <= self.max_entries {
+                break;
+            }
+
+            self.entries.pop_front();
+            dropped += 1;
+        }
+
+        dropped
+    }
+}
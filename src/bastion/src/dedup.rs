@@ -0,0 +1,121 @@
+//!
+//! Deduplication of the payloads received from the members of a
+//! cluster, which can be delivered more than once by the network.
+//!
+//! Every payload sent with [`DistributedContext::tell`] is framed
+//! with a unique message id, and the receiver drops the payloads
+//! whose id is among the ones it received recently.
+//!
+//! [`DistributedContext::tell`]: crate::distributed::DistributedContext::tell
+use crate::compression;
+use fxhash::FxHashMap;
+use std::collections::VecDeque;
+use uuid::Uuid;
+
+/// The codec tag of the payloads framed with their message id.
+const MESSAGE_ID_TAG: &str = "id";
+
+/// The default amount of message ids remembered by a member.
+pub(crate) const DEFAULT_CAPACITY: usize = 4096;
+
+#[derive(Debug)]
+/// The ids of the messages received recently, forgetting the least
+/// recently received ones once `capacity` ids are remembered.
+pub(crate) struct RecentIds {
+    capacity: usize,
+    // The last time every remembered id was received at.
+    seen: FxHashMap<Uuid, u64>,
+    // The ids in the order they were received in, with the time they
+    // were received at, which might be outdated if they were
+    // received again since.
+    order: VecDeque<(Uuid, u64)>,
+    clock: u64,
+}
+
+/// Frames the payload with the given message id.
+pub(crate) fn framed(id: Uuid, payload: &str) -> String {
+    compression::tagged(MESSAGE_ID_TAG, &format!("{}:{}", id, payload))
+}
+
+/// Returns the message id the payload was framed with, if any, and
+/// the payload itself.
+pub(crate) fn unframed(payload: String) -> (Option<Uuid>, String) {
+    let frame = compression::untagged(MESSAGE_ID_TAG, &payload)
+        .and_then(|frame| frame.split_once(':'))
+        .and_then(|(id, body)| Some((Uuid::parse_str(id).ok()?, body.to_string())));
+
+    match frame {
+        Some((id, body)) => (Some(id), body),
+        // NOTE: payloads sent by members which don't frame them are
+        //      never deduplicated.
+        None => (None, payload),
+    }
+}
+
+impl RecentIds {
+    pub(crate) fn new(capacity: usize) -> Self {
+        RecentIds {
+            capacity: capacity.max(1),
+            seen: FxHashMap::default(),
+            order: VecDeque::new(),
+            clock: 0,
+        }
+    }
+
+    /// Remembers the id, returning whether it wasn't already.
+    pub(crate) fn insert(&mut self, id: Uuid) -> bool {
+        self.clock += 1;
+        let fresh = self.seen.insert(id, self.clock).is_none();
+        self.order.push_back((id, self.clock));
+
+        while self.seen.len() > self.capacity {
+            if let Some((id, received)) = self.order.pop_front() {
+                if self.seen.get(&id) == Some(&received) {
+                    self.seen.remove(&id);
+                }
+            }
+        }
+
+        // Drops the outdated entries left by ids received again.
+        if self.order.len() > 2 * self.capacity {
+            let seen = &self.seen;
+            self.order
+                .retain(|(id, received)| seen.get(id) == Some(received));
+        }
+
+        fresh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_round_trip() {
+        let id = Uuid::new_v4();
+        let payload = "a payload: with colons";
+
+        assert_eq!(
+            unframed(framed(id, payload)),
+            (Some(id), payload.to_string())
+        );
+        assert_eq!(unframed(payload.to_string()), (None, payload.to_string()));
+    }
+
+    #[test]
+    fn least_recently_received_ids_are_forgotten() {
+        let mut recent = RecentIds::new(2);
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        assert!(recent.insert(a));
+        assert!(recent.insert(b));
+        assert!(!recent.insert(a));
+
+        // `b` is the least recently received id.
+        assert!(recent.insert(c));
+        assert!(!recent.insert(a));
+        assert!(!recent.insert(c));
+        assert!(recent.insert(b));
+    }
+}
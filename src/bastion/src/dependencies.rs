@@ -0,0 +1,185 @@
+//!
+//! The dependencies declared between supervisors with
+//! [`SupervisorRef::depends_on`], which are checked for cycles
+//! before the system starts (see [`Bastion::try_start`]).
+//!
+//! [`SupervisorRef::depends_on`]: crate::supervisor::SupervisorRef::depends_on
+//! [`Bastion::try_start`]: crate::Bastion::try_start
+use crate::context::BastionId;
+use crate::path::BastionPath;
+use fxhash::FxHashMap;
+use once_cell::sync::Lazy;
+use std::sync::{Arc, Mutex};
+
+pub(crate) static DEPENDENCIES: Lazy<Dependencies> = Lazy::new(Dependencies::default);
+
+#[derive(Debug, Default)]
+pub(crate) struct Dependencies {
+    graph: Mutex<Graph>,
+}
+
+#[derive(Debug, Default)]
+struct Graph {
+    // The path of every supervisor with dependencies or dependents.
+    paths: FxHashMap<BastionId, Arc<BastionPath>>,
+    // The supervisors every supervisor depends on, in the order the
+    // dependencies were declared in.
+    edges: FxHashMap<BastionId, Vec<BastionId>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Visit {
+    InProgress,
+    Done,
+}
+
+impl Dependencies {
+    /// Declares that the supervisor with the `dependent` path depends
+    /// on the one with the `dependency` path.
+    pub(crate) fn add(&self, dependent: &Arc<BastionPath>, dependency: &Arc<BastionPath>) {
+        // FIXME: panics?
+        let mut graph = self.graph.lock().unwrap();
+        graph
+            .paths
+            .insert(dependent.id().clone(), dependent.clone());
+        graph
+            .paths
+            .insert(dependency.id().clone(), dependency.clone());
+
+        let edges = graph.edges.entry(dependent.id().clone()).or_default();
+        if !edges.contains(dependency.id()) {
+            edges.push(dependency.id().clone());
+        }
+    }
+
+    /// Forgets the dependencies of the supervisor with the given
+    /// identifier, and the ones on it.
+    pub(crate) fn unregister(&self, id: &BastionId) {
+        // FIXME: panics?
+        let mut graph = self.graph.lock().unwrap();
+        graph.paths.remove(id);
+        graph.edges.remove(id);
+        for edges in graph.edges.values_mut() {
+            edges.retain(|dependency| dependency != id);
+        }
+    }
+
+    /// Returns the paths of the supervisors forming a cycle, each of
+    /// them depending on the next one and the last one on the first
+    /// one, if any.
+    ///
+    /// On top of the declared dependencies, a supervisor depends on
+    /// its ancestors, which outlive it.
+    pub(crate) fn find_cycle(&self) -> Option<Vec<Arc<BastionPath>>> {
+        // FIXME: panics?
+        let graph = self.graph.lock().unwrap();
+        let mut visits = FxHashMap::default();
+        let mut stack = Vec::new();
+
+        let mut ids = graph.paths.keys().collect::<Vec<_>>();
+        // NOTE: the cycle reported doesn't depend on the hashing.
+        ids.sort_by_key(|id| graph.paths[*id].to_string());
+        for id in ids {
+            if let Some(cycle) = graph.visit(id, &mut visits, &mut stack) {
+                return Some(
+                    cycle
+                        .into_iter()
+                        .map(|id| graph.paths[id].clone())
+                        .collect(),
+                );
+            }
+        }
+
+        None
+    }
+}
+
+impl Graph {
+    // Returns the supervisors the given one depends on.
+    fn dependencies<'a>(&'a self, id: &'a BastionId) -> impl Iterator<Item = &'a BastionId> {
+        let declared = self.edges.get(id).into_iter().flatten();
+        let ancestors = self.paths[id]
+            .iter()
+            .filter(move |ancestor| *ancestor != id && self.paths.contains_key(*ancestor));
+
+        declared.chain(ancestors)
+    }
+
+    // Visits the supervisors the given one depends on, depth first,
+    // returning a cycle as soon as one is found.
+    fn visit<'a>(
+        &'a self,
+        id: &'a BastionId,
+        visits: &mut FxHashMap<&'a BastionId, Visit>,
+        stack: &mut Vec<&'a BastionId>,
+    ) -> Option<Vec<&'a BastionId>> {
+        match visits.get(id) {
+            Some(Visit::Done) => return None,
+            Some(Visit::InProgress) => {
+                let start = stack.iter().position(|visited| *visited == id)?;
+                return Some(stack[start..].to_vec());
+            }
+            None => (),
+        }
+
+        visits.insert(id, Visit::InProgress);
+        stack.push(id);
+        for dependency in self.dependencies(id) {
+            if let Some(cycle) = self.visit(dependency, visits, stack) {
+                return Some(cycle);
+            }
+        }
+        stack.pop();
+        visits.insert(id, Visit::Done);
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::BastionPathElement;
+
+    fn supervisor(parent: &BastionPath) -> Arc<BastionPath> {
+        let elem = BastionPathElement::Supervisor(BastionId::new());
+        Arc::new(parent.clone().append(elem).unwrap())
+    }
+
+    #[test]
+    fn declared_cycles_are_found() {
+        let dependencies = Dependencies::default();
+        let first = supervisor(&BastionPath::root());
+        let second = supervisor(&BastionPath::root());
+        let third = supervisor(&BastionPath::root());
+
+        dependencies.add(&first, &second);
+        dependencies.add(&second, &third);
+        assert!(dependencies.find_cycle().is_none());
+
+        dependencies.add(&third, &first);
+        let mut cycle = dependencies.find_cycle().expect("The cycle wasn't found.");
+        cycle.sort_by_key(|path| path.to_string());
+        let mut expected = vec![first, second, third.clone()];
+        expected.sort_by_key(|path| path.to_string());
+        assert_eq!(cycle, expected);
+
+        dependencies.unregister(third.id());
+        assert!(dependencies.find_cycle().is_none());
+    }
+
+    #[test]
+    fn ancestors_are_depended_on() {
+        let dependencies = Dependencies::default();
+        let parent = supervisor(&BastionPath::root());
+        let child = supervisor(&parent);
+
+        dependencies.add(&child, &parent);
+        assert!(dependencies.find_cycle().is_none());
+
+        dependencies.add(&parent, &child);
+        let cycle = dependencies.find_cycle().expect("The cycle wasn't found.");
+        assert_eq!(cycle.len(), 2);
+        assert!(cycle.contains(&parent) && cycle.contains(&child));
+    }
+}
@@ -2,23 +2,34 @@
 //! Special module that allows users to interact and communicate with a
 //! group of actors through the dispatchers that holds information about
 //! actors grouped together.
+use crate::executor::{spawner, ProcStack, Spawner};
 use crate::{
     child_ref::ChildRef,
-    message::{Answer, Message},
+    context::BastionId,
+    envelope::Envelope,
+    message::{Answer, BastionMessage, Message},
+    path::BastionPath,
     prelude::SendError,
 };
 use crate::{distributor::Distributor, envelope::SignedMessage};
+use crate::{
+    rate_limit::{OverloadPolicy, TokenBucket},
+    system::SYSTEM,
+};
 use anyhow::Result as AnyResult;
+use futures_timer::Delay;
 use lever::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, VecDeque};
 use std::hash::{Hash, Hasher};
-use std::sync::RwLock;
-use std::sync::{
-    atomic::{AtomicUsize, Ordering},
-    Arc,
-};
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 use std::{
     collections::HashMap,
     fmt::{self, Debug},
+    mem, thread,
 };
 use tracing::{debug, trace};
 
@@ -53,6 +64,47 @@ pub enum BroadcastTarget {
     All,
     /// Send the broadcasted message to each actor in group.
     Group(String),
+    /// Send the broadcasted message to the actors of the groups
+    /// attached to an anonymous dispatcher.
+    Anonymous,
+    /// Send the broadcasted message once to every actor of each
+    /// listed group, even if it belongs to several of them.
+    Groups(Vec<String>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Defines what happens to the messages broadcasted to a group
+/// which currently has no public actor, for example because all of
+/// them are being restarted.
+///
+/// The default policy is `Drop`.
+pub enum EmptyGroupPolicy {
+    /// The messages are dropped.
+    Drop,
+    /// The messages are sent to the dead letters right away.
+    DeadLetter,
+    /// The messages are held until an actor joins the group, and
+    /// sent to the dead letters if none joined once the given
+    /// timeout elapsed.
+    Buffer(Duration),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Defines in which order the actors receiving a broadcasted message
+/// are gone through, when it is sent to each of them (see
+/// [`BroadcastTarget::Groups`]).
+///
+/// The default order is `Unordered`.
+pub enum BroadcastOrder {
+    /// The actors are gone through in whichever order their groups
+    /// hold them, which is the fastest.
+    Unordered,
+    /// The actors are gone through sorted by their path.
+    Stable,
+    /// The actors are gone through sorted by their path, starting
+    /// one actor further at each broadcast so that every actor is
+    /// the first one to receive a message in turn.
+    Rotated,
 }
 
 /// A `Recipient` is responsible for maintaining it's list
@@ -97,15 +149,21 @@ pub enum DispatcherType {
 pub type DefaultDispatcherHandler = RoundRobinHandler;
 
 /// Dispatcher that will do simple round-robin distribution
+///
+/// The children are served in turn in a stable order, starting after
+/// the last served one, so that a child joining or leaving the group
+/// doesn't make the rotation skip or serve twice any other child.
 #[derive(Default, Debug)]
 pub struct RoundRobinHandler {
-    index: AtomicUsize,
+    // The identifier of the last served child, if any.
+    last: Mutex<Option<BastionId>>,
     recipients: RecipientMap,
 }
 
 impl RoundRobinHandler {
     fn public_recipients(&self) -> Vec<ChildRef> {
-        self.recipients
+        let recipients = self
+            .recipients
             .iter()
             .filter_map(|entry| {
                 if entry.0.is_public() {
@@ -114,7 +172,28 @@ impl RoundRobinHandler {
                     None
                 }
             })
-            .collect()
+            .collect();
+
+        Self::rotation(recipients)
+    }
+
+    // Sorts the children in the order they are served in.
+    fn rotation(mut childrefs: Vec<ChildRef>) -> Vec<ChildRef> {
+        childrefs.sort_by_key(|child_ref| child_ref.id().0);
+        childrefs
+    }
+
+    // Returns the index of the first child served after the child
+    // with the given identifier, whether it is still in the rotation
+    // or not.
+    fn next_index(childrefs: &[ChildRef], last: &Option<BastionId>) -> usize {
+        match last {
+            Some(last) => childrefs
+                .iter()
+                .position(|child_ref| child_ref.id().0 > last.0)
+                .unwrap_or(0),
+            None => 0,
+        }
     }
 }
 
@@ -126,9 +205,11 @@ impl Recipient for RoundRobinHandler {
             return None;
         }
 
-        let current_index = self.index.load(Ordering::SeqCst) % entries.len();
-        self.index.store(current_index + 1, Ordering::SeqCst);
-        entries.get(current_index).map(std::clone::Clone::clone)
+        // FIXME: panics?
+        let mut last = self.last.lock().unwrap();
+        let next = entries[Self::next_index(&entries, &last)].clone();
+        *last = Some(next.id().clone());
+        Some(next)
     }
 
     fn all(&self) -> Vec<ChildRef> {
@@ -155,35 +236,325 @@ impl DispatcherHandler for RoundRobinHandler {
     }
     // Each child in turn will receive a message.
     fn broadcast_message(&self, entries: &DispatcherMap, message: &Arc<SignedMessage>) {
-        let public_childrefs = entries
-            .iter()
-            .filter_map(|entry| {
-                if entry.0.is_public() {
-                    Some(entry.0)
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>();
+        let public_childrefs = Self::rotation(
+            entries
+                .iter()
+                .filter_map(|entry| {
+                    if entry.0.is_public() {
+                        Some(entry.0)
+                    } else {
+                        None
+                    }
+                })
+                .collect(),
+        );
 
         if public_childrefs.is_empty() {
             debug!("no public children to broadcast message to");
             return;
         }
-        let current_index = self.index.load(Ordering::SeqCst) % public_childrefs.len();
+        // FIXME: panics?
+        let mut last = self.last.lock().unwrap();
+        let current_index = Self::next_index(&public_childrefs, &last);
 
-        if let Some(entry) = public_childrefs.get(current_index) {
-            debug!(
+        if let Some(index) = Self::deliver(&public_childrefs, current_index, message) {
+            *last = Some(public_childrefs[index].id().clone());
+        }
+    }
+    // Each child in turn will receive a message, the share of the
+    // batch of every child being sent to it as a single envelope.
+    fn broadcast_batch(&self, entries: &DispatcherMap, messages: &[Arc<SignedMessage>]) {
+        let public_childrefs = Self::rotation(
+            entries
+                .iter()
+                .filter_map(|entry| {
+                    if entry.0.is_public() {
+                        Some(entry.0)
+                    } else {
+                        None
+                    }
+                })
+                .collect(),
+        );
+
+        if public_childrefs.is_empty() {
+            debug!("no public children to broadcast messages to");
+            return;
+        }
+        // FIXME: panics?
+        let mut last = self.last.lock().unwrap();
+        let current_index = Self::next_index(&public_childrefs, &last);
+
+        let mut shares = vec![Vec::new(); public_childrefs.len()];
+        for (offset, message) in messages.iter().enumerate() {
+            let index = (current_index + offset) % public_childrefs.len();
+            shares[index].push(message.clone());
+        }
+
+        // NOTE: the share holding the last message is sent last, so that
+        //      `last` is the child which received it.
+        for offset in 1..=public_childrefs.len() {
+            let index = (current_index + messages.len() + offset - 1) % public_childrefs.len();
+            let share = mem::take(&mut shares[index]);
+            if share.is_empty() {
+                continue;
+            }
+
+            if let Some(index) = Self::deliver_batch(&public_childrefs, index, share) {
+                *last = Some(public_childrefs[index].id().clone());
+            }
+        }
+    }
+}
+
+impl RoundRobinHandler {
+    // Sends the message to the first child, starting at `index`, which is
+    // still alive and returns its index.
+    fn deliver(
+        public_childrefs: &[ChildRef],
+        index: usize,
+        message: &Arc<SignedMessage>,
+    ) -> Option<usize> {
+        for offset in 0..public_childrefs.len() {
+            let index = (index + offset) % public_childrefs.len();
+            let entry = &public_childrefs[index];
+            trace!(
                 "sending message to child {}/{} - {}",
-                current_index + 1,
-                entries.len(),
+                index + 1,
+                public_childrefs.len(),
                 entry.path()
             );
-            entry.tell_anonymously(message.clone()).unwrap();
-            self.index.store(current_index + 1, Ordering::SeqCst);
+            if entry.forward(message).is_ok() {
+                return Some(index);
+            }
+
+            debug!("child {} is dead, skipping it", entry.path());
+        }
+
+        debug!("no live children to broadcast message to");
+        None
+    }
+
+    // Sends a share of a batch to the first child, starting at `index`,
+    // which is still alive and returns its index.
+    fn deliver_batch(
+        public_childrefs: &[ChildRef],
+        index: usize,
+        mut share: Vec<Arc<SignedMessage>>,
+    ) -> Option<usize> {
+        for offset in 0..public_childrefs.len() {
+            let index = (index + offset) % public_childrefs.len();
+            let entry = &public_childrefs[index];
+            trace!(
+                "sending {} messages to child {}/{} - {}",
+                share.len(),
+                index + 1,
+                public_childrefs.len(),
+                entry.path()
+            );
+            share = match entry.forward_batch(share) {
+                Ok(()) => return Some(index),
+                Err(share) => share,
+            };
+
+            debug!("child {} is dead, skipping it", entry.path());
+        }
+
+        debug!("no live children to broadcast messages to");
+        None
+    }
+}
+/// The default amount of virtual nodes every child is placed at on the
+/// ring of a [`ConsistentHashHandler`].
+pub const DEFAULT_VIRTUAL_NODES: usize = 100;
+
+/// The default amount of messages a rate limited [`Dispatcher`]
+/// queues before applying its [`OverloadPolicy`].
+pub const DEFAULT_RATE_LIMIT_BACKLOG: usize = 1024;
+
+/// The function extracting the key messages are dispatched by, hashed.
+type HashKey = Box<dyn Fn(&SignedMessage) -> Option<u64> + Send + Sync + 'static>;
+
+/// Dispatcher that sends every message to the child owning its key on
+/// a consistent-hash ring, so that the messages with the same key are
+/// handled by the same child, and that only the keys of a child
+/// joining or leaving the group move to another child.
+///
+/// Every child is placed at several points of the ring (its virtual
+/// nodes, see [`with_virtual_nodes`]), and owns the keys hashed
+/// between the points of the children preceding them and its own.
+///
+/// The messages for which the key function returns `None` are sent to
+/// the child owning the start of the ring.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// let handler = ConsistentHashHandler::new(|msg: &SignedMessage| {
+///     msg.peek::<&'static str>().copied()
+/// })
+/// .with_virtual_nodes(200);
+///
+/// let dispatcher = Dispatcher::with_type(DispatcherType::Named("sessions".to_string()))
+///     .with_handler(Box::new(handler));
+/// ```
+///
+/// [`with_virtual_nodes`]: Self::with_virtual_nodes
+pub struct ConsistentHashHandler {
+    key: HashKey,
+    virtual_nodes: usize,
+    // The children at every point of the ring.
+    ring: RwLock<BTreeMap<u64, ChildRef>>,
+}
+
+impl ConsistentHashHandler {
+    /// Creates a handler dispatching the messages by the key `key`
+    /// returns for them, placing every child at
+    /// [`DEFAULT_VIRTUAL_NODES`] points of the ring.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The function returning the key of a message.
+    pub fn new<K, F>(key: F) -> Self
+    where
+        K: Hash,
+        F: Fn(&SignedMessage) -> Option<K> + Send + Sync + 'static,
+    {
+        let key = Box::new(move |msg: &SignedMessage| key(msg).map(|key| Self::hash(&key)));
+        ConsistentHashHandler {
+            key,
+            virtual_nodes: DEFAULT_VIRTUAL_NODES,
+            ring: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// Sets at how many points of the ring every child is placed.
+    ///
+    /// More virtual nodes spread the keys more evenly between the
+    /// children, at the cost of a larger ring to build and search.
+    ///
+    /// # Arguments
+    ///
+    /// * `virtual_nodes` - The amount of points of every child, which
+    ///     is at least one.
+    pub fn with_virtual_nodes(mut self, virtual_nodes: usize) -> Self {
+        trace!(
+            "Placing every child at {} points of the ring.",
+            virtual_nodes
+        );
+        self.virtual_nodes = virtual_nodes.max(1);
+        self
+    }
+
+    /// Returns at how many points of the ring every child is placed.
+    pub fn virtual_nodes(&self) -> usize {
+        self.virtual_nodes
+    }
+
+    fn hash<T: Hash>(value: &T) -> u64 {
+        // NOTE: `DefaultHasher::new` always uses the same keys, so the
+        //      keys are owned by the same children between runs.
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    // Places the public children at their points of the ring.
+    fn rebuild(&self, entries: &DispatcherMap) {
+        let mut ring = BTreeMap::new();
+        for (child_ref, _) in entries.iter() {
+            if !child_ref.is_public() {
+                continue;
+            }
+
+            for node in 0..self.virtual_nodes {
+                ring.insert(Self::hash(&(child_ref.id(), node)), child_ref.clone());
+            }
+        }
+
+        // FIXME: panics?
+        *self.ring.write().unwrap() = ring;
+    }
+
+    // Returns the child owning the given hash, if any.
+    fn owner(&self, hash: u64) -> Option<ChildRef> {
+        // FIXME: panics?
+        let ring = self.ring.read().unwrap();
+        ring.range(hash..)
+            .next()
+            .or_else(|| ring.iter().next())
+            .map(|(_, child_ref)| child_ref.clone())
+    }
+}
+
+impl DispatcherHandler for ConsistentHashHandler {
+    // The ring is rebuilt every time a child joins or leaves the group.
+    fn notify(
+        &self,
+        _from_child: &ChildRef,
+        entries: &DispatcherMap,
+        _notification_type: NotificationType,
+    ) {
+        self.rebuild(entries);
+    }
+    // The child owning the message's key receives it.
+    fn broadcast_message(&self, _entries: &DispatcherMap, message: &Arc<SignedMessage>) {
+        let hash = (self.key)(message).unwrap_or(0);
+        let owner = match self.owner(hash) {
+            Some(owner) => owner,
+            None => {
+                debug!("no public children to broadcast message to");
+                return;
+            }
         };
+
+        trace!("sending message to child {}", owner.path());
+        if owner.forward(message).is_err() {
+            debug!("child {} is dead, dropping the message", owner.path());
+        }
+    }
+    // The child owning the keys of messages receives them, as a single
+    // envelope for the whole batch.
+    fn broadcast_batch(&self, _entries: &DispatcherMap, messages: &[Arc<SignedMessage>]) {
+        let mut shares: Vec<(ChildRef, Vec<Arc<SignedMessage>>)> = Vec::new();
+        for message in messages {
+            let hash = (self.key)(message).unwrap_or(0);
+            let owner = match self.owner(hash) {
+                Some(owner) => owner,
+                None => {
+                    debug!("no public children to broadcast messages to");
+                    return;
+                }
+            };
+
+            match shares
+                .iter_mut()
+                .find(|(child, _)| child.id() == owner.id())
+            {
+                Some((_, share)) => share.push(message.clone()),
+                None => shares.push((owner, vec![message.clone()])),
+            }
+        }
+
+        for (owner, share) in shares {
+            trace!("sending {} messages to child {}", share.len(), owner.path());
+            if owner.forward_batch(share).is_err() {
+                debug!("child {} is dead, dropping the messages", owner.path());
+            }
+        }
+    }
+}
+
+impl Debug for ConsistentHashHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConsistentHashHandler")
+            .field("virtual_nodes", &self.virtual_nodes)
+            .finish()
     }
 }
+
 /// Generic trait which any custom dispatcher handler must implement for
 /// the further usage by the `Dispatcher` instances.
 pub trait DispatcherHandler {
@@ -196,6 +567,17 @@ pub trait DispatcherHandler {
     );
     /// Broadcasts the message to actors in according to the implemented behaviour.
     fn broadcast_message(&self, entries: &DispatcherMap, message: &Arc<SignedMessage>);
+    /// Broadcasts the messages to actors in according to the implemented behaviour,
+    /// in the given order.
+    ///
+    /// The default implementation broadcasts each message in turn, sending an envelope per
+    /// message. Handlers can override it to send the share of the batch of every actor
+    /// as a single envelope.
+    fn broadcast_batch(&self, entries: &DispatcherMap, messages: &[Arc<SignedMessage>]) {
+        for message in messages {
+            self.broadcast_message(entries, message);
+        }
+    }
 }
 
 /// A generic implementation of the Bastion dispatcher
@@ -213,6 +595,40 @@ pub struct Dispatcher {
     /// Special field that stores information about all
     /// registered actors in the group.
     actors: DispatcherMap,
+    /// The token bucket throttling the messages sent to the group,
+    /// if any.
+    rate_limit: Option<Mutex<RateLimit>>,
+    /// What happens to the messages sent while the group is empty.
+    empty_group: EmptyGroupPolicy,
+    /// The messages held until an actor joins the group, with the
+    /// instant they are dead-lettered at.
+    buffered: Mutex<VecDeque<(Instant, Arc<SignedMessage>)>>,
+    /// The window within which identical messages are collapsed into
+    /// a single delivery, if any.
+    coalesce: Option<Coalesce>,
+}
+
+/// The function extracting the key messages are coalesced by, hashed.
+type CoalesceKey = Box<dyn Fn(&SignedMessage) -> Option<u64> + Send + Sync + 'static>;
+
+struct Coalesce {
+    window: Duration,
+    key: CoalesceKey,
+    // The keys of the messages delivered within the window, with when
+    // they were delivered.
+    delivered: Mutex<HashMap<u64, Instant>>,
+}
+
+#[derive(Debug)]
+struct RateLimit {
+    bucket: TokenBucket,
+    // The messages waiting for a token, in the order they were sent,
+    // which are delivered by a task while it isn't empty.
+    backlog: VecDeque<Arc<SignedMessage>>,
+    // How many messages the backlog holds at most, and what happens
+    // to the messages sent while it is full.
+    capacity: usize,
+    overload: OverloadPolicy,
 }
 
 impl Dispatcher {
@@ -243,9 +659,138 @@ impl Dispatcher {
             dispatcher_type,
             handler: Box::new(DefaultDispatcherHandler::default()),
             actors: Default::default(),
+            rate_limit: None,
+            empty_group: EmptyGroupPolicy::default(),
+            buffered: Mutex::new(VecDeque::new()),
+            coalesce: None,
         }
     }
 
+    /// Caps the rate at which messages are sent to the group to
+    /// `per_second` messages per second, allowing bursts of up to
+    /// `per_second` messages.
+    ///
+    /// The messages exceeding the limit aren't dropped but queued,
+    /// and delivered in order as the limit allows it. Once
+    /// [`DEFAULT_RATE_LIMIT_BACKLOG`] messages are queued, the
+    /// senders wait for the backlog to have room (see
+    /// [`Dispatcher::with_rate_limit_with`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `per_second` - The maximum sustained rate.
+    pub fn with_rate_limit(self, per_second: NonZeroU32) -> Self {
+        self.with_rate_limit_with(
+            per_second,
+            DEFAULT_RATE_LIMIT_BACKLOG,
+            OverloadPolicy::Backpressure,
+        )
+    }
+
+    /// Caps the rate at which messages are sent to the group to
+    /// `per_second` messages per second, allowing bursts of up to
+    /// `per_second` messages, queuing up to `backlog` messages above
+    /// it and handling the other ones as set by `overload`.
+    ///
+    /// With [`OverloadPolicy::Backpressure`], the senders block until
+    /// the backlog has room. With [`OverloadPolicy::Shed`], the
+    /// messages are dropped and kept with the dead letters.
+    ///
+    /// # Arguments
+    ///
+    /// * `per_second` - The maximum sustained rate.
+    /// * `backlog` - How many messages exceeding the limit are queued
+    ///     at most.
+    /// * `overload` - Whether the messages sent while the backlog is
+    ///     full wait for it or are dropped.
+    pub fn with_rate_limit_with(
+        mut self,
+        per_second: NonZeroU32,
+        backlog: usize,
+        overload: OverloadPolicy,
+    ) -> Self {
+        trace!(
+            "Limiting the {:?} dispatcher to {} messages per second, with a backlog of {} messages and the {:?} policy.",
+            self.dispatcher_type,
+            per_second,
+            backlog,
+            overload
+        );
+        self.rate_limit = Some(Mutex::new(RateLimit {
+            bucket: TokenBucket::new(per_second.get(), per_second.get()),
+            backlog: VecDeque::new(),
+            capacity: backlog,
+            overload,
+        }));
+        self
+    }
+
+    /// Collapses the identical messages sent to the group within
+    /// `window` of each other into a single delivery.
+    ///
+    /// Two messages are identical if `key` returns the same key for
+    /// both of them. The first one is delivered, and the following
+    /// ones are dropped until `window` elapsed since it was sent.
+    /// The messages for which `key` returns `None` are never
+    /// collapsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - How long the messages identical to a delivered
+    ///     one are dropped for.
+    /// * `key` - The function returning the key of a message.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// let dispatcher = Dispatcher::with_type(DispatcherType::Named("config".to_string()))
+    ///     .with_coalesce(Duration::from_millis(500), |msg: &SignedMessage| {
+    ///         msg.peek::<&'static str>().copied()
+    ///     });
+    /// ```
+    pub fn with_coalesce<K, F>(mut self, window: Duration, key: F) -> Self
+    where
+        K: Hash,
+        F: Fn(&SignedMessage) -> Option<K> + Send + Sync + 'static,
+    {
+        trace!(
+            "Coalescing the messages sent to the {:?} dispatcher within {:?}.",
+            self.dispatcher_type,
+            window
+        );
+        let key = Box::new(move |msg: &SignedMessage| {
+            let mut hasher = fxhash::FxHasher::default();
+            key(msg)?.hash(&mut hasher);
+            Some(hasher.finish())
+        });
+        self.coalesce = Some(Coalesce {
+            window,
+            key,
+            delivered: Mutex::new(HashMap::new()),
+        });
+        self
+    }
+
+    /// Sets what happens to the messages sent to the group while it
+    /// has no public actor (see [`EmptyGroupPolicy`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `empty_group` - The policy applied to the messages sent
+    ///     while the group is empty.
+    pub fn with_empty_group_policy(mut self, empty_group: EmptyGroupPolicy) -> Self {
+        trace!(
+            "Setting the {:?} dispatcher's empty group policy to {:?}.",
+            self.dispatcher_type,
+            empty_group
+        );
+        self.empty_group = empty_group;
+        self
+    }
+
     /// Sets the handler for the dispatcher.
     pub fn with_handler(
         mut self,
@@ -264,6 +809,7 @@ impl Dispatcher {
         self.actors.insert(key.to_owned(), module_name)?;
         self.handler
             .notify(key, &self.actors, NotificationType::Register);
+        self.deliver_buffered();
         Ok(())
     }
 
@@ -276,6 +822,19 @@ impl Dispatcher {
         }
     }
 
+    /// Removes the actor with the given identifier, if it was registered.
+    pub(crate) fn remove_id(&self, id: &BastionId) {
+        let child_ref = self
+            .actors
+            .iter()
+            .map(|entry| entry.0)
+            .find(|child_ref| child_ref.id() == id);
+
+        if let Some(child_ref) = child_ref {
+            self.remove(&child_ref);
+        }
+    }
+
     /// Forwards the message to the handler for processing.
     pub fn notify(&self, from_child: &ChildRef, notification_type: NotificationType) {
         self.handler
@@ -286,7 +845,298 @@ impl Dispatcher {
     /// The logic of who and how should receive the message relies onto
     /// the handler implementation.
     pub fn broadcast_message(&self, message: &Arc<SignedMessage>) {
-        self.handler.broadcast_message(&self.actors, &message);
+        if self.coalesces(message) {
+            trace!(
+                "Dispatcher({:?}): Dropping a message identical to a recent one.",
+                self.dispatcher_type
+            );
+            return;
+        }
+
+        if self.holds_back() {
+            return self.handle_empty_group(message);
+        }
+
+        self.deliver(message);
+    }
+
+    // Returns whether the message is identical to one delivered
+    // within the coalescing window, recording it otherwise.
+    fn coalesces(&self, message: &SignedMessage) -> bool {
+        let coalesce = match &self.coalesce {
+            Some(coalesce) => coalesce,
+            None => return false,
+        };
+        let key = match (coalesce.key)(message) {
+            Some(key) => key,
+            None => return false,
+        };
+
+        let now = Instant::now();
+        // FIXME: panics?
+        let mut delivered = coalesce.delivered.lock().unwrap();
+        delivered.retain(|_, sent_at| now.duration_since(*sent_at) < coalesce.window);
+        if delivered.contains_key(&key) {
+            return true;
+        }
+
+        delivered.insert(key, now);
+        false
+    }
+
+    // Returns whether the messages sent to the group can't be
+    // delivered right away because of the empty group policy.
+    fn holds_back(&self) -> bool {
+        if self.empty_group == EmptyGroupPolicy::Drop {
+            return false;
+        }
+
+        // FIXME: panics?
+        !self.buffered.lock().unwrap().is_empty() || self.members().is_empty()
+    }
+
+    // Dead-letters or buffers the message, depending on the empty
+    // group policy.
+    fn handle_empty_group(&self, message: &Arc<SignedMessage>) {
+        match self.empty_group {
+            EmptyGroupPolicy::Drop => self.deliver(message),
+            EmptyGroupPolicy::DeadLetter => {
+                debug!(
+                    "Dispatcher({:?}): Dead-lettering the message: the group is empty.",
+                    self.dispatcher_type
+                );
+                GlobalDispatcher::dead_letter(message);
+            }
+            EmptyGroupPolicy::Buffer(timeout) => {
+                trace!(
+                    "Dispatcher({:?}): The group is empty, buffering the message.",
+                    self.dispatcher_type
+                );
+                {
+                    // FIXME: panics?
+                    let mut buffered = self.buffered.lock().unwrap();
+                    if buffered.is_empty() {
+                        self.expire_after(timeout);
+                    }
+                    buffered.push_back((Instant::now() + timeout, message.clone()));
+                }
+
+                // NOTE: an actor might have joined the group meanwhile.
+                self.deliver_buffered();
+            }
+        }
+    }
+
+    // Delivers the buffered messages if an actor joined the group,
+    // dead-lettering the ones which expired.
+    fn deliver_buffered(&self) {
+        if self.members().is_empty() {
+            return;
+        }
+
+        // FIXME: panics?
+        let mut buffered = self.buffered.lock().unwrap();
+        if !buffered.is_empty() {
+            debug!(
+                "Dispatcher({:?}): Delivering {} buffered messages.",
+                self.dispatcher_type,
+                buffered.len()
+            );
+        }
+
+        // NOTE: messages are delivered while holding the lock so that
+        //      new messages can't overtake the buffered ones.
+        let now = Instant::now();
+        for (deadline, message) in buffered.drain(..) {
+            if deadline <= now {
+                GlobalDispatcher::dead_letter(&message);
+            } else {
+                self.deliver(&message);
+            }
+        }
+    }
+
+    // Dead-letters the buffered messages of the registered dispatcher
+    // of the same type once they expire.
+    fn expire_after(&self, wait: Duration) {
+        let dispatcher_type = self.dispatcher_type.clone();
+        spawner().spawn(
+            async move {
+                let mut wait = wait;
+                loop {
+                    Delay::new(wait).await;
+                    let dispatcher = match SYSTEM.dispatcher().dispatchers.get(&dispatcher_type) {
+                        Some(dispatcher) => dispatcher,
+                        None => return,
+                    };
+
+                    match dispatcher.expire_buffered() {
+                        Some(next) => wait = next,
+                        None => return,
+                    }
+                }
+            },
+            ProcStack::default(),
+        );
+    }
+
+    // Dead-letters the buffered messages which expired, returning how
+    // long to wait before the next one expires, if any.
+    fn expire_buffered(&self) -> Option<Duration> {
+        let now = Instant::now();
+        // FIXME: panics?
+        let mut buffered = self.buffered.lock().unwrap();
+        while let Some((deadline, _)) = buffered.front() {
+            if *deadline > now {
+                return Some(*deadline - now);
+            }
+
+            debug!(
+                "Dispatcher({:?}): Dead-lettering a buffered message: no actor joined the group in time.",
+                self.dispatcher_type
+            );
+            let (_, message) = buffered.pop_front().unwrap();
+            GlobalDispatcher::dead_letter(&message);
+        }
+
+        None
+    }
+
+    // Sends the message to the group of actors, once the rate limit
+    // allows it.
+    fn deliver(&self, message: &Arc<SignedMessage>) {
+        let rate_limit = match &self.rate_limit {
+            Some(rate_limit) => rate_limit,
+            None => return self.handler.broadcast_message(&self.actors, &message),
+        };
+
+        // FIXME: panics?
+        let mut limit = rate_limit.lock().unwrap();
+        loop {
+            // NOTE: messages are delivered while holding the lock so
+            //      that they can't overtake the backlog.
+            let wait = match self.drain(&mut limit) {
+                Some(wait) => wait,
+                None => match limit.bucket.try_acquire() {
+                    Ok(()) => return self.handler.broadcast_message(&self.actors, &message),
+                    Err(wait) => wait,
+                },
+            };
+
+            if limit.backlog.len() < limit.capacity {
+                trace!(
+                    "Dispatcher({:?}): Rate limited, queuing the message.",
+                    self.dispatcher_type
+                );
+                if limit.backlog.is_empty() {
+                    self.drain_after(wait);
+                }
+
+                limit.backlog.push_back(message.clone());
+                return;
+            }
+
+            match limit.overload {
+                OverloadPolicy::Shed => {
+                    debug!(
+                        "Dispatcher({:?}): Rate limited with a full backlog, shedding the message.",
+                        self.dispatcher_type
+                    );
+                    return GlobalDispatcher::dead_letter(message);
+                }
+                OverloadPolicy::Backpressure => {
+                    trace!(
+                        "Dispatcher({:?}): Rate limited with a full backlog, waiting {:?}.",
+                        self.dispatcher_type,
+                        wait
+                    );
+                    // NOTE: the lock is released while waiting so that
+                    //      the backlog keeps being delivered.
+                    drop(limit);
+                    thread::sleep(wait);
+                    // FIXME: panics?
+                    limit = rate_limit.lock().unwrap();
+                }
+            }
+        }
+    }
+
+    // Delivers the backlog of the registered dispatcher of the same
+    // type as tokens become available.
+    fn drain_after(&self, wait: Duration) {
+        let dispatcher_type = self.dispatcher_type.clone();
+        spawner().spawn(
+            async move {
+                let mut wait = wait;
+                loop {
+                    Delay::new(wait).await;
+                    let dispatcher = match SYSTEM.dispatcher().dispatchers.get(&dispatcher_type) {
+                        Some(dispatcher) => dispatcher,
+                        None => {
+                            debug!(
+                                "Dispatcher({:?}): Dropping the backlog: not registered.",
+                                dispatcher_type
+                            );
+                            return;
+                        }
+                    };
+
+                    match dispatcher.drain_backlog() {
+                        Some(next) => wait = next,
+                        None => return,
+                    }
+                }
+            },
+            ProcStack::default(),
+        );
+    }
+
+    // Delivers as much of the backlog as possible, returning how
+    // long to wait before delivering the rest of it, if any.
+    fn drain_backlog(&self) -> Option<Duration> {
+        // FIXME: panics?
+        let mut rate_limit = self.rate_limit.as_ref()?.lock().unwrap();
+        self.drain(&mut rate_limit)
+    }
+
+    // Delivers as much of the locked backlog as possible, returning
+    // how long to wait before delivering the rest of it, if any.
+    fn drain(&self, rate_limit: &mut RateLimit) -> Option<Duration> {
+        while !rate_limit.backlog.is_empty() {
+            if let Err(wait) = rate_limit.bucket.try_acquire() {
+                return Some(wait);
+            }
+
+            let message = rate_limit.backlog.pop_front().unwrap();
+            self.handler.broadcast_message(&self.actors, &message);
+        }
+
+        None
+    }
+
+    /// Returns the public actors registered in the dispatcher.
+    pub(crate) fn members(&self) -> Vec<ChildRef> {
+        self.actors
+            .iter()
+            .map(|entry| entry.0)
+            .filter(ChildRef::is_public)
+            .collect()
+    }
+
+    /// Sends the messages to the group of actors, in the given order.
+    /// The logic of who and how should receive the messages relies onto
+    /// the handler implementation.
+    ///
+    /// With a rate limit, the messages are throttled one by one, and
+    /// with a coalescing window, they are collapsed one by one.
+    pub fn broadcast_batch(&self, messages: &[Arc<SignedMessage>]) {
+        if self.rate_limit.is_some() || self.coalesce.is_some() || self.holds_back() {
+            for message in messages {
+                self.broadcast_message(message);
+            }
+        } else {
+            self.handler.broadcast_batch(&self.actors, messages);
+        }
     }
 }
 
@@ -301,6 +1151,12 @@ impl Debug for Dispatcher {
     }
 }
 
+impl Debug for Coalesce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Coalesce(window: {:?})", self.window)
+    }
+}
+
 impl DispatcherType {
     pub(crate) fn name(&self) -> String {
         match self {
@@ -308,6 +1164,16 @@ impl DispatcherType {
             DispatcherType::Named(value) => value.to_owned(),
         }
     }
+
+    /// Returns the type of the dispatcher with the given name, as
+    /// returned by [`DispatcherType::name`].
+    pub(crate) fn from_name(name: &str) -> Self {
+        if name == DispatcherType::Anonymous.name() {
+            DispatcherType::Anonymous
+        } else {
+            DispatcherType::Named(name.to_string())
+        }
+    }
 }
 
 impl Default for Dispatcher {
@@ -316,10 +1182,26 @@ impl Default for Dispatcher {
             dispatcher_type: DispatcherType::default(),
             handler: Box::new(DefaultDispatcherHandler::default()),
             actors: LOTable::new(),
+            rate_limit: None,
+            empty_group: EmptyGroupPolicy::default(),
+            buffered: Mutex::new(VecDeque::new()),
+            coalesce: None,
         }
     }
 }
 
+impl Default for EmptyGroupPolicy {
+    fn default() -> Self {
+        EmptyGroupPolicy::Drop
+    }
+}
+
+impl Default for BroadcastOrder {
+    fn default() -> Self {
+        BroadcastOrder::Unordered
+    }
+}
+
 impl Default for DispatcherType {
     fn default() -> Self {
         DispatcherType::Anonymous
@@ -352,6 +1234,13 @@ pub(crate) struct GlobalDispatcher {
     pub dispatchers: LOTable<DispatcherType, Arc<Box<Dispatcher>>>,
     // TODO: switch to LOTable once lever implements write optimized granularity
     pub distributors: Arc<RwLock<HashMap<Distributor, Box<(dyn RecipientHandler)>>>>,
+    /// The subscribers of each topic of the event bus.
+    pub topics: RwLock<HashMap<String, Box<(dyn RecipientHandler)>>>,
+    /// The launched children, by identifier, so that they can be
+    /// resolved from their path.
+    pub children: RwLock<HashMap<BastionId, ChildRef>>,
+    /// How many broadcasts were sent in the `Rotated` order.
+    pub rotation: AtomicUsize,
 }
 
 impl GlobalDispatcher {
@@ -359,12 +1248,15 @@ impl GlobalDispatcher {
     pub(crate) fn new() -> Self {
         GlobalDispatcher {
             dispatchers: LOTable::new(),
-            distributors: Arc::new(RwLock::new(HashMap::new()))
+            distributors: Arc::new(RwLock::new(HashMap::new())),
             // TODO: switch to LOTable once lever implements write optimized granularity
             // distributors: LOTableBuilder::new()
                 //.with_concurrency(TransactionConcurrency::Optimistic)
                 //.with_isolation(TransactionIsolation::Serializable)
                 //.build(),
+            topics: RwLock::new(HashMap::new()),
+            children: RwLock::new(HashMap::new()),
+            rotation: AtomicUsize::new(0),
         }
     }
 
@@ -402,6 +1294,68 @@ impl GlobalDispatcher {
             })
     }
 
+    /// Adds the launched child to the children that can be resolved
+    /// from their path.
+    pub(crate) fn register_child(&self, child_ref: &ChildRef) -> AnyResult<()> {
+        let mut children = self
+            .children
+            .write()
+            .map_err(|error| anyhow::anyhow!("couldn't get write lock on children {:?}", error))?;
+        children.insert(child_ref.id().clone(), child_ref.clone());
+        Ok(())
+    }
+
+    /// Removes the child with the given identifier from the children
+    /// that can be resolved from their path.
+    pub(crate) fn remove_child(&self, id: &BastionId) {
+        if let Ok(mut children) = self.children.write() {
+            children.remove(id);
+        }
+    }
+
+    /// Returns whether the child with the given identifier is
+    /// launched and wasn't cancelled.
+    pub(crate) fn is_child_alive(&self, id: &BastionId) -> bool {
+        match self.children.read() {
+            Ok(children) => children
+                .get(id)
+                .map_or(false, |child_ref| !child_ref.sender().is_closed()),
+            Err(_) => false,
+        }
+    }
+
+    /// Returns the child living at the given path, if any.
+    ///
+    /// The children that were cancelled without getting the chance to
+    /// remove themselves are dropped from the registry along the way.
+    pub(crate) fn resolve(&self, path: &BastionPath) -> Option<ChildRef> {
+        match path.elem() {
+            Some(elem) if elem.is_child() => (),
+            _ => return None,
+        }
+
+        let child_ref = self.children.read().ok()?.get(path.id()).cloned()?;
+        if child_ref.sender().is_closed() {
+            self.remove_child(path.id());
+            return None;
+        }
+
+        if **child_ref.path() == *path {
+            Some(child_ref)
+        } else {
+            None
+        }
+    }
+
+    /// Removes the actor with the given identifier from the given dispatchers.
+    pub(crate) fn remove_id(&self, dispatchers: &[DispatcherType], id: &BastionId) {
+        for key in dispatchers {
+            if let Some(dispatcher) = self.dispatchers.get(key) {
+                dispatcher.remove_id(id);
+            }
+        }
+    }
+
     /// Passes the notification from the actor to everyone that registered in the same
     /// groups as the caller.
     pub(crate) fn notify(
@@ -419,9 +1373,9 @@ impl GlobalDispatcher {
             })
     }
 
-    /// Broadcasts the given message in according with the specified target.
-    pub(crate) fn broadcast_message(&self, target: BroadcastTarget, message: &Arc<SignedMessage>) {
-        let acked_dispatchers = match target {
+    /// Returns the types of the dispatchers matching the specified target.
+    fn targeted_dispatchers(&self, target: BroadcastTarget) -> Vec<DispatcherType> {
+        match target {
             BroadcastTarget::All => self
                 .dispatchers
                 .iter()
@@ -431,23 +1385,154 @@ impl GlobalDispatcher {
                 let target_dispatcher = name.into();
                 vec![target_dispatcher]
             }
-        };
+            BroadcastTarget::Groups(names) => names.into_iter().map(Into::into).collect(),
+            BroadcastTarget::Anonymous => vec![DispatcherType::Anonymous],
+        }
+    }
+
+    /// Broadcasts the given message in according with the specified target.
+    pub(crate) fn broadcast_message(&self, target: BroadcastTarget, message: &Arc<SignedMessage>) {
+        self.broadcast_message_ordered(target, message, BroadcastOrder::default())
+    }
+
+    /// Broadcasts the given message in according with the specified target,
+    /// going through its recipients in the given order if it is sent to
+    /// each of them.
+    pub(crate) fn broadcast_message_ordered(
+        &self,
+        target: BroadcastTarget,
+        message: &Arc<SignedMessage>,
+        order: BroadcastOrder,
+    ) {
+        if let BroadcastTarget::Groups(names) = target {
+            return self.broadcast_to_groups(names, &[message.clone()], order);
+        }
+
+        let acked_dispatchers = self.targeted_dispatchers(target);
 
         for dispatcher_type in acked_dispatchers {
             match self.dispatchers.get(&dispatcher_type) {
                 Some(dispatcher) => {
                     dispatcher.broadcast_message(&message.clone());
                 }
-                // TODO: Put the message into the dead queue
                 None => {
                     let name = dispatcher_type.name();
                     debug!(
                         "The message can't be delivered to the group with the '{}' name.",
                         name
                     );
+                    Self::dead_letter(message);
+                }
+            }
+        }
+    }
+
+    /// Broadcasts the given messages, in order, in according with the specified target.
+    pub(crate) fn broadcast_batch(&self, target: BroadcastTarget, messages: &[Arc<SignedMessage>]) {
+        if let BroadcastTarget::Groups(names) = target {
+            return self.broadcast_to_groups(names, messages, BroadcastOrder::default());
+        }
+
+        let acked_dispatchers = self.targeted_dispatchers(target);
+
+        for dispatcher_type in acked_dispatchers {
+            match self.dispatchers.get(&dispatcher_type) {
+                Some(dispatcher) => {
+                    dispatcher.broadcast_batch(messages);
+                }
+                None => {
+                    let name = dispatcher_type.name();
+                    debug!(
+                        "The messages can't be delivered to the group with the '{}' name.",
+                        name
+                    );
+                    messages.iter().for_each(Self::dead_letter);
+                }
+            }
+        }
+    }
+
+    /// Sends the given messages, in order, to every public actor of the
+    /// groups with the given names, delivering a single copy of each
+    /// message to the actors belonging to several of them.
+    fn broadcast_to_groups(
+        &self,
+        names: Vec<String>,
+        messages: &[Arc<SignedMessage>],
+        order: BroadcastOrder,
+    ) {
+        let mut members: Vec<ChildRef> = Vec::new();
+
+        for name in names {
+            match self.dispatchers.get(&name.clone().into()) {
+                Some(dispatcher) => {
+                    for member in dispatcher.members() {
+                        if !members.contains(&member) {
+                            members.push(member);
+                        }
+                    }
+                }
+                None => {
+                    debug!(
+                        "The messages can't be delivered to the group with the '{}' name.",
+                        name
+                    );
+                    messages.iter().for_each(Self::dead_letter);
+                }
+            }
+        }
+
+        let members = self.arrange(members, order);
+        for message in messages {
+            for member in members.iter() {
+                if member.forward(message).is_err() {
+                    debug!("child {} is dead, skipping it", member.path());
+                }
+            }
+        }
+    }
+
+    /// Sorts the recipients of a broadcast in the given order.
+    fn arrange(&self, mut members: Vec<ChildRef>, order: BroadcastOrder) -> Vec<ChildRef> {
+        if order == BroadcastOrder::Unordered {
+            return members;
+        }
+
+        members.sort_by_cached_key(|member| member.path().to_string());
+        if order == BroadcastOrder::Rotated && !members.is_empty() {
+            let offset = self.rotation.fetch_add(1, Ordering::SeqCst) % members.len();
+            members.rotate_left(offset);
+        }
+
+        members
+    }
+
+    /// Routes a message that can't be delivered to the dead letters.
+    fn dead_letter(message: &Arc<SignedMessage>) {
+        // Broadcasted messages can always be cloned.
+        if let Some(msg) = message.msg.try_clone() {
+            let msg = BastionMessage::Message(msg);
+            let sign = message.sign.clone();
+            SYSTEM.dead_letters().send(Envelope { msg, sign }).ok();
+        }
+    }
+
+    /// Returns the public actors of the dispatchers matching the specified target,
+    /// without duplicates.
+    pub(crate) fn members(&self, target: BroadcastTarget) -> Vec<ChildRef> {
+        let mut members: Vec<ChildRef> = Vec::new();
+
+        for dispatcher_type in self.targeted_dispatchers(target) {
+            if let Some(dispatcher) = self.dispatchers.get(&dispatcher_type) {
+                for member in dispatcher.members() {
+                    if !members.contains(&member) {
+                        members.push(member);
+                    }
                 }
             }
         }
+
+        members
     }
 
     pub(crate) fn tell<M>(&self, distributor: Distributor, message: M) -> Result<(), SendError>
@@ -537,7 +1622,9 @@ impl GlobalDispatcher {
         let dispatcher_type = dispatcher.dispatcher_type();
         let is_registered = self.dispatchers.contains_key(&dispatcher_type);
 
-        if is_registered && dispatcher_type != DispatcherType::Anonymous {
+        // NOTE: the anonymous dispatcher is shared by every group
+        //      attached to one, so it isn't replaced either.
+        if is_registered {
             debug!(
                 "The dispatcher with the '{:?}' name already registered in the cluster.",
                 dispatcher_type
@@ -556,6 +1643,20 @@ impl GlobalDispatcher {
         Ok(())
     }
 
+    /// Removes the dispatcher of the given type from the global registry,
+    /// returning whether it was registered.
+    pub(crate) fn remove_dispatcher_type(
+        &self,
+        dispatcher_type: &DispatcherType,
+    ) -> AnyResult<bool> {
+        if !self.dispatchers.contains_key(dispatcher_type) {
+            return Ok(false);
+        }
+
+        self.dispatchers.remove(dispatcher_type)?;
+        Ok(true)
+    }
+
     /// Appends the information about actor to the recipients.
     pub(crate) fn register_recipient(
         &self,
@@ -594,6 +1695,96 @@ impl GlobalDispatcher {
         Ok(())
     }
 
+    /// Subscribes the actor to the given topic.
+    pub(crate) fn subscribe(&self, topic: &str, child_ref: ChildRef) -> AnyResult<()> {
+        let mut topics = self
+            .topics
+            .write()
+            .map_err(|error| anyhow::anyhow!("couldn't get write lock on topics {:?}", error))?;
+        if let Some(subscribers) = topics.get(topic) {
+            subscribers.register(child_ref);
+        } else {
+            let subscribers = DefaultRecipientHandler::default();
+            subscribers.register(child_ref);
+            topics.insert(
+                topic.to_string(),
+                Box::new(subscribers) as Box<(dyn RecipientHandler)>,
+            );
+        }
+        Ok(())
+    }
+
+    /// Unsubscribes the actor from the given topic, removing the topic
+    /// if it has no remaining subscribers.
+    pub(crate) fn unsubscribe(&self, topic: &str, child_ref: &ChildRef) -> AnyResult<()> {
+        let mut topics = self
+            .topics
+            .write()
+            .map_err(|error| anyhow::anyhow!("couldn't get write lock on topics {:?}", error))?;
+        let is_empty = match topics.get(topic) {
+            Some(subscribers) => {
+                subscribers.remove(child_ref);
+                subscribers.all().is_empty()
+            }
+            None => false,
+        };
+        if is_empty {
+            topics.remove(topic);
+        }
+        Ok(())
+    }
+
+    /// Unsubscribes the actor from every topic it subscribed to.
+    pub(crate) fn unsubscribe_all(&self, child_ref: &ChildRef) -> AnyResult<()> {
+        let mut topics = self
+            .topics
+            .write()
+            .map_err(|error| anyhow::anyhow!("couldn't get write lock on topics {:?}", error))?;
+        topics.retain(|_, subscribers| {
+            subscribers.remove(child_ref);
+            !subscribers.all().is_empty()
+        });
+        Ok(())
+    }
+
+    /// Sends a copy of the message to each subscriber of the given topic,
+    /// unsubscribing the ones that are dead.
+    ///
+    /// The message is routed to the dead letters if the topic has no
+    /// subscribers.
+    pub(crate) fn publish(&self, topic: &str, message: &Arc<SignedMessage>) -> AnyResult<()> {
+        let subscribers = self
+            .topics
+            .read()
+            .map_err(|error| anyhow::anyhow!("couldn't get read lock on topics {:?}", error))?
+            .get(topic)
+            .map(|subscribers| subscribers.all())
+            .unwrap_or_default();
+
+        if subscribers.is_empty() {
+            debug!(
+                "The message can't be delivered to the '{}' topic: no subscribers.",
+                topic
+            );
+            Self::dead_letter(message);
+            return Ok(());
+        }
+
+        for subscriber in subscribers {
+            // Published messages can always be cloned.
+            let msg = match message.msg.try_clone() {
+                Some(msg) => BastionMessage::Message(msg),
+                None => break,
+            };
+            let env = Envelope::new_with_sign(msg, message.sign.clone());
+            if subscriber.send(env).is_err() {
+                debug!("subscriber {} is dead, unsubscribing it", subscriber.path());
+                self.unsubscribe(topic, &subscriber)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Adds distributor to the global registry.
     pub(crate) fn register_distributor(&self, distributor: &Distributor) -> AnyResult<()> {
         let mut distributors = self.distributors.write().map_err(|error| {
@@ -634,10 +1825,11 @@ mod tests {
     use crate::child_ref::ChildRef;
     use crate::context::BastionId;
     use crate::dispatcher::*;
-    use crate::envelope::{RefAddr, SignedMessage};
-    use crate::message::Msg;
-    use crate::path::BastionPath;
+    use crate::envelope::{Envelope, RefAddr, SignedMessage};
+    use crate::message::{BastionMessage, Msg};
+    use crate::path::{BastionPath, BastionPathElement};
     use futures::channel::mpsc;
+    use std::collections::HashMap;
     use std::sync::{Arc, Mutex};
 
     #[derive(Clone)]
@@ -965,4 +2157,269 @@ mod tests {
         // Distributor is now removed because it has no remaining recipients.
         assert!(global_dispatcher.distributors.read().unwrap().is_empty());
     }
+
+    // Returns a child of the same group for each of the given identifiers,
+    // with the receiver of its messages.
+    fn group_children(
+        ids: &[BastionId],
+    ) -> Vec<(ChildRef, mpsc::UnboundedReceiver<crate::envelope::Envelope>)> {
+        let children_path = BastionPath::root()
+            .append(BastionPathElement::Supervisor(BastionId::new()))
+            .unwrap()
+            .append(BastionPathElement::Children(BastionId::new()))
+            .unwrap();
+
+        ids.iter()
+            .map(|id| {
+                let (sender, receiver) = mpsc::unbounded();
+                let path = children_path
+                    .clone()
+                    .append(BastionPathElement::Child(id.clone()))
+                    .unwrap();
+                let child_ref =
+                    ChildRef::new(id.clone(), sender, "test_name".to_string(), Arc::new(path));
+                (child_ref, receiver)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_global_dispatcher_stable_broadcast_order() {
+        let global_dispatcher = GlobalDispatcher::new();
+        let dispatcher_type = DispatcherType::Named("ordered".to_string());
+        let local_dispatcher = Arc::new(Box::new(Dispatcher::with_type(dispatcher_type)));
+        global_dispatcher
+            .register_dispatcher(&local_dispatcher)
+            .unwrap();
+
+        let ids = (0..5).map(|_| BastionId::new()).collect::<Vec<_>>();
+        let mut children = group_children(&ids);
+        for (child_ref, _) in children.iter() {
+            local_dispatcher
+                .register(child_ref, "my::test::module".to_string())
+                .unwrap();
+        }
+
+        let mut sorted = children
+            .iter()
+            .map(|(child_ref, _)| child_ref.clone())
+            .collect::<Vec<_>>();
+        sorted.sort_by_key(|child_ref| child_ref.path().to_string());
+
+        let (sender, _) = mpsc::unbounded();
+        let message = Arc::new(SignedMessage::new(
+            Msg::broadcast("A message containing data."),
+            RefAddr::new(Arc::new(BastionPath::root()), sender),
+        ));
+        let members = global_dispatcher.members(BroadcastTarget::Group("ordered".to_string()));
+        let arranged = global_dispatcher.arrange(members, BroadcastOrder::Stable);
+        assert_eq!(arranged, sorted);
+
+        global_dispatcher.broadcast_message_ordered(
+            BroadcastTarget::Groups(vec!["ordered".to_string()]),
+            &message,
+            BroadcastOrder::Stable,
+        );
+        for (_, receiver) in children.iter_mut() {
+            assert!(matches!(receiver.try_next(), Ok(Some(_))));
+            assert!(receiver.try_next().is_err());
+        }
+    }
+
+    #[test]
+    fn test_global_dispatcher_rotated_broadcast_order() {
+        let global_dispatcher = GlobalDispatcher::new();
+        let ids = (0..3).map(|_| BastionId::new()).collect::<Vec<_>>();
+        let members = group_children(&ids)
+            .into_iter()
+            .map(|(child_ref, _)| child_ref)
+            .collect::<Vec<_>>();
+        let stable = global_dispatcher.arrange(members.clone(), BroadcastOrder::Stable);
+
+        for offset in 0..4 {
+            let mut expected = stable.clone();
+            expected.rotate_left(offset % 3);
+            assert_eq!(
+                global_dispatcher.arrange(members.clone(), BroadcastOrder::Rotated),
+                expected
+            );
+        }
+        assert_eq!(
+            global_dispatcher.arrange(members.clone(), BroadcastOrder::Unordered),
+            members
+        );
+    }
+
+    #[test]
+    fn test_consistent_hash_handler_virtual_nodes_even_distribution() {
+        const CHILDREN: u128 = 5;
+        const RINGS: u128 = 20;
+        const KEYS: u64 = 10_000;
+
+        // Returns the variance of the amount of keys owned by every
+        // child, averaged over several rings.
+        let variance = |virtual_nodes: usize| {
+            let mut total = 0.0;
+            for ring in 0..RINGS {
+                let handler = ConsistentHashHandler::new(|_: &SignedMessage| None::<u64>)
+                    .with_virtual_nodes(virtual_nodes);
+                let entries = DispatcherMap::default();
+                for child in 0..CHILDREN {
+                    let (sender, _) = mpsc::unbounded();
+                    let path = Arc::new(BastionPath::root());
+                    let id = BastionId(uuid::Uuid::from_u128(ring * CHILDREN + child));
+                    let child_ref = ChildRef::new(id, sender, "test_name".to_string(), path);
+                    entries
+                        .insert(child_ref, "my::test::module".to_string())
+                        .unwrap();
+                }
+                handler.rebuild(&entries);
+
+                let mut owned = HashMap::new();
+                for key in 0..KEYS {
+                    let owner = handler.owner(ConsistentHashHandler::hash(&key)).unwrap();
+                    *owned.entry(owner.id().clone()).or_insert(0) += 1;
+                }
+
+                let mean = KEYS as f64 / CHILDREN as f64;
+                total += (0..CHILDREN)
+                    .map(|child| {
+                        let id = BastionId(uuid::Uuid::from_u128(ring * CHILDREN + child));
+                        let count = *owned.get(&id).unwrap_or(&0) as f64;
+                        (count - mean).powi(2)
+                    })
+                    .sum::<f64>()
+                    / CHILDREN as f64;
+            }
+
+            total / RINGS as f64
+        };
+
+        let sparse = variance(1);
+        let dense = variance(200);
+        assert!(
+            dense * 10.0 < sparse,
+            "variance with 200 virtual nodes: {}, with 1: {}",
+            dense,
+            sparse
+        );
+    }
+
+    #[test]
+    fn test_round_robin_handler_rotation_survives_removals() {
+        let handler = RoundRobinHandler::default();
+        let entries = DispatcherMap::default();
+        let mut children = Vec::new();
+        for _ in 0..4 {
+            let (sender, receiver) = mpsc::unbounded();
+            let path = Arc::new(BastionPath::root());
+            let child_ref = ChildRef::new(BastionId::new(), sender, "test_name".to_string(), path);
+            entries
+                .insert(child_ref.clone(), "my::test::module".to_string())
+                .unwrap();
+            children.push((child_ref, receiver));
+        }
+        let rotation = RoundRobinHandler::rotation(
+            children
+                .iter()
+                .map(|(child_ref, _)| child_ref.clone())
+                .collect(),
+        );
+
+        // Returns the children served by each of the broadcasted messages.
+        let mut broadcast = |entries: &DispatcherMap, count: usize| {
+            let mut served = Vec::new();
+            for _ in 0..count {
+                let (sender, _) = mpsc::unbounded();
+                let path = Arc::new(BastionPath::root());
+                let message = Arc::new(SignedMessage::new(
+                    Msg::broadcast("A message containing data."),
+                    RefAddr::new(path, sender),
+                ));
+                handler.broadcast_message(entries, &message);
+
+                for (child_ref, receiver) in children.iter_mut() {
+                    while let Ok(Some(_)) = receiver.try_next() {
+                        served.push(child_ref.clone());
+                    }
+                }
+            }
+
+            served
+        };
+
+        let served = broadcast(&entries, 6);
+        let expected = (0..6).map(|i| rotation[i % 4].clone()).collect::<Vec<_>>();
+        assert_eq!(served, expected);
+
+        // Removes the last served child mid-rotation: the rotation
+        // goes on with the next child, without skipping it.
+        entries.remove(&rotation[1]).unwrap();
+        let survivors = vec![
+            rotation[2].clone(),
+            rotation[3].clone(),
+            rotation[0].clone(),
+        ];
+        let served = broadcast(&entries, 9);
+        let expected = (0..9).map(|i| survivors[i % 3].clone()).collect::<Vec<_>>();
+        assert_eq!(served, expected);
+    }
+
+    #[test]
+    fn test_round_robin_handler_batch_sends_an_envelope_per_child() {
+        let handler = RoundRobinHandler::default();
+        let entries = DispatcherMap::default();
+        let mut children = Vec::new();
+        for _ in 0..4 {
+            let (sender, receiver) = mpsc::unbounded();
+            let path = Arc::new(BastionPath::root());
+            let child_ref = ChildRef::new(BastionId::new(), sender, "test_name".to_string(), path);
+            entries
+                .insert(child_ref.clone(), "my::test::module".to_string())
+                .unwrap();
+            children.push((child_ref, receiver));
+        }
+        let rotation = RoundRobinHandler::rotation(
+            children
+                .iter()
+                .map(|(child_ref, _)| child_ref.clone())
+                .collect(),
+        );
+
+        let messages = (0..10usize)
+            .map(|i| {
+                let (sender, _) = mpsc::unbounded();
+                let path = Arc::new(BastionPath::root());
+                Arc::new(SignedMessage::new(
+                    Msg::broadcast(i),
+                    RefAddr::new(path, sender),
+                ))
+            })
+            .collect::<Vec<_>>();
+        handler.broadcast_batch(&entries, &messages);
+
+        // Every child received a single envelope, holding its share
+        // of the batch in order.
+        for (position, child_ref) in rotation.iter().enumerate() {
+            let (_, receiver) = children
+                .iter_mut()
+                .find(|(child, _)| child.id() == child_ref.id())
+                .unwrap();
+            let share = match receiver.try_next() {
+                Ok(Some(Envelope {
+                    msg: BastionMessage::Batch(share),
+                    ..
+                })) => share,
+                env => panic!("unexpected envelope: {:?}", env),
+            };
+            assert!(receiver.try_next().is_err());
+
+            let values = share
+                .iter()
+                .map(|message| *message.peek::<usize>().unwrap())
+                .collect::<Vec<_>>();
+            let expected = (position..10).step_by(4).collect::<Vec<_>>();
+            assert_eq!(values, expected);
+        }
+    }
 }
@@ -1,22 +1,40 @@
 //!
 //! Cluster formation and distributed actor instantiation
 use crate::children_ref::ChildrenRef;
+use crate::compression::{self, Compression};
 use crate::context::*;
+use crate::dedup::{self, RecentIds};
+#[cfg(feature = "encryption")]
+use crate::encryption::{Encryption, EncryptionKey, Keyring};
+use crate::errors::TellError;
+use crate::events::{SystemEvent, EVENTS};
+use crate::failure_detector::{FailureDetectors, PhiAccrualConfig};
+use crate::membership::{Membership, ReconnectPolicy};
 use crate::message::Message;
+use crate::ordering::{self, GapPolicy, OrderedDelivery, ReorderBuffers};
+use crate::outbound::{self, OutboundQueue, OutboundQueues};
+use crate::path::{BastionPath, RemoteNode};
+#[cfg(any(test, feature = "testkit"))]
+use crate::transport::MockTransport;
+use crate::transport::{ClusterEvent, ClusterTransport, GossipConfig};
 use crate::Bastion;
 
 use crate::message::Msg;
 
 use artillery_core::cluster::ap::*;
 use artillery_core::epidemic::prelude::*;
-use std::sync::Arc;
+use bytes::Bytes;
+use fxhash::FxHashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use core::future::Future;
-use futures::future;
+use futures::future::{self, FutureExt};
 use tracing::*;
 
-use lever::table::lotable::*;
-
 use uuid::Uuid;
 
 ///
@@ -25,13 +43,34 @@ use uuid::Uuid;
 pub struct ClusterMessage {
     pub(crate) msg: Msg,
     pub(crate) member: Uuid,
+    pub(crate) path: BastionPath,
 }
 
 impl ClusterMessage {
     ///
     /// Create a `ClusterMessage` from a `Msg` and a member
     pub fn new(msg: Msg, member: Uuid) -> Self {
-        ClusterMessage { msg, member }
+        let path = BastionPath::remote(RemoteNode::new(member, member.to_string(), None));
+        ClusterMessage { msg, member, path }
+    }
+
+    pub(crate) fn with_path(mut self, path: BastionPath) -> Self {
+        self.path = path;
+        self
+    }
+
+    ///
+    /// Gets the node id of the member which sent the message
+    pub fn member(&self) -> Uuid {
+        self.member
+    }
+
+    ///
+    /// Gets the path of the sender, whose [`RemoteNode`] is named after the name the member was
+    /// configured with (see [`ClusterConfig::with_node_name`]), or its node id otherwise, and has
+    /// the member's address.
+    pub fn path(&self) -> &BastionPath {
+        &self.path
     }
 
     ///
@@ -39,6 +78,312 @@ impl ClusterMessage {
     pub fn extract(self) -> Msg {
         self.msg
     }
+
+    ///
+    /// Extracts the payload of the message as [`Bytes`], which takes over the buffer the payload
+    /// was received in instead of copying it.
+    ///
+    /// Returns `Err(msg)` with the message if its payload isn't one received from a member.
+    pub fn into_bytes(self) -> Result<Bytes, Msg> {
+        self.msg.downcast::<String>().map(Bytes::from)
+    }
+}
+
+///
+/// Configuration of the cluster a distributed actor is part of.
+#[derive(Debug, Clone)]
+pub struct ClusterConfig {
+    backend: Backend,
+    compression: Option<Compression>,
+    #[cfg(feature = "encryption")]
+    encryption: Option<Encryption>,
+    reconnect: ReconnectPolicy,
+    phi_accrual: Option<PhiAccrualConfig>,
+    metadata: HashMap<String, String>,
+    node_name: Option<String>,
+    node_id: Option<Uuid>,
+    dedup_capacity: usize,
+    ordered_delivery: Option<OrderedDelivery>,
+    gossip_fan_out: Option<usize>,
+    gossip_interval: Option<Duration>,
+    suspicion_timeout: Option<Duration>,
+    outbound_queue: Option<OutboundQueue>,
+    quorum_size: Option<usize>,
+}
+
+/// The codec tag of the payloads announcing the metadata of a member.
+const METADATA_TAG: &str = "meta";
+
+#[derive(Debug, Clone)]
+enum Backend {
+    Artillery(&'static ArtilleryAPClusterConfig),
+    Transport {
+        node_id: Uuid,
+        transport: Arc<dyn ClusterTransport>,
+    },
+}
+
+// Why a payload wasn't sent to a member.
+#[derive(Debug)]
+enum SendError {
+    TooLarge { size: usize, max: usize },
+    Encryption(anyhow::Error),
+}
+
+impl ClusterConfig {
+    ///
+    /// Creates a cluster configuration from the underlying cluster's configuration,
+    /// without payload compression and with the default [`ReconnectPolicy`].
+    pub fn new(ap: &'static ArtilleryAPClusterConfig) -> Self {
+        ClusterConfig::with_backend(Backend::Artillery(ap))
+    }
+
+    ///
+    /// Creates a cluster configuration for a member whose payloads and membership events are
+    /// carried by the given transport instead of the underlying cluster's sockets, like a
+    /// [`MockTransport`] (with the `testkit` feature).
+    ///
+    /// [`MockTransport`]: crate::transport::MockTransport
+    pub fn from_transport<T>(node_id: Uuid, transport: T) -> Self
+    where
+        T: ClusterTransport + 'static,
+    {
+        let transport = Arc::new(transport);
+        ClusterConfig::with_backend(Backend::Transport { node_id, transport })
+    }
+
+    fn with_backend(backend: Backend) -> Self {
+        ClusterConfig {
+            backend,
+            compression: None,
+            #[cfg(feature = "encryption")]
+            encryption: None,
+            reconnect: ReconnectPolicy::default(),
+            phi_accrual: None,
+            metadata: HashMap::new(),
+            node_name: None,
+            node_id: None,
+            dedup_capacity: dedup::DEFAULT_CAPACITY,
+            ordered_delivery: None,
+            gossip_fan_out: None,
+            gossip_interval: None,
+            suspicion_timeout: None,
+            outbound_queue: None,
+            quorum_size: None,
+        }
+    }
+
+    ///
+    /// Compresses the payloads sent with [`DistributedContext::tell`] using the given settings.
+    ///
+    /// Compressed payloads are tagged with their codec and transparently decompressed by
+    /// [`DistributedContext::recv`], so members which don't compress their payloads can
+    /// still be part of the cluster.
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    ///
+    /// Encrypts the payloads exchanged with the other members using the given settings,
+    /// dropping the payloads which aren't encrypted with an accepted key.
+    ///
+    /// Every member of the cluster needs to be configured with the same key, which can later be
+    /// rotated node-by-node with [`DistributedContext::accept_key`] and
+    /// [`DistributedContext::rotate_key`].
+    #[cfg(feature = "encryption")]
+    pub fn with_encryption(mut self, encryption: Encryption) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
+
+    ///
+    /// Sets how the peers whose link dropped are given a chance to reconnect before being
+    /// removed from [`DistributedContext::members`].
+    pub fn with_reconnect(mut self, reconnect: ReconnectPolicy) -> Self {
+        self.reconnect = reconnect;
+        self
+    }
+
+    ///
+    /// Also considers members down once the suspicion level computed by a phi-accrual failure
+    /// detector exceeds the configured threshold, adapting to the jitter observed on the network.
+    ///
+    /// Every payload received from a member and every cluster event reporting it alive counts
+    /// as one of its heartbeats.
+    pub fn with_phi_accrual(mut self, phi_accrual: PhiAccrualConfig) -> Self {
+        self.phi_accrual = Some(phi_accrual);
+        self
+    }
+
+    ///
+    /// Sets the metadata of this member, replacing the tags previously set.
+    ///
+    /// The metadata is announced to the other members as soon as they are seen joining (or
+    /// reconnecting), so that they can select this member with [`DistributedContext::members_where`].
+    ///
+    /// Membership events are only handled while [`DistributedContext::recv`] is awaited: a member
+    /// which doesn't receive messages neither announces its metadata nor stores the one announced
+    /// by the other members.
+    pub fn with_metadata(mut self, metadata: HashMap<String, String>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    ///
+    /// Sets the name of this member, announced to the other members along with its metadata (under
+    /// [`NODE_NAME_KEY`]) and used as the name of its [`RemoteNode`] in the paths of the messages
+    /// they receive from it (see [`ClusterMessage::path`]).
+    ///
+    /// Members which aren't named, or whose name isn't a valid [`RemoteNode`] name (see
+    /// [`RemoteNode::try_new`]), are identified by their node id instead.
+    pub fn with_node_name<N>(mut self, node_name: N) -> Self
+    where
+        N: Into<String>,
+    {
+        self.node_name = Some(node_name.into());
+        self
+    }
+
+    ///
+    /// Sets the node id of this member instead of the one of the underlying cluster's
+    /// configuration (usually a random one), so that the ids the other members see it with (see
+    /// [`ClusterMessage::member`]) are the same across runs, which makes tests reproducible and
+    /// logs easier to correlate.
+    ///
+    /// The node id of a member whose payloads are carried by a [`ClusterTransport`] is the one of
+    /// its transport (see [`MockNetwork::join_with_id`]), so it can't be overridden.
+    ///
+    /// [`MockNetwork::join_with_id`]: crate::transport::MockNetwork::join_with_id
+    pub fn with_node_id(mut self, node_id: Uuid) -> Self {
+        self.node_id = Some(node_id);
+        self
+    }
+
+    ///
+    /// Adds a tag to the metadata of this member (see [`ClusterConfig::with_metadata`]).
+    pub fn with_tag<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    ///
+    /// Sets how many of the message ids received recently are remembered to drop the payloads
+    /// delivered more than once (4096 by default).
+    ///
+    /// Every payload sent with [`DistributedContext::tell`] carries a unique message id, and
+    /// [`DistributedContext::recv`] drops the payloads whose id is among the ones it remembers.
+    pub fn with_dedup_capacity(mut self, capacity: usize) -> Self {
+        self.dedup_capacity = capacity;
+        self
+    }
+
+    ///
+    /// Sets the amount of members a quorum is a strict majority of (see
+    /// [`DistributedContext::has_quorum`]).
+    ///
+    /// By default, a quorum is a majority of every member this member ever knew about, which
+    /// never shrinks, so that a cluster which was scaled down needs its new size to be set to
+    /// regain a quorum.
+    pub fn with_quorum_size(mut self, quorum_size: usize) -> Self {
+        self.quorum_size = Some(quorum_size);
+        self
+    }
+
+    ///
+    /// Delivers the payloads received from every member in the order that member sent them,
+    /// even if the network reorders them.
+    ///
+    /// Every payload sent with [`DistributedContext::tell`] then carries a sequence number
+    /// specific to its receiver, and [`DistributedContext::recv`] holds the payloads received
+    /// ahead of time until the ones sent before them arrive, or until the gap timeout of
+    /// `ordered_delivery` elapsed (see [`GapPolicy`]).
+    pub fn with_ordered_delivery(mut self, ordered_delivery: OrderedDelivery) -> Self {
+        self.ordered_delivery = Some(ordered_delivery);
+        self
+    }
+
+    ///
+    /// Sets how many peers this member gossips with every round.
+    ///
+    /// A larger fan-out makes the membership converge in fewer rounds, at the cost of more
+    /// traffic. Defaults to the underlying cluster's configuration (see [`GossipConfig`]).
+    pub fn with_gossip_fan_out(mut self, fan_out: usize) -> Self {
+        self.gossip_fan_out = Some(fan_out);
+        self
+    }
+
+    ///
+    /// Sets the time between two gossip rounds of this member.
+    ///
+    /// Defaults to the underlying cluster's configuration (see [`GossipConfig`]).
+    pub fn with_gossip_interval(mut self, interval: Duration) -> Self {
+        self.gossip_interval = Some(interval);
+        self
+    }
+
+    ///
+    /// Sets the time after which a peer which doesn't answer this member's gossip is suspected.
+    ///
+    /// Defaults to the underlying cluster's configuration (see [`GossipConfig`]).
+    pub fn with_suspicion_timeout(mut self, suspicion_timeout: Duration) -> Self {
+        self.suspicion_timeout = Some(suspicion_timeout);
+        self
+    }
+
+    ///
+    /// Queues the payloads sent to every member while the transport can't carry them (see
+    /// [`ClusterTransport::can_send`]), up to the capacity of `outbound_queue`, instead of handing
+    /// them to the transport regardless.
+    ///
+    /// Once the queue of a member is full, the sender either blocks until the transport carried
+    /// the oldest payload queued for it, or drops that payload to make room for the new one (see
+    /// [`OutboundOverflow`]). The payloads dropped for every member are counted (see
+    /// `DistributedContext::outbound_dropped`), and a member which was removed loses the payloads
+    /// queued for it.
+    ///
+    /// [`OutboundOverflow`]: crate::outbound::OutboundOverflow
+    pub fn with_outbound_queue(mut self, outbound_queue: OutboundQueue) -> Self {
+        self.outbound_queue = Some(outbound_queue);
+        self
+    }
+
+    ///
+    /// Gets the gossip settings of this member, falling back to the underlying cluster's
+    /// configuration (or [`GossipConfig::default`] for transports) for the ones which weren't set.
+    pub fn gossip(&self) -> GossipConfig {
+        let defaults = match &self.backend {
+            Backend::Artillery(ap) => GossipConfig {
+                fan_out: ap.cluster_config.ping_request_host_count,
+                interval: ap.cluster_config.ping_interval,
+                suspicion_timeout: ap.cluster_config.ping_timeout,
+            },
+            Backend::Transport { .. } => GossipConfig::default(),
+        };
+
+        GossipConfig {
+            fan_out: self.gossip_fan_out.unwrap_or(defaults.fan_out),
+            interval: self.gossip_interval.unwrap_or(defaults.interval),
+            suspicion_timeout: self.suspicion_timeout.unwrap_or(defaults.suspicion_timeout),
+        }
+    }
+}
+
+impl From<&'static ArtilleryAPClusterConfig> for ClusterConfig {
+    fn from(ap: &'static ArtilleryAPClusterConfig) -> Self {
+        ClusterConfig::new(ap)
+    }
+}
+
+#[cfg(any(test, feature = "testkit"))]
+impl From<MockTransport> for ClusterConfig {
+    fn from(transport: MockTransport) -> Self {
+        ClusterConfig::from_transport(transport.node_id(), transport)
+    }
 }
 
 ///
@@ -47,19 +392,83 @@ impl ClusterMessage {
 pub struct DistributedContext {
     bctx: BastionContext,
     me: Uuid,
-    members: LOTable<Uuid, ArtilleryMember>,
-    cluster: Arc<Cluster>,
+    members: Mutex<Membership<ArtilleryMember>>,
+    detectors: Option<Mutex<FailureDetectors>>,
+    cluster: Arc<dyn ClusterTransport>,
+    compression: Option<Compression>,
+    #[cfg(feature = "encryption")]
+    keyring: Option<Mutex<Keyring>>,
+    metadata: HashMap<String, String>,
+    peers_metadata: Mutex<FxHashMap<Uuid, HashMap<String, String>>>,
+    // The ids of the messages received recently.
+    recent_ids: Mutex<RecentIds>,
+    // The payloads received ahead of time, if they are delivered in order.
+    reorder: Option<Mutex<ReorderBuffers>>,
+    // The sequence number of the next payload sent to every member, if the payloads are
+    // delivered in order.
+    sequences: Mutex<FxHashMap<Uuid, u64>>,
+    // The payloads which were received in order, but weren't decoded yet.
+    ready: Mutex<VecDeque<(Uuid, String)>>,
+    // The events received from the transport which weren't handled yet.
+    events: Mutex<VecDeque<ClusterEvent>>,
+    // The payloads this member broadcasted to itself, which weren't received yet.
+    loopback: Mutex<VecDeque<String>>,
+    // The payloads waiting for the transport to carry them to every member, if they are queued.
+    outbound: Option<Mutex<OutboundQueues>>,
+    quorum: AtomicBool,
+    isolated: AtomicBool,
 }
 
 impl DistributedContext {
     ///
     /// Initializes distributed context with underlying actor's local context and cluster handle.
-    fn new(bctx: BastionContext, cluster: Arc<Cluster>, me: Uuid) -> Self {
+    fn new(
+        bctx: BastionContext,
+        cluster: Arc<dyn ClusterTransport>,
+        me: Uuid,
+        config: &ClusterConfig,
+    ) -> Self {
+        let detectors = config
+            .phi_accrual
+            .clone()
+            .map(|config| Mutex::new(FailureDetectors::new(config)));
+
+        let mut metadata = config.metadata.clone();
+        if let Some(node_name) = &config.node_name {
+            metadata.insert(NODE_NAME_KEY.to_string(), node_name.clone());
+        }
+
         DistributedContext {
             bctx,
             me,
-            members: LOTable::new(),
+            members: Mutex::new(
+                Membership::new(config.reconnect.clone()).with_quorum_size(config.quorum_size),
+            ),
+            detectors,
             cluster,
+            compression: config.compression,
+            #[cfg(feature = "encryption")]
+            keyring: config
+                .encryption
+                .as_ref()
+                .map(|encryption| Mutex::new(Keyring::new(encryption))),
+            metadata,
+            peers_metadata: Mutex::new(FxHashMap::default()),
+            recent_ids: Mutex::new(RecentIds::new(config.dedup_capacity)),
+            reorder: config
+                .ordered_delivery
+                .as_ref()
+                .map(|ordered_delivery| Mutex::new(ReorderBuffers::new(ordered_delivery))),
+            sequences: Mutex::new(FxHashMap::default()),
+            ready: Mutex::new(VecDeque::new()),
+            events: Mutex::new(VecDeque::new()),
+            loopback: Mutex::new(VecDeque::new()),
+            outbound: config
+                .outbound_queue
+                .as_ref()
+                .map(|outbound_queue| Mutex::new(OutboundQueues::new(outbound_queue))),
+            quorum: AtomicBool::new(true),
+            isolated: AtomicBool::new(false),
         }
     }
 
@@ -81,59 +490,969 @@ impl DistributedContext {
     /// Get current members of the cluster.
     ///
     /// This list is continuously updates with cluster state.
-    /// If a node is down it won't appear in this list.
+    /// If a node is down it won't appear in this list, unless it is still trying to reconnect
+    /// (see [`ClusterConfig::with_reconnect`]).
     /// If a node is suspected, it will still be in here. When suspected message delivery isn't guaranteed.
     pub fn members(&self) -> Vec<ArtilleryMember> {
+        // FIXME: panics?
         self.members
-            .values()
-            .filter(|m| m.host_key() != self.me)
+            .lock()
+            .unwrap()
+            .members()
+            .into_iter()
+            .filter(|(id, _)| *id != self.me)
+            .map(|(_, member)| member)
             .collect()
     }
 
+    ///
+    /// Get the current members of the cluster whose metadata matches the given predicate.
+    ///
+    /// Members whose metadata wasn't announced yet are matched against empty metadata, which is
+    /// also the case of every member until this member awaits [`DistributedContext::recv`].
+    /// See [`ClusterConfig::with_metadata`].
+    pub fn members_where<P>(&self, predicate: P) -> Vec<ArtilleryMember>
+    where
+        P: Fn(&HashMap<String, String>) -> bool,
+    {
+        let empty = HashMap::new();
+        // FIXME: panics?
+        let peers_metadata = self.peers_metadata.lock().unwrap();
+
+        self.members()
+            .into_iter()
+            .filter(|member| {
+                let metadata = peers_metadata.get(&member.host_key()).unwrap_or(&empty);
+                predicate(metadata)
+            })
+            .collect()
+    }
+
+    ///
+    /// Gets the remote path of a member of the cluster, rendered as `name@addr/` and named after
+    /// the name it announced (see [`ClusterConfig::with_node_name`]), or its node id otherwise.
+    ///
+    /// The node id of the member can be recovered from the path with [`BastionPath::host_key`],
+    /// e.g. to [`tell`] it a message, so that local and remote senders can be addressed by their
+    /// paths alike.
+    ///
+    /// [`tell`]: DistributedContext::tell
+    pub fn path_of_member(&self, member: &ArtilleryMember) -> BastionPath {
+        self.path_of(member.host_key())
+    }
+
+    ///
+    /// Gets the metadata announced by a member of the cluster, if any.
+    ///
+    /// The announced metadata is only stored while [`DistributedContext::recv`] is awaited.
+    pub fn metadata_of(&self, member: &Uuid) -> Option<HashMap<String, String>> {
+        // FIXME: panics?
+        self.peers_metadata.lock().unwrap().get(member).cloned()
+    }
+
+    ///
+    /// Returns whether this member currently sees a quorum: a strict majority of the members it
+    /// ever knew, including itself and the members which were since removed, or of the quorum
+    /// size set with [`ClusterConfig::with_quorum_size`].
+    ///
+    /// During a network partition, at most one side of the cluster has a quorum, so that
+    /// applications can gate writes on it to avoid a split-brain. Losing and regaining it emits
+    /// [`SystemEvent::QuorumLost`] and [`SystemEvent::QuorumRegained`] on [`Bastion::event_stream`].
+    pub fn has_quorum(&self) -> bool {
+        self.quorum.load(Ordering::SeqCst)
+    }
+
+    ///
+    /// Returns whether this member is isolated: it knew about other members, but can't reach any
+    /// of them anymore.
+    ///
+    /// Once it reaches some of them again, it rejoins the cluster instead of joining it as a new
+    /// member: it re-announces its metadata to them, merges their view of the membership with the
+    /// members it retained (see [`ClusterConfig::with_reconnect`]), and emits
+    /// [`SystemEvent::Rejoined`] on [`Bastion::event_stream`].
+    pub fn is_isolated(&self) -> bool {
+        self.isolated.load(Ordering::SeqCst)
+    }
+
     ///
     /// Send a fire and forget style message to a destined cluster member.
     /// Message needs to be stringified or apply the rules of bastion's [Message] trait.
-    pub fn tell<M>(&self, to: &Uuid, msg: M) -> Result<(), M>
+    ///
+    /// The payload is compressed if the cluster was configured with [`ClusterConfig::with_compression`],
+    /// framed with its sequence number if it was configured with
+    /// [`ClusterConfig::with_ordered_delivery`], framed with a unique message id, then encrypted if
+    /// it was configured with `ClusterConfig::with_encryption`.
+    ///
+    /// Returns [`TellError::MessageTooLarge`] without sending anything if the resulting payload
+    /// is larger than the transport can carry (see [`ClusterTransport::max_payload_size`]), and
+    /// [`TellError::EncryptionFailed`] if it couldn't be encrypted.
+    pub fn tell<M>(&self, to: &Uuid, msg: M) -> Result<(), TellError<M>>
     where
         M: Message + AsRef<str>,
     {
         debug!("Sending payload");
-        self.cluster.send_payload(*to, msg);
+        let payload = compression::encode(msg.as_ref(), self.compression.as_ref());
+        match self.send_framed(*to, Uuid::new_v4(), &payload) {
+            Ok(()) => Ok(()),
+            Err(SendError::TooLarge { size, max }) => {
+                Err(TellError::MessageTooLarge { msg, size, max })
+            }
+            Err(SendError::Encryption(e)) => Err(TellError::EncryptionFailed {
+                msg,
+                reason: e.to_string(),
+            }),
+        }
+    }
+
+    ///
+    /// Send a fire and forget style message to a destined cluster member, like
+    /// [`DistributedContext::tell`], if it is one of the members this member knows about.
+    ///
+    /// Returns [`TellError::UnknownMember`] without sending anything if the member isn't (or isn't
+    /// anymore) part of [`DistributedContext::members`], instead of letting the transport silently
+    /// drop the payload, and [`TellError::MessageTooLarge`] like [`DistributedContext::tell`].
+    ///
+    /// Only the failures which can be detected before sending the payload are reported: an
+    /// `Ok(())` doesn't mean that the member received it.
+    pub fn try_tell<M>(&self, to: &Uuid, msg: M) -> Result<(), TellError<M>>
+    where
+        M: Message + AsRef<str>,
+    {
+        // FIXME: panics?
+        let known = self
+            .members
+            .lock()
+            .unwrap()
+            .members()
+            .iter()
+            .any(|(id, _)| id == to && *id != self.me);
+        if !known {
+            debug!(
+                "DistributedContext({}): Not sending payload to unknown Member({}).",
+                self.me, to
+            );
+            return Err(TellError::UnknownMember { msg, member: *to });
+        }
+
+        self.tell(to, msg)
+    }
+
+    ///
+    /// Gets the amount of payloads queued for the given member, waiting for the transport to carry
+    /// them, if the cluster was configured with [`ClusterConfig::with_outbound_queue`].
+    pub fn outbound_queued(&self, member: &Uuid) -> usize {
+        self.outbound.as_ref().map_or(0, |outbound| {
+            // FIXME: panics?
+            outbound.lock().unwrap().len(*member)
+        })
+    }
+
+    ///
+    /// Gets the amount of payloads sent to the given member which were dropped from its outbound
+    /// queue, because it was full or because the member was removed (see
+    /// [`ClusterConfig::with_outbound_queue`]).
+    #[cfg(feature = "metrics")]
+    pub fn outbound_dropped(&self, member: &Uuid) -> u64 {
+        self.outbound.as_ref().map_or(0, |outbound| {
+            // FIXME: panics?
+            outbound.lock().unwrap().dropped(*member)
+        })
+    }
+
+    ///
+    /// Send a fire and forget style message to every member of the cluster, except this one.
+    ///
+    /// The payload is compressed, framed and encrypted once, like with
+    /// [`DistributedContext::tell`], then sent to every member returned by
+    /// [`DistributedContext::members`]. Every member receives it once, as the copies share the
+    /// same message id (if the cluster was configured with [`ClusterConfig::with_ordered_delivery`],
+    /// every copy is framed and encrypted separately, with the sequence number of its receiver).
+    ///
+    /// Returns `Err(msg)` without sending anything if the payload is larger than the transport
+    /// can carry (see [`ClusterTransport::max_payload_size`]) or couldn't be encrypted.
+    pub fn broadcast<M>(&self, msg: M) -> Result<(), M>
+    where
+        M: Message + AsRef<str>,
+    {
+        self.broadcast_to_members(msg, false)
+    }
+
+    ///
+    /// Send a fire and forget style message to every member of the cluster, including this one,
+    /// which receives it from [`DistributedContext::recv`] like the other members.
+    ///
+    /// See [`DistributedContext::broadcast`].
+    pub fn broadcast_including_self<M>(&self, msg: M) -> Result<(), M>
+    where
+        M: Message + AsRef<str>,
+    {
+        self.broadcast_to_members(msg, true)
+    }
+
+    fn broadcast_to_members<M>(&self, msg: M, include_self: bool) -> Result<(), M>
+    where
+        M: Message + AsRef<str>,
+    {
+        let members = self.members();
+        debug!(
+            "DistributedContext({}): Broadcasting payload to {} members.",
+            self.me,
+            members.len()
+        );
+        let payload = compression::encode(msg.as_ref(), self.compression.as_ref());
+        let id = Uuid::new_v4();
+        let shared = match self.encrypt(dedup::framed(id, &payload)) {
+            Ok(shared) => shared,
+            Err(e) => {
+                error!(
+                    "DistributedContext({}): Not broadcasting a payload which couldn't be encrypted: {}",
+                    self.me, e
+                );
+                return Err(msg);
+            }
+        };
+        if let Some(max) = self.oversized(&shared) {
+            error!(
+                "DistributedContext({}): Not broadcasting a {} bytes payload, above the {} bytes the transport can carry.",
+                self.me,
+                shared.len(),
+                max
+            );
+            return Err(msg);
+        }
+
+        for member in members {
+            let to = member.host_key();
+            if self.reorder.is_none() {
+                self.send_payload(to, shared.clone());
+            } else {
+                match self.send_framed(to, id, &payload) {
+                    Ok(()) => (),
+                    // NOTE: the sequence number can make a copy slightly larger than the others.
+                    Err(SendError::TooLarge { size, max }) => error!(
+                        "DistributedContext({}): Not sending a {} bytes payload to Member({}), above the {} bytes the transport can carry.",
+                        self.me, size, to, max
+                    ),
+                    Err(SendError::Encryption(e)) => error!(
+                        "DistributedContext({}): Not sending a payload to Member({}) which couldn't be encrypted: {}",
+                        self.me, to, e
+                    ),
+                }
+            }
+        }
+
+        if include_self {
+            // FIXME: panics?
+            self.loopback
+                .lock()
+                .unwrap()
+                .push_back(msg.as_ref().to_string());
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Starts accepting the payloads encrypted with the given key, while still sending with the
+    /// current one.
+    ///
+    /// When rotating keys, every member should accept the new key before any member rotates to it
+    /// with [`DistributedContext::rotate_key`].
+    ///
+    /// Returns `Err(())` if the cluster wasn't configured with [`ClusterConfig::with_encryption`].
+    #[cfg(feature = "encryption")]
+    pub fn accept_key(&self, key: EncryptionKey) -> Result<(), ()> {
+        let keyring = self.keyring.as_ref().ok_or(())?;
+        debug!("DistributedContext({}): Accepting a new key.", self.me);
+        // FIXME: panics?
+        keyring.lock().unwrap().accept(key);
         Ok(())
     }
 
+    ///
+    /// Encrypts the payloads sent from now on with the given key, while still accepting the
+    /// payloads encrypted with the previous one until the overlap window elapsed (see
+    /// [`Encryption::with_overlap`]), so that the other members can rotate their key in turn.
+    ///
+    /// Returns `Err(())` if the cluster wasn't configured with [`ClusterConfig::with_encryption`].
+    #[cfg(feature = "encryption")]
+    pub fn rotate_key(&self, key: EncryptionKey) -> Result<(), ()> {
+        let keyring = self.keyring.as_ref().ok_or(())?;
+        info!("DistributedContext({}): Rotating key.", self.me);
+        // FIXME: panics?
+        keyring.lock().unwrap().rotate(key, Instant::now());
+        Ok(())
+    }
+
+    fn encrypt(&self, payload: String) -> anyhow::Result<String> {
+        #[cfg(feature = "encryption")]
+        {
+            if let Some(keyring) = &self.keyring {
+                // FIXME: panics?
+                return Ok(keyring.lock().unwrap().encrypt(&payload)?);
+            }
+        }
+
+        Ok(payload)
+    }
+
+    fn decrypt(&self, payload: String) -> anyhow::Result<String> {
+        #[cfg(feature = "encryption")]
+        {
+            if let Some(keyring) = &self.keyring {
+                // FIXME: panics?
+                let mut keyring = keyring.lock().unwrap();
+                return Ok(keyring.decrypt(&payload, Instant::now())?);
+            }
+        }
+
+        Ok(payload)
+    }
+
+    /// Frames the payload with the sequence number of the next payload sent to `to` (if the
+    /// payloads are delivered in order) and the given message id, encrypts it and sends it.
+    ///
+    /// Returns the size of the payload and the largest one the transport can carry if it is
+    /// larger, or why it couldn't be encrypted, in which case nothing is sent.
+    fn send_framed(&self, to: Uuid, id: Uuid, payload: &str) -> Result<(), SendError> {
+        // FIXME: panics?
+        let mut sequences = self
+            .reorder
+            .as_ref()
+            .map(|_| self.sequences.lock().unwrap());
+        let seq = sequences
+            .as_mut()
+            .map(|sequences| *sequences.entry(to).or_insert(0));
+        let payload = match seq {
+            Some(seq) => self.encrypt(dedup::framed(id, &ordering::sequenced(seq, payload))),
+            None => self.encrypt(dedup::framed(id, payload)),
+        }
+        .map_err(SendError::Encryption)?;
+
+        if let Some(max) = self.oversized(&payload) {
+            return Err(SendError::TooLarge {
+                size: payload.len(),
+                max,
+            });
+        }
+
+        // NOTE: the sequence number is only used up once the payload is sent, so that the
+        //      receiver doesn't wait for a payload which was never sent.
+        if let (Some(sequences), Some(seq)) = (sequences.as_mut(), seq) {
+            sequences.insert(to, seq + 1);
+        }
+        drop(sequences);
+
+        self.send_payload(to, payload);
+        Ok(())
+    }
+
+    /// Hands the payload to the transport, or queues it if the transport can't carry it yet and
+    /// the payloads are queued.
+    fn send_payload(&self, to: Uuid, mut payload: String) {
+        let outbound = match &self.outbound {
+            Some(outbound) => outbound,
+            None => return self.cluster.send_payload(to, payload),
+        };
+
+        loop {
+            // FIXME: panics?
+            let mut queues = outbound.lock().unwrap();
+            self.flush(&mut queues, to);
+            match queues.push(to, payload) {
+                Ok(()) => return self.flush(&mut queues, to),
+                Err(blocked) => payload = blocked,
+            }
+            drop(queues);
+
+            trace!(
+                "DistributedContext({}): Waiting for the queue of Member({}) to have room.",
+                self.me,
+                to
+            );
+            thread::sleep(outbound::BLOCK_INTERVAL);
+        }
+    }
+
+    /// Hands the payloads queued for `to` to the transport, for as long as it can carry them.
+    fn flush(&self, queues: &mut OutboundQueues, to: Uuid) {
+        while self.cluster.can_send(to) {
+            match queues.pop(to) {
+                Some(payload) => self.cluster.send_payload(to, payload),
+                None => return,
+            }
+        }
+    }
+
+    /// Hands the payloads queued for every member to the transport, if they are queued.
+    fn flush_outbound(&self) {
+        if let Some(outbound) = &self.outbound {
+            // FIXME: panics?
+            let mut queues = outbound.lock().unwrap();
+            for to in queues.backed_up() {
+                self.flush(&mut queues, to);
+            }
+        }
+    }
+
+    /// Returns the size of the largest payload the transport can carry if the payload is
+    /// larger.
+    fn oversized(&self, payload: &str) -> Option<usize> {
+        self.cluster
+            .max_payload_size()
+            .filter(|max| payload.len() > *max)
+    }
+
+    /// Holds the payload until the ones `member` sent before it were received, if the payloads
+    /// are delivered in order, returning it otherwise.
+    fn reordered(&self, member: Uuid, payload: String) -> Option<String> {
+        let (seq, payload) = ordering::unsequenced(payload);
+        let (reorder, seq) = match (&self.reorder, seq) {
+            (Some(reorder), Some(seq)) => (reorder, seq),
+            _ => return Some(payload),
+        };
+
+        // FIXME: panics?
+        let released = reorder
+            .lock()
+            .unwrap()
+            .push(member, seq, payload, Instant::now());
+        trace!(
+            "DistributedContext({}): Received payload #{} from {}, releasing {}.",
+            self.me,
+            seq,
+            member,
+            released.len()
+        );
+        // FIXME: panics?
+        self.ready
+            .lock()
+            .unwrap()
+            .extend(released.into_iter().map(|payload| (member, payload)));
+
+        None
+    }
+
+    /// Skips the payloads which were waited for for longer than the gap timeout, releasing the
+    /// ones received after them.
+    fn skip_gaps(&self) {
+        let reorder = match &self.reorder {
+            Some(reorder) => reorder,
+            None => return,
+        };
+
+        let (gaps, released, policy) = {
+            // FIXME: panics?
+            let mut reorder = reorder.lock().unwrap();
+            let (gaps, released) = reorder.expire(Instant::now());
+            (gaps, released, reorder.gap_policy())
+        };
+
+        for gap in gaps {
+            match policy {
+                GapPolicy::Skip => debug!(
+                    "DistributedContext({}): Skipping {} payloads from {}.",
+                    self.me, gap.missing, gap.member
+                ),
+                GapPolicy::Surface => {
+                    warn!(
+                        "DistributedContext({}): Skipping {} payloads from {}.",
+                        self.me, gap.missing, gap.member
+                    );
+                    EVENTS.emit(SystemEvent::DeliveryGap {
+                        node: self.me,
+                        from: gap.member,
+                        missing: gap.missing,
+                    });
+                }
+            }
+        }
+
+        // FIXME: panics?
+        self.ready.lock().unwrap().extend(released);
+    }
+
+    fn decoded(&self, member: Uuid, payload: String) -> Option<ClusterMessage> {
+        match compression::decode(payload) {
+            Ok(msg) => {
+                let msg = ClusterMessage::new(Msg::tell(msg), member);
+                Some(msg.with_path(self.path_of(member)))
+            }
+            Err(e) => {
+                warn!(
+                    "DistributedContext({}): Dropping payload from {}: {}",
+                    self.me, member, e
+                );
+                None
+            }
+        }
+    }
+
+    /// The path of the messages sent by `member`, named after the name it announced.
+    fn path_of(&self, member: Uuid) -> BastionPath {
+        let metadata = if member == self.me {
+            Some(self.metadata.clone())
+        } else {
+            self.metadata_of(&member)
+        };
+        let name = metadata
+            .and_then(|mut metadata| metadata.remove(NODE_NAME_KEY))
+            // The name would break the rendering of the path otherwise.
+            .filter(|name| RemoteNode::check_name(name).is_ok())
+            .unwrap_or_else(|| member.to_string());
+
+        // FIXME: panics?
+        let addr = self
+            .members
+            .lock()
+            .unwrap()
+            .members()
+            .into_iter()
+            .find(|(id, _)| *id == member)
+            .and_then(|(_, member)| member.remote_host());
+
+        BastionPath::remote(RemoteNode::new(member, name, addr))
+    }
+
+    fn next_event(&self) -> Option<ClusterEvent> {
+        // FIXME: panics?
+        let mut events = self.events.lock().unwrap();
+        if events.is_empty() {
+            events.extend(self.cluster.try_recv_events());
+        }
+
+        events.pop_front()
+    }
+
+    fn announce_metadata(&self, to: Uuid) {
+        debug!(
+            "DistributedContext({}): Announcing metadata to Member({}).",
+            self.me, to
+        );
+        match serde_json::to_string(&self.metadata) {
+            Ok(metadata) => {
+                let payload = compression::tagged(METADATA_TAG, &metadata);
+                match self.encrypt(payload) {
+                    Ok(payload) => self.send_payload(to, payload),
+                    Err(e) => warn!(
+                        "DistributedContext({}): Couldn't encrypt metadata: {}",
+                        self.me, e
+                    ),
+                }
+            }
+            Err(e) => warn!(
+                "DistributedContext({}): Couldn't serialize metadata: {}",
+                self.me, e
+            ),
+        }
+    }
+
+    /// Stores the metadata if the payload announces it, returning whether it did.
+    fn handle_metadata(&self, member: Uuid, payload: &str) -> bool {
+        let metadata = match compression::untagged(METADATA_TAG, payload) {
+            Some(metadata) => metadata,
+            None => return false,
+        };
+
+        match serde_json::from_str(metadata) {
+            Ok(metadata) => {
+                debug!(
+                    "DistributedContext({}): Member({}) announced metadata: {:?}",
+                    self.me, member, metadata
+                );
+                // FIXME: panics?
+                self.peers_metadata.lock().unwrap().insert(member, metadata);
+            }
+            Err(e) => warn!(
+                "DistributedContext({}): Dropping metadata from {}: {}",
+                self.me, member, e
+            ),
+        }
+
+        true
+    }
+
+    /// Returns whether the payload was already received, remembering its message id otherwise.
+    fn is_duplicate(&self, id: Option<Uuid>) -> bool {
+        match id {
+            // FIXME: panics?
+            Some(id) => !self.recent_ids.lock().unwrap().insert(id),
+            None => false,
+        }
+    }
+
+    fn forget_metadata(&self, member: &Uuid) {
+        // FIXME: panics?
+        self.peers_metadata.lock().unwrap().remove(member);
+    }
+
+    fn forget_outbound(&self, member: &Uuid) {
+        if let Some(outbound) = &self.outbound {
+            // FIXME: panics?
+            outbound.lock().unwrap().forget(*member);
+        }
+    }
+
+    /// Resets the sequence numbers exchanged with a member which was removed, rejoined or
+    /// reconnected, whose own ones restart from zero if it restarted meanwhile.
+    fn forget_sequences(&self, member: &Uuid) {
+        if let Some(reorder) = &self.reorder {
+            // FIXME: panics?
+            reorder.lock().unwrap().forget(*member);
+            // FIXME: panics?
+            self.sequences.lock().unwrap().remove(member);
+        }
+    }
+
+    fn update_quorum(&self) {
+        // FIXME: panics?
+        let has_quorum = self.members.lock().unwrap().has_quorum(self.me);
+        if self.quorum.swap(has_quorum, Ordering::SeqCst) == has_quorum {
+            return;
+        }
+
+        if has_quorum {
+            info!("DistributedContext({}): Quorum regained.", self.me);
+            EVENTS.emit(SystemEvent::QuorumRegained { node: self.me });
+        } else {
+            warn!("DistributedContext({}): Quorum lost.", self.me);
+            EVENTS.emit(SystemEvent::QuorumLost { node: self.me });
+        }
+    }
+
+    /// Updates whether this member is isolated, returning the peers it can reach again if it
+    /// just rejoined the cluster.
+    fn update_isolation(&self) -> Option<Vec<Uuid>> {
+        let (isolated, reachable) = {
+            // FIXME: panics?
+            let membership = self.members.lock().unwrap();
+            (
+                membership.is_isolated(self.me),
+                membership.reachable(self.me),
+            )
+        };
+        match (self.isolated.swap(isolated, Ordering::SeqCst), isolated) {
+            (true, false) => (),
+            (false, true) => {
+                warn!(
+                    "DistributedContext({}): Isolated from every member.",
+                    self.me
+                );
+                return None;
+            }
+            _ => return None,
+        }
+
+        info!(
+            "DistributedContext({}): Rejoined the cluster through {} members.",
+            self.me,
+            reachable.len()
+        );
+        EVENTS.emit(SystemEvent::Rejoined {
+            node: self.me,
+            peers: reachable.len(),
+        });
+        Some(reachable)
+    }
+
+    fn heartbeat(&self, member: Uuid) {
+        if let Some(detectors) = &self.detectors {
+            // FIXME: panics?
+            detectors.lock().unwrap().heartbeat(member, Instant::now());
+        }
+    }
+
+    /// Considers the members suspected by the phi-accrual failure detector as down.
+    fn handle_suspected(&self) {
+        let detectors = match &self.detectors {
+            Some(detectors) => detectors,
+            None => return,
+        };
+
+        let now = Instant::now();
+        let suspected = {
+            // FIXME: panics?
+            let mut detectors = detectors.lock().unwrap();
+            let suspected = detectors.suspected(now);
+            // NOTE: the members get a new detector once they're reported alive again.
+            suspected.iter().for_each(|id| detectors.remove(id));
+            suspected
+        };
+
+        for suspected in suspected {
+            debug!(
+                "DistributedContext({}): Member({}) suspected by the failure detector.",
+                self.me, suspected
+            );
+            // FIXME: panics?
+            self.members.lock().unwrap().down(suspected, now);
+        }
+    }
+
     ///
     /// Channel that aggregates incoming cluster events to this node.
+    ///
+    /// The membership events are handled while awaiting it, which is when this member announces
+    /// its metadata to the members joining the cluster and stores the metadata they announce (see
+    /// [`ClusterConfig::with_metadata`]).
     pub async fn recv(&self) -> Result<ClusterMessage, ()> {
         debug!(
             "DistributedContext({}): Waiting to receive message.",
             self.me
         );
         loop {
-            for (members, event) in self.cluster.events.try_iter() {
+            // FIXME: panics?
+            if let Some(msg) = self.loopback.lock().unwrap().pop_front() {
+                let msg = ClusterMessage::new(Msg::tell(msg), self.me);
+                return Ok(msg.with_path(self.path_of(self.me)));
+            }
+
+            self.skip_gaps();
+            self.flush_outbound();
+            // FIXME: panics?
+            let ready = self.ready.lock().unwrap().pop_front();
+            if let Some((member, msg)) = ready {
+                match self.decoded(member, msg) {
+                    Some(msg) => return Ok(msg),
+                    None => continue,
+                }
+            }
+
+            while let Some((members, event)) = self.next_event() {
                 warn!(event = format!("{:?}", event).as_str(), "Cluster event");
                 if let ArtilleryMemberEvent::Payload(member, msg) = event {
-                    return Ok(ClusterMessage::new(Msg::tell(msg), member.host_key()));
-                }
+                    self.heartbeat(member.host_key());
+                    let msg = match self.decrypt(msg) {
+                        Ok(msg) => msg,
+                        Err(e) => {
+                            warn!(
+                                "DistributedContext({}): Dropping payload from {}: {}",
+                                self.me,
+                                member.host_key(),
+                                e
+                            );
+                            continue;
+                        }
+                    };
+                    if self.handle_metadata(member.host_key(), &msg) {
+                        continue;
+                    }
 
-                members.iter().for_each(|m| match m.state() {
-                    ArtilleryMemberState::Alive => {
-                        let _ = self.members.insert(m.host_key(), m.clone());
+                    let (id, msg) = dedup::unframed(msg);
+                    if self.is_duplicate(id) {
+                        debug!(
+                            "DistributedContext({}): Dropping duplicated payload from {}.",
+                            self.me,
+                            member.host_key()
+                        );
+                        continue;
                     }
-                    ArtilleryMemberState::Down => {
-                        let _ = self.members.remove(&m.host_key());
+
+                    let msg = match self.reordered(member.host_key(), msg) {
+                        Some(msg) => msg,
+                        None => continue,
+                    };
+                    match self.decoded(member.host_key(), msg) {
+                        Some(msg) => return Ok(msg),
+                        None => continue,
                     }
-                    _ => {}
+                }
+
+                let mut joined = Vec::new();
+                let mut removed = Vec::new();
+                {
+                    // FIXME: panics?
+                    let mut membership = self.members.lock().unwrap();
+                    members.iter().for_each(|m| match m.state() {
+                        ArtilleryMemberState::Alive => {
+                            self.heartbeat(m.host_key());
+                            if membership.alive(m.host_key(), m.clone()) {
+                                joined.push(m.host_key());
+                            }
+                        }
+                        ArtilleryMemberState::Down => {
+                            // NOTE: the peer is probed again for every reconnect attempt
+                            //      (see `poll_reconnects`), and reported as alive again once
+                            //      its link is restored.
+                            if membership.down(m.host_key(), Instant::now()) {
+                                debug!(
+                                    "DistributedContext({}): Member({}) removed.",
+                                    self.me,
+                                    m.host_key()
+                                );
+                                removed.push(m.host_key());
+                            }
+                        }
+                        _ => {}
+                    });
+                }
+
+                removed.iter().for_each(|id| {
+                    self.forget_metadata(id);
+                    self.forget_outbound(id);
+                    self.forget_sequences(id);
                 });
+                // NOTE: the members which reconnected may have restarted meanwhile.
+                joined.iter().for_each(|id| self.forget_sequences(id));
+                self.update_quorum();
+                if let Some(reachable) = self.update_isolation() {
+                    // NOTE: the peers may have forgotten this member while it was isolated.
+                    reachable.into_iter().for_each(|id| {
+                        self.forget_sequences(&id);
+                        self.announce_metadata(id);
+                    });
+                } else if !self.metadata.is_empty() {
+                    joined
+                        .into_iter()
+                        .filter(|id| *id != self.me)
+                        .for_each(|id| self.announce_metadata(id));
+                }
+            }
+
+            self.handle_suspected();
+            self.update_quorum();
+
+            let cluster = &self.cluster;
+            // FIXME: panics?
+            let failed = self
+                .members
+                .lock()
+                .unwrap()
+                .poll_reconnects(Instant::now(), |id| cluster.probe(id));
+            for failed in failed {
+                debug!(
+                    "DistributedContext({}): Member({}) removed after failing to reconnect.",
+                    self.me, failed
+                );
+                self.forget_metadata(&failed);
+                self.forget_outbound(&failed);
+                self.forget_sequences(&failed);
             }
         }
     }
 }
 
+/// The metadata key holding the name of a member, see [`ClusterConfig::with_node_name`].
+pub const NODE_NAME_KEY: &str = "node_name";
+
+/// The metadata key holding the weight of a member by default.
+pub const DEFAULT_WEIGHT_KEY: &str = "weight";
+
+///
+/// Routes payloads to the members of the cluster proportionally to their weight, so that a member
+/// of weight 4 receives four times as many payloads as a member of weight 1.
+///
+/// The weight of a member is read from the metadata it announced (see
+/// [`ClusterConfig::with_tag`]) under [`DEFAULT_WEIGHT_KEY`] by default. Members which didn't
+/// announce a valid weight get the default weight, which is 1 unless set otherwise, and members of
+/// weight 0 never receive anything.
+///
+/// The members are selected with a smooth weighted round-robin, which interleaves them instead of
+/// sending bursts of payloads to the heaviest ones.
+#[derive(Debug)]
+pub struct RemoteDispatcher {
+    weight_key: String,
+    default_weight: u32,
+    // The current weight of every member selected from so far.
+    current: Mutex<FxHashMap<Uuid, i64>>,
+}
+
+impl RemoteDispatcher {
+    ///
+    /// Creates a dispatcher reading the weights under [`DEFAULT_WEIGHT_KEY`] and giving a weight
+    /// of 1 to the members which didn't announce one.
+    pub fn new() -> Self {
+        RemoteDispatcher {
+            weight_key: DEFAULT_WEIGHT_KEY.to_string(),
+            default_weight: 1,
+            current: Mutex::new(FxHashMap::default()),
+        }
+    }
+
+    ///
+    /// Sets the metadata key the weights of the members are read from.
+    pub fn with_weight_key<K: Into<String>>(mut self, weight_key: K) -> Self {
+        self.weight_key = weight_key.into();
+        self
+    }
+
+    ///
+    /// Sets the weight of the members which didn't announce a valid one, which can be 0 to only
+    /// route payloads to the members which did.
+    pub fn with_default_weight(mut self, default_weight: u32) -> Self {
+        self.default_weight = default_weight;
+        self
+    }
+
+    ///
+    /// Selects the member the next payload should be routed to, if any member has a positive
+    /// weight.
+    pub fn select(&self, dctx: &DistributedContext) -> Option<Uuid> {
+        let weighted: Vec<(Uuid, i64)> = dctx
+            .members()
+            .iter()
+            .map(|member| member.host_key())
+            .map(|id| (id, self.weight_of(dctx, &id)))
+            .filter(|(_, weight)| *weight > 0)
+            .collect();
+        let total: i64 = weighted.iter().map(|(_, weight)| weight).sum();
+
+        // FIXME: panics?
+        let mut current = self.current.lock().unwrap();
+        current.retain(|id, _| weighted.iter().any(|(member, _)| member == id));
+
+        let mut selected: Option<(Uuid, i64)> = None;
+        for (id, weight) in weighted {
+            let current = current.entry(id).or_insert(0);
+            *current += weight;
+            if selected.map_or(true, |(_, max)| *current > max) {
+                selected = Some((id, *current));
+            }
+        }
+
+        let (id, _) = selected?;
+        trace!("RemoteDispatcher: Selected Member({}).", id);
+        *current.get_mut(&id).unwrap() -= total;
+        Some(id)
+    }
+
+    ///
+    /// Sends a fire and forget style message to the member selected with
+    /// [`RemoteDispatcher::select`], returning its node id.
+    ///
+    /// Returns `Err(msg)` if no member could be selected, or if the message is larger than the
+    /// transport can carry (see [`DistributedContext::tell`]).
+    pub fn tell<M>(&self, dctx: &DistributedContext, msg: M) -> Result<Uuid, M>
+    where
+        M: Message + AsRef<str>,
+    {
+        let to = match self.select(dctx) {
+            Some(to) => to,
+            None => return Err(msg),
+        };
+
+        dctx.tell(&to, msg).map_err(TellError::into_msg)?;
+        Ok(to)
+    }
+
+    fn weight_of(&self, dctx: &DistributedContext, member: &Uuid) -> i64 {
+        dctx.metadata_of(member)
+            .and_then(|metadata| metadata.get(&self.weight_key)?.parse::<u32>().ok())
+            .unwrap_or(self.default_weight)
+            .into()
+    }
+}
+
+impl Default for RemoteDispatcher {
+    fn default() -> Self {
+        RemoteDispatcher::new()
+    }
+}
+
 ///
 /// Creates distributed cluster actor
 pub(crate) fn cluster_actor<I, F>(
-    cluster_config: &'static ArtilleryAPClusterConfig,
+    cluster_config: ClusterConfig,
     action: I,
 ) -> Result<ChildrenRef, ()>
 where
@@ -142,24 +1461,66 @@ where
 {
     let action = Arc::new(action);
 
+    let gossip = cluster_config.gossip();
+    if let Backend::Transport { transport, .. } = &cluster_config.backend {
+        transport.configure_gossip(gossip);
+    }
+
     Bastion::spawn(move |ctx: BastionContext| {
-        let ap_cluster = Arc::new(ArtilleryAPCluster::new(cluster_config.clone()).unwrap());
-        let dctx = Arc::new(DistributedContext::new(
-            ctx,
-            ap_cluster.cluster(),
-            cluster_config.node_id,
-        ));
         let action = action.clone();
 
-        let core = async move {
-            let _ap_events = ap_cluster.clone();
+        let core = match cluster_config.backend.clone() {
+            Backend::Artillery(ap) => {
+                let mut ap = ap.clone();
+                ap.cluster_config.ping_request_host_count = gossip.fan_out;
+                ap.cluster_config.ping_interval = gossip.interval;
+                ap.cluster_config.ping_timeout = gossip.suspicion_timeout;
+                if let Some(node_id) = cluster_config.node_id {
+                    ap.node_id = node_id;
+                }
+
+                let node_id = ap.node_id;
+                let ap_cluster = Arc::new(ArtilleryAPCluster::new(ap).unwrap());
+                let dctx = Arc::new(DistributedContext::new(
+                    ctx,
+                    ap_cluster.cluster(),
+                    node_id,
+                    &cluster_config,
+                ));
+
+                async move {
+                    let _ap_events = ap_cluster.clone();
+
+                    // Detach cluster launch
+                    let cluster_handle = blocking!(ap_cluster.launch().await);
 
-            // Detach cluster launch
-            let cluster_handle = blocking!(ap_cluster.launch().await);
+                    let events_handle = blocking!(action(dctx).await);
 
-            let events_handle = blocking!(action(dctx).await);
+                    future::join(events_handle, cluster_handle).await;
+                }
+                .boxed()
+            }
+            Backend::Transport { node_id, transport } => {
+                if matches!(cluster_config.node_id, Some(configured) if configured != node_id) {
+                    warn!(
+                        "DistributedContext({}): Ignoring the configured node id, which can't be applied to a transport.",
+                        node_id
+                    );
+                }
 
-            future::join(events_handle, cluster_handle).await
+                let dctx = Arc::new(DistributedContext::new(
+                    ctx,
+                    transport,
+                    node_id,
+                    &cluster_config,
+                ));
+
+                // The transport doesn't need to be launched.
+                async move {
+                    blocking!(action(dctx).await).await;
+                }
+                .boxed()
+            }
         };
 
         async move {
@@ -0,0 +1,273 @@
+//!
+//! Optional encryption of the payloads exchanged between the members
+//! of a cluster.
+//!
+//! Every member sends its payloads encrypted with its current key and
+//! decrypts the payloads it receives with any key of its accept set,
+//! so that keys can be rotated node-by-node without restarting the
+//! cluster: every member first accepts the new key, then starts
+//! sending with it, and stops accepting its previous key once the
+//! overlap window elapsed.
+use crate::compression;
+use chacha20poly1305::aead::{Aead, NewAead};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand_core::{OsRng, RngCore};
+use std::fmt::{self, Debug, Formatter};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// The codec tag of encrypted payloads.
+const ENCRYPTED_TAG: &str = "enc";
+/// The size (in bytes) of the nonce prepended to every ciphertext.
+const NONCE_SIZE: usize = 12;
+
+/// The default duration during which a rotated key is still accepted.
+pub const DEFAULT_OVERLAP: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Clone, PartialEq, Eq)]
+/// A 256-bit key used to encrypt the payloads sent to the other
+/// members of a cluster with ChaCha20-Poly1305.
+pub struct EncryptionKey([u8; 32]);
+
+#[derive(Debug, Clone)]
+/// The encryption settings of a cluster, set with
+/// [`ClusterConfig::with_encryption`].
+///
+/// [`ClusterConfig::with_encryption`]: crate::distributed::ClusterConfig::with_encryption
+pub struct Encryption {
+    key: EncryptionKey,
+    overlap: Duration,
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum EncryptError {
+    #[error("couldn't generate a nonce: {0}")]
+    Nonce(rand_core::Error),
+    #[error("payload too large to be encrypted")]
+    TooLarge,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub(crate) enum DecryptError {
+    #[error("unencrypted payload")]
+    NotEncrypted,
+    #[error("malformed encrypted payload")]
+    Malformed,
+    #[error("payload encrypted with a key which isn't accepted")]
+    UnknownKey,
+}
+
+#[derive(Debug)]
+/// The keys of a member: the one its payloads are encrypted with,
+/// and the ones the payloads it receives can be encrypted with.
+pub(crate) struct Keyring {
+    current: EncryptionKey,
+    // The other accepted keys, with the instant they stop being
+    // accepted at if they were rotated.
+    accepted: Vec<(EncryptionKey, Option<Instant>)>,
+    overlap: Duration,
+}
+
+impl EncryptionKey {
+    /// Creates a key from its bytes, which should be generated
+    /// by a cryptographically secure random number generator and
+    /// shared with the other members of the cluster.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The 32 bytes of the key.
+    pub fn new(bytes: [u8; 32]) -> Self {
+        EncryptionKey(bytes)
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(Key::from_slice(&self.0))
+    }
+}
+
+impl Debug for EncryptionKey {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        // NOTE: keys are never logged.
+        fmt.write_str("EncryptionKey(..)")
+    }
+}
+
+impl Encryption {
+    /// Creates new encryption settings sending with the given key
+    /// and accepting a rotated key for [`DEFAULT_OVERLAP`].
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key used to encrypt and decrypt the payloads.
+    pub fn new(key: EncryptionKey) -> Self {
+        let overlap = DEFAULT_OVERLAP;
+
+        Encryption { key, overlap }
+    }
+
+    /// Sets how long a key is still accepted once it was rotated
+    /// with [`DistributedContext::rotate_key`], which should leave
+    /// enough time for the other members to rotate theirs.
+    ///
+    /// # Arguments
+    ///
+    /// * `overlap` - How long a rotated key is still accepted.
+    ///
+    /// [`DistributedContext::rotate_key`]: crate::distributed::DistributedContext::rotate_key
+    pub fn with_overlap(mut self, overlap: Duration) -> Self {
+        self.overlap = overlap;
+        self
+    }
+
+    /// Returns how long a rotated key is still accepted.
+    pub fn overlap(&self) -> Duration {
+        self.overlap
+    }
+}
+
+impl Keyring {
+    pub(crate) fn new(encryption: &Encryption) -> Self {
+        Keyring {
+            current: encryption.key.clone(),
+            accepted: Vec::new(),
+            overlap: encryption.overlap,
+        }
+    }
+
+    /// Accepts the payloads encrypted with `key` until it is
+    /// rotated, without sending with it.
+    pub(crate) fn accept(&mut self, key: EncryptionKey) {
+        if key == self.current {
+            return;
+        }
+
+        self.accepted.retain(|(accepted, _)| *accepted != key);
+        self.accepted.push((key, None));
+    }
+
+    /// Sends with `key` from now on, still accepting the previous
+    /// key during the overlap window.
+    pub(crate) fn rotate(&mut self, key: EncryptionKey, now: Instant) {
+        if key == self.current {
+            return;
+        }
+
+        self.accepted.retain(|(accepted, _)| *accepted != key);
+        let previous = std::mem::replace(&mut self.current, key);
+        self.accepted.push((previous, Some(now + self.overlap)));
+    }
+
+    pub(crate) fn encrypt(&self, payload: &str) -> Result<String, EncryptError> {
+        // NOTE: the nonces are drawn from the OS' random number
+        //      generator, which is safe for far more payloads than
+        //      a key should be used for.
+        let mut nonce = [0; NONCE_SIZE];
+        OsRng
+            .try_fill_bytes(&mut nonce)
+            .map_err(EncryptError::Nonce)?;
+        let nonce = Nonce::from_slice(&nonce);
+        // NOTE: encrypting only fails if the payload is too large
+        //      to be authenticated (more than 256 GiB).
+        let ciphertext = self
+            .current
+            .cipher()
+            .encrypt(nonce, payload.as_bytes())
+            .map_err(|_| EncryptError::TooLarge)?;
+
+        let mut body = nonce.to_vec();
+        body.extend(ciphertext);
+        Ok(compression::tagged(ENCRYPTED_TAG, &base64::encode(body)))
+    }
+
+    pub(crate) fn decrypt(&mut self, payload: &str, now: Instant) -> Result<String, DecryptError> {
+        self.accepted
+            .retain(|(_, until)| until.map_or(true, |until| now < until));
+
+        let body =
+            compression::untagged(ENCRYPTED_TAG, payload).ok_or(DecryptError::NotEncrypted)?;
+        let body = base64::decode(body).map_err(|_| DecryptError::Malformed)?;
+        if body.len() < NONCE_SIZE {
+            return Err(DecryptError::Malformed);
+        }
+
+        let (nonce, ciphertext) = body.split_at(NONCE_SIZE);
+        let nonce = Nonce::from_slice(nonce);
+        let keys = std::iter::once(&self.current).chain(self.accepted.iter().map(|(key, _)| key));
+        for key in keys {
+            if let Ok(plaintext) = key.cipher().decrypt(nonce, ciphertext) {
+                return String::from_utf8(plaintext).map_err(|_| DecryptError::Malformed);
+            }
+        }
+
+        Err(DecryptError::UnknownKey)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyring(key: u8) -> Keyring {
+        let encryption =
+            Encryption::new(EncryptionKey::new([key; 32])).with_overlap(Duration::from_secs(60));
+        Keyring::new(&encryption)
+    }
+
+    #[test]
+    fn payloads_are_encrypted() {
+        let mut sender = keyring(1);
+        let payload = "a secret payload";
+
+        let encrypted = sender.encrypt(payload).unwrap();
+        assert!(!encrypted.contains(payload));
+        assert_ne!(encrypted, sender.encrypt(payload).unwrap());
+        assert_eq!(
+            sender.decrypt(&encrypted, Instant::now()),
+            Ok(payload.to_string())
+        );
+
+        assert_eq!(
+            keyring(2).decrypt(&encrypted, Instant::now()),
+            Err(DecryptError::UnknownKey)
+        );
+        assert_eq!(
+            sender.decrypt(payload, Instant::now()),
+            Err(DecryptError::NotEncrypted)
+        );
+    }
+
+    #[test]
+    fn rotated_keys_are_accepted_during_the_overlap() {
+        let now = Instant::now();
+        let mut a = keyring(1);
+        let mut b = keyring(1);
+
+        // B accepts the new key before A rotates to it...
+        b.accept(EncryptionKey::new([2; 32]));
+        a.rotate(EncryptionKey::new([2; 32]), now);
+        assert_eq!(
+            b.decrypt(&a.encrypt("from a").unwrap(), now),
+            Ok("from a".to_string())
+        );
+        assert_eq!(
+            a.decrypt(&b.encrypt("from b").unwrap(), now),
+            Ok("from b".to_string())
+        );
+
+        // ...then rotates too.
+        b.rotate(EncryptionKey::new([2; 32]), now);
+        assert_eq!(
+            a.decrypt(&b.encrypt("from b").unwrap(), now),
+            Ok("from b".to_string())
+        );
+
+        // The previous key isn't accepted anymore once the overlap elapsed.
+        let old = keyring(1).encrypt("too late").unwrap();
+        let later = now + Duration::from_secs(61);
+        assert_eq!(a.decrypt(&old, later), Err(DecryptError::UnknownKey));
+        assert_eq!(
+            b.decrypt(&a.encrypt("from a").unwrap(), later),
+            Ok("from a".to_string())
+        );
+    }
+}
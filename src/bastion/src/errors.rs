@@ -4,14 +4,18 @@
 //! A ReceiveError may however be raised when calling try_recv() or try_recv_timeout()
 //! More errors may happen in the future.
 
+use crate::child_ref::ChildRef;
 use crate::envelope::Envelope;
 use crate::message::Msg;
+use crate::path::BastionPath;
 use crate::system::STRING_INTERNER;
 use crate::{distributor::Distributor, message::BastionMessage};
 use futures::channel::mpsc::TrySendError;
 use std::fmt::Debug;
 use std::time::Duration;
 use thiserror::Error;
+#[cfg(feature = "distributed")]
+use uuid::Uuid;
 
 #[derive(Debug)]
 /// These errors happen
@@ -26,6 +30,98 @@ pub enum ReceiveError {
     Other,
 }
 
+#[derive(Error, Debug)]
+/// `AnswerError`s occur when a [`TypedAnswer`] can't resolve to the
+/// `Result` answered by the asked child, as opposed to the
+/// application-level error that the child can answer with.
+///
+/// [`TypedAnswer`]: crate::message::TypedAnswer
+pub enum AnswerError {
+    #[error("the question was dropped without being answered.")]
+    /// The child dropped the question without answering it, or
+    /// stopped before doing so
+    Dropped,
+    #[error("no answer was received after {0:?}.")]
+    /// The child didn't answer before the timeout elapsed
+    Timeout(Duration),
+    #[error("the answer wasn't of the expected type: {0:?}")]
+    /// The child answered with a message of another type
+    UnexpectedType(Msg),
+}
+
+#[derive(Error, Debug)]
+/// `AskError`s occur when a question asked with [`ChildRef::ask`]
+/// or [`ChildRef::ask_sync`] can't resolve to an answer of the
+/// expected type
+///
+/// [`ChildRef::ask`]: crate::child_ref::ChildRef::ask
+/// [`ChildRef::ask_sync`]: crate::child_ref::ChildRef::ask_sync
+pub enum AskError {
+    #[error("couldn't send the question: {0}")]
+    /// The question couldn't be sent to the child
+    Send(SendError),
+    #[error("the question was dropped without being answered.")]
+    /// The child dropped the question without answering it, or
+    /// stopped before doing so
+    Dropped,
+    #[error("couldn't decode the answer: {0:?}")]
+    /// The child answered with a message of another type than the
+    /// expected one
+    Decode(Msg),
+    #[error("no answer was received after {0:?}.")]
+    /// The child didn't answer before the timeout elapsed
+    Timeout(Duration),
+    #[error("can't block on a question from within a child.")]
+    /// The question was asked synchronously from the future of a
+    /// child, which would have blocked one of the executor's threads
+    Blocking,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+/// Why a message of a batch sent with
+/// [`BastionContext::tell_all_or_nothing`] couldn't be enqueued
+///
+/// [`BastionContext::tell_all_or_nothing`]: crate::context::BastionContext::tell_all_or_nothing
+pub enum RejectionReason {
+    #[error("the recipient's mailbox can't admit the messages sent to it.")]
+    /// The messages of the batch sent to the recipient would have
+    /// taken its mailbox above the high-watermark of its group, see
+    /// [`Children::with_mailbox_high_watermark`]
+    ///
+    /// [`Children::with_mailbox_high_watermark`]: crate::children::Children::with_mailbox_high_watermark
+    Overloaded,
+    #[error("the recipient stopped.")]
+    /// The recipient stopped
+    Disconnected,
+    #[error("the message is {size} bytes long, above {max}.")]
+    /// The message is larger than the maximum message size, see
+    /// [`Config::with_max_message_size`]
+    ///
+    /// [`Config::with_max_message_size`]: crate::config::Config::with_max_message_size
+    MessageTooLarge {
+        /// The size of the message, in bytes
+        size: usize,
+        /// The largest message allowed, in bytes
+        max: usize,
+    },
+}
+
+#[derive(Debug)]
+/// A message of a batch sent with
+/// [`BastionContext::tell_all_or_nothing`], handed back without
+/// having been enqueued because the batch was rejected
+///
+/// [`BastionContext::tell_all_or_nothing`]: crate::context::BastionContext::tell_all_or_nothing
+pub struct RejectedMessage<M> {
+    /// The child the message was sent to
+    pub to: ChildRef,
+    /// The message
+    pub msg: M,
+    /// Why the message couldn't be enqueued, or `None` if it could
+    /// have been but was rejected along with the rest of the batch
+    pub reason: Option<RejectionReason>,
+}
+
 #[derive(Error, Debug)]
 /// `SendError`s occur when a message couldn't be dispatched through a distributor
 pub enum SendError {
@@ -35,6 +131,36 @@ pub enum SendError {
     #[error("couldn't send message. Channel is Full.")]
     /// Channel is full, can't send a message
     Full(Msg),
+    #[error("couldn't send message. Recipient is overloaded.")]
+    /// The recipient's mailbox is above its high-watermark, see
+    /// [`Children::with_mailbox_high_watermark`]
+    ///
+    /// [`Children::with_mailbox_high_watermark`]: crate::children::Children::with_mailbox_high_watermark
+    Overloaded(Msg),
+    #[error("couldn't send message. Message is {size} bytes long, above the {max} bytes allowed.")]
+    /// The message is larger than the maximum message size the system
+    /// was configured with, see [`Config::with_max_message_size`]
+    ///
+    /// [`Config::with_max_message_size`]: crate::config::Config::with_max_message_size
+    MessageTooLarge {
+        /// The message which wasn't sent
+        msg: Msg,
+        /// The size of the message, in bytes
+        size: usize,
+        /// The maximum size of a message, in bytes
+        max: usize,
+    },
+    #[error("couldn't ask message. {max} asks are already outstanding.")]
+    /// As many asks as the sender's group allows are still waiting
+    /// for their answer, see [`Children::with_max_outstanding_asks`]
+    ///
+    /// [`Children::with_max_outstanding_asks`]: crate::children::Children::with_max_outstanding_asks
+    TooManyAsks {
+        /// The message which wasn't sent
+        msg: Msg,
+        /// The maximum amount of outstanding asks
+        max: usize,
+    },
     #[error("couldn't send a message I should have not sent. {0}")]
     /// This error is returned when we try to send a message
     /// that is not a BastionMessage::Message variant
@@ -47,6 +173,82 @@ pub enum SendError {
     EmptyRecipient,
 }
 
+#[derive(Error, Debug)]
+/// `StartError`s occur when the system refuses to start, see
+/// [`Bastion::try_start`]
+///
+/// [`Bastion::try_start`]: crate::Bastion::try_start
+pub enum StartError {
+    #[error("the dependencies between supervisors form a cycle: {}", format_cycle(.0))]
+    /// The dependencies declared with [`SupervisorRef::depends_on`]
+    /// form a cycle, going through the supervisors with the given
+    /// paths, each of them depending on the next one and the last one
+    /// on the first one
+    ///
+    /// [`SupervisorRef::depends_on`]: crate::supervisor::SupervisorRef::depends_on
+    DependencyCycle(Vec<BastionPath>),
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+/// `SpecError`s occur when a supervision tree can't be built from a
+/// spec with [`Bastion::from_spec`], or a running tree can't be
+/// described by one with [`TopologySnapshot::to_spec`]
+///
+/// [`Bastion::from_spec`]: crate::Bastion::from_spec
+/// [`TopologySnapshot::to_spec`]: crate::topology::TopologySnapshot::to_spec
+pub enum SpecError {
+    #[error("no exec closure was registered with the name {0:?}.")]
+    /// The spec references an exec closure by a name none was
+    /// registered with, see [`Bastion::register_exec`]
+    ///
+    /// [`Bastion::register_exec`]: crate::Bastion::register_exec
+    UnknownExec(String),
+    #[error("the children group {0} wasn't built from a spec.")]
+    /// The children group with the given identifier wasn't built from
+    /// a spec, so its exec closure can't be referenced by name
+    UnregisteredExec(String),
+    #[error("couldn't launch the supervisors of the spec.")]
+    /// The supervisors of the spec couldn't be launched, e.g. because
+    /// the system wasn't initialized
+    Launch,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+/// `PathError`s occur when a [`BastionPath`] or a [`RemoteNode`]
+/// can't be built from raw components with [`BastionPath::try_new`]
+/// or [`RemoteNode::try_new`], because they wouldn't render to a
+/// path that can be told apart from its components, or when a
+/// [`RemoteNode`] can't be parsed with [`RemoteNode::parse`]
+///
+/// [`RemoteNode`]: crate::path::RemoteNode
+/// [`BastionPath::try_new`]: crate::path::BastionPath::try_new
+/// [`RemoteNode::try_new`]: crate::path::RemoteNode::try_new
+/// [`RemoteNode::parse`]: crate::path::RemoteNode::parse
+pub enum PathError {
+    #[error("{0:?} isn't a valid node name.")]
+    /// The name of the node is empty, or contains a `@`, a `/`, a
+    /// whitespace or a control character
+    InvalidNodeName(String),
+    #[error("{0:?} isn't a valid node address.")]
+    /// The address of the node isn't a socket address, e.g. an IPv6
+    /// address without brackets around it
+    InvalidNodeAddr(String),
+    #[error("the path's elements are misplaced: {0}.")]
+    /// An element of the path can't be nested in the previous one,
+    /// e.g. a child outside of a children group or a supervisor
+    /// within one
+    MisplacedElement(String),
+}
+
+fn format_cycle(cycle: &[BastionPath]) -> String {
+    cycle
+        .iter()
+        .chain(cycle.first())
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
 impl From<TrySendError<Envelope>> for SendError {
     fn from(tse: TrySendError<Envelope>) -> Self {
         let is_disconnected = tse.is_disconnected();
@@ -68,3 +270,60 @@ impl From<Distributor> for SendError {
         Self::NoDistributor(STRING_INTERNER.resolve(distributor.interned()).to_string())
     }
 }
+
+#[cfg(feature = "distributed")]
+#[derive(Error, Debug)]
+/// `TellError`s occur when a message couldn't be sent to a member of a
+/// cluster with [`DistributedContext::tell`]
+///
+/// [`DistributedContext::tell`]: crate::distributed::DistributedContext::tell
+pub enum TellError<M> {
+    #[error("couldn't send message. Payload is {size} bytes long, above the {max} bytes the transport can carry.")]
+    /// Once compressed, framed and encrypted, the message is larger
+    /// than the transport can carry in one datagram (see
+    /// [`ClusterTransport::max_payload_size`])
+    ///
+    /// [`ClusterTransport::max_payload_size`]: crate::transport::ClusterTransport::max_payload_size
+    MessageTooLarge {
+        /// The message which wasn't sent
+        msg: M,
+        /// The size of the payload, in bytes
+        size: usize,
+        /// The largest payload the transport can carry, in bytes
+        max: usize,
+    },
+    #[error("couldn't send message. Member {member} isn't part of the cluster.")]
+    /// The member the message was sent to isn't one of the members
+    /// this member knows about (see [`DistributedContext::try_tell`])
+    ///
+    /// [`DistributedContext::try_tell`]: crate::distributed::DistributedContext::try_tell
+    UnknownMember {
+        /// The message which wasn't sent
+        msg: M,
+        /// The node id the message was sent to
+        member: Uuid,
+    },
+    #[error("couldn't send message. The payload couldn't be encrypted: {reason}")]
+    /// The payload couldn't be encrypted (see
+    /// [`ClusterConfig::with_encryption`])
+    ///
+    /// [`ClusterConfig::with_encryption`]: crate::distributed::ClusterConfig::with_encryption
+    EncryptionFailed {
+        /// The message which wasn't sent
+        msg: M,
+        /// Why the payload couldn't be encrypted
+        reason: String,
+    },
+}
+
+#[cfg(feature = "distributed")]
+impl<M> TellError<M> {
+    /// Returns the message which wasn't sent.
+    pub fn into_msg(self) -> M {
+        match self {
+            TellError::MessageTooLarge { msg, .. } => msg,
+            TellError::UnknownMember { msg, .. } => msg,
+            TellError::EncryptionFailed { msg, .. } => msg,
+        }
+    }
+}
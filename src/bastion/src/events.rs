@@ -0,0 +1,325 @@
+//!
+//! System-wide lifecycle events that can be observed through
+//! [`Bastion::event_stream`].
+//!
+//! [`Bastion::event_stream`]: crate::Bastion::event_stream
+use crate::context::BastionId;
+use crate::path::BastionPath;
+use crate::supervisor::FaultReason;
+use futures::task::AtomicWaker;
+use futures::Stream;
+use once_cell::sync::Lazy;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, Weak};
+use std::task::{Context, Poll};
+use std::time::SystemTime;
+#[cfg(feature = "distributed")]
+use uuid::Uuid;
+
+/// The maximum amount of events buffered for a single subscriber
+/// before the oldest ones start getting dropped.
+pub(crate) const EVENT_STREAM_CAPACITY: usize = 1024;
+
+pub(crate) static EVENTS: Lazy<EventBus> = Lazy::new(EventBus::new);
+
+#[derive(Debug, Clone)]
+/// A lifecycle event emitted by the system whenever one of its
+/// supervisors or children changes state.
+pub enum SystemEvent {
+    /// A supervisor was launched.
+    SupervisorLaunched {
+        /// The identifier of the supervisor.
+        id: BastionId,
+    },
+    /// A supervisor faulted and is going to be restarted.
+    SupervisorFaulted {
+        /// The identifier of the supervisor.
+        id: BastionId,
+    },
+    /// A faulted supervisor was restarted.
+    SupervisorRestarted {
+        /// The identifier of the supervisor.
+        id: BastionId,
+    },
+    /// A supervisor stopped.
+    SupervisorStopped {
+        /// The identifier of the supervisor.
+        id: BastionId,
+    },
+    /// A child faulted, either by returning an error or by panicking.
+    ChildFaulted {
+        /// The identifier of the child.
+        id: BastionId,
+        /// The identifier of the children group of the child.
+        parent_id: BastionId,
+        /// Why the child faulted, including its panic message if it
+        /// panicked.
+        reason: FaultReason,
+    },
+    /// A faulted child was restarted.
+    ChildRestarted {
+        /// The identifier of the new child.
+        id: BastionId,
+        /// The identifier of the child that was replaced.
+        old_id: BastionId,
+    },
+    /// The circuit breaker of a child opened: the child won't be
+    /// restarted until its cooldown elapsed.
+    CircuitOpened {
+        /// The identifier of the child.
+        id: BastionId,
+    },
+    /// The circuit breaker of a child became half-open: the child is
+    /// restarted to probe its recovery.
+    CircuitHalfOpened {
+        /// The identifier of the child.
+        id: BastionId,
+    },
+    /// The circuit breaker of a child closed after a successful probe.
+    CircuitClosed {
+        /// The identifier of the child.
+        id: BastionId,
+    },
+    /// A child stopped.
+    ChildStopped {
+        /// The identifier of the child.
+        id: BastionId,
+    },
+    /// A message broadcasted to a children group was enqueued in the
+    /// mailbox of one of its elements, see
+    /// [`Children::with_delivery_receipts`].
+    ///
+    /// [`Children::with_delivery_receipts`]: crate::children::Children::with_delivery_receipts
+    BroadcastDelivered {
+        /// The identifier of the children group.
+        group: BastionId,
+        /// The sequence number of the broadcast within the group,
+        /// shared by all the receipts of the same broadcast.
+        broadcast: u64,
+        /// The path of the element.
+        path: Arc<BastionPath>,
+        /// When the message was enqueued.
+        at: SystemTime,
+    },
+    /// A message was received by the dead letters children group.
+    DeadLetter {
+        /// The path of the sender of the message.
+        sender: Arc<BastionPath>,
+    },
+    #[cfg(feature = "distributed")]
+    /// A cluster member stopped seeing a majority of the members it
+    /// ever knew, see [`DistributedContext::has_quorum`].
+    ///
+    /// [`DistributedContext::has_quorum`]: crate::distributed::DistributedContext::has_quorum
+    QuorumLost {
+        /// The node id of the member.
+        node: Uuid,
+    },
+    #[cfg(feature = "distributed")]
+    /// A cluster member sees a majority of the members it ever knew
+    /// again, see [`DistributedContext::has_quorum`].
+    ///
+    /// [`DistributedContext::has_quorum`]: crate::distributed::DistributedContext::has_quorum
+    QuorumRegained {
+        /// The node id of the member.
+        node: Uuid,
+    },
+    #[cfg(feature = "distributed")]
+    /// A cluster member which couldn't reach any of its peers reached
+    /// some of them again, re-announced itself to them and merged
+    /// their view of the membership, see
+    /// [`DistributedContext::is_isolated`].
+    ///
+    /// [`DistributedContext::is_isolated`]: crate::distributed::DistributedContext::is_isolated
+    Rejoined {
+        /// The node id of the member.
+        node: Uuid,
+        /// The amount of peers the member reached again.
+        peers: usize,
+    },
+    #[cfg(feature = "distributed")]
+    /// A cluster member skipped payloads it waited for for longer than
+    /// the gap timeout of its ordered delivery, see
+    /// [`GapPolicy::Surface`].
+    ///
+    /// [`GapPolicy::Surface`]: crate::ordering::GapPolicy::Surface
+    DeliveryGap {
+        /// The node id of the member.
+        node: Uuid,
+        /// The node id of the member which sent the skipped payloads.
+        from: Uuid,
+        /// The amount of skipped payloads.
+        missing: u64,
+    },
+}
+
+#[derive(Debug)]
+pub(crate) struct EventBus {
+    subscribers: Mutex<Vec<Weak<Subscription>>>,
+}
+
+#[derive(Debug)]
+struct Subscription {
+    events: Mutex<VecDeque<SystemEvent>>,
+    capacity: usize,
+    waker: AtomicWaker,
+}
+
+#[derive(Debug)]
+/// A stream of [`SystemEvent`]s, created by calling
+/// [`Bastion::event_stream`].
+///
+/// Every stream receives all the events emitted after its creation.
+/// If a stream is not polled fast enough, its oldest buffered events
+/// are dropped instead of slowing down the system.
+///
+/// [`Bastion::event_stream`]: crate::Bastion::event_stream
+pub struct EventStream {
+    subscription: Arc<Subscription>,
+}
+
+impl EventBus {
+    fn new() -> Self {
+        let subscribers = Mutex::new(Vec::new());
+
+        EventBus { subscribers }
+    }
+
+    pub(crate) fn subscribe(&self, capacity: usize) -> EventStream {
+        let subscription = Arc::new(Subscription {
+            events: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+            waker: AtomicWaker::new(),
+        });
+
+        // FIXME: panics?
+        self.subscribers
+            .lock()
+            .unwrap()
+            .push(Arc::downgrade(&subscription));
+
+        EventStream { subscription }
+    }
+
+    pub(crate) fn emit(&self, event: SystemEvent) {
+        // FIXME: panics?
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers.retain(|subscriber| match subscriber.upgrade() {
+            Some(subscription) => {
+                subscription.push(event.clone());
+                true
+            }
+            None => false,
+        });
+    }
+}
+
+impl Subscription {
+    fn push(&self, event: SystemEvent) {
+        {
+            // FIXME: panics?
+            let mut events = self.events.lock().unwrap();
+            if events.len() >= self.capacity {
+                events.pop_front();
+            }
+
+            events.push_back(event);
+        }
+
+        self.waker.wake();
+    }
+
+    fn pop(&self) -> Option<SystemEvent> {
+        // FIXME: panics?
+        self.events.lock().unwrap().pop_front()
+    }
+}
+
+impl Stream for EventStream {
+    type Item = SystemEvent;
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Option<Self::Item>> {
+        if let Some(event) = self.subscription.pop() {
+            return Poll::Ready(Some(event));
+        }
+
+        self.subscription.waker.register(ctx.waker());
+
+        // The event might have been pushed before the waker was registered.
+        match self.subscription.pop() {
+            Some(event) => Poll::Ready(Some(event)),
+            None => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EventBus, SystemEvent};
+    use crate::context::BastionId;
+    use futures::executor;
+    use futures::poll;
+    use futures::prelude::*;
+    use std::task::Poll;
+
+    fn stopped(id: &BastionId) -> SystemEvent {
+        SystemEvent::ChildStopped { id: id.clone() }
+    }
+
+    #[test]
+    fn every_subscriber_receives_every_event() {
+        let bus = EventBus::new();
+        let streams = vec![bus.subscribe(8), bus.subscribe(8)];
+
+        let id = BastionId::new();
+        bus.emit(stopped(&id));
+
+        executor::block_on(async {
+            for mut stream in streams {
+                match poll!(stream.next()) {
+                    Poll::Ready(Some(SystemEvent::ChildStopped { id: received })) => {
+                        assert_eq!(received, id)
+                    }
+                    _ => panic!(),
+                }
+
+                assert!(poll!(stream.next()).is_pending());
+            }
+        });
+    }
+
+    #[test]
+    fn slow_subscriber_drops_oldest_events() {
+        let bus = EventBus::new();
+        let mut stream = bus.subscribe(2);
+
+        let ids = (0..3).map(|_| BastionId::new()).collect::<Vec<_>>();
+        for id in &ids {
+            bus.emit(stopped(id));
+        }
+
+        executor::block_on(async {
+            for id in &ids[1..] {
+                match poll!(stream.next()) {
+                    Poll::Ready(Some(SystemEvent::ChildStopped { id: received })) => {
+                        assert_eq!(&received, id)
+                    }
+                    _ => panic!(),
+                }
+            }
+
+            assert!(poll!(stream.next()).is_pending());
+        });
+    }
+
+    #[test]
+    fn dropped_subscribers_are_removed() {
+        let bus = EventBus::new();
+        let stream = bus.subscribe(8);
+        drop(stream);
+
+        bus.emit(stopped(&BastionId::new()));
+        assert!(bus.subscribers.lock().unwrap().is_empty());
+    }
+}
@@ -1,8 +1,215 @@
 //! A module that exposes the functions used under the hoods from `bastion`s macros: `spawn!`, `run!`
 //! and `blocking!`.
+use crate::bulkhead::Bulkhead;
+use crate::config::Config;
+use crate::system::CONFIG;
+#[cfg(feature = "tokio-runtime")]
+use lightproc::lightproc::LightProc;
 pub use lightproc::proc_stack::ProcStack;
 use lightproc::recoverable_handle::RecoverableHandle;
+use once_cell::sync::Lazy;
+use std::cell::Cell;
 use std::future::Future;
+use std::sync::Arc;
+use tracing::{debug, trace};
+
+// NOTE: this is first accessed when the system is initialized, which is
+//      when the runtime used by all of bastion's tasks is chosen.
+static SPAWNER: Lazy<RuntimeSpawner> = Lazy::new(|| {
+    #[cfg(feature = "tokio-runtime")]
+    if let Some(spawner) = CONFIG.get().and_then(Config::tokio_spawner) {
+        return RuntimeSpawner::Tokio(spawner.clone());
+    }
+
+    RuntimeSpawner::Pool(PoolSpawner)
+});
+
+// NOTE: this is first accessed when the first blocking task is spawned,
+//      which should happen once the system was initialized.
+static BLOCKING_SLOTS: Lazy<Option<Arc<Bulkhead>>> = Lazy::new(|| {
+    CONFIG
+        .get()
+        .and_then(Config::blocking_pool_size)
+        .map(|size| Arc::new(Bulkhead::new(size)))
+});
+
+thread_local! {
+    // Whether this thread is currently polling the future of a child,
+    // in which case blocking it would stall the executor.
+    static POLLING_CHILD: Cell<bool> = Cell::new(false);
+}
+
+pub(crate) fn spawner() -> &'static RuntimeSpawner {
+    &SPAWNER
+}
+
+/// Resets whether the current thread is polling the future of a
+/// child once dropped, even if the poll panicked.
+struct PollingChild(bool);
+
+impl Drop for PollingChild {
+    fn drop(&mut self) {
+        POLLING_CHILD.with(|polling| polling.set(self.0));
+    }
+}
+
+/// Calls `poll`, marking the current thread as polling the future of
+/// a child until it returns.
+pub(crate) fn polling_child<T>(poll: impl FnOnce() -> T) -> T {
+    let _guard = PollingChild(POLLING_CHILD.with(|polling| polling.replace(true)));
+    poll()
+}
+
+/// Returns whether the current thread is polling the future of a
+/// child, and thus shouldn't be blocked.
+pub(crate) fn is_polling_child() -> bool {
+    POLLING_CHILD.with(Cell::get)
+}
+
+/// An abstraction over the runtime onto which bastion spawns its
+/// tasks (the system, supervisors, children groups and children, but
+/// also the futures given to [`spawn`] and [`blocking`]).
+///
+/// The implementation used is [`PoolSpawner`] by default, and a
+/// [`TokioSpawner`] when the `tokio-runtime` feature is enabled and
+/// one was given to [`Config::with_tokio_spawner`], in which case
+/// bastion's tasks are spawned onto its Tokio runtime.
+///
+/// [`Config::with_tokio_spawner`]: crate::config::Config::with_tokio_spawner
+pub trait Spawner: Send + Sync + 'static {
+    /// Spawns a future onto the runtime and returns its handle.
+    ///
+    /// # Arguments
+    ///
+    /// * `future` - The future to spawn.
+    /// * `stack` - The [`ProcStack`] of the spawned process.
+    fn spawn<F, T>(&self, future: F, stack: ProcStack) -> RecoverableHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static;
+
+    /// Spawns a future that might block the thread it runs on onto
+    /// the runtime and returns its handle.
+    ///
+    /// # Arguments
+    ///
+    /// * `future` - The future to spawn.
+    /// * `stack` - The [`ProcStack`] of the spawned process.
+    fn spawn_blocking<F, T>(&self, future: F, stack: ProcStack) -> RecoverableHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static;
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+/// A [`Spawner`] spawning futures onto `bastion-executor`'s pools.
+pub struct PoolSpawner;
+
+#[derive(Debug, Clone)]
+/// The [`Spawner`] selected when the system was initialized.
+pub(crate) enum RuntimeSpawner {
+    Pool(PoolSpawner),
+    #[cfg(feature = "tokio-runtime")]
+    Tokio(TokioSpawner),
+}
+
+impl Spawner for RuntimeSpawner {
+    fn spawn<F, T>(&self, future: F, stack: ProcStack) -> RecoverableHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        match self {
+            RuntimeSpawner::Pool(spawner) => spawner.spawn(future, stack),
+            #[cfg(feature = "tokio-runtime")]
+            RuntimeSpawner::Tokio(spawner) => spawner.spawn(future, stack),
+        }
+    }
+
+    fn spawn_blocking<F, T>(&self, future: F, stack: ProcStack) -> RecoverableHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        match self {
+            RuntimeSpawner::Pool(spawner) => spawner.spawn_blocking(future, stack),
+            #[cfg(feature = "tokio-runtime")]
+            RuntimeSpawner::Tokio(spawner) => spawner.spawn_blocking(future, stack),
+        }
+    }
+}
+
+impl Spawner for PoolSpawner {
+    fn spawn<F, T>(&self, future: F, stack: ProcStack) -> RecoverableHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        bastion_executor::pool::spawn(future, stack)
+    }
+
+    fn spawn_blocking<F, T>(&self, future: F, stack: ProcStack) -> RecoverableHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        bastion_executor::blocking::spawn_blocking(future, stack)
+    }
+}
+
+#[cfg(feature = "tokio-runtime")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "tokio-runtime")))]
+#[derive(Debug, Clone)]
+/// A [`Spawner`] spawning futures onto a Tokio runtime.
+pub struct TokioSpawner {
+    handle: tokio::runtime::Handle,
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl TokioSpawner {
+    /// Creates a new `TokioSpawner` spawning futures onto the runtime
+    /// the given handle belongs to.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the Tokio runtime to spawn onto.
+    pub fn new(handle: tokio::runtime::Handle) -> Self {
+        TokioSpawner { handle }
+    }
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl Spawner for TokioSpawner {
+    fn spawn<F, T>(&self, future: F, stack: ProcStack) -> RecoverableHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let handle = self.handle.clone();
+        let schedule = move |proc: LightProc| {
+            handle.spawn(async move { proc.run() });
+        };
+
+        let (proc, handle) = LightProc::recoverable(future, schedule, stack);
+        proc.schedule();
+        handle
+    }
+
+    fn spawn_blocking<F, T>(&self, future: F, stack: ProcStack) -> RecoverableHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let handle = self.handle.clone();
+        let schedule = move |proc: LightProc| {
+            handle.spawn_blocking(move || proc.run());
+        };
+
+        let (proc, handle) = LightProc::recoverable(future, schedule, stack);
+        proc.schedule();
+        handle
+    }
+}
 
 /// Spawns a blocking task, which will run on the blocking thread pool,
 /// and returns the handle.
@@ -33,7 +240,65 @@ where
     F: Future<Output = R> + Send + 'static,
     R: Send + 'static,
 {
-    bastion_executor::blocking::spawn_blocking(future, lightproc::proc_stack::ProcStack::default())
+    blocking_named("blocking", future)
+}
+
+/// Spawns a blocking task labelled with the given name, which will
+/// run on the blocking thread pool, and returns the handle.
+///
+/// The label is attached to the traces emitted when the task waits
+/// for a slot of the blocking pool (see
+/// [`Config::with_blocking_pool_size`]), starts and completes.
+///
+/// # Arguments
+///
+/// * `label` - The name of the task, used to observe it.
+/// * `future` - The blocking future to run.
+///
+/// # Example
+/// ```
+/// # use std::{thread, time};
+/// # #[cfg(feature = "tokio-runtime")]
+/// # #[tokio::main]
+/// # async fn main() {
+/// #    run();    
+/// # }
+/// #
+/// # #[cfg(not(feature = "tokio-runtime"))]
+/// # fn main() {
+/// #    run();    
+/// # }
+/// #
+/// # fn run() {
+/// use bastion::executor::blocking_named;
+/// let task = blocking_named("outbound-loop", async move {
+///     thread::sleep(time::Duration::from_millis(3000));
+/// });
+/// # }
+/// ```
+pub fn blocking_named<F, R>(label: &str, future: F) -> RecoverableHandle<R>
+where
+    F: Future<Output = R> + Send + 'static,
+    R: Send + 'static,
+{
+    let label = label.to_string();
+    let bounded = async move {
+        // NOTE: waiting for a slot doesn't hold a thread of the pool.
+        let _permit = match &*BLOCKING_SLOTS {
+            Some(slots) => {
+                trace!("Blocking({}): Waiting for a slot.", label);
+                Some(slots.acquire().await)
+            }
+            None => None,
+        };
+
+        debug!("Blocking({}): Running.", label);
+        let output = future.await;
+        debug!("Blocking({}): Completed.", label);
+        output
+    };
+
+    spawner().spawn_blocking(bounded, ProcStack::default())
 }
 
 /// Block the current thread until passed
@@ -108,5 +373,5 @@ where
     F: Future<Output = T> + Send + 'static,
     T: Send + 'static,
 {
-    bastion_executor::pool::spawn(future, lightproc::proc_stack::ProcStack::default())
+    spawner().spawn(future, ProcStack::default())
 }
@@ -0,0 +1,330 @@
+//!
+//! A phi-accrual failure detector, which adapts the moment a member of a
+//! cluster is suspected to be down to the jitter observed on its
+//! heartbeats.
+//!
+//! Instead of a binary up/down answer, the detector computes a suspicion
+//! level, `phi`, from the history of the heartbeats' inter-arrival times.
+//! A `phi` of `1` means that there is about a 10% chance that considering
+//! the member down is a mistake, a `phi` of `2` about a 1% chance, a `phi`
+//! of `3` about a 0.1% chance, and so on.
+use fxhash::FxHashMap;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq)]
+/// The configuration of the phi-accrual failure detector of a cluster,
+/// set with [`ClusterConfig::with_phi_accrual`].
+///
+/// [`ClusterConfig::with_phi_accrual`]: crate::distributed::ClusterConfig::with_phi_accrual
+pub struct PhiAccrualConfig {
+    threshold: f64,
+    max_samples: usize,
+    min_std_deviation: Duration,
+    first_heartbeat_estimate: Duration,
+}
+
+#[derive(Debug, Clone)]
+/// A phi-accrual failure detector, monitoring a single member.
+pub struct PhiAccrualDetector {
+    config: PhiAccrualConfig,
+    // The inter-arrival times of the last heartbeats, in milliseconds.
+    intervals: VecDeque<f64>,
+    last_heartbeat: Option<Instant>,
+}
+
+#[derive(Debug)]
+pub(crate) struct FailureDetectors {
+    config: PhiAccrualConfig,
+    detectors: FxHashMap<Uuid, PhiAccrualDetector>,
+}
+
+impl PhiAccrualConfig {
+    /// Creates a configuration suspecting members once their `phi`
+    /// exceeds `8`, which is what Akka and Cassandra use by default.
+    pub fn new() -> Self {
+        PhiAccrualConfig {
+            threshold: 8.0,
+            max_samples: 1000,
+            min_std_deviation: Duration::from_millis(100),
+            first_heartbeat_estimate: Duration::from_secs(1),
+        }
+    }
+
+    /// Sets the `phi` above which a member is considered down.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - The suspicion level above which a member is
+    ///     considered down.
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Sets the amount of inter-arrival times kept in the history of
+    /// every member.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_samples` - The size of the history.
+    pub fn with_max_samples(mut self, max_samples: usize) -> Self {
+        self.max_samples = max_samples.max(1);
+        self
+    }
+
+    /// Sets the minimum standard deviation used when computing `phi`,
+    /// so that perfectly regular heartbeats don't make the detector
+    /// overly sensitive.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_std_deviation` - The minimum standard deviation.
+    pub fn with_min_std_deviation(mut self, min_std_deviation: Duration) -> Self {
+        self.min_std_deviation = min_std_deviation;
+        self
+    }
+
+    /// Sets the expected interval between heartbeats, used until the
+    /// first inter-arrival time of a member is known.
+    ///
+    /// # Arguments
+    ///
+    /// * `first_heartbeat_estimate` - The expected interval between
+    ///     heartbeats.
+    pub fn with_first_heartbeat_estimate(mut self, first_heartbeat_estimate: Duration) -> Self {
+        self.first_heartbeat_estimate = first_heartbeat_estimate;
+        self
+    }
+
+    /// Returns the `phi` above which a member is considered down.
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+}
+
+impl Default for PhiAccrualConfig {
+    fn default() -> Self {
+        PhiAccrualConfig::new()
+    }
+}
+
+impl PhiAccrualDetector {
+    /// Creates a detector which didn't receive any heartbeat yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration of the detector.
+    pub fn new(config: PhiAccrualConfig) -> Self {
+        let intervals = VecDeque::with_capacity(config.max_samples);
+
+        PhiAccrualDetector {
+            config,
+            intervals,
+            last_heartbeat: None,
+        }
+    }
+
+    /// Records a heartbeat of the monitored member.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - When the heartbeat arrived.
+    pub fn heartbeat(&mut self, now: Instant) {
+        let interval = match self.last_heartbeat {
+            Some(last_heartbeat) => millis(now.saturating_duration_since(last_heartbeat)),
+            // NOTE: the estimate is seeded as two samples so that the
+            //      standard deviation isn't zero.
+            None => {
+                let estimate = millis(self.config.first_heartbeat_estimate);
+                self.push_interval(estimate - estimate / 4.0);
+                estimate + estimate / 4.0
+            }
+        };
+
+        self.push_interval(interval);
+        self.last_heartbeat = Some(now);
+    }
+
+    /// Returns the suspicion level of the monitored member, or `0` if
+    /// it didn't send any heartbeat yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - When the suspicion level is computed.
+    pub fn phi(&self, now: Instant) -> f64 {
+        let last_heartbeat = match self.last_heartbeat {
+            Some(last_heartbeat) => last_heartbeat,
+            None => return 0.0,
+        };
+
+        let elapsed = millis(now.saturating_duration_since(last_heartbeat));
+        let samples = self.intervals.len() as f64;
+        let mean = self.intervals.iter().sum::<f64>() / samples;
+        let variance = self
+            .intervals
+            .iter()
+            .map(|interval| (interval - mean).powi(2))
+            .sum::<f64>()
+            / samples;
+        let std_deviation = variance.sqrt().max(millis(self.config.min_std_deviation));
+
+        // A logistic approximation of the normal distribution's
+        // cumulative distribution function.
+        let y = (elapsed - mean) / std_deviation;
+        let e = (-y * (1.5976 + 0.070566 * y * y)).exp();
+        if elapsed > mean {
+            -(e / (1.0 + e)).log10()
+        } else {
+            -(1.0 - 1.0 / (1.0 + e)).log10()
+        }
+    }
+
+    /// Returns whether the monitored member is considered available,
+    /// which is the case as long as its suspicion level doesn't exceed
+    /// the configured threshold.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - When the availability is checked.
+    pub fn is_available(&self, now: Instant) -> bool {
+        self.phi(now) <= self.config.threshold
+    }
+
+    fn push_interval(&mut self, interval: f64) {
+        if self.intervals.len() >= self.config.max_samples {
+            self.intervals.pop_front();
+        }
+
+        self.intervals.push_back(interval);
+    }
+}
+
+impl FailureDetectors {
+    pub(crate) fn new(config: PhiAccrualConfig) -> Self {
+        FailureDetectors {
+            config,
+            detectors: FxHashMap::default(),
+        }
+    }
+
+    pub(crate) fn heartbeat(&mut self, id: Uuid, now: Instant) {
+        let config = &self.config;
+        self.detectors
+            .entry(id)
+            .or_insert_with(|| PhiAccrualDetector::new(config.clone()))
+            .heartbeat(now);
+    }
+
+    pub(crate) fn remove(&mut self, id: &Uuid) {
+        self.detectors.remove(id);
+    }
+
+    /// Returns the members whose suspicion level exceeds the threshold.
+    pub(crate) fn suspected(&self, now: Instant) -> Vec<Uuid> {
+        self.detectors
+            .iter()
+            .filter(|(_, detector)| !detector.is_available(now))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FailureDetectors, PhiAccrualConfig, PhiAccrualDetector};
+    use std::time::{Duration, Instant};
+    use uuid::Uuid;
+
+    fn config() -> PhiAccrualConfig {
+        PhiAccrualConfig::new()
+            .with_threshold(8.0)
+            .with_max_samples(10)
+            .with_first_heartbeat_estimate(Duration::from_secs(1))
+    }
+
+    // Feeds heartbeats every second, returning when the last one arrived.
+    fn regular_history(detector: &mut PhiAccrualDetector, start: Instant) -> Instant {
+        let mut now = start;
+        for _ in 0..20 {
+            detector.heartbeat(now);
+            now += Duration::from_secs(1);
+        }
+
+        now - Duration::from_secs(1)
+    }
+
+    #[test]
+    fn phi_is_zero_without_heartbeats() {
+        let detector = PhiAccrualDetector::new(config());
+
+        assert!(detector.phi(Instant::now()).abs() < f64::EPSILON);
+        assert!(detector.is_available(Instant::now()));
+    }
+
+    #[test]
+    fn phi_crosses_the_threshold_after_the_expected_gap() {
+        let mut detector = PhiAccrualDetector::new(config());
+        let last = regular_history(&mut detector, Instant::now());
+
+        // Heartbeats arriving on time don't raise any suspicion...
+        assert!(detector.phi(last + Duration::from_secs(1)) < 1.0);
+        // ...and neither do slightly late ones...
+        let phi = detector.phi(last + Duration::from_millis(1500));
+        assert!(phi < 8.0, "phi={}", phi);
+        assert!(detector.is_available(last + Duration::from_millis(1500)));
+        // ...but the threshold is crossed after the expected gap...
+        let phi = detector.phi(last + Duration::from_millis(1600));
+        assert!(phi > 8.0, "phi={}", phi);
+        assert!(!detector.is_available(last + Duration::from_millis(1600)));
+        // ...and phi keeps growing.
+        assert!(detector.phi(last + Duration::from_secs(3)) > phi);
+    }
+
+    #[test]
+    fn jitter_delays_the_suspicion() {
+        let start = Instant::now();
+        let mut regular = PhiAccrualDetector::new(config());
+        let regular_last = regular_history(&mut regular, start);
+
+        let mut jittery = PhiAccrualDetector::new(config());
+        let mut jittery_last = start;
+        for i in 0..20 {
+            jittery_last += match i % 2 {
+                0 => Duration::from_millis(500),
+                _ => Duration::from_millis(1500),
+            };
+            jittery.heartbeat(jittery_last);
+        }
+
+        let gap = Duration::from_millis(1600);
+        assert!(!regular.is_available(regular_last + gap));
+        assert!(jittery.is_available(jittery_last + gap));
+    }
+
+    #[test]
+    fn detectors_report_suspected_members() {
+        let mut detectors = FailureDetectors::new(config());
+        let (alive, silent) = (Uuid::new_v4(), Uuid::new_v4());
+        let start = Instant::now();
+
+        for i in 0..20 {
+            let now = start + Duration::from_secs(i);
+            detectors.heartbeat(alive, now);
+            if i < 10 {
+                detectors.heartbeat(silent, now);
+            }
+        }
+
+        let now = start + Duration::from_secs(20);
+        assert_eq!(detectors.suspected(now), vec![silent]);
+
+        detectors.remove(&silent);
+        assert!(detectors.suspected(now).is_empty());
+    }
+}
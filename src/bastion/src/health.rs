@@ -0,0 +1,223 @@
+//!
+//! The health of the system, returned by [`Bastion::health`] to
+//! answer liveness and readiness probes.
+//!
+//! [`Bastion::health`]: crate::Bastion::health
+use crate::context::BastionId;
+use fxhash::{FxHashMap, FxHashSet};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll, Waker};
+
+pub(crate) static HEALTH: Lazy<HealthRegistry> = Lazy::new(HealthRegistry::new);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// Whether the system is running.
+pub enum Liveness {
+    /// The system is running.
+    Alive,
+    /// The system was stopped or killed.
+    Dead,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// Whether the system is able to handle messages.
+pub enum Readiness {
+    /// Every children group is running at least as many elements
+    /// as it was configured with.
+    Ready,
+    /// A children group wasn't started yet or is running less
+    /// elements than it was configured with (e.g. because some of
+    /// them faulted and are waiting to be restarted).
+    NotReady,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A point-in-time view of the health of the system.
+pub struct Health {
+    /// Whether the system is running.
+    pub liveness: Liveness,
+    /// Whether the system is able to handle messages.
+    pub readiness: Readiness,
+}
+
+#[derive(Debug)]
+pub(crate) struct HealthRegistry {
+    // The amount of elements every children group was configured
+    // with and is running.
+    groups: Mutex<FxHashMap<BastionId, (usize, usize)>>,
+    // The children which entered their message loop.
+    ready: Mutex<ReadyChildren>,
+}
+
+#[derive(Debug, Default)]
+struct ReadyChildren {
+    ids: FxHashSet<BastionId>,
+    // The children being restarted by their supervisor, which didn't
+    // enter their message loop again yet, by children group.
+    restarting: FxHashMap<BastionId, FxHashSet<BastionId>>,
+    // The tasks waiting for children to be ready, which are all
+    // woken up when one of them is.
+    waiters: Vec<Waker>,
+}
+
+/// A future resolving once all the children it waits for entered
+/// their message loop.
+pub(crate) struct AwaitReady {
+    ids: Vec<BastionId>,
+}
+
+/// A future resolving once none of the elements of a children group
+/// is being restarted.
+pub(crate) struct RestartCompleted {
+    group: BastionId,
+}
+
+impl Health {
+    /// Returns whether the system is running.
+    pub fn is_alive(&self) -> bool {
+        self.liveness == Liveness::Alive
+    }
+
+    /// Returns whether the system is running and able to handle
+    /// messages.
+    pub fn is_ready(&self) -> bool {
+        self.is_alive() && self.readiness == Readiness::Ready
+    }
+}
+
+impl HealthRegistry {
+    fn new() -> Self {
+        let groups = Mutex::new(FxHashMap::default());
+        let ready = Mutex::new(ReadyChildren::default());
+
+        HealthRegistry { groups, ready }
+    }
+
+    pub(crate) fn register_children(&self, id: &BastionId, redundancy: usize, running: usize) {
+        // FIXME: panics?
+        let mut groups = self.groups.lock().unwrap();
+        groups.insert(id.clone(), (redundancy, running));
+    }
+
+    pub(crate) fn unregister(&self, id: &BastionId) {
+        // FIXME: panics?
+        self.groups.lock().unwrap().remove(id);
+
+        // NOTE: the elements of a stopped group won't be restarted.
+        // FIXME: panics?
+        let mut ready = self.ready.lock().unwrap();
+        if ready.restarting.remove(id).is_some() {
+            for waker in ready.waiters.drain(..) {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Marks the child with the given identifier as having entered
+    /// its message loop.
+    pub(crate) fn child_ready(&self, id: &BastionId) {
+        // FIXME: panics?
+        let mut ready = self.ready.lock().unwrap();
+        ready.ids.insert(id.clone());
+        ready.restarting.retain(|_, restarting| {
+            restarting.remove(id);
+            !restarting.is_empty()
+        });
+        for waker in ready.waiters.drain(..) {
+            waker.wake();
+        }
+    }
+
+    /// Forgets that the child with the given identifier entered its
+    /// message loop, because it stopped or faulted.
+    pub(crate) fn child_down(&self, id: &BastionId) {
+        // FIXME: panics?
+        self.ready.lock().unwrap().ids.remove(id);
+    }
+
+    /// Marks the child with the given identifier, which is part of
+    /// the children group with the given identifier, as being
+    /// restarted until it enters its message loop again.
+    pub(crate) fn child_restarting(&self, group: &BastionId, id: &BastionId) {
+        // FIXME: panics?
+        let mut ready = self.ready.lock().unwrap();
+        ready.ids.remove(id);
+        ready
+            .restarting
+            .entry(group.clone())
+            .or_default()
+            .insert(id.clone());
+    }
+
+    /// Returns a future resolving once none of the elements of the
+    /// children group with the given identifier is being restarted.
+    pub(crate) fn restart_completed(&self, group: BastionId) -> RestartCompleted {
+        RestartCompleted { group }
+    }
+
+    /// Returns a future resolving once all the children with the
+    /// given identifiers entered their message loop.
+    pub(crate) fn await_ready(&self, ids: Vec<BastionId>) -> AwaitReady {
+        AwaitReady { ids }
+    }
+
+    pub(crate) fn readiness(&self) -> Readiness {
+        // FIXME: panics?
+        let groups = self.groups.lock().unwrap();
+        let degraded = groups
+            .values()
+            .any(|(redundancy, running)| running < redundancy);
+
+        if degraded {
+            Readiness::NotReady
+        } else {
+            Readiness::Ready
+        }
+    }
+}
+
+impl Future for AwaitReady {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        // FIXME: panics?
+        let mut ready = HEALTH.ready.lock().unwrap();
+        if self.ids.iter().all(|id| ready.ids.contains(id)) {
+            return Poll::Ready(());
+        }
+
+        if !ready
+            .waiters
+            .iter()
+            .any(|waker| waker.will_wake(cx.waker()))
+        {
+            ready.waiters.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+impl Future for RestartCompleted {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        // FIXME: panics?
+        let mut ready = HEALTH.ready.lock().unwrap();
+        if !ready.restarting.contains_key(&self.group) {
+            return Poll::Ready(());
+        }
+
+        if !ready
+            .waiters
+            .iter()
+            .any(|waker| waker.will_wake(cx.waker()))
+        {
+            ready.waiters.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
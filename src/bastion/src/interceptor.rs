@@ -0,0 +1,119 @@
+//!
+//! Interceptors are hooks on the message path, which see every message
+//! before it is delivered to the mailbox of an element and can let it
+//! through, transform it or drop it.
+//!
+//! They are registered for the whole system using
+//! [`Bastion::add_interceptor`].
+//!
+//! [`Bastion::add_interceptor`]: crate::Bastion::add_interceptor
+use crate::envelope::SignedMessage;
+use crate::path::BastionPath;
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use tracing::trace;
+
+pub(crate) static INTERCEPTORS: Lazy<Interceptors> = Lazy::new(Interceptors::new);
+
+/// A hook on the message path, which can let messages through,
+/// transform them or drop them before they are delivered.
+///
+/// This trait is implemented for closures taking the message and
+/// the path of its recipient.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # #[cfg(feature = "tokio-runtime")]
+/// # #[tokio::main]
+/// # async fn main() {
+/// #    run();    
+/// # }
+/// #
+/// # #[cfg(not(feature = "tokio-runtime"))]
+/// # fn main() {
+/// #    run();    
+/// # }
+/// #
+/// # fn run() {
+/// # Bastion::init();
+/// #
+/// #[derive(Debug)]
+/// struct Tracer;
+///
+/// impl Interceptor for Tracer {
+///     fn intercept(&self, msg: SignedMessage, to: &BastionPath) -> Option<SignedMessage> {
+///         println!("{:?} -> {:?}", msg.signature().path(), to);
+///         Some(msg)
+///     }
+/// }
+///
+/// Bastion::add_interceptor(Tracer);
+/// #
+/// # Bastion::start();
+/// # Bastion::stop();
+/// # Bastion::block_until_stopped();
+/// # }
+/// ```
+pub trait Interceptor: Send + Sync + 'static {
+    /// Intercepts a message about to be delivered to the element at
+    /// the `to` path, returning the message to deliver instead, or
+    /// `None` to drop it.
+    ///
+    /// The messages routed by a dispatcher are wrapped in an
+    /// `Arc<SignedMessage>`.
+    fn intercept(&self, msg: SignedMessage, to: &BastionPath) -> Option<SignedMessage>;
+}
+
+impl<F> Interceptor for F
+where
+    F: Fn(SignedMessage, &BastionPath) -> Option<SignedMessage> + Send + Sync + 'static,
+{
+    fn intercept(&self, msg: SignedMessage, to: &BastionPath) -> Option<SignedMessage> {
+        self(msg, to)
+    }
+}
+
+pub(crate) struct Interceptors {
+    chain: RwLock<Vec<Box<dyn Interceptor>>>,
+    // Allows to skip locking the chain while it's empty.
+    enabled: AtomicBool,
+}
+
+impl Interceptors {
+    fn new() -> Self {
+        Interceptors {
+            chain: RwLock::new(Vec::new()),
+            enabled: AtomicBool::new(false),
+        }
+    }
+
+    pub(crate) fn add(&self, interceptor: Box<dyn Interceptor>) {
+        // FIXME: panics?
+        self.chain.write().unwrap().push(interceptor);
+        self.enabled.store(true, Ordering::SeqCst);
+    }
+
+    /// Runs the message through the interceptors, in the order they
+    /// were added, until one of them drops it.
+    pub(crate) fn intercept(&self, msg: SignedMessage, to: &BastionPath) -> Option<SignedMessage> {
+        if !self.enabled.load(Ordering::SeqCst) {
+            return Some(msg);
+        }
+
+        // FIXME: panics?
+        let chain = self.chain.read().unwrap();
+        let msg = chain
+            .iter()
+            .try_fold(msg, |msg, interceptor| interceptor.intercept(msg, to));
+
+        if msg.is_none() {
+            trace!("Interceptors: Dropped a message sent to {:?}.", to);
+        }
+
+        msg
+    }
+}
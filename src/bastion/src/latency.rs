@@ -0,0 +1,195 @@
+//!
+//! The processing latency of the messages handled by the elements of
+//! every children group, from the moment they reach an element's
+//! mailbox to the moment the element is done handling them (see
+//! [`ChildrenRef::latency_percentiles`]).
+//!
+//! [`ChildrenRef::latency_percentiles`]: crate::children_ref::ChildrenRef::latency_percentiles
+use crate::context::BastionId;
+use fxhash::FxHashMap;
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub(crate) static LATENCIES: Lazy<LatencyRegistry> = Lazy::new(LatencyRegistry::default);
+
+/// The amount of linear sub-buckets each power of two is split in,
+/// which bounds the relative error of the recorded values to 1/16th.
+const SUB_BUCKETS: u64 = 16;
+/// The amount of bits needed to index the sub-buckets.
+const SUB_BUCKET_BITS: u32 = 4;
+/// The amount of buckets needed to record any `u64`.
+const BUCKETS: usize = ((64 - SUB_BUCKET_BITS) as usize + 1) * SUB_BUCKETS as usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// The percentiles of the processing latency of the messages handled
+/// by the elements of a children group.
+///
+/// The percentiles are accurate to about 6% of their value, while
+/// `max` is exact. All of them are zero if no message was handled.
+pub struct Percentiles {
+    /// The amount of messages handled.
+    pub count: u64,
+    /// The median latency.
+    pub p50: Duration,
+    /// The 90th percentile of the latency.
+    pub p90: Duration,
+    /// The 99th percentile of the latency.
+    pub p99: Duration,
+    /// The highest latency.
+    pub max: Duration,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct LatencyRegistry {
+    groups: Mutex<FxHashMap<BastionId, Histogram>>,
+}
+
+#[derive(Debug, Clone)]
+/// A histogram of durations in microseconds, whose buckets grow
+/// exponentially while being split linearly.
+struct Histogram {
+    counts: Vec<u64>,
+    count: u64,
+    max: u64,
+}
+
+impl LatencyRegistry {
+    /// Records the latency of a message handled by an element of the
+    /// children group with the given identifier.
+    pub(crate) fn record(&self, group: &BastionId, latency: Duration) {
+        let micros = latency.as_micros().min(u64::MAX as u128) as u64;
+        // FIXME: panics?
+        let mut groups = self.groups.lock().unwrap();
+        groups
+            .entry(group.clone())
+            .or_insert_with(Histogram::new)
+            .record(micros);
+    }
+
+    /// Returns the percentiles of the latencies recorded for the
+    /// children group with the given identifier.
+    pub(crate) fn percentiles(&self, group: &BastionId) -> Percentiles {
+        // FIXME: panics?
+        let groups = self.groups.lock().unwrap();
+        groups
+            .get(group)
+            .map(Histogram::percentiles)
+            .unwrap_or_default()
+    }
+
+    /// Forgets the latencies recorded for the children group with the
+    /// given identifier.
+    pub(crate) fn unregister(&self, group: &BastionId) {
+        // FIXME: panics?
+        self.groups.lock().unwrap().remove(group);
+    }
+}
+
+impl Histogram {
+    fn new() -> Self {
+        Histogram {
+            counts: vec![0; BUCKETS],
+            count: 0,
+            max: 0,
+        }
+    }
+
+    fn record(&mut self, value: u64) {
+        self.counts[Self::index(value)] += 1;
+        self.count += 1;
+        self.max = self.max.max(value);
+    }
+
+    fn percentiles(&self) -> Percentiles {
+        Percentiles {
+            count: self.count,
+            p50: Duration::from_micros(self.percentile(0.5)),
+            p90: Duration::from_micros(self.percentile(0.9)),
+            p99: Duration::from_micros(self.percentile(0.99)),
+            max: Duration::from_micros(self.max),
+        }
+    }
+
+    // Returns the highest value of the bucket holding the value below
+    // which the given fraction of the recorded values are.
+    fn percentile(&self, quantile: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+
+        let rank = ((quantile * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (index, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Self::highest(index).min(self.max);
+            }
+        }
+
+        self.max
+    }
+
+    // Returns the index of the bucket the value falls in.
+    fn index(value: u64) -> usize {
+        if value < 2 * SUB_BUCKETS {
+            return value as usize;
+        }
+
+        let shift = 63 - value.leading_zeros() - SUB_BUCKET_BITS;
+        (shift as u64 * SUB_BUCKETS + (value >> shift)) as usize
+    }
+
+    // Returns the highest value falling in the bucket with the given
+    // index.
+    fn highest(index: usize) -> u64 {
+        let index = index as u64;
+        if index < 2 * SUB_BUCKETS {
+            return index;
+        }
+
+        let shift = index / SUB_BUCKETS - 1;
+        let lowest = (index % SUB_BUCKETS + SUB_BUCKETS) << shift;
+        lowest + ((1 << shift) - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buckets_bound_the_relative_error() {
+        for value in (0..100_000).chain(vec![u64::MAX / 3, u64::MAX]) {
+            let index = Histogram::index(value);
+            assert!(index < BUCKETS);
+
+            let highest = Histogram::highest(index);
+            assert!(highest >= value);
+            assert!(highest - value <= value / SUB_BUCKETS);
+        }
+    }
+
+    #[test]
+    fn percentiles_follow_the_recorded_values() {
+        let mut histogram = Histogram::new();
+        assert_eq!(histogram.percentiles(), Percentiles::default());
+
+        for value in 1..=1000 {
+            histogram.record(value);
+        }
+
+        let percentiles = histogram.percentiles();
+        assert_eq!(percentiles.count, 1000);
+        assert_eq!(percentiles.max, Duration::from_micros(1000));
+        for (percentile, expected) in vec![
+            (percentiles.p50, 500),
+            (percentiles.p90, 900),
+            (percentiles.p99, 990),
+        ] {
+            let micros = percentile.as_micros() as u64;
+            assert!(micros >= expected, "{} < {}", micros, expected);
+            assert!(micros <= expected + expected / SUB_BUCKETS);
+        }
+    }
+}
@@ -24,6 +24,7 @@
 //!
 //! ## Guarantees
 //! * At most once delivery for all the messages.
+//! * FIFO delivery of the messages sent by one sender to one recipient (see [`BastionContext::tell`]).
 //! * Completely asynchronous system design.
 //! * Asynchronous program boundaries with [fort].
 //! * Dynamic supervision of supervisors (adding a subtree later during the execution)
@@ -45,6 +46,7 @@
 //!
 //! [lightproc]: https://docs.rs/lightproc/
 //! [fort]: https://docs.rs/fort/
+//! [`BastionContext::tell`]: crate::context::BastionContext::tell
 //!
 
 #![doc(
@@ -67,9 +69,17 @@ mod macros;
 
 mod bastion;
 mod broadcast;
+mod bulkhead;
 mod callbacks;
 mod child;
 mod config;
+mod dead_letters;
+mod dependencies;
+mod overflow;
+mod persistence;
+mod rate_limit;
+#[cfg(feature = "json-logs")]
+mod supervision_log;
 mod system;
 
 pub mod child_ref;
@@ -78,22 +88,46 @@ pub mod children_ref;
 pub mod context;
 pub mod dispatcher;
 pub mod envelope;
+pub mod events;
 pub mod executor;
+pub mod health;
+pub mod interceptor;
 #[cfg(not(target_os = "windows"))]
 pub mod io;
+#[cfg(feature = "metrics")]
+pub mod latency;
 pub mod message;
 pub mod path;
+pub mod pipeline;
+pub mod quiesce;
 #[cfg(feature = "scaling")]
 pub mod resizer;
+pub mod retry;
+pub mod shutdown;
+pub mod sources;
+pub mod spec;
 pub mod supervisor;
+#[cfg(feature = "testkit")]
+pub mod testkit;
+pub mod topology;
+pub mod watch;
 
 pub mod errors;
 
 pub mod distributor;
 
 distributed_api! {
+    pub mod compression;
+    pub(crate) mod dedup;
     // pub mod dist_messages;
     pub mod distributed;
+    #[cfg(feature = "encryption")]
+    pub mod encryption;
+    pub mod failure_detector;
+    pub mod membership;
+    pub mod ordering;
+    pub mod outbound;
+    pub mod transport;
 }
 
 ///
@@ -102,33 +136,67 @@ pub mod prelude {
     pub use crate::bastion::Bastion;
     pub use crate::callbacks::Callbacks;
     pub use crate::child_ref::ChildRef;
-    pub use crate::children::Children;
+    pub use crate::children::{Children, MailboxHandover, PanicPolicy};
     pub use crate::children_ref::ChildrenRef;
     pub use crate::config::Config;
     pub use crate::context::{BastionContext, BastionId, NIL_ID};
     pub use crate::dispatcher::{
-        BroadcastTarget, DefaultDispatcherHandler, Dispatcher, DispatcherHandler, DispatcherMap,
-        DispatcherType, NotificationType,
+        BroadcastOrder, BroadcastTarget, ConsistentHashHandler, DefaultDispatcherHandler,
+        Dispatcher, DispatcherHandler, DispatcherMap, DispatcherType, EmptyGroupPolicy,
+        NotificationType,
     };
     pub use crate::distributor::Distributor;
     pub use crate::envelope::{RefAddr, SignedMessage};
     pub use crate::errors::*;
+    pub use crate::events::{EventStream, SystemEvent};
+    pub use crate::health::{Health, Liveness, Readiness};
+    pub use crate::interceptor::Interceptor;
     #[cfg(not(target_os = "windows"))]
     pub use crate::io::*;
-    pub use crate::message::{Answer, AnswerSender, Message, MessageHandler, Msg};
+    #[cfg(feature = "metrics")]
+    pub use crate::latency::Percentiles;
+    pub use crate::message::{
+        AckReport, Answer, AnswerSender, GroupAnswers, GroupReply, Message, MessageHandler, Msg,
+        TypedAnswer,
+    };
     pub use crate::msg;
-    pub use crate::path::{BastionPath, BastionPathElement};
+    pub use crate::overflow::DropPriorities;
+    pub use crate::path::{BastionPath, BastionPathElement, RemoteNode};
+    pub use crate::pipeline::{Pipeline, PipelineRef, Stage};
+    pub use crate::quiesce::QuiesceGuard;
+    pub use crate::rate_limit::OverloadPolicy;
     #[cfg(feature = "scaling")]
-    pub use crate::resizer::{OptimalSizeExploringResizer, UpperBound, UpscaleStrategy};
+    pub use crate::resizer::{
+        OptimalSizeExploringResizer, Resizer, UpperBound, UpscaleStrategy, UtilizationResizer,
+    };
+    pub use crate::retry::RetryPolicy;
+    pub use crate::shutdown::{ShutdownReport, ShutdownStatus};
+    pub use crate::sources::ContextEvent;
+    pub use crate::spec::{ChildrenSpec, SupervisorSpec, TreeSpec};
     pub use crate::supervisor::{
-        ActorRestartStrategy, RestartPolicy, RestartStrategy, SupervisionStrategy, Supervisor,
-        SupervisorRef,
+        ActorRestartStrategy, BackoffJitter, FaultReason, RestartPolicy, RestartStrategy,
+        SupervisionStrategy, Supervisor, SupervisorRef,
     };
-    pub use crate::{answer, blocking, children, run, spawn, supervisor};
+    pub use crate::topology::{ChildrenNode, SupervisorNode, TopologySnapshot};
+    pub use crate::watch::{Down, DownReason};
+    pub use crate::{answer, blocking, blocking_named, children, run, spawn, supervisor};
+    // Shared, reference-counted buffers which can be sent and
+    // broadcasted without copying their content.
+    pub use bytes::Bytes;
 
     distributed_api! {
+        pub use crate::compression::{Codec, Compression};
         // pub use crate::dist_messages::*;
         pub use crate::distributed::*;
+        #[cfg(feature = "encryption")]
+        pub use crate::encryption::{Encryption, EncryptionKey};
+        pub use crate::failure_detector::{PhiAccrualConfig, PhiAccrualDetector};
+        pub use crate::membership::ReconnectPolicy;
+        pub use crate::ordering::{GapPolicy, OrderedDelivery};
+        pub use crate::outbound::{OutboundOverflow, OutboundQueue};
+        pub use crate::transport::{
+            ClusterEvent, ClusterTransport, GossipConfig, MAX_UDP_PAYLOAD_SIZE,
+        };
         pub use artillery_core::cluster::ap::*;
         pub use artillery_core::epidemic::prelude::*;
     }
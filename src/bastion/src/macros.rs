@@ -238,6 +238,40 @@ macro_rules! blocking {
     };
 }
 
+/// Spawns a blocking task labelled with the given name, which will
+/// run on the blocking thread pool, and returns the handle.
+///
+/// # Example
+/// ```
+/// # use std::{thread, time};
+/// # use bastion::prelude::*;
+/// # #[cfg(feature = "tokio-runtime")]
+/// # #[tokio::main]
+/// # async fn main() {
+/// #    run();    
+/// # }
+/// #
+/// # #[cfg(not(feature = "tokio-runtime"))]
+/// # fn main() {
+/// #    run();    
+/// # }
+/// #
+/// # fn run() {
+/// let task = blocking_named!("outbound-loop", {
+///     thread::sleep(time::Duration::from_millis(3000));
+/// });
+/// run!(task);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! blocking_named {
+    ($label:expr, $($tokens:tt)*) => {
+        $crate::executor::blocking_named($label, async move {
+            $($tokens)*
+        })
+    };
+}
+
 /// This macro blocks the current thread until passed
 /// future is resolved with an output (including the panic).
 ///
@@ -0,0 +1,467 @@
+//!
+//! Membership tracking of the peers of a cluster, retaining the peers
+//! whose link dropped for as long as they are trying to reconnect.
+use fxhash::{FxHashMap, FxHashSet};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// How the peers of a cluster whose link dropped are given a chance
+/// to reconnect before being removed from the members, set with
+/// [`ClusterConfig::with_reconnect`].
+///
+/// A peer reported as down by the failure detector is retained in the
+/// members and gets `max_attempts` reconnect attempts, separated by an
+/// exponential backoff starting at `initial_backoff` and bounded by
+/// `max_backoff`, every attempt probing it again through the transport
+/// (see [`ClusterTransport::probe`]). If it is reported as alive again
+/// during this time, it is kept as if its link never dropped; otherwise
+/// it is removed once the backoff following the last attempt elapsed.
+///
+/// [`ClusterConfig::with_reconnect`]: crate::distributed::ClusterConfig::with_reconnect
+/// [`ClusterTransport::probe`]: crate::transport::ClusterTransport::probe
+pub struct ReconnectPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+#[derive(Debug)]
+pub(crate) struct Membership<M> {
+    policy: ReconnectPolicy,
+    peers: FxHashMap<Uuid, Peer<M>>,
+    // Every peer ever reported as alive, which a quorum is a
+    // majority of unless its size was set.
+    known: FxHashSet<Uuid>,
+    // The amount of members a quorum is a majority of, if set.
+    quorum_size: Option<usize>,
+}
+
+#[derive(Debug)]
+struct Peer<M> {
+    member: M,
+    // Set while the peer's link is down.
+    reconnect: Option<Reconnect>,
+}
+
+#[derive(Debug)]
+struct Reconnect {
+    attempts: u32,
+    next_attempt: Instant,
+}
+
+impl ReconnectPolicy {
+    /// Creates a policy giving peers 5 reconnect attempts, with a
+    /// backoff going from 100 milliseconds up to 5 seconds.
+    pub fn new() -> Self {
+        ReconnectPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+
+    /// Creates a policy which removes peers as soon as their link
+    /// dropped.
+    pub fn never() -> Self {
+        ReconnectPolicy::new().with_max_attempts(0)
+    }
+
+    /// Sets the amount of failed reconnect attempts after which a
+    /// peer is declared failed and removed from the members.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_attempts` - The amount of reconnect attempts.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Sets the backoff between two reconnect attempts, which doubles
+    /// after every failed attempt.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_backoff` - The delay before the first attempt.
+    /// * `max_backoff` - The maximum delay between two attempts.
+    pub fn with_backoff(mut self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Returns the amount of failed reconnect attempts after which a
+    /// peer is declared failed.
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    fn backoff(&self, attempts: u32) -> Duration {
+        // Saturates instead of overflowing after a lot of attempts.
+        let factor = 1u32.checked_shl(attempts).unwrap_or(u32::MAX);
+        self.initial_backoff
+            .checked_mul(factor)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        ReconnectPolicy::new()
+    }
+}
+
+impl<M: Clone> Membership<M> {
+    pub(crate) fn new(policy: ReconnectPolicy) -> Self {
+        Membership {
+            policy,
+            peers: FxHashMap::default(),
+            known: FxHashSet::default(),
+            quorum_size: None,
+        }
+    }
+
+    /// Sets the amount of members a quorum is a majority of, instead
+    /// of every peer ever known.
+    pub(crate) fn with_quorum_size(mut self, quorum_size: Option<usize>) -> Self {
+        self.quorum_size = quorum_size;
+        self
+    }
+
+    /// Returns the members, including the ones trying to reconnect.
+    pub(crate) fn members(&self) -> Vec<(Uuid, M)> {
+        self.peers
+            .iter()
+            .map(|(id, peer)| (*id, peer.member.clone()))
+            .collect()
+    }
+
+    pub(crate) fn is_reconnecting(&self, id: &Uuid) -> bool {
+        match self.peers.get(id) {
+            Some(peer) => peer.reconnect.is_some(),
+            None => false,
+        }
+    }
+
+    /// Records that the failure detector reported the peer as alive,
+    /// returning whether it just joined or reconnected.
+    pub(crate) fn alive(&mut self, id: Uuid, member: M) -> bool {
+        self.known.insert(id);
+        let previous = self.peers.insert(
+            id,
+            Peer {
+                member,
+                reconnect: None,
+            },
+        );
+
+        match previous {
+            Some(Peer {
+                reconnect: Some(reconnect),
+                ..
+            }) => {
+                debug!(
+                    "Membership: Peer({}) reconnected after {} attempts.",
+                    id, reconnect.attempts
+                );
+                true
+            }
+            Some(_) => false,
+            None => true,
+        }
+    }
+
+    /// Records that the failure detector reported the peer as down,
+    /// returning whether it was removed right away.
+    pub(crate) fn down(&mut self, id: Uuid, now: Instant) -> bool {
+        if self.policy.max_attempts == 0 {
+            return self.peers.remove(&id).is_some();
+        }
+
+        if let Some(peer) = self.peers.get_mut(&id) {
+            if peer.reconnect.is_none() {
+                debug!("Membership: Peer({}) link dropped, reconnecting.", id);
+                peer.reconnect = Some(Reconnect {
+                    attempts: 0,
+                    next_attempt: now + self.policy.backoff(0),
+                });
+            }
+        }
+
+        false
+    }
+
+    /// Returns whether the given member and the peers whose link is
+    /// up are a strict majority of the quorum size if it was set, or
+    /// else of the peers ever known, including the ones which were
+    /// since removed.
+    pub(crate) fn has_quorum(&self, me: Uuid) -> bool {
+        let reachable = self
+            .peers
+            .iter()
+            .filter(|(id, peer)| **id != me && peer.reconnect.is_none())
+            .count()
+            + 1;
+        let known = match self.quorum_size {
+            Some(quorum_size) => quorum_size,
+            None => self.known.iter().filter(|id| **id != me).count() + 1,
+        };
+
+        reachable * 2 > known
+    }
+
+    /// Returns the peers other than the given member whose link is
+    /// up.
+    pub(crate) fn reachable(&self, me: Uuid) -> Vec<Uuid> {
+        self.peers
+            .iter()
+            .filter(|(id, peer)| **id != me && peer.reconnect.is_none())
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Returns whether the given member knew about other peers but
+    /// can't reach any of them anymore, because the link of every
+    /// one of them dropped or they were removed.
+    pub(crate) fn is_isolated(&self, me: Uuid) -> bool {
+        self.known.iter().any(|id| *id != me) && self.reachable(me).is_empty()
+    }
+
+    /// Goes through the reconnect attempts that are due, probing the
+    /// peers again with `probe`, and returns the peers that failed
+    /// every attempt and were removed.
+    pub(crate) fn poll_reconnects<P>(&mut self, now: Instant, mut probe: P) -> Vec<Uuid>
+    where
+        P: FnMut(Uuid),
+    {
+        let policy = &self.policy;
+        let mut failed = Vec::new();
+
+        for (id, peer) in self.peers.iter_mut() {
+            let reconnect = match &mut peer.reconnect {
+                Some(reconnect) if reconnect.next_attempt <= now => reconnect,
+                _ => continue,
+            };
+
+            if reconnect.attempts >= policy.max_attempts {
+                warn!(
+                    "Membership: Peer({}) failed to reconnect after {} attempts.",
+                    id, reconnect.attempts
+                );
+                failed.push(*id);
+            } else {
+                reconnect.attempts += 1;
+                debug!(
+                    "Membership: Probing Peer({}) (attempt {}).",
+                    id, reconnect.attempts
+                );
+                probe(*id);
+                reconnect.next_attempt = now + policy.backoff(reconnect.attempts);
+            }
+        }
+
+        for id in &failed {
+            self.peers.remove(id);
+        }
+
+        failed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Membership, ReconnectPolicy};
+    use std::time::{Duration, Instant};
+    use uuid::Uuid;
+
+    fn policy() -> ReconnectPolicy {
+        ReconnectPolicy::new()
+            .with_max_attempts(3)
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(250))
+    }
+
+    fn ids(membership: &Membership<&'static str>) -> Vec<Uuid> {
+        membership.members().into_iter().map(|(id, _)| id).collect()
+    }
+
+    #[test]
+    fn backoff_is_exponential_and_bounded() {
+        let policy = policy();
+
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(2), Duration::from_millis(250));
+        assert_eq!(policy.backoff(64), Duration::from_millis(250));
+    }
+
+    #[test]
+    fn peer_is_retained_after_reconnecting() {
+        let mut membership = Membership::new(policy());
+        let peer = Uuid::new_v4();
+        let start = Instant::now();
+
+        assert!(membership.alive(peer, "peer"));
+        assert!(!membership.alive(peer, "peer"));
+        // The link drops...
+        assert!(!membership.down(peer, start));
+        assert!(membership.is_reconnecting(&peer));
+        assert_eq!(ids(&membership), vec![peer]);
+
+        // ...a first attempt probes it...
+        let mut probed = Vec::new();
+        let failed =
+            membership.poll_reconnects(start + Duration::from_millis(100), |id| probed.push(id));
+        assert!(failed.is_empty());
+        assert_eq!(probed, vec![peer]);
+        assert_eq!(ids(&membership), vec![peer]);
+
+        // ...and the link is restored.
+        assert!(membership.alive(peer, "peer"));
+        assert!(!membership.is_reconnecting(&peer));
+
+        let failed = membership.poll_reconnects(start + Duration::from_secs(60), |_| {
+            panic!("a reconnected peer was probed")
+        });
+        assert!(failed.is_empty());
+        assert_eq!(ids(&membership), vec![peer]);
+    }
+
+    #[test]
+    fn peer_is_removed_after_failed_attempts() {
+        let mut membership = Membership::new(policy());
+        let peer = Uuid::new_v4();
+        let start = Instant::now();
+
+        membership.alive(peer, "peer");
+        membership.down(peer, start);
+
+        // Attempts happen after 100ms, then 200ms, then 250ms...
+        let mut now = start;
+        let mut probes = 0;
+        for backoff in &[100, 200, 250] {
+            now += Duration::from_millis(*backoff);
+            assert!(membership.poll_reconnects(now, |_| probes += 1).is_empty());
+            assert_eq!(ids(&membership), vec![peer]);
+        }
+        assert_eq!(probes, 3);
+
+        // Not due yet.
+        assert!(membership
+            .poll_reconnects(now + Duration::from_millis(249), |_| probes += 1)
+            .is_empty());
+
+        // ...and the peer is removed once the last one failed.
+        now += Duration::from_millis(250);
+        assert_eq!(membership.poll_reconnects(now, |_| probes += 1), vec![peer]);
+        assert!(ids(&membership).is_empty());
+        assert_eq!(probes, 3);
+    }
+
+    #[test]
+    fn never_reconnecting_removes_peers_right_away() {
+        let mut membership = Membership::new(ReconnectPolicy::never());
+        let peer = Uuid::new_v4();
+
+        membership.alive(peer, "peer");
+        assert!(membership.down(peer, Instant::now()));
+        assert!(ids(&membership).is_empty());
+    }
+
+    #[test]
+    fn quorum_is_a_majority_of_the_known_peers() {
+        let mut membership = Membership::new(ReconnectPolicy::never());
+        let me = Uuid::new_v4();
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        assert!(membership.has_quorum(me));
+
+        membership.alive(me, "me");
+        membership.alive(first, "first");
+        membership.alive(second, "second");
+        assert!(membership.has_quorum(me));
+
+        membership.down(first, Instant::now());
+        assert!(membership.has_quorum(me));
+        // Removed peers are still part of the known ones.
+        membership.down(second, Instant::now());
+        assert!(!membership.has_quorum(me));
+
+        membership.alive(second, "second");
+        assert!(membership.has_quorum(me));
+    }
+
+    #[test]
+    fn quorum_is_a_majority_of_its_size_if_set() {
+        let mut membership = Membership::new(ReconnectPolicy::never()).with_quorum_size(Some(3));
+        let me = Uuid::new_v4();
+        let peers = (0..4).map(|_| Uuid::new_v4()).collect::<Vec<_>>();
+
+        membership.alive(me, "me");
+        for peer in peers.iter() {
+            membership.alive(*peer, "peer");
+        }
+        // Once the cluster was scaled down to 3 members, the peers
+        // which left don't count towards the quorum anymore.
+        membership.down(peers[0], Instant::now());
+        membership.down(peers[1], Instant::now());
+        assert!(membership.has_quorum(me));
+
+        membership.down(peers[2], Instant::now());
+        assert!(membership.has_quorum(me));
+        membership.down(peers[3], Instant::now());
+        assert!(!membership.has_quorum(me));
+    }
+
+    #[test]
+    fn member_is_isolated_once_every_peer_is_unreachable() {
+        let mut membership = Membership::new(policy());
+        let me = Uuid::new_v4();
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        // Not knowing about any peer yet isn't being isolated.
+        membership.alive(me, "me");
+        assert!(!membership.is_isolated(me));
+
+        membership.alive(first, "first");
+        membership.alive(second, "second");
+        membership.down(first, Instant::now());
+        assert!(!membership.is_isolated(me));
+        assert_eq!(membership.reachable(me), vec![second]);
+
+        membership.down(second, Instant::now());
+        assert!(membership.is_isolated(me));
+        // Removed peers still count as known ones.
+        let mut now = Instant::now();
+        let mut failed = Vec::new();
+        for _ in 0..4 {
+            now += Duration::from_secs(1);
+            failed.extend(membership.poll_reconnects(now, |_| ()));
+        }
+        assert_eq!(failed.len(), 2);
+        assert!(membership.is_isolated(me));
+
+        // Rejoining doesn't duplicate the peers.
+        membership.alive(first, "first");
+        membership.alive(first, "first");
+        assert!(!membership.is_isolated(me));
+        let mut ids = ids(&membership);
+        ids.sort();
+        let mut expected = vec![me, first];
+        expected.sort();
+        assert_eq!(ids, expected);
+    }
+
+    #[test]
+    fn reconnecting_peers_are_not_part_of_a_quorum() {
+        let mut membership = Membership::new(policy());
+        let me = Uuid::new_v4();
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        membership.alive(first, "first");
+        membership.alive(second, "second");
+
+        membership.down(first, Instant::now());
+        membership.down(second, Instant::now());
+        assert!(!membership.has_quorum(me));
+    }
+}
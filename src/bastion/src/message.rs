@@ -7,18 +7,33 @@
 //! * Messages are not guaranteed to be ordered, all message's order is causal.
 //!
 use crate::callbacks::CallbackType;
+use crate::child::Init;
+use crate::child_ref::ChildRef;
 use crate::children::Children;
-use crate::context::{BastionId, ContextState};
+use crate::config::Config;
+use crate::context::{BastionContext, BastionId, ContextState};
 use crate::envelope::{RefAddr, SignedMessage};
-use crate::supervisor::{SupervisionStrategy, Supervisor};
+use crate::errors::AnswerError;
+use crate::supervisor::{Adoption, FaultReason, SupervisionStrategy, Supervisor, SupervisorRef};
+use crate::system::CONFIG;
 
+use bytes::Bytes;
 use futures::channel::oneshot::{self, Receiver};
-use std::any::{type_name, Any};
+use futures::future::BoxFuture;
+use futures::prelude::*;
+use futures::stream::FuturesUnordered;
+use futures_timer::Delay;
+use std::any::{type_name, Any, TypeId};
+use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::future::Future;
+use std::marker::PhantomData;
+use std::mem;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 use tracing::{debug, trace};
 
 /// A trait that any message sent needs to implement (it is
@@ -33,6 +48,41 @@ use tracing::{debug, trace};
 pub trait Message: Any + Send + Sync + Debug {}
 impl<T> Message for T where T: Any + Send + Sync + Debug {}
 
+/// Checks that the message isn't larger than the maximum message size
+/// the system was configured with (see [`Config::with_max_message_size`]),
+/// returning its size and the maximum otherwise.
+///
+/// [`Config::with_max_message_size`]: crate::config::Config::with_max_message_size
+pub(crate) fn check_message_size<M: Message>(msg: &M) -> Result<(), (usize, usize)> {
+    let max = match CONFIG.get().and_then(Config::max_message_size) {
+        Some(max) => max,
+        None => return Ok(()),
+    };
+
+    let size = message_size(msg);
+    if size > max {
+        Err((size, max))
+    } else {
+        Ok(())
+    }
+}
+
+// Returns the length of the payload of the messages made of bytes or
+// text, or the amount of memory the message uses in place otherwise.
+fn message_size(msg: &dyn Any) -> usize {
+    if let Some(bytes) = msg.downcast_ref::<Bytes>() {
+        bytes.len()
+    } else if let Some(bytes) = msg.downcast_ref::<Vec<u8>>() {
+        bytes.len()
+    } else if let Some(text) = msg.downcast_ref::<String>() {
+        text.len()
+    } else if let Some(text) = msg.downcast_ref::<&'static str>() {
+        text.len()
+    } else {
+        mem::size_of_val(msg)
+    }
+}
+
 /// Allows to respond to questions.
 ///
 /// This type features the [`respond`] method, that allows to respond to a
@@ -127,7 +177,167 @@ pub struct AnswerSender(oneshot::Sender<SignedMessage>, RefAddr);
 ///
 /// [`Future`]: std::future::Future
 /// [`ChildRef::ask_anonymously`]: crate::child_ref::ChildRef::ask_anonymously
-pub struct Answer(Receiver<SignedMessage>);
+pub struct Answer(Receiver<SignedMessage>, Option<AskSlot>);
+
+#[derive(Debug, Clone, Default)]
+// The asks sent by an element whose answer wasn't received nor
+// dropped yet, bounded if its group was configured with a maximum
+// (see `Children::with_max_outstanding_asks`).
+pub(crate) struct OutstandingAsks {
+    max: Option<usize>,
+    count: Arc<AtomicUsize>,
+}
+
+#[derive(Debug)]
+// One of the outstanding asks of an element, released once its
+// answer is received or dropped.
+pub(crate) struct AskSlot(Arc<AtomicUsize>);
+
+#[derive(Debug)]
+/// A [`Future`] returned by [`Answer::typed`] and which resolves
+/// to the `Result<T, E>` answered by the asked child, keeping
+/// the errors of the application (the `E` answered by the
+/// child) apart from the ones of the question itself (an
+/// [`AnswerError`]).
+///
+/// # Example
+///
+/// ```
+/// # use bastion::prelude::*;
+/// #
+/// # #[cfg(feature = "tokio-runtime")]
+/// # #[tokio::main]
+/// # async fn main() {
+/// #    run();    
+/// # }
+/// #
+/// # #[cfg(not(feature = "tokio-runtime"))]
+/// # fn main() {
+/// #    run();    
+/// # }
+/// #
+/// # fn run() {
+///     # Bastion::init();
+/// #[derive(Debug, PartialEq)]
+/// enum LookupError {
+///     NotFound,
+/// }
+///
+///     # let children_ref =
+/// Bastion::children(|children| {
+///     children.with_exec(|ctx: BastionContext| {
+///         async move {
+///             msg! { ctx.recv().await?,
+///                 key: &'static str =!> {
+///                     // The child answers with an application-level error...
+///                     let answer: Result<u64, LookupError> = Err(LookupError::NotFound);
+///                     answer!(ctx, answer).unwrap();
+///                 };
+///                 _: _ => ();
+///             }
+///
+///             Ok(())
+///         }
+///     })
+/// }).expect("Couldn't create the children group.");
+///
+///     # Bastion::children(|children| {
+///         # children.with_exec(move |ctx: BastionContext| {
+///             # let child_ref = children_ref.elems()[0].clone();
+///             # async move {
+/// let answer = ctx
+///     .ask(&child_ref.addr(), "key")
+///     .expect("Couldn't send the message.")
+///     .typed::<u64, LookupError>();
+///
+/// // ...which the asker receives apart from the errors of the question.
+/// match answer.await {
+///     Ok(Ok(value)) => { /* ... */ }
+///     Ok(Err(LookupError::NotFound)) => { /* ... */ }
+///     Err(err) => panic!("Couldn't receive the answer: {}", err),
+/// }
+///                 #
+///                 # Ok(())
+///             # }
+///         # })
+///     # }).unwrap();
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+pub struct TypedAnswer<T, E> {
+    answer: Answer,
+    timeout: Option<(Duration, Delay)>,
+    _result: PhantomData<fn() -> Result<T, E>>,
+}
+
+#[derive(Debug)]
+/// A reply yielded by the [`GroupAnswers`] returned by
+/// [`BastionContext::ask_group`].
+///
+/// [`BastionContext::ask_group`]: crate::context::BastionContext::ask_group
+pub enum GroupReply {
+    /// A member of the group answered the question.
+    Answered {
+        /// The member that answered.
+        from: ChildRef,
+        /// The answer of the member.
+        answer: SignedMessage,
+    },
+    /// A member of the group couldn't be asked the question,
+    /// dropped it without answering or didn't answer before
+    /// the timeout elapsed.
+    Missing {
+        /// The member that didn't answer.
+        from: ChildRef,
+    },
+}
+
+#[derive(Debug)]
+/// A [`Stream`] returned by [`BastionContext::ask_group`],
+/// yielding the reply of every asked member of the group as
+/// soon as it arrives.
+///
+/// The stream ends once every member replied. Once its timeout
+/// elapsed, the members that didn't answer yet are yielded as
+/// [`GroupReply::Missing`] before it ends.
+///
+/// [`Stream`]: futures::Stream
+/// [`BastionContext::ask_group`]: crate::context::BastionContext::ask_group
+pub struct GroupAnswers {
+    // The members that didn't reply yet, indexed like the answers
+    // they were asked for.
+    pending: Vec<Option<ChildRef>>,
+    answers: FuturesUnordered<BoxFuture<'static, (usize, Result<SignedMessage, ()>)>>,
+    // The members that are known to be missing but weren't yielded
+    // yet.
+    missing: VecDeque<ChildRef>,
+    timeout: Delay,
+}
+
+#[derive(Debug, Default)]
+/// The report returned by [`BastionContext::broadcast_with_ack`],
+/// telling which members of the group acknowledged the message.
+///
+/// [`BastionContext::broadcast_with_ack`]: crate::context::BastionContext::broadcast_with_ack
+pub struct AckReport {
+    /// The members that acknowledged the message, using
+    /// [`BastionContext::ack`], in the order they did.
+    ///
+    /// [`BastionContext::ack`]: crate::context::BastionContext::ack
+    pub acked: Vec<ChildRef>,
+    /// The members that couldn't be sent the message, handled it
+    /// without acknowledging it or didn't acknowledge it before
+    /// the timeout elapsed.
+    pub missing: Vec<ChildRef>,
+}
+
+#[derive(Debug)]
+// A message sent with `BastionContext::broadcast_with_ack`, which is
+// unwrapped into a `Tell` once received.
+struct AckRequest(Box<dyn Any + Send + Sync + 'static>);
 
 #[derive(Debug)]
 /// A message returned by [`BastionContext::recv`] or
@@ -198,7 +408,9 @@ pub struct Answer(Receiver<SignedMessage>);
 ///
 /// [`BastionContext::recv`]: crate::context::BastionContext::recv
 /// [`BastionContext::try_recv`]: crate::context::BastionContext::try_recv
-pub struct Msg(MsgInner);
+// The instant after which the message is dropped instead of being
+// delivered, if it was sent with a time-to-live.
+pub struct Msg(MsgInner, Option<Instant>);
 
 #[derive(Debug)]
 enum MsgInner {
@@ -214,6 +426,9 @@ enum MsgInner {
 pub(crate) enum BastionMessage {
     Start,
     Stop,
+    GracefulStop {
+        drain_timeout: Duration,
+    },
     Kill,
     Deploy(Box<Deployment>),
     Prune {
@@ -227,9 +442,11 @@ pub(crate) enum BastionMessage {
         state: Arc<Pin<Box<ContextState>>>,
     },
     Message(Msg),
+    Batch(Vec<Arc<SignedMessage>>),
     RestartRequired {
         id: BastionId,
         parent_id: BastionId,
+        reason: FaultReason,
     },
     FinishedChild {
         id: BastionId,
@@ -251,8 +468,20 @@ pub(crate) enum BastionMessage {
     },
     Faulted {
         id: BastionId,
+        reason: FaultReason,
     },
     Heartbeat,
+    Pause,
+    Resume,
+    Hold,
+    Release,
+    Thaw,
+    Handover {
+        id: BastionId,
+        to: SupervisorRef,
+    },
+    Adopt(Box<Adoption>),
+    Reparent(SupervisorRef),
 }
 
 #[derive(Debug)]
@@ -281,24 +510,66 @@ impl AnswerSender {
 impl Msg {
     pub(crate) fn broadcast<M: Message>(msg: M) -> Self {
         let inner = MsgInner::Broadcast(Arc::new(msg));
-        Msg(inner)
+        Msg(inner, None)
     }
 
     pub(crate) fn tell<M: Message>(msg: M) -> Self {
         let inner = MsgInner::Tell(Box::new(msg));
-        Msg(inner)
+        Msg(inner, None)
     }
 
     pub(crate) fn ask<M: Message>(msg: M, sign: RefAddr) -> (Self, Answer) {
         let msg = Box::new(msg);
         let (sender, recver) = oneshot::channel();
         let sender = AnswerSender(sender, sign);
-        let answer = Answer(recver);
+        let answer = Answer(recver, None);
 
         let sender = Some(sender);
         let inner = MsgInner::Ask { msg, sender };
 
-        (Msg(inner), answer)
+        (Msg(inner, None), answer)
+    }
+
+    pub(crate) fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.1 = Some(Instant::now() + ttl);
+        self
+    }
+
+    /// Returns whether the message was sent with a time-to-live
+    /// which elapsed.
+    pub(crate) fn is_expired(&self) -> bool {
+        self.1.map_or(false, |deadline| deadline <= Instant::now())
+    }
+
+    pub(crate) fn without_ttl(mut self) -> Self {
+        self.1 = None;
+        self
+    }
+
+    pub(crate) fn ack_request<M: Message>(msg: M, sign: RefAddr) -> (Self, Answer) {
+        Msg::ask(AckRequest(Box::new(msg)), sign)
+    }
+
+    /// Unwraps the message if it was sent with an acknowledgement
+    /// request, returning the sender allowing to acknowledge it.
+    pub(crate) fn take_ack_request(&mut self) -> Option<AnswerSender> {
+        let is_ack_request = match &self.0 {
+            MsgInner::Ask { msg, .. } => msg.is::<AckRequest>(),
+            _ => false,
+        };
+        if !is_ack_request {
+            return None;
+        }
+
+        let inner = std::mem::replace(&mut self.0, MsgInner::Tell(Box::new(())));
+        if let MsgInner::Ask { msg, sender } = inner {
+            let msg: Box<dyn Any + 'static> = msg;
+            let AckRequest(msg) = *msg.downcast().unwrap();
+            self.0 = MsgInner::Tell(msg);
+            sender
+        } else {
+            unreachable!()
+        }
     }
 
     #[doc(hidden)]
@@ -326,6 +597,17 @@ impl Msg {
         }
     }
 
+    /// Returns the type of the message's content.
+    pub(crate) fn content_type_id(&self) -> TypeId {
+        // NOTE: the content is dereferenced, as the box (or `Arc`)
+        //      holding it is itself `Any`.
+        match &self.0 {
+            MsgInner::Tell(msg) => Any::type_id(&**msg),
+            MsgInner::Ask { msg, .. } => Any::type_id(&**msg),
+            MsgInner::Broadcast(msg) => Any::type_id(&**msg),
+        }
+    }
+
     #[doc(hidden)]
     pub fn is<M: Message>(&self) -> bool {
         match &self.0 {
@@ -338,6 +620,7 @@ impl Msg {
     #[doc(hidden)]
     pub fn downcast<M: Message>(self) -> Result<M, Self> {
         trace!("{:?}: Downcasting to {}.", self, type_name::<M>());
+        let deadline = self.1;
         match self.0 {
             MsgInner::Tell(msg) => {
                 if msg.is::<M>() {
@@ -345,7 +628,7 @@ impl Msg {
                     Ok(*msg.downcast().unwrap())
                 } else {
                     let inner = MsgInner::Tell(msg);
-                    Err(Msg(inner))
+                    Err(Msg(inner, deadline))
                 }
             }
             MsgInner::Ask { msg, sender } => {
@@ -354,13 +637,46 @@ impl Msg {
                     Ok(*msg.downcast().unwrap())
                 } else {
                     let inner = MsgInner::Ask { msg, sender };
-                    Err(Msg(inner))
+                    Err(Msg(inner, deadline))
                 }
             }
             _ => Err(self),
         }
     }
 
+    #[doc(hidden)]
+    pub fn matches_guard<M, G>(&mut self, guard: G) -> bool
+    where
+        M: Message,
+        G: FnOnce(M) -> (M, bool),
+    {
+        trace!("{:?}: Matching guard of {}.", self, type_name::<M>());
+        let msg = match &mut self.0 {
+            MsgInner::Tell(msg) => msg,
+            MsgInner::Ask { msg, .. } => msg,
+            MsgInner::Broadcast(_) => return false,
+        };
+        if !msg.is::<M>() {
+            return false;
+        }
+
+        // NOTE: the guard is given the message by value, which is
+        //      put back once it was evaluated.
+        let taken: Box<dyn Any + 'static> = std::mem::replace(msg, Box::new(()));
+        let (taken, matched) = guard(*taken.downcast().unwrap());
+        *msg = Box::new(taken);
+
+        matched
+    }
+
+    pub(crate) fn peek<M: Message>(&self) -> Option<&M> {
+        match &self.0 {
+            MsgInner::Tell(msg) => msg.downcast_ref(),
+            MsgInner::Ask { msg, .. } => msg.downcast_ref(),
+            MsgInner::Broadcast(msg) => msg.downcast_ref(),
+        }
+    }
+
     #[doc(hidden)]
     pub fn downcast_ref<M: Message>(&self) -> Option<Arc<M>> {
         trace!("{:?}: Downcasting to ref of {}.", self, type_name::<M>());
@@ -377,26 +693,52 @@ impl Msg {
         trace!("{:?}: Trying to clone.", self);
         if let MsgInner::Broadcast(msg) = &self.0 {
             let inner = MsgInner::Broadcast(msg.clone());
-            Some(Msg(inner))
+            Some(Msg(inner, self.1))
         } else {
             None
         }
     }
 
+    #[doc(hidden)]
+    pub fn take<M: Message>(self) -> Result<M, Self> {
+        trace!("{:?}: Taking {}.", self, type_name::<M>());
+        let msg = match self.try_unwrap() {
+            Ok(msg) => return Ok(msg),
+            Err(msg) => msg,
+        };
+        if !msg.is_tell() {
+            return Err(msg);
+        }
+
+        // NOTE: messages forwarded by dispatchers are wrapped in an
+        //      `Arc<SignedMessage>`, which is unwrapped if this was its
+        //      only owner.
+        let deadline = msg.1;
+        let forwarded = msg.downcast::<Arc<SignedMessage>>()?;
+        let wrap = |forwarded| Msg(MsgInner::Tell(Box::new(forwarded)), deadline);
+        match Arc::try_unwrap(forwarded) {
+            Ok(SignedMessage { msg, sign }) => msg
+                .take()
+                .map_err(|msg| wrap(Arc::new(SignedMessage::new(msg, sign)))),
+            Err(forwarded) => Err(wrap(forwarded)),
+        }
+    }
+
     pub(crate) fn try_unwrap<M: Message>(self) -> Result<M, Self> {
         debug!("{:?}: Trying to unwrap.", self);
+        let deadline = self.1;
         if let MsgInner::Broadcast(msg) = self.0 {
             match msg.downcast() {
                 Ok(msg) => match Arc::try_unwrap(msg) {
                     Ok(msg) => Ok(msg),
                     Err(msg) => {
                         let inner = MsgInner::Broadcast(msg);
-                        Err(Msg(inner))
+                        Err(Msg(inner, deadline))
                     }
                 },
                 Err(msg) => {
                     let inner = MsgInner::Broadcast(msg);
-                    Err(Msg(inner))
+                    Err(Msg(inner, deadline))
                 }
             }
         } else {
@@ -424,6 +766,10 @@ impl BastionMessage {
         BastionMessage::Stop
     }
 
+    pub(crate) fn graceful_stop(drain_timeout: Duration) -> Self {
+        BastionMessage::GracefulStop { drain_timeout }
+    }
+
     pub(crate) fn kill() -> Self {
         BastionMessage::Kill
     }
@@ -479,8 +825,20 @@ impl BastionMessage {
         (BastionMessage::Message(msg), answer)
     }
 
-    pub(crate) fn restart_required(id: BastionId, parent_id: BastionId) -> Self {
-        BastionMessage::RestartRequired { id, parent_id }
+    pub(crate) fn batch(messages: Vec<Arc<SignedMessage>>) -> Self {
+        BastionMessage::Batch(messages)
+    }
+
+    pub(crate) fn restart_required(
+        id: BastionId,
+        parent_id: BastionId,
+        reason: FaultReason,
+    ) -> Self {
+        BastionMessage::RestartRequired {
+            id,
+            parent_id,
+            reason,
+        }
     }
 
     pub(crate) fn finished_child(id: BastionId, parent_id: BastionId) -> Self {
@@ -507,19 +865,54 @@ impl BastionMessage {
         BastionMessage::Stopped { id }
     }
 
-    pub(crate) fn faulted(id: BastionId) -> Self {
-        BastionMessage::Faulted { id }
+    pub(crate) fn faulted(id: BastionId, reason: FaultReason) -> Self {
+        BastionMessage::Faulted { id, reason }
     }
 
     pub(crate) fn heartbeat() -> Self {
         BastionMessage::Heartbeat
     }
 
+    pub(crate) fn pause() -> Self {
+        BastionMessage::Pause
+    }
+
+    pub(crate) fn resume() -> Self {
+        BastionMessage::Resume
+    }
+
+    pub(crate) fn hold() -> Self {
+        BastionMessage::Hold
+    }
+
+    pub(crate) fn release() -> Self {
+        BastionMessage::Release
+    }
+
+    pub(crate) fn thaw() -> Self {
+        BastionMessage::Thaw
+    }
+
+    pub(crate) fn handover(id: BastionId, to: SupervisorRef) -> Self {
+        BastionMessage::Handover { id, to }
+    }
+
+    pub(crate) fn adopt(adoption: Adoption) -> Self {
+        BastionMessage::Adopt(adoption.into())
+    }
+
+    pub(crate) fn reparent(to: SupervisorRef) -> Self {
+        BastionMessage::Reparent(to)
+    }
+
     pub(crate) fn try_clone(&self) -> Option<Self> {
         trace!("{:?}: Trying to clone.", self);
         let clone = match self {
             BastionMessage::Start => BastionMessage::start(),
             BastionMessage::Stop => BastionMessage::stop(),
+            BastionMessage::GracefulStop { drain_timeout } => {
+                BastionMessage::graceful_stop(*drain_timeout)
+            }
             BastionMessage::Kill => BastionMessage::kill(),
             // FIXME
             BastionMessage::Deploy(_) => unimplemented!(),
@@ -540,9 +933,12 @@ impl BastionMessage {
                 state.clone(),
             ),
             BastionMessage::Message(msg) => BastionMessage::Message(msg.try_clone()?),
-            BastionMessage::RestartRequired { id, parent_id } => {
-                BastionMessage::restart_required(id.clone(), parent_id.clone())
-            }
+            BastionMessage::Batch(messages) => BastionMessage::batch(messages.clone()),
+            BastionMessage::RestartRequired {
+                id,
+                parent_id,
+                reason,
+            } => BastionMessage::restart_required(id.clone(), parent_id.clone(), reason.clone()),
             BastionMessage::FinishedChild { id, parent_id } => {
                 BastionMessage::finished_child(id.clone(), parent_id.clone())
             }
@@ -553,8 +949,20 @@ impl BastionMessage {
             BastionMessage::DropChild { id } => BastionMessage::drop_child(id.clone()),
             BastionMessage::SetState { state } => BastionMessage::set_state(state.clone()),
             BastionMessage::Stopped { id } => BastionMessage::stopped(id.clone()),
-            BastionMessage::Faulted { id } => BastionMessage::faulted(id.clone()),
+            BastionMessage::Faulted { id, reason } => {
+                BastionMessage::faulted(id.clone(), reason.clone())
+            }
             BastionMessage::Heartbeat => BastionMessage::heartbeat(),
+            BastionMessage::Pause => BastionMessage::pause(),
+            BastionMessage::Resume => BastionMessage::resume(),
+            BastionMessage::Hold => BastionMessage::hold(),
+            BastionMessage::Release => BastionMessage::release(),
+            BastionMessage::Thaw => BastionMessage::thaw(),
+            BastionMessage::Handover { id, to } => BastionMessage::handover(id.clone(), to.clone()),
+            // NOTE: an adoption moves the adopted elements, which can't
+            //      be adopted twice.
+            BastionMessage::Adopt(_) => return None,
+            BastionMessage::Reparent(to) => BastionMessage::reparent(to.clone()),
         };
 
         Some(clone)
@@ -569,12 +977,174 @@ impl BastionMessage {
     }
 }
 
+impl OutstandingAsks {
+    pub(crate) fn new(max: Option<usize>) -> Self {
+        OutstandingAsks {
+            max,
+            count: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+
+    /// Takes a slot for a new ask, unless as many asks as allowed are
+    /// already outstanding, in which case the maximum is returned.
+    pub(crate) fn acquire(&self) -> Result<AskSlot, usize> {
+        let max = self.max.unwrap_or(usize::MAX);
+        self.count
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+                if count < max {
+                    Some(count + 1)
+                } else {
+                    None
+                }
+            })
+            .map_err(|_| max)?;
+
+        Ok(AskSlot(self.count.clone()))
+    }
+}
+
+impl Drop for AskSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Answer {
+    pub(crate) fn with_slot(mut self, slot: AskSlot) -> Self {
+        self.1 = Some(slot);
+        self
+    }
+
+    /// Turns this `Answer` into a [`TypedAnswer`] expecting the
+    /// child to answer with a `Result<T, E>`.
+    pub fn typed<T: Message, E: Message>(self) -> TypedAnswer<T, E> {
+        TypedAnswer {
+            answer: self,
+            timeout: None,
+            _result: PhantomData,
+        }
+    }
+}
+
+impl<T: Message, E: Message> TypedAnswer<T, E> {
+    /// Makes this `TypedAnswer` resolve to
+    /// `Err(AnswerError::Timeout(timeout))` if the child didn't
+    /// answer once `timeout` elapsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long to wait for the answer.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some((timeout, Delay::new(timeout)));
+        self
+    }
+}
+
 impl Future for Answer {
     type Output = Result<SignedMessage, ()>;
 
     fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
         debug!("{:?}: Polling.", self);
-        Pin::new(&mut self.get_mut().0).poll(ctx).map_err(|_| ())
+        let this = self.get_mut();
+        let answer = Pin::new(&mut this.0).poll(ctx).map_err(|_| ());
+        if answer.is_ready() {
+            // NOTE: the ask isn't outstanding anymore, even if the
+            //      answer is kept around.
+            this.1.take();
+        }
+
+        answer
+    }
+}
+
+impl<T: Message, E: Message> Future for TypedAnswer<T, E> {
+    type Output = Result<Result<T, E>, AnswerError>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match this.answer.poll_unpin(ctx) {
+            Poll::Ready(Ok(answer)) => {
+                let answer = answer.msg.downcast().map_err(AnswerError::UnexpectedType);
+                return Poll::Ready(answer);
+            }
+            Poll::Ready(Err(())) => return Poll::Ready(Err(AnswerError::Dropped)),
+            Poll::Pending => (),
+        }
+
+        if let Some((timeout, delay)) = &mut this.timeout {
+            if delay.poll_unpin(ctx).is_ready() {
+                debug!("TypedAnswer: Timed out.");
+                return Poll::Ready(Err(AnswerError::Timeout(*timeout)));
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+impl GroupAnswers {
+    pub(crate) fn new(timeout: Duration) -> Self {
+        GroupAnswers {
+            pending: Vec::new(),
+            answers: FuturesUnordered::new(),
+            missing: VecDeque::new(),
+            timeout: Delay::new(timeout),
+        }
+    }
+
+    pub(crate) fn push_answer(&mut self, from: ChildRef, answer: Answer) {
+        let index = self.pending.len();
+        self.pending.push(Some(from));
+        self.answers
+            .push(answer.map(move |answer| (index, answer)).boxed());
+    }
+
+    pub(crate) fn push_missing(&mut self, from: ChildRef) {
+        self.missing.push_back(from);
+    }
+}
+
+impl Stream for GroupAnswers {
+    type Item = GroupReply;
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if let Some(from) = this.missing.pop_front() {
+            return Poll::Ready(Some(GroupReply::Missing { from }));
+        }
+
+        match this.answers.poll_next_unpin(ctx) {
+            Poll::Ready(Some((index, answer))) => {
+                // NOTE: every index is only answered once.
+                let from = this.pending[index].take().unwrap();
+                let reply = match answer {
+                    Ok(answer) => GroupReply::Answered { from, answer },
+                    Err(()) => GroupReply::Missing { from },
+                };
+
+                return Poll::Ready(Some(reply));
+            }
+            Poll::Ready(None) => return Poll::Ready(None),
+            Poll::Pending => (),
+        }
+
+        if this.timeout.poll_unpin(ctx).is_ready() {
+            debug!("GroupAnswers: Timed out.");
+            this.answers = FuturesUnordered::new();
+            this.missing.extend(this.pending.drain(..).flatten());
+
+            return Poll::Ready(
+                this.missing
+                    .pop_front()
+                    .map(|from| GroupReply::Missing { from }),
+            );
+        }
+
+        Poll::Pending
     }
 }
 
@@ -584,12 +1154,21 @@ impl Future for Answer {
 ///
 /// Each case is defined as:
 /// - an optional `ref` which will make the case only match
-///   if the message was broadcasted
+///   if the message was broadcasted, or an optional `move` which
+///   will make the case take ownership of the message, whether it
+///   was "told" or broadcasted (a broadcasted message, even if it
+///   was forwarded by a dispatcher, only matches if this child is
+///   its only receiver left)
 /// - a variable name for the message if it matched this case
 /// - a colon
 /// - a type that the message must be of to match this case
 ///   (note that if the message was broadcasted, the actual
-///   type of the variable will be a reference to this type)
+///   type of the variable will be a reference to this type,
+///   which can be made explicit by prefixing a type name with
+///   `&`, as in `ref data: &Bytes`)
+/// - an optional guard (`if` followed by a condition using the
+///   variable) which will make the case only match if the
+///   condition holds, the next cases being tried otherwise
 /// - an arrow (`=>`) with an optional bang (`!`) between
 ///   the equal and greater-than signs which will make the
 ///   case only match if the message can be answered
@@ -643,7 +1222,11 @@ impl Future for Answer {
 ///                         assert_eq!(msg, &BCAST_MSG);
 ///                         // Handle the message...
 ///                     };
-///                     // We match `&'static str`s "told" to this child...
+///                     // We match a specific `&'static str` "told" to this child...
+///                     msg: &'static str if msg == "reload" => {
+///                         // Handle the message...
+///                     };
+///                     // We match the other `&'static str`s "told" to this child...
 ///                     msg: &'static str => {
 ///                         assert_eq!(msg, TELL_MSG);
 ///                         // Handle the message...
@@ -677,77 +1260,122 @@ impl Future for Answer {
 /// [`BastionContext::try_recv`]: crate::context::BastionContext::try_recv
 macro_rules! msg {
     ($msg:expr, $($tokens:tt)+) => {
-        msg!(@internal $msg, (), (), (), $($tokens)+)
+        msg!(@internal $msg, (), (), (), (), $($tokens)+)
+    };
+
+    (@internal
+        $msg:expr,
+        $bcases:tt,
+        $tcases:tt,
+        $acases:tt,
+        ($($ovar:ident, $oty:ty, $ohandle:expr,)*),
+        move $var:ident: $ty:ty => $handle:expr;
+        $($rest:tt)+
+    ) => {
+        msg!(@internal $msg,
+            $bcases,
+            $tcases,
+            $acases,
+            ($($ovar, $oty, $ohandle,)* $var, $ty, $handle,),
+            $($rest)+
+        )
+    };
+
+    (@internal
+        $msg:expr,
+        ($($bvar:ident, $bty:ty, [$($bguard:tt)*], $bhandle:expr,)*),
+        ($($tvar:ident, $tty:ty, [$($tguard:tt)*], $thandle:expr,)*),
+        ($($avar:ident, $aty:ty, [$($aguard:tt)*], $ahandle:expr,)*),
+        $ocases:tt,
+        ref $var:ident: &$ty:ty => $handle:expr;
+        $($rest:tt)+
+    ) => {
+        msg!(@internal $msg,
+            ($($bvar, $bty, [$($bguard)*], $bhandle,)* $var, $ty, [], $handle,),
+            ($($tvar, $tty, [$($tguard)*], $thandle,)*),
+            ($($avar, $aty, [$($aguard)*], $ahandle,)*),
+            $ocases,
+            $($rest)+
+        )
     };
 
     (@internal
         $msg:expr,
-        ($($bvar:ident, $bty:ty, $bhandle:expr,)*),
-        ($($tvar:ident, $tty:ty, $thandle:expr,)*),
-        ($($avar:ident, $aty:ty, $ahandle:expr,)*),
+        ($($bvar:ident, $bty:ty, [$($bguard:tt)*], $bhandle:expr,)*),
+        ($($tvar:ident, $tty:ty, [$($tguard:tt)*], $thandle:expr,)*),
+        ($($avar:ident, $aty:ty, [$($aguard:tt)*], $ahandle:expr,)*),
+        $ocases:tt,
         ref $var:ident: $ty:ty => $handle:expr;
         $($rest:tt)+
     ) => {
         msg!(@internal $msg,
-            ($($bvar, $bty, $bhandle,)* $var, $ty, $handle,),
-            ($($tvar, $tty, $thandle,)*),
-            ($($avar, $aty, $ahandle,)*),
+            ($($bvar, $bty, [$($bguard)*], $bhandle,)* $var, $ty, [], $handle,),
+            ($($tvar, $tty, [$($tguard)*], $thandle,)*),
+            ($($avar, $aty, [$($aguard)*], $ahandle,)*),
+            $ocases,
             $($rest)+
         )
     };
 
     (@internal
         $msg:expr,
-        ($($bvar:ident, $bty:ty, $bhandle:expr,)*),
-        ($($tvar:ident, $tty:ty, $thandle:expr,)*),
-        ($($avar:ident, $aty:ty, $ahandle:expr,)*),
+        ($($bvar:ident, $bty:ty, [$($bguard:tt)*], $bhandle:expr,)*),
+        ($($tvar:ident, $tty:ty, [$($tguard:tt)*], $thandle:expr,)*),
+        ($($avar:ident, $aty:ty, [$($aguard:tt)*], $ahandle:expr,)*),
+        $ocases:tt,
         $var:ident: $ty:ty => $handle:expr;
         $($rest:tt)+
     ) => {
         msg!(@internal $msg,
-            ($($bvar, $bty, $bhandle,)*),
-            ($($tvar, $tty, $thandle,)* $var, $ty, $handle,),
-            ($($avar, $aty, $ahandle,)*),
+            ($($bvar, $bty, [$($bguard)*], $bhandle,)*),
+            ($($tvar, $tty, [$($tguard)*], $thandle,)* $var, $ty, [], $handle,),
+            ($($avar, $aty, [$($aguard)*], $ahandle,)*),
+            $ocases,
             $($rest)+
         )
     };
 
     (@internal
         $msg:expr,
-        ($($bvar:ident, $bty:ty, $bhandle:expr,)*),
-        ($($tvar:ident, $tty:ty, $thandle:expr,)*),
-        ($($avar:ident, $aty:ty, $ahandle:expr,)*),
+        ($($bvar:ident, $bty:ty, [$($bguard:tt)*], $bhandle:expr,)*),
+        ($($tvar:ident, $tty:ty, [$($tguard:tt)*], $thandle:expr,)*),
+        ($($avar:ident, $aty:ty, [$($aguard:tt)*], $ahandle:expr,)*),
+        $ocases:tt,
         $var:ident: $ty:ty =!> $handle:expr;
         $($rest:tt)+
     ) => {
         msg!(@internal $msg,
-            ($($bvar, $bty, $bhandle,)*),
-            ($($tvar, $tty, $thandle,)*),
-            ($($avar, $aty, $ahandle,)* $var, $ty, $handle,),
+            ($($bvar, $bty, [$($bguard)*], $bhandle,)*),
+            ($($tvar, $tty, [$($tguard)*], $thandle,)*),
+            ($($avar, $aty, [$($aguard)*], $ahandle,)* $var, $ty, [], $handle,),
+            $ocases,
             $($rest)+
         )
     };
 
     (@internal
         $msg:expr,
-        ($($bvar:ident, $bty:ty, $bhandle:expr,)*),
-        ($($tvar:ident, $tty:ty, $thandle:expr,)*),
-        ($($avar:ident, $aty:ty, $ahandle:expr,)*),
+        ($($bvar:ident, $bty:ty, [$($bguard:tt)*], $bhandle:expr,)*),
+        ($($tvar:ident, $tty:ty, [$($tguard:tt)*], $thandle:expr,)*),
+        ($($avar:ident, $aty:ty, [$($aguard:tt)*], $ahandle:expr,)*),
+        $ocases:tt,
         _: _ => $handle:expr;
     ) => {
         msg!(@internal $msg,
-            ($($bvar, $bty, $bhandle,)*),
-            ($($tvar, $tty, $thandle,)*),
-            ($($avar, $aty, $ahandle,)*),
+            ($($bvar, $bty, [$($bguard)*], $bhandle,)*),
+            ($($tvar, $tty, [$($tguard)*], $thandle,)*),
+            ($($avar, $aty, [$($aguard)*], $ahandle,)*),
+            $ocases,
             msg: _ => $handle;
         )
     };
 
     (@internal
         $msg:expr,
-        ($($bvar:ident, $bty:ty, $bhandle:expr,)*),
-        ($($tvar:ident, $tty:ty, $thandle:expr,)*),
-        ($($avar:ident, $aty:ty, $ahandle:expr,)*),
+        ($($bvar:ident, $bty:ty, [$($bguard:tt)*], $bhandle:expr,)*),
+        ($($tvar:ident, $tty:ty, [$($tguard:tt)*], $thandle:expr,)*),
+        ($($avar:ident, $aty:ty, [$($aguard:tt)*], $ahandle:expr,)*),
+        ($($ovar:ident, $oty:ty, $ohandle:expr,)*),
         $var:ident: _ => $handle:expr;
     ) => { {
         let mut signed = $msg;
@@ -766,13 +1394,13 @@ macro_rules! msg {
                 unreachable!();
             }
             $(
-                else if $var.is::<$bty>() {
+                else if $var.is::<$bty>() && msg!(@ref_guard $var, $bvar, $bty, $($bguard)*) {
                     let $bvar = &*$var.downcast_ref::<$bty>().unwrap();
                     { $bhandle }
                 }
             )*
             else {
-                { $handle }
+                msg!(@owned $var, $handle, $($ovar, $oty, $ohandle,)*)
             }
         } else if sender.is_some() {
             let sender = sender.unwrap();
@@ -790,7 +1418,7 @@ macro_rules! msg {
                 unreachable!();
             }
             $(
-                else if $var.is::<$aty>() {
+                else if $var.is::<$aty>() && msg!(@guard $var, $avar, $aty, $($aguard)*) {
                     let $avar = $var.downcast::<$aty>().unwrap();
                     { $ahandle }
                 }
@@ -803,16 +1431,193 @@ macro_rules! msg {
                 unreachable!();
             }
             $(
-                else if $var.is::<$tty>() {
+                else if $var.is::<$tty>() && msg!(@guard $var, $tvar, $tty, $($tguard)*) {
                     let $tvar = $var.downcast::<$tty>().unwrap();
                     { $thandle }
                 }
             )*
             else {
-                { $handle }
+                msg!(@owned $var, $handle, $($ovar, $oty, $ohandle,)*)
             }
         }
     } };
+
+    // Cases with a guard, whose type is collected until the `if`.
+    (@internal
+        $msg:expr,
+        $bcases:tt,
+        $tcases:tt,
+        $acases:tt,
+        $ocases:tt,
+        ref $var:ident: $($rest:tt)+
+    ) => {
+        msg!(@guarded_ty $msg, $bcases, $tcases, $acases, $ocases, (ref $var), [] $($rest)+)
+    };
+
+    (@internal
+        $msg:expr,
+        $bcases:tt,
+        $tcases:tt,
+        $acases:tt,
+        $ocases:tt,
+        $var:ident: $($rest:tt)+
+    ) => {
+        msg!(@guarded_ty $msg, $bcases, $tcases, $acases, $ocases, ($var), [] $($rest)+)
+    };
+
+    (@guarded_ty
+        $msg:expr,
+        $bcases:tt,
+        $tcases:tt,
+        $acases:tt,
+        $ocases:tt,
+        $case:tt,
+        [$($ty:tt)*] if $($rest:tt)+
+    ) => {
+        msg!(@guarded $msg, $bcases, $tcases, $acases, $ocases, $case, [$($ty)*], [] $($rest)+)
+    };
+
+    (@guarded_ty
+        $msg:expr,
+        $bcases:tt,
+        $tcases:tt,
+        $acases:tt,
+        $ocases:tt,
+        $case:tt,
+        [$($ty:tt)*] $next:tt $($rest:tt)+
+    ) => {
+        msg!(@guarded_ty $msg, $bcases, $tcases, $acases, $ocases, $case, [$($ty)* $next] $($rest)+)
+    };
+
+    // The guard is collected until the arrow.
+    (@guarded
+        $msg:expr,
+        ($($bvar:ident, $bty:ty, [$($bguard:tt)*], $bhandle:expr,)*),
+        $tcases:tt,
+        $acases:tt,
+        $ocases:tt,
+        (ref $var:ident),
+        [& $($ty:tt)+],
+        [$($guard:tt)+] => $handle:expr;
+        $($rest:tt)+
+    ) => {
+        msg!(@internal $msg,
+            ($($bvar, $bty, [$($bguard)*], $bhandle,)* $var, $($ty)+, [$($guard)+], $handle,),
+            $tcases,
+            $acases,
+            $ocases,
+            $($rest)+
+        )
+    };
+
+    (@guarded
+        $msg:expr,
+        ($($bvar:ident, $bty:ty, [$($bguard:tt)*], $bhandle:expr,)*),
+        $tcases:tt,
+        $acases:tt,
+        $ocases:tt,
+        (ref $var:ident),
+        [$($ty:tt)+],
+        [$($guard:tt)+] => $handle:expr;
+        $($rest:tt)+
+    ) => {
+        msg!(@internal $msg,
+            ($($bvar, $bty, [$($bguard)*], $bhandle,)* $var, $($ty)+, [$($guard)+], $handle,),
+            $tcases,
+            $acases,
+            $ocases,
+            $($rest)+
+        )
+    };
+
+    (@guarded
+        $msg:expr,
+        $bcases:tt,
+        ($($tvar:ident, $tty:ty, [$($tguard:tt)*], $thandle:expr,)*),
+        $acases:tt,
+        $ocases:tt,
+        ($var:ident),
+        [$($ty:tt)+],
+        [$($guard:tt)+] => $handle:expr;
+        $($rest:tt)+
+    ) => {
+        msg!(@internal $msg,
+            $bcases,
+            ($($tvar, $tty, [$($tguard)*], $thandle,)* $var, $($ty)+, [$($guard)+], $handle,),
+            $acases,
+            $ocases,
+            $($rest)+
+        )
+    };
+
+    (@guarded
+        $msg:expr,
+        $bcases:tt,
+        $tcases:tt,
+        ($($avar:ident, $aty:ty, [$($aguard:tt)*], $ahandle:expr,)*),
+        $ocases:tt,
+        ($var:ident),
+        [$($ty:tt)+],
+        [$($guard:tt)+] =!> $handle:expr;
+        $($rest:tt)+
+    ) => {
+        msg!(@internal $msg,
+            $bcases,
+            $tcases,
+            ($($avar, $aty, [$($aguard)*], $ahandle,)* $var, $($ty)+, [$($guard)+], $handle,),
+            $ocases,
+            $($rest)+
+        )
+    };
+
+    (@guarded
+        $msg:expr,
+        $bcases:tt,
+        $tcases:tt,
+        $acases:tt,
+        $ocases:tt,
+        $case:tt,
+        $ty:tt,
+        [$($guard:tt)*] $next:tt $($rest:tt)+
+    ) => {
+        msg!(@guarded $msg, $bcases, $tcases, $acases, $ocases, $case, $ty, [$($guard)* $next] $($rest)+)
+    };
+
+    // Tries to take the message by value for each of the `move`
+    // cases, in order, falling back to the default case.
+    (@owned $var:ident, $handle:expr,) => {
+        { $handle }
+    };
+
+    (@owned $var:ident, $handle:expr, $ovar:ident, $oty:ty, $ohandle:expr, $($rest:tt)*) => {
+        match $var.take::<$oty>() {
+            Ok($ovar) => { $ohandle }
+            #[allow(unused_mut)]
+            Err(mut $var) => msg!(@owned $var, $handle, $($rest)*),
+        }
+    };
+
+    // Whether the message, which is of the given type, matches the
+    // guard of a case, if any.
+    (@guard $var:ident, $gvar:ident, $ty:ty,) => {
+        true
+    };
+
+    (@guard $var:ident, $gvar:ident, $ty:ty, $($guard:tt)+) => {
+        $var.matches_guard::<$ty, _>(|$gvar: $ty| {
+            let matched = $($guard)+;
+            ($gvar, matched)
+        })
+    };
+
+    (@ref_guard $var:ident, $gvar:ident, $ty:ty,) => {
+        true
+    };
+
+    (@ref_guard $var:ident, $gvar:ident, $ty:ty, $($guard:tt)+) => { {
+        let $gvar = &*$var.downcast_ref::<$ty>().unwrap();
+        $($guard)+
+    } };
 }
 
 #[macro_export]
@@ -1085,10 +1890,13 @@ impl<O> MessageHandler<O> {
         match self.state.take_message() {
             Ok(SignedMessage {
                 msg:
-                    Msg(MsgInner::Ask {
-                        msg,
-                        sender: Some(sender),
-                    }),
+                    Msg(
+                        MsgInner::Ask {
+                            msg,
+                            sender: Some(sender),
+                        },
+                        _,
+                    ),
                 ..
             }) if msg.is::<T>() => {
                 let msg: Box<dyn Any> = msg;
@@ -1109,7 +1917,7 @@ impl<O> MessageHandler<O> {
         );
         match self.state.take_message() {
             Ok(SignedMessage {
-                msg: Msg(MsgInner::Broadcast(msg)),
+                msg: Msg(MsgInner::Broadcast(msg), _),
                 sign,
             }) if msg.is::<T>() => {
                 let msg: Arc<dyn Any + Send + Sync + 'static> = msg;
@@ -1125,7 +1933,7 @@ impl<O> MessageHandler<O> {
         debug!("try_into_tell with type {}", std::any::type_name::<T>());
         match self.state.take_message() {
             Ok(SignedMessage {
-                msg: Msg(MsgInner::Tell(msg)),
+                msg: Msg(MsgInner::Tell(msg), _),
                 sign,
             }) if msg.is::<T>() => {
                 let msg: Box<dyn Any> = msg;
@@ -1136,3 +1944,145 @@ impl<O> MessageHandler<O> {
         }
     }
 }
+
+// A handler registered with `Children::on` or `Children::on_question`,
+// returning the message back if it isn't of its type.
+type TypedHandler = Arc<
+    dyn Fn(
+            Arc<BastionContext>,
+            SignedMessage,
+        ) -> Result<BoxFuture<'static, Result<(), ()>>, SignedMessage>
+        + Send
+        + Sync,
+>;
+// The handler registered with `Children::on_fallback`.
+type FallbackHandler = Arc<
+    dyn Fn(Arc<BastionContext>, SignedMessage) -> BoxFuture<'static, Result<(), ()>> + Send + Sync,
+>;
+
+#[derive(Clone, Default)]
+/// The handlers of the message types received by the elements of a
+/// children group, driving their message loop instead of an exec
+/// closure (see `Children::on`).
+pub(crate) struct TypedHandlers {
+    handlers: Vec<TypedHandler>,
+    fallback: Option<FallbackHandler>,
+}
+
+impl TypedHandlers {
+    pub(crate) fn on<T, H, F>(&mut self, handler: H)
+    where
+        T: Message + Clone,
+        H: Fn(Arc<BastionContext>, T) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        self.handlers.push(Arc::new(
+            move |ctx: Arc<BastionContext>, smsg: SignedMessage| {
+                let SignedMessage { msg, sign } = smsg;
+                let msg = match msg.take::<T>() {
+                    Ok(msg) => return Ok(handler(ctx, msg).boxed()),
+                    Err(msg) => msg,
+                };
+
+                match shared::<T>(&msg) {
+                    Some(msg) => Ok(handler(ctx, msg).boxed()),
+                    None => Err(SignedMessage::new(msg, sign)),
+                }
+            },
+        ));
+    }
+
+    pub(crate) fn on_question<T, H, F>(&mut self, handler: H)
+    where
+        T: Message,
+        H: Fn(Arc<BastionContext>, T, AnswerSender) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        self.handlers.push(Arc::new(
+            move |ctx: Arc<BastionContext>, smsg: SignedMessage| {
+                let SignedMessage { mut msg, sign } = smsg;
+                if !msg.is_ask() || !msg.is::<T>() {
+                    return Err(SignedMessage::new(msg, sign));
+                }
+
+                match msg.take_sender() {
+                    // NOTE: the message was checked to be a `T`.
+                    Some(sender) => Ok(handler(ctx, msg.downcast::<T>().unwrap(), sender).boxed()),
+                    None => Err(SignedMessage::new(msg, sign)),
+                }
+            },
+        ));
+    }
+
+    pub(crate) fn on_fallback<H, F>(&mut self, handler: H)
+    where
+        H: Fn(Arc<BastionContext>, SignedMessage) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        self.fallback = Some(Arc::new(
+            move |ctx: Arc<BastionContext>, msg: SignedMessage| handler(ctx, msg).boxed(),
+        ));
+    }
+
+    /// Hands the message to the first handler of its type, or to the
+    /// fallback handler if there is none, dropping it if there isn't
+    /// a fallback handler either.
+    pub(crate) async fn dispatch(
+        &self,
+        ctx: Arc<BastionContext>,
+        mut msg: SignedMessage,
+    ) -> Result<(), ()> {
+        for handler in &self.handlers {
+            match handler(ctx.clone(), msg) {
+                Ok(handling) => return handling.await,
+                Err(unmatched) => msg = unmatched,
+            }
+        }
+
+        match &self.fallback {
+            Some(fallback) => fallback(ctx, msg).await,
+            None => {
+                debug!(
+                    "BastionContext({}): Dropping unhandled message: {:?}",
+                    ctx.current().id(),
+                    msg
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the exec closure receiving the messages of every element
+    /// and handing them to the handlers.
+    pub(crate) fn init(&self) -> Init {
+        let handlers = self.clone();
+        Init::new(move |ctx: BastionContext| {
+            let handlers = handlers.clone();
+            async move {
+                let ctx = Arc::new(ctx);
+                loop {
+                    let msg = ctx.recv().await?;
+                    handlers.dispatch(ctx.clone(), msg).await?;
+                }
+            }
+        })
+    }
+}
+
+/// Returns a copy of the message if it is a `T` shared with other
+/// recipients (because it was broadcasted or forwarded by a
+/// dispatcher).
+fn shared<T: Message + Clone>(msg: &Msg) -> Option<T> {
+    msg.peek::<T>()
+        .or_else(|| msg.peek::<Arc<SignedMessage>>()?.msg.peek::<T>())
+        .cloned()
+}
+
+impl Debug for TypedHandlers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TypedHandlers")
+            .field("handlers", &self.handlers.len())
+            .field("fallback", &self.fallback.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,306 @@
+//!
+//! Ordered delivery of the payloads exchanged between the members of a
+//! cluster, whose transport can deliver them out of order.
+//!
+//! When enabled with [`ClusterConfig::with_ordered_delivery`], every
+//! payload sent with [`DistributedContext::tell`] is framed with a
+//! sequence number specific to its sender and receiver, and the
+//! receiver holds the payloads received ahead of time until the
+//! missing ones arrive. The sequence numbers exchanged with a member
+//! start over once it is removed, rejoins or reconnects, since it may
+//! have restarted meanwhile.
+//!
+//! [`ClusterConfig::with_ordered_delivery`]: crate::distributed::ClusterConfig::with_ordered_delivery
+//! [`DistributedContext::tell`]: crate::distributed::DistributedContext::tell
+use crate::compression;
+use fxhash::FxHashMap;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// The codec tag of the payloads framed with their sequence number.
+const SEQUENCE_TAG: &str = "seq";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// What a member does once it waited for a missing payload for longer
+/// than the gap timeout of its [`OrderedDelivery`].
+///
+/// In both cases, the payloads received after the missing ones are
+/// delivered and the missing ones are dropped if they arrive later.
+pub enum GapPolicy {
+    /// The missing payloads are skipped silently.
+    Skip,
+    /// The missing payloads are skipped, and a
+    /// [`SystemEvent::DeliveryGap`] is emitted on
+    /// [`Bastion::event_stream`].
+    ///
+    /// [`SystemEvent::DeliveryGap`]: crate::events::SystemEvent::DeliveryGap
+    /// [`Bastion::event_stream`]: crate::Bastion::event_stream
+    Surface,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The configuration of the ordered delivery of the payloads received
+/// by a member, set with [`ClusterConfig::with_ordered_delivery`].
+///
+/// [`ClusterConfig::with_ordered_delivery`]: crate::distributed::ClusterConfig::with_ordered_delivery
+pub struct OrderedDelivery {
+    gap_timeout: Duration,
+    gap_policy: GapPolicy,
+}
+
+#[derive(Debug)]
+/// The payloads received ahead of time from every member, held until
+/// the ones sent before them are received.
+pub(crate) struct ReorderBuffers {
+    gap_timeout: Duration,
+    gap_policy: GapPolicy,
+    buffers: FxHashMap<Uuid, ReorderBuffer>,
+}
+
+#[derive(Debug, Default)]
+struct ReorderBuffer {
+    // The sequence number of the next payload to deliver.
+    next: u64,
+    // The payloads received ahead of time, by sequence number.
+    pending: BTreeMap<u64, String>,
+    // When the buffer started waiting for the next payload, if
+    // payloads are pending.
+    waiting_since: Option<Instant>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Payloads which were skipped after waiting for them for longer than
+/// the gap timeout.
+pub(crate) struct Gap {
+    pub(crate) member: Uuid,
+    pub(crate) missing: u64,
+}
+
+impl OrderedDelivery {
+    /// Creates a configuration waiting up to 500ms for a missing
+    /// payload before skipping it silently.
+    pub fn new() -> Self {
+        OrderedDelivery {
+            gap_timeout: Duration::from_millis(500),
+            gap_policy: GapPolicy::Skip,
+        }
+    }
+
+    /// Sets how long the payloads received after a missing one are
+    /// held before the missing one is skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `gap_timeout` - How long to wait for a missing payload.
+    pub fn with_gap_timeout(mut self, gap_timeout: Duration) -> Self {
+        self.gap_timeout = gap_timeout;
+        self
+    }
+
+    /// Sets what happens once a missing payload is skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `gap_policy` - The policy applied to the missing payloads.
+    pub fn with_gap_policy(mut self, gap_policy: GapPolicy) -> Self {
+        self.gap_policy = gap_policy;
+        self
+    }
+
+    /// Returns how long a missing payload is waited for.
+    pub fn gap_timeout(&self) -> Duration {
+        self.gap_timeout
+    }
+
+    /// Returns what happens once a missing payload is skipped.
+    pub fn gap_policy(&self) -> GapPolicy {
+        self.gap_policy
+    }
+}
+
+impl Default for OrderedDelivery {
+    fn default() -> Self {
+        OrderedDelivery::new()
+    }
+}
+
+/// Frames the payload with the given sequence number.
+pub(crate) fn sequenced(seq: u64, payload: &str) -> String {
+    compression::tagged(SEQUENCE_TAG, &format!("{}:{}", seq, payload))
+}
+
+/// Returns the sequence number the payload was framed with, if any,
+/// and the payload itself.
+pub(crate) fn unsequenced(payload: String) -> (Option<u64>, String) {
+    let frame = compression::untagged(SEQUENCE_TAG, &payload)
+        .and_then(|frame| frame.split_once(':'))
+        .and_then(|(seq, body)| Some((seq.parse().ok()?, body.to_string())));
+
+    match frame {
+        Some((seq, body)) => (Some(seq), body),
+        None => (None, payload),
+    }
+}
+
+impl ReorderBuffers {
+    pub(crate) fn new(config: &OrderedDelivery) -> Self {
+        ReorderBuffers {
+            gap_timeout: config.gap_timeout,
+            gap_policy: config.gap_policy,
+            buffers: FxHashMap::default(),
+        }
+    }
+
+    /// Adds a payload received from `member`, returning the payloads
+    /// which can be delivered, in order.
+    pub(crate) fn push(
+        &mut self,
+        member: Uuid,
+        seq: u64,
+        payload: String,
+        now: Instant,
+    ) -> Vec<String> {
+        let buffer = self.buffers.entry(member).or_default();
+        if seq < buffer.next {
+            // NOTE: the payload was already delivered or skipped.
+            return Vec::new();
+        }
+
+        buffer.pending.insert(seq, payload);
+        buffer.release(now)
+    }
+
+    /// Skips the payloads waited for for longer than the gap timeout,
+    /// returning the gaps and the payloads which can be delivered
+    /// now, in order.
+    pub(crate) fn expire(&mut self, now: Instant) -> (Vec<Gap>, Vec<(Uuid, String)>) {
+        let mut gaps = Vec::new();
+        let mut ready = Vec::new();
+        for (member, buffer) in self.buffers.iter_mut() {
+            let waiting_since = match buffer.waiting_since {
+                Some(waiting_since) => waiting_since,
+                None => continue,
+            };
+            if now.duration_since(waiting_since) < self.gap_timeout {
+                continue;
+            }
+
+            // NOTE: there is a pending payload if the buffer is waiting.
+            let first = *buffer.pending.keys().next().unwrap();
+            gaps.push(Gap {
+                member: *member,
+                missing: first - buffer.next,
+            });
+            buffer.next = first;
+            buffer.waiting_since = None;
+            ready.extend(
+                buffer
+                    .release(now)
+                    .into_iter()
+                    .map(|payload| (*member, payload)),
+            );
+        }
+
+        (gaps, ready)
+    }
+
+    /// Drops the payloads held for a member which was removed, rejoined
+    /// or reconnected, whose sequence numbers restart from zero if it
+    /// restarted meanwhile.
+    pub(crate) fn forget(&mut self, member: Uuid) {
+        self.buffers.remove(&member);
+    }
+
+    pub(crate) fn gap_policy(&self) -> GapPolicy {
+        self.gap_policy
+    }
+}
+
+impl ReorderBuffer {
+    // Removes the payloads which follow the last delivered one.
+    fn release(&mut self, now: Instant) -> Vec<String> {
+        let mut ready = Vec::new();
+        while let Some(payload) = self.pending.remove(&self.next) {
+            ready.push(payload);
+            self.next += 1;
+            self.waiting_since = None;
+        }
+
+        if !self.pending.is_empty() && self.waiting_since.is_none() {
+            self.waiting_since = Some(now);
+        }
+
+        ready
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_round_trip() {
+        let payload = "a payload: with colons";
+
+        assert_eq!(
+            unsequenced(sequenced(42, payload)),
+            (Some(42), payload.to_string())
+        );
+        assert_eq!(
+            unsequenced(payload.to_string()),
+            (None, payload.to_string())
+        );
+    }
+
+    #[test]
+    fn payloads_are_released_in_order() {
+        let mut buffers = ReorderBuffers::new(&OrderedDelivery::new());
+        let member = Uuid::new_v4();
+        let now = Instant::now();
+
+        assert!(buffers.push(member, 2, "c".to_string(), now).is_empty());
+        assert!(buffers.push(member, 1, "b".to_string(), now).is_empty());
+        assert_eq!(
+            buffers.push(member, 0, "a".to_string(), now),
+            vec!["a", "b", "c"]
+        );
+        // Already delivered.
+        assert!(buffers.push(member, 1, "b".to_string(), now).is_empty());
+    }
+
+    #[test]
+    fn gaps_are_skipped_after_the_timeout() {
+        let config = OrderedDelivery::new().with_gap_timeout(Duration::from_millis(100));
+        let mut buffers = ReorderBuffers::new(&config);
+        let member = Uuid::new_v4();
+        let now = Instant::now();
+
+        assert_eq!(buffers.push(member, 0, "a".to_string(), now), vec!["a"]);
+        assert!(buffers.push(member, 3, "d".to_string(), now).is_empty());
+        assert_eq!(buffers.expire(now), (vec![], vec![]));
+
+        let (gaps, ready) = buffers.expire(now + Duration::from_millis(100));
+        assert_eq!(gaps, vec![Gap { member, missing: 2 }]);
+        assert_eq!(ready, vec![(member, "d".to_string())]);
+
+        // The skipped payloads are dropped if they arrive late.
+        let later = now + Duration::from_millis(200);
+        assert!(buffers.push(member, 1, "b".to_string(), later).is_empty());
+        assert_eq!(buffers.push(member, 4, "e".to_string(), later), vec!["e"]);
+    }
+
+    #[test]
+    fn forgotten_members_start_over() {
+        let mut buffers = ReorderBuffers::new(&OrderedDelivery::new());
+        let member = Uuid::new_v4();
+        let now = Instant::now();
+
+        assert_eq!(buffers.push(member, 0, "a".to_string(), now), vec!["a"]);
+        assert_eq!(buffers.push(member, 1, "b".to_string(), now), vec!["b"]);
+
+        // The member restarted, sending its payloads from zero again.
+        buffers.forget(member);
+        assert_eq!(buffers.push(member, 0, "c".to_string(), now), vec!["c"]);
+    }
+}
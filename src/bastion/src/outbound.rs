@@ -0,0 +1,245 @@
+//!
+//! Bounded queues holding the payloads sent to every member of a
+//! cluster until the transport can carry them, so that a slow member
+//! only backs up its own queue.
+//!
+//! When enabled with [`ClusterConfig::with_outbound_queue`], every
+//! payload is handed to the transport right away if it can carry it
+//! (see [`ClusterTransport::can_send`]), and queued for its receiver
+//! otherwise. Once the queue of a member is full, the payloads sent to
+//! it are handled according to the queue's [`OutboundOverflow`].
+//!
+//! [`ClusterConfig::with_outbound_queue`]: crate::distributed::ClusterConfig::with_outbound_queue
+//! [`ClusterTransport::can_send`]: crate::transport::ClusterTransport::can_send
+use fxhash::FxHashMap;
+use std::collections::VecDeque;
+use std::time::Duration;
+use tracing::trace;
+use uuid::Uuid;
+
+/// How long a member waits before trying to queue a payload again,
+/// once the queue of its receiver is full and blocks.
+pub(crate) const BLOCK_INTERVAL: Duration = Duration::from_millis(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// What happens to the payloads sent to a member whose
+/// [`OutboundQueue`] is full.
+pub enum OutboundOverflow {
+    /// The sender waits until the transport carried the oldest payload
+    /// queued for the member, blocking its thread.
+    Block,
+    /// The oldest payload queued for the member is dropped to make
+    /// room for the new one, and counted as dropped.
+    DropOldest,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The configuration of the queues holding the payloads sent to every
+/// member until the transport can carry them, set with
+/// [`ClusterConfig::with_outbound_queue`].
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn configure(config: ClusterConfig) -> ClusterConfig {
+/// // Up to 1024 payloads are queued for every member, the oldest
+/// // ones being dropped once a member's queue is full.
+/// let queue = OutboundQueue::new(1024).with_overflow(OutboundOverflow::DropOldest);
+///
+/// config.with_outbound_queue(queue)
+/// # }
+/// ```
+///
+/// [`ClusterConfig::with_outbound_queue`]: crate::distributed::ClusterConfig::with_outbound_queue
+pub struct OutboundQueue {
+    capacity: usize,
+    overflow: OutboundOverflow,
+}
+
+#[derive(Debug)]
+/// The payloads waiting for the transport to carry them to every
+/// member.
+pub(crate) struct OutboundQueues {
+    capacity: usize,
+    overflow: OutboundOverflow,
+    queues: FxHashMap<Uuid, PeerQueue>,
+}
+
+#[derive(Debug, Default)]
+struct PeerQueue {
+    pending: VecDeque<String>,
+    // The amount of payloads dropped because the queue was full or
+    // its member was removed.
+    dropped: u64,
+}
+
+impl OutboundQueue {
+    /// Creates a configuration queueing up to `capacity` payloads for
+    /// every member and blocking the sender once a queue is full.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The amount of payloads queued for every member.
+    pub fn new(capacity: usize) -> Self {
+        OutboundQueue {
+            capacity,
+            overflow: OutboundOverflow::Block,
+        }
+    }
+
+    /// Sets what happens to the payloads sent to a member whose queue
+    /// is full.
+    ///
+    /// # Arguments
+    ///
+    /// * `overflow` - The policy applied once a queue is full.
+    pub fn with_overflow(mut self, overflow: OutboundOverflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Returns the amount of payloads queued for every member.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns what happens to the payloads sent to a member whose
+    /// queue is full.
+    pub fn overflow(&self) -> OutboundOverflow {
+        self.overflow
+    }
+}
+
+impl OutboundQueues {
+    pub(crate) fn new(config: &OutboundQueue) -> Self {
+        OutboundQueues {
+            // NOTE: a queue always holds the payload being sent.
+            capacity: config.capacity.max(1),
+            overflow: config.overflow,
+            queues: FxHashMap::default(),
+        }
+    }
+
+    /// Queues the payload for `to`, dropping the oldest payload queued
+    /// for it if its queue is full and drops them, or returning the
+    /// payload if it blocks.
+    pub(crate) fn push(&mut self, to: Uuid, payload: String) -> Result<(), String> {
+        let queue = self.queues.entry(to).or_default();
+        if queue.pending.len() >= self.capacity {
+            match self.overflow {
+                OutboundOverflow::Block => return Err(payload),
+                OutboundOverflow::DropOldest => {
+                    queue.pending.pop_front();
+                    queue.dropped += 1;
+                    trace!(
+                        "OutboundQueues: Dropped the oldest payload for Member({}), {} so far.",
+                        to,
+                        queue.dropped
+                    );
+                }
+            }
+        }
+
+        queue.pending.push_back(payload);
+        Ok(())
+    }
+
+    /// Returns the oldest payload queued for `to`, if any.
+    pub(crate) fn pop(&mut self, to: Uuid) -> Option<String> {
+        self.queues.get_mut(&to)?.pending.pop_front()
+    }
+
+    /// Returns the members which have payloads queued.
+    pub(crate) fn backed_up(&self) -> Vec<Uuid> {
+        self.queues
+            .iter()
+            .filter(|(_, queue)| !queue.pending.is_empty())
+            .map(|(member, _)| *member)
+            .collect()
+    }
+
+    /// Drops the payloads queued for a member which was removed,
+    /// counting them as dropped.
+    pub(crate) fn forget(&mut self, member: Uuid) {
+        if let Some(queue) = self.queues.get_mut(&member) {
+            queue.dropped += queue.pending.len() as u64;
+            queue.pending.clear();
+        }
+    }
+
+    pub(crate) fn len(&self, member: Uuid) -> usize {
+        self.queues
+            .get(&member)
+            .map_or(0, |queue| queue.pending.len())
+    }
+
+    #[cfg(feature = "metrics")]
+    pub(crate) fn dropped(&self, member: Uuid) -> u64 {
+        self.queues.get(&member).map_or(0, |queue| queue.dropped)
+    }
+}
+
+impl Default for OutboundQueue {
+    /// Queues up to 1024 payloads for every member, blocking the
+    /// sender once a queue is full.
+    fn default() -> Self {
+        OutboundQueue::new(1024)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queues(capacity: usize, overflow: OutboundOverflow) -> OutboundQueues {
+        OutboundQueues::new(&OutboundQueue::new(capacity).with_overflow(overflow))
+    }
+
+    #[test]
+    fn full_queues_drop_their_oldest_payloads() {
+        let mut queues = queues(2, OutboundOverflow::DropOldest);
+        let (slow, fast) = (Uuid::new_v4(), Uuid::new_v4());
+
+        for i in 0..5 {
+            queues.push(slow, i.to_string()).unwrap();
+        }
+        queues.push(fast, "fast".to_string()).unwrap();
+
+        assert_eq!(queues.len(slow), 2);
+        assert_eq!(queues.pop(slow), Some("3".to_string()));
+        assert_eq!(queues.pop(slow), Some("4".to_string()));
+        assert_eq!(queues.pop(slow), None);
+        assert_eq!(queues.pop(fast), Some("fast".to_string()));
+        assert!(queues.backed_up().is_empty());
+    }
+
+    #[test]
+    fn full_queues_can_block() {
+        let mut queues = queues(1, OutboundOverflow::Block);
+        let member = Uuid::new_v4();
+
+        queues.push(member, "first".to_string()).unwrap();
+        assert_eq!(
+            queues.push(member, "second".to_string()),
+            Err("second".to_string())
+        );
+        assert_eq!(queues.backed_up(), vec![member]);
+
+        assert_eq!(queues.pop(member), Some("first".to_string()));
+        queues.push(member, "second".to_string()).unwrap();
+        assert_eq!(queues.len(member), 1);
+    }
+
+    #[test]
+    fn removed_members_lose_their_payloads() {
+        let mut queues = queues(4, OutboundOverflow::Block);
+        let member = Uuid::new_v4();
+
+        queues.push(member, "lost".to_string()).unwrap();
+        queues.forget(member);
+        assert_eq!(queues.len(member), 0);
+        assert_eq!(queues.pop(member), None);
+    }
+}
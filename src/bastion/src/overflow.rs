@@ -0,0 +1,103 @@
+//!
+//! The drop priorities of the message types, deciding which messages
+//! are evicted first from the mailbox of an element once it is full
+//! (see [`Children::with_typed_overflow`]).
+//!
+//! [`Children::with_typed_overflow`]: crate::children::Children::with_typed_overflow
+use crate::message::{Message, Msg};
+use fxhash::FxHashMap;
+use std::any::{type_name, TypeId};
+use tracing::trace;
+
+#[derive(Debug, Clone, Default)]
+/// The drop priorities of the message types received by the elements
+/// of a children group, used once their mailbox is full to evict the
+/// messages of the lowest priority first.
+///
+/// The messages whose type wasn't given a priority have the default
+/// priority, which is `0` unless set with [`with_default_priority`].
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # #[cfg(feature = "tokio-runtime")]
+/// # #[tokio::main]
+/// # async fn main() {
+/// #    run();
+/// # }
+/// #
+/// # #[cfg(not(feature = "tokio-runtime"))]
+/// # fn main() {
+/// #    run();
+/// # }
+/// #
+/// # fn run() {
+/// # Bastion::init();
+/// #
+/// #[derive(Debug)]
+/// struct Ping;
+/// #[derive(Debug)]
+/// struct Command(String);
+///
+/// // The pings are evicted before the commands.
+/// let priorities = DropPriorities::new()
+///     .with_priority::<Ping>(0)
+///     .with_priority::<Command>(10);
+/// #
+/// # Bastion::start();
+/// # Bastion::stop();
+/// # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`with_default_priority`]: Self::with_default_priority
+pub struct DropPriorities {
+    priorities: FxHashMap<TypeId, u32>,
+    default: u32,
+}
+
+impl DropPriorities {
+    /// Creates drop priorities giving every message type the default
+    /// priority of `0`.
+    pub fn new() -> Self {
+        DropPriorities::default()
+    }
+
+    /// Sets the drop priority of the messages of type `M`, the ones of
+    /// the lowest priority being evicted first.
+    ///
+    /// # Arguments
+    ///
+    /// * `priority` - The drop priority of the messages of type `M`.
+    pub fn with_priority<M: Message>(mut self, priority: u32) -> Self {
+        trace!(
+            "DropPriorities: Setting the priority of {}: {}",
+            type_name::<M>(),
+            priority
+        );
+        self.priorities.insert(TypeId::of::<M>(), priority);
+        self
+    }
+
+    /// Sets the drop priority of the messages whose type wasn't given
+    /// a priority with [`with_priority`].
+    ///
+    /// # Arguments
+    ///
+    /// * `priority` - The default drop priority.
+    ///
+    /// [`with_priority`]: Self::with_priority
+    pub fn with_default_priority(mut self, priority: u32) -> Self {
+        self.default = priority;
+        self
+    }
+
+    pub(crate) fn priority_of(&self, msg: &Msg) -> u32 {
+        self.priorities
+            .get(&msg.content_type_id())
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
@@ -3,10 +3,14 @@
 //! later will be used to route messages to them
 
 use crate::context::{BastionId, NIL_ID};
+use crate::errors::PathError;
+use std::convert::TryFrom;
 use std::fmt;
+use std::net::SocketAddr;
 use std::result::Result;
+use uuid::Uuid;
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 /// Represents a Path for a System, Supervisor, Children or Child.
 ///
 /// BastionPath can be used to identify message senders.
@@ -58,6 +62,20 @@ pub struct BastionPath {
     // TODO: possibly more effective collection depending on how we'll use it in routing
     parent_chain: Vec<BastionId>,
     this: Option<BastionPathElement>,
+    // The cluster member the path belongs to, if it is remote.
+    node: Option<RemoteNode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The cluster member a remote [`BastionPath`] belongs to, rendered
+/// as `name@addr`.
+///
+/// The name of a member is the one it was configured with (see
+/// `ClusterConfig::with_node_name`), or its node id otherwise.
+pub struct RemoteNode {
+    host_key: Uuid,
+    name: String,
+    addr: Option<SocketAddr>,
 }
 
 impl BastionPath {
@@ -66,9 +84,124 @@ impl BastionPath {
         BastionPath {
             parent_chain: vec![],
             this: None,
+            node: None,
         }
     }
 
+    /// Builds the path made of the given elements, each of them nested
+    /// in the previous one, and belonging to the given cluster member
+    /// if any (or to this process otherwise).
+    ///
+    /// The first element needs to be a supervisor, which can be
+    /// followed by other supervisors, then by a children group and
+    /// one of its children. A path without elements is the root path.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The cluster member the path belongs to, if it is
+    ///     remote (see [`RemoteNode::try_new`]).
+    /// * `elements` - The elements of the path, from the outermost
+    ///     supervisor to the element itself.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children_ref = Bastion::children(|children| children).unwrap();
+    /// let child = children_ref.elems()[0].path();
+    ///
+    /// let supervisor = BastionPathElement::Supervisor(child.group_path().group_path().id().clone());
+    /// let children = BastionPathElement::Children(children_ref.id().clone());
+    /// let rebuilt = BastionPath::try_new(None, vec![supervisor.clone(), children.clone()]).unwrap();
+    /// assert_eq!(rebuilt, child.group_path());
+    ///
+    /// // A children group can't be nested in another one.
+    /// assert!(BastionPath::try_new(None, vec![supervisor, children.clone(), children]).is_err());
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn try_new<I>(node: Option<RemoteNode>, elements: I) -> Result<BastionPath, PathError>
+    where
+        I: IntoIterator<Item = BastionPathElement>,
+    {
+        let mut path = BastionPath::root();
+        path.node = node;
+
+        elements.into_iter().try_fold(path, |path, element| {
+            path.append(element)
+                .map_err(|err| PathError::MisplacedElement(err.to_string()))
+        })
+    }
+
+    // A sender on another member of a cluster.
+    #[cfg(feature = "distributed")]
+    pub(crate) fn remote(node: RemoteNode) -> BastionPath {
+        BastionPath {
+            parent_chain: vec![],
+            this: None,
+            node: Some(node),
+        }
+    }
+
+    /// Returns the cluster member this path belongs to, if it is the
+    /// path of a remote sender.
+    pub fn node(&self) -> Option<&RemoteNode> {
+        self.node.as_ref()
+    }
+
+    /// Checks whether this path belongs to another member of a
+    /// cluster (see [`node`]).
+    ///
+    /// [`node`]: Self::node
+    pub fn is_remote(&self) -> bool {
+        self.node.is_some()
+    }
+
+    /// Checks whether this path belongs to this process, as opposed
+    /// to another member of a cluster (see [`is_remote`]).
+    ///
+    /// [`is_remote`]: Self::is_remote
+    pub fn is_local(&self) -> bool {
+        self.node.is_none()
+    }
+
+    /// Returns the name of the cluster member this path belongs to,
+    /// if it is remote.
+    pub fn node_name(&self) -> Option<&str> {
+        self.node.as_ref().map(RemoteNode::name)
+    }
+
+    /// Returns the address of the cluster member this path belongs
+    /// to, if it is remote and its address is known.
+    pub fn remote_addr(&self) -> Option<SocketAddr> {
+        self.node.as_ref().and_then(RemoteNode::addr)
+    }
+
+    /// Returns the node id of the cluster member this path belongs
+    /// to, if it is remote, which can be used to send it messages
+    /// with `DistributedContext::tell`.
+    pub fn host_key(&self) -> Option<Uuid> {
+        self.node.as_ref().map(RemoteNode::host_key)
+    }
+
     /// iterates over path elements
     pub(crate) fn iter(&self) -> impl Iterator<Item = &BastionId> {
         let parent_iter = self.parent_chain.iter();
@@ -180,6 +313,102 @@ impl BastionPath {
         &self.this
     }
 
+    /// Returns the path of the group of the element, which is the path
+    /// of its parent: the path of a child's children group, or of a
+    /// children group's or a supervisor's supervisor.
+    ///
+    /// The group of the elements supervised by the system, and of
+    /// the root itself, is the root path.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children_ref = Bastion::children(|children| children.with_redundancy(2)).unwrap();
+    /// let child_path = children_ref.elems()[0].path();
+    /// assert_eq!(child_path.group_path().id(), children_ref.id());
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn group_path(&self) -> BastionPath {
+        let mut parent_chain = self.parent_chain.clone();
+        let this = match (parent_chain.pop(), &self.this) {
+            (None, _) => None,
+            (Some(id), Some(BastionPathElement::Child(_))) => {
+                Some(BastionPathElement::Children(id))
+            }
+            (Some(id), _) => Some(BastionPathElement::Supervisor(id)),
+        };
+
+        BastionPath {
+            parent_chain,
+            this,
+            node: self.node.clone(),
+        }
+    }
+
+    /// Checks whether both paths refer to elements of the same group,
+    /// ignoring the elements' own identifiers (see [`group_path`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The path to compare this one to.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    ///     # Bastion::init();
+    ///     #
+    /// let workers = Bastion::children(|children| children.with_redundancy(2)).unwrap();
+    /// let others = Bastion::children(|children| children).unwrap();
+    ///
+    /// let worker = workers.elems()[0].path();
+    /// assert!(worker.same_group(workers.elems()[1].path()));
+    /// assert!(!worker.same_group(others.elems()[0].path()));
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`group_path`]: Self::group_path
+    pub fn same_group(&self, other: &BastionPath) -> bool {
+        self.this.is_some() && other.this.is_some() && self.parent_chain == other.parent_chain
+    }
+
     /// Checks whether `BastionPath` is a dead-letters path.
     ///
     /// # Example
@@ -235,6 +464,10 @@ impl BastionPath {
 
 impl fmt::Display for BastionPath {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(node) = &self.node {
+            write!(f, "{}", node)?;
+        }
+
         write!(
             f,
             "/{}",
@@ -248,6 +481,10 @@ impl fmt::Display for BastionPath {
 
 impl fmt::Debug for BastionPath {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(node) = &self.node {
+            write!(f, "{}", node)?;
+        }
+
         match &self.this {
             Some(this @ BastionPathElement::Supervisor(_))
             | Some(this @ BastionPathElement::Children(_)) => write!(
@@ -288,6 +525,141 @@ impl fmt::Debug for BastionPath {
     }
 }
 
+impl TryFrom<Vec<BastionPathElement>> for BastionPath {
+    type Error = PathError;
+
+    fn try_from(elements: Vec<BastionPathElement>) -> Result<Self, Self::Error> {
+        BastionPath::try_new(None, elements)
+    }
+}
+
+impl RemoteNode {
+    // Doesn't check the name, which is either a node id or checked
+    // with `check_name` beforehand.
+    #[cfg(feature = "distributed")]
+    pub(crate) fn new(host_key: Uuid, name: String, addr: Option<SocketAddr>) -> Self {
+        RemoteNode {
+            host_key,
+            name,
+            addr,
+        }
+    }
+
+    /// Creates the cluster member with the given node id, name and
+    /// address, if any, which can be used to build the path of a
+    /// remote element with [`BastionPath::try_new`].
+    ///
+    /// The name is rendered before the address (`name@addr`) and the
+    /// elements of the path (`name@addr/...`), so it can't be empty
+    /// nor contain a `@`, a `/`, a whitespace or a control character.
+    ///
+    /// # Arguments
+    ///
+    /// * `host_key` - The node id of the member.
+    /// * `name` - The name of the member.
+    /// * `addr` - The address of the member, if it is known.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// use uuid::Uuid;
+    ///
+    /// let node = RemoteNode::try_new(Uuid::new_v4(), "node-a", None).unwrap();
+    /// let path = BastionPath::try_new(Some(node), vec![]).unwrap();
+    /// assert_eq!(path.to_string(), "node-a/");
+    ///
+    /// assert!(RemoteNode::try_new(Uuid::new_v4(), "node@a", None).is_err());
+    /// ```
+    pub fn try_new<N>(host_key: Uuid, name: N, addr: Option<SocketAddr>) -> Result<Self, PathError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+        RemoteNode::check_name(&name)?;
+
+        Ok(RemoteNode {
+            host_key,
+            name,
+            addr,
+        })
+    }
+
+    /// Parses the cluster member rendered as `name@addr` (or `name`
+    /// if its address isn't known), with the given node id, which
+    /// isn't rendered.
+    ///
+    /// The address is split from the name at the first `@`, which
+    /// names can't contain, so IPv6 addresses are parsed in their
+    /// bracketed form (`name@[::1]:8080`).
+    ///
+    /// # Arguments
+    ///
+    /// * `host_key` - The node id of the member.
+    /// * `node` - The member, as rendered by its path.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// use std::net::SocketAddr;
+    /// use uuid::Uuid;
+    ///
+    /// let node = RemoteNode::parse(Uuid::new_v4(), "node-a@[::1]:8080").unwrap();
+    /// assert_eq!(node.name(), "node-a");
+    /// assert_eq!(node.addr(), Some("[::1]:8080".parse::<SocketAddr>().unwrap()));
+    ///
+    /// assert!(RemoteNode::parse(Uuid::new_v4(), "node-a@::1:8080").is_err());
+    /// ```
+    pub fn parse(host_key: Uuid, node: &str) -> Result<Self, PathError> {
+        let (name, addr) = match node.find('@') {
+            Some(at) => {
+                let addr = &node[at + 1..];
+                let addr = addr
+                    .parse()
+                    .map_err(|_| PathError::InvalidNodeAddr(addr.to_string()))?;
+                (&node[..at], Some(addr))
+            }
+            None => (node, None),
+        };
+
+        RemoteNode::try_new(host_key, name, addr)
+    }
+
+    pub(crate) fn check_name(name: &str) -> Result<(), PathError> {
+        let invalid = |c: char| c == '@' || c == '/' || c.is_whitespace() || c.is_control();
+        if name.is_empty() || name.contains(invalid) {
+            return Err(PathError::InvalidNodeName(name.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the node id of the member.
+    pub fn host_key(&self) -> Uuid {
+        self.host_key
+    }
+
+    /// Returns the name of the member.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the address of the member, if it is known.
+    pub fn addr(&self) -> Option<SocketAddr> {
+        self.addr
+    }
+}
+
+impl fmt::Display for RemoteNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.addr {
+            Some(addr) => write!(f, "{}@{}", self.name, addr),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
 #[derive(Clone, PartialEq)]
 /// Represents BastionPath element
 ///
@@ -473,11 +845,13 @@ impl BastionPath {
             sv @ BastionPathElement::Supervisor(_) => match self.this {
                 None => Ok(BastionPath {
                     parent_chain: self.parent_chain,
+                    node: self.node,
                     this: Some(sv),
                 }),
                 Some(BastionPathElement::Supervisor(id)) => {
                     let mut path = BastionPath {
                         parent_chain: self.parent_chain,
+                        node: self.node,
                         this: Some(sv),
                     };
                     path.parent_chain.push(id);
@@ -486,6 +860,7 @@ impl BastionPath {
                 this => Err(AppendError {
                     path: BastionPath {
                         parent_chain: self.parent_chain,
+                        node: self.node,
                         this,
                     },
                     element: sv,
@@ -495,6 +870,7 @@ impl BastionPath {
                 Some(BastionPathElement::Supervisor(id)) => {
                     let mut path = BastionPath {
                         parent_chain: self.parent_chain,
+                        node: self.node,
                         this: Some(children),
                     };
                     path.parent_chain.push(id);
@@ -503,6 +879,7 @@ impl BastionPath {
                 this => Err(AppendError {
                     path: BastionPath {
                         parent_chain: self.parent_chain,
+                        node: self.node,
                         this,
                     },
                     element: children,
@@ -512,6 +889,7 @@ impl BastionPath {
                 Some(BastionPathElement::Children(id)) => {
                     let mut path = BastionPath {
                         parent_chain: self.parent_chain,
+                        node: self.node,
                         this: Some(child),
                     };
                     path.parent_chain.push(id);
@@ -520,6 +898,7 @@ impl BastionPath {
                 this => Err(AppendError {
                     path: BastionPath {
                         parent_chain: self.parent_chain,
+                        node: self.node,
                         this,
                     },
                     element: child,
@@ -720,4 +1099,235 @@ mod tests {
             "Child is not appendable to a child"
         );
     }
+
+    // Groups
+
+    #[test]
+    fn group_path_strips_the_last_element() {
+        let sv_id = BastionId::new();
+        let children_id = BastionId::new();
+        let sv_path = BastionPath::root()
+            .append(BastionPathElement::Supervisor(sv_id.clone()))
+            .unwrap();
+        let children_path = sv_path
+            .clone()
+            .append(BastionPathElement::Children(children_id.clone()))
+            .unwrap();
+        let child_path = children_path
+            .clone()
+            .append(BastionPathElement::Child(BastionId::new()))
+            .unwrap();
+
+        let group = child_path.group_path();
+        assert_eq!(
+            group.elem(),
+            &Some(BastionPathElement::Children(children_id))
+        );
+        assert_eq!(
+            group.iter().collect::<Vec<_>>(),
+            children_path.iter().collect::<Vec<_>>()
+        );
+
+        let group = children_path.group_path();
+        assert_eq!(group.elem(), &Some(BastionPathElement::Supervisor(sv_id)));
+        assert_eq!(
+            group.iter().collect::<Vec<_>>(),
+            sv_path.iter().collect::<Vec<_>>()
+        );
+
+        assert_eq!(sv_path.group_path().elem(), &None);
+        assert_eq!(BastionPath::root().group_path().elem(), &None);
+    }
+
+    #[test]
+    fn same_group_with_multiple_elements() {
+        let sv_id = BastionId::new();
+        let children = |children_id: &BastionId| {
+            BastionPath::root()
+                .append(BastionPathElement::Supervisor(sv_id.clone()))
+                .unwrap()
+                .append(BastionPathElement::Children(children_id.clone()))
+                .unwrap()
+        };
+        let child = |children_id: &BastionId| {
+            children(children_id)
+                .append(BastionPathElement::Child(BastionId::new()))
+                .unwrap()
+        };
+        let workers_id = BastionId::new();
+        let others_id = BastionId::new();
+
+        let worker = child(&workers_id);
+        assert!(worker.same_group(&worker));
+        assert!(worker.same_group(&child(&workers_id)));
+        assert!(!worker.same_group(&child(&others_id)));
+        // A group isn't part of itself.
+        assert!(!worker.same_group(&children(&workers_id)));
+        // Sibling groups are part of the same group.
+        assert!(children(&workers_id).same_group(&children(&others_id)));
+    }
+
+    #[test]
+    fn same_group_with_a_single_element() {
+        let sv_path = |sv_id: BastionId| {
+            BastionPath::root()
+                .append(BastionPathElement::Supervisor(sv_id))
+                .unwrap()
+        };
+        let sv_id = BastionId::new();
+        let nested = sv_path(sv_id.clone())
+            .append(BastionPathElement::Supervisor(BastionId::new()))
+            .unwrap();
+
+        assert!(sv_path(BastionId::new()).same_group(&sv_path(BastionId::new())));
+        assert!(!sv_path(sv_id).same_group(&nested));
+        assert!(!BastionPath::root().same_group(&sv_path(BastionId::new())));
+        assert!(!BastionPath::root().same_group(&BastionPath::root()));
+    }
+
+    #[test]
+    #[cfg(feature = "distributed")]
+    fn remote_paths_are_prefixed_with_their_node() {
+        let addr = SocketAddr::from(([10, 0, 0, 1], 4000));
+        let host_key = Uuid::new_v4();
+        let path = BastionPath::remote(RemoteNode::new(host_key, "node-a".to_string(), Some(addr)));
+        assert!(path.is_remote());
+        assert!(!path.is_local());
+        assert_eq!(path.to_string(), "node-a@10.0.0.1:4000/");
+        assert_eq!(path.host_key(), Some(host_key));
+        assert_eq!(path.node_name(), Some("node-a"));
+        assert_eq!(path.remote_addr(), Some(addr));
+
+        let unknown =
+            BastionPath::remote(RemoteNode::new(Uuid::new_v4(), "node-b".to_string(), None));
+        assert_eq!(unknown.to_string(), "node-b/");
+        assert_eq!(unknown.node_name(), Some("node-b"));
+        assert_eq!(unknown.remote_addr(), None);
+        assert!(!BastionPath::root().is_remote());
+        assert_eq!(BastionPath::root().host_key(), None);
+    }
+
+    // Raw components
+
+    #[test]
+    fn try_new_rejects_invalid_node_names() {
+        for name in &["node@a", "node/a", "node a", "node\na", ""] {
+            assert_eq!(
+                RemoteNode::try_new(Uuid::new_v4(), *name, None),
+                Err(PathError::InvalidNodeName(name.to_string()))
+            );
+        }
+
+        let addr = SocketAddr::from(([10, 0, 0, 1], 4000));
+        let node = RemoteNode::try_new(Uuid::new_v4(), "node-a", Some(addr)).unwrap();
+        let path = BastionPath::try_new(Some(node), vec![]).unwrap();
+        assert_eq!(path.to_string(), "node-a@10.0.0.1:4000/");
+    }
+
+    #[test]
+    fn remote_nodes_round_trip_through_their_rendering() {
+        let host_key = Uuid::new_v4();
+        let addr = SocketAddr::from(([10, 0, 0, 1], 4000));
+        let node = RemoteNode::try_new(host_key, "node-a", Some(addr)).unwrap();
+        assert_eq!(node.to_string(), "node-a@10.0.0.1:4000");
+        assert_eq!(RemoteNode::parse(host_key, &node.to_string()), Ok(node));
+
+        let host_key = Uuid::new_v4();
+        let node = RemoteNode::parse(host_key, "node").unwrap();
+        assert_eq!(node, RemoteNode::try_new(host_key, "node", None).unwrap());
+    }
+
+    #[test]
+    fn ipv6_remote_nodes_round_trip_through_their_rendering() {
+        let host_key = Uuid::new_v4();
+        let addr = SocketAddr::from(([0x2001, 0xdb8, 0, 0, 0, 0, 0, 1], 9000));
+        let node = RemoteNode::try_new(host_key, "node", Some(addr)).unwrap();
+
+        // The address is bracketed, so that its colons can't be
+        // mistaken for the port separator.
+        let rendered = node.to_string();
+        assert_eq!(rendered, "node@[2001:db8::1]:9000");
+        assert_eq!(RemoteNode::parse(host_key, &rendered), Ok(node.clone()));
+
+        let path = BastionPath::try_new(Some(node), vec![]).unwrap();
+        assert_eq!(path.to_string(), "node@[2001:db8::1]:9000/");
+
+        let host_key = Uuid::new_v4();
+        let addr = "[::1]:8080".parse().unwrap();
+        let node = RemoteNode::try_new(host_key, "node-a", Some(addr)).unwrap();
+        assert_eq!(RemoteNode::parse(host_key, &node.to_string()), Ok(node));
+    }
+
+    #[test]
+    fn parse_rejects_invalid_nodes() {
+        for addr in &["2001:db8::1:9000", "[2001:db8::1]", "10.0.0.1", ""] {
+            assert_eq!(
+                RemoteNode::parse(Uuid::new_v4(), &format!("node@{}", addr)),
+                Err(PathError::InvalidNodeAddr(addr.to_string()))
+            );
+        }
+
+        assert_eq!(
+            RemoteNode::parse(Uuid::new_v4(), "@10.0.0.1:4000"),
+            Err(PathError::InvalidNodeName(String::new()))
+        );
+    }
+
+    #[test]
+    fn try_new_rejects_scope_breaking_elements() {
+        let res = BastionPath::try_new(None, vec![BastionPathElement::Child(BastionId::new())]);
+        assert_eq!(
+            res.unwrap_err(),
+            PathError::MisplacedElement("Child is not appendable to root".to_string())
+        );
+
+        let res = BastionPath::try_from(vec![
+            BastionPathElement::Supervisor(BastionId::new()),
+            BastionPathElement::Children(BastionId::new()),
+            BastionPathElement::Supervisor(BastionId::new()),
+        ]);
+        assert_eq!(
+            res.unwrap_err(),
+            PathError::MisplacedElement("Supervisor is not appendable to children".to_string())
+        );
+    }
+
+    #[test]
+    fn try_new_accepts_multiple_elements() {
+        let ids = (0..4).map(|_| BastionId::new()).collect::<Vec<_>>();
+        let elements = vec![
+            BastionPathElement::Supervisor(ids[0].clone()),
+            BastionPathElement::Supervisor(ids[1].clone()),
+            BastionPathElement::Children(ids[2].clone()),
+            BastionPathElement::Child(ids[3].clone()),
+        ];
+        let path = BastionPath::try_from(elements.clone()).unwrap();
+        assert_eq!(
+            path.iter().collect::<Vec<_>>(),
+            ids.iter().collect::<Vec<_>>()
+        );
+        assert!(path.elem().as_ref().unwrap().is_child());
+        assert!(path.is_local());
+
+        let expected = elements
+            .into_iter()
+            .try_fold(BastionPath::root(), BastionPath::append)
+            .unwrap();
+        assert_eq!(path, expected);
+        assert_eq!(BastionPath::try_from(vec![]).unwrap(), BastionPath::root());
+    }
+
+    #[test]
+    fn local_paths_have_no_node() {
+        let sv_id = BastionId::new();
+        let path = BastionPath::root()
+            .append(BastionPathElement::Supervisor(sv_id.clone()))
+            .unwrap();
+        assert!(path.is_local());
+        assert!(!path.is_remote());
+        assert_eq!(path.node_name(), None);
+        assert_eq!(path.remote_addr(), None);
+        assert_eq!(path.id(), &sv_id);
+        assert!(path.elem().as_ref().unwrap().is_supervisor());
+    }
 }
@@ -0,0 +1,538 @@
+//!
+//! A write-ahead log persisting the messages received by the elements
+//! of a children group, so that the ones which weren't handled yet
+//! when the group crashed are replayed once it is launched again.
+//!
+//! The log is split into segments, which are appended to until they
+//! hold [`SEGMENT_ENTRIES`] messages. The oldest segments are removed
+//! once all their messages were handled.
+//!
+//! The records are written by a thread dedicated to the log, in
+//! batches synced once each. A message is only handled once the
+//! batch holding it was synced, so the elements appending messages
+//! meanwhile share a single sync.
+use crate::message::{Message, Msg};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug, Formatter};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+use tracing::{debug, trace, warn};
+
+/// The amount of messages appended to a segment of the log before a
+/// new segment is started.
+pub(crate) const SEGMENT_ENTRIES: usize = 1024;
+
+const SEGMENT_PREFIX: &str = "segment-";
+const SEGMENT_EXTENSION: &str = "log";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum Record {
+    Append { seq: u64, payload: String },
+    Done { seq: u64 },
+}
+
+/// Persists the messages of type `M` received by the elements of a
+/// children group (see [`Children::with_persistence`]).
+///
+/// [`Children::with_persistence`]: crate::children::Children::with_persistence
+pub(crate) struct Persistence {
+    wal: Mutex<Wal>,
+    encode: fn(&Msg) -> Option<String>,
+    decode: fn(&str) -> Option<Msg>,
+}
+
+struct Wal {
+    dir: PathBuf,
+    // The index of the segment currently appended to.
+    segment: u64,
+    // The operations waiting for the writer thread, and the thread
+    // itself.
+    writer: Option<(Sender<WalOp>, JoinHandle<()>)>,
+    // The amount of messages appended to the current segment.
+    appended: usize,
+    next_seq: u64,
+    // The messages which weren't handled yet, with the index of the
+    // segment holding them.
+    pending: BTreeMap<u64, (u64, String)>,
+    // The amount of messages which weren't handled yet held by
+    // every segment, in the order the segments were created in.
+    live: BTreeMap<u64, usize>,
+    // The messages found pending when the log was opened, which
+    // weren't replayed yet.
+    recovered: Vec<u64>,
+}
+
+/// An operation handed to the writer thread of a [`Wal`].
+enum WalOp {
+    // A record, and who to tell once it was synced if anyone.
+    Record(String, Option<Sender<()>>),
+    Roll {
+        segment: u64,
+    },
+    Remove {
+        segment: u64,
+    },
+    #[cfg(test)]
+    Flush(Sender<()>),
+}
+
+/// Writes the records of a log, syncing it once every operation
+/// waiting to be handled was handled.
+struct Writer {
+    dir: PathBuf,
+    // The segment currently written to, and whether it was written
+    // to since it was last synced.
+    file: File,
+    dirty: bool,
+    // Who to tell once the records written since the log was last
+    // synced are synced.
+    synced: Vec<Sender<()>>,
+}
+
+impl Persistence {
+    pub(crate) fn open<M, P>(dir: P) -> io::Result<Self>
+    where
+        M: Message + Serialize + DeserializeOwned,
+        P: AsRef<Path>,
+    {
+        let wal = Wal::open(dir.as_ref())?;
+
+        Ok(Persistence {
+            wal: Mutex::new(wal),
+            encode: encode::<M>,
+            decode: decode::<M>,
+        })
+    }
+
+    /// Appends the message to the log if it is of the persisted type,
+    /// returning its sequence number once it was synced to the disk.
+    pub(crate) fn append(&self, msg: &Msg) -> Option<u64> {
+        let payload = (self.encode)(msg)?;
+        // FIXME: panics?
+        let (seq, synced) = self.wal.lock().unwrap().append(payload);
+        // NOTE: the log isn't locked meanwhile, so that the messages
+        //      appended by other elements are synced along with it.
+        synced.recv().ok();
+        Some(seq)
+    }
+
+    /// Marks the message as handled, so that it isn't replayed.
+    pub(crate) fn complete(&self, seq: u64) {
+        // FIXME: panics?
+        self.wal.lock().unwrap().complete(seq);
+    }
+
+    /// Returns the message with the given sequence number if it
+    /// wasn't handled yet.
+    pub(crate) fn replay(&self, seq: u64) -> Option<Msg> {
+        // FIXME: panics?
+        let wal = self.wal.lock().unwrap();
+        let (_, payload) = wal.pending.get(&seq)?;
+        (self.decode)(payload)
+    }
+
+    /// Returns the messages which weren't handled when the log was
+    /// opened, in the order they were received in, the first time
+    /// it is called.
+    pub(crate) fn recover(&self) -> Vec<(u64, Msg)> {
+        // FIXME: panics?
+        let recovered = std::mem::take(&mut self.wal.lock().unwrap().recovered);
+        debug!("Persistence: Recovering {} messages.", recovered.len());
+
+        let mut msgs = Vec::with_capacity(recovered.len());
+        for seq in recovered {
+            match self.replay(seq) {
+                Some(msg) => msgs.push((seq, msg)),
+                None => {
+                    warn!("Persistence: Dropping undecodable message #{}.", seq);
+                    self.complete(seq);
+                }
+            }
+        }
+
+        msgs
+    }
+}
+
+impl Wal {
+    fn open(dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+
+        let mut segments = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if let Some(segment) = segment_index(&path) {
+                segments.push(segment);
+            }
+        }
+        segments.sort_unstable();
+
+        let mut next_seq = 0;
+        let mut pending = BTreeMap::new();
+        let mut live = BTreeMap::new();
+        for segment in segments.iter().copied() {
+            live.insert(segment, 0);
+
+            let file = File::open(segment_path(dir, segment))?;
+            for line in BufReader::new(file).lines() {
+                let line = line?;
+                // NOTE: the last record might have been partially
+                //      written if the process crashed meanwhile.
+                match serde_json::from_str(&line) {
+                    Ok(Record::Append { seq, payload }) => {
+                        next_seq = next_seq.max(seq + 1);
+                        pending.insert(seq, (segment, payload));
+                    }
+                    Ok(Record::Done { seq }) => {
+                        pending.remove(&seq);
+                    }
+                    Err(e) => warn!("Persistence: Skipping a corrupted record: {}", e),
+                }
+            }
+        }
+
+        for (segment, _) in pending.values() {
+            *live.entry(*segment).or_insert(0) += 1;
+        }
+
+        let segment = segments.last().map_or(0, |last| last + 1);
+        let file = create_segment(dir, segment)?;
+        live.insert(segment, 0);
+        let writer = Writer::spawn(dir.to_path_buf(), file)?;
+        let recovered = pending.keys().copied().collect();
+        debug!(
+            "Persistence: Opened the log in {:?} with {} pending messages.",
+            dir,
+            pending.len()
+        );
+
+        let mut wal = Wal {
+            dir: dir.to_path_buf(),
+            segment,
+            writer: Some(writer),
+            appended: 0,
+            next_seq,
+            pending,
+            live,
+            recovered,
+        };
+        wal.compact();
+
+        Ok(wal)
+    }
+
+    /// Appends the payload to the log, returning its sequence number
+    /// and a receiver told once it was synced.
+    fn append(&mut self, payload: String) -> (u64, Receiver<()>) {
+        if self.appended >= SEGMENT_ENTRIES {
+            self.roll();
+        }
+
+        let seq = self.next_seq;
+        let record = Record::Append {
+            seq,
+            payload: payload.clone(),
+        };
+        let (synced, wait) = mpsc::channel();
+        self.write(&record, Some(synced));
+        trace!("Persistence: Appended message #{}.", seq);
+
+        self.next_seq += 1;
+        self.appended += 1;
+        self.pending.insert(seq, (self.segment, payload));
+        *self.live.entry(self.segment).or_insert(0) += 1;
+
+        (seq, wait)
+    }
+
+    fn complete(&mut self, seq: u64) {
+        let (segment, _) = match self.pending.remove(&seq) {
+            Some(pending) => pending,
+            None => return,
+        };
+
+        // NOTE: a message marked as done which wasn't synced yet is
+        //      replayed if the process crashes meanwhile.
+        self.write(&Record::Done { seq }, None);
+        trace!("Persistence: Marked message #{} as done.", seq);
+        if let Some(live) = self.live.get_mut(&segment) {
+            *live -= 1;
+        }
+
+        self.compact()
+    }
+
+    /// Hands the record to the writer thread, which writes it to the
+    /// current segment and tells `synced` once it was synced.
+    fn write(&self, record: &Record, synced: Option<Sender<()>>) {
+        match serde_json::to_string(record) {
+            Ok(mut line) => {
+                line.push('\n');
+                self.send(WalOp::Record(line, synced));
+            }
+            Err(e) => warn!("Persistence: Couldn't serialize a record: {}", e),
+        }
+    }
+
+    fn send(&self, op: WalOp) {
+        if let Some((writer, _)) = &self.writer {
+            // NOTE: the writer thread only stops once the log is dropped.
+            writer.send(op).ok();
+        }
+    }
+
+    fn roll(&mut self) {
+        self.segment += 1;
+        debug!("Persistence: Starting segment #{}.", self.segment);
+        self.send(WalOp::Roll {
+            segment: self.segment,
+        });
+        self.appended = 0;
+        self.live.insert(self.segment, 0);
+
+        self.compact()
+    }
+
+    #[cfg(test)]
+    /// Waits until the writer thread handled every operation sent so
+    /// far.
+    fn flush(&self) {
+        let (flushed, wait) = mpsc::channel();
+        self.send(WalOp::Flush(flushed));
+        wait.recv().ok();
+    }
+
+    /// Removes the oldest segments as long as all their messages
+    /// were handled.
+    fn compact(&mut self) {
+        // NOTE: a segment can hold the records marking as done the
+        //      messages of older segments, which is why segments are
+        //      only removed once all the older ones were.
+        while let Some((&segment, &live)) = self.live.iter().next() {
+            if live > 0 || segment == self.segment {
+                break;
+            }
+
+            debug!("Persistence: Removing segment #{}.", segment);
+            self.send(WalOp::Remove { segment });
+            self.live.remove(&segment);
+        }
+    }
+}
+
+impl Drop for Wal {
+    fn drop(&mut self) {
+        // NOTE: the writer thread writes the records it was sent
+        //      before stopping.
+        if let Some((writer, thread)) = self.writer.take() {
+            drop(writer);
+            thread.join().ok();
+        }
+    }
+}
+
+impl Writer {
+    fn spawn(dir: PathBuf, file: File) -> io::Result<(Sender<WalOp>, JoinHandle<()>)> {
+        let (sender, ops) = mpsc::channel();
+        let writer = Writer {
+            dir,
+            file,
+            dirty: false,
+            synced: Vec::new(),
+        };
+        let thread = thread::Builder::new()
+            .name("bastion-wal".to_string())
+            .spawn(move || writer.run(ops))?;
+
+        Ok((sender, thread))
+    }
+
+    fn run(mut self, ops: Receiver<WalOp>) {
+        // NOTE: every operation sent before blocking is handled before
+        //      the log is synced.
+        while let Ok(op) = ops.recv() {
+            self.handle(op);
+            for op in ops.try_iter() {
+                self.handle(op);
+            }
+
+            self.sync();
+        }
+    }
+
+    fn handle(&mut self, op: WalOp) {
+        let result = match op {
+            WalOp::Record(line, synced) => {
+                self.synced.extend(synced);
+                self.write(&line)
+            }
+            WalOp::Roll { segment } => self.roll(segment),
+            WalOp::Remove { segment } => fs::remove_file(segment_path(&self.dir, segment)),
+            #[cfg(test)]
+            WalOp::Flush(flushed) => {
+                self.sync();
+                flushed.send(()).ok();
+                Ok(())
+            }
+        };
+
+        if let Err(e) = result {
+            warn!("Persistence: Couldn't write to the log: {}", e);
+        }
+    }
+
+    fn write(&mut self, line: &str) -> io::Result<()> {
+        self.file.write_all(line.as_bytes())?;
+        self.dirty = true;
+        Ok(())
+    }
+
+    fn roll(&mut self, segment: u64) -> io::Result<()> {
+        // NOTE: the records of the previous segment are synced before
+        //      it is closed.
+        self.sync();
+        self.file = create_segment(&self.dir, segment)?;
+        Ok(())
+    }
+
+    fn sync(&mut self) {
+        if self.dirty {
+            if let Err(e) = self.file.sync_data() {
+                warn!("Persistence: Couldn't sync the log: {}", e);
+            }
+            self.dirty = false;
+        }
+
+        for synced in self.synced.drain(..) {
+            synced.send(()).ok();
+        }
+    }
+}
+
+fn encode<M>(msg: &Msg) -> Option<String>
+where
+    M: Message + Serialize,
+{
+    let msg = msg.peek::<M>()?;
+    match serde_json::to_string(msg) {
+        Ok(payload) => Some(payload),
+        Err(e) => {
+            warn!("Persistence: Couldn't serialize {:?}: {}", msg, e);
+            None
+        }
+    }
+}
+
+fn decode<M>(payload: &str) -> Option<Msg>
+where
+    M: Message + DeserializeOwned,
+{
+    serde_json::from_str::<M>(payload).ok().map(Msg::tell)
+}
+
+fn segment_path(dir: &Path, segment: u64) -> PathBuf {
+    dir.join(format!(
+        "{}{:020}.{}",
+        SEGMENT_PREFIX, segment, SEGMENT_EXTENSION
+    ))
+}
+
+fn segment_index(path: &Path) -> Option<u64> {
+    if path.extension()? != SEGMENT_EXTENSION {
+        return None;
+    }
+
+    path.file_stem()?
+        .to_str()?
+        .strip_prefix(SEGMENT_PREFIX)?
+        .parse()
+        .ok()
+}
+
+fn create_segment(dir: &Path, segment: u64) -> io::Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(segment_path(dir, segment))
+}
+
+impl Debug for Persistence {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        // FIXME: panics?
+        let wal = self.wal.lock().unwrap();
+        fmt.debug_struct("Persistence")
+            .field("dir", &wal.dir)
+            .field("segment", &wal.segment)
+            .field("pending", &wal.pending.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("bastion-persistence-{}", Uuid::new_v4()))
+    }
+
+    fn segments(dir: &Path) -> usize {
+        fs::read_dir(dir)
+            .unwrap()
+            .filter(|entry| segment_index(&entry.as_ref().unwrap().path()).is_some())
+            .count()
+    }
+
+    #[test]
+    fn test_completed_segments_are_compacted() {
+        let dir = temp_dir();
+        let mut wal = Wal::open(&dir).unwrap();
+
+        let seqs = (0..SEGMENT_ENTRIES * 2 + 1)
+            .map(|i| wal.append(i.to_string()).0)
+            .collect::<Vec<_>>();
+        wal.flush();
+        assert_eq!(segments(&dir), 3);
+
+        // Completing the messages of the first segment removes it...
+        for seq in &seqs[..SEGMENT_ENTRIES] {
+            wal.complete(*seq);
+        }
+        wal.flush();
+        assert_eq!(segments(&dir), 2);
+
+        // ...and only the other messages are recovered once the log
+        // is opened again.
+        drop(wal);
+        let wal = Wal::open(&dir).unwrap();
+        assert_eq!(wal.recovered, seqs[SEGMENT_ENTRIES..].to_vec());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_appended_messages_are_synced_before_returning() {
+        let dir = temp_dir();
+        let persistence = Persistence::open::<String, _>(&dir).unwrap();
+
+        let seq = persistence.append(&Msg::tell("hello".to_string())).unwrap();
+
+        // The message is recovered by a log opened right away, while
+        // the first one and its writer thread are still alive.
+        let wal = Wal::open(&dir).unwrap();
+        assert_eq!(wal.recovered, vec![seq]);
+        assert_eq!(wal.pending[&seq].1, "\"hello\"");
+
+        drop(wal);
+        drop(persistence);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,321 @@
+//!
+//! Staged pipelines of children groups, where each stage transforms
+//! the items it receives and forwards its output to the next stage.
+//!
+//! Each stage of a [`Pipeline`] is launched as a children group
+//! joining a dispatcher, to which the previous stage (or
+//! [`PipelineRef::push`] for the first stage) broadcasts its output
+//! and which distributes the items among the stage's elements.
+use crate::children_ref::ChildrenRef;
+use crate::context::{BastionContext, BastionId};
+use crate::dispatcher::{BroadcastTarget, Dispatcher, DispatcherType};
+use crate::envelope::{RefAddr, SignedMessage};
+use crate::message::{Message, Msg};
+use crate::supervisor::SupervisorRef;
+use crate::system::SYSTEM;
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use tracing::{debug, trace};
+
+// Handles an item received by a stage, given the target of the next
+// stage if there is one.
+type StageHandler =
+    Arc<dyn Fn(&BastionContext, &SignedMessage, Option<&BroadcastTarget>) + Send + Sync>;
+
+/// A stage of a [`Pipeline`], transforming the items of type `I` it
+/// receives into items of type `O` forwarded to the next stage.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// let parse = Stage::new(|line: &String| line.len()).with_redundancy(4);
+/// ```
+pub struct Stage<I, O> {
+    redundancy: usize,
+    handler: StageHandler,
+    _types: PhantomData<fn(I) -> O>,
+}
+
+/// A chain of [`Stage`]s taking items of type `I` and outputting
+/// items of type `O` from its last stage, built using
+/// [`Pipeline::new`] and [`Pipeline::then`] and launched using
+/// [`Bastion::pipeline`] or [`SupervisorRef::pipeline`].
+///
+/// [`Bastion::pipeline`]: crate::Bastion::pipeline
+pub struct Pipeline<I, O> {
+    stages: Vec<(usize, StageHandler)>,
+    _types: PhantomData<fn(I) -> O>,
+}
+
+#[derive(Debug)]
+/// A reference to a launched [`Pipeline`] taking items of type `I`.
+pub struct PipelineRef<I> {
+    input: String,
+    stages: Vec<ChildrenRef>,
+    _input: PhantomData<fn(I)>,
+}
+
+impl<I, O> Stage<I, O>
+where
+    I: Message,
+    O: Message,
+{
+    /// Creates a new stage applying the given closure to every item
+    /// it receives and forwarding its output to the next stage of
+    /// the pipeline, if there is one.
+    ///
+    /// The stage is made of a single element by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `transform` - The closure applied to the received items.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// let double = Stage::new(|item: &usize| item * 2);
+    /// ```
+    pub fn new<F>(transform: F) -> Self
+    where
+        F: Fn(&I) -> O + Send + Sync + 'static,
+    {
+        let handler =
+            move |ctx: &BastionContext, item: &SignedMessage, next: Option<&BroadcastTarget>| {
+                match item.peek::<I>() {
+                    Some(input) => {
+                        let output = transform(input);
+                        if let Some(next) = next {
+                            trace!("Pipeline: Forwarding {:?} to {:?}.", output, next);
+                            ctx.broadcast_message(next.clone(), output);
+                        }
+                    }
+                    None => debug!("Pipeline: Ignoring unexpected item: {:?}", item),
+                }
+            };
+
+        Stage {
+            redundancy: 1,
+            handler: Arc::new(handler),
+            _types: PhantomData,
+        }
+    }
+
+    /// Sets the amount of elements the stage is launched with, among
+    /// which the items it receives are distributed.
+    ///
+    /// # Arguments
+    ///
+    /// * `redundancy` - The amount of elements of the stage.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// let double = Stage::new(|item: &usize| item * 2).with_redundancy(4);
+    /// ```
+    pub fn with_redundancy(mut self, redundancy: usize) -> Self {
+        self.redundancy = redundancy;
+        self
+    }
+}
+
+impl<I, O> Pipeline<I, O>
+where
+    I: Message,
+    O: Message,
+{
+    /// Creates a new pipeline starting with the given stage.
+    ///
+    /// # Arguments
+    ///
+    /// * `stage` - The first stage of the pipeline.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// let pipeline = Pipeline::new(Stage::new(|item: &usize| item * 2));
+    /// ```
+    pub fn new(stage: Stage<I, O>) -> Self {
+        Pipeline {
+            stages: vec![(stage.redundancy, stage.handler)],
+            _types: PhantomData,
+        }
+    }
+
+    /// Appends a stage to the pipeline, receiving the output of its
+    /// current last stage.
+    ///
+    /// # Arguments
+    ///
+    /// * `stage` - The stage to append to the pipeline.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// let pipeline = Pipeline::new(Stage::new(|item: &usize| item * 2))
+    ///     .then(Stage::new(|item: &usize| format!("item #{}", item)).with_redundancy(2))
+    ///     .then(Stage::new(|text: &String| println!("{}", text)));
+    /// ```
+    pub fn then<P>(mut self, stage: Stage<O, P>) -> Pipeline<I, P>
+    where
+        P: Message,
+    {
+        self.stages.push((stage.redundancy, stage.handler));
+
+        Pipeline {
+            stages: self.stages,
+            _types: PhantomData,
+        }
+    }
+
+    pub(crate) fn launch(self, supervisor: &SupervisorRef) -> Result<PipelineRef<I>, ()> {
+        let id = BastionId::new();
+        let names = (0..self.stages.len())
+            .map(|index| format!("pipeline-{}-stage-{}", id, index))
+            .collect::<Vec<_>>();
+        debug!("Pipeline({}): Launching {} stages.", id, names.len());
+
+        // The stages are launched starting from the last one, so that
+        // each stage's output can be received by the next one as soon
+        // as it is launched.
+        let mut stages = Vec::with_capacity(names.len());
+        for (index, (redundancy, handler)) in self.stages.into_iter().enumerate().rev() {
+            let name = names[index].clone();
+            let next = names
+                .get(index + 1)
+                .map(|name| BroadcastTarget::Group(name.clone()));
+
+            let stage = supervisor.children(move |children| {
+                children
+                    .with_redundancy(redundancy)
+                    .with_dispatcher(Dispatcher::with_type(DispatcherType::Named(name)))
+                    .with_exec(move |ctx: BastionContext| {
+                        let handler = handler.clone();
+                        let next = next.clone();
+                        async move {
+                            loop {
+                                let SignedMessage { msg, .. } = ctx.recv().await?;
+                                match msg.downcast::<Arc<SignedMessage>>() {
+                                    Ok(item) => handler(&ctx, &item, next.as_ref()),
+                                    Err(msg) => {
+                                        debug!("Pipeline: Ignoring unexpected message: {:?}", msg)
+                                    }
+                                }
+                            }
+                        }
+                    })
+            })?;
+            stages.push(stage);
+        }
+        stages.reverse();
+
+        Ok(PipelineRef {
+            input: names[0].clone(),
+            stages,
+            _input: PhantomData,
+        })
+    }
+}
+
+impl<I> PipelineRef<I>
+where
+    I: Message,
+{
+    /// Sends an item to the first stage of the pipeline this
+    /// `PipelineRef` is referencing.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The item to send to the pipeline.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// let pipeline = Bastion::pipeline(
+    ///     Pipeline::new(Stage::new(|item: &usize| item * 2))
+    ///         .then(Stage::new(|item: &usize| println!("{}", item))),
+    /// ).expect("Couldn't launch the pipeline.");
+    ///
+    /// pipeline.push(21);
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn push(&self, item: I) {
+        debug!("Pipeline: Pushing {:?} to {}.", item, self.input);
+        let msg = Arc::new(SignedMessage::new(
+            Msg::broadcast(item),
+            RefAddr::dead_letters(),
+        ));
+
+        SYSTEM
+            .dispatcher()
+            .broadcast_message(BroadcastTarget::Group(self.input.clone()), &msg);
+    }
+
+    /// Returns the children groups of the pipeline's stages, in the
+    /// order items go through them.
+    pub fn stages(&self) -> &[ChildrenRef] {
+        &self.stages
+    }
+}
+
+impl<I> Clone for PipelineRef<I> {
+    fn clone(&self) -> Self {
+        PipelineRef {
+            input: self.input.clone(),
+            stages: self.stages.clone(),
+            _input: PhantomData,
+        }
+    }
+}
+
+impl<I, O> Debug for Stage<I, O> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Stage")
+            .field("redundancy", &self.redundancy)
+            .finish()
+    }
+}
+
+impl<I, O> Debug for Pipeline<I, O> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        let redundancies = self
+            .stages
+            .iter()
+            .map(|(redundancy, _)| redundancy)
+            .collect::<Vec<_>>();
+
+        fmt.debug_struct("Pipeline")
+            .field("stages", &redundancies)
+            .finish()
+    }
+}
@@ -0,0 +1,76 @@
+//!
+//! Suspending the delivery of messages system-wide, returned by
+//! [`Bastion::quiesce`].
+//!
+//! [`Bastion::quiesce`]: crate::Bastion::quiesce
+use crate::envelope::Envelope;
+use crate::message::BastionMessage;
+use crate::system::SYSTEM;
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tracing::{debug, trace};
+
+pub(crate) static QUIESCE: Lazy<Quiesce> = Lazy::new(Quiesce::new);
+
+#[derive(Debug)]
+pub(crate) struct Quiesce {
+    // The amount of `QuiesceGuard`s that weren't dropped yet.
+    guards: AtomicUsize,
+}
+
+#[derive(Debug)]
+#[must_use = "the delivery of messages resumes as soon as the guard is dropped"]
+/// A guard suspending the delivery of messages to every element of
+/// the system until it is dropped, returned by [`Bastion::quiesce`].
+///
+/// The messages sent meanwhile are kept in the mailboxes of their
+/// receivers, which receive them in order once the last guard is
+/// dropped.
+///
+/// [`Bastion::quiesce`]: crate::Bastion::quiesce
+pub struct QuiesceGuard {
+    _priv: (),
+}
+
+impl Quiesce {
+    fn new() -> Self {
+        Quiesce {
+            guards: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns whether messages shouldn't be delivered, because a
+    /// [`QuiesceGuard`] is alive.
+    pub(crate) fn is_quiesced(&self) -> bool {
+        self.guards.load(Ordering::SeqCst) > 0
+    }
+
+    pub(crate) fn guard(&self) -> QuiesceGuard {
+        let guards = self.guards.fetch_add(1, Ordering::SeqCst) + 1;
+        debug!(
+            "Quiesce: Suspending the delivery of messages ({} guards).",
+            guards
+        );
+        QuiesceGuard { _priv: () }
+    }
+
+    fn release(&self) {
+        let guards = self.guards.fetch_sub(1, Ordering::SeqCst) - 1;
+        if guards > 0 {
+            trace!("Quiesce: Released a guard ({} left).", guards);
+            return;
+        }
+
+        debug!("Quiesce: Resuming the delivery of messages.");
+        // NOTE: the elements which tried to receive a message meanwhile
+        //      wait to be woken up to try again.
+        let envelope = Envelope::from_dead_letters(BastionMessage::thaw());
+        SYSTEM.sender().unbounded_send(envelope).ok();
+    }
+}
+
+impl Drop for QuiesceGuard {
+    fn drop(&mut self) {
+        QUIESCE.release();
+    }
+}
@@ -0,0 +1,170 @@
+//!
+//! A token bucket used to cap the rate at which messages are delivered,
+//! and the global rate limit capping the rate at which the elements of
+//! the whole system receive messages (see
+//! [`Bastion::set_global_rate_limit`]).
+//!
+//! [`Bastion::set_global_rate_limit`]: crate::Bastion::set_global_rate_limit
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+pub(crate) static GLOBAL_RATE_LIMIT: Lazy<GlobalRateLimit> = Lazy::new(GlobalRateLimit::default);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// What happens to the messages an element is about to receive while
+/// the global rate limit is exhausted (see
+/// [`Bastion::set_global_rate_limit_with`]), or the messages sent to a
+/// rate limited dispatcher while its backlog is full (see
+/// [`Dispatcher::with_rate_limit_with`]).
+///
+/// The default policy is `Backpressure`.
+///
+/// [`Bastion::set_global_rate_limit_with`]: crate::Bastion::set_global_rate_limit_with
+/// [`Dispatcher::with_rate_limit_with`]: crate::dispatcher::Dispatcher::with_rate_limit_with
+pub enum OverloadPolicy {
+    /// The elements wait for the rate limit to let the messages
+    /// through, so that their mailboxes fill up and the producers
+    /// waiting on them (see [`Children::with_mailbox_high_watermark`])
+    /// are slowed down.
+    ///
+    /// [`Children::with_mailbox_high_watermark`]: crate::children::Children::with_mailbox_high_watermark
+    Backpressure,
+    /// The messages are dropped and kept with the dead letters (see
+    /// [`Bastion::drain_dead_letters`]).
+    ///
+    /// [`Bastion::drain_dead_letters`]: crate::Bastion::drain_dead_letters
+    Shed,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct GlobalRateLimit {
+    limit: Mutex<Option<(TokenBucket, OverloadPolicy)>>,
+}
+
+#[derive(Debug)]
+/// Allows bursts of up to `capacity` messages, while capping the
+/// sustained rate to `per_second` messages per second.
+pub(crate) struct TokenBucket {
+    capacity: f64,
+    per_second: f64,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    /// Creates a full bucket.
+    pub(crate) fn new(per_second: u32, capacity: u32) -> Self {
+        assert!(per_second > 0, "The rate limit must be positive.");
+        let capacity = f64::from(capacity.max(1));
+
+        TokenBucket {
+            capacity,
+            per_second: f64::from(per_second),
+            tokens: capacity,
+            refilled_at: Instant::now(),
+        }
+    }
+
+    /// Takes a token from the bucket, or returns how long to wait
+    /// until one is available.
+    pub(crate) fn try_acquire(&mut self) -> Result<(), Duration> {
+        self.try_acquire_at(Instant::now())
+    }
+
+    /// Gives back a token which was taken but not used.
+    pub(crate) fn refund(&mut self) {
+        self.tokens = (self.tokens + 1.0).min(self.capacity);
+    }
+
+    fn try_acquire_at(&mut self, now: Instant) -> Result<(), Duration> {
+        let elapsed = now.saturating_duration_since(self.refilled_at);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.per_second).min(self.capacity);
+        self.refilled_at = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            let missing = 1.0 - self.tokens;
+            Err(Duration::from_secs_f64(missing / self.per_second))
+        }
+    }
+}
+
+impl Default for OverloadPolicy {
+    fn default() -> Self {
+        OverloadPolicy::Backpressure
+    }
+}
+
+impl GlobalRateLimit {
+    /// Caps the rate at which messages are received to `per_second`
+    /// messages per second, allowing bursts of up to `per_second`
+    /// messages.
+    pub(crate) fn set(&self, per_second: u32, overload: OverloadPolicy) {
+        let bucket = TokenBucket::new(per_second, per_second);
+        // FIXME: panics?
+        *self.limit.lock().unwrap() = Some((bucket, overload));
+    }
+
+    /// Removes the cap on the rate at which messages are received.
+    pub(crate) fn clear(&self) {
+        // FIXME: panics?
+        *self.limit.lock().unwrap() = None;
+    }
+
+    /// Takes a token, or returns the overload policy and how long to
+    /// wait until one is available.
+    pub(crate) fn try_acquire(&self) -> Result<(), (OverloadPolicy, Duration)> {
+        // FIXME: panics?
+        match &mut *self.limit.lock().unwrap() {
+            Some((bucket, overload)) => bucket.try_acquire().map_err(|wait| (*overload, wait)),
+            None => Ok(()),
+        }
+    }
+
+    /// Gives back a token which was taken but not used.
+    pub(crate) fn refund(&self) {
+        // FIXME: panics?
+        if let Some((bucket, _)) = &mut *self.limit.lock().unwrap() {
+            bucket.refund();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bursts_are_capped_to_the_capacity() {
+        let mut bucket = TokenBucket::new(10, 5);
+        let now = Instant::now();
+
+        for _ in 0..5 {
+            assert!(bucket.try_acquire_at(now).is_ok());
+        }
+        let wait = bucket.try_acquire_at(now).unwrap_err();
+        assert_eq!(wait, Duration::from_millis(100));
+    }
+
+    #[test]
+    fn tokens_are_refilled_at_the_rate() {
+        let mut bucket = TokenBucket::new(10, 1);
+        let now = Instant::now();
+
+        assert!(bucket.try_acquire_at(now).is_ok());
+        assert!(bucket
+            .try_acquire_at(now + Duration::from_millis(50))
+            .is_err());
+        assert!(bucket
+            .try_acquire_at(now + Duration::from_millis(150))
+            .is_ok());
+        // The bucket never holds more than its capacity.
+        assert!(bucket.try_acquire_at(now + Duration::from_secs(10)).is_ok());
+        assert!(bucket
+            .try_acquire_at(now + Duration::from_secs(10))
+            .is_err());
+    }
+}
@@ -12,8 +12,9 @@ use fxhash::FxHashMap;
 use lever::table::lotable::LOTable;
 use lightproc::recoverable_handle::RecoverableHandle;
 use std::cmp::min;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 #[cfg(feature = "scaling")]
 #[derive(Debug)]
@@ -100,6 +101,85 @@ pub(crate) enum ScalingRule {
     DoNothing,
 }
 
+#[cfg(feature = "scaling")]
+#[derive(Debug, Clone)]
+/// A resizer keeping the utilization of the mailboxes of a
+/// children group within a band, by launching elements while the
+/// group is overloaded and stopping them once it calmed down.
+///
+/// The utilization of the group is the average amount of messages
+/// waiting in the mailboxes of its elements. It is inspected every
+/// `interval`:
+/// * above the band, enough elements are launched (up to `max`)
+///   for the utilization to get back within it.
+/// * below the band for a whole `cooldown`, the least busy element
+///   is stopped (down to `min`), and the group waits for another
+///   `cooldown` before stopping the next one.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// # use std::time::Duration;
+/// #
+/// # #[cfg(feature = "tokio-runtime")]
+/// # #[tokio::main]
+/// # async fn main() {
+/// #    run();    
+/// # }
+/// #
+/// # #[cfg(not(feature = "tokio-runtime"))]
+/// # fn main() {
+/// #    run();    
+/// # }
+/// #
+/// # fn run() {
+///     # Bastion::init();
+///     #
+/// Bastion::children(|children| {
+///     children.with_resizer(
+///         UtilizationResizer::new(2, 16)
+///             .with_target(1.0, 8.0)
+///             .with_cooldown(Duration::from_secs(10)),
+///     )
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+pub struct UtilizationResizer {
+    // The minimal and maximal amounts of elements of the group.
+    min: usize,
+    max: usize,
+    // The band of average mailbox depths the group is kept in.
+    low: f64,
+    high: f64,
+    // How long the group has to stay below the band before an
+    // element gets stopped.
+    cooldown: Duration,
+    // How often the utilization of the group is inspected.
+    interval: Duration,
+    // Since when the group was below the band, if it is.
+    calm_since: Option<Instant>,
+}
+
+#[cfg(feature = "scaling")]
+#[derive(Debug)]
+/// The resizing policies a children group can be configured with
+/// using [`Children::with_resizer`].
+///
+/// [`Children::with_resizer`]: crate::children::Children::with_resizer
+pub enum Resizer {
+    /// Scales the group based on the statistics collected by its
+    /// elements, see [`OptimalSizeExploringResizer`].
+    OptimalSizeExploring(OptimalSizeExploringResizer),
+    /// Keeps the utilization of the mailboxes of the group within
+    /// a band, see [`UtilizationResizer`].
+    Utilization(UtilizationResizer),
+}
+
 #[cfg(feature = "scaling")]
 impl OptimalSizeExploringResizer {
     /// Returns an atomic reference to data with actor statistics.
@@ -294,6 +374,154 @@ impl Default for OptimalSizeExploringResizer {
     }
 }
 
+#[cfg(feature = "scaling")]
+impl UtilizationResizer {
+    /// Creates a new resizer keeping between `min` and `max`
+    /// elements in the group, with a target band of 1 to 10 waiting
+    /// messages per element, a cooldown of 30 seconds and an
+    /// interval of one second.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - The minimal amount of elements (at least one).
+    /// * `max` - The maximal amount of elements.
+    pub fn new(min: usize, max: usize) -> Self {
+        let min = min.max(1);
+        let max = max.max(min);
+
+        UtilizationResizer {
+            min,
+            max,
+            low: 1.0,
+            high: 10.0,
+            cooldown: Duration::from_secs(30),
+            interval: Duration::from_secs(1),
+            calm_since: None,
+        }
+    }
+
+    /// Sets the band of average mailbox depths the group is kept in.
+    ///
+    /// # Arguments
+    ///
+    /// * `low` - The utilization below which elements are stopped.
+    /// * `high` - The utilization above which elements are launched.
+    pub fn with_target(mut self, low: f64, high: f64) -> Self {
+        self.low = low;
+        self.high = high.max(low);
+        self
+    }
+
+    /// Sets how long the group has to stay below the band before
+    /// each element gets stopped.
+    ///
+    /// # Arguments
+    ///
+    /// * `cooldown` - How long to wait before each downscale.
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Sets how often the utilization of the group is inspected.
+    ///
+    /// Note that this overrides the heartbeat tick of the group.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - How often to inspect the group.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Returns the minimal amount of elements of the group.
+    pub fn min(&self) -> usize {
+        self.min
+    }
+
+    /// Returns the maximal amount of elements of the group.
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Returns how often the utilization of the group is inspected.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Decides how to resize the group given the mailbox depth of
+    /// each of its elements.
+    pub(crate) fn scale(
+        &mut self,
+        depths: &FxHashMap<BastionId, Arc<AtomicUsize>>,
+        now: Instant,
+    ) -> ScalingRule {
+        let size = depths.len();
+        if size < self.min {
+            self.calm_since = None;
+            return ScalingRule::Upscale((self.min - size) as u64);
+        }
+
+        let mut depths = depths
+            .iter()
+            .map(|(id, depth)| (id.clone(), depth.load(Ordering::SeqCst)))
+            .collect::<Vec<_>>();
+        // The least busy elements are stopped first.
+        depths.sort_by_key(|(_, depth)| *depth);
+
+        if size > self.max {
+            self.calm_since = None;
+            let ids = depths.into_iter().take(size - self.max);
+            return ScalingRule::Downscale(ids.map(|(id, _)| id).collect());
+        }
+
+        let total = depths.iter().map(|(_, depth)| *depth).sum::<usize>();
+        let utilization = total as f64 / size as f64;
+        if utilization > self.high {
+            self.calm_since = None;
+            let wanted = (total as f64 / self.high).ceil() as usize;
+            let wanted = wanted.max(size + 1).min(self.max);
+            return match wanted - size {
+                0 => ScalingRule::DoNothing,
+                count => ScalingRule::Upscale(count as u64),
+            };
+        }
+
+        if utilization >= self.low || size == self.min {
+            self.calm_since = None;
+            return ScalingRule::DoNothing;
+        }
+
+        match self.calm_since {
+            Some(since) if now.duration_since(since) >= self.cooldown => {
+                // The next element is only stopped after another cooldown.
+                self.calm_since = Some(now);
+                ScalingRule::Downscale(vec![depths.remove(0).0])
+            }
+            Some(_) => ScalingRule::DoNothing,
+            None => {
+                self.calm_since = Some(now);
+                ScalingRule::DoNothing
+            }
+        }
+    }
+}
+
+#[cfg(feature = "scaling")]
+impl From<OptimalSizeExploringResizer> for Resizer {
+    fn from(resizer: OptimalSizeExploringResizer) -> Self {
+        Resizer::OptimalSizeExploring(resizer)
+    }
+}
+
+#[cfg(feature = "scaling")]
+impl From<UtilizationResizer> for Resizer {
+    fn from(resizer: UtilizationResizer) -> Self {
+        Resizer::Utilization(resizer)
+    }
+}
+
 #[cfg(feature = "scaling")]
 impl ActorGroupStats {
     fn actors_count_mask() -> u64 {
@@ -353,7 +581,14 @@ impl ActorGroupStats {
 
 #[cfg(test)]
 mod tests {
-    use crate::resizer::{ActorGroupStats, OptimalSizeExploringResizer};
+    use crate::context::BastionId;
+    use crate::resizer::{
+        ActorGroupStats, OptimalSizeExploringResizer, ScalingRule, UtilizationResizer,
+    };
+    use fxhash::FxHashMap;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
 
     #[test]
     fn test_resizer_stores_empty_stats_by_default() {
@@ -406,4 +641,59 @@ mod tests {
         assert_eq!(updated_stats.actors_count, 10);
         assert_eq!(updated_stats.average_mailbox_size, 50);
     }
+
+    fn depths(depths: &[usize]) -> FxHashMap<BastionId, Arc<AtomicUsize>> {
+        depths
+            .iter()
+            .map(|depth| (BastionId::new(), Arc::new(AtomicUsize::new(*depth))))
+            .collect()
+    }
+
+    #[test]
+    fn test_utilization_resizer_upscales_above_the_band() {
+        let mut resizer = UtilizationResizer::new(1, 4).with_target(1.0, 5.0);
+        let now = Instant::now();
+
+        match resizer.scale(&depths(&[12]), now) {
+            ScalingRule::Upscale(count) => assert_eq!(count, 2),
+            rule => panic!("unexpected rule: {:?}", rule),
+        }
+        match resizer.scale(&depths(&[30, 30]), now) {
+            ScalingRule::Upscale(count) => assert_eq!(count, 2),
+            rule => panic!("unexpected rule: {:?}", rule),
+        }
+        match resizer.scale(&depths(&[30, 30, 30, 30]), now) {
+            ScalingRule::DoNothing => (),
+            rule => panic!("unexpected rule: {:?}", rule),
+        }
+    }
+
+    #[test]
+    fn test_utilization_resizer_downscales_after_the_cooldown() {
+        let cooldown = Duration::from_secs(10);
+        let mut resizer = UtilizationResizer::new(1, 4).with_cooldown(cooldown);
+        let group = depths(&[0, 1]);
+        let now = Instant::now();
+
+        match resizer.scale(&group, now) {
+            ScalingRule::DoNothing => (),
+            rule => panic!("unexpected rule: {:?}", rule),
+        }
+        match resizer.scale(&group, now + cooldown / 2) {
+            ScalingRule::DoNothing => (),
+            rule => panic!("unexpected rule: {:?}", rule),
+        }
+        match resizer.scale(&group, now + cooldown) {
+            ScalingRule::Downscale(ids) => {
+                assert_eq!(ids.len(), 1);
+                assert_eq!(group[&ids[0]].load(std::sync::atomic::Ordering::SeqCst), 0);
+            }
+            rule => panic!("unexpected rule: {:?}", rule),
+        }
+        // The group never goes below `min`.
+        match resizer.scale(&depths(&[0]), now + cooldown * 3) {
+            ScalingRule::DoNothing => (),
+            rule => panic!("unexpected rule: {:?}", rule),
+        }
+    }
 }
@@ -0,0 +1,70 @@
+//!
+//! Retries of the processing of a message by a child, before its
+//! failure is escalated.
+//!
+//! See [`BastionContext::retry`].
+//!
+//! [`BastionContext::retry`]: crate::context::BastionContext::retry
+use crate::supervisor::ActorRestartStrategy;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+/// How many times [`BastionContext::retry`] attempts to process a
+/// message, and how long it waits between two attempts.
+///
+/// The default policy makes 3 attempts, one right after the other.
+///
+/// [`BastionContext::retry`]: crate::context::BastionContext::retry
+pub struct RetryPolicy {
+    max_attempts: usize,
+    backoff: ActorRestartStrategy,
+}
+
+impl RetryPolicy {
+    /// Creates a policy making up to `max_attempts` attempts (at
+    /// least one), one right after the other.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_attempts` - The amount of attempts, including the
+    ///     first one.
+    pub fn new(max_attempts: usize) -> Self {
+        RetryPolicy {
+            max_attempts: max_attempts.max(1),
+            backoff: ActorRestartStrategy::Immediate,
+        }
+    }
+
+    /// Sets how long to wait before every retry, computed like the
+    /// restart delays of an actor from the amount of retries which
+    /// were already made.
+    ///
+    /// # Arguments
+    ///
+    /// * `backoff` - The strategy computing the delays.
+    pub fn with_backoff(mut self, backoff: ActorRestartStrategy) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Returns the amount of attempts, including the first one.
+    pub fn max_attempts(&self) -> usize {
+        self.max_attempts
+    }
+
+    /// Returns the strategy computing the delays between attempts.
+    pub fn backoff(&self) -> &ActorRestartStrategy {
+        &self.backoff
+    }
+
+    /// The delay before the next attempt, after `retries` retries.
+    pub(crate) fn delay(&self, retries: usize) -> Option<Duration> {
+        self.backoff.calculate(retries)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new(3)
+    }
+}
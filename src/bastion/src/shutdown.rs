@@ -0,0 +1,223 @@
+//!
+//! The outcome of stopping the system, returned by
+//! [`Bastion::stop_with_timeout`].
+//!
+//! [`Bastion::stop_with_timeout`]: crate::Bastion::stop_with_timeout
+use crate::context::{BastionId, NIL_ID};
+use crate::path::BastionPath;
+use fxhash::FxHashMap;
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Instant;
+use tracing::{debug, warn};
+
+pub(crate) static SHUTDOWN: Lazy<ShutdownTracker> = Lazy::new(ShutdownTracker::new);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How a component of the system ended up once it was told to stop.
+pub enum ShutdownStatus {
+    /// The component stopped before the timeout elapsed.
+    Stopped,
+    /// The component was still running once the timeout elapsed.
+    TimedOut,
+    /// The component faulted or panicked while stopping.
+    Errored,
+}
+
+#[derive(Debug, Clone, Default)]
+/// The status of every element of the system once it was told to
+/// stop, keyed by the element's path.
+pub struct ShutdownReport {
+    entries: Vec<(BastionPath, ShutdownStatus)>,
+}
+
+#[derive(Debug)]
+pub(crate) struct ShutdownTracker {
+    state: Mutex<TrackerState>,
+    exited: Condvar,
+    next_token: AtomicU64,
+}
+
+#[derive(Debug, Default)]
+struct TrackerState {
+    // The currently running elements, with the token of the guard
+    // that registered them.
+    live: FxHashMap<BastionId, (u64, Arc<BastionPath>)>,
+    // The elements which were running when the shutdown started,
+    // and whether they exited since then.
+    stopping: Option<FxHashMap<BastionId, (Arc<BastionPath>, Option<ShutdownStatus>)>>,
+}
+
+/// Unregisters an element from the [`ShutdownTracker`] once it is
+/// dropped, whether the element returned or was cancelled.
+#[derive(Debug)]
+pub(crate) struct ShutdownGuard {
+    id: BastionId,
+    token: u64,
+    errored: bool,
+}
+
+impl ShutdownReport {
+    /// Returns the path and status of every element of the system.
+    pub fn entries(&self) -> &[(BastionPath, ShutdownStatus)] {
+        &self.entries
+    }
+
+    /// Returns the status of the element with the given path, if it
+    /// was running when the system was told to stop.
+    pub fn status(&self, path: &BastionPath) -> Option<ShutdownStatus> {
+        self.entries
+            .iter()
+            .find(|(entry, _)| entry == path)
+            .map(|(_, status)| *status)
+    }
+
+    /// Returns whether every element stopped before the timeout
+    /// elapsed.
+    pub fn is_clean(&self) -> bool {
+        self.entries
+            .iter()
+            .all(|(_, status)| *status == ShutdownStatus::Stopped)
+    }
+
+    /// Returns the paths of the elements which were still running
+    /// once the timeout elapsed.
+    pub fn timed_out(&self) -> Vec<&BastionPath> {
+        self.with_status(ShutdownStatus::TimedOut)
+    }
+
+    /// Returns the paths of the elements which faulted or panicked
+    /// while stopping.
+    pub fn errored(&self) -> Vec<&BastionPath> {
+        self.with_status(ShutdownStatus::Errored)
+    }
+
+    fn with_status(&self, status: ShutdownStatus) -> Vec<&BastionPath> {
+        self.entries
+            .iter()
+            .filter(|(_, entry)| *entry == status)
+            .map(|(path, _)| path)
+            .collect()
+    }
+}
+
+impl ShutdownTracker {
+    fn new() -> Self {
+        ShutdownTracker {
+            state: Mutex::new(TrackerState::default()),
+            exited: Condvar::new(),
+            next_token: AtomicU64::new(0),
+        }
+    }
+
+    /// Registers a running element, returning the guard which will
+    /// unregister it once dropped.
+    pub(crate) fn register(&self, id: BastionId, path: Arc<BastionPath>) -> ShutdownGuard {
+        let token = self.next_token.fetch_add(1, Ordering::Relaxed);
+        // FIXME: panics?
+        let mut state = self.state.lock().unwrap();
+        state.live.insert(id.clone(), (token, path));
+
+        ShutdownGuard {
+            id,
+            token,
+            errored: false,
+        }
+    }
+
+    /// Starts keeping track of the elements which are currently
+    /// running (except the dead letters' one, which is stopped along
+    /// with the system), until [`ShutdownTracker::report`] is called.
+    pub(crate) fn begin(&self) {
+        // FIXME: panics?
+        let mut state = self.state.lock().unwrap();
+        let stopping = state
+            .live
+            .iter()
+            .filter(|(_, (_, path))| path.group_path().id() != &NIL_ID)
+            .map(|(id, (_, path))| (id.clone(), (path.clone(), None)))
+            .collect::<FxHashMap<_, _>>();
+        debug!("Shutdown: Waiting for {} elements to stop.", stopping.len());
+        state.stopping = Some(stopping);
+    }
+
+    /// Waits for every element which was running when
+    /// [`ShutdownTracker::begin`] was called to exit, or for the
+    /// deadline to be reached, and returns their status.
+    pub(crate) fn report(&self, deadline: Instant) -> ShutdownReport {
+        // FIXME: panics?
+        let mut state = self.state.lock().unwrap();
+        loop {
+            let pending = state.stopping.as_ref().map_or(0, |stopping| {
+                stopping
+                    .values()
+                    .filter(|(_, status)| status.is_none())
+                    .count()
+            });
+
+            let now = Instant::now();
+            if pending == 0 || now >= deadline {
+                break;
+            }
+
+            // FIXME: panics?
+            state = self.exited.wait_timeout(state, deadline - now).unwrap().0;
+        }
+
+        let mut entries = state
+            .stopping
+            .take()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(_, (path, status))| {
+                let status = status.unwrap_or_else(|| {
+                    warn!("Shutdown: {:?} didn't stop in time.", path);
+                    ShutdownStatus::TimedOut
+                });
+                ((*path).clone(), status)
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|(path, _)| path.to_string());
+
+        ShutdownReport { entries }
+    }
+
+    fn exited(&self, guard: &ShutdownGuard, status: ShutdownStatus) {
+        // FIXME: panics?
+        let mut state = self.state.lock().unwrap();
+        // NOTE: the element might have been registered again by a
+        //      restart before the previous guard was dropped.
+        if matches!(state.live.get(&guard.id), Some((token, _)) if *token == guard.token) {
+            state.live.remove(&guard.id);
+        }
+
+        if let Some(stopping) = &mut state.stopping {
+            if let Some((_, exited @ None)) = stopping.get_mut(&guard.id) {
+                *exited = Some(status);
+                self.exited.notify_all();
+            }
+        }
+    }
+}
+
+impl ShutdownGuard {
+    /// Marks the element as faulted, so that it is reported as
+    /// errored if it happens while the system is stopping.
+    pub(crate) fn errored(&mut self) {
+        self.errored = true;
+    }
+}
+
+impl Drop for ShutdownGuard {
+    fn drop(&mut self) {
+        let status = if self.errored || thread::panicking() {
+            ShutdownStatus::Errored
+        } else {
+            ShutdownStatus::Stopped
+        };
+
+        SHUTDOWN.exited(self, status);
+    }
+}
@@ -0,0 +1,129 @@
+//!
+//! The asynchronous sources (timers and streams) an element of a
+//! children group can wait on alongside its mailbox, receiving the
+//! events of all of them through [`BastionContext::next_event`].
+//!
+//! [`BastionContext::next_event`]: crate::context::BastionContext::next_event
+use crate::envelope::SignedMessage;
+use futures::prelude::*;
+use futures_timer::Delay;
+use std::any::Any;
+use std::fmt::{self, Debug, Formatter};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+#[derive(Debug)]
+/// An event returned by [`BastionContext::next_event`], coming
+/// either from the mailbox of the element or from one of the sources
+/// registered with [`BastionContext::register_timer`] and
+/// [`BastionContext::register_stream`].
+///
+/// [`BastionContext::next_event`]: crate::context::BastionContext::next_event
+/// [`BastionContext::register_timer`]: crate::context::BastionContext::register_timer
+/// [`BastionContext::register_stream`]: crate::context::BastionContext::register_stream
+pub enum ContextEvent {
+    /// A message received in the mailbox of the element, which can
+    /// be matched with [`msg!`].
+    ///
+    /// [`msg!`]: crate::msg!
+    Message(SignedMessage),
+    /// A tick of the timer registered with the given name.
+    Tick(String),
+    /// An item of the stream registered with the given name, which
+    /// can be downcast to the type of the items of the stream.
+    Item(String, Box<dyn Any + Send>),
+    /// The stream registered with the given name ended, and was
+    /// unregistered.
+    Ended(String),
+}
+
+type BoxedStream = Pin<Box<dyn Stream<Item = Box<dyn Any + Send>> + Send>>;
+
+#[derive(Default)]
+/// The timers and streams registered by an element, which are kept
+/// between two calls of [`BastionContext::next_event`] so that no
+/// tick or item is lost when the future it returned is dropped.
+///
+/// [`BastionContext::next_event`]: crate::context::BastionContext::next_event
+pub(crate) struct EventSources {
+    timers: Vec<Timer>,
+    streams: Vec<(String, BoxedStream)>,
+}
+
+struct Timer {
+    name: String,
+    interval: Duration,
+    delay: Delay,
+}
+
+impl EventSources {
+    pub(crate) fn register_timer(&mut self, name: String, interval: Duration) {
+        let delay = Delay::new(interval);
+        self.timers.push(Timer {
+            name,
+            interval,
+            delay,
+        });
+    }
+
+    pub(crate) fn register_stream<S>(&mut self, name: String, stream: S)
+    where
+        S: Stream + Send + 'static,
+        S::Item: Send + 'static,
+    {
+        let stream = stream.map(|item| Box::new(item) as Box<dyn Any + Send>);
+        self.streams.push((name, Box::pin(stream)));
+    }
+
+    /// Polls the registered sources in the order they were
+    /// registered in, timers first, returning the first event one of
+    /// them is ready with.
+    pub(crate) fn poll_next_event(&mut self, cx: &mut Context) -> Poll<ContextEvent> {
+        for timer in self.timers.iter_mut() {
+            if Pin::new(&mut timer.delay).poll(cx).is_ready() {
+                timer.delay.reset(timer.interval);
+                return Poll::Ready(ContextEvent::Tick(timer.name.clone()));
+            }
+        }
+
+        for index in 0..self.streams.len() {
+            let (name, stream) = &mut self.streams[index];
+            match stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    return Poll::Ready(ContextEvent::Item(name.clone(), item));
+                }
+                Poll::Ready(None) => {
+                    let (name, _) = self.streams.remove(index);
+                    return Poll::Ready(ContextEvent::Ended(name));
+                }
+                Poll::Pending => (),
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Debug for EventSources {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("EventSources")
+            .field(
+                "timers",
+                &self
+                    .timers
+                    .iter()
+                    .map(|timer| (&timer.name, timer.interval))
+                    .collect::<Vec<_>>(),
+            )
+            .field(
+                "streams",
+                &self
+                    .streams
+                    .iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
@@ -0,0 +1,165 @@
+//!
+//! A declarative description of a supervision tree, which can be
+//! deserialized from a document (e.g. in JSON or TOML) and built with
+//! [`Bastion::from_spec`], or taken from a running tree with
+//! [`TopologySnapshot::to_spec`].
+//!
+//! The exec closures of the children groups are referenced by the
+//! names they were registered with using [`Bastion::register_exec`].
+//!
+//! [`Bastion::from_spec`]: crate::Bastion::from_spec
+//! [`Bastion::register_exec`]: crate::Bastion::register_exec
+//! [`TopologySnapshot::to_spec`]: crate::topology::TopologySnapshot::to_spec
+use crate::children::Children;
+use crate::context::BastionContext;
+use crate::dispatcher::{Dispatcher, DispatcherType};
+use crate::errors::SpecError;
+use crate::supervisor::{SupervisionStrategy, Supervisor};
+use fxhash::FxHashMap;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+
+pub(crate) static EXECS: Lazy<Execs> = Lazy::new(Execs::default);
+
+type BoxedExec = Pin<Box<dyn Future<Output = Result<(), ()>> + Send>>;
+
+pub(crate) type RegisteredExec = Arc<dyn Fn(BastionContext) -> BoxedExec + Send + Sync>;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// A supervision tree, starting from its top-level supervisors.
+pub struct TreeSpec {
+    /// The top-level supervisors, in the order they are launched.
+    #[serde(default)]
+    pub supervisors: Vec<SupervisorSpec>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// A supervisor of a supervision tree.
+pub struct SupervisorSpec {
+    /// The strategy the supervisor uses when one of its supervised
+    /// entities dies.
+    #[serde(default)]
+    pub strategy: SupervisionStrategy,
+    /// The supervisors supervised by this supervisor, in the order
+    /// they are launched.
+    #[serde(default)]
+    pub supervisors: Vec<SupervisorSpec>,
+    /// The children groups supervised by this supervisor, in the
+    /// order they are launched.
+    #[serde(default)]
+    pub children: Vec<ChildrenSpec>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A children group of a supervision tree.
+pub struct ChildrenSpec {
+    /// The name of the children group, if any.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// The name the exec closure of the group's elements was
+    /// registered with (see [`Bastion::register_exec`]).
+    ///
+    /// [`Bastion::register_exec`]: crate::Bastion::register_exec
+    pub exec: String,
+    /// The amount of elements of the group.
+    #[serde(default = "default_redundancy")]
+    pub redundancy: usize,
+    /// The names of the dispatchers attached to the group.
+    #[serde(default)]
+    pub dispatchers: Vec<String>,
+}
+
+#[derive(Default)]
+pub(crate) struct Execs {
+    execs: RwLock<FxHashMap<String, RegisteredExec>>,
+}
+
+fn default_redundancy() -> usize {
+    1
+}
+
+impl TreeSpec {
+    /// Returns an error if an exec closure referenced by this spec
+    /// wasn't registered.
+    pub(crate) fn validate(&self) -> Result<(), SpecError> {
+        self.supervisors
+            .iter()
+            .try_for_each(SupervisorSpec::validate)
+    }
+}
+
+impl SupervisorSpec {
+    fn validate(&self) -> Result<(), SpecError> {
+        self.supervisors
+            .iter()
+            .try_for_each(SupervisorSpec::validate)?;
+        self.children.iter().try_for_each(|children| {
+            if EXECS.contains(&children.exec) {
+                Ok(())
+            } else {
+                Err(SpecError::UnknownExec(children.exec.clone()))
+            }
+        })
+    }
+
+    /// Configures the given supervisor as described by this spec,
+    /// which must have been validated.
+    pub(crate) fn build(&self, supervisor: Supervisor) -> Supervisor {
+        let mut supervisor = supervisor.with_strategy(self.strategy.clone());
+        for children in self.children.iter() {
+            supervisor = supervisor.children(|group| children.build(group));
+        }
+        for spec in self.supervisors.iter() {
+            supervisor = supervisor.supervisor(|sp| spec.build(sp));
+        }
+
+        supervisor
+    }
+}
+
+impl ChildrenSpec {
+    fn build(&self, children: Children) -> Children {
+        // NOTE: the exec closures were checked to be registered by
+        //      `TreeSpec::validate`, and can't be unregistered.
+        let mut children = children
+            .with_registered_exec(&self.exec)
+            .expect("the exec closure isn't registered")
+            .with_redundancy(self.redundancy);
+        if let Some(name) = &self.name {
+            children = children.with_name(name.clone());
+        }
+        for name in self.dispatchers.iter() {
+            let dispatcher_type = DispatcherType::from_name(name);
+            children = children.with_dispatcher(Dispatcher::with_type(dispatcher_type));
+        }
+
+        children
+    }
+}
+
+impl Execs {
+    /// Registers the given exec closure with the given name, replacing
+    /// the one already registered with it, if any.
+    pub(crate) fn register<I, F>(&self, name: String, init: I)
+    where
+        I: Fn(BastionContext) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        let exec: RegisteredExec = Arc::new(move |ctx| -> BoxedExec { Box::pin(init(ctx)) });
+        // FIXME: panics?
+        self.execs.write().unwrap().insert(name, exec);
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<RegisteredExec> {
+        // FIXME: panics?
+        self.execs.read().unwrap().get(name).cloned()
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        // FIXME: panics?
+        self.execs.read().unwrap().contains_key(name)
+    }
+}
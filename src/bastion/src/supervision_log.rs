@@ -0,0 +1,71 @@
+//!
+//! Structured logs of the supervision events (faults, restarts, stops
+//! and dead letters), emitted as JSON objects through `tracing` with
+//! the `bastion::supervision` target, so that log pipelines can ingest
+//! them without parsing the other logs.
+//!
+//! Every entry has the following fields:
+//! - `event`: the kind of the event (e.g. `child_restarted`),
+//! - `path`: the path of the element the event is about, or of the
+//!     sender of the message for the dead letters,
+//! - `reason`: why the element faulted or was restarted (`panicked`,
+//!     `returned`, `escalated`, `launch_timed_out` or `unresponsive`),
+//!     if known,
+//! - `message`: the panic message, if the element panicked,
+//! - `timestamp`: when the event happened, in milliseconds since the
+//!     Unix epoch.
+use crate::path::BastionPath;
+use crate::supervisor::FaultReason;
+use serde_json::json;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::info;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SupervisionEvent {
+    ChildFaulted,
+    ChildRestarted,
+    ChildStopped,
+    SupervisorFaulted,
+    SupervisorRestarted,
+    DeadLetter,
+}
+
+impl SupervisionEvent {
+    fn name(self) -> &'static str {
+        match self {
+            SupervisionEvent::ChildFaulted => "child_faulted",
+            SupervisionEvent::ChildRestarted => "child_restarted",
+            SupervisionEvent::ChildStopped => "child_stopped",
+            SupervisionEvent::SupervisorFaulted => "supervisor_faulted",
+            SupervisionEvent::SupervisorRestarted => "supervisor_restarted",
+            SupervisionEvent::DeadLetter => "dead_letter",
+        }
+    }
+}
+
+/// Logs the given event about the element at the given path, which
+/// faulted or was restarted for the given reason, if known.
+pub(crate) fn log(event: SupervisionEvent, path: &BastionPath, reason: Option<&FaultReason>) {
+    let (reason, message) = match reason {
+        Some(FaultReason::Panicked(message)) => (Some("panicked"), Some(message.as_str())),
+        Some(FaultReason::Returned) => (Some("returned"), None),
+        Some(FaultReason::Escalated) => (Some("escalated"), None),
+        Some(FaultReason::LaunchTimedOut) => (Some("launch_timed_out"), None),
+        Some(FaultReason::Unresponsive) => (Some("unresponsive"), None),
+        Some(FaultReason::Killed) => (Some("killed"), None),
+        None => (None, None),
+    };
+    // NOTE: a clock set before the epoch isn't worth failing over.
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64);
+
+    let entry = json!({
+        "event": event.name(),
+        "path": path.to_string(),
+        "reason": reason,
+        "message": message,
+        "timestamp": timestamp,
+    });
+    info!(target: "bastion::supervision", "{}", entry);
+}
@@ -5,18 +5,26 @@ use crate::broadcast::{Broadcast, Parent, Sender};
 use crate::callbacks::Callbacks;
 use crate::children::Children;
 use crate::children_ref::ChildrenRef;
-use crate::context::{BastionId, ContextState};
+use crate::context::{BastionContext, BastionId, ContextState};
+use crate::dead_letters::DEAD_LETTER_HANDLERS;
+use crate::dependencies::DEPENDENCIES;
+use crate::dispatcher::Dispatcher;
 use crate::envelope::Envelope;
+use crate::executor::{spawner, Spawner};
+use crate::health::HEALTH;
 use crate::message::{BastionMessage, Deployment, Message};
 use crate::path::{BastionPath, BastionPathElement};
+use crate::pipeline::{Pipeline, PipelineRef};
+use crate::system::SYSTEM;
+use crate::topology::TOPOLOGY;
 
-use bastion_executor::pool;
 use futures::prelude::*;
 use futures::stream::FuturesOrdered;
 use futures::{pending, poll};
 use futures_timer::Delay;
 use fxhash::FxHashMap;
 use lightproc::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::cmp::{Eq, PartialEq};
 use std::ops::Range;
 use std::pin::Pin;
@@ -24,6 +32,7 @@ use std::sync::Arc;
 use std::task::Poll;
 use std::time::Duration;
 use tracing::{debug, trace, warn};
+use uuid::Uuid;
 
 #[derive(Debug)]
 /// A supervisor that can supervise both [`Children`] and other
@@ -95,6 +104,10 @@ pub struct Supervisor {
     // This is used when resetting only.
     killed: FxHashMap<BastionId, Supervised>,
     strategy: SupervisionStrategy,
+    // The strategies of the supervised children groups which were
+    // given their own (see `Children::with_strategy`), used instead
+    // of `strategy` when their elements fault.
+    group_strategies: FxHashMap<BastionId, SupervisionStrategy>,
     restart_strategy: RestartStrategy,
     // The callbacks called at the supervisor's different
     // lifecycle events.
@@ -112,6 +125,12 @@ pub struct Supervisor {
     subtree_restarts: usize,
     // Store the maximum acceptable restarts for the supervisor.
     subtree_restarts_limit: usize,
+    // The dispatchers joined by all the elements of all the
+    // supervised children groups.
+    dispatchers: Vec<Arc<Box<Dispatcher>>>,
+    // The children group the dead letters of the subtree are routed
+    // to, if any.
+    dead_letter_handler: Option<ChildrenRef>,
 }
 
 #[derive(Debug, Clone)]
@@ -119,6 +138,19 @@ struct TrackedChildState {
     id: BastionId,
     state: Arc<Pin<Box<ContextState>>>,
     restarts_counts: usize,
+    // The delay the element was last restarted after, if any.
+    last_delay: Option<Duration>,
+}
+
+#[derive(Debug)]
+/// A children group handed over by its supervisor to another one,
+/// along with what its supervisor tracked about it.
+pub(crate) struct Adoption {
+    id: BastionId,
+    sender: Sender,
+    launched: RecoverableHandle<Supervised>,
+    tracked: Vec<TrackedChildState>,
+    strategy: Option<SupervisionStrategy>,
 }
 
 #[derive(Debug)]
@@ -131,6 +163,8 @@ enum RestartedElement {
 enum ActorSearchMethod {
     OneActor { id: BastionId, parent_id: BastionId },
     FromActor { id: BastionId, parent_id: BastionId },
+    FromActorInGroup { id: BastionId, parent_id: BastionId },
+    Group { parent_id: BastionId },
     All,
 }
 
@@ -143,9 +177,46 @@ pub struct SupervisorRef {
     id: BastionId,
     sender: Sender,
     path: Arc<BastionPath>,
+    dispatchers: Vec<Arc<Box<Dispatcher>>>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The reason why an element of a children group faulted, which is
+/// given to the callback set with [`Callbacks::with_on_fault`] of
+/// its supervisor and decides whether it is restarted.
+///
+/// [`Callbacks::with_on_fault`]: crate::callbacks::Callbacks::with_on_fault
+pub enum FaultReason {
+    /// The element's future panicked with the given message.
+    Panicked(String),
+    /// The element's future returned an error.
+    Returned,
+    /// The element's future returned an error after escalating its
+    /// fault with [`BastionContext::escalate`], in which case it
+    /// isn't restarted but its supervisor faults instead.
+    ///
+    /// [`BastionContext::escalate`]: crate::context::BastionContext::escalate
+    Escalated,
+    /// The element didn't reach its running state within the launch
+    /// timeout of its group (see [`Children::with_launch_timeout`]).
+    ///
+    /// [`Children::with_launch_timeout`]: crate::children::Children::with_launch_timeout
+    LaunchTimedOut,
+    /// The element didn't prove it isn't hung for longer than its
+    /// group tolerates (see [`Children::with_heartbeat`]).
+    ///
+    /// [`Children::with_heartbeat`]: crate::children::Children::with_heartbeat
+    Unresponsive,
+    /// The element was killed, either explicitly (see [`ChildRef::kill`])
+    /// or because an element it is linked to terminated abnormally (see
+    /// [`BastionContext::link`]).
+    ///
+    /// [`ChildRef::kill`]: crate::child_ref::ChildRef::kill
+    /// [`BastionContext::link`]: crate::context::BastionContext::link
+    Killed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 /// The strategy a supervisor should use when one of its
 /// supervised children groups or supervisors dies (in
 /// the case of a children group, it could be because one
@@ -156,12 +227,23 @@ pub enum SupervisionStrategy {
     /// When a children group dies (either because it got
     /// killed, it panicked or returned an error), only
     /// this group is restarted.
+    ///
+    /// When an element of a children group faults, only this
+    /// element is restarted, while the other elements of the
+    /// group and the other supervised groups keep running with
+    /// their current state. This is the strategy to use for
+    /// independent children.
     OneForOne,
     /// When a children group dies (either because it got
     /// killed, it panicked or returned an error), all the
     /// children groups are restarted (even those which were
     /// stopped) in the same order they were added to the
     /// supervisor.
+    ///
+    /// Every element of those groups is stopped and restarted,
+    /// even the ones that didn't fault, so that they all start
+    /// again from a fresh state. The messages sent to them meanwhile
+    /// are only received once all of them were restarted.
     OneForAll,
     /// When a children group dies (either because it got
     /// killed, it panicked or returned an error), this
@@ -204,6 +286,27 @@ pub enum RestartPolicy {
 pub struct RestartStrategy {
     restart_policy: RestartPolicy,
     strategy: ActorRestartStrategy,
+    jitter: BackoffJitter,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The randomization applied to the restart delays computed by an
+/// [`ActorRestartStrategy`], so that the actors which failed at the
+/// same time aren't all restarted at the same time.
+///
+/// The default is [`None`].
+///
+/// [`None`]: BackoffJitter::None
+pub enum BackoffJitter {
+    /// Restart the actor after exactly the computed delay.
+    None,
+    /// Restart the actor after a delay drawn uniformly between zero
+    /// and the computed delay.
+    Full,
+    /// Restart the actor after a delay drawn uniformly between the
+    /// strategy's initial timeout and three times the previous delay
+    /// of the actor, without exceeding the computed delay.
+    Decorrelated,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -255,6 +358,51 @@ impl ActorRestartStrategy {
             _ => None,
         }
     }
+
+    // The delay before the first restart.
+    fn initial_timeout(&self) -> Option<Duration> {
+        match *self {
+            ActorRestartStrategy::LinearBackOff { timeout } => Some(timeout),
+            ActorRestartStrategy::ExponentialBackOff { timeout, .. } => Some(timeout),
+            _ => None,
+        }
+    }
+}
+
+impl BackoffJitter {
+    /// Applies the jitter to the delay computed by the given strategy.
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - The strategy the delay was computed with.
+    /// * `delay` - The delay computed by the strategy.
+    /// * `previous` - The delay the actor was previously restarted
+    ///     after, if any.
+    pub fn apply(
+        &self,
+        strategy: &ActorRestartStrategy,
+        delay: Duration,
+        previous: Option<Duration>,
+    ) -> Duration {
+        match self {
+            BackoffJitter::None => delay,
+            BackoffJitter::Full => delay.mul_f64(random_unit()),
+            BackoffJitter::Decorrelated => {
+                let base = strategy.initial_timeout().unwrap_or(delay).min(delay);
+                let upper = previous.unwrap_or(base) * 3;
+                let spread = upper.checked_sub(base).unwrap_or_default();
+                (base + spread.mul_f64(random_unit())).min(delay)
+            }
+        }
+    }
+}
+
+// Returns a number drawn uniformly in `[0, 1)`.
+fn random_unit() -> f64 {
+    // NOTE: the identifiers are random, which is enough to spread
+    //      restarts without depending on a random number generator.
+    let bits = Uuid::new_v4().as_u128() as u64;
+    (bits >> 11) as f64 / (1u64 << 53) as f64
 }
 
 impl Supervisor {
@@ -267,6 +415,7 @@ impl Supervisor {
         let stopped = FxHashMap::default();
         let killed = FxHashMap::default();
         let strategy = SupervisionStrategy::default();
+        let group_strategies = FxHashMap::default();
         let restart_strategy = RestartStrategy::default();
         let callbacks = Callbacks::new();
         let is_system_supervisor = false;
@@ -274,6 +423,8 @@ impl Supervisor {
         let started = false;
         let subtree_restarts = 0;
         let subtree_restarts_limit = 3;
+        let dispatchers = Vec::new();
+        let dead_letter_handler = None;
 
         Supervisor {
             bcast,
@@ -284,6 +435,7 @@ impl Supervisor {
             stopped,
             killed,
             strategy,
+            group_strategies,
             restart_strategy,
             callbacks,
             is_system_supervisor,
@@ -291,6 +443,8 @@ impl Supervisor {
             started,
             subtree_restarts,
             subtree_restarts_limit,
+            dispatchers,
+            dead_letter_handler,
         }
     }
 
@@ -419,8 +573,9 @@ impl Supervisor {
         let id = self.bcast.id().clone();
         let sender = self.bcast.sender().clone();
         let path = self.bcast.path().clone();
+        let dispatchers = self.dispatchers.clone();
 
-        SupervisorRef::new(id, sender, path)
+        SupervisorRef::new(id, sender, path, dispatchers)
     }
 
     /// Creates a new supervisor, passes it through the specified
@@ -644,7 +799,7 @@ impl Supervisor {
             self.id(),
             bcast.id()
         );
-        let children = Children::new(bcast);
+        let children = Children::new(bcast).with_shared_dispatchers(&self.dispatchers);
         let mut children = init(children);
         debug!("Children({}): Initialized.", children.id());
 
@@ -673,6 +828,68 @@ impl Supervisor {
         self
     }
 
+    /// Creates a task running the future returned by `init` and
+    /// supervises it like a children group of a single element,
+    /// without it having to handle messages.
+    ///
+    /// If the future panics or returns `Err(())`, the task is
+    /// faulted and restarted in accordance with the supervisor's
+    /// [`SupervisionStrategy`] and [`RestartStrategy`], calling
+    /// `init` again to get a new future. If it returns `Ok(())`,
+    /// the task is stopped.
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - The closure returning the future to supervise,
+    ///     called every time the task is (re)started.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::supervisor(|sp| {
+    ///     sp.supervise_future(|| async {
+    ///         // Flush the metrics, keep a connection alive...
+    ///
+    ///         // ...and return `Ok(())` once done, or `Err(())` to
+    ///         // get restarted.
+    ///         Ok(())
+    ///     })
+    /// }).expect("Couldn't create the supervisor.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn supervise_future<I, F>(self, init: I) -> Self
+    where
+        I: Fn() -> F + Send + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        debug!("Supervisor({}): Supervising a future.", self.id());
+        self.children(|children| {
+            children
+                .with_redundancy(1)
+                .with_exec(move |_: BastionContext| init())
+        })
+    }
+
     /// Creates a new [`Children`], passes it through the specified
     /// `init` closure and then starts supervising it.
     ///
@@ -741,10 +958,13 @@ impl Supervisor {
             self.id(),
             bcast.id()
         );
-        let children = Children::new(bcast);
+        let children = Children::new(bcast).with_shared_dispatchers(&self.dispatchers);
         let mut children = init(children);
         debug!("Children({}): Initialized.", children.id());
         // FIXME: children group elems launched without the group itself being launched
+        if let Err(e) = children.register_dispatchers() {
+            warn!("couldn't register all dispatchers into the registry: {}", e);
+        };
         children.launch_elems();
 
         let children_ref = children.as_ref();
@@ -760,13 +980,71 @@ impl Supervisor {
         children_ref
     }
 
+    /// Preallocates the registries of the supervisor for the
+    /// expected amount of supervised children groups and
+    /// supervisors, so that supervising a known amount of them
+    /// doesn't have the registries grow incrementally.
+    ///
+    /// This is only a hint: the registries still grow if more
+    /// children groups or supervisors are supervised.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The expected amount of supervised children
+    ///     groups and supervisors.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::supervisor(|sp| {
+    ///     (0..128).fold(sp.with_capacity_hint(128), |sp, _| sp.supervisor(|sp| sp))
+    /// }).expect("Couldn't create the supervisor");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn with_capacity_hint(mut self, capacity: usize) -> Self {
+        trace!(
+            "Supervisor({}): Setting capacity hint: {}",
+            self.id(),
+            capacity
+        );
+        self.order.reserve(capacity);
+        self.tracked_groups.reserve(capacity);
+        self.tracked_groups_order.reserve(capacity);
+        self.launched.reserve(capacity);
+        self.stopped.reserve(capacity);
+        self.killed.reserve(capacity);
+        self
+    }
+
     /// Sets the strategy the supervisor should use when one
     /// of its supervised children groups or supervisors dies
     /// (in the case of a children group, it could be because one
     /// of its elements panicked or returned an error).
     ///
     /// The default strategy is
-    /// [`SupervisionStrategy::OneForOne`].
+    /// [`SupervisionStrategy::OneForOne`]. The children groups given
+    /// their own strategy with [`Children::with_strategy`] use it
+    /// instead when their elements fault.
     ///
     /// # Arguments
     ///
@@ -814,6 +1092,8 @@ impl Supervisor {
     /// # Bastion::block_until_stopped();
     /// # }
     /// ```
+    ///
+    /// [`Children::with_strategy`]: crate::children::Children::with_strategy
     pub fn with_strategy(mut self, strategy: SupervisionStrategy) -> Self {
         trace!(
             "Supervisor({}): Setting strategy: {:?}",
@@ -878,6 +1158,66 @@ impl Supervisor {
         self
     }
 
+    /// Routes the messages dead-lettered within the subtree of this
+    /// supervisor to the given children group instead of the global
+    /// dead letters.
+    ///
+    /// Those are the messages that the children groups and elements
+    /// of the subtree couldn't handle, for example because they
+    /// expired or were received while elements were down. The dead
+    /// letters of a supervisor nested in this one are routed to its
+    /// own handler if it has one.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The children group receiving the dead letters.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// let handler = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| async move {
+    ///         loop {
+    ///             let dead_letter = ctx.recv().await?;
+    ///             println!("Couldn't deliver {:?}", dead_letter);
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// Bastion::supervisor(|sp| {
+    ///     sp.with_dead_letter_handler(handler)
+    /// }).expect("Couldn't create the supervisor.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn with_dead_letter_handler(mut self, handler: ChildrenRef) -> Self {
+        trace!(
+            "Supervisor({}): Setting dead letter handler: Children({})",
+            self.id(),
+            handler.id()
+        );
+        self.dead_letter_handler = Some(handler);
+        self
+    }
+
     /// Sets the callbacks that will get called at this supervisor's
     /// different lifecycle events.
     ///
@@ -931,6 +1271,61 @@ impl Supervisor {
         self
     }
 
+    /// Appends the elements of all the children groups of this
+    /// supervisor to the declared dispatcher, including the groups
+    /// created afterwards, so that the messages broadcasted to it
+    /// are distributed across all of them.
+    ///
+    /// Note that this method must be called before creating the
+    /// children groups sharing the dispatcher.
+    ///
+    /// # Arguments
+    ///
+    /// * `dispatcher` - The dispatcher shared by all the children
+    ///     groups of this supervisor.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// Bastion::supervisor(|sp| {
+    ///     sp.with_dispatcher(Dispatcher::with_type(DispatcherType::Named(
+    ///         "workers".to_string(),
+    ///     )))
+    ///     .children(|children| children.with_redundancy(2))
+    ///     .children(|children| children.with_redundancy(3))
+    /// }).expect("Couldn't create the supervisor.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn with_dispatcher(mut self, dispatcher: Dispatcher) -> Self {
+        trace!(
+            "Supervisor({}): Adding dispatcher: {:?}",
+            self.id(),
+            dispatcher.dispatcher_type()
+        );
+        self.dispatchers.push(Arc::new(Box::new(dispatcher)));
+        self
+    }
+
     async fn restart(&mut self, objects: Vec<RestartedElement>) {
         debug!(
             "Supervisor({}): Restarting {:?} elements",
@@ -939,6 +1334,27 @@ impl Supervisor {
         );
         let mut restart_futures = FuturesOrdered::new();
 
+        // NOTE: the groups restarting several elements hold the delivery
+        //      of messages until all of them are restored, so that the
+        //      messages routed to them are never received while only
+        //      some of their elements were restarted.
+        let mut restored = FxHashMap::default();
+        for object in objects.iter() {
+            if let RestartedElement::Child { parent_id, .. } = object {
+                *restored.entry(parent_id.clone()).or_insert(0) += 1;
+            }
+        }
+        let held = restored
+            .into_iter()
+            .filter(|(_, count)| *count > 1)
+            .map(|(parent_id, _)| parent_id)
+            .collect::<Vec<_>>();
+        for parent_id in held.iter() {
+            let msg = BastionMessage::hold();
+            let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+            self.bcast.send_child(parent_id, env);
+        }
+
         for object in objects {
             match object {
                 RestartedElement::Supervisor(supervisor_id) => {
@@ -968,8 +1384,22 @@ impl Supervisor {
                         RestartPolicy::Tries(max_retries) => restarts_count < max_retries,
                     };
 
+                    let delay = if restart_required {
+                        let delay = self
+                            .restart_strategy
+                            .delay(restarts_count, tracked_state.last_delay);
+                        tracked_state.last_delay = delay;
+                        delay
+                    } else {
+                        None
+                    };
+
                     let msg = match restart_required {
                         true => {
+                            // NOTE: all the restarted elements are marked
+                            //      before any of them is restarted, so that
+                            //      the restart of the whole group is awaited.
+                            HEALTH.child_restarting(&parent_id, &id);
                             tracked_state.increase_restarts_counter();
                             let state = tracked_state.state();
                             BastionMessage::restore_child(id, state)
@@ -979,11 +1409,9 @@ impl Supervisor {
                             BastionMessage::drop_child(id)
                         }
                     };
-                    let restart_strategy = self.restart_strategy.clone();
-
                     restart_futures.push(async move {
-                        if restart_required {
-                            restart_strategy.apply_strategy(restarts_count).await;
+                        if let Some(delay) = delay {
+                            Delay::new(delay).await;
                         }
 
                         (parent_id, msg)
@@ -996,6 +1424,12 @@ impl Supervisor {
             let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
             self.bcast.send_child(&receiver, env);
         }
+
+        for parent_id in held.iter() {
+            let msg = BastionMessage::release();
+            let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+            self.bcast.send_child(parent_id, env);
+        }
     }
 
     fn remove_child(&mut self, id: &BastionId, parent_id: &BastionId) {
@@ -1017,28 +1451,23 @@ impl Supervisor {
 
     async fn stop(&mut self, range: Range<usize>) {
         debug!("Supervisor({}): Stopping range: {:?}", self.id(), range);
-        if range.start == 0 {
-            self.bcast.stop_children();
-        } else {
-            // FIXME: panics
-            for id in self.order.get(range.clone()).unwrap() {
-                trace!("Supervised({}): Stopping Supervised({}).", self.id(), id);
-                self.bcast.stop_child(id);
-            }
-        }
+        // NOTE: the elements are stopped one at a time, in the reverse
+        //      of the order they were added in, so that an element is
+        //      only stopped once the ones added after it (which might
+        //      depend on it) are.
+        // FIXME: panics
+        let ids = self.order.get(range.clone()).unwrap().to_vec();
+        for id in ids.iter().rev() {
+            trace!("Supervised({}): Stopping Supervised({}).", self.id(), id);
+            self.bcast.stop_child(id);
 
-        let mut supervised = FuturesOrdered::new();
-        // FIXME: panics?
-        for id in self.order.get(range.clone()).unwrap() {
             // TODO: Err if None?
-            if let Some((_, launched)) = self.launched.remove(&id) {
-                // TODO: add a "stopped" list and poll from it instead of awaiting
-                supervised.push(launched);
-            }
-        }
+            let launched = match self.launched.remove(id) {
+                Some((_, launched)) => launched,
+                None => continue,
+            };
 
-        while let Some(supervised) = supervised.next().await {
-            match supervised {
+            match launched.await {
                 Some(supervised) => {
                     trace!(
                         "Supervisor({}): Supervised({}) stopped.",
@@ -1054,6 +1483,10 @@ impl Supervisor {
                 None => unimplemented!(),
             }
         }
+
+        if range.start == 0 {
+            self.bcast.stop_children();
+        }
     }
 
     async fn kill(&mut self, range: Range<usize>) {
@@ -1097,15 +1530,58 @@ impl Supervisor {
 
     fn stopped(&mut self) {
         debug!("Supervisor({}): Stopped.", self.id());
+        TOPOLOGY.unregister(self.id());
+        DEAD_LETTER_HANDLERS.unregister(self.id());
+        DEPENDENCIES.unregister(self.id());
+        self.remove_dispatchers();
         self.bcast.stopped();
     }
 
-    fn faulted(&mut self) {
+    fn faulted(&mut self, reason: FaultReason) {
         debug!("Supervisor({}): Faulted.", self.id());
-        self.bcast.faulted();
+        TOPOLOGY.unregister(self.id());
+        DEAD_LETTER_HANDLERS.unregister(self.id());
+        DEPENDENCIES.unregister(self.id());
+        self.remove_dispatchers();
+        self.bcast.faulted(reason);
+    }
+
+    /// Removes the dispatchers shared by the supervised children
+    /// groups from the global dispatcher.
+    fn remove_dispatchers(&self) {
+        let global_dispatcher = SYSTEM.dispatcher();
+
+        for dispatcher in self.dispatchers.iter() {
+            if let Err(e) = global_dispatcher.remove_dispatcher(dispatcher) {
+                warn!("couldn't remove the dispatcher from the registry: {}", e);
+            }
+        }
     }
 
     async fn recover(&mut self, id: BastionId, parent_id: BastionId) -> Result<(), ()> {
+        if let Some(strategy) = self.group_strategies.get(&parent_id) {
+            debug!(
+                "Supervisor({}): Recovering Children({}) using its strategy: {:?}",
+                self.id(),
+                parent_id,
+                strategy
+            );
+
+            // NOTE: the strategy of a group only applies to its own
+            //      elements, the other groups are never restarted.
+            let search_method = match strategy {
+                SupervisionStrategy::OneForOne => ActorSearchMethod::OneActor { id, parent_id },
+                SupervisionStrategy::OneForAll => ActorSearchMethod::Group { parent_id },
+                SupervisionStrategy::RestForOne => {
+                    ActorSearchMethod::FromActorInGroup { id, parent_id }
+                }
+            };
+            let objects = self.search_restarted_objects(search_method);
+            self.restart(objects).await;
+
+            return Ok(());
+        }
+
         debug!(
             "Supervisor({}): Recovering using strategy: {:?}",
             self.id(),
@@ -1184,6 +1660,35 @@ impl Supervisor {
                     }
                 }
             }
+            ActorSearchMethod::FromActorInGroup { id, parent_id } => {
+                let childs = match self.tracked_groups.get(&parent_id) {
+                    Some(childs) => childs,
+                    None => return objects,
+                };
+                let start_index = match self.tracked_groups_order.get(&id) {
+                    Some(index) => *index,
+                    None => return objects,
+                };
+
+                childs.iter().skip(start_index).for_each(|tracked_state| {
+                    let element = RestartedElement::Child {
+                        id: tracked_state.id(),
+                        parent_id: parent_id.clone(),
+                    };
+                    objects.push(element)
+                });
+            }
+            ActorSearchMethod::Group { parent_id } => {
+                if let Some(childs) = self.tracked_groups.get(&parent_id) {
+                    for tracked_state in childs {
+                        let element = RestartedElement::Child {
+                            id: tracked_state.id(),
+                            parent_id: parent_id.clone(),
+                        };
+                        objects.push(element);
+                    }
+                }
+            }
             ActorSearchMethod::All => {
                 for id in self.order.iter() {
                     match self.tracked_groups.get(&id) {
@@ -1208,6 +1713,94 @@ impl Supervisor {
         objects
     }
 
+    // Hands the launched children group with the given identifier over
+    // to the given supervisor, without stopping it.
+    fn hand_over(&mut self, id: BastionId, to: SupervisorRef) {
+        let launched = match self.launched.remove(&id) {
+            Some((_, launched)) => launched,
+            None => {
+                debug!(
+                    "Supervisor({}): Can't hand Supervised({}) over: not launched.",
+                    self.id(),
+                    id
+                );
+                return;
+            }
+        };
+        // FIXME: panics?
+        let sender = self.bcast.take_child(&id).unwrap();
+
+        let tracked = self.tracked_groups.remove(&id).unwrap_or_default();
+        let strategy = self.group_strategies.remove(&id);
+        for tracked_state in tracked.iter() {
+            self.tracked_groups_order.remove(&tracked_state.id);
+        }
+
+        self.order.retain(|order_id| order_id != &id);
+        for (index, order_id) in self.order.iter().enumerate() {
+            if let Some((launched_index, _)) = self.launched.get_mut(order_id) {
+                *launched_index = index;
+            }
+        }
+
+        debug!(
+            "Supervisor({}): Handing Children({}) over to Supervisor({}).",
+            self.id(),
+            id,
+            to.id()
+        );
+        let adoption = Adoption {
+            id,
+            sender,
+            launched,
+            tracked,
+            strategy,
+        };
+        let msg = BastionMessage::adopt(adoption);
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        if to.send(env).is_err() {
+            warn!(
+                "Supervisor({}): Supervisor({}) is stopped, the children group is lost.",
+                self.id(),
+                to.id()
+            );
+        }
+    }
+
+    // Supervises the children group handed over by another supervisor.
+    fn adopt(&mut self, adoption: Adoption) {
+        let Adoption {
+            id,
+            sender,
+            launched,
+            tracked,
+            strategy,
+        } = adoption;
+        debug!("Supervisor({}): Adopting Children({}).", self.id(), id);
+
+        for (index, tracked_state) in tracked.iter().enumerate() {
+            self.tracked_groups_order
+                .insert(tracked_state.id.clone(), index);
+        }
+        if !tracked.is_empty() {
+            self.tracked_groups.insert(id.clone(), tracked);
+        }
+        if let Some(strategy) = strategy {
+            self.group_strategies.insert(id.clone(), strategy);
+        }
+
+        // NOTE: the children group only reports to this supervisor
+        //      once it is tracked, so that no restart is missed.
+        let msg = BastionMessage::reparent(self.as_ref());
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        sender.unbounded_send(env).ok();
+        self.bcast.adopt(id.clone(), sender);
+
+        self.launched
+            .insert(id.clone(), (self.order.len(), launched));
+        self.order.push(id);
+    }
+
     async fn restart_subtree(&mut self) {
         if self.subtree_restarts < self.subtree_restarts_limit {
             self.subtree_restarts += 1;
@@ -1244,6 +1837,10 @@ impl Supervisor {
                     children.id()
                 );
                 children.callbacks().before_start();
+                if let Some(strategy) = children.strategy() {
+                    self.group_strategies
+                        .insert(children.id().clone(), strategy.clone());
+                }
                 Supervised::children(children)
             }
         };
@@ -1285,6 +1882,7 @@ impl Supervisor {
         &mut self,
         id: BastionId,
         parent_id: BastionId,
+        reason: FaultReason,
     ) -> Result<(), ()> {
         if self.launched.contains_key(&id) {
             warn!("Supervisor({}): Supervised({}) faulted.", self.id(), id);
@@ -1293,7 +1891,7 @@ impl Supervisor {
         if self.recover(id, parent_id).await.is_err() {
             // TODO: stop or kill?
             self.kill(0..self.order.len()).await;
-            self.faulted();
+            self.faulted(reason);
 
             return Err(());
         }
@@ -1314,6 +1912,14 @@ impl Supervisor {
                 self.deinit_with_stop().await;
                 return Err(());
             }
+            // NOTE: only groups of children can be stopped gracefully.
+            Envelope {
+                msg: BastionMessage::GracefulStop { .. },
+                ..
+            } => debug!(
+                "Supervisor({}): Ignoring a graceful stop request.",
+                self.id()
+            ),
             Envelope {
                 msg: BastionMessage::Kill,
                 ..
@@ -1340,6 +1946,7 @@ impl Supervisor {
                     strategy
                 );
                 self.strategy = strategy;
+                TOPOLOGY.register_supervisor(self.id(), self.bcast.parent(), &self.strategy);
             }
             Envelope {
                 msg: BastionMessage::ApplyCallback { .. },
@@ -1378,10 +1985,36 @@ impl Supervisor {
                 self.bcast.send_children(env);
             }
             Envelope {
-                msg: BastionMessage::RestartRequired { id, parent_id },
+                msg: BastionMessage::Batch(_),
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg:
+                    BastionMessage::RestartRequired {
+                        id,
+                        parent_id,
+                        reason,
+                    },
                 ..
             } => {
-                if self.recover_supervised_object(id, parent_id).await.is_err() {
+                self.callbacks.on_fault(&id, &reason);
+                if reason == FaultReason::Escalated {
+                    warn!(
+                        "Supervisor({}): Supervised({}) escalated its fault.",
+                        self.id(),
+                        id
+                    );
+                    self.kill(0..self.order.len()).await;
+                    self.faulted(reason);
+
+                    return Err(());
+                }
+
+                if self
+                    .recover_supervised_object(id, parent_id, reason)
+                    .await
+                    .is_err()
+                {
                     return Err(());
                 }
             }
@@ -1410,13 +2043,51 @@ impl Supervisor {
                 ..
             } => self.cleanup_supervised_object(id).await,
             Envelope {
-                msg: BastionMessage::Faulted { id },
+                msg: BastionMessage::Faulted { id, .. },
                 ..
             } => self.cleanup_supervised_object(id).await,
             Envelope {
                 msg: BastionMessage::Heartbeat,
                 ..
             } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Pause,
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Resume,
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Hold,
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Release,
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Thaw,
+                ..
+            } => {
+                debug!(
+                    "Supervisor({}): Resuming the delivery of messages.",
+                    self.id()
+                );
+                self.bcast.send_children(env);
+            }
+            Envelope {
+                msg: BastionMessage::Handover { id, to },
+                ..
+            } => self.hand_over(id, to),
+            Envelope {
+                msg: BastionMessage::Adopt(adoption),
+                ..
+            } => self.adopt(*adoption),
+            Envelope {
+                msg: BastionMessage::Reparent(_),
+                ..
+            } => unreachable!(),
         }
 
         Ok(())
@@ -1494,14 +2165,28 @@ impl Supervisor {
 
     pub(crate) fn launch(self) -> RecoverableHandle<Self> {
         debug!("Supervisor({}): Launching.", self.id());
+        TOPOLOGY.register_supervisor(self.id(), self.bcast.parent(), &self.strategy);
+        if let Some(handler) = &self.dead_letter_handler {
+            DEAD_LETTER_HANDLERS.register(self.id(), handler.clone());
+        }
         let stack = self.stack();
-        pool::spawn(self.run(), stack)
+        spawner().spawn(self.run(), stack)
     }
 }
 
 impl SupervisorRef {
-    pub(crate) fn new(id: BastionId, sender: Sender, path: Arc<BastionPath>) -> Self {
-        SupervisorRef { id, sender, path }
+    pub(crate) fn new(
+        id: BastionId,
+        sender: Sender,
+        path: Arc<BastionPath>,
+        dispatchers: Vec<Arc<Box<Dispatcher>>>,
+    ) -> Self {
+        SupervisorRef {
+            id,
+            sender,
+            path,
+            dispatchers,
+        }
     }
 
     /// Returns the identifier of the supervisor this `SupervisorRef`
@@ -1678,6 +2363,62 @@ impl SupervisorRef {
         self.children_with_id(BastionId::new(), init)
     }
 
+    /// Launches the stages of the given [`Pipeline`] as children
+    /// groups supervised by the supervisor this `SupervisorRef` is
+    /// referencing, each of them forwarding its output to the next
+    /// one through a dispatcher.
+    ///
+    /// This method returns a [`PipelineRef`] allowing to push items
+    /// to the pipeline if it succeeded, or `Err(())` otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `pipeline` - The pipeline to launch.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// # let sp_ref: SupervisorRef = Bastion::supervisor(|sp| sp).unwrap();
+    /// let pipeline = sp_ref.pipeline(
+    ///     Pipeline::new(Stage::new(|line: &String| line.len()).with_redundancy(4))
+    ///         .then(Stage::new(|len: &usize| println!("{}", len))),
+    /// ).expect("Couldn't launch the pipeline.");
+    ///
+    /// pipeline.push("Hello, world!".to_string());
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Pipeline`]: crate::pipeline::Pipeline
+    /// [`PipelineRef`]: crate::pipeline::PipelineRef
+    pub fn pipeline<I, O>(&self, pipeline: Pipeline<I, O>) -> Result<PipelineRef<I>, ()>
+    where
+        I: Message,
+        O: Message,
+    {
+        debug!("SupervisorRef({}): Launching pipeline.", self.id());
+        pipeline.launch(self)
+    }
+
     pub(crate) fn children_with_id<C>(&self, id: BastionId, init: C) -> Result<ChildrenRef, ()>
     where
         C: FnOnce(Children) -> Children,
@@ -1691,10 +2432,13 @@ impl SupervisorRef {
             self.id(),
             bcast.id()
         );
-        let children = Children::new(bcast);
+        let children = Children::new(bcast).with_shared_dispatchers(&self.dispatchers);
         let mut children = init(children);
         debug!("Children({}): Initialized.", children.id());
         // FIXME: children group elems launched without the group itself being launched
+        if let Err(e) = children.register_dispatchers() {
+            warn!("couldn't register all dispatchers into the registry: {}", e);
+        };
         children.launch_elems();
 
         let children_ref = children.as_ref();
@@ -1891,6 +2635,58 @@ impl SupervisorRef {
         self.send(env).map_err(|_| ())
     }
 
+    /// Declares that the supervisor this `SupervisorRef` is
+    /// referencing depends on the one `other` is referencing, that is
+    /// that `other` has to outlive it.
+    ///
+    /// Every supervisor already depends on its ancestors, which only
+    /// stop once it did. The declared dependencies are checked when
+    /// the system starts, which is refused if they form a cycle (see
+    /// [`Bastion::try_start`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The supervisor this one depends on.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bastion::prelude::*;
+    /// #
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "tokio-runtime"))]
+    /// # fn main() {
+    /// #    run();    
+    /// # }
+    /// #
+    /// # fn run() {
+    /// # Bastion::init();
+    /// #
+    /// let storage = Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
+    /// let api = Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
+    /// api.depends_on(&storage);
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Bastion::try_start`]: crate::Bastion::try_start
+    pub fn depends_on(&self, other: &SupervisorRef) {
+        debug!(
+            "SupervisorRef({}): Depending on Supervisor({}).",
+            self.id(),
+            other.id()
+        );
+        DEPENDENCIES.add(self.path(), other.path());
+    }
+
     /// Sends a message to the supervisor this `SupervisorRef`
     /// is referencing to tell it to kill every running children
     /// groups and supervisors that it is supervising.
@@ -1950,6 +2746,7 @@ impl TrackedChildState {
             id,
             state,
             restarts_counts: 0,
+            last_delay: None,
         }
     }
 
@@ -2011,7 +2808,7 @@ impl Supervised {
         let stack = self.stack();
         match self {
             Supervised::Supervisor(supervisor) => {
-                pool::spawn(
+                spawner().spawn(
                     async {
                         // FIXME: panics?
                         let supervisor = supervisor.launch().await.unwrap();
@@ -2021,7 +2818,7 @@ impl Supervised {
                 )
             }
             Supervised::Children(children) => {
-                pool::spawn(
+                spawner().spawn(
                     async {
                         // FIXME: panics?
                         let children = children.launch().await.unwrap();
@@ -2072,6 +2869,7 @@ impl RestartStrategy {
         RestartStrategy {
             restart_policy,
             strategy,
+            jitter: BackoffJitter::None,
         }
     }
 
@@ -2086,6 +2884,11 @@ impl RestartStrategy {
         self.strategy.clone()
     }
 
+    /// Returns the jitter applied to the restart delays.
+    pub fn jitter(&self) -> BackoffJitter {
+        self.jitter
+    }
+
     /// Sets the limit of attempts for restoring failed actors.
     pub fn with_restart_policy(mut self, restart_policy: RestartPolicy) -> Self {
         self.restart_policy = restart_policy;
@@ -2100,10 +2903,36 @@ impl RestartStrategy {
         self
     }
 
-    pub(crate) async fn apply_strategy(&self, restarts_count: usize) {
-        if let Some(dur) = self.strategy.calculate(restarts_count) {
-            Delay::new(dur).await;
-        }
+    /// Sets the jitter applied to the restart delays computed by
+    /// the actor restart strategy, so that the actors which failed
+    /// together are restarted at different times.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::time::Duration;
+    /// # use bastion::prelude::*;
+    /// #
+    /// let restart_strategy = RestartStrategy::default()
+    ///     .with_actor_restart_strategy(ActorRestartStrategy::ExponentialBackOff {
+    ///         timeout: Duration::from_millis(100),
+    ///         multiplier: 2.0,
+    ///     })
+    ///     .with_jitter(BackoffJitter::Full);
+    /// ```
+    pub fn with_jitter(mut self, jitter: BackoffJitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    // Computes the delay before restarting an actor, if any.
+    pub(crate) fn delay(
+        &self,
+        restarts_count: usize,
+        previous: Option<Duration>,
+    ) -> Option<Duration> {
+        let delay = self.strategy.calculate(restarts_count)?;
+        Some(self.jitter.apply(&self.strategy, delay, previous))
     }
 }
 
@@ -2118,6 +2947,7 @@ impl Default for RestartStrategy {
         RestartStrategy {
             restart_policy: RestartPolicy::Always,
             strategy: ActorRestartStrategy::default(),
+            jitter: BackoffJitter::None,
         }
     }
 }
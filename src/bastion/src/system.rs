@@ -1,22 +1,27 @@
 use crate::broadcast::{Broadcast, Parent, Sender};
 use crate::children_ref::ChildrenRef;
+use crate::config::Config;
 use crate::context::{BastionContext, BastionId, NIL_ID};
+use crate::dead_letters::{DEAD_LETTERS, DEAD_LETTER_HANDLERS};
 use crate::dispatcher::GlobalDispatcher;
 use crate::envelope::Envelope;
+use crate::events::{SystemEvent, EVENTS};
+use crate::executor::{spawner, Spawner};
 use crate::message::{BastionMessage, Deployment};
 use crate::path::{BastionPath, BastionPathElement};
-use crate::supervisor::{Supervisor, SupervisorRef};
+#[cfg(feature = "json-logs")]
+use crate::supervision_log::{self, SupervisionEvent};
+use crate::supervisor::{FaultReason, Supervisor, SupervisorRef};
 use async_mutex::Mutex as AsyncMutex;
-use bastion_executor::pool;
 use futures::prelude::*;
 use futures::stream::FuturesUnordered;
 use futures::{pending, poll};
 use fxhash::{FxHashMap, FxHashSet};
 use lasso::ThreadedRodeo;
 use lightproc::prelude::*;
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
 use std::sync::{Arc, Condvar, Mutex};
-use std::task::Poll;
+use std::task::{Context, Poll, Waker};
 use tracing::{debug, error, info, trace, warn};
 
 pub(crate) static STRING_INTERNER: Lazy<Arc<ThreadedRodeo>> =
@@ -24,6 +29,9 @@ pub(crate) static STRING_INTERNER: Lazy<Arc<ThreadedRodeo>> =
 
 pub(crate) static SYSTEM: Lazy<GlobalSystem> = Lazy::new(System::init);
 
+// Set by `Bastion::init_with` before the system is initialized.
+pub(crate) static CONFIG: OnceCell<Config> = OnceCell::new();
+
 pub(crate) struct GlobalSystem {
     sender: Sender,
     supervisor: SupervisorRef,
@@ -32,6 +40,8 @@ pub(crate) struct GlobalSystem {
     handle: Arc<AsyncMutex<Option<RecoverableHandle<()>>>>,
     running: Mutex<bool>,
     stopping_cvar: Condvar,
+    // The tasks waiting for the system to be stopped.
+    stopping_wakers: Mutex<Vec<Waker>>,
     dispatcher: GlobalDispatcher,
 }
 
@@ -39,6 +49,9 @@ pub(crate) struct GlobalSystem {
 struct System {
     bcast: Broadcast,
     launched: FxHashMap<BastionId, RecoverableHandle<Supervisor>>,
+    // The order in which the launched supervisors were launched.
+    order: Vec<BastionId>,
+    // The supervisors which faulted and are waiting to be restarted.
     // TODO: set limit
     restart: FxHashSet<BastionId>,
     waiting: FuturesUnordered<RecoverableHandle<Supervisor>>,
@@ -59,6 +72,7 @@ impl GlobalSystem {
         let path = Arc::new(BastionPath::root());
         let running = Mutex::new(true);
         let stopping_cvar = Condvar::new();
+        let stopping_wakers = Mutex::new(Vec::new());
         let dispatcher = GlobalDispatcher::new();
 
         GlobalSystem {
@@ -69,6 +83,7 @@ impl GlobalSystem {
             handle,
             running,
             stopping_cvar,
+            stopping_wakers,
             dispatcher,
         }
     }
@@ -85,6 +100,16 @@ impl GlobalSystem {
         &self.dead_letters
     }
 
+    /// Returns the dead letters the messages which can't be delivered
+    /// by the element at the given path are routed to: the handler of
+    /// its closest supervisor configured with one, or the global dead
+    /// letters otherwise.
+    pub(crate) fn dead_letters_of(&self, path: &BastionPath) -> ChildrenRef {
+        DEAD_LETTER_HANDLERS
+            .handler_of(path)
+            .unwrap_or_else(|| self.dead_letters.clone())
+    }
+
     pub(crate) fn handle(&self) -> Arc<AsyncMutex<Option<RecoverableHandle<()>>>> {
         self.handle.clone()
     }
@@ -101,6 +126,17 @@ impl GlobalSystem {
         // FIXME: panics
         *self.running.lock().unwrap() = false;
         self.stopping_cvar.notify_all();
+
+        // FIXME: panics
+        let wakers = std::mem::take(&mut *self.stopping_wakers.lock().unwrap());
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    pub(crate) fn is_running(&self) -> bool {
+        // FIXME: panics
+        *self.running.lock().unwrap()
     }
 
     pub(crate) fn wait_until_stopped(&self) {
@@ -110,35 +146,63 @@ impl GlobalSystem {
             running = self.stopping_cvar.wait(running).unwrap();
         }
     }
+
+    pub(crate) fn poll_stopped(&self, cx: &mut Context) -> Poll<()> {
+        // FIXME: panics
+        let running = self.running.lock().unwrap();
+        if !*running {
+            return Poll::Ready(());
+        }
+
+        // NOTE: the waker is registered while `running` is locked, so
+        //      that it can't be missed by `notify_stopped`.
+        // FIXME: panics
+        let mut wakers = self.stopping_wakers.lock().unwrap();
+        if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+        }
+
+        Poll::Pending
+    }
 }
 
 impl System {
-    fn init() -> GlobalSystem {
-        info!("System: Initializing.");
+    fn new() -> Self {
+        System::with_capacity(0)
+    }
+
+    fn with_capacity(capacity: usize) -> Self {
         let parent = Parent::none();
         let bcast = Broadcast::new_root(parent);
-        let launched = FxHashMap::default();
-        let restart = FxHashSet::default();
+        let launched = FxHashMap::with_capacity_and_hasher(capacity, Default::default());
+        let order = Vec::with_capacity(capacity);
+        let restart = FxHashSet::with_capacity_and_hasher(capacity, Default::default());
         let waiting = FuturesUnordered::new();
         let pre_start_msgs = Vec::new();
         let started = false;
 
-        let sender = bcast.sender().clone();
-
-        let system = System {
+        System {
             bcast,
             launched,
+            order,
             restart,
             waiting,
             pre_start_msgs,
             started,
-        };
+        }
+    }
+
+    fn init() -> GlobalSystem {
+        info!("System: Initializing.");
+        let capacity = CONFIG.get().map_or(0, Config::capacity_hint);
+        let system = System::with_capacity(capacity);
+        let sender = system.bcast.sender().clone();
 
         debug!("System: Creating the system supervisor.");
         let parent = Parent::system();
         let bcast = Broadcast::new(parent, BastionPathElement::Supervisor(NIL_ID));
 
-        let supervisor = Supervisor::system(bcast);
+        let supervisor = Supervisor::system(bcast).with_capacity_hint(capacity);
         let supervisor_ref = supervisor.as_ref();
 
         let msg = BastionMessage::deploy_supervisor(supervisor);
@@ -151,7 +215,7 @@ impl System {
 
         debug!("System: Launching.");
         let stack = system.stack();
-        let handle = pool::spawn(system.run(), stack);
+        let handle = spawner().spawn(system.run(), stack);
 
         let dead_letters_ref =
             Self::spawn_dead_letters(&supervisor_ref).expect("Can't spawn dead letters");
@@ -170,6 +234,11 @@ impl System {
                 loop {
                     let smsg = ctx.recv().await?;
                     debug!("Received dead letter: {:?}", smsg);
+                    let sender = smsg.signature().path().clone();
+                    #[cfg(feature = "json-logs")]
+                    supervision_log::log(SupervisionEvent::DeadLetter, &sender, None);
+                    EVENTS.emit(SystemEvent::DeadLetter { sender });
+                    DEAD_LETTERS.push(smsg);
                 }
             })
         })
@@ -196,19 +265,66 @@ impl System {
         self.bcast.register(supervisor.bcast());
 
         info!("System: Launching Supervisor({}).", supervisor.id());
+        #[cfg(feature = "json-logs")]
+        supervision_log::log(
+            SupervisionEvent::SupervisorRestarted,
+            supervisor.bcast().path(),
+            None,
+        );
         let id = supervisor.id().clone();
         let launched = supervisor.launch();
-        self.launched.insert(id, launched);
+        self.launched.insert(id.clone(), launched);
+        self.order.push(id.clone());
+
+        EVENTS.emit(SystemEvent::SupervisorRestarted { id });
     }
 
     async fn stop(&mut self) -> Vec<Supervisor> {
+        let mut supervisors = Vec::new();
+        // NOTE: the supervisors are stopped one at a time, in the reverse
+        //      of the order they were launched in.
+        while let Some(id) = self.order.pop() {
+            self.bcast.stop_child(&id);
+
+            // TODO: Err if None?
+            let mut launched = match self.launched.remove(&id) {
+                Some(launched) => launched,
+                None => continue,
+            };
+
+            let stopped = loop {
+                if let Poll::Ready(stopped) = poll!(&mut launched) {
+                    break stopped;
+                }
+
+                if self.is_killed().await {
+                    info!("System: Killing while stopping.");
+                    self.launched.insert(id, launched);
+                    self.kill().await;
+
+                    return supervisors;
+                }
+
+                pending!();
+            };
+
+            match stopped {
+                Some(supervisor) => {
+                    debug!("System: Supervisor({}) stopped.", supervisor.id());
+                    supervisors.push(supervisor);
+                }
+                None => {
+                    error!("System: Unknown supervisor cancelled instead of stopped.");
+                }
+            }
+        }
+
         self.bcast.stop_children();
 
         for (_, launched) in self.launched.drain() {
             self.waiting.push(launched);
         }
 
-        let mut supervisors = Vec::new();
         loop {
             match poll!(&mut self.waiting.next()) {
                 Poll::Ready(Some(Some(supervisor))) => {
@@ -219,7 +335,34 @@ impl System {
                     error!("System: Unknown supervisor cancelled instead of stopped.");
                 }
                 Poll::Ready(None) => return supervisors,
-                Poll::Pending => pending!(),
+                Poll::Pending => {
+                    if self.is_killed().await {
+                        info!("System: Killing while stopping.");
+                        self.kill().await;
+
+                        return supervisors;
+                    }
+
+                    pending!();
+                }
+            }
+        }
+    }
+
+    // Checks whether the system was told to be killed while it is
+    // stopping, dropping the other messages it received since it
+    // won't handle them anymore.
+    async fn is_killed(&mut self) -> bool {
+        loop {
+            match poll!(&mut self.bcast.next()) {
+                Poll::Ready(Some(Envelope {
+                    msg: BastionMessage::Kill,
+                    ..
+                })) => return true,
+                Poll::Ready(Some(msg)) => {
+                    trace!("System: Dropping a message while stopping: {:?}", msg);
+                }
+                Poll::Ready(None) | Poll::Pending => return false,
             }
         }
     }
@@ -231,6 +374,7 @@ impl System {
             launched.cancel();
         }
 
+        self.order.clear();
         for (_, launched) in self.launched.drain() {
             launched.cancel();
 
@@ -268,7 +412,10 @@ impl System {
                 info!("System: Launching Supervisor({}).", supervisor.id());
                 let id = supervisor.id().clone();
                 let launched = supervisor.launch();
-                self.launched.insert(id, launched);
+                self.launched.insert(id.clone(), launched);
+                self.order.push(id.clone());
+
+                EVENTS.emit(SystemEvent::SupervisorLaunched { id });
             }
             // FIXME
             Deployment::Children(_) => unimplemented!(),
@@ -278,18 +425,40 @@ impl System {
     async fn prune_supervised_object(&mut self, id: BastionId) {
         // TODO: Err if None?
         if let Some(launched) = self.launched.remove(&id) {
+            self.order.retain(|launched_id| launched_id != &id);
             // TODO: stop or kill?
             self.bcast.kill_child(&id);
             self.waiting.push(launched);
         }
     }
 
-    fn restart_supervised_object(&mut self, id: BastionId) {
+    fn restart_supervised_object(&mut self, id: BastionId, reason: Option<FaultReason>) {
+        // NOTE: a supervisor can report that it stopped or faulted
+        //      more than once (and its own faults can be reported by
+        //      its children too), but it must only be relaunched once.
+        if self.restart.contains(&id) {
+            debug!(
+                "System: Supervisor({}) is already restarting, ignoring the fault.",
+                id
+            );
+            return;
+        }
+
         // TODO: Err if None?
         if let Some(launched) = self.launched.remove(&id) {
-            warn!("System: Supervisor({}) faulted.", id);
+            warn!("System: Supervisor({}) faulted (reason: {:?}).", id, reason);
+            self.order.retain(|launched_id| launched_id != &id);
             self.waiting.push(launched);
-            self.restart.insert(id);
+            self.restart.insert(id.clone());
+
+            #[cfg(feature = "json-logs")]
+            {
+                let path = BastionPath::clone(self.bcast.path())
+                    .append(BastionPathElement::Supervisor(id.clone()))
+                    .expect("couldn't append a supervisor to the system's path");
+                supervision_log::log(SupervisionEvent::SupervisorFaulted, &path, reason.as_ref());
+            }
+            EVENTS.emit(SystemEvent::SupervisorFaulted { id });
         }
     }
 
@@ -310,6 +479,11 @@ impl System {
 
                 return Err(());
             }
+            // NOTE: only groups of children can be stopped gracefully.
+            Envelope {
+                msg: BastionMessage::GracefulStop { .. },
+                ..
+            } => debug!("System: Ignoring a graceful stop request."),
             Envelope {
                 msg: BastionMessage::Kill,
                 ..
@@ -347,6 +521,10 @@ impl System {
                 debug!("System: Broadcasting a message: {:?}", message);
                 self.bcast.send_children(env);
             }
+            Envelope {
+                msg: BastionMessage::Batch(_),
+                ..
+            } => unreachable!(),
             Envelope {
                 msg: BastionMessage::RestartRequired { .. },
                 ..
@@ -374,15 +552,50 @@ impl System {
             Envelope {
                 msg: BastionMessage::Stopped { id, .. },
                 ..
-            } => self.restart_supervised_object(id),
+            } => self.restart_supervised_object(id, None),
             Envelope {
-                msg: BastionMessage::Faulted { id, .. },
+                msg: BastionMessage::Faulted { id, reason },
                 ..
-            } => self.restart_supervised_object(id),
+            } => self.restart_supervised_object(id, Some(reason)),
             Envelope {
                 msg: BastionMessage::Heartbeat,
                 ..
             } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Pause,
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Resume,
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Hold,
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Release,
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Thaw,
+                ..
+            } => {
+                debug!("System: Resuming the delivery of messages.");
+                self.bcast.send_children(env);
+            }
+            Envelope {
+                msg: BastionMessage::Handover { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Adopt(_),
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Reparent(_),
+                ..
+            } => unreachable!(),
         }
 
         Ok(())
@@ -390,9 +603,25 @@ impl System {
 
     async fn run(mut self) {
         info!("System: Launched.");
+        self.serve().await;
+
+        let handle = SYSTEM.handle();
+        let mut system = handle.lock().await;
+        *system = None;
+
+        SYSTEM.notify_stopped();
+    }
+
+    /// Services the system's supervisors and messages until the system
+    /// is stopped, killed or its channel gets closed.
+    ///
+    /// The system's task sleeps until a supervisor it waits for stops
+    /// or it receives a message, instead of being polled in the
+    /// meantime.
+    async fn serve(&mut self) {
         loop {
-            match poll!(&mut self.waiting.next()) {
-                Poll::Ready(Some(Some(supervisor))) => {
+            match self.next_wakeup().await {
+                Wakeup::Stopped(Some(supervisor)) => {
                     let id = supervisor.id();
                     self.bcast.unregister(&id);
 
@@ -400,18 +629,19 @@ impl System {
                         self.recover(supervisor).await;
                     } else {
                         supervisor.callbacks().after_stop();
-                    }
 
-                    continue;
+                        let id = id.clone();
+                        EVENTS.emit(SystemEvent::SupervisorStopped { id });
+                    }
+                }
+                // NOTE: the supervisor was cancelled, so there is nothing
+                //      left to recover or to call callbacks on, but the
+                //      other supervisors still need to be serviced.
+                Wakeup::Stopped(None) => {
+                    warn!("System: Unknown Supervisor cancelled instead of stopped.");
                 }
-                // FIXME
-                Poll::Ready(Some(None)) => unimplemented!(),
-                Poll::Ready(None) | Poll::Pending => (),
-            }
-
-            match poll!(&mut self.bcast.next()) {
                 // TODO: Err if started == true?
-                Poll::Ready(Some(Envelope {
+                Wakeup::Received(Some(Envelope {
                     msg: BastionMessage::Start,
                     ..
                 })) => {
@@ -435,38 +665,255 @@ impl System {
                         trace!("System: Replaying message: {:?}", msg);
                         // FIXME: Err(Error)?
                         if self.handle(msg).await.is_err() {
-                            let handle = SYSTEM.handle();
-                            let mut system = handle.lock().await;
-                            *system = None;
-
-                            SYSTEM.notify_stopped();
-
                             return;
                         }
                     }
                 }
-                Poll::Ready(Some(msg)) if !self.started => {
+                // NOTE: the system can be killed before being started,
+                //      instead of waiting for it to be.
+                Wakeup::Received(Some(Envelope {
+                    msg: BastionMessage::Kill,
+                    ..
+                })) if !self.started => {
+                    info!("System: Killing.");
+                    self.kill().await;
+
+                    return;
+                }
+                Wakeup::Received(Some(msg)) if !self.started => {
                     trace!("System: Received a new message (started=false): {:?}", msg);
                     self.pre_start_msgs.push(msg);
                 }
-                Poll::Ready(Some(msg)) => {
+                Wakeup::Received(Some(msg)) => {
                     trace!("System: Received a new message (started=true): {:?}", msg);
                     if self.handle(msg).await.is_err() {
-                        let handle = SYSTEM.handle();
-                        let mut system = handle.lock().await;
-                        *system = None;
-
-                        SYSTEM.notify_stopped();
-
                         return;
                     }
                 }
-                // NOTE: because `Broadcast` always holds both a `Sender` and
-                //      `Receiver` of the same channel, this would only be
-                //      possible if the channel was closed, which never happens.
-                Poll::Ready(None) => unreachable!(),
-                Poll::Pending => pending!(),
+                // NOTE: `Broadcast` always holds both a `Sender` and a
+                //      `Receiver` of the same channel, so this only happens
+                //      if the channel was explicitly closed (e.g. during a
+                //      shutdown race). We then stop the same way as if we
+                //      received `BastionMessage::Stop`.
+                Wakeup::Received(None) => {
+                    info!("System: Channel closed, stopping.");
+                    for supervisor in self.stop().await {
+                        supervisor.callbacks().after_stop();
+                    }
+
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Waits until a supervisor the system waits for stops or the
+    /// system receives a message, the stopped supervisors being
+    /// serviced first.
+    ///
+    /// Both the supervisors and the system's channel register the
+    /// task's waker when they aren't ready, so that the task is only
+    /// polled again once one of them is.
+    fn next_wakeup(&mut self) -> impl Future<Output = Wakeup> + '_ {
+        future::poll_fn(move |cx| {
+            // NOTE: `waiting` is exhausted while it is empty, in which
+            //      case supervisors are only pushed to it by this task.
+            if let Poll::Ready(Some(stopped)) = self.waiting.poll_next_unpin(cx) {
+                return Poll::Ready(Wakeup::Stopped(stopped));
+            }
+
+            self.bcast.poll_next_unpin(cx).map(Wakeup::Received)
+        })
+    }
+}
+
+// What woke the system's task up, see `System::next_wakeup`.
+enum Wakeup {
+    // A supervisor the system waited for stopped, or was cancelled.
+    Stopped(Option<Supervisor>),
+    // The system received a message, or its channel was closed.
+    Received(Option<Envelope>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::System;
+    use crate::broadcast::{Broadcast, Parent};
+    use crate::context::BastionId;
+    use crate::envelope::Envelope;
+    use crate::message::{BastionMessage, Deployment};
+    use crate::path::BastionPathElement;
+    use crate::supervisor::Supervisor;
+    use futures::executor;
+    use futures::prelude::*;
+    use futures::task::{self, ArcWake};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::Context;
+
+    // Counts how many times the task it wakes up was woken up.
+    #[derive(Default)]
+    struct WakeCounter(AtomicUsize);
+
+    impl ArcWake for WakeCounter {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn serve_returns_when_channel_closed_before_start() {
+        let mut system = System::new();
+        system.bcast.sender().close_channel();
+
+        executor::block_on(system.serve());
+        assert!(!system.started);
+        assert!(system.launched.is_empty());
+    }
+
+    #[test]
+    fn serve_returns_when_channel_closed_after_start() {
+        let mut system = System::new();
+
+        let msg = BastionMessage::start();
+        let env = Envelope::new(
+            msg,
+            system.bcast.path().clone(),
+            system.bcast.sender().clone(),
+        );
+        system.bcast.send_self(env);
+        system.bcast.sender().close_channel();
+
+        executor::block_on(system.serve());
+        assert!(system.started);
+        assert!(system.waiting.is_empty());
+    }
+
+    #[test]
+    fn serve_sleeps_until_woken_up() {
+        let mut system = System::new();
+        let sender = system.bcast.sender().clone();
+        let path = system.bcast.path().clone();
+
+        let wakes = Arc::new(WakeCounter::default());
+        let waker = task::waker(wakes.clone());
+        let mut cx = Context::from_waker(&waker);
+        {
+            let mut serve = Box::pin(system.serve());
+
+            // While idle, the system neither is ready nor wakes itself
+            // up to be polled again...
+            for _ in 0..10 {
+                assert!(serve.as_mut().poll(&mut cx).is_pending());
             }
+            assert_eq!(wakes.0.load(Ordering::SeqCst), 0);
+
+            // ...until it receives a message, which it handles as soon
+            // as it is polled again.
+            let env = Envelope::new(BastionMessage::start(), path, sender.clone());
+            sender.unbounded_send(env).unwrap();
+            assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+            assert!(serve.as_mut().poll(&mut cx).is_pending());
+            assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+
+            sender.close_channel();
+            assert_eq!(wakes.0.load(Ordering::SeqCst), 2);
+            assert!(serve.as_mut().poll(&mut cx).is_ready());
         }
+
+        assert!(system.started);
+    }
+
+    #[test]
+    fn capacity_hint_preallocates_registries() {
+        let mut system = System::with_capacity(64);
+        assert!(system.launched.capacity() >= 64);
+        assert!(system.order.capacity() >= 64);
+        assert!(system.restart.capacity() >= 64);
+
+        // Registering as many supervisors as hinted doesn't grow the
+        // registries, while it does without any hint.
+        let mut unhinted = System::new();
+        let hinted = (
+            system.launched.capacity(),
+            system.order.capacity(),
+            system.restart.capacity(),
+        );
+        for _ in 0..64 {
+            for system in &mut [&mut system, &mut unhinted] {
+                let id = BastionId::new();
+                let bcast =
+                    Broadcast::new(Parent::system(), BastionPathElement::Supervisor(id.clone()));
+                let deployment = Deployment::Supervisor(Supervisor::new(bcast));
+                executor::block_on(system.deploy(Box::new(deployment)));
+                system.restart.insert(id);
+            }
+        }
+        assert_eq!(system.launched.len(), 64);
+        assert_eq!(system.order.len(), 64);
+        assert_eq!(
+            (
+                system.launched.capacity(),
+                system.order.capacity(),
+                system.restart.capacity(),
+            ),
+            hinted
+        );
+        assert!(unhinted.launched.capacity() > 0);
+        assert!(unhinted.order.capacity() > 0);
+        assert!(unhinted.restart.capacity() > 0);
+        executor::block_on(system.kill());
+        executor::block_on(unhinted.kill());
+        system.restart.clear();
+
+        let msg = BastionMessage::start();
+        let env = Envelope::new(
+            msg,
+            system.bcast.path().clone(),
+            system.bcast.sender().clone(),
+        );
+        system.bcast.send_self(env);
+        system.bcast.sender().close_channel();
+
+        // Behaves like a system without any hint.
+        executor::block_on(system.serve());
+        assert!(system.started);
+        assert!(system.launched.is_empty());
+        assert!(system.waiting.is_empty());
+    }
+
+    #[test]
+    fn duplicate_faults_only_relaunch_once() {
+        let mut system = System::new();
+        system.started = true;
+
+        let id = BastionId::new();
+        let bcast = Broadcast::new(Parent::system(), BastionPathElement::Supervisor(id.clone()));
+        let deployment = Deployment::Supervisor(Supervisor::new(bcast));
+        executor::block_on(system.deploy(Box::new(deployment)));
+
+        // A burst of faults for the same supervisor...
+        for _ in 0..10 {
+            system.restart_supervised_object(id.clone(), None);
+        }
+
+        // ...only waits for it to stop once...
+        assert_eq!(system.waiting.len(), 1);
+        assert_eq!(system.restart.len(), 1);
+        assert!(system.launched.is_empty());
+
+        system.bcast.kill_child(&id);
+        let supervisor = executor::block_on(system.waiting.next())
+            .expect("the supervisor wasn't waited for")
+            .expect("the supervisor was cancelled");
+        assert!(system.restart.remove(supervisor.id()));
+        executor::block_on(system.recover(supervisor));
+
+        // ...and relaunches it once.
+        assert_eq!(system.launched.len(), 1);
+        assert_eq!(system.order.len(), 1);
+        assert!(system.restart.is_empty());
+
+        executor::block_on(system.kill());
     }
 }
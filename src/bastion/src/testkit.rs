@@ -0,0 +1,471 @@
+//!
+//! Deterministic test harness for actors.
+//!
+//! [`TestProbe`] is an address that can be handed to actors (or
+//! registered in a dispatcher like any other group element) and
+//! which records what is sent to it, so that a test can
+//! synchronously assert on it.
+//!
+//! [`TestActor`] runs an actor's future without any executor or
+//! timer, delivering the messages that were sent to it and polling
+//! it only when the test asks for it.
+use crate::broadcast::{Receiver, Sender};
+use crate::child_ref::ChildRef;
+use crate::children_ref::ChildrenRef;
+use crate::context::{BastionContext, BastionId, ContextState};
+use crate::dispatcher::{Dispatcher, DispatcherType};
+use crate::envelope::{Envelope, RefAddr, SignedMessage};
+use crate::message::{Answer, BastionMessage, Message, Msg};
+use crate::path::{BastionPath, BastionPathElement};
+use crate::system::SYSTEM;
+use anyhow::Result as AnyResult;
+use futures::channel::mpsc;
+use futures::task::noop_waker_ref;
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tracing::{debug, trace};
+
+#[derive(Debug)]
+/// An address recording every message sent to it.
+///
+/// Actors can send messages to a probe using its [`ChildRef`] or
+/// its [`RefAddr`], reply to the messages the probe sent them using
+/// their signature, or send messages to it through a dispatcher
+/// it [joined].
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// # use bastion::testkit::{TestActor, TestProbe};
+/// #
+/// # #[cfg(feature = "tokio-runtime")]
+/// # #[tokio::main]
+/// # async fn main() {
+/// #    run();
+/// # }
+/// #
+/// # #[cfg(not(feature = "tokio-runtime"))]
+/// # fn main() {
+/// #    run();
+/// # }
+/// #
+/// # fn run() {
+/// let mut actor = TestActor::new(|ctx: BastionContext| async move {
+///     loop {
+///         msg! { ctx.recv().await?,
+///             msg: &'static str => {
+///                 let sender = signature!();
+///                 ctx.tell(&sender, msg.len()).expect("Couldn't reply.");
+///             };
+///             _: _ => ();
+///         }
+///     }
+/// });
+///
+/// let mut probe = TestProbe::new();
+/// probe.tell(actor.child_ref(), "hello").expect("Couldn't send the message.");
+/// actor.run_until_idle();
+///
+/// assert_eq!(probe.expect_msg_of::<usize>(), 5);
+/// probe.expect_no_msg();
+/// # }
+/// ```
+///
+/// [joined]: TestProbe::join
+pub struct TestProbe {
+    child: ChildRef,
+    recver: Receiver,
+    // The messages of a batch that weren't retrieved yet.
+    batched: VecDeque<SignedMessage>,
+}
+
+/// An actor whose future is polled step-by-step by the test
+/// driving it, instead of being spawned on the executor.
+///
+/// Only the messages sent to the actor are delivered to it, the
+/// lifecycle ones (like stop or kill requests) are ignored.
+///
+/// Because nothing wakes it up, an actor waiting on something else
+/// than [`BastionContext::recv`] (like a timer) won't make progress
+/// before the next step which polls it after the awaited event
+/// happened.
+///
+/// See [`TestProbe`] for an example.
+pub struct TestActor {
+    ctx_id: BastionId,
+    child: ChildRef,
+    recver: Receiver,
+    state: Arc<Pin<Box<ContextState>>>,
+    exec: Pin<Box<dyn Future<Output = Result<(), ()>>>>,
+    result: Option<Result<(), ()>>,
+}
+
+impl TestProbe {
+    /// Creates a new probe, with an empty mailbox.
+    pub fn new() -> Self {
+        let (child, recver, _) = test_child_ref();
+        debug!("TestProbe({}): Creating.", child.id());
+
+        TestProbe {
+            child,
+            recver,
+            batched: VecDeque::new(),
+        }
+    }
+
+    /// Returns a [`ChildRef`] referencing this probe, which can be
+    /// used anywhere a group element is expected.
+    pub fn child_ref(&self) -> &ChildRef {
+        &self.child
+    }
+
+    /// Returns the signature of the messages sent by this probe,
+    /// which can be used to send messages to it.
+    pub fn addr(&self) -> RefAddr {
+        RefAddr::new(self.child.path().clone(), self.child.sender().clone())
+    }
+
+    /// Sends a message to an element, signed by this probe so that
+    /// the element can reply to it.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(msg)`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The element to send the message to.
+    /// * `msg` - The message to send.
+    pub fn tell<M: Message>(&self, to: &ChildRef, msg: M) -> Result<(), M> {
+        debug!("TestProbe({}): Telling message: {:?}", self.child.id(), msg);
+        let msg = BastionMessage::tell(msg);
+        let env = Envelope::new_with_sign(msg, self.addr());
+        // FIXME: panics?
+        to.send(env).map_err(|env| env.into_msg().unwrap())
+    }
+
+    /// Sends a question to an element, signed by this probe, and
+    /// returns the [`Answer`] which will be resolved once it
+    /// answered it.
+    ///
+    /// This method returns [`Answer`] if it succeeded, or
+    /// `Err(msg)` otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The element to send the question to.
+    /// * `msg` - The question to send.
+    pub fn ask<M: Message>(&self, to: &ChildRef, msg: M) -> Result<Answer, M> {
+        debug!("TestProbe({}): Asking message: {:?}", self.child.id(), msg);
+        let (msg, answer) = BastionMessage::ask(msg, self.addr());
+        let env = Envelope::new_with_sign(msg, self.addr());
+        // FIXME: panics?
+        to.send(env).map_err(|env| env.into_msg().unwrap())?;
+
+        Ok(answer)
+    }
+
+    /// Registers this probe in the given dispatcher (registering
+    /// the dispatcher too if no group declared it yet), so that
+    /// the messages broadcasted to it are routed to the probe like
+    /// to any other element of the group.
+    ///
+    /// # Arguments
+    ///
+    /// * `dispatcher` - The dispatcher to register this probe in.
+    pub fn join(&self, dispatcher: Dispatcher) -> AnyResult<()> {
+        let dispatcher_type = dispatcher.dispatcher_type();
+        let global_dispatcher = SYSTEM.dispatcher();
+        global_dispatcher.register_dispatcher(&Arc::new(Box::new(dispatcher)))?;
+        global_dispatcher.register(&[dispatcher_type], &self.child, module_path!().to_string())
+    }
+
+    /// Removes this probe from the given dispatcher.
+    ///
+    /// # Arguments
+    ///
+    /// * `dispatcher_type` - The type of the dispatcher to remove
+    ///     this probe from.
+    pub fn leave(&self, dispatcher_type: DispatcherType) {
+        SYSTEM.dispatcher().remove(&[dispatcher_type], &self.child);
+    }
+
+    /// Returns the oldest message sent to this probe that wasn't
+    /// retrieved yet, if any.
+    ///
+    /// The messages routed to this probe by a dispatcher are returned
+    /// as they were broadcasted, signed by their original sender.
+    pub fn try_recv(&mut self) -> Option<SignedMessage> {
+        if let Some(msg) = self.batched.pop_front() {
+            return Some(msg);
+        }
+
+        while let Ok(Some(env)) = self.recver.try_next() {
+            match env {
+                Envelope {
+                    msg: BastionMessage::Message(msg),
+                    sign,
+                } => {
+                    trace!(
+                        "TestProbe({}): Received message: {:?}",
+                        self.child.id(),
+                        msg
+                    );
+                    return Some(undispatched(SignedMessage::new(msg, sign)));
+                }
+                Envelope {
+                    msg: BastionMessage::Batch(messages),
+                    ..
+                } => {
+                    trace!(
+                        "TestProbe({}): Received a batch of {} messages.",
+                        self.child.id(),
+                        messages.len()
+                    );
+                    self.batched.extend(messages.into_iter().map(|message| {
+                        let sign = message.signature().clone();
+                        undispatched(SignedMessage::new(Msg::tell(message), sign))
+                    }));
+                    if let Some(msg) = self.batched.pop_front() {
+                        return Some(msg);
+                    }
+                }
+                env => trace!("TestProbe({}): Ignoring: {:?}", self.child.id(), env),
+            }
+        }
+
+        None
+    }
+
+    /// Returns the oldest message sent to this probe that wasn't
+    /// retrieved yet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no message was sent to this probe.
+    pub fn expect_msg(&mut self) -> SignedMessage {
+        match self.try_recv() {
+            Some(msg) => msg,
+            None => panic!("TestProbe({}): Expected a message.", self.child.id()),
+        }
+    }
+
+    /// Returns the oldest message sent to this probe that wasn't
+    /// retrieved yet, whether it was told, asked or broadcasted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no message was sent to this probe or if it isn't
+    /// of type `M`.
+    pub fn expect_msg_of<M: Message + Clone>(&mut self) -> M {
+        let (msg, _) = self.expect_msg().extract();
+        if let Some(msg) = msg.downcast_ref::<M>() {
+            return (*msg).clone();
+        }
+
+        match msg.downcast::<M>() {
+            Ok(msg) => msg,
+            Err(msg) => panic!(
+                "TestProbe({}): Expected a message of type {}, got: {:?}",
+                self.child.id(),
+                std::any::type_name::<M>(),
+                msg
+            ),
+        }
+    }
+
+    /// Checks that every message sent to this probe was retrieved.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a message sent to this probe wasn't retrieved yet.
+    pub fn expect_no_msg(&mut self) {
+        if let Some(msg) = self.try_recv() {
+            panic!(
+                "TestProbe({}): Expected no message, got: {:?}",
+                self.child.id(),
+                msg
+            );
+        }
+    }
+}
+
+impl Default for TestProbe {
+    fn default() -> Self {
+        TestProbe::new()
+    }
+}
+
+impl TestActor {
+    /// Creates a new actor from its future, without polling it.
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - The closure taking the actor's [`BastionContext`]
+    ///     and returning its future, like [`Children::with_exec`].
+    ///
+    /// [`Children::with_exec`]: crate::children::Children::with_exec
+    pub fn new<I, F>(init: I) -> Self
+    where
+        I: FnOnce(BastionContext) -> F,
+        F: Future<Output = Result<(), ()>> + 'static,
+    {
+        let (child, recver, parent_path) = test_child_ref();
+        debug!("TestActor({}): Creating.", child.id());
+
+        // The parent's mailbox isn't read: asking the group to do
+        // something has no effect.
+        let (parent_sender, _) = mpsc::unbounded();
+        let parent = ChildrenRef::new(
+            BastionId::new(),
+            parent_sender,
+            Arc::new(parent_path),
+            vec![child.clone()],
+            vec![],
+            vec![],
+        );
+
+        let state = Arc::new(Box::pin(ContextState::new()));
+        let ctx_id = child.id().clone();
+        let ctx = BastionContext::new(ctx_id.clone(), child.clone(), parent, None, state.clone());
+        let exec = Box::pin(init(ctx));
+
+        TestActor {
+            ctx_id,
+            child,
+            recver,
+            state,
+            exec,
+            result: None,
+        }
+    }
+
+    /// Returns a [`ChildRef`] referencing this actor, which can be
+    /// used to send it messages.
+    pub fn child_ref(&self) -> &ChildRef {
+        &self.child
+    }
+
+    /// Delivers the oldest message sent to this actor which wasn't
+    /// delivered yet, if any, and polls it once.
+    ///
+    /// This method returns whether a message was delivered or the
+    /// actor stopped, ie. whether another step could make progress.
+    pub fn step(&mut self) -> bool {
+        if self.result.is_some() {
+            return false;
+        }
+
+        let delivered = self.deliver();
+
+        let mut cx = Context::from_waker(noop_waker_ref());
+        match self.exec.as_mut().poll(&mut cx) {
+            Poll::Ready(result) => {
+                debug!("TestActor({}): Stopped: {:?}", self.ctx_id, result);
+                self.result = Some(result);
+                true
+            }
+            Poll::Pending => delivered,
+        }
+    }
+
+    /// Steps this actor until every message sent to it was
+    /// delivered, or it stopped.
+    pub fn run_until_idle(&mut self) {
+        while self.step() {}
+    }
+
+    /// Returns whether the messages delivered to this actor were all
+    /// handled and it is waiting for a new one.
+    pub fn is_idle(&self) -> bool {
+        self.result.is_none() && self.state.is_drained()
+    }
+
+    /// Returns what this actor's future returned, if it stopped.
+    pub fn result(&self) -> Option<Result<(), ()>> {
+        self.result
+    }
+
+    fn deliver(&mut self) -> bool {
+        while let Ok(Some(env)) = self.recver.try_next() {
+            match env {
+                Envelope {
+                    msg: BastionMessage::Message(msg),
+                    sign,
+                } => {
+                    debug!("TestActor({}): Received a message: {:?}", self.ctx_id, msg);
+                    self.state.push_message(msg, sign);
+                    return true;
+                }
+                Envelope {
+                    msg: BastionMessage::Batch(messages),
+                    ..
+                } => {
+                    debug!(
+                        "TestActor({}): Received a batch of {} messages.",
+                        self.ctx_id,
+                        messages.len()
+                    );
+                    for message in messages {
+                        let sign = message.signature().clone();
+                        self.state.push_message(Msg::tell(message), sign);
+                    }
+                    return true;
+                }
+                env => trace!("TestActor({}): Ignoring: {:?}", self.ctx_id, env),
+            }
+        }
+
+        false
+    }
+}
+
+impl Debug for TestActor {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("TestActor")
+            .field("child", &self.child)
+            .field("result", &self.result)
+            .finish()
+    }
+}
+
+// Dispatchers wrap the messages they route, see
+// `DispatcherHandler::broadcast_message`.
+fn undispatched(msg: SignedMessage) -> SignedMessage {
+    let (msg, sign) = msg.extract();
+    let dispatched = match msg.downcast::<Arc<SignedMessage>>() {
+        Ok(dispatched) => dispatched,
+        Err(msg) => return SignedMessage::new(msg, sign),
+    };
+
+    match Arc::try_unwrap(dispatched) {
+        Ok(dispatched) => dispatched,
+        Err(dispatched) => match dispatched.msg.try_clone() {
+            Some(msg) => SignedMessage::new(msg, dispatched.sign.clone()),
+            None => SignedMessage::new(Msg::tell(dispatched), sign),
+        },
+    }
+}
+
+// Creates a public element of a group supervised by a supervisor
+// that the system doesn't know about.
+fn test_child_ref() -> (ChildRef, Receiver, BastionPath) {
+    let (sender, recver): (Sender, Receiver) = mpsc::unbounded();
+    let id = BastionId::new();
+    let parent_path = BastionPath::root()
+        .append(BastionPathElement::Supervisor(BastionId::new()))
+        .and_then(|path| path.append(BastionPathElement::Children(BastionId::new())))
+        .expect("Couldn't create the path of a test group.");
+    let path = parent_path
+        .clone()
+        .append(BastionPathElement::Child(id.clone()))
+        .expect("Couldn't create the path of a test element.");
+    let name = format!("test-{}", id);
+
+    let child = ChildRef::new(id, sender, name, Arc::new(path));
+    (child, recver, parent_path)
+}
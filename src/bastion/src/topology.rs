@@ -0,0 +1,275 @@
+//!
+//! A snapshot of the shape of the supervision tree, returned by
+//! [`Bastion::topology`].
+//!
+//! [`Bastion::topology`]: crate::Bastion::topology
+use crate::broadcast::Parent;
+use crate::context::{BastionId, NIL_ID};
+use crate::errors::SpecError;
+use crate::spec::{ChildrenSpec, SupervisorSpec, TreeSpec};
+use crate::supervisor::SupervisionStrategy;
+use fxhash::FxHashMap;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+pub(crate) static TOPOLOGY: Lazy<Topology> = Lazy::new(Topology::new);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A point-in-time view of the supervision tree, starting from the
+/// top-level supervisors.
+pub struct TopologySnapshot {
+    /// The top-level supervisors, in the order they were launched.
+    pub supervisors: Vec<SupervisorNode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A supervisor of the supervision tree.
+pub struct SupervisorNode {
+    /// The identifier of the supervisor.
+    pub id: String,
+    /// The strategy the supervisor uses when one of its supervised
+    /// entities dies.
+    #[serde(default)]
+    pub strategy: SupervisionStrategy,
+    /// The supervisors supervised by this supervisor, in the order
+    /// they were launched.
+    pub supervisors: Vec<SupervisorNode>,
+    /// The children groups supervised by this supervisor, in the
+    /// order they were launched.
+    pub children: Vec<ChildrenNode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A children group of the supervision tree.
+pub struct ChildrenNode {
+    /// The identifier of the children group.
+    pub id: String,
+    /// The name of the children group.
+    pub name: String,
+    /// The name the exec closure of the group was registered with,
+    /// if the group was built from a spec (see [`Bastion::from_spec`]).
+    ///
+    /// [`Bastion::from_spec`]: crate::Bastion::from_spec
+    #[serde(default)]
+    pub exec: Option<String>,
+    /// The amount of elements the group was configured with.
+    pub redundancy: usize,
+    /// The amount of elements of the group that are currently
+    /// launched.
+    pub live: usize,
+    /// The names of the dispatchers attached to the group.
+    pub dispatchers: Vec<String>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Topology {
+    nodes: Mutex<FxHashMap<BastionId, Node>>,
+    next_order: AtomicUsize,
+}
+
+#[derive(Debug)]
+struct Node {
+    // The order in which the node was first registered.
+    order: usize,
+    parent: Option<BastionId>,
+    kind: NodeKind,
+}
+
+#[derive(Debug)]
+enum NodeKind {
+    Supervisor(SupervisionStrategy),
+    Children(ChildrenNode),
+}
+
+impl Topology {
+    fn new() -> Self {
+        let nodes = Mutex::new(FxHashMap::default());
+        let next_order = AtomicUsize::new(0);
+
+        Topology { nodes, next_order }
+    }
+
+    pub(crate) fn register_supervisor(
+        &self,
+        id: &BastionId,
+        parent: &Parent,
+        strategy: &SupervisionStrategy,
+    ) {
+        self.upsert(id, parent, NodeKind::Supervisor(strategy.clone()));
+    }
+
+    pub(crate) fn register_children(&self, id: &BastionId, parent: &Parent, node: ChildrenNode) {
+        self.upsert(id, parent, NodeKind::Children(node));
+    }
+
+    pub(crate) fn unregister(&self, id: &BastionId) {
+        // FIXME: panics?
+        self.nodes.lock().unwrap().remove(id);
+    }
+
+    fn upsert(&self, id: &BastionId, parent: &Parent, kind: NodeKind) {
+        let parent = parent.clone().into_supervisor().map(|sv| sv.id().clone());
+
+        // FIXME: panics?
+        let mut nodes = self.nodes.lock().unwrap();
+        let order = match nodes.get(id) {
+            Some(node) => node.order,
+            None => self.next_order.fetch_add(1, Ordering::SeqCst),
+        };
+
+        nodes.insert(
+            id.clone(),
+            Node {
+                order,
+                parent,
+                kind,
+            },
+        );
+    }
+
+    pub(crate) fn snapshot(&self) -> TopologySnapshot {
+        // FIXME: panics?
+        let nodes = self.nodes.lock().unwrap();
+        let supervisors = Self::supervisors_of(&nodes, None);
+
+        TopologySnapshot { supervisors }
+    }
+
+    fn sorted_nodes_of<'a>(
+        nodes: &'a FxHashMap<BastionId, Node>,
+        parent: Option<&BastionId>,
+    ) -> Vec<(&'a BastionId, &'a Node)> {
+        let mut found = nodes
+            .iter()
+            .filter(|(_, node)| node.parent.as_ref() == parent)
+            .collect::<Vec<_>>();
+        found.sort_by_key(|(_, node)| node.order);
+
+        found
+    }
+
+    fn supervisors_of(
+        nodes: &FxHashMap<BastionId, Node>,
+        parent: Option<&BastionId>,
+    ) -> Vec<SupervisorNode> {
+        Self::sorted_nodes_of(nodes, parent)
+            .into_iter()
+            .filter_map(|(id, node)| match &node.kind {
+                NodeKind::Supervisor(strategy) => Some(SupervisorNode {
+                    id: id.to_string(),
+                    strategy: strategy.clone(),
+                    supervisors: Self::supervisors_of(nodes, Some(id)),
+                    children: Self::children_of(nodes, id),
+                }),
+                NodeKind::Children(_) => None,
+            })
+            .collect()
+    }
+
+    fn children_of(nodes: &FxHashMap<BastionId, Node>, parent: &BastionId) -> Vec<ChildrenNode> {
+        Self::sorted_nodes_of(nodes, Some(parent))
+            .into_iter()
+            .filter_map(|(_, node)| match &node.kind {
+                NodeKind::Children(children) => Some(children.clone()),
+                NodeKind::Supervisor(_) => None,
+            })
+            .collect()
+    }
+}
+
+impl TopologySnapshot {
+    /// Returns the supervisor with the given identifier, if it is
+    /// part of this snapshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the supervisor to look for.
+    pub fn supervisor(&self, id: &BastionId) -> Option<&SupervisorNode> {
+        let id = id.to_string();
+        let mut stack = self.supervisors.iter().collect::<Vec<_>>();
+        while let Some(node) = stack.pop() {
+            if node.id == id {
+                return Some(node);
+            }
+
+            stack.extend(node.supervisors.iter());
+        }
+
+        None
+    }
+
+    /// Returns the spec describing the top-level supervisors of this
+    /// snapshot, which can be built again with [`Bastion::from_spec`].
+    ///
+    /// The system supervisor, which is launched with the system, isn't
+    /// part of the spec.
+    ///
+    /// This method returns an error if a children group wasn't built
+    /// from a spec, because its exec closure can't be referenced then.
+    ///
+    /// [`Bastion::from_spec`]: crate::Bastion::from_spec
+    pub fn to_spec(&self) -> Result<TreeSpec, SpecError> {
+        let system = NIL_ID.to_string();
+        let supervisors = self
+            .supervisors
+            .iter()
+            .filter(|node| node.id != system)
+            .map(SupervisorNode::to_spec)
+            .collect::<Result<_, _>>()?;
+
+        Ok(TreeSpec { supervisors })
+    }
+}
+
+impl SupervisorNode {
+    /// Returns the spec describing this supervisor, which can be built
+    /// again with [`Bastion::from_spec`].
+    ///
+    /// This method returns an error if a children group wasn't built
+    /// from a spec, because its exec closure can't be referenced then.
+    ///
+    /// [`Bastion::from_spec`]: crate::Bastion::from_spec
+    pub fn to_spec(&self) -> Result<SupervisorSpec, SpecError> {
+        let supervisors = self
+            .supervisors
+            .iter()
+            .map(SupervisorNode::to_spec)
+            .collect::<Result<_, _>>()?;
+        let children = self
+            .children
+            .iter()
+            .map(ChildrenNode::to_spec)
+            .collect::<Result<_, _>>()?;
+
+        Ok(SupervisorSpec {
+            strategy: self.strategy.clone(),
+            supervisors,
+            children,
+        })
+    }
+}
+
+impl ChildrenNode {
+    /// Returns the spec describing this children group, which can be
+    /// built again with [`Bastion::from_spec`].
+    ///
+    /// This method returns an error if the group wasn't built from a
+    /// spec, because its exec closure can't be referenced then.
+    ///
+    /// [`Bastion::from_spec`]: crate::Bastion::from_spec
+    pub fn to_spec(&self) -> Result<ChildrenSpec, SpecError> {
+        let exec = self
+            .exec
+            .clone()
+            .ok_or_else(|| SpecError::UnregisteredExec(self.id.clone()))?;
+
+        Ok(ChildrenSpec {
+            name: Some(self.name.clone()),
+            exec,
+            redundancy: self.redundancy,
+            dispatchers: self.dispatchers.clone(),
+        })
+    }
+}
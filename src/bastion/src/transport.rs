@@ -0,0 +1,1144 @@
+//!
+//! Transports carrying the payloads and membership events of a
+//! cluster, including an in-memory one which lets clusters of
+//! in-process nodes be tested without any socket (with the `testkit`
+//! feature).
+use artillery_core::epidemic::prelude::*;
+use std::fmt::Debug;
+use std::net::SocketAddr;
+#[cfg(any(test, feature = "testkit"))]
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+#[cfg(any(test, feature = "testkit"))]
+use std::time::Instant;
+#[cfg(any(test, feature = "testkit"))]
+use tracing::{debug, trace};
+use uuid::Uuid;
+
+/// A membership snapshot and the event which produced it, as
+/// received from a cluster.
+pub type ClusterEvent = (Vec<ArtilleryMember>, ArtilleryMemberEvent);
+
+/// What carries the payloads and membership events between the
+/// members of a cluster, set with [`ClusterConfig::from_transport`].
+///
+/// [`ClusterConfig::from_transport`]: crate::distributed::ClusterConfig::from_transport
+pub trait ClusterTransport: Debug + Send + Sync {
+    /// Sends a payload to the given member.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The node id of the member to send the payload to.
+    /// * `payload` - The payload to send.
+    fn send_payload(&self, to: Uuid, payload: String);
+
+    /// Returns the events received since the last call, without
+    /// waiting for any.
+    fn try_recv_events(&self) -> Vec<ClusterEvent>;
+
+    /// Applies the gossip settings of the member, which transports
+    /// that don't gossip can ignore.
+    ///
+    /// # Arguments
+    ///
+    /// * `gossip` - The gossip settings set on the member's
+    ///     [`ClusterConfig`].
+    ///
+    /// [`ClusterConfig`]: crate::distributed::ClusterConfig
+    fn configure_gossip(&self, gossip: GossipConfig) {
+        let _ = gossip;
+    }
+
+    /// Returns the size of the largest payload the transport can
+    /// carry, in bytes, if it is limited.
+    ///
+    /// Larger payloads are rejected by [`DistributedContext::tell`]
+    /// instead of being handed to the transport.
+    ///
+    /// [`DistributedContext::tell`]: crate::distributed::DistributedContext::tell
+    fn max_payload_size(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns whether the transport can carry a payload to the given
+    /// member right away, instead of it backing up or being dropped.
+    ///
+    /// When a member was configured with
+    /// [`ClusterConfig::with_outbound_queue`], the payloads sent to a
+    /// member the transport can't carry anything to are queued until
+    /// it can. Transports which don't know can keep the default.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The node id of the member to send a payload to.
+    ///
+    /// [`ClusterConfig::with_outbound_queue`]: crate::distributed::ClusterConfig::with_outbound_queue
+    fn can_send(&self, to: Uuid) -> bool {
+        let _ = to;
+        true
+    }
+
+    /// Checks the link to the given member again, which happens for
+    /// every reconnect attempt of a member whose link dropped (see
+    /// [`ClusterConfig::with_reconnect`]).
+    ///
+    /// If the link was restored, the transport reports the member as
+    /// alive again through [`try_recv_events`]. Transports whose
+    /// failure detector keeps probing the members whose link dropped
+    /// on its own, like the underlying cluster's, can keep the
+    /// default.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The node id of the member to probe.
+    ///
+    /// [`ClusterConfig::with_reconnect`]: crate::distributed::ClusterConfig::with_reconnect
+    /// [`try_recv_events`]: ClusterTransport::try_recv_events
+    fn probe(&self, to: Uuid) {
+        let _ = to;
+    }
+}
+
+/// The size of the largest payload a UDP datagram can carry over
+/// IPv4, in bytes, which limits the payloads sent by a [`Cluster`].
+pub const MAX_UDP_PAYLOAD_SIZE: usize = 65_507;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How the members of a cluster gossip about its membership (see
+/// [`ClusterConfig::with_gossip_fan_out`]).
+///
+/// [`ClusterConfig::with_gossip_fan_out`]: crate::distributed::ClusterConfig::with_gossip_fan_out
+pub struct GossipConfig {
+    /// The amount of peers contacted every gossip round.
+    pub fan_out: usize,
+    /// The time between two gossip rounds.
+    pub interval: Duration,
+    /// The time after which an unresponsive peer is suspected.
+    pub suspicion_timeout: Duration,
+}
+
+impl Default for GossipConfig {
+    /// The defaults of the underlying cluster: 3 peers every second,
+    /// suspected after 3 seconds.
+    fn default() -> Self {
+        GossipConfig {
+            fan_out: 3,
+            interval: Duration::from_secs(1),
+            suspicion_timeout: Duration::from_secs(3),
+        }
+    }
+}
+
+impl ClusterTransport for Cluster {
+    fn send_payload(&self, to: Uuid, payload: String) {
+        Cluster::send_payload(self, to, payload);
+    }
+
+    fn try_recv_events(&self) -> Vec<ClusterEvent> {
+        self.events.try_iter().collect()
+    }
+
+    fn max_payload_size(&self) -> Option<usize> {
+        Some(MAX_UDP_PAYLOAD_SIZE)
+    }
+}
+
+#[cfg(any(test, feature = "testkit"))]
+#[derive(Debug, Clone)]
+/// An in-memory network connecting [`MockTransport`]s, passing the
+/// payloads between them through channels and optionally losing,
+/// duplicating, delaying or reordering them.
+///
+/// The faults are drawn from a seeded pseudo-random generator, so
+/// that a given seed always leads to the same faults for the same
+/// sequence of payloads.
+///
+/// By default, every node is notified of the others as soon as they
+/// join. With [`with_gossip`], nodes only learn about each other by
+/// gossiping, one round at a time (see [`gossip_round`]).
+///
+/// [`with_gossip`]: MockNetwork::with_gossip
+/// [`gossip_round`]: MockNetwork::gossip_round
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// use bastion::transport::MockNetwork;
+/// # use std::time::Duration;
+/// #
+/// let network = MockNetwork::with_seed(42)
+///     .with_loss(0.1)
+///     .with_delay(Duration::from_millis(5), Duration::from_millis(50))
+///     .with_reordering(true);
+///
+/// let first = network.join();
+/// let second = network.join();
+/// first.send_payload(second.node_id(), "hello".to_string());
+/// ```
+pub struct MockNetwork {
+    inner: Arc<Mutex<Network>>,
+}
+
+#[cfg(any(test, feature = "testkit"))]
+#[derive(Debug, Clone)]
+/// A node of a [`MockNetwork`].
+pub struct MockTransport {
+    node_id: Uuid,
+    inner: Arc<Mutex<Network>>,
+}
+
+#[cfg(any(test, feature = "testkit"))]
+#[derive(Debug)]
+struct Network {
+    loss: f64,
+    duplication: f64,
+    min_delay: Duration,
+    max_delay: Duration,
+    reordering: bool,
+    gossip: bool,
+    max_payload_size: Option<usize>,
+    rng: XorShift,
+    nodes: Vec<Node>,
+    sent: u64,
+}
+
+#[cfg(any(test, feature = "testkit"))]
+#[derive(Debug)]
+struct Node {
+    addr: SocketAddr,
+    member: ArtilleryMember,
+    // The side of the partition the node is on, nodes only being
+    // able to reach the ones on the same side.
+    side: usize,
+    inbox: Vec<InFlight>,
+    // The node ids of the nodes this node knows about, including
+    // itself, when the nodes gossip.
+    known: Vec<Uuid>,
+    // The amount of peers this node contacts every gossip round.
+    fan_out: usize,
+    // The amount of payloads which can wait to be received by this
+    // node, if it is throttled.
+    capacity: Option<usize>,
+}
+
+#[cfg(any(test, feature = "testkit"))]
+#[derive(Debug)]
+struct InFlight {
+    deliver_at: Instant,
+    seq: u64,
+    event: ClusterEvent,
+}
+
+#[cfg(any(test, feature = "testkit"))]
+#[derive(Debug)]
+struct XorShift(u64);
+
+#[cfg(any(test, feature = "testkit"))]
+impl MockNetwork {
+    /// Creates a network which delivers every payload right away
+    /// and in order.
+    pub fn new() -> Self {
+        MockNetwork::with_seed(0x5EED)
+    }
+
+    /// Creates a network whose faults are drawn using the given
+    /// seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed of the pseudo-random generator.
+    pub fn with_seed(seed: u64) -> Self {
+        let network = Network {
+            loss: 0.0,
+            duplication: 0.0,
+            min_delay: Duration::from_secs(0),
+            max_delay: Duration::from_secs(0),
+            reordering: false,
+            gossip: false,
+            max_payload_size: None,
+            rng: XorShift::new(seed),
+            nodes: Vec::new(),
+            sent: 0,
+        };
+
+        MockNetwork {
+            inner: Arc::new(Mutex::new(network)),
+        }
+    }
+
+    /// Sets the probability for every payload to be lost.
+    ///
+    /// # Arguments
+    ///
+    /// * `loss` - The probability, between `0` and `1`.
+    pub fn with_loss(self, loss: f64) -> Self {
+        self.network().loss = loss.max(0.0).min(1.0);
+        self
+    }
+
+    /// Sets the probability for every payload to be delivered twice.
+    ///
+    /// # Arguments
+    ///
+    /// * `duplication` - The probability, between `0` and `1`.
+    pub fn with_duplication(self, duplication: f64) -> Self {
+        self.network().duplication = duplication.max(0.0).min(1.0);
+        self
+    }
+
+    /// Delays every payload by a duration drawn between the given
+    /// bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_delay` - The minimum delay of a payload.
+    /// * `max_delay` - The maximum delay of a payload.
+    pub fn with_delay(self, min_delay: Duration, max_delay: Duration) -> Self {
+        {
+            let mut network = self.network();
+            network.min_delay = min_delay;
+            network.max_delay = max_delay.max(min_delay);
+        }
+        self
+    }
+
+    /// Sets whether the payloads received by a node at the same time
+    /// are handed to it in a random order instead of the order they
+    /// were sent in.
+    ///
+    /// # Arguments
+    ///
+    /// * `reordering` - Whether the payloads are reordered.
+    pub fn with_reordering(self, reordering: bool) -> Self {
+        self.network().reordering = reordering;
+        self
+    }
+
+    /// Sets whether the nodes only learn about each other by
+    /// gossiping instead of being notified of every node joining.
+    ///
+    /// A node joining then only knows about the node which joined
+    /// right before it (which learns about it in return), and the
+    /// rest of the membership spreads with every [`gossip_round`].
+    ///
+    /// # Arguments
+    ///
+    /// * `gossip` - Whether the nodes gossip.
+    ///
+    /// [`gossip_round`]: MockNetwork::gossip_round
+    pub fn with_gossip(self, gossip: bool) -> Self {
+        self.network().gossip = gossip;
+        self
+    }
+
+    /// Limits the size of the payloads the nodes can send, like a
+    /// datagram-based transport would (see [`MAX_UDP_PAYLOAD_SIZE`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `max_payload_size` - The size of the largest payload, in
+    ///     bytes.
+    pub fn with_max_payload_size(self, max_payload_size: usize) -> Self {
+        self.network().max_payload_size = Some(max_payload_size);
+        self
+    }
+
+    /// Adds a new node to the network, notifying every node of it
+    /// (or only the node it joins through, if the nodes gossip).
+    pub fn join(&self) -> MockTransport {
+        self.join_with_id(Uuid::new_v4())
+    }
+
+    /// Adds a new node with the given node id to the network, like
+    /// [`join`] does, so that the ids the other nodes see it with are
+    /// the same across runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id` - The node id of the new node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a node with the same node id already joined the
+    /// network.
+    ///
+    /// [`join`]: MockNetwork::join
+    pub fn join_with_id(&self, node_id: Uuid) -> MockTransport {
+        let mut network = self.network();
+        assert!(
+            network.index_of(node_id).is_none(),
+            "Node({}) already joined the network.",
+            node_id
+        );
+
+        // Nodes only have a fake address, which is never bound.
+        let port = network.nodes.len() as u16 + 1;
+        let addr = SocketAddr::from(([127, 0, 0, 1], port));
+        let member = ArtilleryMember::new(node_id, addr, 0, ArtilleryMemberState::Alive);
+        debug!("MockNetwork: Node({}) joined.", node_id);
+
+        let seed = network.nodes.len().checked_sub(1);
+        let mut known = vec![node_id];
+        if let Some(seed) = seed {
+            known.push(network.nodes[seed].member.host_key());
+        }
+
+        network.nodes.push(Node {
+            addr,
+            member: member.clone(),
+            side: 0,
+            inbox: Vec::new(),
+            known,
+            fan_out: GossipConfig::default().fan_out,
+            capacity: None,
+        });
+
+        if !network.gossip {
+            network.notify(member, ArtilleryMemberEvent::Joined);
+        } else if let Some(seed) = seed {
+            let joined = network.nodes.len() - 1;
+            network.nodes[seed].known.push(node_id);
+            network.learn(seed, joined);
+            network.learn(joined, seed);
+        }
+
+        MockTransport {
+            node_id,
+            inner: self.inner.clone(),
+        }
+    }
+
+    /// Cuts the given node off the network: the payloads sent to or
+    /// by it are lost and every other node is notified it went down.
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id` - The node id of the node to disconnect.
+    pub fn disconnect(&self, node_id: Uuid) {
+        self.set_state(node_id, ArtilleryMemberState::Down);
+    }
+
+    /// Reconnects a node cut off by [`disconnect`], notifying every
+    /// other node it is up again.
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id` - The node id of the node to reconnect.
+    ///
+    /// [`disconnect`]: MockNetwork::disconnect
+    pub fn reconnect(&self, node_id: Uuid) {
+        self.set_state(node_id, ArtilleryMemberState::Alive);
+    }
+
+    /// Restores the link of a node cut off by [`disconnect`] without
+    /// notifying the other nodes, which only find out it is up again
+    /// once they probe it (see [`ClusterTransport::probe`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id` - The node id of the node to restore.
+    ///
+    /// [`disconnect`]: MockNetwork::disconnect
+    pub fn restore(&self, node_id: Uuid) {
+        if let Some(node) = self.network().node_mut(node_id) {
+            debug!("MockNetwork: Node({}) was silently restored.", node_id);
+            node.member = ArtilleryMember::new(node_id, node.addr, 0, ArtilleryMemberState::Alive);
+        }
+    }
+
+    /// Slows the given node down, like a peer which doesn't keep up
+    /// with its traffic: the payloads sent to it are dropped while
+    /// `capacity` of them are waiting to be received by it, and
+    /// [`ClusterTransport::can_send`] returns `false` for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id` - The node id of the node to throttle.
+    /// * `capacity` - The amount of payloads which can wait to be
+    ///     received by the node.
+    pub fn throttle(&self, node_id: Uuid, capacity: usize) {
+        if let Some(node) = self.network().node_mut(node_id) {
+            debug!(
+                "MockNetwork: Node({}) throttled to {} payloads.",
+                node_id, capacity
+            );
+            node.capacity = Some(capacity);
+        }
+    }
+
+    /// Partitions the network in two: the given nodes and the other
+    /// ones can only reach the nodes on their side, and every node
+    /// is notified that the ones on the other side went down.
+    ///
+    /// # Arguments
+    ///
+    /// * `side` - The node ids of the nodes on one side of the
+    ///     partition.
+    pub fn partition(&self, side: &[Uuid]) {
+        let mut network = self.network();
+        for node in network.nodes.iter_mut() {
+            node.side = if side.contains(&node.member.host_key()) {
+                1
+            } else {
+                0
+            };
+        }
+
+        debug!("MockNetwork: Partitioned {:?} off the network.", side);
+        network.notify_sides(ArtilleryMemberEvent::WentDown);
+    }
+
+    /// Heals a partition created by [`partition`], notifying every
+    /// node that the ones on the other side are up again.
+    ///
+    /// [`partition`]: MockNetwork::partition
+    pub fn heal(&self) {
+        let mut network = self.network();
+        // The sides the nodes were on, to notify every node of the
+        // ones it couldn't reach.
+        let previous = network
+            .nodes
+            .iter()
+            .map(|node| node.side)
+            .collect::<Vec<_>>();
+        for node in network.nodes.iter_mut() {
+            node.side = 0;
+        }
+
+        debug!("MockNetwork: Healed the partition.");
+        let now = Instant::now();
+        for viewer in 0..network.nodes.len() {
+            let members = network.members_seen_by(viewer);
+            for other in 0..network.nodes.len() {
+                if previous[viewer] == previous[other] {
+                    continue;
+                }
+
+                let member = network.nodes[other].member.clone();
+                network.push(
+                    viewer,
+                    now,
+                    (members.clone(), ArtilleryMemberEvent::WentUp(member)),
+                );
+            }
+        }
+    }
+
+    /// Runs a gossip round, in which every node exchanges the node
+    /// ids it knows about with as many of the peers it knows about
+    /// as its gossip fan-out (see [`ClusterConfig::with_gossip_fan_out`]),
+    /// chosen at random.
+    ///
+    /// Rounds only run when this method is called, regardless of the
+    /// gossip interval of the nodes.
+    ///
+    /// [`ClusterConfig::with_gossip_fan_out`]: crate::distributed::ClusterConfig::with_gossip_fan_out
+    pub fn gossip_round(&self) {
+        let mut network = self.network();
+        // NOTE: the nodes exchange what they knew at the start of the
+        //      round, so that the order they gossip in doesn't matter.
+        let views = network
+            .nodes
+            .iter()
+            .map(|node| node.known.clone())
+            .collect::<Vec<_>>();
+        let mut learned = vec![Vec::new(); views.len()];
+
+        for from in 0..views.len() {
+            if !network.is_reachable(from) {
+                continue;
+            }
+
+            let mut peers = views[from]
+                .iter()
+                .filter_map(|id| network.index_of(*id))
+                .filter(|to| *to != from && network.nodes[*to].side == network.nodes[from].side)
+                .filter(|to| network.is_reachable(*to))
+                .collect::<Vec<_>>();
+            network.rng.shuffle(&mut peers);
+            peers.truncate(network.nodes[from].fan_out);
+
+            for to in peers {
+                learned[to].extend(views[from].iter().copied());
+                learned[from].extend(views[to].iter().copied());
+            }
+        }
+
+        for (viewer, learned) in learned.into_iter().enumerate() {
+            for id in learned {
+                if network.nodes[viewer].known.contains(&id) {
+                    continue;
+                }
+
+                network.nodes[viewer].known.push(id);
+                if let Some(other) = network.index_of(id) {
+                    network.learn(viewer, other);
+                }
+            }
+        }
+    }
+
+    /// Returns whether every node connected to the network knows
+    /// about every other one.
+    pub fn is_converged(&self) -> bool {
+        let network = self.network();
+        let connected = network
+            .nodes
+            .iter()
+            .filter(|node| node.member.state() == ArtilleryMemberState::Alive)
+            .map(|node| node.member.host_key())
+            .collect::<Vec<_>>();
+
+        network
+            .nodes
+            .iter()
+            .filter(|node| node.member.state() == ArtilleryMemberState::Alive)
+            .all(|node| connected.iter().all(|id| node.known.contains(id)))
+    }
+
+    fn set_state(&self, node_id: Uuid, state: ArtilleryMemberState) {
+        let mut network = self.network();
+        let member = match network.node_mut(node_id) {
+            Some(node) => {
+                node.member = ArtilleryMember::new(node_id, node.addr, 0, state);
+                node.member.clone()
+            }
+            None => return,
+        };
+
+        debug!("MockNetwork: Node({}) is now {:?}.", node_id, state);
+        match state {
+            ArtilleryMemberState::Alive => network.notify(member, ArtilleryMemberEvent::WentUp),
+            _ => network.notify(member, ArtilleryMemberEvent::WentDown),
+        }
+    }
+
+    fn network(&self) -> std::sync::MutexGuard<Network> {
+        // FIXME: panics?
+        self.inner.lock().unwrap()
+    }
+}
+
+#[cfg(any(test, feature = "testkit"))]
+impl Default for MockNetwork {
+    fn default() -> Self {
+        MockNetwork::new()
+    }
+}
+
+#[cfg(any(test, feature = "testkit"))]
+impl MockTransport {
+    /// Returns the node id of this node.
+    pub fn node_id(&self) -> Uuid {
+        self.node_id
+    }
+
+    fn events_at(&self, now: Instant) -> Vec<ClusterEvent> {
+        // FIXME: panics?
+        let mut network = self.inner.lock().unwrap();
+        let reordering = network.reordering;
+        let Network { nodes, rng, .. } = &mut *network;
+
+        let node = match nodes
+            .iter_mut()
+            .find(|node| node.member.host_key() == self.node_id)
+        {
+            Some(node) => node,
+            None => return Vec::new(),
+        };
+
+        let (mut due, pending) = node
+            .inbox
+            .drain(..)
+            .partition::<Vec<_>, _>(|in_flight| in_flight.deliver_at <= now);
+        node.inbox = pending;
+
+        if reordering {
+            rng.shuffle(&mut due);
+        } else {
+            due.sort_by_key(|in_flight| (in_flight.deliver_at, in_flight.seq));
+        }
+
+        due.into_iter().map(|in_flight| in_flight.event).collect()
+    }
+}
+
+#[cfg(any(test, feature = "testkit"))]
+impl ClusterTransport for MockTransport {
+    fn send_payload(&self, to: Uuid, payload: String) {
+        // FIXME: panics?
+        let mut network = self.inner.lock().unwrap();
+        let (sender, side) = match network.node_mut(self.node_id) {
+            Some(node) if node.member.state() == ArtilleryMemberState::Alive => {
+                (node.member.clone(), node.side)
+            }
+            _ => {
+                trace!("MockNetwork: Node({}) is disconnected.", self.node_id);
+                return;
+            }
+        };
+
+        if let Some(max) = network.max_payload_size.filter(|max| payload.len() > *max) {
+            trace!(
+                "MockNetwork: Dropping a {} bytes payload for Node({}), above {} bytes.",
+                payload.len(),
+                to,
+                max
+            );
+            return;
+        }
+
+        if !network.accepts(to) {
+            trace!(
+                "MockNetwork: Node({}) is backed up, dropping a payload.",
+                to
+            );
+            return;
+        }
+
+        if network.loss > 0.0 && network.rng.next_f64() < network.loss {
+            trace!("MockNetwork: Losing a payload for Node({}).", to);
+            return;
+        }
+
+        if network.duplication > 0.0 && network.rng.next_f64() < network.duplication {
+            trace!("MockNetwork: Duplicating a payload for Node({}).", to);
+            let event = ArtilleryMemberEvent::Payload(sender.clone(), payload.clone());
+            network.deliver(to, side, event);
+        }
+
+        let event = ArtilleryMemberEvent::Payload(sender, payload);
+        network.deliver(to, side, event);
+    }
+
+    fn try_recv_events(&self) -> Vec<ClusterEvent> {
+        self.events_at(Instant::now())
+    }
+
+    fn max_payload_size(&self) -> Option<usize> {
+        // FIXME: panics?
+        self.inner.lock().unwrap().max_payload_size
+    }
+
+    fn can_send(&self, to: Uuid) -> bool {
+        // FIXME: panics?
+        self.inner.lock().unwrap().accepts(to)
+    }
+
+    fn configure_gossip(&self, gossip: GossipConfig) {
+        // FIXME: panics?
+        let mut network = self.inner.lock().unwrap();
+        if let Some(node) = network.node_mut(self.node_id) {
+            trace!(
+                "MockNetwork: Node({}) gossips with {} peers.",
+                self.node_id,
+                gossip.fan_out
+            );
+            node.fan_out = gossip.fan_out;
+        }
+    }
+
+    fn probe(&self, to: Uuid) {
+        // FIXME: panics?
+        let mut network = self.inner.lock().unwrap();
+        let (from, other) = match (network.index_of(self.node_id), network.index_of(to)) {
+            (Some(from), Some(other)) => (from, other),
+            _ => return,
+        };
+
+        if !network.is_reachable(from)
+            || !network.is_reachable(other)
+            || network.nodes[from].side != network.nodes[other].side
+        {
+            trace!(
+                "MockNetwork: Node({}) couldn't reach Node({}).",
+                self.node_id,
+                to
+            );
+            return;
+        }
+
+        trace!("MockNetwork: Node({}) reached Node({}).", self.node_id, to);
+        let members = network.members_seen_by(from);
+        let member = network.nodes[other].member.clone();
+        network.push(
+            from,
+            Instant::now(),
+            (members, ArtilleryMemberEvent::WentUp(member)),
+        );
+    }
+}
+
+#[cfg(any(test, feature = "testkit"))]
+impl Network {
+    fn node_mut(&mut self, node_id: Uuid) -> Option<&mut Node> {
+        self.nodes
+            .iter_mut()
+            .find(|node| node.member.host_key() == node_id)
+    }
+
+    fn index_of(&self, node_id: Uuid) -> Option<usize> {
+        self.nodes
+            .iter()
+            .position(|node| node.member.host_key() == node_id)
+    }
+
+    // Whether the node can be sent a payload without exceeding its
+    // capacity, if it is throttled.
+    fn accepts(&self, node_id: Uuid) -> bool {
+        let node = match self
+            .nodes
+            .iter()
+            .find(|node| node.member.host_key() == node_id)
+        {
+            Some(node) => node,
+            None => return true,
+        };
+
+        node.capacity.map_or(true, |capacity| {
+            let waiting = node
+                .inbox
+                .iter()
+                .filter(|in_flight| matches!(in_flight.event.1, ArtilleryMemberEvent::Payload(..)))
+                .count();
+            waiting < capacity
+        })
+    }
+
+    fn is_reachable(&self, index: usize) -> bool {
+        self.nodes[index].member.state() == ArtilleryMemberState::Alive
+    }
+
+    // The members as seen by the node at the given index, which sees
+    // the nodes on the other side of a partition as down (and only
+    // sees the nodes it knows about, if the nodes gossip).
+    fn members_seen_by(&self, viewer: usize) -> Vec<ArtilleryMember> {
+        let side = self.nodes[viewer].side;
+        let known = &self.nodes[viewer].known;
+        self.nodes
+            .iter()
+            .filter(|node| !self.gossip || known.contains(&node.member.host_key()))
+            .map(|node| {
+                if node.side == side {
+                    node.member.clone()
+                } else {
+                    let key = node.member.host_key();
+                    ArtilleryMember::new(key, node.addr, 0, ArtilleryMemberState::Down)
+                }
+            })
+            .collect()
+    }
+
+    fn push(&mut self, to: usize, deliver_at: Instant, event: ClusterEvent) {
+        self.sent += 1;
+        let seq = self.sent;
+        self.nodes[to].inbox.push(InFlight {
+            deliver_at,
+            seq,
+            event,
+        });
+    }
+
+    // Membership events are never lost nor delayed.
+    fn notify(
+        &mut self,
+        member: ArtilleryMember,
+        event: fn(ArtilleryMember) -> ArtilleryMemberEvent,
+    ) {
+        let now = Instant::now();
+
+        for viewer in 0..self.nodes.len() {
+            let members = self.members_seen_by(viewer);
+            self.push(viewer, now, (members, event(member.clone())));
+        }
+    }
+
+    // Notifies the node at the given index that it learned about the
+    // other one by gossiping.
+    fn learn(&mut self, viewer: usize, other: usize) {
+        let members = self.members_seen_by(viewer);
+        let member = self.nodes[other].member.clone();
+        self.push(
+            viewer,
+            Instant::now(),
+            (members, ArtilleryMemberEvent::Joined(member)),
+        );
+    }
+
+    // Notifies every node of each node on the other side of the
+    // partition.
+    fn notify_sides(&mut self, event: fn(ArtilleryMember) -> ArtilleryMemberEvent) {
+        let now = Instant::now();
+
+        for viewer in 0..self.nodes.len() {
+            let members = self.members_seen_by(viewer);
+            for other in 0..self.nodes.len() {
+                if self.nodes[viewer].side == self.nodes[other].side {
+                    continue;
+                }
+
+                let key = self.nodes[other].member.host_key();
+                let member = match members.iter().find(|member| member.host_key() == key) {
+                    Some(member) => member.clone(),
+                    // The viewer doesn't know about the node yet.
+                    None => continue,
+                };
+                self.push(viewer, now, (members.clone(), event(member)));
+            }
+        }
+    }
+
+    fn deliver(&mut self, to: Uuid, side: usize, event: ArtilleryMemberEvent) {
+        let delay = self.min_delay + (self.max_delay - self.min_delay).mul_f64(self.rng.next_f64());
+        let deliver_at = Instant::now() + delay;
+
+        let index = self.nodes.iter().position(|node| {
+            node.member.host_key() == to
+                && node.member.state() == ArtilleryMemberState::Alive
+                && node.side == side
+        });
+        match index {
+            Some(index) => {
+                let members = self.members_seen_by(index);
+                self.push(index, deliver_at, (members, event));
+            }
+            None => trace!("MockNetwork: Node({}) is unreachable.", to),
+        }
+    }
+}
+
+#[cfg(any(test, feature = "testkit"))]
+impl XorShift {
+    fn new(seed: u64) -> Self {
+        // The state must never be zero.
+        XorShift(seed.max(1))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    // Uniformly distributed in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = (self.next_u64() % (i as u64 + 1)) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payloads(events: Vec<ClusterEvent>) -> Vec<String> {
+        events
+            .into_iter()
+            .filter_map(|(_, event)| match event {
+                ArtilleryMemberEvent::Payload(_, payload) => Some(payload),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn send(from: &MockTransport, to: &MockTransport, count: usize) {
+        for i in 0..count {
+            from.send_payload(to.node_id(), i.to_string());
+        }
+    }
+
+    #[test]
+    fn nodes_are_notified_of_joins() {
+        let network = MockNetwork::new();
+        let first = network.join();
+        let second = network.join();
+
+        let events = first.try_recv_events();
+        assert_eq!(events.len(), 2);
+        let (members, _) = events.last().unwrap();
+        let ids = members.iter().map(|m| m.host_key()).collect::<Vec<_>>();
+        assert_eq!(ids, vec![first.node_id(), second.node_id()]);
+
+        assert_eq!(second.try_recv_events().len(), 1);
+    }
+
+    #[test]
+    fn payloads_are_delivered_in_order() {
+        let network = MockNetwork::new();
+        let (first, second) = (network.join(), network.join());
+
+        send(&first, &second, 10);
+        let expected = (0..10).map(|i| i.to_string()).collect::<Vec<_>>();
+        assert_eq!(payloads(second.try_recv_events()), expected);
+        assert!(payloads(first.try_recv_events()).is_empty());
+    }
+
+    #[test]
+    fn payloads_can_be_lost() {
+        let network = MockNetwork::with_seed(7).with_loss(0.5);
+        let (first, second) = (network.join(), network.join());
+
+        send(&first, &second, 1000);
+        let received = payloads(second.try_recv_events()).len();
+        assert!(received > 400 && received < 600, "received={}", received);
+
+        let network = MockNetwork::new().with_loss(1.0);
+        let (first, second) = (network.join(), network.join());
+        send(&first, &second, 10);
+        assert!(payloads(second.try_recv_events()).is_empty());
+    }
+
+    #[test]
+    fn payloads_can_be_duplicated() {
+        let network = MockNetwork::new().with_duplication(1.0);
+        let (first, second) = (network.join(), network.join());
+
+        send(&first, &second, 3);
+        let expected = vec!["0", "0", "1", "1", "2", "2"];
+        assert_eq!(payloads(second.try_recv_events()), expected);
+    }
+
+    #[test]
+    fn payloads_can_be_delayed() {
+        let delay = Duration::from_secs(60);
+        let network = MockNetwork::new().with_delay(delay, delay);
+        let (first, second) = (network.join(), network.join());
+
+        send(&first, &second, 3);
+        assert!(payloads(second.events_at(Instant::now())).is_empty());
+        let later = Instant::now() + delay;
+        assert_eq!(payloads(second.events_at(later)).len(), 3);
+    }
+
+    #[test]
+    fn payloads_can_be_reordered() {
+        let network = MockNetwork::with_seed(3).with_reordering(true);
+        let (first, second) = (network.join(), network.join());
+
+        send(&first, &second, 10);
+        let received = payloads(second.try_recv_events());
+        let mut sorted = received.clone();
+        sorted.sort_by_key(|payload| payload.parse::<usize>().unwrap());
+
+        assert_ne!(received, sorted);
+        assert_eq!(sorted, (0..10).map(|i| i.to_string()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn oversized_payloads_are_dropped() {
+        let network = MockNetwork::new().with_max_payload_size(4);
+        let (first, second) = (network.join(), network.join());
+        assert_eq!(first.max_payload_size(), Some(4));
+
+        first.send_payload(second.node_id(), "small".to_string());
+        first.send_payload(second.node_id(), "tiny".to_string());
+        assert_eq!(payloads(second.try_recv_events()), vec!["tiny"]);
+    }
+
+    #[test]
+    fn throttled_nodes_drop_payloads_once_backed_up() {
+        let network = MockNetwork::new();
+        let (first, second) = (network.join(), network.join());
+        network.throttle(second.node_id(), 2);
+
+        assert!(first.can_send(second.node_id()));
+        send(&first, &second, 3);
+        assert!(!first.can_send(second.node_id()));
+        assert!(second.can_send(first.node_id()));
+
+        assert_eq!(payloads(second.try_recv_events()), vec!["0", "1"]);
+        assert!(first.can_send(second.node_id()));
+    }
+
+    #[test]
+    fn disconnected_nodes_are_unreachable() {
+        let network = MockNetwork::new();
+        let (first, second) = (network.join(), network.join());
+        first.try_recv_events();
+
+        network.disconnect(second.node_id());
+        let events = first.try_recv_events();
+        let (members, _) = events.last().unwrap();
+        assert!(members
+            .iter()
+            .any(|m| m.host_key() == second.node_id() && m.state() == ArtilleryMemberState::Down));
+
+        send(&first, &second, 3);
+        send(&second, &first, 3);
+        assert!(payloads(second.try_recv_events()).is_empty());
+        assert!(payloads(first.try_recv_events()).is_empty());
+
+        network.reconnect(second.node_id());
+        send(&first, &second, 3);
+        assert_eq!(payloads(second.try_recv_events()).len(), 3);
+    }
+
+    #[test]
+    fn partitioned_nodes_only_reach_their_side() {
+        let network = MockNetwork::new();
+        let (first, second, third) = (network.join(), network.join(), network.join());
+        first.try_recv_events();
+        second.try_recv_events();
+
+        network.partition(&[first.node_id()]);
+        let events = first.try_recv_events();
+        assert_eq!(events.len(), 2);
+        let (members, _) = events.last().unwrap();
+        let down = members
+            .iter()
+            .filter(|m| m.state() == ArtilleryMemberState::Down)
+            .map(|m| m.host_key())
+            .collect::<Vec<_>>();
+        assert_eq!(down, vec![second.node_id(), third.node_id()]);
+        assert_eq!(second.try_recv_events().len(), 1);
+
+        send(&first, &second, 3);
+        send(&second, &first, 3);
+        send(&third, &second, 3);
+        assert!(payloads(first.try_recv_events()).is_empty());
+        assert_eq!(payloads(second.try_recv_events()).len(), 3);
+
+        network.heal();
+        let events = first.try_recv_events();
+        let (members, _) = events.last().unwrap();
+        assert!(members
+            .iter()
+            .all(|m| m.state() == ArtilleryMemberState::Alive));
+
+        send(&second, &first, 3);
+        assert_eq!(payloads(first.try_recv_events()).len(), 3);
+    }
+
+    #[test]
+    fn gossiping_nodes_learn_about_each_other_in_rounds() {
+        let network = MockNetwork::new().with_gossip(true);
+        let first = network.join();
+        let second = network.join();
+        let third = network.join();
+
+        // The first node only knows about the one which joined
+        // through it...
+        let events = first.try_recv_events();
+        assert_eq!(events.len(), 1);
+        let (members, _) = events.last().unwrap();
+        let ids = members.iter().map(|m| m.host_key()).collect::<Vec<_>>();
+        assert_eq!(ids, vec![first.node_id(), second.node_id()]);
+        assert!(!network.is_converged());
+
+        // ...until it learns about the last one by gossiping.
+        network.gossip_round();
+        assert!(network.is_converged());
+        let events = first.try_recv_events();
+        let (members, _) = events.last().unwrap();
+        assert_eq!(members.len(), 3);
+        assert!(members.iter().any(|m| m.host_key() == third.node_id()));
+    }
+}
@@ -0,0 +1,221 @@
+//!
+//! The monitors an element of a children group sets on other elements
+//! with [`BastionContext::watch`], which send it a [`Down`] message
+//! once the element it watches terminates, and the links it sets with
+//! [`BastionContext::link`], which terminate it once the element it
+//! is linked to terminates abnormally.
+//!
+//! [`BastionContext::watch`]: crate::context::BastionContext::watch
+//! [`BastionContext::link`]: crate::context::BastionContext::link
+use crate::child_ref::ChildRef;
+use crate::context::BastionId;
+use crate::path::BastionPath;
+use crate::supervisor::FaultReason;
+use fxhash::{FxHashMap, FxHashSet};
+use once_cell::sync::Lazy;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, trace};
+
+pub(crate) static WATCHES: Lazy<Watches> = Lazy::new(Watches::default);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Why a watched element terminated (see [`Down`]).
+pub enum DownReason {
+    /// The element was stopped, or its future returned successfully.
+    Stopped,
+    /// The element was killed.
+    Killed,
+    /// The element faulted, either by returning an error or by
+    /// panicking.
+    Faulted(FaultReason),
+}
+
+#[derive(Debug, Clone)]
+/// The message an element watching another one with
+/// [`BastionContext::watch`] receives once the watched element
+/// terminated, and an element trapping exits receives once an element
+/// it is linked to with [`BastionContext::link`] terminated
+/// abnormally.
+///
+/// The watch (or link) is removed once this message is sent: if the
+/// element is restarted, it has to be watched (or linked) again.
+///
+/// [`BastionContext::watch`]: crate::context::BastionContext::watch
+/// [`BastionContext::link`]: crate::context::BastionContext::link
+pub struct Down {
+    /// The path of the element which terminated.
+    pub path: Arc<BastionPath>,
+    /// Why the element terminated.
+    pub reason: DownReason,
+}
+
+impl DownReason {
+    /// Returns whether the element terminated abnormally, that is
+    /// whether it was killed or faulted.
+    pub fn is_abnormal(&self) -> bool {
+        *self != DownReason::Stopped
+    }
+}
+
+type Monitored = FxHashMap<BastionId, Vec<ChildRef>>;
+
+#[derive(Debug, Default)]
+pub(crate) struct Watches {
+    monitors: Mutex<Monitors>,
+}
+
+#[derive(Debug, Default)]
+struct Monitors {
+    // The elements watching every watched element.
+    watchers: Monitored,
+    // The elements every linked element is linked to.
+    links: Monitored,
+    // The elements receiving a `Down` message instead of terminating
+    // when an element they are linked to terminates abnormally.
+    trapping: FxHashSet<BastionId>,
+}
+
+impl Watches {
+    /// Makes the `watcher` element watch the one with the `target`
+    /// identifier, unless it already does.
+    pub(crate) fn watch(&self, target: &BastionId, watcher: &ChildRef) {
+        // FIXME: panics?
+        let mut monitors = self.monitors.lock().unwrap();
+        Self::add(&mut monitors.watchers, target, watcher);
+    }
+
+    /// Makes the element with the `watcher` identifier stop watching
+    /// the one with the `target` identifier.
+    pub(crate) fn unwatch(&self, target: &BastionId, watcher: &BastionId) {
+        // FIXME: panics?
+        let mut monitors = self.monitors.lock().unwrap();
+        Self::remove(&mut monitors.watchers, target, watcher);
+    }
+
+    /// Links the `first` and `second` elements together, unless they
+    /// already are.
+    pub(crate) fn link(&self, first: &ChildRef, second: &ChildRef) {
+        // FIXME: panics?
+        let mut monitors = self.monitors.lock().unwrap();
+        Self::add(&mut monitors.links, first.id(), second);
+        Self::add(&mut monitors.links, second.id(), first);
+    }
+
+    /// Removes the link between the elements with the `first` and
+    /// `second` identifiers.
+    pub(crate) fn unlink(&self, first: &BastionId, second: &BastionId) {
+        // FIXME: panics?
+        let mut monitors = self.monitors.lock().unwrap();
+        Self::remove(&mut monitors.links, first, second);
+        Self::remove(&mut monitors.links, second, first);
+    }
+
+    /// Sets whether the element with the given identifier receives a
+    /// [`Down`] message instead of terminating when an element it is
+    /// linked to terminates abnormally.
+    pub(crate) fn trap_exit(&self, id: &BastionId, trap_exit: bool) {
+        // FIXME: panics?
+        let mut monitors = self.monitors.lock().unwrap();
+        if trap_exit {
+            monitors.trapping.insert(id.clone());
+        } else {
+            monitors.trapping.remove(id);
+        }
+    }
+
+    /// Sends a [`Down`] message to the elements watching the element
+    /// with the given identifier and path, which terminated, kills the
+    /// elements linked to it if it terminated abnormally (or sends
+    /// them a [`Down`] message if they trap exits), and removes its
+    /// watches and links.
+    pub(crate) fn down(&self, id: &BastionId, path: &Arc<BastionPath>, reason: DownReason) {
+        let (mut notified, killed) = {
+            // FIXME: panics?
+            let mut monitors = self.monitors.lock().unwrap();
+            let watchers = monitors.watchers.remove(id).unwrap_or_default();
+            let linked = monitors.links.remove(id).unwrap_or_default();
+            // NOTE: the watches and links die with the element which
+            //      set them.
+            Self::forget(&mut monitors.watchers, id);
+            Self::forget(&mut monitors.links, id);
+            monitors.trapping.remove(id);
+
+            let (trapping, killed): (Vec<_>, Vec<_>) = if reason.is_abnormal() {
+                linked
+                    .into_iter()
+                    .partition(|linked| monitors.trapping.contains(linked.id()))
+            } else {
+                (Vec::new(), Vec::new())
+            };
+
+            let mut notified = watchers;
+            for linked in trapping {
+                if !notified.iter().any(|watcher| watcher.id() == linked.id()) {
+                    notified.push(linked);
+                }
+            }
+
+            (notified, killed)
+        };
+
+        for linked in killed {
+            debug!(
+                "Watches: Killing Child({}) linked to Child({}).",
+                linked.id(),
+                id
+            );
+            if linked.kill().is_err() {
+                debug!(
+                    "Watches: Child({}) linked to Child({}) is dead.",
+                    linked.id(),
+                    id
+                );
+            }
+        }
+
+        for watcher in notified.drain(..) {
+            trace!(
+                "Watches: Sending Down({:?}) of Child({}) to Child({}).",
+                reason,
+                id,
+                watcher.id()
+            );
+            let down = Down {
+                path: path.clone(),
+                reason: reason.clone(),
+            };
+            if watcher.tell_anonymously(down).is_err() {
+                debug!(
+                    "Watches: Child({}) watching Child({}) is dead.",
+                    watcher.id(),
+                    id
+                );
+            }
+        }
+    }
+
+    fn add(monitored: &mut Monitored, id: &BastionId, monitor: &ChildRef) {
+        let monitors = monitored.entry(id.clone()).or_default();
+        if !monitors.iter().any(|other| other.id() == monitor.id()) {
+            monitors.push(monitor.clone());
+        }
+    }
+
+    fn remove(monitored: &mut Monitored, id: &BastionId, monitor: &BastionId) {
+        if let Some(monitors) = monitored.get_mut(id) {
+            monitors.retain(|other| other.id() != monitor);
+            if monitors.is_empty() {
+                monitored.remove(id);
+            }
+        }
+    }
+
+    // Removes the element with the given identifier from the monitors
+    // of every element.
+    fn forget(monitored: &mut Monitored, id: &BastionId) {
+        monitored.retain(|_, monitors| {
+            monitors.retain(|monitor| monitor.id() != id);
+            !monitors.is_empty()
+        });
+    }
+}
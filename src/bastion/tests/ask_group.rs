@@ -0,0 +1,143 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until_within;
+use futures::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_ask_group() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_ask_group() {
+        super::run()
+    }
+}
+
+const REDUNDANCY: usize = 3;
+
+// Creates a group whose elements answer with their identifier,
+// except for one of them if `with_silent` is set.
+fn group(name: &str, with_silent: bool) -> ChildrenRef {
+    let dispatcher = DispatcherType::Named(name.to_string());
+    let silent = Arc::new(AtomicBool::new(with_silent));
+
+    Bastion::children(move |children| {
+        children
+            .with_redundancy(REDUNDANCY)
+            .with_dispatcher(Dispatcher::with_type(dispatcher.clone()))
+            .with_exec(move |ctx: BastionContext| {
+                let silent = silent.swap(false, Ordering::SeqCst);
+                async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            _msg: &'static str =!> {
+                                if silent {
+                                    // Holds on to the question without ever
+                                    // answering it.
+                                    future::pending::<()>().await;
+                                }
+
+                                answer!(ctx, ctx.current().id().clone()).unwrap();
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.")
+}
+
+// The identifiers of the members that answered and of the ones
+// that didn't.
+type Outcome = (Vec<BastionId>, Vec<BastionId>);
+
+fn ask(name: &'static str, timeout: Duration) -> Outcome {
+    let outcome = Arc::new(Mutex::new(None));
+    let outcome_inner = outcome.clone();
+
+    Bastion::spawn(move |ctx: BastionContext| {
+        let outcome = outcome_inner.clone();
+        async move {
+            let target = BroadcastTarget::Group(name.to_string());
+            let replies = ctx
+                .ask_group(target, "who are you?", timeout)
+                .collect::<Vec<_>>()
+                .await;
+
+            let mut answered = Vec::new();
+            let mut missing = Vec::new();
+            for reply in replies {
+                match reply {
+                    GroupReply::Answered { from, answer } => {
+                        msg! { answer,
+                            id: BastionId => assert_eq!(&id, from.id());
+                            _: _ => panic!("unexpected answer");
+                        }
+                        answered.push(from.id().clone());
+                    }
+                    GroupReply::Missing { from } => missing.push(from.id().clone()),
+                }
+            }
+
+            *outcome.lock().unwrap() = Some((answered, missing));
+            Ok(())
+        }
+    })
+    .expect("Couldn't create the children group.");
+
+    assert!(wait_until_within(Duration::from_secs(10), || {
+        outcome.lock().unwrap().is_some()
+    }));
+
+    let outcome = outcome.lock().unwrap().take();
+    outcome.expect("the group was never asked")
+}
+
+fn sorted(mut ids: Vec<BastionId>) -> Vec<BastionId> {
+    ids.sort_by_key(|id| id.to_string());
+    ids
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let answering = group("answering", false);
+    let partially_answering = group("partially_answering", true);
+    // Leaves time to the elements to register to their dispatcher.
+    thread::sleep(Duration::from_millis(200));
+
+    let members = |group: &ChildrenRef| {
+        group
+            .elems()
+            .iter()
+            .map(|child| child.id().clone())
+            .collect::<Vec<_>>()
+    };
+
+    let (answered, missing) = ask("answering", Duration::from_secs(5));
+    assert_eq!(sorted(answered), sorted(members(&answering)));
+    assert!(missing.is_empty());
+
+    let (answered, missing) = ask("partially_answering", Duration::from_millis(500));
+    assert_eq!(answered.len(), REDUNDANCY - 1);
+    assert_eq!(missing.len(), 1);
+    let mut all = answered;
+    all.extend(missing);
+    assert_eq!(sorted(all), sorted(members(&partially_answering)));
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_ask_sync() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_ask_sync() {
+        super::run()
+    }
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // The first child answers the numbers it's asked with their
+    // double, while the second one keeps the questions unanswered.
+    let doubler = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    number: u64 =!> {
+                        answer!(ctx, number * 2).unwrap();
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+    let doubler = doubler.elems()[0].clone();
+
+    let silent = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            let mut questions = vec![];
+            loop {
+                let question = ctx.recv().await?;
+                questions.push(question);
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+    let silent = &silent.elems()[0];
+
+    // The answer is received synchronously within the timeout...
+    let doubled = doubler.ask_sync::<u64, u64>(21, Duration::from_secs(5));
+    assert_eq!(doubled.unwrap(), 42);
+
+    // ...unless the child doesn't answer on time...
+    let timeout = Duration::from_millis(100);
+    match silent.ask_sync::<u64, u64>(21, timeout) {
+        Err(AskError::Timeout(elapsed)) => assert_eq!(elapsed, timeout),
+        other => panic!("unexpected answer: {:?}", other),
+    }
+
+    // ...and it refuses to block the executor from within a child.
+    let refused = Arc::new(AtomicBool::new(false));
+    let refused_clone = refused.clone();
+    Bastion::children(move |children| {
+        let doubler = doubler.clone();
+        let refused = refused_clone.clone();
+        children.with_exec(move |_: BastionContext| {
+            let doubler = doubler.clone();
+            let refused = refused.clone();
+            async move {
+                if let Err(AskError::Blocking) =
+                    doubler.ask_sync::<u64, u64>(21, Duration::from_secs(5))
+                {
+                    refused.store(true, Ordering::SeqCst);
+                }
+
+                Ok(())
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    assert!(wait_until(|| refused.load(Ordering::SeqCst)));
+    assert!(refused.load(Ordering::SeqCst));
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,68 @@
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_await_ready() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_await_ready() {
+        super::run()
+    }
+}
+
+const REDUNDANCY: usize = 4;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let looping = Arc::new(AtomicUsize::new(0));
+    let looping_ref = looping.clone();
+    let children = Bastion::children(move |children| {
+        let looping = looping_ref.clone();
+        children
+            .with_redundancy(REDUNDANCY)
+            .with_exec(move |ctx: BastionContext| {
+                let looping = looping.clone();
+                async move {
+                    looping.fetch_add(1, Ordering::SeqCst);
+
+                    loop {
+                        msg! { ctx.recv().await?,
+                            _msg: &'static str =!> {
+                                answer!(ctx, "pong").unwrap();
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    run!(children.await_ready());
+    // Every element already runs its message loop...
+    assert_eq!(looping.load(Ordering::SeqCst), REDUNDANCY);
+
+    // ...so that asking them right away gets answered.
+    for child in children.elems() {
+        let answer = child.ask_anonymously("ping").unwrap();
+        let mut pong = None;
+        msg! { run!(answer).unwrap(),
+            answer: &'static str => pong = Some(answer);
+            _: _ => ();
+        }
+        assert_eq!(pong, Some("pong"));
+    }
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_backpressure() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_backpressure() {
+        super::run()
+    }
+}
+
+const HIGH_WATERMARK: usize = 8;
+
+fn group(released: Arc<AtomicBool>) -> ChildRef {
+    let children = Bastion::children(move |children| {
+        children
+            .with_mailbox_high_watermark(HIGH_WATERMARK)
+            .with_exec(move |ctx: BastionContext| {
+                let released = released.clone();
+                async move {
+                    // The element doesn't handle its messages until
+                    // it is released, letting its mailbox fill up.
+                    while !released.load(Ordering::SeqCst) {
+                        Delay::new(Duration::from_millis(10)).await;
+                    }
+
+                    loop {
+                        msg! { ctx.recv().await?,
+                            question: usize =!> {
+                                answer!(ctx, question).unwrap();
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    children.elems()[0].clone()
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let released = Arc::new(AtomicBool::new(false));
+    let overloaded = group(released.clone());
+    let fresh = group(Arc::new(AtomicBool::new(true)));
+
+    for _ in 0..HIGH_WATERMARK * 2 {
+        overloaded.tell_anonymously("backlog").unwrap();
+    }
+
+    // The element's mailbox eventually goes past the high-watermark.
+    let overflowed = wait_until(|| match overloaded.try_ask_anonymously(0usize) {
+        Err(SendError::Overloaded(_)) => true,
+        Ok(_) => false,
+        Err(err) => panic!("Unexpected error: {}", err),
+    });
+    assert!(overflowed, "The mailbox never overflowed.");
+
+    // Subsequent questions fail fast...
+    for question in 0..10usize {
+        let asked_at = Instant::now();
+        match overloaded.try_ask_anonymously(question) {
+            Err(SendError::Overloaded(msg)) => {
+                assert!(asked_at.elapsed() < Duration::from_millis(100));
+                msg! { msg,
+                    refused: usize => assert_eq!(refused, question);
+                    _: _ => panic!("Unexpected message.");
+                }
+            }
+            _ => panic!("Asking an overloaded element should fail."),
+        }
+        assert!(overloaded.ask_anonymously(question).is_err());
+    }
+
+    // ...while a fresh element still accepts them.
+    let answer = fresh.try_ask_anonymously(42usize).unwrap();
+    msg! { run!(answer).unwrap(),
+        answer: usize => assert_eq!(answer, 42);
+        _: _ => panic!("Unexpected answer.");
+    }
+
+    // Once released, the element catches up and accepts them again.
+    released.store(true, Ordering::SeqCst);
+    let mut answer = None;
+    let caught_up = wait_until(|| {
+        answer = overloaded.try_ask_anonymously(7usize).ok();
+        answer.is_some()
+    });
+    assert!(caught_up, "The element never caught up.");
+    msg! { run!(answer.unwrap()).unwrap(),
+        answer: usize => assert_eq!(answer, 7);
+        _: _ => panic!("Unexpected answer.");
+    }
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
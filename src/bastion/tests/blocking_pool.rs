@@ -0,0 +1,60 @@
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_blocking_pool() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_blocking_pool() {
+        super::run()
+    }
+}
+
+const POOL_SIZE: usize = 2;
+const TASKS: usize = 8;
+
+fn run() {
+    Bastion::init_with(Config::new().with_blocking_pool_size(POOL_SIZE));
+    Bastion::start();
+
+    let running = Arc::new(AtomicUsize::new(0));
+    let max_running = Arc::new(AtomicUsize::new(0));
+
+    let tasks = (0..TASKS)
+        .map(|i| {
+            let running = running.clone();
+            let max_running = max_running.clone();
+            blocking_named!(&format!("task-{}", i), {
+                let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(now_running, Ordering::SeqCst);
+
+                thread::sleep(Duration::from_millis(50));
+
+                running.fetch_sub(1, Ordering::SeqCst);
+                i
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // More tasks than the pool size were spawned: they all queue up
+    // and complete instead of deadlocking.
+    let completed = tasks
+        .into_iter()
+        .map(|task| run!(task).expect("The blocking task panicked."))
+        .collect::<Vec<_>>();
+    assert_eq!(completed, (0..TASKS).collect::<Vec<_>>());
+    assert!(max_running.load(Ordering::SeqCst) <= POOL_SIZE);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
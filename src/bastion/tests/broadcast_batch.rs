@@ -0,0 +1,142 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{unwrap, wait_until_within};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_broadcast_batch() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_broadcast_batch() {
+        super::run()
+    }
+}
+
+// The messages received by every element of a group, in the order
+// they were received.
+type Received = Arc<Mutex<HashMap<BastionId, Vec<usize>>>>;
+
+fn receivers(name: &str, redundancy: usize, started: Arc<AtomicUsize>) -> Received {
+    let received = Received::default();
+    let received_inner = received.clone();
+    let dispatcher = DispatcherType::Named(name.to_string());
+
+    Bastion::children(move |children| {
+        let received = received_inner.clone();
+        let started = started.clone();
+        children
+            .with_redundancy(redundancy)
+            .with_dispatcher(Dispatcher::with_type(dispatcher.clone()))
+            .with_exec(move |ctx: BastionContext| {
+                let received = received.clone();
+                let started = started.clone();
+                async move {
+                    let id = ctx.current().id().clone();
+                    started.fetch_add(1, Ordering::SeqCst);
+
+                    loop {
+                        msg! { ctx.recv().await?,
+                            raw_message: Arc<SignedMessage> => {
+                                msg! { unwrap(raw_message).await,
+                                    ref value: usize => {
+                                        received
+                                            .lock()
+                                            .unwrap()
+                                            .entry(id.clone())
+                                            .or_default()
+                                            .push(*value);
+                                    };
+                                    _: _ => ();
+                                }
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    received
+}
+
+fn received_count(received: &Received) -> usize {
+    received.lock().unwrap().values().map(Vec::len).sum()
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    const MESSAGES: usize = 7;
+    const STRESS_MESSAGES: usize = 10_000;
+
+    let started = Arc::new(AtomicUsize::new(0));
+    let sequential = receivers("sequential", 3, started.clone());
+    let batch = receivers("batch", 3, started.clone());
+    let stress = receivers("stress", 1, started.clone());
+    assert!(wait_until_within(Duration::from_secs(10), || {
+        started.load(Ordering::SeqCst) == 7
+    }));
+
+    Bastion::spawn(|ctx: BastionContext| async move {
+        for i in 0..MESSAGES {
+            ctx.broadcast_message(BroadcastTarget::Group("sequential".to_string()), i);
+        }
+
+        ctx.broadcast_batch(
+            BroadcastTarget::Group("batch".to_string()),
+            (0..MESSAGES).collect(),
+        );
+        ctx.broadcast_batch(
+            BroadcastTarget::Group("stress".to_string()),
+            (0..STRESS_MESSAGES).collect(),
+        );
+
+        Ok(())
+    })
+    .expect("Couldn't create the children group.");
+
+    assert!(wait_until_within(Duration::from_secs(10), || {
+        received_count(&sequential) == MESSAGES
+            && received_count(&batch) == MESSAGES
+            && received_count(&stress) == STRESS_MESSAGES
+    }));
+
+    // The batch is distributed exactly like sequential broadcasts.
+    let distribution = |received: &Received| {
+        let mut distribution = received
+            .lock()
+            .unwrap()
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        distribution.sort();
+        distribution
+    };
+    assert_eq!(distribution(&batch), distribution(&sequential));
+    assert_eq!(
+        distribution(&batch),
+        vec![vec![0, 3, 6], vec![1, 4], vec![2, 5]]
+    );
+
+    // A single element receives the whole batch in order.
+    assert_eq!(
+        distribution(&stress),
+        vec![(0..STRESS_MESSAGES).collect::<Vec<_>>()]
+    );
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
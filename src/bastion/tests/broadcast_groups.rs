@@ -0,0 +1,103 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{unwrap, wait_until};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_broadcast_groups() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_broadcast_groups() {
+        super::run()
+    }
+}
+
+const GROUPS: [&str; 3] = ["Rounder", "Logger", "Auditor"];
+
+// The identifiers of the children that received the message, once
+// per received copy.
+type Received = Arc<Mutex<Vec<BastionId>>>;
+
+// Creates a single child belonging to the groups with the given names.
+fn child(names: &[&str], started: Arc<AtomicUsize>, received: Received) -> ChildrenRef {
+    let names = names
+        .iter()
+        .map(|name| name.to_string())
+        .collect::<Vec<_>>();
+
+    Bastion::children(move |children| {
+        let children = names.iter().fold(children, |children, name| {
+            children.with_dispatcher(Dispatcher::with_type(DispatcherType::Named(name.clone())))
+        });
+
+        children.with_exec(move |ctx: BastionContext| {
+            let started = started.clone();
+            let received = received.clone();
+            async move {
+                started.fetch_add(1, Ordering::SeqCst);
+
+                loop {
+                    msg! { ctx.recv().await?,
+                        raw_message: Arc<SignedMessage> => {
+                            msg! { unwrap(raw_message).await,
+                                _msg: &'static str => {
+                                    received.lock().unwrap().push(ctx.current().id().clone());
+                                };
+                                _: _ => ();
+                            }
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.")
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let started = Arc::new(AtomicUsize::new(0));
+    let received: Received = Arc::new(Mutex::new(Vec::new()));
+
+    let rounder = child(&["Rounder"], started.clone(), received.clone());
+    let logger = child(&["Logger"], started.clone(), received.clone());
+    // Belongs to two of the targeted groups.
+    let shared = child(&["Logger", "Auditor"], started.clone(), received.clone());
+    assert!(wait_until(|| started.load(Ordering::SeqCst) == 3));
+
+    Bastion::spawn(|ctx: BastionContext| async move {
+        let names = GROUPS.iter().map(|name| name.to_string()).collect();
+        ctx.broadcast_message(BroadcastTarget::Groups(names), "hello");
+
+        Ok(())
+    })
+    .expect("Couldn't create the sender.");
+
+    assert!(wait_until(|| received.lock().unwrap().len() >= 3));
+    // Leaves the time for an extra copy to be received.
+    thread::sleep(Duration::from_millis(100));
+
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 3);
+    for children in &[rounder, logger, shared] {
+        let id = children.elems()[0].id();
+        assert_eq!(received.iter().filter(|from| *from == id).count(), 1);
+    }
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_broadcast_with_ack() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_broadcast_with_ack() {
+        super::run()
+    }
+}
+
+const GROUP: &str = "replicas";
+const REPLICAS: usize = 4;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let started = Arc::new(AtomicUsize::new(0));
+    // The identifier of the replica which doesn't acknowledge the
+    // commands.
+    let silent = Arc::new(Mutex::new(None));
+
+    let started_ref = started.clone();
+    let silent_ref = silent.clone();
+    let replicas = Bastion::children(move |children| {
+        let started = started_ref.clone();
+        let silent = silent_ref.clone();
+        children
+            .with_redundancy(REPLICAS)
+            .with_dispatcher(Dispatcher::with_type(DispatcherType::Named(
+                GROUP.to_string(),
+            )))
+            .with_exec(move |ctx: BastionContext| {
+                let started = started.clone();
+                let silent = silent.clone();
+                async move {
+                    let acks = started.fetch_add(1, Ordering::SeqCst) != REPLICAS - 1;
+                    if !acks {
+                        *silent.lock().unwrap() = Some(ctx.current().id().clone());
+                    }
+
+                    loop {
+                        msg! { ctx.recv().await?,
+                            msg: &'static str => {
+                                assert_eq!(msg, "apply");
+                                if acks {
+                                    ctx.ack().unwrap();
+                                    // It can only be acknowledged once.
+                                    assert!(ctx.ack().is_err());
+                                }
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the replicas.");
+    assert!(wait_until(|| started.load(Ordering::SeqCst) == REPLICAS));
+
+    let report = Arc::new(Mutex::new(None));
+    let report_ref = report.clone();
+    Bastion::spawn(move |ctx: BastionContext| {
+        let report = report_ref.clone();
+        async move {
+            let target = BroadcastTarget::Group(GROUP.to_string());
+            let acks = ctx
+                .broadcast_with_ack(target, "apply", Duration::from_millis(500))
+                .await;
+            *report.lock().unwrap() = Some(acks);
+
+            Ok(())
+        }
+    })
+    .expect("Couldn't create the sender.");
+    assert!(wait_until(|| report.lock().unwrap().is_some()));
+
+    let report = report.lock().unwrap().take().expect("No report.");
+    let silent = silent.lock().unwrap().clone().expect("No silent replica.");
+
+    let acked = report
+        .acked
+        .iter()
+        .map(|child| child.id().clone())
+        .collect::<HashSet<_>>();
+    let expected = replicas
+        .elems()
+        .iter()
+        .map(|child| child.id().clone())
+        .filter(|id| id != &silent)
+        .collect::<HashSet<_>>();
+    assert_eq!(acked.len(), REPLICAS - 1);
+    assert_eq!(acked, expected);
+
+    let missing = report
+        .missing
+        .iter()
+        .map(|child| child.id().clone())
+        .collect::<Vec<_>>();
+    assert_eq!(missing, vec![silent]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,99 @@
+use bastion::prelude::*;
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_bulkhead() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_bulkhead() {
+        super::run()
+    }
+}
+
+// More greedy children than the executor has threads, so that they
+// would starve the other groups without a bulkhead.
+const GREEDY: usize = 32;
+const MAX_CONCURRENCY: usize = 2;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let active = Arc::new(AtomicUsize::new(0));
+    let max_active = Arc::new(AtomicUsize::new(0));
+    let iterations = Arc::new(AtomicUsize::new(0));
+
+    let active_ref = active.clone();
+    let max_active_ref = max_active.clone();
+    let iterations_ref = iterations.clone();
+    Bastion::children(move |children| {
+        children
+            .with_redundancy(GREEDY)
+            .with_bulkhead(MAX_CONCURRENCY)
+            .with_exec(move |_ctx: BastionContext| {
+                let active = active_ref.clone();
+                let max_active = max_active_ref.clone();
+                let iterations = iterations_ref.clone();
+                async move {
+                    loop {
+                        let now_active = active.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_active.fetch_max(now_active, Ordering::SeqCst);
+                        // Blocks the thread executing the child.
+                        thread::sleep(Duration::from_millis(20));
+                        active.fetch_sub(1, Ordering::SeqCst);
+                        iterations.fetch_add(1, Ordering::SeqCst);
+
+                        Delay::new(Duration::from_millis(1)).await;
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the greedy group.");
+
+    let sensitive = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    _msg: &'static str =!> {
+                        answer!(ctx, "pong").unwrap();
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the latency-sensitive group.");
+    let sensitive = &sensitive.elems()[0];
+
+    // Leaves time for the greedy group to saturate its bulkhead.
+    thread::sleep(Duration::from_millis(200));
+
+    for _ in 0..10 {
+        let sent_at = Instant::now();
+        let answer = sensitive.ask_anonymously("ping").unwrap();
+        msg! { run!(answer).unwrap(),
+            msg: &'static str => assert_eq!(msg, "pong");
+            _: _ => panic!("Unexpected answer.");
+        }
+        assert!(sent_at.elapsed() < Duration::from_secs(1));
+    }
+
+    // The greedy group still made progress, but never with more
+    // elements executing at once than allowed.
+    assert!(iterations.load(Ordering::SeqCst) > 0);
+    assert!(max_active.load(Ordering::SeqCst) <= MAX_CONCURRENCY);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_bytes_messages_are_not_copied() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_bytes_messages_are_not_copied() {
+        super::run()
+    }
+}
+
+const REDUNDANCY: usize = 3;
+const PAYLOAD_SIZE: usize = 16 * 1024 * 1024;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // The address and length of the buffers received by the elements.
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_inner = received.clone();
+    let children = Bastion::children(move |children| {
+        let received = received_inner.clone();
+        children
+            .with_redundancy(REDUNDANCY)
+            .with_exec(move |ctx: BastionContext| {
+                let received = received.clone();
+                async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            ref data: &Bytes => {
+                                received.lock().unwrap().push((data.as_ptr() as usize, data.len()));
+                            };
+                            data: Bytes => {
+                                received.lock().unwrap().push((data.as_ptr() as usize, data.len()));
+                            };
+                            // Any type can be made explicit, not only names.
+                            ref data: &Vec<u8> => {
+                                received.lock().unwrap().push((data.as_ptr() as usize, data.len()));
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let payload = Bytes::from(vec![42u8; PAYLOAD_SIZE]);
+    children
+        .broadcast(payload.clone())
+        .expect("Couldn't broadcast the message.");
+    children.elems()[0]
+        .tell_anonymously(payload.clone())
+        .expect("Couldn't send the message.");
+
+    assert!(wait_until(
+        || received.lock().unwrap().len() >= REDUNDANCY + 1
+    ));
+
+    // Every element saw the very same allocation.
+    let expected = (payload.as_ptr() as usize, PAYLOAD_SIZE);
+    assert_eq!(*received.lock().unwrap(), vec![expected; REDUNDANCY + 1]);
+
+    received.lock().unwrap().clear();
+    let vec = vec![42u8; PAYLOAD_SIZE];
+    let expected = (vec.as_ptr() as usize, PAYLOAD_SIZE);
+    children
+        .broadcast(vec)
+        .expect("Couldn't broadcast the message.");
+
+    assert!(wait_until(|| received.lock().unwrap().len() >= REDUNDANCY));
+    assert_eq!(*received.lock().unwrap(), vec![expected; REDUNDANCY]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use futures::future::{self, Either};
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_cancellation() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_cancellation() {
+        super::run()
+    }
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let working = Arc::new(AtomicBool::new(false));
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    let working_ref = working.clone();
+    let events_exec = events.clone();
+    let events_stop = events.clone();
+    let children = Bastion::children(move |children| {
+        let working = working_ref.clone();
+        let events = events_exec.clone();
+        let events_stop = events_stop.clone();
+        let callbacks = Callbacks::new().with_after_stop(move || {
+            events_stop.lock().unwrap().push("stopped");
+        });
+
+        children
+            .with_callbacks(callbacks)
+            .with_exec(move |ctx: BastionContext| {
+                let working = working.clone();
+                let events = events.clone();
+                async move {
+                    working.store(true, Ordering::SeqCst);
+
+                    let work = Delay::new(Duration::from_secs(60));
+                    match future::select(Box::pin(work), Box::pin(ctx.cancelled())).await {
+                        Either::Left(_) => events.lock().unwrap().push("finished"),
+                        Either::Right(_) => {
+                            events.lock().unwrap().push("cancelled");
+                            // The cleanup can itself be asynchronous.
+                            Delay::new(Duration::from_millis(50)).await;
+                            events.lock().unwrap().push("cleaned up");
+                        }
+                    }
+
+                    Ok(())
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    assert!(wait_until(|| working.load(Ordering::SeqCst)));
+
+    let child = children.elems()[0].clone();
+    child.stop().expect("Couldn't stop the child.");
+
+    assert!(wait_until(|| events.lock().unwrap().contains(&"stopped")));
+
+    // The child observed the cancellation and cleaned up before
+    // stopping.
+    let events = events.lock().unwrap().clone();
+    assert_eq!(&events[..3], &["cancelled", "cleaned up", "stopped"]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
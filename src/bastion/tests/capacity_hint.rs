@@ -0,0 +1,92 @@
+use bastion::prelude::*;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_capacity_hint() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_capacity_hint() {
+        super::run()
+    }
+}
+
+const GROUPS: usize = 16;
+
+// Creates a supervisor of `GROUPS` children groups, twice as many as
+// the hint (if any), answering questions with their index.
+fn supervisor(hint: Option<usize>) -> Vec<ChildrenRef> {
+    let groups = Arc::new(Mutex::new(Vec::new()));
+
+    let groups_ref = groups.clone();
+    Bastion::supervisor(move |sp| {
+        let sp = match hint {
+            Some(hint) => sp.with_capacity_hint(hint),
+            None => sp,
+        };
+
+        for index in 0..GROUPS {
+            let group = sp.children_ref(move |children| {
+                children.with_exec(move |ctx: BastionContext| async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            _msg: &'static str =!> {
+                                answer!(ctx, index).unwrap();
+                            };
+                            _: _ => ();
+                        }
+                    }
+                })
+            });
+            groups_ref.lock().unwrap().push(group);
+        }
+
+        sp
+    })
+    .expect("Couldn't create the supervisor.");
+
+    let groups = groups.lock().unwrap().clone();
+    groups
+}
+
+fn answers(groups: &[ChildrenRef]) -> Vec<usize> {
+    groups
+        .iter()
+        .map(|group| {
+            let answer = group.elems()[0].ask_anonymously("index").unwrap();
+            let mut index = None;
+            msg! { run!(answer).unwrap(),
+                answer: usize => index = Some(answer);
+                _: _ => ();
+            }
+            index.expect("Unexpected answer.")
+        })
+        .collect()
+}
+
+fn run() {
+    Bastion::init_with(Config::new().with_capacity_hint(GROUPS / 2));
+    Bastion::start();
+
+    let hinted = supervisor(Some(GROUPS / 2));
+    let unhinted = supervisor(None);
+
+    // The hinted supervisor grew past its hint, and behaves like the
+    // unhinted one.
+    let expected = (0..GROUPS).collect::<Vec<_>>();
+    assert_eq!(answers(&hinted), expected);
+    assert_eq!(answers(&unhinted), expected);
+
+    for group in hinted.iter().chain(unhinted.iter()) {
+        group.stop().unwrap();
+    }
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_child_index() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_child_index() {
+        super::run()
+    }
+}
+
+const REDUNDANCY: usize = 5;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // The id and index of every element, every time it starts.
+    let started = Arc::new(Mutex::new(Vec::new()));
+    let started_ref = started.clone();
+    let children = Bastion::children(|children| {
+        children
+            .with_redundancy(REDUNDANCY)
+            .with_exec(move |ctx: BastionContext| {
+                let started = started_ref.clone();
+                async move {
+                    let id = ctx.current().id().clone();
+                    started.lock().unwrap().push((id, ctx.index()));
+
+                    msg! { ctx.recv().await?,
+                        _: _ => ();
+                    }
+
+                    // Faults, to get restarted.
+                    Err(())
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    assert!(wait_until(|| started.lock().unwrap().len() == REDUNDANCY));
+    let mut initial = started.lock().unwrap().clone();
+    initial.sort_by_key(|(_, index)| *index);
+    let indices = initial.iter().map(|(_, index)| *index).collect::<Vec<_>>();
+    assert_eq!(indices, (0..=4).collect::<Vec<_>>());
+
+    // Restarts the element with index 2...
+    let (id, index) = initial[2].clone();
+    let child = children
+        .elems()
+        .iter()
+        .find(|child| child.id() == &id)
+        .expect("Couldn't find the element.");
+    child.tell_anonymously("fault").unwrap();
+
+    // ...which keeps its id and index.
+    assert!(wait_until(
+        || started.lock().unwrap().len() == REDUNDANCY + 1
+    ));
+    assert_eq!(started.lock().unwrap().last(), Some(&(id, index)));
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
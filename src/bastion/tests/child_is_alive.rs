@@ -0,0 +1,63 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_child_is_alive() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_child_is_alive() {
+        super::run()
+    }
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let supervisor = Bastion::supervisor(|sp| {
+        // Leaves time to observe the element while it is down.
+        sp.with_restart_strategy(RestartStrategy::default().with_actor_restart_strategy(
+            ActorRestartStrategy::LinearBackOff {
+                timeout: Duration::from_millis(300),
+            },
+        ))
+    })
+    .expect("Couldn't create the supervisor.");
+    let children = supervisor
+        .children(|children| {
+            children.with_exec(|ctx: BastionContext| async move {
+                msg! { ctx.recv().await?,
+                    _: _ => ();
+                }
+
+                // Faults, to get restarted.
+                Err(())
+            })
+        })
+        .expect("Couldn't create the children group.");
+
+    let child = children.elems()[0].clone();
+    assert!(wait_until(|| child.is_alive()));
+    assert!(child.is_alive());
+
+    child.tell_anonymously("fault").unwrap();
+    assert!(wait_until(|| !child.is_alive()));
+    assert!(!child.is_alive());
+
+    // The restarted element keeps the identifier of the faulted one.
+    assert!(wait_until(|| child.is_alive()));
+    assert!(child.is_alive());
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,146 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{wait_for, wait_until};
+use futures::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_circuit_breaker() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_circuit_breaker() {
+        super::run()
+    }
+}
+
+// Returns the amount of dead letters emitted since the last call.
+fn dead_letters(events: &mut EventStream) -> usize {
+    let mut count = 0;
+    while let Some(Some(event)) = events.next().now_or_never() {
+        if let SystemEvent::DeadLetter { .. } = event {
+            count += 1;
+        }
+    }
+
+    count
+}
+
+fn run() {
+    Bastion::init();
+    let mut events = Bastion::event_stream();
+    Bastion::start();
+
+    let healthy = Arc::new(AtomicBool::new(false));
+    let healthy_inner = healthy.clone();
+    let children = Bastion::children(move |children| {
+        let healthy = healthy_inner.clone();
+        children
+            .with_circuit_breaker(2, Duration::from_secs(5), Duration::from_millis(300))
+            .with_exec(move |ctx: BastionContext| {
+                let healthy = healthy.clone();
+                async move {
+                    if !healthy.load(Ordering::SeqCst) {
+                        return Err(());
+                    }
+
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let id = children.elems()[0].id().clone();
+
+    let opened = wait_for(&mut events, |event| match event {
+        SystemEvent::CircuitOpened { id: opened } => opened == &id,
+        _ => false,
+    });
+    assert!(opened, "the circuit never opened");
+
+    children.broadcast("dropped").unwrap();
+    let dead_lettered = wait_for(&mut events, |event| match event {
+        SystemEvent::DeadLetter { .. } => true,
+        SystemEvent::CircuitHalfOpened { .. } => panic!("half-opened before dead-lettering"),
+        _ => false,
+    });
+    assert!(dead_lettered, "the message wasn't dead-lettered");
+
+    healthy.store(true, Ordering::SeqCst);
+
+    let half_opened = wait_for(&mut events, |event| match event {
+        SystemEvent::CircuitHalfOpened { id: half_opened } => half_opened == &id,
+        _ => false,
+    });
+    assert!(half_opened, "the circuit never became half-open");
+
+    let closed = wait_for(&mut events, |event| match event {
+        SystemEvent::CircuitClosed { id: closed } => closed == &id,
+        SystemEvent::CircuitOpened { .. } => panic!("the probe faulted"),
+        _ => false,
+    });
+    assert!(closed, "the circuit never closed");
+
+    // The first element of this group always faults, while the other
+    // one handles the messages.
+    let faulty = Arc::new(Mutex::new(None));
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let faulty_inner = faulty.clone();
+    let received_inner = received.clone();
+    let group = Bastion::children(move |children| {
+        let faulty = faulty_inner.clone();
+        let received = received_inner.clone();
+        children
+            .with_redundancy(2)
+            .with_circuit_breaker(2, Duration::from_secs(5), Duration::from_secs(5))
+            .with_exec(move |ctx: BastionContext| {
+                let faulty = faulty.clone();
+                let received = received.clone();
+                async move {
+                    let id = ctx.current().id().clone();
+                    if *faulty.lock().unwrap().get_or_insert_with(|| id.clone()) == id {
+                        return Err(());
+                    }
+
+                    loop {
+                        msg! { ctx.recv().await?,
+                            msg: &'static str => received.lock().unwrap().push(msg);
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let opened = wait_for(&mut events, |event| match event {
+        SystemEvent::CircuitOpened { id: opened } => {
+            Some(opened) == faulty.lock().unwrap().as_ref()
+        }
+        _ => false,
+    });
+    assert!(opened, "the circuit never opened");
+
+    // Only the message sent to the faulty element is dead-lettered.
+    dead_letters(&mut events);
+    group.broadcast("hello").unwrap();
+    assert!(wait_until(|| !received.lock().unwrap().is_empty()));
+    thread::sleep(Duration::from_millis(200));
+    assert_eq!(*received.lock().unwrap(), vec!["hello"]);
+    assert_eq!(dead_letters(&mut events), 1);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,118 @@
+#![cfg(all(feature = "distributed", feature = "testkit"))]
+
+mod common;
+
+use bastion::prelude::*;
+use bastion::transport::{MockNetwork, MockTransport};
+use common::wait_until;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use uuid::Uuid;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_cluster_broadcast() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_cluster_broadcast() {
+        super::run()
+    }
+}
+
+const RECEIVERS: usize = 3;
+const GREETING: &str = "hello, cluster";
+
+// The greetings received by every node.
+type Received = Arc<Mutex<Vec<Uuid>>>;
+
+fn receiver(transport: MockTransport, received: Received) {
+    Bastion::distributed(transport, move |dctx| {
+        let received = received.clone();
+        async move {
+            loop {
+                let msg = dctx.recv().await?;
+                let payload: String = msg.extract().downcast().unwrap();
+                if payload == GREETING {
+                    received.lock().unwrap().push(dctx.current());
+                }
+            }
+        }
+    })
+    .expect("Couldn't start a receiver.");
+}
+
+fn broadcaster(transport: MockTransport, broadcasted: Arc<AtomicBool>, received: Received) {
+    Bastion::distributed(transport, move |dctx| {
+        let broadcasted = broadcasted.clone();
+        let received = received.clone();
+        async move {
+            loop {
+                // The driver pokes the broadcaster until it knows every
+                // receiver (and the driver itself).
+                let msg = dctx.recv().await?;
+                let payload: String = msg.extract().downcast().unwrap();
+                if payload == GREETING {
+                    received.lock().unwrap().push(dctx.current());
+                }
+
+                if !broadcasted.load(Ordering::SeqCst) && dctx.members().len() > RECEIVERS {
+                    dctx.broadcast(GREETING.to_string()).unwrap();
+                    broadcasted.store(true, Ordering::SeqCst);
+                }
+            }
+        }
+    })
+    .expect("Couldn't start the broadcaster.");
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let network =
+        MockNetwork::with_seed(7).with_delay(Duration::from_millis(1), Duration::from_millis(5));
+    let transports: Vec<MockTransport> = (0..RECEIVERS).map(|_| network.join()).collect();
+    let ids: Vec<Uuid> = transports.iter().map(MockTransport::node_id).collect();
+    let broadcaster_transport = network.join();
+    let broadcaster_id = broadcaster_transport.node_id();
+    let driver = network.join();
+
+    let received: Received = Arc::new(Mutex::new(Vec::new()));
+    for transport in transports {
+        receiver(transport, received.clone());
+    }
+    let broadcasted = Arc::new(AtomicBool::new(false));
+    broadcaster(broadcaster_transport, broadcasted.clone(), received.clone());
+
+    let poked = wait_until(|| {
+        let poked = broadcasted.load(Ordering::SeqCst);
+        if !poked {
+            driver.send_payload(broadcaster_id, "poke".to_string());
+        }
+        poked
+    });
+    assert!(poked, "The greeting was never broadcasted.");
+
+    assert!(wait_until(|| received.lock().unwrap().len() >= RECEIVERS));
+    // Leaves the time for an extra copy to be received.
+    thread::sleep(Duration::from_millis(100));
+
+    // Every other member received the greeting exactly once, while the
+    // broadcaster didn't receive it.
+    let mut received = received.lock().unwrap().clone();
+    received.sort();
+    let mut expected = ids.clone();
+    expected.sort();
+    assert_eq!(received, expected);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
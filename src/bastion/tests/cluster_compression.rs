@@ -0,0 +1,117 @@
+#![cfg(all(
+    feature = "distributed",
+    feature = "compression-lz4",
+    feature = "testkit"
+))]
+
+mod common;
+
+use bastion::prelude::*;
+use bastion::transport::{MockNetwork, MockTransport};
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_cluster_compression() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_cluster_compression() {
+        super::run()
+    }
+}
+
+// The largest payload the network carries, in bytes.
+const MAX_PAYLOAD_SIZE: usize = 4096;
+
+// Starts a node sending `payload` to the receiver, returning the size
+// of the payload on the wire if it was too large to be sent.
+fn sender(
+    transport: MockTransport,
+    codec: Codec,
+    receiver_id: Uuid,
+    payload: String,
+) -> Arc<Mutex<Option<Option<usize>>>> {
+    let sent = Arc::new(Mutex::new(None));
+    let sent_ref = sent.clone();
+    let config = ClusterConfig::from(transport).with_compression(Compression::new(codec));
+    Bastion::distributed(config, move |dctx| {
+        let sent = sent_ref.clone();
+        let payload = payload.clone();
+        async move {
+            let result = match dctx.tell(&receiver_id, payload) {
+                Ok(()) => None,
+                Err(TellError::MessageTooLarge { size, .. }) => Some(size),
+                Err(err) => panic!("unexpected error: {}", err),
+            };
+            *sent.lock().unwrap() = Some(result);
+
+            loop {
+                dctx.recv().await?;
+            }
+        }
+    })
+    .expect("Couldn't start a sender.");
+
+    sent
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let network = MockNetwork::new().with_max_payload_size(MAX_PAYLOAD_SIZE);
+    let receiver = network.join();
+    let receiver_id = receiver.node_id();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_ref = received.clone();
+    Bastion::distributed(receiver, move |dctx| {
+        let received = received_ref.clone();
+        async move {
+            loop {
+                let msg = dctx.recv().await?;
+                let payload: String = msg.extract().downcast().unwrap();
+                received.lock().unwrap().push(payload);
+            }
+        }
+    })
+    .expect("Couldn't start the receiver.");
+
+    // A payload eight times as large as the network carries, which
+    // compresses well.
+    let payload = format!(
+        "[{}]",
+        vec!["{\"key\":\"value\"}"; MAX_PAYLOAD_SIZE / 2].join(",")
+    );
+    assert!(payload.len() > 8 * MAX_PAYLOAD_SIZE);
+
+    let uncompressed = sender(network.join(), Codec::None, receiver_id, payload.clone());
+    let compressed = sender(network.join(), Codec::Lz4, receiver_id, payload.clone());
+
+    assert!(wait_until(|| uncompressed.lock().unwrap().is_some()));
+    assert!(wait_until(|| compressed.lock().unwrap().is_some()));
+
+    // Sent as-is, the payload is larger on the wire than the message...
+    let size = uncompressed
+        .lock()
+        .unwrap()
+        .expect("the uncompressed payload wasn't sent")
+        .expect("the uncompressed payload fit on the wire");
+    assert!(size > payload.len(), "size={}", size);
+
+    // ...while compressed, it fits and is received intact.
+    assert_eq!(*compressed.lock().unwrap(), Some(None));
+    assert!(wait_until(|| !received.lock().unwrap().is_empty()));
+    assert_eq!(*received.lock().unwrap(), vec![payload]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
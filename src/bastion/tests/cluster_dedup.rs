@@ -0,0 +1,75 @@
+#![cfg(all(feature = "distributed", feature = "testkit"))]
+
+mod common;
+
+use bastion::prelude::*;
+use bastion::transport::MockNetwork;
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_cluster_dedup() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_cluster_dedup() {
+        super::run()
+    }
+}
+
+const SENT: [&str; 3] = ["first", "second", "second"];
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // Every payload is delivered twice by the network.
+    let network = MockNetwork::with_seed(42).with_duplication(1.0);
+    let sender = network.join();
+    let receiver = network.join();
+    let receiver_id = receiver.node_id();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_ref = received.clone();
+    Bastion::distributed(receiver, move |dctx| {
+        let received = received_ref.clone();
+        async move {
+            loop {
+                let msg = dctx.recv().await?;
+                let payload: String = msg.extract().downcast().unwrap();
+                received.lock().unwrap().push(payload);
+            }
+        }
+    })
+    .expect("Couldn't start the receiver.");
+
+    // The two "second" messages are distinct messages with the same
+    // content, which are both delivered.
+    Bastion::distributed(sender, move |dctx| async move {
+        for msg in SENT.iter() {
+            dctx.tell(&receiver_id, msg.to_string()).unwrap();
+        }
+
+        loop {
+            dctx.recv().await?;
+        }
+    })
+    .expect("Couldn't start the sender.");
+
+    assert!(wait_until(|| received.lock().unwrap().len() >= SENT.len()));
+    // Leaves time for the duplicates to be (wrongly) handled.
+    thread::sleep(Duration::from_millis(200));
+
+    assert_eq!(*received.lock().unwrap(), SENT.to_vec());
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,77 @@
+#![cfg(all(feature = "distributed", feature = "testkit"))]
+
+use bastion::prelude::*;
+use bastion::transport::MockNetwork;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_cluster_gossip() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_cluster_gossip() {
+        super::run()
+    }
+}
+
+const NODES: usize = 10;
+const MAX_ROUNDS: usize = 100;
+
+// Returns the amount of gossip rounds it took for the membership of a
+// cluster whose members gossip with `fan_out` peers to converge.
+fn rounds_to_converge(fan_out: usize) -> usize {
+    let network = MockNetwork::with_seed(7).with_gossip(true);
+    for _ in 0..NODES {
+        let transport = network.join();
+        let config = ClusterConfig::from_transport(transport.node_id(), transport)
+            .with_gossip_fan_out(fan_out);
+        assert_eq!(config.gossip().fan_out, fan_out);
+
+        Bastion::distributed(config, |dctx| async move {
+            loop {
+                dctx.recv().await?;
+            }
+        })
+        .expect("Couldn't start a cluster node.");
+    }
+
+    let mut rounds = 0;
+    while !network.is_converged() && rounds < MAX_ROUNDS {
+        network.gossip_round();
+        rounds += 1;
+    }
+
+    assert!(
+        network.is_converged(),
+        "The membership never converged with a fan-out of {}.",
+        fan_out
+    );
+    rounds
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // The default fan-out matches the underlying cluster's one.
+    let default = ClusterConfig::from(MockNetwork::new().join());
+    assert_eq!(default.gossip(), GossipConfig::default());
+
+    let small = rounds_to_converge(1);
+    let large = rounds_to_converge(NODES - 1);
+    assert!(
+        large < small,
+        "A fan-out of {} took {} rounds, while a fan-out of 1 took {}.",
+        NODES - 1,
+        large,
+        small
+    );
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
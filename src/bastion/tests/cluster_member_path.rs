@@ -0,0 +1,100 @@
+#![cfg(all(feature = "distributed", feature = "testkit"))]
+
+mod common;
+
+use bastion::prelude::*;
+use bastion::transport::MockNetwork;
+use common::wait_until;
+use futures_timer::Delay;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_cluster_member_path() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_cluster_member_path() {
+        super::run()
+    }
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let network = MockNetwork::new();
+    let sender = network.join();
+    let receiver = network.join();
+    let sender_id = sender.node_id();
+    let receiver_id = receiver.node_id();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_ref = received.clone();
+    Bastion::distributed(receiver, move |dctx| {
+        let received = received_ref.clone();
+        async move {
+            loop {
+                let msg = dctx.recv().await?;
+                let host_key = msg.path().host_key();
+                let payload: String = msg.extract().downcast().unwrap();
+                received.lock().unwrap().push((host_key, payload));
+            }
+        }
+    })
+    .expect("Couldn't start the receiver.");
+
+    let rendered = Arc::new(Mutex::new(None));
+    let rendered_ref = rendered.clone();
+    Bastion::distributed(sender, move |dctx| {
+        let rendered = rendered_ref.clone();
+        async move {
+            // The receiver is addressed by the path built from its
+            // member, once it is known.
+            let member = loop {
+                if let Some(member) = dctx.members().into_iter().next() {
+                    break member;
+                }
+                Delay::new(Duration::from_millis(10)).await;
+            };
+            let path = dctx.path_of_member(&member);
+            assert!(path.is_remote());
+            *rendered.lock().unwrap() = Some(path.to_string());
+
+            let host_key = path.host_key().expect("the path isn't remote");
+            dctx.tell(&host_key, "hello".to_string()).unwrap();
+
+            loop {
+                dctx.recv().await?;
+            }
+        }
+    })
+    .expect("Couldn't start the sender.");
+
+    assert!(wait_until(|| !received.lock().unwrap().is_empty()));
+
+    // The receiver didn't announce a name, so its path is named after
+    // its node id.
+    let rendered = rendered.lock().unwrap().clone().expect("no path was built");
+    assert!(
+        rendered.starts_with(&receiver_id.to_string()),
+        "{}",
+        rendered
+    );
+
+    // The message was sent to the member the path was built from, and
+    // the path it was received with leads back to the sender.
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec![(Some(sender_id), "hello".to_string())]
+    );
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,91 @@
+#![cfg(all(feature = "distributed", feature = "testkit"))]
+
+mod common;
+
+use bastion::prelude::*;
+use bastion::transport::MockNetwork;
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_cluster_message_size() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_cluster_message_size() {
+        super::run()
+    }
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // The network carries payloads as large as UDP datagrams.
+    let network = MockNetwork::new().with_max_payload_size(MAX_UDP_PAYLOAD_SIZE);
+    let sender = network.join();
+    let receiver = network.join();
+    let receiver_id = receiver.node_id();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_ref = received.clone();
+    Bastion::distributed(receiver, move |dctx| {
+        let received = received_ref.clone();
+        async move {
+            loop {
+                // The payloads are taken over without being copied.
+                let payload = dctx.recv().await?.into_bytes().unwrap();
+                received.lock().unwrap().push(payload);
+            }
+        }
+    })
+    .expect("Couldn't start the receiver.");
+
+    let oversized = "x".repeat(MAX_UDP_PAYLOAD_SIZE + 1);
+    let rejected = Arc::new(Mutex::new(None));
+    let rejected_ref = rejected.clone();
+    Bastion::distributed(sender, move |dctx| {
+        let oversized = oversized.clone();
+        let rejected = rejected_ref.clone();
+        async move {
+            match dctx.tell(&receiver_id, oversized) {
+                Err(TellError::MessageTooLarge { msg, size, max }) => {
+                    *rejected.lock().unwrap() = Some((msg.len(), size, max));
+                }
+                Err(err) => panic!("unexpected error: {}", err),
+                Ok(()) => panic!("the oversized message was sent"),
+            }
+            dctx.tell(&receiver_id, "small".to_string()).unwrap();
+
+            loop {
+                dctx.recv().await?;
+            }
+        }
+    })
+    .expect("Couldn't start the sender.");
+
+    assert!(wait_until(|| !received.lock().unwrap().is_empty()));
+    // Leaves time for the oversized message to be (wrongly) received.
+    thread::sleep(Duration::from_millis(200));
+
+    let (len, size, max) = rejected.lock().unwrap().expect("no message was rejected");
+    assert_eq!(len, MAX_UDP_PAYLOAD_SIZE + 1);
+    // The size is the one of the framed payload, which is larger than
+    // the message.
+    assert!(size > len, "size={}", size);
+    assert_eq!(max, MAX_UDP_PAYLOAD_SIZE);
+
+    // The messages sent afterwards are still delivered.
+    assert_eq!(*received.lock().unwrap(), vec![Bytes::from("small")]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
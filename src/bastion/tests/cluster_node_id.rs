@@ -0,0 +1,86 @@
+#![cfg(all(feature = "distributed", feature = "testkit"))]
+
+mod common;
+
+use bastion::prelude::*;
+use bastion::transport::{MockNetwork, MockTransport};
+use common::wait_until;
+use futures_timer::Delay;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_cluster_node_id() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_cluster_node_id() {
+        super::run()
+    }
+}
+
+// Starts a member which greets the other one once it knows it and
+// records the node ids of the members it receives greetings from.
+fn member(transport: MockTransport, peer: Uuid, received: Arc<Mutex<Vec<(Uuid, Option<Uuid>)>>>) {
+    Bastion::distributed(transport, move |dctx| {
+        let received = received.clone();
+        async move {
+            while dctx.members().is_empty() {
+                Delay::new(Duration::from_millis(10)).await;
+            }
+            dctx.tell(&peer, "hello".to_string()).unwrap();
+
+            loop {
+                let msg = dctx.recv().await?;
+                let sender = (msg.member(), msg.path().host_key());
+                received.lock().unwrap().push(sender);
+            }
+        }
+    })
+    .expect("Couldn't start the member.");
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let first_id = Uuid::from_u128(1);
+    let second_id = Uuid::from_u128(2);
+
+    let network = MockNetwork::new();
+    let first = network.join_with_id(first_id);
+    let second = network.join_with_id(second_id);
+    assert_eq!(first.node_id(), first_id);
+    assert_eq!(second.node_id(), second_id);
+
+    let received_by_first = Arc::new(Mutex::new(Vec::new()));
+    let received_by_second = Arc::new(Mutex::new(Vec::new()));
+    member(first, second_id, received_by_first.clone());
+    member(second, first_id, received_by_second.clone());
+
+    assert!(wait_until(|| {
+        !received_by_first.lock().unwrap().is_empty()
+            && !received_by_second.lock().unwrap().is_empty()
+    }));
+
+    // Every member received the greeting of the other one, from the
+    // node id it was configured with.
+    assert_eq!(
+        *received_by_first.lock().unwrap(),
+        vec![(second_id, Some(second_id))]
+    );
+    assert_eq!(
+        *received_by_second.lock().unwrap(),
+        vec![(first_id, Some(first_id))]
+    );
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
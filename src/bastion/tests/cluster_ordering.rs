@@ -0,0 +1,107 @@
+#![cfg(all(feature = "distributed", feature = "testkit"))]
+
+mod common;
+
+use bastion::prelude::*;
+use bastion::transport::MockNetwork;
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_cluster_ordering() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_cluster_ordering() {
+        super::run()
+    }
+}
+
+const SENT: usize = 20;
+
+// A network delaying every payload by up to 50ms and handing the
+// payloads received at the same time in a random order.
+fn reordering_network() -> MockNetwork {
+    MockNetwork::with_seed(3)
+        .with_delay(Duration::from_millis(0), Duration::from_millis(50))
+        .with_reordering(true)
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let sent = (0..SENT).map(|i| i.to_string()).collect::<Vec<_>>();
+
+    // Without ordered delivery, the payloads are received out of order.
+    let network = reordering_network();
+    let sender = network.join();
+    let receiver = network.join();
+    for payload in sent.iter() {
+        sender.send_payload(receiver.node_id(), payload.clone());
+    }
+    thread::sleep(Duration::from_millis(100));
+    let received = receiver
+        .try_recv_events()
+        .into_iter()
+        .filter_map(|(_, event)| match event {
+            ArtilleryMemberEvent::Payload(_, payload) => Some(payload),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(received.len(), SENT);
+    assert_ne!(received, sent);
+
+    let network = reordering_network();
+    let ordered = OrderedDelivery::new().with_gap_timeout(Duration::from_secs(2));
+    let sender = network.join();
+    let receiver = network.join();
+    let receiver_id = receiver.node_id();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_ref = received.clone();
+    let config = ClusterConfig::from(receiver).with_ordered_delivery(ordered.clone());
+    Bastion::distributed(config, move |dctx| {
+        let received = received_ref.clone();
+        async move {
+            loop {
+                let msg = dctx.recv().await?;
+                let payload: String = msg.extract().downcast().unwrap();
+                received.lock().unwrap().push(payload);
+            }
+        }
+    })
+    .expect("Couldn't start the receiver.");
+
+    let to_send = sent.clone();
+    let config = ClusterConfig::from(sender).with_ordered_delivery(ordered);
+    Bastion::distributed(config, move |dctx| {
+        let to_send = to_send.clone();
+        async move {
+            for payload in to_send {
+                dctx.tell(&receiver_id, payload).unwrap();
+            }
+
+            loop {
+                dctx.recv().await?;
+            }
+        }
+    })
+    .expect("Couldn't start the sender.");
+
+    assert!(wait_until(|| received.lock().unwrap().len() >= SENT));
+
+    // The application sees the payloads in the order they were sent.
+    assert_eq!(*received.lock().unwrap(), sent);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,93 @@
+#![cfg(all(feature = "distributed", feature = "testkit"))]
+
+mod common;
+
+use bastion::prelude::*;
+use bastion::transport::{MockNetwork, MockTransport};
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_cluster_ordering_restart() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_cluster_ordering_restart() {
+        super::run()
+    }
+}
+
+const SENT: usize = 10;
+
+// Starts a sender telling the given payloads to the receiver, then
+// stopping.
+fn start_sender(transport: MockTransport, receiver_id: Uuid, payloads: Vec<String>) {
+    let config = ClusterConfig::from(transport).with_ordered_delivery(OrderedDelivery::new());
+    Bastion::distributed(config, move |dctx| {
+        let payloads = payloads.clone();
+        async move {
+            for payload in payloads {
+                dctx.tell(&receiver_id, payload).unwrap();
+            }
+
+            Ok(())
+        }
+    })
+    .expect("Couldn't start the sender.");
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let network = MockNetwork::new();
+    let sender = network.join();
+    let receiver = network.join();
+    let (sender_id, receiver_id) = (sender.node_id(), receiver.node_id());
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_ref = received.clone();
+    let config = ClusterConfig::from(receiver).with_ordered_delivery(OrderedDelivery::new());
+    Bastion::distributed(config, move |dctx| {
+        let received = received_ref.clone();
+        async move {
+            loop {
+                let msg = dctx.recv().await?;
+                let payload: String = msg.extract().downcast().unwrap();
+                received.lock().unwrap().push(payload);
+            }
+        }
+    })
+    .expect("Couldn't start the receiver.");
+
+    let before = (0..SENT)
+        .map(|i| format!("before {}", i))
+        .collect::<Vec<_>>();
+    start_sender(sender.clone(), receiver_id, before.clone());
+    assert!(wait_until(|| received.lock().unwrap().len() == SENT));
+    assert_eq!(*received.lock().unwrap(), before);
+
+    // The sender restarts, numbering its payloads from zero again...
+    network.disconnect(sender_id);
+    network.reconnect(sender_id);
+    let after = (0..SENT)
+        .map(|i| format!("after {}", i))
+        .collect::<Vec<_>>();
+    start_sender(sender, receiver_id, after.clone());
+
+    // ...and the receiver doesn't mistake them for the payloads it
+    // already delivered.
+    assert!(wait_until(|| received.lock().unwrap().len() == 2 * SENT));
+    let expected = before.into_iter().chain(after).collect::<Vec<_>>();
+    assert_eq!(*received.lock().unwrap(), expected);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,111 @@
+#![cfg(all(feature = "distributed", feature = "testkit"))]
+
+mod common;
+
+use bastion::prelude::*;
+use bastion::transport::{MockNetwork, MockTransport};
+use common::wait_until;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use uuid::Uuid;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_cluster_reconnect() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_cluster_reconnect() {
+        super::run()
+    }
+}
+
+type Contexts = Arc<Mutex<HashMap<Uuid, Arc<DistributedContext>>>>;
+
+fn node(transport: MockTransport, contexts: Contexts, received: Arc<Mutex<Vec<String>>>) {
+    // Without being probed again, the peer would be removed after
+    // about 1.5 seconds.
+    let reconnect = ReconnectPolicy::new()
+        .with_max_attempts(4)
+        .with_backoff(Duration::from_millis(100), Duration::from_millis(800));
+    let config = ClusterConfig::from(transport).with_reconnect(reconnect);
+    Bastion::distributed(config, move |dctx| {
+        let contexts = contexts.clone();
+        let received = received.clone();
+        async move {
+            contexts
+                .lock()
+                .unwrap()
+                .insert(dctx.current(), dctx.clone());
+            loop {
+                let msg = dctx.recv().await?;
+                let payload: String = msg.extract().downcast().unwrap();
+                received.lock().unwrap().push(payload);
+            }
+        }
+    })
+    .expect("Couldn't start a cluster node.");
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let network = MockNetwork::new();
+    let (first, second) = (network.join(), network.join());
+    let (first_id, second_id) = (first.node_id(), second.node_id());
+
+    let contexts: Contexts = Arc::new(Mutex::new(HashMap::new()));
+    let received = Arc::new(Mutex::new(Vec::new()));
+    node(first, contexts.clone(), Arc::new(Mutex::new(Vec::new())));
+    node(second, contexts.clone(), received.clone());
+
+    let context = |id: &Uuid| contexts.lock().unwrap().get(id).cloned();
+    assert!(
+        wait_until(|| context(&first_id).map_or(false, |dctx| dctx.members().len() == 1)),
+        "the cluster didn't form"
+    );
+    let dctx = context(&first_id).unwrap();
+
+    // The link drops...
+    network.disconnect(second_id);
+    assert!(
+        wait_until(|| dctx.is_isolated()),
+        "the link drop wasn't noticed"
+    );
+
+    // ...and is restored without the first node being told, which
+    // only notices it by probing the second one again.
+    network.restore(second_id);
+    assert!(
+        wait_until(|| !dctx.is_isolated()),
+        "the restored link wasn't noticed"
+    );
+
+    // The peer was retained, even once it would have been removed
+    // if it wasn't probed again.
+    thread::sleep(Duration::from_secs(2));
+    let members = dctx
+        .members()
+        .iter()
+        .map(|member| member.host_key())
+        .collect::<Vec<_>>();
+    assert_eq!(members, vec![second_id]);
+    assert!(!dctx.is_isolated());
+
+    dctx.tell(&second_id, "hello".to_string()).unwrap();
+    assert!(
+        wait_until(|| *received.lock().unwrap() == vec!["hello".to_string()]),
+        "the message wasn't received"
+    );
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,123 @@
+#![cfg(all(feature = "distributed", feature = "testkit"))]
+
+mod common;
+
+use bastion::prelude::*;
+use bastion::transport::{MockNetwork, MockTransport};
+use common::{wait_for, wait_until};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_cluster_rejoin() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_cluster_rejoin() {
+        super::run()
+    }
+}
+
+const NODES: usize = 3;
+
+type Contexts = Arc<Mutex<HashMap<Uuid, Arc<DistributedContext>>>>;
+
+fn node(transport: MockTransport, contexts: Contexts) {
+    let config = ClusterConfig::from(transport).with_tag("role", "worker");
+    Bastion::distributed(config, move |dctx| {
+        let contexts = contexts.clone();
+        async move {
+            contexts
+                .lock()
+                .unwrap()
+                .insert(dctx.current(), dctx.clone());
+            loop {
+                dctx.recv().await?;
+            }
+        }
+    })
+    .expect("Couldn't start a cluster node.");
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let network = MockNetwork::new();
+    let transports: Vec<MockTransport> = (0..NODES).map(|_| network.join()).collect();
+    let ids: Vec<Uuid> = transports.iter().map(MockTransport::node_id).collect();
+
+    let contexts: Contexts = Arc::new(Mutex::new(HashMap::new()));
+    for transport in transports {
+        node(transport, contexts.clone());
+    }
+
+    let context = |id: &Uuid| contexts.lock().unwrap().get(id).cloned();
+    // The sorted node ids of the members every node sees.
+    let views = || {
+        ids.iter()
+            .map(|id| {
+                let mut members = context(id)
+                    .map(|dctx| dctx.members())
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|member| member.host_key())
+                    .collect::<Vec<_>>();
+                members.sort();
+                members
+            })
+            .collect::<Vec<_>>()
+    };
+    let consistent = || {
+        views().iter().zip(ids.iter()).all(|(members, id)| {
+            let mut expected = ids
+                .iter()
+                .filter(|other| *other != id)
+                .copied()
+                .collect::<Vec<_>>();
+            expected.sort();
+            *members == expected
+        })
+    };
+    assert!(wait_until(consistent), "the cluster didn't form");
+
+    let mut events = Bastion::event_stream();
+    let lone = ids[0];
+    let dctx = context(&lone).unwrap();
+    network.partition(&[lone]);
+    assert!(
+        wait_until(|| dctx.is_isolated()),
+        "the node wasn't isolated"
+    );
+    assert!(!dctx.has_quorum());
+
+    network.heal();
+    assert!(wait_for(&mut events, |event| match event {
+        SystemEvent::Rejoined { node, peers } => *node == lone && *peers == NODES - 1,
+        _ => false,
+    }));
+    assert!(!dctx.is_isolated());
+    assert!(dctx.has_quorum());
+
+    // Every node sees every other one exactly once...
+    assert!(wait_until(consistent), "{:?}", views());
+    // ...and the other nodes know about the metadata it re-announced.
+    let has_role = |id: &Uuid| {
+        context(id)
+            .and_then(|dctx| dctx.metadata_of(&lone))
+            .map_or(false, |metadata| {
+                metadata.get("role").map(String::as_str) == Some("worker")
+            })
+    };
+    assert!(wait_until(|| ids[1..].iter().all(|id| has_role(id))));
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
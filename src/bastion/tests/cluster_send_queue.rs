@@ -0,0 +1,108 @@
+#![cfg(all(feature = "distributed", feature = "metrics", feature = "testkit"))]
+
+mod common;
+
+use bastion::prelude::*;
+use bastion::transport::MockNetwork;
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_cluster_send_queue() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_cluster_send_queue() {
+        super::run()
+    }
+}
+
+const PAYLOADS: usize = 50;
+const SLOW_CAPACITY: usize = 2;
+const QUEUE_CAPACITY: usize = 8;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let network = MockNetwork::new();
+    let sender = network.join();
+    let fast = network.join();
+    // The slow peer never receives the payloads sent to it, so that
+    // the network can't carry more than a few of them.
+    let slow = network.join();
+    let (fast_id, slow_id) = (fast.node_id(), slow.node_id());
+    network.throttle(slow_id, SLOW_CAPACITY);
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_ref = received.clone();
+    Bastion::distributed(fast, move |dctx| {
+        let received = received_ref.clone();
+        async move {
+            loop {
+                let msg = dctx.recv().await?;
+                let payload: String = msg.extract().downcast().unwrap();
+                received.lock().unwrap().push(payload);
+            }
+        }
+    })
+    .expect("Couldn't start the fast peer.");
+
+    let queue = OutboundQueue::new(QUEUE_CAPACITY).with_overflow(OutboundOverflow::DropOldest);
+    let config = ClusterConfig::from(sender).with_outbound_queue(queue);
+    let stats = Arc::new(Mutex::new(None));
+    let stats_ref = stats.clone();
+    Bastion::distributed(config, move |dctx| {
+        let stats = stats_ref.clone();
+        async move {
+            for i in 0..PAYLOADS {
+                dctx.tell(&fast_id, i.to_string()).unwrap();
+                dctx.tell(&slow_id, i.to_string()).unwrap();
+            }
+
+            *stats.lock().unwrap() = Some((
+                (
+                    dctx.outbound_queued(&slow_id),
+                    dctx.outbound_dropped(&slow_id),
+                ),
+                (
+                    dctx.outbound_queued(&fast_id),
+                    dctx.outbound_dropped(&fast_id),
+                ),
+            ));
+
+            loop {
+                dctx.recv().await?;
+            }
+        }
+    })
+    .expect("Couldn't start the sender.");
+
+    assert!(wait_until(|| stats.lock().unwrap().is_some()));
+    let (slow_stats, fast_stats) = stats.lock().unwrap().take().unwrap();
+    // The slow peer's queue is full, the payloads sent to it after the
+    // network and its queue were both full having been dropped...
+    assert_eq!(
+        slow_stats,
+        (
+            QUEUE_CAPACITY,
+            (PAYLOADS - SLOW_CAPACITY - QUEUE_CAPACITY) as u64
+        )
+    );
+    // ...while the fast peer's queue stayed empty and it received
+    // every payload.
+    assert_eq!(fast_stats, (0, 0));
+
+    assert!(wait_until(|| received.lock().unwrap().len() == PAYLOADS));
+    let expected = (0..PAYLOADS).map(|i| i.to_string()).collect::<Vec<_>>();
+    assert_eq!(*received.lock().unwrap(), expected);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,93 @@
+#![cfg(all(feature = "distributed", feature = "testkit"))]
+
+mod common;
+
+use bastion::prelude::*;
+use bastion::transport::MockNetwork;
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_cluster_unknown_member() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_cluster_unknown_member() {
+        super::run()
+    }
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let network = MockNetwork::new();
+    let sender = network.join();
+    let receiver = network.join();
+    let receiver_id = receiver.node_id();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_ref = received.clone();
+    Bastion::distributed(receiver, move |dctx| {
+        let received = received_ref.clone();
+        async move {
+            loop {
+                let msg = dctx.recv().await?;
+                let payload: String = msg.extract().downcast().unwrap();
+                received.lock().unwrap().push(payload);
+            }
+        }
+    })
+    .expect("Couldn't start the receiver.");
+
+    let context = Arc::new(Mutex::new(None));
+    let context_ref = context.clone();
+    Bastion::distributed(sender, move |dctx| {
+        *context_ref.lock().unwrap() = Some(dctx.clone());
+        async move {
+            loop {
+                dctx.recv().await?;
+            }
+        }
+    })
+    .expect("Couldn't start the sender.");
+
+    assert!(wait_until(|| {
+        context
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map_or(false, |dctx| !dctx.members().is_empty())
+    }));
+    let dctx = context
+        .lock()
+        .unwrap()
+        .clone()
+        .expect("The sender didn't start.");
+
+    // The message is handed back instead of being silently dropped.
+    let unknown = Uuid::new_v4();
+    match dctx.try_tell(&unknown, "lost".to_string()) {
+        Err(TellError::UnknownMember { msg, member }) => {
+            assert_eq!(msg, "lost");
+            assert_eq!(member, unknown);
+        }
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(()) => panic!("the message was sent to an unknown member"),
+    }
+
+    // The known members are still sent messages.
+    dctx.try_tell(&receiver_id, "found".to_string()).unwrap();
+    assert!(wait_until(|| !received.lock().unwrap().is_empty()));
+    assert_eq!(*received.lock().unwrap(), vec!["found".to_string()]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
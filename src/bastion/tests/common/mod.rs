@@ -0,0 +1,81 @@
+// Helpers shared by the integration tests, each of which only uses
+// some of them.
+#![allow(dead_code)]
+
+use bastion::prelude::*;
+use futures::prelude::*;
+use futures_timer::Delay;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// How long the helpers wait before giving up.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+// Waits until the condition holds, for up to `TIMEOUT`, returning
+// whether it held.
+#[must_use]
+pub fn wait_until<F>(condition: F) -> bool
+where
+    F: FnMut() -> bool,
+{
+    wait_until_within(TIMEOUT, condition)
+}
+
+// Waits until the condition holds, for up to `timeout`, returning
+// whether it held.
+#[must_use]
+pub fn wait_until_within<F>(timeout: Duration, mut condition: F) -> bool
+where
+    F: FnMut() -> bool,
+{
+    let deadline = Instant::now() + timeout;
+    while !condition() {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    true
+}
+
+// Waits for an event matching the predicate to be emitted, for up
+// to `TIMEOUT`, returning whether one was.
+#[must_use]
+pub fn wait_for<P>(events: &mut EventStream, mut predicate: P) -> bool
+where
+    P: FnMut(&SystemEvent) -> bool,
+{
+    run!(async {
+        let found = async {
+            while let Some(event) = events.next().await {
+                if predicate(&event) {
+                    return true;
+                }
+            }
+
+            false
+        };
+
+        let timeout = Delay::new(TIMEOUT).map(|_| false);
+
+        future::select(found.boxed_local(), timeout.boxed_local())
+            .await
+            .factor_first()
+            .0
+    })
+}
+
+// The sender might still hold the message for a short while after it
+// was delivered.
+pub async fn unwrap(mut raw_message: Arc<SignedMessage>) -> SignedMessage {
+    loop {
+        match Arc::try_unwrap(raw_message) {
+            Ok(message) => return message,
+            Err(raw) => raw_message = raw,
+        }
+
+        Delay::new(Duration::from_millis(1)).await;
+    }
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{wait_for, wait_until};
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_local_storage() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_local_storage() {
+        super::run()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Counter(usize);
+
+fn wait_for_restart(events: &mut EventStream) {
+    let restarted = wait_for(events, |event| {
+        matches!(event, SystemEvent::ChildRestarted { .. })
+    });
+
+    assert!(restarted, "the child was never restarted");
+}
+
+fn wait_for_reads(reads: &Mutex<Vec<Option<usize>>>, count: usize) -> Vec<Option<usize>> {
+    assert!(wait_until(|| reads.lock().unwrap().len() >= count));
+
+    reads.lock().unwrap().clone()
+}
+
+fn run() {
+    Bastion::init();
+    let mut events = Bastion::event_stream();
+    Bastion::start();
+
+    let reads = Arc::new(Mutex::new(Vec::new()));
+    let reads_inner = reads.clone();
+    let children = Bastion::children(move |children| {
+        let reads = reads_inner.clone();
+        children.with_exec(move |mut ctx: BastionContext| {
+            let reads = reads.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        ref msg: &'static str => {
+                            match *msg {
+                                "set" => {
+                                    assert_eq!(ctx.local_set(Counter(42)), None);
+                                }
+                                "get" => {
+                                    let read = ctx.local_get::<Counter>().map(|counter| counter.0);
+                                    reads.lock().unwrap().push(read);
+                                }
+                                "fault" => return Err(()),
+                                _ => unreachable!(),
+                            }
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    children.broadcast("set").unwrap();
+    children.broadcast("get").unwrap();
+    assert_eq!(wait_for_reads(&reads, 1), vec![Some(42)]);
+
+    children.broadcast("fault").unwrap();
+    wait_for_restart(&mut events);
+
+    children.broadcast("get").unwrap();
+    assert_eq!(wait_for_reads(&reads, 2), vec![Some(42), None]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_context_path() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_context_path() {
+        super::run()
+    }
+}
+
+const REDUNDANCY: usize = 3;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // The path, id and index every element reports.
+    let started = Arc::new(Mutex::new(Vec::new()));
+    let started_ref = started.clone();
+    let supervisor = Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
+    let children = supervisor
+        .children(move |children| {
+            children
+                .with_redundancy(REDUNDANCY)
+                .with_exec(move |ctx: BastionContext| {
+                    let started = started_ref.clone();
+                    async move {
+                        let path = ctx.path().clone();
+                        let id = ctx.current().id().clone();
+                        started.lock().unwrap().push((path, id, ctx.index()));
+
+                        Ok(())
+                    }
+                })
+        })
+        .expect("Couldn't create the children group.");
+
+    assert!(wait_until(|| started.lock().unwrap().len() == REDUNDANCY));
+    let mut started = started.lock().unwrap().clone();
+    started.sort_by_key(|(_, _, index)| *index);
+
+    let indices = started
+        .iter()
+        .map(|(_, _, index)| *index)
+        .collect::<Vec<_>>();
+    assert_eq!(indices, (0..REDUNDANCY).collect::<Vec<_>>());
+
+    for (path, id, _) in started.iter() {
+        // The path is the one of the element itself...
+        assert!(path.is_local());
+        assert!(path.elem().as_ref().unwrap().is_child());
+        assert_eq!(path.id(), id);
+        // ...within its children group...
+        let group = path.group_path();
+        assert_eq!(&group, &**children.path());
+        assert_eq!(group.id(), children.id());
+        // ...supervised by the supervisor it was created by.
+        let scope = group.group_path();
+        assert!(scope.elem().as_ref().unwrap().is_supervisor());
+        assert_eq!(scope.id(), supervisor.id());
+    }
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
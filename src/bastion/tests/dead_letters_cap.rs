@@ -0,0 +1,58 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_dead_letters_cap() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_dead_letters_cap() {
+        super::run()
+    }
+}
+
+const MAX_ENTRIES: usize = 5;
+const MESSAGES: usize = 8;
+
+fn run() {
+    Bastion::init();
+    Bastion::configure_dead_letters(MAX_ENTRIES, Duration::from_secs(60));
+    Bastion::start();
+
+    // Nobody is part of the group, so every message is dead-lettered.
+    Bastion::spawn(|ctx: BastionContext| async move {
+        for i in 0..MESSAGES {
+            ctx.broadcast_message(BroadcastTarget::Group("nobody".to_string()), i);
+        }
+
+        Ok(())
+    })
+    .expect("Couldn't create the children group.");
+
+    let evicted = (MESSAGES - MAX_ENTRIES) as u64;
+    assert!(wait_until(|| Bastion::dropped_dead_letters() == evicted));
+
+    // The oldest dead letters were evicted...
+    assert_eq!(Bastion::dropped_dead_letters(), evicted);
+    assert_eq!(Bastion::dead_letters_count(), MAX_ENTRIES);
+
+    // ...while the most recent ones are kept, in order.
+    let kept = Bastion::drain_dead_letters()
+        .iter()
+        .filter_map(|dead_letter| dead_letter.peek::<usize>().copied())
+        .collect::<Vec<_>>();
+    assert_eq!(kept, (MESSAGES - MAX_ENTRIES..MESSAGES).collect::<Vec<_>>());
+    assert_eq!(Bastion::dead_letters_count(), 0);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
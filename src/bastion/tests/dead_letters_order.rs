@@ -0,0 +1,82 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_dead_letters_order() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_dead_letters_order() {
+        super::run()
+    }
+}
+
+const MESSAGES: usize = 50;
+
+// Dead-letters the messages of the given range, alternating between
+// numbers and text, by broadcasting them to a group nobody is part
+// of.
+fn dead_letter(range: std::ops::Range<usize>) {
+    Bastion::spawn(move |ctx: BastionContext| {
+        let range = range.clone();
+        async move {
+            for i in range {
+                let target = BroadcastTarget::Group("nobody".to_string());
+                if i % 2 == 0 {
+                    ctx.broadcast_message(target, i);
+                } else {
+                    ctx.broadcast_message(target, i.to_string());
+                }
+            }
+
+            Ok(())
+        }
+    })
+    .expect("Couldn't create the children group.");
+}
+
+// The drained dead letters, as the index they were sent with.
+fn drain() -> Vec<usize> {
+    Bastion::drain_dead_letters()
+        .iter()
+        .map(|dead_letter| {
+            if let Some(i) = dead_letter.peek::<usize>() {
+                *i
+            } else if let Some(i) = dead_letter.peek::<String>() {
+                i.parse().unwrap()
+            } else {
+                panic!("unexpected dead letter: {:?}", dead_letter);
+            }
+        })
+        .collect()
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::configure_dead_letters(2 * MESSAGES, Duration::from_secs(60));
+    Bastion::start();
+
+    dead_letter(0..MESSAGES);
+    assert!(wait_until(|| Bastion::dead_letters_count() == MESSAGES));
+    assert_eq!(drain(), (0..MESSAGES).collect::<Vec<_>>());
+    assert_eq!(Bastion::dead_letters_count(), 0);
+
+    // The dead letters received after a drain are drained in order
+    // too.
+    dead_letter(MESSAGES..2 * MESSAGES);
+    assert!(wait_until(|| Bastion::dead_letters_count() == MESSAGES));
+    assert_eq!(drain(), (MESSAGES..2 * MESSAGES).collect::<Vec<_>>());
+    assert_eq!(Bastion::dropped_dead_letters(), 0);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{wait_for, wait_until};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_deadletter_replay() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_deadletter_replay() {
+        super::run()
+    }
+}
+
+const MISSED: [&str; 3] = ["first", "second", "third"];
+
+fn run() {
+    Bastion::init();
+    let mut events = Bastion::event_stream();
+    Bastion::start();
+
+    // Only the first element crashes.
+    let crashed = Arc::new(AtomicBool::new(false));
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let crashed_ref = crashed.clone();
+    let received_ref = received.clone();
+    let supervisor = Bastion::supervisor(|sp| {
+        // Leaves time to send messages while the element is down.
+        sp.with_restart_strategy(RestartStrategy::default().with_actor_restart_strategy(
+            ActorRestartStrategy::LinearBackOff {
+                timeout: Duration::from_millis(300),
+            },
+        ))
+    })
+    .expect("Couldn't create the supervisor.");
+    let children = supervisor
+        .children(move |children| {
+            let crashed = crashed_ref.clone();
+            let received = received_ref.clone();
+            children
+                .with_deadletter_replay(Duration::from_secs(5))
+                .with_exec(move |ctx: BastionContext| {
+                    let crashed = crashed.clone();
+                    let received = received.clone();
+                    async move {
+                        loop {
+                            msg! { ctx.recv().await?,
+                                ref msg: &'static str if *msg == "crash" => {
+                                    if !crashed.swap(true, Ordering::SeqCst) {
+                                        return Err(());
+                                    }
+                                };
+                                ref msg: &'static str => {
+                                    received.lock().unwrap().push(*msg);
+                                };
+                                _: _ => ();
+                            }
+                        }
+                    }
+                })
+        })
+        .expect("Couldn't create the children group.");
+
+    children.broadcast("crash").unwrap();
+    let faulted = wait_for(&mut events, |event| {
+        matches!(event, SystemEvent::ChildFaulted { .. })
+    });
+    assert!(faulted, "the child never faulted");
+
+    // The messages sent while the element is down are dead-lettered...
+    for msg in MISSED.iter() {
+        children.broadcast(*msg).unwrap();
+    }
+    let mut dead_letters = 0;
+    let dead_lettered = wait_for(&mut events, |event| {
+        if let SystemEvent::DeadLetter { .. } = event {
+            dead_letters += 1;
+        }
+        dead_letters == MISSED.len()
+    });
+    assert!(dead_lettered, "the messages weren't dead-lettered");
+    assert!(received.lock().unwrap().is_empty());
+
+    // ...and replayed in order once it is restarted.
+    let restarted = wait_for(&mut events, |event| {
+        matches!(event, SystemEvent::ChildRestarted { .. })
+    });
+    assert!(restarted, "the child never restarted");
+    assert!(wait_until(|| received.lock().unwrap().len() == MISSED.len()));
+    assert_eq!(*received.lock().unwrap(), MISSED.to_vec());
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
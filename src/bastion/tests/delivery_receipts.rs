@@ -0,0 +1,120 @@
+use bastion::prelude::*;
+use futures::prelude::*;
+use futures_timer::Delay;
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_delivery_receipts() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_delivery_receipts() {
+        super::run()
+    }
+}
+
+const REDUNDANCY: usize = 3;
+
+// Collects the paths of the receipts of the broadcasts to the given
+// group until `expected` were received or the timeout elapsed.
+fn receipts(
+    events: &mut EventStream,
+    group: &BastionId,
+    expected: usize,
+    timeout: Duration,
+) -> Vec<String> {
+    run!(async {
+        let mut paths = Vec::new();
+        let collect = async {
+            while let Some(event) = events.next().await {
+                if let SystemEvent::BroadcastDelivered {
+                    group: id,
+                    broadcast,
+                    path,
+                    ..
+                } = event
+                {
+                    if &id == group {
+                        assert_eq!(broadcast, 1);
+                        paths.push(path.to_string());
+                        if paths.len() == expected {
+                            break;
+                        }
+                    }
+                }
+            }
+        };
+
+        future::select(collect.boxed(), Delay::new(timeout)).await;
+        paths
+    })
+}
+
+fn run() {
+    Bastion::init();
+    let mut events = Bastion::event_stream();
+    Bastion::start();
+
+    let children = Bastion::children(|children| {
+        children
+            .with_redundancy(REDUNDANCY)
+            .with_delivery_receipts()
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    // The only element of this group stops right away, leaving it
+    // without elements.
+    let empty = Bastion::children(|children| {
+        children
+            .with_delivery_receipts()
+            .with_exec(|_: BastionContext| async move { Ok(()) })
+    })
+    .expect("Couldn't create the children group.");
+    let stopped = empty.elems()[0].id().clone();
+    run!(async {
+        while let Some(event) = events.next().await {
+            if let SystemEvent::ChildStopped { id } = event {
+                if id == stopped {
+                    break;
+                }
+            }
+        }
+    });
+    thread::sleep(Duration::from_millis(100));
+
+    // A receipt is emitted for every element of the group...
+    children.broadcast("A message.").unwrap();
+    let mut delivered = receipts(
+        &mut events,
+        children.id(),
+        REDUNDANCY,
+        Duration::from_secs(5),
+    );
+    let mut expected = children
+        .elems()
+        .iter()
+        .map(|child| child.path().to_string())
+        .collect::<Vec<_>>();
+    delivered.sort();
+    expected.sort();
+    assert_eq!(delivered, expected);
+
+    // ...and none for a group without elements.
+    empty.broadcast("A message.").unwrap();
+    let delivered = receipts(&mut events, empty.id(), 1, Duration::from_millis(200));
+    assert!(delivered.is_empty());
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
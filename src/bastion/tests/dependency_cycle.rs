@@ -0,0 +1,53 @@
+use bastion::prelude::*;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_dependency_cycle() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_dependency_cycle() {
+        super::run()
+    }
+}
+
+fn run() {
+    Bastion::init();
+
+    let storage = Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
+    let cache = storage
+        .supervisor(|sp| sp)
+        .expect("Couldn't create the supervisor.");
+    let api = Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
+
+    // The cache is supervised by the storage, which has to outlive
+    // it, but the storage is declared as depending on the api, which
+    // depends on the cache.
+    api.depends_on(&cache);
+    storage.depends_on(&api);
+
+    let cycle = match Bastion::try_start() {
+        Err(StartError::DependencyCycle(cycle)) => cycle,
+        Ok(()) => panic!("The system started despite the cycle."),
+    };
+
+    let mut participants = cycle
+        .iter()
+        .map(|path| path.id().clone())
+        .collect::<Vec<_>>();
+    participants.sort_by_key(ToString::to_string);
+    let mut expected = vec![storage.id().clone(), cache.id().clone(), api.id().clone()];
+    expected.sort_by_key(ToString::to_string);
+    assert_eq!(participants, expected);
+
+    // The error names every participant.
+    let err = StartError::DependencyCycle(cycle).to_string();
+    for id in &expected {
+        assert!(err.contains(&id.to_string()), "{}", err);
+    }
+}
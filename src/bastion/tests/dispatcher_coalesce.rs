@@ -0,0 +1,92 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_dispatcher_coalesce() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_dispatcher_coalesce() {
+        super::run()
+    }
+}
+
+const REPEATS: usize = 10;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let started = Arc::new(AtomicBool::new(false));
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let started_ref = started.clone();
+    let received_ref = received.clone();
+    Bastion::children(move |children| {
+        let started = started_ref.clone();
+        let received = received_ref.clone();
+        children
+            .with_redundancy(3)
+            .with_dispatcher(
+                Dispatcher::with_type(DispatcherType::Named("config".to_string()))
+                    .with_coalesce(Duration::from_secs(5), |msg: &SignedMessage| {
+                        msg.peek::<&'static str>().copied()
+                    }),
+            )
+            .with_exec(move |ctx: BastionContext| {
+                let started = started.clone();
+                let received = received.clone();
+                async move {
+                    started.store(true, Ordering::SeqCst);
+
+                    loop {
+                        msg! { ctx.recv().await?,
+                            raw_message: Arc<SignedMessage> => {
+                                if let Some(key) = raw_message.peek::<&'static str>() {
+                                    received.lock().unwrap().push(*key);
+                                }
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    assert!(wait_until(|| started.load(Ordering::SeqCst)));
+
+    Bastion::spawn(|ctx: BastionContext| async move {
+        let target = || BroadcastTarget::Group("config".to_string());
+        for _ in 0..REPEATS {
+            ctx.broadcast_message(target(), "config changed");
+        }
+        ctx.broadcast_message(target(), "cache flushed");
+
+        Ok(())
+    })
+    .expect("Couldn't create the sender.");
+
+    assert!(wait_until(|| received.lock().unwrap().len() >= 2));
+    // Leaves the time to the collapsed messages to show up.
+    thread::sleep(Duration::from_millis(200));
+
+    let mut received = received.lock().unwrap().clone();
+    received.sort_unstable();
+    assert_eq!(received, vec!["cache flushed", "config changed"]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
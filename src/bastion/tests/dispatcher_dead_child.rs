@@ -0,0 +1,126 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{unwrap, wait_for, wait_until};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_dispatcher_dead_child() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_dispatcher_dead_child() {
+        super::run()
+    }
+}
+
+const GROUP: &str = "dead-child";
+const REDUNDANCY: usize = 3;
+const MESSAGES: usize = 30;
+
+// The messages received by the elements of the group, with the
+// identifier of their receiver.
+type Received = Arc<Mutex<Vec<(BastionId, usize)>>>;
+
+fn run() {
+    Bastion::init();
+    let mut events = Bastion::event_stream();
+    Bastion::start();
+
+    let started = Arc::new(AtomicUsize::new(0));
+    let received: Received = Arc::new(Mutex::new(Vec::new()));
+
+    // The faulted element is only restarted after the messages were sent.
+    let restart_strategy = RestartStrategy::new(
+        RestartPolicy::Always,
+        ActorRestartStrategy::LinearBackOff {
+            timeout: Duration::from_secs(2),
+        },
+    );
+    let supervisor = Bastion::supervisor(|sp| sp.with_restart_strategy(restart_strategy))
+        .expect("Couldn't create the supervisor.");
+
+    let started_ref = started.clone();
+    let received_ref = received.clone();
+    let children = supervisor
+        .children(move |children| {
+            let started = started_ref.clone();
+            let received = received_ref.clone();
+            children
+                .with_redundancy(REDUNDANCY)
+                .with_dispatcher(Dispatcher::with_type(DispatcherType::Named(
+                    GROUP.to_string(),
+                )))
+                .with_exec(move |ctx: BastionContext| {
+                    let started = started.clone();
+                    let received = received.clone();
+                    async move {
+                        let id = ctx.current().id().clone();
+                        started.fetch_add(1, Ordering::SeqCst);
+
+                        loop {
+                            msg! { ctx.recv().await?,
+                                _msg: &'static str => {
+                                    // Dies...
+                                    return Err(());
+                                };
+                                raw_message: Arc<SignedMessage> => {
+                                    msg! { unwrap(raw_message).await,
+                                        value: usize => {
+                                            received.lock().unwrap().push((id.clone(), value));
+                                        };
+                                        _: _ => ();
+                                    }
+                                };
+                                _: _ => ();
+                            }
+                        }
+                    }
+                })
+        })
+        .expect("Couldn't create the children group.");
+    assert!(wait_until(|| started.load(Ordering::SeqCst) == REDUNDANCY));
+
+    let victim = children.elems()[0].clone();
+    victim.tell_anonymously("die").unwrap();
+
+    let faulted = wait_for(&mut events, |event| match event {
+        SystemEvent::ChildFaulted { id, .. } => id == victim.id(),
+        _ => false,
+    });
+    assert!(faulted, "the victim didn't die");
+
+    Bastion::spawn(|ctx: BastionContext| async move {
+        for value in 0..MESSAGES {
+            ctx.broadcast_message(BroadcastTarget::Group(GROUP.to_string()), value);
+        }
+
+        Ok(())
+    })
+    .expect("Couldn't create the sender.");
+
+    assert!(wait_until(|| received.lock().unwrap().len() == MESSAGES));
+
+    // No message was lost on the dead element: they all landed on the
+    // survivors.
+    let received = received.lock().unwrap();
+    let mut values = received.iter().map(|(_, value)| *value).collect::<Vec<_>>();
+    values.sort_unstable();
+    assert_eq!(values, (0..MESSAGES).collect::<Vec<_>>());
+    assert!(received.iter().all(|(id, _)| id != victim.id()));
+    let survivors = &children.elems()[1..];
+    assert!(received
+        .iter()
+        .all(|(id, _)| survivors.iter().any(|survivor| survivor.id() == id)));
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{unwrap, wait_until, wait_until_within};
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_dispatcher_rate_limit() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_dispatcher_rate_limit() {
+        super::run()
+    }
+}
+
+const MESSAGES: usize = 100;
+const PER_SECOND: u32 = 10;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let started = Arc::new(AtomicBool::new(false));
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let started_ref = started.clone();
+    let received_ref = received.clone();
+    Bastion::children(move |children| {
+        let started = started_ref.clone();
+        let received = received_ref.clone();
+        children
+            .with_dispatcher(
+                Dispatcher::with_type(DispatcherType::Named("downstream".to_string()))
+                    .with_rate_limit(NonZeroU32::new(PER_SECOND).unwrap()),
+            )
+            .with_exec(move |ctx: BastionContext| {
+                let started = started.clone();
+                let received = received.clone();
+                async move {
+                    started.store(true, Ordering::SeqCst);
+
+                    loop {
+                        msg! { ctx.recv().await?,
+                            raw_message: Arc<SignedMessage> => {
+                                msg! { unwrap(raw_message).await,
+                                    value: usize => {
+                                        received.lock().unwrap().push((value, Instant::now()));
+                                    };
+                                    _: _ => ();
+                                }
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    assert!(wait_until(|| started.load(Ordering::SeqCst)));
+
+    let sent_at = Instant::now();
+    Bastion::spawn(|ctx: BastionContext| async move {
+        for value in 0..MESSAGES {
+            ctx.broadcast_message(BroadcastTarget::Group("downstream".to_string()), value);
+        }
+
+        Ok(())
+    })
+    .expect("Couldn't create the sender.");
+
+    assert!(wait_until_within(Duration::from_secs(20), || {
+        received.lock().unwrap().len() >= MESSAGES
+    }));
+
+    let received = received.lock().unwrap();
+    let values = received.iter().map(|(value, _)| *value).collect::<Vec<_>>();
+    assert_eq!(values, (0..MESSAGES).collect::<Vec<_>>());
+
+    // A first burst of `PER_SECOND` messages is let through, then the
+    // remaining ones are delivered at `PER_SECOND` messages per second.
+    let elapsed = received.last().unwrap().1 - sent_at;
+    assert!(elapsed >= Duration::from_secs(8), "took {:?}", elapsed);
+    assert!(elapsed <= Duration::from_secs(12), "took {:?}", elapsed);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
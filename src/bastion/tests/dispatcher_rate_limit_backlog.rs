@@ -0,0 +1,137 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{unwrap, wait_until, wait_until_within};
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_dispatcher_rate_limit_backlog() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_dispatcher_rate_limit_backlog() {
+        super::run()
+    }
+}
+
+const MESSAGES: usize = 10;
+const PER_SECOND: u32 = 10;
+const BACKLOG: usize = 2;
+
+// Creates a group behind a dispatcher allowing `PER_SECOND` messages
+// per second and queuing `BACKLOG` of them, which records the messages
+// its element received.
+fn consumer(group: &str, overload: OverloadPolicy) -> Arc<Mutex<Vec<usize>>> {
+    let started = Arc::new(AtomicBool::new(false));
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let group = group.to_string();
+    let started_ref = started.clone();
+    let received_ref = received.clone();
+    Bastion::children(move |children| {
+        let started = started_ref.clone();
+        let received = received_ref.clone();
+        children
+            .with_dispatcher(
+                Dispatcher::with_type(DispatcherType::Named(group.clone())).with_rate_limit_with(
+                    NonZeroU32::new(PER_SECOND).unwrap(),
+                    BACKLOG,
+                    overload,
+                ),
+            )
+            .with_exec(move |ctx: BastionContext| {
+                let started = started.clone();
+                let received = received.clone();
+                async move {
+                    started.store(true, Ordering::SeqCst);
+
+                    loop {
+                        msg! { ctx.recv().await?,
+                            raw_message: Arc<SignedMessage> => {
+                                msg! { unwrap(raw_message).await,
+                                    value: usize => {
+                                        received.lock().unwrap().push(value);
+                                    };
+                                    _: _ => ();
+                                }
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    assert!(wait_until(|| started.load(Ordering::SeqCst)));
+    received
+}
+
+// Broadcasts `MESSAGES` messages to the group, returning how long
+// sending them took.
+fn send(group: &str) -> Duration {
+    let sent = Arc::new(Mutex::new(None));
+
+    let group = group.to_string();
+    let sent_ref = sent.clone();
+    Bastion::spawn(move |ctx: BastionContext| {
+        let group = group.clone();
+        let sent = sent_ref.clone();
+        async move {
+            let started_at = Instant::now();
+            for value in 0..MESSAGES {
+                ctx.broadcast_message(BroadcastTarget::Group(group.clone()), value);
+            }
+
+            *sent.lock().unwrap() = Some(started_at.elapsed());
+            Ok(())
+        }
+    })
+    .expect("Couldn't create the sender.");
+
+    assert!(wait_until(|| sent.lock().unwrap().is_some()));
+    let elapsed = *sent.lock().unwrap();
+    elapsed.unwrap()
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // Once the backlog is full, the sender waits for it to have room.
+    let received = consumer("backpressure", OverloadPolicy::Backpressure);
+    let elapsed = send("backpressure");
+
+    // The first message goes through at once and `BACKLOG` are
+    // queued, then the sender waits for a token for every other one.
+    let waited = Duration::from_millis(1_000 * (MESSAGES - 1 - BACKLOG) as u64 / PER_SECOND as u64);
+    assert!(elapsed >= waited * 3 / 4, "took {:?}", elapsed);
+
+    assert!(wait_until_within(Duration::from_secs(5), || {
+        received.lock().unwrap().len() >= MESSAGES
+    }));
+    assert_eq!(*received.lock().unwrap(), (0..MESSAGES).collect::<Vec<_>>());
+
+    // Once the backlog is full, the messages are dead-lettered.
+    let received = consumer("shed", OverloadPolicy::Shed);
+    Bastion::drain_dead_letters();
+    let elapsed = send("shed");
+    assert!(elapsed < Duration::from_millis(500), "took {:?}", elapsed);
+
+    assert!(wait_until(|| received.lock().unwrap().len() > BACKLOG));
+    let shed = Bastion::drain_dead_letters();
+    assert_eq!(shed.len(), MESSAGES - 1 - BACKLOG);
+    assert_eq!(*received.lock().unwrap(), (0..=BACKLOG).collect::<Vec<_>>());
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{wait_for, wait_until};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_empty_group_policy() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_empty_group_policy() {
+        super::run()
+    }
+}
+
+fn register(group: &str, policy: EmptyGroupPolicy) {
+    let dispatcher = Dispatcher::with_type(DispatcherType::Named(group.to_string()))
+        .with_empty_group_policy(policy);
+    Bastion::register_dispatcher(dispatcher).expect("Couldn't register the dispatcher.");
+}
+
+// Broadcasts `value` to the group, returning the identifier of the sender.
+fn broadcast(group: &'static str, value: usize) -> BastionId {
+    let sender = Bastion::spawn(move |ctx: BastionContext| async move {
+        ctx.broadcast_message(BroadcastTarget::Group(group.to_string()), value);
+        Ok(())
+    })
+    .expect("Couldn't create the sender.");
+
+    sender.elems()[0].id().clone()
+}
+
+fn dead_lettered_from(events: &mut EventStream, sender: &BastionId) -> bool {
+    wait_for(events, |event| match event {
+        SystemEvent::DeadLetter { sender: from } => from.id() == sender,
+        _ => false,
+    })
+}
+
+fn run() {
+    Bastion::init();
+    let mut events = Bastion::event_stream();
+    Bastion::start();
+
+    // The message is held until a child joins the group...
+    register("buffered", EmptyGroupPolicy::Buffer(Duration::from_secs(5)));
+    broadcast("buffered", 1);
+    thread::sleep(Duration::from_millis(100));
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_inner = received.clone();
+    Bastion::children(move |children| {
+        let received = received_inner.clone();
+        children
+            .with_dispatcher(Dispatcher::with_type(DispatcherType::Named(
+                "buffered".to_string(),
+            )))
+            .with_exec(move |ctx: BastionContext| {
+                let received = received.clone();
+                async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            broadcasted: Arc<SignedMessage> => {
+                                if let Some(value) = broadcasted.peek::<usize>() {
+                                    received.lock().unwrap().push(*value);
+                                }
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    // ...which then receives it.
+    assert!(wait_until(|| *received.lock().unwrap() == vec![1]));
+
+    // Without any child joining, it is dead-lettered once the timeout
+    // elapsed.
+    let timeout = Duration::from_millis(300);
+    register("unstaffed", EmptyGroupPolicy::Buffer(timeout));
+    let sent_at = Instant::now();
+    let sender = broadcast("unstaffed", 2);
+    assert!(
+        dead_lettered_from(&mut events, &sender),
+        "the buffered message wasn't dead-lettered"
+    );
+    assert!(sent_at.elapsed() >= timeout);
+
+    // It can also be dead-lettered right away.
+    register("strict", EmptyGroupPolicy::DeadLetter);
+    let sender = broadcast("strict", 3);
+    assert!(
+        dead_lettered_from(&mut events, &sender),
+        "the message wasn't dead-lettered"
+    );
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_child_faulted_event() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_child_faulted_event() {
+        super::run()
+    }
+}
+
+fn run() {
+    Bastion::init();
+    let mut events = Bastion::event_stream();
+    Bastion::start();
+
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            msg! { ctx.recv().await?,
+                _: _ => ();
+            }
+
+            Err(())
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    let child = children.elems()[0].clone();
+    child.tell_anonymously("fault").unwrap();
+
+    let expected = child.id().clone();
+    let found = wait_for(&mut events, |event| match event {
+        SystemEvent::ChildFaulted { id, parent_id, .. } if id == &expected => {
+            assert_eq!(parent_id, children.id());
+            true
+        }
+        _ => false,
+    });
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+
+    assert!(found, "no ChildFaulted event received for the child");
+}
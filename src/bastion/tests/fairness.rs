@@ -0,0 +1,116 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until_within;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_fairness() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_fairness() {
+        super::run()
+    }
+}
+
+// The amount of messages the busy element is flooded with.
+const BUSY_MESSAGES: usize = 5_000;
+// The fairness budget of the busy element.
+const BUDGET: usize = 8;
+// The amount of messages the quiet element is sent.
+const PINGS: usize = 5;
+// The maximum amount of messages the busy element is allowed to
+// handle between a message being sent to the quiet element and the
+// quiet element handling it.
+const MAX_DELAY: usize = 256;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let busy_handled = Arc::new(AtomicUsize::new(0));
+    let busy_handled_ref = busy_handled.clone();
+    let busy = Bastion::children(move |children| {
+        let busy_handled = busy_handled_ref.clone();
+        children
+            .with_fairness_budget(BUDGET)
+            .with_exec(move |ctx: BastionContext| {
+                let busy_handled = busy_handled.clone();
+                async move {
+                    loop {
+                        ctx.recv().await?;
+                        // Simulates some work without ever yielding.
+                        let start = Instant::now();
+                        while start.elapsed() < Duration::from_micros(200) {}
+                        busy_handled.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the busy group.");
+
+    // The amount of messages the busy element handled when the quiet
+    // element handled each of its messages.
+    let quiet_handled = Arc::new(Mutex::new(Vec::new()));
+    let quiet_handled_ref = quiet_handled.clone();
+    let busy_handled_ref = busy_handled.clone();
+    let quiet = Bastion::children(move |children| {
+        let quiet_handled = quiet_handled_ref.clone();
+        let busy_handled = busy_handled_ref.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let quiet_handled = quiet_handled.clone();
+            let busy_handled = busy_handled.clone();
+            async move {
+                loop {
+                    ctx.recv().await?;
+                    let handled = busy_handled.load(Ordering::SeqCst);
+                    quiet_handled.lock().unwrap().push(handled);
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the quiet group.");
+
+    let busy = &busy.elems()[0];
+    let quiet = &quiet.elems()[0];
+    for i in 0..BUSY_MESSAGES {
+        busy.tell_anonymously(i).unwrap();
+    }
+    assert!(wait_until_within(Duration::from_secs(10), || {
+        busy_handled.load(Ordering::SeqCst) > 0
+    }));
+
+    // The quiet element is sent messages while the busy one is
+    // saturated.
+    let mut sent_at = Vec::new();
+    for i in 0..PINGS {
+        sent_at.push(busy_handled.load(Ordering::SeqCst));
+        quiet.tell_anonymously(i).unwrap();
+        assert!(wait_until_within(Duration::from_secs(10), || {
+            quiet_handled.lock().unwrap().len() > i
+        }));
+    }
+
+    let quiet_handled = quiet_handled.lock().unwrap().clone();
+    assert_eq!(quiet_handled.len(), PINGS);
+    for (sent, handled) in sent_at.into_iter().zip(quiet_handled.into_iter()) {
+        assert!(
+            handled - sent <= MAX_DELAY,
+            "the quiet element waited for {} messages of the busy one",
+            handled - sent
+        );
+        assert!(handled < BUSY_MESSAGES, "the busy element was done first");
+    }
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_fault_reason() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_fault_reason() {
+        super::run()
+    }
+}
+
+const PANIC_MESSAGE: &str = "the child panicked on purpose";
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let faults = Arc::new(Mutex::new(Vec::new()));
+    let faults_ref = faults.clone();
+    let supervisor = Bastion::supervisor(move |sp| {
+        let faults = faults_ref.clone();
+        let callbacks = Callbacks::new().with_on_fault(move |id, reason| {
+            faults.lock().unwrap().push((id.clone(), reason.clone()));
+        });
+
+        sp.with_callbacks(callbacks)
+    })
+    .expect("Couldn't create the supervisor.");
+
+    let panicked = Arc::new(AtomicBool::new(false));
+    let children = supervisor
+        .children(move |children| {
+            children.with_exec(move |ctx: BastionContext| {
+                let panicked = panicked.clone();
+                async move {
+                    // Only panics the first time it runs.
+                    if !panicked.swap(true, Ordering::SeqCst) {
+                        panic!("{}", PANIC_MESSAGE);
+                    }
+
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+        })
+        .expect("Couldn't create the children group.");
+    let child = children.elems()[0].clone();
+
+    assert!(wait_until(|| !faults.lock().unwrap().is_empty()));
+
+    assert_eq!(
+        *faults.lock().unwrap(),
+        vec![(
+            child.id().clone(),
+            FaultReason::Panicked(PANIC_MESSAGE.to_string())
+        )]
+    );
+
+    // The restarted element faults too once it is killed.
+    assert!(wait_until(|| children
+        .elems()
+        .first()
+        .map_or(false, ChildRef::is_alive)));
+    let child = children.elems()[0].clone();
+    child.kill().expect("Couldn't kill the child.");
+    assert!(wait_until(|| faults.lock().unwrap().len() == 2));
+
+    let faults = faults.lock().unwrap().clone();
+    assert_eq!(faults[1], (child.id().clone(), FaultReason::Killed));
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
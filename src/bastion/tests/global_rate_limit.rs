@@ -0,0 +1,116 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{wait_until, wait_until_within};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_global_rate_limit() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_global_rate_limit() {
+        super::run()
+    }
+}
+
+const PER_SECOND: u32 = 20;
+const MESSAGES: usize = 60;
+
+// Creates a group whose element records when it received every
+// message.
+fn consumer() -> (ChildRef, Arc<Mutex<Vec<Instant>>>) {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_ref = received.clone();
+    let children = Bastion::children(move |children| {
+        let received = received_ref.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let received = received.clone();
+            async move {
+                loop {
+                    ctx.recv().await?;
+                    received.lock().unwrap().push(Instant::now());
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the consumer.");
+
+    (children.elems()[0].clone(), received)
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // The messages above the rate limit wait for it.
+    let (child, received) = consumer();
+    Bastion::set_global_rate_limit(PER_SECOND);
+
+    let sent_at = Instant::now();
+    for i in 0..MESSAGES {
+        child.tell_anonymously(i).unwrap();
+    }
+
+    assert!(wait_until_within(Duration::from_secs(10), || {
+        received.lock().unwrap().len() == MESSAGES
+    }));
+    let received = received.lock().unwrap().clone();
+    assert_eq!(received.len(), MESSAGES);
+
+    // A burst of `PER_SECOND` messages goes through at once, then the
+    // remaining ones are delivered at `PER_SECOND` messages per second.
+    let elapsed = *received.last().unwrap() - sent_at;
+    assert!(
+        elapsed >= Duration::from_millis(1_600) && elapsed <= Duration::from_secs(3),
+        "elapsed={:?}",
+        elapsed
+    );
+    let sustained = received[MESSAGES - 1] - received[PER_SECOND as usize];
+    let rate = (MESSAGES - 1 - PER_SECOND as usize) as f64 / sustained.as_secs_f64();
+    assert!(
+        rate >= PER_SECOND as f64 * 0.75 && rate <= PER_SECOND as f64 * 1.25,
+        "rate={}",
+        rate
+    );
+
+    // The messages above the rate limit are shed.
+    let (child, received) = consumer();
+    Bastion::set_global_rate_limit_with(PER_SECOND, OverloadPolicy::Shed);
+    Bastion::drain_dead_letters();
+
+    for i in 0..MESSAGES {
+        child.tell_anonymously(i).unwrap();
+    }
+
+    assert!(wait_until(|| received.lock().unwrap().len()
+        + Bastion::dead_letters_count()
+        == MESSAGES));
+    let delivered = received.lock().unwrap().len();
+    let shed = Bastion::drain_dead_letters().len();
+    assert_eq!(delivered + shed, MESSAGES);
+    assert!(delivered >= PER_SECOND as usize, "delivered={}", delivered);
+    assert!(shed > 0);
+
+    // Once cleared, the messages aren't limited anymore.
+    Bastion::clear_global_rate_limit();
+    let (child, received) = consumer();
+    let sent_at = Instant::now();
+    for i in 0..MESSAGES {
+        child.tell_anonymously(i).unwrap();
+    }
+
+    assert!(wait_until(|| received.lock().unwrap().len() == MESSAGES));
+    assert_eq!(received.lock().unwrap().len(), MESSAGES);
+    assert!(sent_at.elapsed() < Duration::from_secs(1));
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
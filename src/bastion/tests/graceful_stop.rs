@@ -0,0 +1,111 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use futures_timer::Delay;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_graceful_stop_drains_mailbox() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_graceful_stop_drains_mailbox() {
+        super::run()
+    }
+}
+
+const MESSAGES: usize = 5;
+
+// Starts a group handling the messages it receives slowly, either
+// waiting for them or polling its mailbox.
+fn group(handled: Arc<Mutex<Vec<String>>>, polling: bool) -> ChildrenRef {
+    Bastion::children(move |children| {
+        let handled_exec = handled.clone();
+        let callbacks = Callbacks::new().with_after_stop(move || {
+            handled.lock().unwrap().push("stopped".to_string());
+        });
+
+        children
+            .with_callbacks(callbacks)
+            .with_exec(move |ctx: BastionContext| {
+                let handled = handled_exec.clone();
+                async move {
+                    loop {
+                        let msg = if polling {
+                            match ctx.try_recv().await {
+                                Some(msg) => msg,
+                                None => {
+                                    Delay::new(Duration::from_millis(10)).await;
+                                    continue;
+                                }
+                            }
+                        } else {
+                            ctx.recv().await?
+                        };
+
+                        msg! { msg,
+                            ref msg: String => {
+                                // Slow enough for the messages to queue up.
+                                Delay::new(Duration::from_millis(10)).await;
+                                handled.lock().unwrap().push(msg.clone());
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.")
+}
+
+// Stops the group gracefully once it was sent the messages, checking
+// that they were all handled before it stopped, without waiting for
+// the drain timeout to elapse.
+fn check(polling: bool) {
+    let handled = Arc::new(Mutex::new(Vec::new()));
+    let children = group(handled.clone(), polling);
+
+    for i in 0..MESSAGES {
+        children
+            .broadcast(format!("message {}", i))
+            .expect("Couldn't broadcast the message.");
+    }
+
+    children
+        .graceful_stop(Duration::from_secs(60))
+        .expect("Couldn't stop the children group.");
+
+    assert!(wait_until(|| handled
+        .lock()
+        .unwrap()
+        .contains(&"stopped".to_string())));
+
+    let handled = handled.lock().unwrap().clone();
+    let stopped = handled
+        .iter()
+        .position(|msg| msg == "stopped")
+        .expect("the children group never stopped");
+    let expected = (0..MESSAGES)
+        .map(|i| format!("message {}", i))
+        .collect::<Vec<_>>();
+    assert_eq!(&handled[..stopped], &expected[..]);
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    check(false);
+    check(true);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_group_strategy() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_group_strategy() {
+        super::run()
+    }
+}
+
+const REDUNDANCY: usize = 3;
+
+// Creates a group with the given strategy whose elements count how
+// many times they started and fault when told to.
+fn group(
+    supervisor: &SupervisorRef,
+    strategy: SupervisionStrategy,
+    starts: Arc<AtomicUsize>,
+) -> ChildrenRef {
+    supervisor
+        .children(move |children| {
+            children
+                .with_redundancy(REDUNDANCY)
+                .with_strategy(strategy)
+                .with_exec(move |ctx: BastionContext| {
+                    let starts = starts.clone();
+                    async move {
+                        starts.fetch_add(1, Ordering::SeqCst);
+                        loop {
+                            msg! { ctx.recv().await?,
+                                msg: &'static str => {
+                                    assert_eq!(msg, "fault");
+                                    return Err(());
+                                };
+                                _: _ => ();
+                            }
+                        }
+                    }
+                })
+        })
+        .expect("Couldn't create the children group.")
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // The supervisor's own strategy is overridden by the one of each
+    // of its groups.
+    let supervisor = Bastion::supervisor(|sp| sp.with_strategy(SupervisionStrategy::OneForAll))
+        .expect("Couldn't create the supervisor.");
+
+    let critical_starts = Arc::new(AtomicUsize::new(0));
+    let critical = group(
+        &supervisor,
+        SupervisionStrategy::OneForAll,
+        critical_starts.clone(),
+    );
+    let best_effort_starts = Arc::new(AtomicUsize::new(0));
+    let best_effort = group(
+        &supervisor,
+        SupervisionStrategy::OneForOne,
+        best_effort_starts.clone(),
+    );
+
+    assert!(wait_until(|| {
+        critical_starts.load(Ordering::SeqCst) == REDUNDANCY
+            && best_effort_starts.load(Ordering::SeqCst) == REDUNDANCY
+    }));
+
+    // Only the faulted element of the best-effort group is restarted...
+    best_effort.elems()[0]
+        .tell_anonymously("fault")
+        .expect("Couldn't send the message.");
+    assert!(wait_until(
+        || best_effort_starts.load(Ordering::SeqCst) == REDUNDANCY + 1
+    ));
+    thread::sleep(Duration::from_millis(200));
+    assert_eq!(best_effort_starts.load(Ordering::SeqCst), REDUNDANCY + 1);
+    assert_eq!(critical_starts.load(Ordering::SeqCst), REDUNDANCY);
+
+    // ...while all the elements of the critical group are, but none
+    // of the best-effort one.
+    critical.elems()[0]
+        .tell_anonymously("fault")
+        .expect("Couldn't send the message.");
+    assert!(wait_until(
+        || critical_starts.load(Ordering::SeqCst) == 2 * REDUNDANCY
+    ));
+    thread::sleep(Duration::from_millis(200));
+    assert_eq!(critical_starts.load(Ordering::SeqCst), 2 * REDUNDANCY);
+    assert_eq!(best_effort_starts.load(Ordering::SeqCst), REDUNDANCY + 1);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
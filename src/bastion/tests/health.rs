@@ -0,0 +1,81 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_health() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_health() {
+        super::run()
+    }
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // Only one element crashes, once.
+    let crashed = Arc::new(AtomicBool::new(false));
+
+    let crashed_ref = crashed.clone();
+    let supervisor = Bastion::supervisor(|sp| {
+        // Keeps the element down for a while before restarting it.
+        sp.with_restart_strategy(RestartStrategy::default().with_actor_restart_strategy(
+            ActorRestartStrategy::LinearBackOff {
+                timeout: Duration::from_millis(300),
+            },
+        ))
+    })
+    .expect("Couldn't create the supervisor.");
+    let children = supervisor
+        .children(move |children| {
+            let crashed = crashed_ref.clone();
+            children
+                .with_redundancy(2)
+                .with_exec(move |ctx: BastionContext| {
+                    let crashed = crashed.clone();
+                    async move {
+                        loop {
+                            msg! { ctx.recv().await?,
+                                ref _msg: &'static str => {
+                                    if !crashed.swap(true, Ordering::SeqCst) {
+                                        return Err(());
+                                    }
+                                };
+                                _: _ => ();
+                            }
+                        }
+                    }
+                })
+        })
+        .expect("Couldn't create the children group.");
+
+    assert!(wait_until(|| Bastion::health().is_ready()));
+    assert_eq!(Bastion::health().liveness, Liveness::Alive);
+
+    // The system isn't ready while the group is below its redundancy...
+    children.broadcast("crash").unwrap();
+    assert!(wait_until(|| {
+        Bastion::health().readiness == Readiness::NotReady
+    }));
+    assert!(Bastion::health().is_alive());
+
+    // ...and recovers once the element is restarted.
+    assert!(wait_until(|| Bastion::health().is_ready()));
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+    assert_eq!(Bastion::health().liveness, Liveness::Dead);
+}
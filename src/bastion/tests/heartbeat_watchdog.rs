@@ -0,0 +1,90 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{wait_for, wait_until};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_heartbeat_watchdog() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_heartbeat_watchdog() {
+        super::run()
+    }
+}
+
+const INTERVAL: Duration = Duration::from_millis(100);
+const MISS_THRESHOLD: u32 = 3;
+
+// Creates a watched group of one element counting how many times it
+// started, which blocks its thread for `block` the first time.
+fn group(starts: Arc<AtomicUsize>, block: Option<Duration>) -> ChildrenRef {
+    Bastion::children(move |children| {
+        children
+            .with_heartbeat(INTERVAL, MISS_THRESHOLD)
+            .with_exec(move |ctx: BastionContext| {
+                let starts = starts.clone();
+                async move {
+                    if starts.fetch_add(1, Ordering::SeqCst) == 0 {
+                        if let Some(block) = block {
+                            thread::sleep(block);
+                        }
+                    }
+
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.")
+}
+
+fn run() {
+    Bastion::init();
+    let mut events = Bastion::event_stream();
+    Bastion::start();
+
+    let hung_starts = Arc::new(AtomicUsize::new(0));
+    let hung = group(hung_starts.clone(), Some(Duration::from_secs(1)));
+    let hung_id = hung.elems()[0].id().clone();
+    let responsive_starts = Arc::new(AtomicUsize::new(0));
+    let _responsive = group(responsive_starts.clone(), None);
+
+    // The element blocking past the threshold faults as unresponsive...
+    let mut reason = None;
+    assert!(wait_for(&mut events, |event| match event {
+        SystemEvent::ChildFaulted {
+            id,
+            reason: faulted,
+            ..
+        } if id == &hung_id => {
+            reason = Some(faulted.clone());
+            true
+        }
+        _ => false,
+    }));
+    assert_eq!(reason, Some(FaultReason::Unresponsive));
+
+    // ...and is restarted...
+    assert!(wait_until(|| hung_starts.load(Ordering::SeqCst) == 2));
+    assert_eq!(hung_starts.load(Ordering::SeqCst), 2);
+
+    // ...while the element waiting for messages is left alone.
+    thread::sleep(INTERVAL * MISS_THRESHOLD * 2);
+    assert_eq!(responsive_starts.load(Ordering::SeqCst), 1);
+    assert_eq!(hung_starts.load(Ordering::SeqCst), 2);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,46 @@
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+use uuid::Uuid;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_id_generator() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_id_generator() {
+        super::run()
+    }
+}
+
+fn run() {
+    Bastion::init();
+
+    let next = AtomicU64::new(1);
+    BastionId::set_generator(move || Uuid::from_u128(next.fetch_add(1, Ordering::SeqCst).into()));
+
+    let ids: Vec<Uuid> = (0..3)
+        .map(|_| {
+            let supervisor = Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
+            *supervisor.id().uuid()
+        })
+        .collect();
+    assert_eq!(
+        ids,
+        vec![Uuid::from_u128(1), Uuid::from_u128(2), Uuid::from_u128(3)]
+    );
+
+    // v4 UUIDs are generated again once the generator is reset.
+    BastionId::reset_generator();
+    let supervisor = Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
+    assert_eq!(supervisor.id().uuid().get_version_num(), 4);
+
+    Bastion::start();
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
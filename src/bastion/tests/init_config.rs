@@ -0,0 +1,51 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_init_config() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_init_config() {
+        super::run()
+    }
+}
+
+const MAX_ENTRIES: usize = 3;
+const MESSAGES: usize = 7;
+
+fn run() {
+    let config = Config::new()
+        .with_heartbeat_tick(Duration::from_millis(100))
+        .with_dead_letters(MAX_ENTRIES, Duration::from_secs(60));
+    Bastion::init_with(config);
+    Bastion::start();
+
+    // Nobody is part of the group, so every message is dead-lettered,
+    // and only the configured amount of them is kept.
+    Bastion::spawn(|ctx: BastionContext| async move {
+        for i in 0..MESSAGES {
+            ctx.broadcast_message(BroadcastTarget::Group("nobody".to_string()), i);
+        }
+
+        Ok(())
+    })
+    .expect("Couldn't create the sender.");
+
+    let evicted = (MESSAGES - MAX_ENTRIES) as u64;
+    assert!(wait_until(|| Bastion::dropped_dead_letters() == evicted));
+    assert_eq!(Bastion::dropped_dead_letters(), evicted);
+    assert_eq!(Bastion::dead_letters_count(), MAX_ENTRIES);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
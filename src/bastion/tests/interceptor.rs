@@ -0,0 +1,96 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_interceptor() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_interceptor() {
+        super::run()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Request {
+    authorized: bool,
+    value: usize,
+}
+
+// Drops the requests which aren't authorized.
+#[derive(Debug)]
+struct Auth;
+
+impl Interceptor for Auth {
+    fn intercept(&self, msg: SignedMessage, _: &BastionPath) -> Option<SignedMessage> {
+        match msg.peek::<Request>() {
+            Some(request) if !request.authorized => None,
+            _ => Some(msg),
+        }
+    }
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // The interceptors see the messages in the order they were added:
+    // unauthorized requests never reach the second one.
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let seen_ref = seen.clone();
+    Bastion::add_interceptor(Auth);
+    Bastion::add_interceptor(move |msg: SignedMessage, _: &BastionPath| {
+        if let Some(request) = msg.peek::<Request>() {
+            seen_ref.lock().unwrap().push(request.value);
+        }
+
+        Some(msg)
+    });
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_ref = received.clone();
+    let children = Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let received = received_ref.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        request: Request => {
+                            received.lock().unwrap().push(request.value);
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    let child = &children.elems()[0];
+    for value in 0..10 {
+        let authorized = value % 2 == 0;
+        child
+            .tell_anonymously(Request { authorized, value })
+            .unwrap();
+    }
+
+    assert!(wait_until(|| received.lock().unwrap().len() >= 5));
+    thread::sleep(Duration::from_millis(50));
+
+    assert_eq!(*received.lock().unwrap(), vec![0, 2, 4, 6, 8]);
+    assert_eq!(*seen.lock().unwrap(), vec![0, 2, 4, 6, 8]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,119 @@
+#![cfg(all(feature = "encryption", feature = "testkit"))]
+
+mod common;
+
+use bastion::prelude::*;
+use bastion::transport::{MockNetwork, MockTransport};
+use common::wait_until;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_key_rotation() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_key_rotation() {
+        super::run()
+    }
+}
+
+type Contexts = Arc<Mutex<HashMap<Uuid, Arc<DistributedContext>>>>;
+// The payloads received by every node.
+type Received = Arc<Mutex<Vec<(Uuid, String)>>>;
+
+fn old_key() -> EncryptionKey {
+    EncryptionKey::new([1; 32])
+}
+
+fn new_key() -> EncryptionKey {
+    EncryptionKey::new([2; 32])
+}
+
+fn node(transport: MockTransport, contexts: Contexts, received: Received) {
+    let encryption = Encryption::new(old_key()).with_overlap(Duration::from_secs(60));
+    let config = ClusterConfig::from(transport).with_encryption(encryption);
+    Bastion::distributed(config, move |dctx| {
+        let contexts = contexts.clone();
+        let received = received.clone();
+        async move {
+            contexts
+                .lock()
+                .unwrap()
+                .insert(dctx.current(), dctx.clone());
+            loop {
+                let msg = dctx.recv().await?;
+                let payload: String = msg.extract().downcast().unwrap();
+                received.lock().unwrap().push((dctx.current(), payload));
+            }
+        }
+    })
+    .expect("Couldn't start a cluster node.");
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let network = MockNetwork::with_seed(42);
+    let (a, b) = (network.join(), network.join());
+    let (a_id, b_id) = (a.node_id(), b.node_id());
+
+    let contexts: Contexts = Arc::new(Mutex::new(HashMap::new()));
+    let received: Received = Arc::new(Mutex::new(Vec::new()));
+    node(a, contexts.clone(), received.clone());
+    node(b, contexts.clone(), received.clone());
+    assert!(wait_until(|| contexts.lock().unwrap().len() == 2));
+    let a = contexts.lock().unwrap()[&a_id].clone();
+    let b = contexts.lock().unwrap()[&b_id].clone();
+
+    let exchange = |payload: &str| {
+        received.lock().unwrap().clear();
+        a.tell(&b_id, format!("{} from a", payload)).unwrap();
+        b.tell(&a_id, format!("{} from b", payload)).unwrap();
+
+        let expected = vec![
+            (a_id, format!("{} from b", payload)),
+            (b_id, format!("{} from a", payload)),
+        ];
+        wait_until(|| {
+            let mut received = received.lock().unwrap().clone();
+            received.sort();
+            let mut expected = expected.clone();
+            expected.sort();
+            received == expected
+        })
+    };
+
+    assert!(
+        exchange("before"),
+        "Payloads weren't exchanged with the old key."
+    );
+
+    // B accepts the new key, then A rotates to it while B still
+    // sends with the old one...
+    b.accept_key(new_key()).unwrap();
+    a.rotate_key(new_key()).unwrap();
+    assert!(
+        exchange("overlap"),
+        "Payloads were dropped during the overlap."
+    );
+
+    // ...and B eventually rotates too.
+    b.rotate_key(new_key()).unwrap();
+    assert!(
+        exchange("after"),
+        "Payloads weren't exchanged with the new key."
+    );
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_kill() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_kill() {
+        super::run()
+    }
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // The element is stuck in a loop, never receiving the messages
+    // telling it to stop.
+    let spins = Arc::new(AtomicUsize::new(0));
+    let spins_ref = spins.clone();
+    Bastion::children(move |children| {
+        children.with_exec(move |_: BastionContext| {
+            let spins = spins_ref.clone();
+            async move {
+                loop {
+                    spins.fetch_add(1, Ordering::SeqCst);
+                    Delay::new(Duration::from_millis(5)).await;
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    assert!(wait_until(|| spins.load(Ordering::SeqCst) > 0));
+
+    // Killing the system doesn't wait for the element.
+    let start = Instant::now();
+    Bastion::kill();
+    assert!(
+        start.elapsed() < Duration::from_secs(1),
+        "killing took {:?}",
+        start.elapsed()
+    );
+    Bastion::block_until_stopped();
+
+    // The element's task was cancelled.
+    let mut spun = spins.load(Ordering::SeqCst);
+    assert!(wait_until(|| {
+        thread::sleep(Duration::from_millis(50));
+        let now = spins.load(Ordering::SeqCst);
+        let cancelled = now == spun;
+        spun = now;
+        cancelled
+    }));
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(spins.load(Ordering::SeqCst), spun);
+
+    // Killing the system again does nothing.
+    Bastion::kill();
+    Bastion::block_until_stopped();
+}
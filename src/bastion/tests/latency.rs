@@ -0,0 +1,68 @@
+#![cfg(feature = "metrics")]
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use futures_timer::Delay;
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_latency_percentiles() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_latency_percentiles() {
+        super::run()
+    }
+}
+
+const MESSAGES: u64 = 20;
+const HANDLING: Duration = Duration::from_millis(20);
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    _: _ => {
+                        Delay::new(HANDLING).await;
+                    };
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+    let child = children.elems()[0].clone();
+
+    // The messages are sent one at a time, so that their latency is
+    // the time it takes to handle them.
+    for _ in 0..MESSAGES {
+        child.tell_anonymously("work").unwrap();
+        thread::sleep(HANDLING * 3);
+    }
+
+    assert!(wait_until(
+        || children.latency_percentiles().count == MESSAGES
+    ));
+    let percentiles = children.latency_percentiles();
+    assert_eq!(percentiles.count, MESSAGES);
+
+    for percentile in vec![percentiles.p50, percentiles.p99] {
+        assert!(percentile >= HANDLING, "{:?}", percentiles);
+        assert!(percentile < HANDLING * 3, "{:?}", percentiles);
+    }
+    assert!(percentiles.max >= percentiles.p99);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{wait_for, wait_until};
+use futures::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_launch_timeout() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_launch_timeout() {
+        super::run()
+    }
+}
+
+static LAUNCHES: AtomicUsize = AtomicUsize::new(0);
+
+fn run() {
+    Bastion::init();
+    let mut events = Bastion::event_stream();
+    Bastion::start();
+
+    let stuck = Bastion::children(|children| {
+        children
+            .with_launch_timeout(Duration::from_millis(100))
+            .with_exec(|ctx: BastionContext| async move {
+                // The first launch never completes its initialization.
+                if LAUNCHES.fetch_add(1, Ordering::SeqCst) == 0 {
+                    future::pending::<()>().await;
+                }
+
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    // The rest of the system isn't stalled by the stuck element.
+    let healthy = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    _msg: &'static str =!> {
+                        answer!(ctx, "pong").unwrap();
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    let expected = stuck.elems()[0].id().clone();
+    let mut reason = None;
+    assert!(wait_for(&mut events, |event| match event {
+        SystemEvent::ChildFaulted {
+            id,
+            reason: faulted,
+            ..
+        } if id == &expected => {
+            reason = Some(faulted.clone());
+            true
+        }
+        _ => false,
+    }));
+    assert_eq!(reason, Some(FaultReason::LaunchTimedOut));
+
+    let answer = healthy.elems()[0]
+        .ask_anonymously("ping")
+        .expect("Couldn't send the message.");
+    let pong = run!(async {
+        msg! { answer.await.expect("Couldn't receive the answer."),
+            msg: &'static str => msg;
+            _: _ => "";
+        }
+    });
+    assert_eq!(pong, "pong");
+
+    // The element was restarted.
+    assert!(wait_until(|| LAUNCHES.load(Ordering::SeqCst) == 2));
+    assert_eq!(LAUNCHES.load(Ordering::SeqCst), 2);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_link() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_link() {
+        super::run()
+    }
+}
+
+type Downs = Arc<Mutex<Vec<Down>>>;
+
+// Creates an element, linked to the given one and trapping exits if
+// asked to, which panics or stops when told to, returning it once
+// it's linked along with the `Down` messages it received.
+fn element(supervisor: &SupervisorRef, linked: Option<ChildRef>, trap: bool) -> (ChildRef, Downs) {
+    let downs = Arc::new(Mutex::new(Vec::new()));
+
+    let downs_ref = downs.clone();
+    let children = supervisor
+        .children(move |children| {
+            let linked = linked.clone();
+            let downs = downs_ref.clone();
+            children.with_exec(move |ctx: BastionContext| {
+                let linked = linked.clone();
+                let downs = downs.clone();
+                async move {
+                    if let Some(linked) = &linked {
+                        ctx.link(linked);
+                    }
+                    ctx.trap_exit(trap);
+
+                    loop {
+                        msg! { ctx.recv().await?,
+                            msg: &'static str => {
+                                match msg {
+                                    "panic" => panic!("the element panicked"),
+                                    "stop" => return Ok(()),
+                                    _ => (),
+                                }
+                            };
+                            down: Down => {
+                                downs.lock().unwrap().push(down);
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+        })
+        .expect("Couldn't create the children group.");
+
+    run!(children.await_ready());
+    (children.elems()[0].clone(), downs)
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // The elements aren't restarted, so that they stay down.
+    let supervisor = Bastion::supervisor(|sp| {
+        sp.with_restart_strategy(
+            RestartStrategy::default().with_restart_policy(RestartPolicy::Never),
+        )
+    })
+    .expect("Couldn't create the supervisor.");
+
+    // A panic terminates the linked element...
+    let (panicking, _) = element(&supervisor, None, false);
+    let (linked, _) = element(&supervisor, Some(panicking.clone()), false);
+    panicking.tell_anonymously("panic").unwrap();
+    assert!(wait_until(|| !linked.is_alive()));
+    assert!(!panicking.is_alive());
+    assert!(!linked.is_alive());
+
+    // ...unlike a clean stop...
+    let (stopping, _) = element(&supervisor, None, false);
+    let (linked, _) = element(&supervisor, Some(stopping.clone()), false);
+    stopping.tell_anonymously("stop").unwrap();
+    assert!(wait_until(|| !stopping.is_alive()));
+    thread::sleep(Duration::from_millis(200));
+    assert!(!stopping.is_alive());
+    assert!(linked.is_alive());
+
+    // ...and the elements trapping exits are told about it instead.
+    let (panicking, _) = element(&supervisor, None, false);
+    let (trapping, downs) = element(&supervisor, Some(panicking.clone()), true);
+    panicking.tell_anonymously("panic").unwrap();
+    assert!(wait_until(|| !downs.lock().unwrap().is_empty()));
+    {
+        let downs = downs.lock().unwrap();
+        assert_eq!(downs.len(), 1);
+        assert_eq!(*downs[0].path, *panicking.path());
+        assert!(
+            matches!(
+                downs[0].reason,
+                DownReason::Faulted(FaultReason::Panicked(_))
+            ),
+            "{:?}",
+            downs[0].reason
+        );
+    }
+    assert!(trapping.is_alive());
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
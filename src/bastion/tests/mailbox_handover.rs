@@ -0,0 +1,142 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use futures_timer::Delay;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use uuid::Uuid;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_mailbox_handover() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_mailbox_handover() {
+        super::run()
+    }
+}
+
+const JOBS: usize = 6;
+// The job the elements fault while handling.
+const POISON: usize = 2;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Job(usize);
+
+// Creates a group whose element faults whenever it handles the
+// poison job, and which waits for the jobs to be sent before handling
+// them, returning the jobs it handled.
+fn group<F>(configure: F, jobs: usize) -> (Arc<Mutex<Vec<usize>>>, ChildRef)
+where
+    F: FnOnce(Children) -> Children,
+{
+    let handled = Arc::new(Mutex::new(Vec::new()));
+    let sent = Arc::new(AtomicBool::new(false));
+    let handled_ref = handled.clone();
+    let sent_ref = sent.clone();
+    let children = Bastion::children(move |children| {
+        configure(children).with_exec(move |ctx: BastionContext| {
+            let handled = handled_ref.clone();
+            let sent = sent_ref.clone();
+            async move {
+                // The jobs pile up in the mailbox in the meantime.
+                while !sent.load(Ordering::SeqCst) {
+                    Delay::new(Duration::from_millis(10)).await;
+                }
+
+                loop {
+                    msg! { ctx.recv().await?,
+                        job: Job => {
+                            if job.0 == POISON {
+                                panic!("poison job");
+                            }
+                            handled.lock().unwrap().push(job.0);
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    let child = children.elems()[0].clone();
+    for job in 0..jobs {
+        child.tell_anonymously(Job(job)).unwrap();
+    }
+    thread::sleep(Duration::from_millis(100));
+    sent.store(true, Ordering::SeqCst);
+
+    (handled, child)
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // The jobs left in the mailbox are handed over to the restarted
+    // element, but not the poison job, even though it was persisted
+    // and could be replayed.
+    let path = std::env::temp_dir().join(format!("bastion-handover-{}", Uuid::new_v4()));
+    let log = path.clone();
+    let (handled, _) = group(
+        move |children| {
+            children
+                .with_persistence::<Job, _>(log)
+                .with_mailbox_handover(MailboxHandover::SkipInFlight)
+        },
+        JOBS,
+    );
+
+    let expected = (0..JOBS).filter(|job| *job != POISON).collect::<Vec<_>>();
+    assert!(wait_until(
+        || handled.lock().unwrap().len() == expected.len()
+    ));
+    // Leaves the time for the poison job to be (wrongly) replayed.
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(*handled.lock().unwrap(), expected);
+
+    // The jobs left in the mailbox are routed to the dead letters, and
+    // the restarted element starts from an empty mailbox.
+    Bastion::drain_dead_letters();
+    let (handled, child) = group(
+        |children| children.with_mailbox_handover(MailboxHandover::Discard),
+        JOBS,
+    );
+
+    let discarded = JOBS - POISON - 1;
+    assert!(wait_until(|| Bastion::dead_letters_count() == discarded));
+    let dead_letters = Bastion::drain_dead_letters();
+    assert_eq!(dead_letters.len(), discarded);
+    assert_eq!(*handled.lock().unwrap(), (0..POISON).collect::<Vec<_>>());
+
+    // The restarted element is resolved again, since it doesn't share
+    // the mailbox of the faulted one.
+    let mut restarted = None;
+    assert!(wait_until(|| {
+        restarted = Bastion::resolve(child.path());
+        restarted.is_some()
+    }));
+    let restarted = restarted.expect("The element wasn't restarted.");
+    restarted.tell_anonymously(Job(JOBS)).unwrap();
+    assert!(wait_until(|| handled.lock().unwrap().len() == POISON + 1));
+    assert_eq!(
+        *handled.lock().unwrap(),
+        (0..POISON).chain(Some(JOBS)).collect::<Vec<_>>()
+    );
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+
+    std::fs::remove_dir_all(&path).ok();
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "testkit")]
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_mailbox_snapshot() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_mailbox_snapshot() {
+        super::run()
+    }
+}
+
+const MESSAGES: usize = 5;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let released = Arc::new(AtomicBool::new(false));
+    let snapshots = Arc::new(Mutex::new(Vec::new()));
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let released_ref = released.clone();
+    let snapshots_ref = snapshots.clone();
+    let received_ref = received.clone();
+    let children = Bastion::children(move |children| {
+        let released = released_ref.clone();
+        let snapshots = snapshots_ref.clone();
+        let received = received_ref.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let released = released.clone();
+            let snapshots = snapshots.clone();
+            let received = received.clone();
+            async move {
+                // The messages are queued until the element is released.
+                while !released.load(Ordering::SeqCst) {
+                    Delay::new(Duration::from_millis(10)).await;
+                }
+
+                // Taking a snapshot twice gives the same messages.
+                for _ in 0..2 {
+                    let snapshot: Vec<usize> = ctx.with_mailbox_snapshot(|messages| {
+                        messages
+                            .iter()
+                            .map(|msg| *msg.peek::<usize>().unwrap())
+                            .collect()
+                    });
+                    snapshots.lock().unwrap().push(snapshot);
+                }
+
+                loop {
+                    msg! { ctx.recv().await?,
+                        msg: usize => {
+                            received.lock().unwrap().push(msg);
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+    let child = children.elems()[0].clone();
+
+    for value in 0..MESSAGES {
+        child.tell_anonymously(value).unwrap();
+    }
+    released.store(true, Ordering::SeqCst);
+
+    assert!(wait_until(|| received.lock().unwrap().len() == MESSAGES));
+
+    let expected = (0..MESSAGES).collect::<Vec<_>>();
+    assert_eq!(
+        *snapshots.lock().unwrap(),
+        vec![expected.clone(), expected.clone()]
+    );
+    // The snapshots didn't consume nor reorder the messages.
+    assert_eq!(*received.lock().unwrap(), expected);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_max_message_size() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_max_message_size() {
+        super::run()
+    }
+}
+
+const MAX_MESSAGE_SIZE: usize = 1024;
+
+fn run() {
+    Bastion::init_with(Config::new().with_max_message_size(MAX_MESSAGE_SIZE));
+    Bastion::start();
+
+    // The length of the payloads received by the element.
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_ref = received.clone();
+    let children = Bastion::children(move |children| {
+        let received = received_ref.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let received = received.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        data: Bytes => {
+                            received.lock().unwrap().push(data.len());
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+    let child = &children.elems()[0];
+
+    // The oversized payload is refused, along with its size and the
+    // limit...
+    let oversized = Bytes::from(vec![0u8; MAX_MESSAGE_SIZE + 1]);
+    match child.try_tell_anonymously(oversized.clone()) {
+        Err(SendError::MessageTooLarge { size, max, .. }) => {
+            assert_eq!(size, MAX_MESSAGE_SIZE + 1);
+            assert_eq!(max, MAX_MESSAGE_SIZE);
+        }
+        other => panic!("The oversized message wasn't refused: {:?}", other),
+    }
+    assert!(child.tell_anonymously(oversized.clone()).is_err());
+    assert!(children.broadcast(oversized).is_err());
+
+    // ...while the one under the limit is delivered.
+    let payload = Bytes::from(vec![0u8; MAX_MESSAGE_SIZE]);
+    child
+        .try_tell_anonymously(payload)
+        .expect("Couldn't send the message.");
+
+    assert!(wait_until(|| !received.lock().unwrap().is_empty()));
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(*received.lock().unwrap(), vec![MAX_MESSAGE_SIZE]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
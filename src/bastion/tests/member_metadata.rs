@@ -0,0 +1,100 @@
+#![cfg(all(feature = "distributed", feature = "testkit"))]
+
+mod common;
+
+use bastion::prelude::*;
+use bastion::transport::{MockNetwork, MockTransport};
+use common::wait_until;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_member_metadata() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_member_metadata() {
+        super::run()
+    }
+}
+
+const ROLES: [&str; 3] = ["shard-owner", "shard-owner", "router"];
+
+// The shard owners every node knows of.
+type Owners = Arc<Mutex<HashMap<Uuid, BTreeSet<Uuid>>>>;
+
+fn node(transport: MockTransport, role: &'static str, owners: Owners) {
+    let config = ClusterConfig::from(transport).with_tag("role", role);
+    Bastion::distributed(config, move |dctx| {
+        let owners = owners.clone();
+        async move {
+            loop {
+                // The driver asks every node to report what it knows.
+                dctx.recv().await?;
+                let known = dctx
+                    .members_where(|metadata| {
+                        metadata.get("role").map(String::as_str) == Some("shard-owner")
+                    })
+                    .iter()
+                    .map(|member| member.host_key())
+                    .collect();
+                owners.lock().unwrap().insert(dctx.current(), known);
+            }
+        }
+    })
+    .expect("Couldn't start a cluster node.");
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let network =
+        MockNetwork::with_seed(7).with_delay(Duration::from_millis(1), Duration::from_millis(5));
+    let transports: Vec<MockTransport> = ROLES.iter().map(|_| network.join()).collect();
+    let ids: Vec<Uuid> = transports.iter().map(MockTransport::node_id).collect();
+    let driver = network.join();
+
+    let owners: Owners = Arc::new(Mutex::new(HashMap::new()));
+    for (transport, role) in transports.into_iter().zip(ROLES.iter()) {
+        node(transport, role, owners.clone());
+    }
+
+    let expected = |node: &Uuid| -> BTreeSet<Uuid> {
+        ids.iter()
+            .zip(ROLES.iter())
+            .filter(|(id, role)| *id != node && **role == "shard-owner")
+            .map(|(id, _)| *id)
+            .collect()
+    };
+    let converged = || {
+        let owners = owners.lock().unwrap();
+        ids.iter().all(|id| owners.get(id) == Some(&expected(id)))
+    };
+
+    assert!(wait_until(|| {
+        let converged = converged();
+        if !converged {
+            for id in &ids {
+                driver.send_payload(*id, "report".to_string());
+            }
+        }
+        converged
+    }));
+
+    let owners = owners.lock().unwrap();
+    for id in &ids {
+        assert_eq!(owners.get(id), Some(&expected(id)), "node {}", id);
+    }
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
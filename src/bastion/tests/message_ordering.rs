@@ -0,0 +1,110 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until_within;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_message_ordering() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_message_ordering() {
+        super::run()
+    }
+}
+
+const MESSAGES: usize = 10_000;
+const SENDERS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Numbered {
+    sender: usize,
+    number: usize,
+}
+
+// The sender of the messages broadcasted with `Bastion::broadcast`.
+const BROADCASTER: usize = SENDERS;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let received = Arc::new(Mutex::new(vec![Vec::new(); SENDERS + 1]));
+
+    let received_ref = received.clone();
+    let receiver = Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let received = received_ref.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        ref msg: Numbered => {
+                            received.lock().unwrap()[msg.sender].push(msg.number);
+                        };
+                        msg: Numbered => {
+                            received.lock().unwrap()[msg.sender].push(msg.number);
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the receiver.");
+    let receiver = receiver.elems()[0].clone();
+
+    // The senders interleave their messages with each other's and
+    // with the broadcasted ones.
+    for sender in 0..SENDERS {
+        let receiver = receiver.addr();
+        Bastion::children(move |children| {
+            children.with_exec(move |ctx: BastionContext| {
+                let receiver = receiver.clone();
+                async move {
+                    for number in 0..MESSAGES {
+                        ctx.tell(&receiver, Numbered { sender, number }).unwrap();
+                    }
+
+                    Ok(())
+                }
+            })
+        })
+        .expect("Couldn't create a sender.");
+    }
+
+    for number in 0..MESSAGES {
+        let msg = Numbered {
+            sender: BROADCASTER,
+            number,
+        };
+        Bastion::broadcast(msg).unwrap();
+    }
+
+    let total = (SENDERS + 1) * MESSAGES;
+    assert!(wait_until_within(Duration::from_secs(30), || {
+        let count: usize = received.lock().unwrap().iter().map(Vec::len).sum();
+        count >= total
+    }));
+
+    let expected = (0..MESSAGES).collect::<Vec<_>>();
+    for (sender, numbers) in received.lock().unwrap().iter().enumerate() {
+        assert_eq!(numbers.len(), MESSAGES, "sender {}", sender);
+        assert!(
+            numbers.windows(2).all(|pair| pair[0] < pair[1]),
+            "sender {}'s messages were reordered",
+            sender
+        );
+        assert_eq!(numbers, &expected, "sender {}", sender);
+    }
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
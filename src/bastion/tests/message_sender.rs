@@ -0,0 +1,96 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_message_sender() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_message_sender() {
+        super::run()
+    }
+}
+
+const GROUP: &str = "receivers";
+
+// The sender paths reported by the outer message and by the
+// broadcasted one.
+type Senders = Arc<Mutex<Vec<(BastionPath, BastionPath)>>>;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let started = Arc::new(AtomicBool::new(false));
+    let senders: Senders = Arc::new(Mutex::new(Vec::new()));
+    let started_inner = started.clone();
+    let senders_inner = senders.clone();
+    Bastion::children(move |children| {
+        let started = started_inner.clone();
+        let senders = senders_inner.clone();
+        children
+            .with_dispatcher(Dispatcher::with_type(DispatcherType::Named(
+                GROUP.to_string(),
+            )))
+            .with_exec(move |ctx: BastionContext| {
+                let started = started.clone();
+                let senders = senders.clone();
+                async move {
+                    started.store(true, Ordering::SeqCst);
+                    loop {
+                        let smsg = ctx.recv().await?;
+                        let outer = smsg.sender().clone();
+                        let (msg, _) = smsg.extract();
+                        if let Ok(broadcasted) = msg.downcast::<Arc<SignedMessage>>() {
+                            let inner = broadcasted.sender().clone();
+                            senders.lock().unwrap().push((outer, inner));
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the receivers.");
+    assert!(wait_until(|| started.load(Ordering::SeqCst)));
+
+    let broadcaster = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            msg! { ctx.recv().await?,
+                _: &'static str => {
+                    ctx.broadcast_message(BroadcastTarget::Group(GROUP.to_string()), "hello");
+                };
+                _: _ => ();
+            }
+
+            Ok(())
+        })
+    })
+    .expect("Couldn't create the broadcaster.");
+
+    let broadcaster_path = broadcaster.elems()[0].path().clone();
+    broadcaster.elems()[0]
+        .tell_anonymously("broadcast")
+        .expect("Couldn't send the message.");
+
+    assert!(wait_until(|| !senders.lock().unwrap().is_empty()));
+
+    // Both the message received and the broadcasted one are signed by
+    // the element which broadcasted it.
+    let senders = senders.lock().unwrap().clone();
+    assert_eq!(
+        senders,
+        vec![((*broadcaster_path).clone(), (*broadcaster_path).clone())]
+    );
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
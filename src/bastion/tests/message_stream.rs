@@ -0,0 +1,80 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use futures::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_message_stream() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_message_stream() {
+        super::run()
+    }
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let taken = Arc::new(Mutex::new(Vec::new()));
+    let ended = Arc::new(AtomicBool::new(false));
+
+    let taken_ref = taken.clone();
+    let ended_ref = ended.clone();
+    let children = Bastion::children(move |children| {
+        let taken = taken_ref.clone();
+        let ended = ended_ref.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let taken = taken.clone();
+            let ended = ended.clone();
+            async move {
+                let mut messages = Box::pin(ctx.into_message_stream());
+
+                let mut first = messages.as_mut().take(3);
+                while let Some(msg) = first.next().await {
+                    msg! { msg,
+                        msg: &'static str => taken.lock().unwrap().push(msg);
+                        _: _ => ();
+                    }
+                }
+
+                // The other messages are left aside until the stream
+                // ends, once the element is asked to stop.
+                while messages.next().await.is_some() {}
+                ended.store(true, Ordering::SeqCst);
+
+                Ok(())
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    let child = &children.elems()[0];
+    for msg in &["first", "second", "third", "fourth"] {
+        child.tell_anonymously(*msg).unwrap();
+    }
+
+    assert!(wait_until(|| taken.lock().unwrap().len() == 3));
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(*taken.lock().unwrap(), vec!["first", "second", "third"]);
+    assert!(!ended.load(Ordering::SeqCst));
+
+    children.stop().unwrap();
+    assert!(wait_until(|| ended.load(Ordering::SeqCst)));
+    assert!(ended.load(Ordering::SeqCst), "the stream didn't end");
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{wait_for, wait_until};
+use futures_timer::Delay;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_message_ttl() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_message_ttl() {
+        super::run()
+    }
+}
+
+fn run() {
+    Bastion::init();
+    let mut events = Bastion::event_stream();
+    Bastion::start();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let received_ref = received.clone();
+    let receiver = Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let received = received_ref.clone();
+            async move {
+                // The messages queue up while the receiver is busy.
+                Delay::new(Duration::from_millis(200)).await;
+
+                loop {
+                    msg! { ctx.recv().await?,
+                        msg: &'static str => {
+                            received.lock().unwrap().push(msg);
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the receiver.");
+    let receiver = receiver.elems()[0].addr();
+
+    let sender = Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let receiver = receiver.clone();
+            async move {
+                ctx.tell_ttl(&receiver, "stale", Duration::from_millis(10))
+                    .unwrap();
+                ctx.tell(&receiver, "fresh").unwrap();
+
+                Ok(())
+            }
+        })
+    })
+    .expect("Couldn't create the sender.");
+    let sender = sender.elems()[0].id().clone();
+
+    let dead_lettered = wait_for(&mut events, |event| match event {
+        SystemEvent::DeadLetter { sender: from } => from.id() == &sender,
+        _ => false,
+    });
+    assert!(dead_lettered, "the expired message wasn't dead-lettered");
+
+    assert!(wait_until(|| !received.lock().unwrap().is_empty()));
+
+    // Only the message sent without a TTL was delivered.
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(*received.lock().unwrap(), vec!["fresh"]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,89 @@
+#![cfg(all(feature = "distributed", feature = "testkit"))]
+
+mod common;
+
+use bastion::prelude::*;
+use bastion::transport::{MockNetwork, MockTransport};
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_mock_cluster() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_mock_cluster() {
+        super::run()
+    }
+}
+
+const NODES: usize = 3;
+
+// The payloads received by every node, with their sender.
+type Received = Arc<Mutex<Vec<(Uuid, Uuid, String)>>>;
+
+fn node(transport: MockTransport, peers: Vec<Uuid>, received: Received) {
+    Bastion::distributed(transport, move |dctx| {
+        let peers = peers.clone();
+        let received = received.clone();
+        async move {
+            for peer in peers.iter().filter(|peer| **peer != dctx.current()) {
+                let greeting = format!("hello from {}", dctx.current());
+                dctx.tell(peer, greeting).unwrap();
+            }
+
+            loop {
+                let msg = dctx.recv().await?;
+                let from = msg.member();
+                let payload: String = msg.extract().downcast().unwrap();
+                received
+                    .lock()
+                    .unwrap()
+                    .push((dctx.current(), from, payload));
+            }
+        }
+    })
+    .expect("Couldn't start a cluster node.");
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let network = MockNetwork::with_seed(42)
+        .with_delay(Duration::from_millis(1), Duration::from_millis(20))
+        .with_reordering(true);
+    let transports: Vec<MockTransport> = (0..NODES).map(|_| network.join()).collect();
+    let ids: Vec<Uuid> = transports.iter().map(MockTransport::node_id).collect();
+
+    let received: Received = Arc::new(Mutex::new(Vec::new()));
+    for transport in transports {
+        node(transport, ids.clone(), received.clone());
+    }
+
+    // Every node greets the two others.
+    let expected = NODES * (NODES - 1);
+    assert!(wait_until(|| received.lock().unwrap().len() >= expected));
+
+    let mut received = received.lock().unwrap().clone();
+    received.sort();
+    let mut greetings = Vec::new();
+    for to in &ids {
+        for from in ids.iter().filter(|from| *from != to) {
+            greetings.push((*to, *from, format!("hello from {}", from)));
+        }
+    }
+    greetings.sort();
+    assert_eq!(received, greetings);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
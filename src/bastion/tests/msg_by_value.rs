@@ -0,0 +1,99 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_msg_by_value() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_msg_by_value() {
+        super::run()
+    }
+}
+
+// A message which can't be cloned, and thus can only be handled by
+// value if it is moved out of the envelope.
+#[derive(Debug, PartialEq)]
+struct Order {
+    id: usize,
+    items: Vec<String>,
+}
+
+#[derive(Debug)]
+struct Cancel(usize);
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let orders = Arc::new(Mutex::new(Vec::new()));
+    let others = Arc::new(Mutex::new(Vec::new()));
+
+    let orders_inner = orders.clone();
+    let others_inner = others.clone();
+    let children = Bastion::children(move |children| {
+        let orders = orders_inner.clone();
+        let others = others_inner.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let orders = orders.clone();
+            let others = others.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        move cancel: Cancel => {
+                            others.lock().unwrap().push(format!("cancel {}", cancel.0));
+                        };
+                        move order: Order => {
+                            // `order` is owned, so its items can be moved out.
+                            let Order { id, items } = order;
+                            orders.lock().unwrap().push(Order { id, items });
+                        };
+                        _: _ => {
+                            others.lock().unwrap().push("other".to_string());
+                        };
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    let child = &children.elems()[0];
+    child
+        .tell_anonymously(Order {
+            id: 1,
+            items: vec!["apple".to_string(), "pear".to_string()],
+        })
+        .expect("Couldn't send the message.");
+    child
+        .tell_anonymously(Cancel(1))
+        .expect("Couldn't send the message.");
+    child
+        .tell_anonymously("unrelated")
+        .expect("Couldn't send the message.");
+
+    assert!(wait_until(|| others.lock().unwrap().len() == 2));
+
+    assert_eq!(
+        *orders.lock().unwrap(),
+        vec![Order {
+            id: 1,
+            items: vec!["apple".to_string(), "pear".to_string()],
+        }]
+    );
+
+    // The other messages didn't match the `Order` case.
+    assert_eq!(*others.lock().unwrap(), vec!["cancel 1", "other"]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
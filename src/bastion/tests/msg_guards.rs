@@ -0,0 +1,99 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_msg_guards() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_msg_guards() {
+        super::run()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Arm {
+    Reload,
+    Stop,
+    Other,
+    AskSmall,
+    AskLarge,
+    BroadcastReload,
+    BroadcastOther,
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let matched = Arc::new(Mutex::new(Vec::new()));
+
+    let matched_ref = matched.clone();
+    let children = Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let matched = matched_ref.clone();
+            async move {
+                loop {
+                    let arm = msg! { ctx.recv().await?,
+                        ref msg: &'static str if *msg == "reload" => Arm::BroadcastReload;
+                        ref _msg: &'static str => Arm::BroadcastOther;
+                        msg: &'static str if msg == "reload" => Arm::Reload;
+                        msg: &'static str if msg == "stop" => Arm::Stop;
+                        _msg: &'static str => Arm::Other;
+                        value: usize if value < 10 =!> {
+                            answer!(ctx, value).unwrap();
+                            Arm::AskSmall
+                        };
+                        value: usize =!> {
+                            answer!(ctx, value).unwrap();
+                            Arm::AskLarge
+                        };
+                        _: _ => continue;
+                    };
+                    matched.lock().unwrap().push(arm);
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+    let child = &children.elems()[0];
+
+    child.tell_anonymously("stop").unwrap();
+    child.tell_anonymously("reload").unwrap();
+    child.tell_anonymously("other").unwrap();
+    for value in [3usize, 42].iter() {
+        let answer = child.ask_anonymously(*value).unwrap();
+        msg! { run!(answer).unwrap(),
+            answer: usize => assert_eq!(answer, *value);
+            _: _ => panic!("Unexpected answer.");
+        }
+    }
+    children.broadcast("reload").unwrap();
+    children.broadcast("other").unwrap();
+
+    let expected = vec![
+        Arm::Stop,
+        Arm::Reload,
+        Arm::Other,
+        Arm::AskSmall,
+        Arm::AskLarge,
+        Arm::BroadcastReload,
+        Arm::BroadcastOther,
+    ];
+    assert!(wait_until(
+        || matched.lock().unwrap().len() >= expected.len()
+    ));
+    assert_eq!(*matched.lock().unwrap(), expected);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
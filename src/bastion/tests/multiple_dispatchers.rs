@@ -0,0 +1,84 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_multiple_dispatchers() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_multiple_dispatchers() {
+        super::run()
+    }
+}
+
+const GROUP: &str = "workers";
+
+fn broadcast(target: BroadcastTarget, value: usize) {
+    Bastion::spawn(move |ctx: BastionContext| {
+        let target = target.clone();
+        async move {
+            ctx.broadcast_message(target, value);
+            Ok(())
+        }
+    })
+    .expect("Couldn't create the sender.");
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let started = Arc::new(AtomicBool::new(false));
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let started_inner = started.clone();
+    let received_inner = received.clone();
+    Bastion::children(move |children| {
+        let started = started_inner.clone();
+        let received = received_inner.clone();
+        children
+            .with_dispatchers(vec![
+                Dispatcher::with_type(DispatcherType::Named(GROUP.to_string())),
+                Dispatcher::with_type(DispatcherType::Anonymous),
+            ])
+            .with_exec(move |ctx: BastionContext| {
+                let started = started.clone();
+                let received = received.clone();
+                async move {
+                    started.store(true, Ordering::SeqCst);
+                    loop {
+                        msg! { ctx.recv().await?,
+                            broadcasted: Arc<SignedMessage> => {
+                                if let Some(value) = broadcasted.peek::<usize>() {
+                                    received.lock().unwrap().push(*value);
+                                }
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    assert!(wait_until(|| started.load(Ordering::SeqCst)));
+
+    // The group is reachable through its named dispatcher...
+    broadcast(BroadcastTarget::Group(GROUP.to_string()), 1);
+    assert!(wait_until(|| *received.lock().unwrap() == vec![1]));
+
+    // ...and through the anonymous one.
+    broadcast(BroadcastTarget::Anonymous, 2);
+    assert!(wait_until(|| *received.lock().unwrap() == vec![1, 2]));
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_next_event() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_next_event() {
+        super::run()
+    }
+}
+
+const MESSAGES: usize = 5;
+const TICK: Duration = Duration::from_millis(20);
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let messages = Arc::new(AtomicUsize::new(0));
+    let ticks = Arc::new(AtomicUsize::new(0));
+
+    let messages_ref = messages.clone();
+    let ticks_ref = ticks.clone();
+    let children = Bastion::children(move |children| {
+        let messages = messages_ref.clone();
+        let ticks = ticks_ref.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let messages = messages.clone();
+            let ticks = ticks.clone();
+            async move {
+                ctx.register_timer("tick", TICK);
+
+                loop {
+                    match ctx.next_event().await? {
+                        ContextEvent::Message(msg) => {
+                            msg! { msg,
+                                _msg: &'static str => {
+                                    messages.fetch_add(1, Ordering::SeqCst);
+                                };
+                                _: _ => ();
+                            }
+                        }
+                        ContextEvent::Tick(name) => {
+                            assert_eq!(name, "tick");
+                            ticks.fetch_add(1, Ordering::SeqCst);
+                        }
+                        _ => (),
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    run!(children.await_ready());
+    for _ in 0..MESSAGES {
+        children.broadcast("work").unwrap();
+    }
+
+    // Both the messages and the ticks went through the same loop.
+    assert!(wait_until(
+        || messages.load(Ordering::SeqCst) == MESSAGES && ticks.load(Ordering::SeqCst) >= 3
+    ));
+    assert_eq!(messages.load(Ordering::SeqCst), MESSAGES);
+    assert!(ticks.load(Ordering::SeqCst) >= 3);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
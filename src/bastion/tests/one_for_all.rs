@@ -0,0 +1,121 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{wait_for, wait_until};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_one_for_all_restarts_every_child() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_one_for_all_restarts_every_child() {
+        super::run()
+    }
+}
+
+const REDUNDANCY: usize = 3;
+
+fn run() {
+    Bastion::init();
+    let mut events = Bastion::event_stream();
+    Bastion::start();
+
+    // The identifiers of the elements, in the order their exec started.
+    let started = Arc::new(Mutex::new(Vec::new()));
+    // The amount of messages every element handled since it started.
+    let handled = Arc::new(Mutex::new(HashMap::new()));
+
+    let started_inner = started.clone();
+    let handled_inner = handled.clone();
+    let supervisor = Bastion::supervisor(|sp| sp.with_strategy(SupervisionStrategy::OneForAll))
+        .expect("Couldn't create the supervisor.");
+    let children = supervisor
+        .children(move |children| {
+            let started = started_inner.clone();
+            let handled = handled_inner.clone();
+            children
+                .with_redundancy(REDUNDANCY)
+                .with_exec(move |ctx: BastionContext| {
+                    let started = started.clone();
+                    let handled = handled.clone();
+                    async move {
+                        let id = ctx.current().id().clone();
+                        started.lock().unwrap().push(id.clone());
+
+                        // The state of the element, which must be fresh
+                        // after a restart.
+                        let mut count = 0;
+                        loop {
+                            msg! { ctx.recv().await?,
+                                ref msg: &'static str => {
+                                    assert_eq!(msg, &"count");
+                                    count += 1;
+                                    handled.lock().unwrap().insert(id.clone(), count);
+                                };
+                                msg: &'static str => {
+                                    assert_eq!(msg, "fault");
+                                    return Err(());
+                                };
+                                _: _ => ();
+                            }
+                        }
+                    }
+                })
+        })
+        .expect("Couldn't create the children group.");
+
+    let ids = children
+        .elems()
+        .iter()
+        .map(|child| child.id().clone())
+        .collect::<Vec<_>>();
+
+    assert!(wait_until(|| started.lock().unwrap().len() == REDUNDANCY));
+    children.broadcast("count").unwrap();
+    assert!(wait_until(|| handled
+        .lock()
+        .unwrap()
+        .values()
+        .all(|count| *count == 1)));
+    assert_eq!(handled.lock().unwrap().len(), REDUNDANCY);
+
+    children.elems()[0]
+        .tell_anonymously("fault")
+        .expect("Couldn't send the message.");
+
+    let mut restarted = Vec::new();
+    assert!(wait_for(&mut events, |event| {
+        if let SystemEvent::ChildRestarted { id, .. } = event {
+            restarted.push(id.clone());
+        }
+        restarted.len() == REDUNDANCY
+    }));
+
+    // Every element was restarted, not only the faulted one.
+    let mut expected = ids.clone();
+    restarted.sort_by_key(|id| id.to_string());
+    expected.sort_by_key(|id| id.to_string());
+    assert_eq!(restarted, expected);
+
+    assert!(wait_until(
+        || started.lock().unwrap().len() == 2 * REDUNDANCY
+    ));
+    assert_eq!(started.lock().unwrap().len(), 2 * REDUNDANCY);
+
+    // The restarted elements start counting from scratch.
+    handled.lock().unwrap().clear();
+    children.broadcast("count").unwrap();
+    assert!(wait_until(|| handled.lock().unwrap().len() == REDUNDANCY));
+    assert!(handled.lock().unwrap().values().all(|count| *count == 1));
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
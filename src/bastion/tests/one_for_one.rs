@@ -0,0 +1,144 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use futures::prelude::*;
+use futures_timer::Delay;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_one_for_one_restarts_only_the_faulted_child() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_one_for_one_restarts_only_the_faulted_child() {
+        super::run()
+    }
+}
+
+const REDUNDANCY: usize = 3;
+const COUNTS: usize = 2;
+
+fn run() {
+    Bastion::init();
+    let mut events = Bastion::event_stream();
+    Bastion::start();
+
+    // The identifiers of the elements, in the order their exec started.
+    let started = Arc::new(Mutex::new(Vec::new()));
+    // The amount of messages every element handled since it started.
+    let handled = Arc::new(Mutex::new(HashMap::new()));
+
+    let started_inner = started.clone();
+    let handled_inner = handled.clone();
+    let supervisor = Bastion::supervisor(|sp| sp.with_strategy(SupervisionStrategy::OneForOne))
+        .expect("Couldn't create the supervisor.");
+    let children = supervisor
+        .children(move |children| {
+            let started = started_inner.clone();
+            let handled = handled_inner.clone();
+            children
+                .with_redundancy(REDUNDANCY)
+                .with_exec(move |ctx: BastionContext| {
+                    let started = started.clone();
+                    let handled = handled.clone();
+                    async move {
+                        let id = ctx.current().id().clone();
+                        started.lock().unwrap().push(id.clone());
+
+                        // The state of the element, which is only reset
+                        // when the element itself is restarted.
+                        let mut count = 0;
+                        handled.lock().unwrap().insert(id.clone(), count);
+                        loop {
+                            msg! { ctx.recv().await?,
+                                ref msg: &'static str => {
+                                    assert_eq!(msg, &"count");
+                                    count += 1;
+                                    handled.lock().unwrap().insert(id.clone(), count);
+                                };
+                                msg: &'static str => {
+                                    assert_eq!(msg, "fault");
+                                    return Err(());
+                                };
+                                _: _ => ();
+                            }
+                        }
+                    }
+                })
+        })
+        .expect("Couldn't create the children group.");
+
+    let ids = children
+        .elems()
+        .iter()
+        .map(|child| child.id().clone())
+        .collect::<Vec<_>>();
+
+    assert!(wait_until(|| started.lock().unwrap().len() == REDUNDANCY));
+    for expected in 1..=COUNTS {
+        children.broadcast("count").unwrap();
+        assert!(wait_until(|| {
+            let handled = handled.lock().unwrap();
+            handled.len() == REDUNDANCY && handled.values().all(|count| *count == expected)
+        }));
+    }
+
+    let faulted = ids[0].clone();
+    children.elems()[0]
+        .tell_anonymously("fault")
+        .expect("Couldn't send the message.");
+
+    let restarted = run!(async {
+        let mut restarted = Vec::new();
+        // Leaves the time for the other elements to be restarted,
+        // which they mustn't be.
+        let mut timeout = Delay::new(Duration::from_millis(500)).fuse();
+        loop {
+            futures::select! {
+                event = events.next().fuse() => match event {
+                    Some(SystemEvent::ChildRestarted { id, .. }) => restarted.push(id),
+                    Some(_) => {}
+                    None => break,
+                },
+                _ = timeout => break,
+            }
+        }
+
+        restarted
+    });
+
+    // Only the faulted element was restarted...
+    assert_eq!(restarted, vec![faulted.clone()]);
+    assert!(wait_until(
+        || started.lock().unwrap().len() == REDUNDANCY + 1
+    ));
+    assert_eq!(started.lock().unwrap().len(), REDUNDANCY + 1);
+
+    // ...and starts counting from scratch, while the other ones kept
+    // their accumulated state.
+    children.broadcast("count").unwrap();
+    let total = 1 + (REDUNDANCY - 1) * (COUNTS + 1);
+    assert!(wait_until(|| handled
+        .lock()
+        .unwrap()
+        .values()
+        .sum::<usize>()
+        == total));
+    let handled = handled.lock().unwrap();
+    for id in &ids {
+        let expected = if *id == faulted { 1 } else { COUNTS + 1 };
+        assert_eq!(handled.get(id), Some(&expected));
+    }
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
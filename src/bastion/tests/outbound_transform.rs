@@ -0,0 +1,123 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_outbound_transform() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_outbound_transform() {
+        super::run()
+    }
+}
+
+#[derive(Debug)]
+struct Request {
+    tenant: Option<&'static str>,
+    body: &'static str,
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // The body and tenant of every request received, and the numbers
+    // received.
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let numbers = Arc::new(Mutex::new(Vec::new()));
+    let requests_ref = requests.clone();
+    let numbers_ref = numbers.clone();
+    let receivers = Bastion::children(move |children| {
+        let requests = requests_ref.clone();
+        let numbers = numbers_ref.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let requests = requests.clone();
+            let numbers = numbers.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        req: Request => {
+                            requests.lock().unwrap().push((req.body, req.tenant));
+                        };
+                        n: u64 => numbers.lock().unwrap().push(n);
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+    let receiver = receivers.elems()[0].clone();
+
+    // A request injected directly isn't stamped...
+    receiver
+        .tell_anonymously(Request {
+            tenant: None,
+            body: "direct",
+        })
+        .unwrap();
+    receiver.tell_anonymously(1u64).unwrap();
+    assert!(wait_until(
+        || requests.lock().unwrap().len() == 1 && numbers.lock().unwrap().len() == 1
+    ));
+
+    // ...while the ones sent by the stamping group are, along with
+    // the other messages it has a transform for.
+    Bastion::children(move |children| {
+        let receiver = receiver.clone();
+        children
+            .with_outbound_transform(|mut req: Request| {
+                req.tenant = Some("acme");
+                req
+            })
+            .with_outbound_transform(|n: u64| n * 10)
+            .with_exec(move |ctx: BastionContext| {
+                let receiver = receiver.clone();
+                async move {
+                    let req = Request {
+                        tenant: None,
+                        body: "told",
+                    };
+                    ctx.tell(&receiver.addr(), req).unwrap();
+                    let req = Request {
+                        tenant: None,
+                        body: "told with a ttl",
+                    };
+                    let ttl = Duration::from_secs(60);
+                    ctx.tell_ttl(&receiver.addr(), req, ttl).unwrap();
+                    ctx.tell(&receiver.addr(), 2u64).unwrap();
+
+                    Ok(())
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    assert!(wait_until(
+        || requests.lock().unwrap().len() == 3 && numbers.lock().unwrap().len() == 2
+    ));
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(
+        *requests.lock().unwrap(),
+        vec![
+            ("direct", None),
+            ("told", Some("acme")),
+            ("told with a ttl", Some("acme")),
+        ]
+    );
+    assert_eq!(*numbers.lock().unwrap(), vec![1, 20]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
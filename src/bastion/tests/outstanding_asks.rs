@@ -0,0 +1,140 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_outstanding_asks() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_outstanding_asks() {
+        super::run()
+    }
+}
+
+const TARGETS: usize = 100;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // Every target answers the number it's asked along with its own
+    // identifier.
+    let targets = Bastion::children(|children| {
+        children
+            .with_redundancy(TARGETS)
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        n: usize =!> {
+                            answer!(ctx, (n, ctx.current().id().clone())).unwrap();
+                        };
+                        _: _ => ();
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    let targets = targets.elems().to_vec();
+    assert_eq!(targets.len(), TARGETS);
+
+    // Asks every target at once before awaiting any answer.
+    let replies = Arc::new(Mutex::new(None));
+    let replies_ref = replies.clone();
+    let asked = targets.clone();
+    Bastion::children(move |children| {
+        let replies = replies_ref.clone();
+        let asked = asked.clone();
+        children
+            .with_max_outstanding_asks(TARGETS)
+            .with_exec(move |ctx: BastionContext| {
+                let replies = replies.clone();
+                let asked = asked.clone();
+                async move {
+                    let mut answers = Vec::with_capacity(asked.len());
+                    for (n, target) in asked.iter().enumerate() {
+                        let answer = ctx
+                            .try_ask(&target.addr(), n)
+                            .expect("Couldn't ask the target.");
+                        answers.push(answer);
+                    }
+                    let outstanding = ctx.outstanding_asks();
+
+                    let mut received = Vec::with_capacity(answers.len());
+                    for answer in answers {
+                        msg! { answer.await?,
+                            reply: (usize, BastionId) => received.push(reply);
+                            _: _ => ();
+                        }
+                    }
+
+                    *replies.lock().unwrap() =
+                        Some((outstanding, received, ctx.outstanding_asks()));
+                    Ok(())
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    assert!(wait_until(|| replies.lock().unwrap().is_some()));
+    let (outstanding, received, remaining) = replies.lock().unwrap().take().unwrap();
+    assert_eq!(outstanding, TARGETS);
+    assert_eq!(remaining, 0);
+    let expected = targets
+        .iter()
+        .enumerate()
+        .map(|(n, target)| (n, target.id().clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(received, expected);
+
+    // Asking above the maximum is refused until an outstanding ask
+    // completes.
+    let outcome = Arc::new(Mutex::new(None));
+    let outcome_ref = outcome.clone();
+    let target = targets[0].clone();
+    Bastion::children(move |children| {
+        let outcome = outcome_ref.clone();
+        let target = target.clone();
+        children
+            .with_max_outstanding_asks(2)
+            .with_exec(move |ctx: BastionContext| {
+                let outcome = outcome.clone();
+                let target = target.clone();
+                async move {
+                    let first = ctx.try_ask(&target.addr(), 1usize).unwrap();
+                    let second = ctx.try_ask(&target.addr(), 2usize).unwrap();
+                    let refused = match ctx.try_ask(&target.addr(), 3usize) {
+                        Err(SendError::TooManyAsks { max, .. }) => Some(max),
+                        _ => None,
+                    };
+                    let refused_ask = ctx.ask(&target.addr(), 3usize).is_err();
+                    let full = ctx.outstanding_asks();
+
+                    drop(first);
+                    let third = ctx.try_ask(&target.addr(), 3usize).is_ok();
+                    second.await?;
+
+                    *outcome.lock().unwrap() =
+                        Some((refused, refused_ask, full, third, ctx.outstanding_asks()));
+                    Ok(())
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    assert!(wait_until(|| outcome.lock().unwrap().is_some()));
+    let outcome = outcome.lock().unwrap().take();
+    // The third answer was dropped without being awaited.
+    assert_eq!(outcome, Some((Some(2), true, 2, true, 0)));
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
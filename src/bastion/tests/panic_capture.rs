@@ -0,0 +1,70 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_for;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_panic_capture() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_panic_capture() {
+        super::run()
+    }
+}
+
+const PANIC_MESSAGE: &str = "the flux capacitor is out of plutonium";
+
+fn run() {
+    Bastion::init();
+    let mut events = Bastion::event_stream();
+    Bastion::start();
+
+    let panicked = Arc::new(AtomicBool::new(false));
+    let children = Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let panicked = panicked.clone();
+            async move {
+                // Only panics the first time it runs.
+                if !panicked.swap(true, Ordering::SeqCst) {
+                    panic!("{}", PANIC_MESSAGE);
+                }
+
+                loop {
+                    ctx.recv().await?;
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    let expected = children.elems()[0].id().clone();
+    let mut reason = None;
+    assert!(wait_for(&mut events, |event| match event {
+        SystemEvent::ChildFaulted {
+            id,
+            reason: faulted,
+            ..
+        } if id == &expected => {
+            reason = Some(faulted.clone());
+            true
+        }
+        _ => false,
+    }));
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+
+    assert_eq!(
+        reason,
+        Some(FaultReason::Panicked(PANIC_MESSAGE.to_string()))
+    );
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_panic_policy() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_panic_policy() {
+        super::run()
+    }
+}
+
+#[derive(Debug)]
+struct Add(u64);
+
+#[derive(Debug)]
+struct Poison;
+
+struct Group {
+    // The total the element accumulated after each message.
+    totals: Arc<Mutex<Vec<u64>>>,
+    // How many times the element was started.
+    starts: Arc<AtomicUsize>,
+    child: ChildRef,
+}
+
+// Creates a group whose element accumulates the amounts it is sent
+// and panics when handling the poison, then sends it two amounts,
+// the poison and another amount.
+fn group(policy: PanicPolicy) -> Group {
+    let totals = Arc::new(Mutex::new(Vec::new()));
+    let starts = Arc::new(AtomicUsize::new(0));
+    let sent = Arc::new(AtomicBool::new(false));
+    let totals_ref = totals.clone();
+    let starts_ref = starts.clone();
+    let sent_ref = sent.clone();
+    let children = Bastion::children(move |children| {
+        children
+            .with_panic_policy(policy)
+            .with_exec(move |ctx: BastionContext| {
+                let totals = totals_ref.clone();
+                let starts = starts_ref.clone();
+                let sent = sent_ref.clone();
+                async move {
+                    starts.fetch_add(1, Ordering::SeqCst);
+                    // The messages pile up in the mailbox in the
+                    // meantime.
+                    while !sent.load(Ordering::SeqCst) {
+                        Delay::new(Duration::from_millis(10)).await;
+                    }
+
+                    let mut total = 0;
+                    loop {
+                        ctx.recv_with(|msg| {
+                            msg! { msg,
+                                add: Add => {
+                                    total += add.0;
+                                    totals.lock().unwrap().push(total);
+                                };
+                                poison: Poison => panic!("{:?}", poison);
+                                _: _ => ();
+                            }
+                        })
+                        .await?;
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let child = children.elems()[0].clone();
+    child.tell_anonymously(Add(1)).unwrap();
+    child.tell_anonymously(Add(2)).unwrap();
+    child.tell_anonymously(Poison).unwrap();
+    child.tell_anonymously(Add(3)).unwrap();
+    thread::sleep(Duration::from_millis(100));
+    sent.store(true, Ordering::SeqCst);
+
+    Group {
+        totals,
+        starts,
+        child,
+    }
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // The element keeps its total across the poison.
+    let resumed = group(PanicPolicy::Resume);
+    assert!(wait_until(|| resumed.totals.lock().unwrap().len() == 3));
+    assert_eq!(*resumed.totals.lock().unwrap(), vec![1, 3, 6]);
+    assert_eq!(resumed.starts.load(Ordering::SeqCst), 1);
+
+    // The element starts from a new total after the poison.
+    let restarted = group(PanicPolicy::Restart);
+    assert!(wait_until(|| restarted.totals.lock().unwrap().len() == 3));
+    assert_eq!(*restarted.totals.lock().unwrap(), vec![1, 3, 3]);
+    assert_eq!(restarted.starts.load(Ordering::SeqCst), 2);
+
+    // The element doesn't handle the messages after the poison, and
+    // isn't restarted.
+    let stopped = group(PanicPolicy::Stop);
+    assert!(wait_until(|| stopped.totals.lock().unwrap().len() == 2));
+    assert!(wait_until(
+        || Bastion::resolve(stopped.child.path()).is_none()
+    ));
+    // Leaves the time for the element to be (wrongly) restarted.
+    thread::sleep(Duration::from_millis(200));
+    assert!(Bastion::resolve(stopped.child.path()).is_none());
+    assert_eq!(*stopped.totals.lock().unwrap(), vec![1, 3]);
+    assert_eq!(stopped.starts.load(Ordering::SeqCst), 1);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
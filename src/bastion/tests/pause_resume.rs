@@ -0,0 +1,82 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_pause_resume() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_pause_resume() {
+        super::run()
+    }
+}
+
+const MESSAGES: usize = 10;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let starts = Arc::new(AtomicUsize::new(0));
+    // The messages handled by the element, with how many it had
+    // handled before, which is kept in its state.
+    let handled = Arc::new(Mutex::new(Vec::new()));
+
+    let starts_ref = starts.clone();
+    let handled_ref = handled.clone();
+    let children = Bastion::children(move |children| {
+        let starts = starts_ref.clone();
+        let handled = handled_ref.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let handled = handled.clone();
+            starts.fetch_add(1, Ordering::SeqCst);
+            async move {
+                let mut count = 0;
+                loop {
+                    msg! { ctx.recv().await?,
+                        ref number: usize => {
+                            handled.lock().unwrap().push((count, *number));
+                            count += 1;
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    children.broadcast(0usize).unwrap();
+    assert!(wait_until(|| handled.lock().unwrap().len() == 1));
+
+    // The messages sent while the group is paused are buffered...
+    children.pause().unwrap();
+    for number in 1..MESSAGES {
+        children.broadcast(number).unwrap();
+    }
+    thread::sleep(Duration::from_millis(200));
+    assert_eq!(*handled.lock().unwrap(), vec![(0, 0)]);
+
+    // ...and delivered in order once it is resumed, to the same
+    // element which kept its state.
+    children.resume().unwrap();
+    assert!(wait_until(|| handled.lock().unwrap().len() == MESSAGES));
+    let expected: Vec<(usize, usize)> = (0..MESSAGES).map(|n| (n, n)).collect();
+    assert_eq!(*handled.lock().unwrap(), expected);
+    assert_eq!(starts.load(Ordering::SeqCst), 1);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
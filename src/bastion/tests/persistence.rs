@@ -0,0 +1,107 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use futures_timer::Delay;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use uuid::Uuid;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_persistence() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_persistence() {
+        super::run()
+    }
+}
+
+const JOBS: usize = 5;
+// The job the first group crashes while handling.
+const CRASHING: usize = 2;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Job(usize);
+
+fn group(path: PathBuf, handled: Arc<Mutex<Vec<usize>>>, crashing: Arc<AtomicBool>) -> ChildrenRef {
+    Bastion::children(move |children| {
+        children
+            .with_persistence::<Job, _>(path)
+            .with_exec(move |ctx: BastionContext| {
+                let handled = handled.clone();
+                let crashing = crashing.clone();
+                async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            job: Job => {
+                                if job.0 == CRASHING && !crashing.swap(true, Ordering::SeqCst) {
+                                    // Hangs until the group is killed,
+                                    // without finishing to handle the job.
+                                    Delay::new(Duration::from_secs(60)).await;
+                                }
+
+                                handled.lock().unwrap().push(job.0);
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.")
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let path = std::env::temp_dir().join(format!("bastion-persistence-{}", Uuid::new_v4()));
+    let crashing = Arc::new(AtomicBool::new(false));
+
+    let handled_before = Arc::new(Mutex::new(Vec::new()));
+    let before = group(path.clone(), handled_before.clone(), crashing.clone());
+    let child = before.elems()[0].clone();
+    for job in 0..JOBS {
+        child.tell_anonymously(Job(job)).unwrap();
+    }
+
+    assert!(wait_until(|| crashing.load(Ordering::SeqCst)));
+    assert_eq!(*handled_before.lock().unwrap(), vec![0, 1]);
+
+    // Simulates a crash: the jobs left aren't marked as done.
+    before.kill().expect("Couldn't kill the children group.");
+    thread::sleep(Duration::from_millis(100));
+
+    let handled_after = Arc::new(Mutex::new(Vec::new()));
+    group(path.clone(), handled_after.clone(), crashing);
+
+    assert!(wait_until(
+        || handled_after.lock().unwrap().len() == JOBS - CRASHING
+    ));
+    // Leaves the time for an already handled job to be replayed.
+    thread::sleep(Duration::from_millis(100));
+
+    // Only the jobs which weren't handled before the crash were
+    // replayed, including the one which was being handled.
+    assert_eq!(
+        *handled_after.lock().unwrap(),
+        (CRASHING..JOBS).collect::<Vec<_>>()
+    );
+    assert_eq!(*handled_before.lock().unwrap(), vec![0, 1]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+
+    std::fs::remove_dir_all(&path).ok();
+}
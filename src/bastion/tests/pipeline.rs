@@ -0,0 +1,63 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_pipeline() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_pipeline() {
+        super::run()
+    }
+}
+
+const ITEMS: usize = 10;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let received_ref = received.clone();
+    let pipeline = Bastion::pipeline(
+        Pipeline::new(Stage::new(|item: &usize| item * 2).with_redundancy(2)).then(Stage::new(
+            move |item: &usize| {
+                received_ref.lock().unwrap().push(*item);
+            },
+        )),
+    )
+    .expect("Couldn't launch the pipeline.");
+
+    let stages = pipeline.stages();
+    assert_eq!(stages.len(), 2);
+    assert_eq!(stages[0].elems().len(), 2);
+    assert_eq!(stages[1].elems().len(), 1);
+
+    for item in 0..ITEMS {
+        pipeline.push(item);
+    }
+
+    assert!(wait_until(|| received.lock().unwrap().len() == ITEMS));
+
+    // Every item went through the first stage before reaching the
+    // second one.
+    let mut received = received.lock().unwrap().clone();
+    received.sort_unstable();
+    assert_eq!(
+        received,
+        (0..ITEMS).map(|item| item * 2).collect::<Vec<_>>()
+    );
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_promote() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_promote() {
+        super::run()
+    }
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // The elements of this supervisor are temporary: they aren't
+    // restarted once they fault.
+    let temporary = Bastion::supervisor(|sp| {
+        sp.with_restart_strategy(
+            RestartStrategy::default().with_restart_policy(RestartPolicy::Never),
+        )
+    })
+    .expect("Couldn't create the supervisor.");
+    let permanent = Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
+
+    let starts = Arc::new(AtomicUsize::new(0));
+    let starts_ref = starts.clone();
+    let permanent_ref = permanent.clone();
+    let group = temporary
+        .children(move |children| {
+            let starts = starts_ref.clone();
+            let permanent = permanent_ref.clone();
+            children.with_exec(move |ctx: BastionContext| {
+                let starts = starts.clone();
+                let permanent = permanent.clone();
+                async move {
+                    starts.fetch_add(1, Ordering::SeqCst);
+
+                    loop {
+                        msg! { ctx.recv().await?,
+                            msg: &'static str => {
+                                match msg {
+                                    "promote" => ctx
+                                        .promote_to(&permanent, SupervisionStrategy::OneForOne)
+                                        .unwrap(),
+                                    "fault" => return Err(()),
+                                    _ => (),
+                                }
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+        })
+        .expect("Couldn't create the children group.");
+    let child = group.elems()[0].clone();
+
+    assert!(wait_until(|| starts.load(Ordering::SeqCst) == 1));
+    child.tell_anonymously("promote").unwrap();
+
+    // The group shows up under its new supervisor once it adopted it.
+    let adopted = || {
+        Bastion::topology()
+            .supervisor(permanent.id())
+            .map_or(false, |supervisor| {
+                supervisor
+                    .children
+                    .iter()
+                    .any(|children| children.id == group.id().to_string())
+            })
+    };
+    assert!(wait_until(adopted));
+    assert!(adopted(), "the group wasn't adopted");
+    // It wasn't restarted to be promoted.
+    assert_eq!(starts.load(Ordering::SeqCst), 1);
+
+    // The new supervisor restarts it once it faults.
+    child.tell_anonymously("fault").unwrap();
+    assert!(wait_until(|| starts.load(Ordering::SeqCst) == 2));
+    assert_eq!(starts.load(Ordering::SeqCst), 2);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_pubsub() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_pubsub() {
+        super::run()
+    }
+}
+
+const TOPIC: &str = "events";
+
+// Asks the publisher to publish an `Event` with the same number.
+#[derive(Debug)]
+struct Publish(usize);
+
+#[derive(Debug)]
+struct Event(usize);
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let subscribed = Arc::new(AtomicUsize::new(0));
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    for subscriber in 0..2 {
+        let subscribed = subscribed.clone();
+        let received = received.clone();
+        Bastion::children(move |children| {
+            children.with_exec(move |ctx: BastionContext| {
+                let subscribed = subscribed.clone();
+                let received = received.clone();
+                async move {
+                    ctx.subscribe(TOPIC);
+                    subscribed.fetch_add(1, Ordering::SeqCst);
+
+                    loop {
+                        msg! { ctx.recv().await?,
+                            ref event: Event => {
+                                // The second subscriber only wants the first event.
+                                if subscriber == 1 {
+                                    ctx.unsubscribe(TOPIC);
+                                }
+                                received.lock().unwrap().push((subscriber, event.0));
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+        })
+        .expect("Couldn't create a subscriber.");
+    }
+
+    let publisher = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    msg: Publish => {
+                        ctx.publish(TOPIC, Event(msg.0));
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the publisher.");
+    let publisher = &publisher.elems()[0];
+
+    assert!(wait_until(|| subscribed.load(Ordering::SeqCst) == 2));
+    assert_eq!(subscribed.load(Ordering::SeqCst), 2);
+
+    publisher.tell_anonymously(Publish(1)).unwrap();
+    assert!(wait_until(|| received.lock().unwrap().len() >= 2));
+    let mut events = received.lock().unwrap().clone();
+    events.sort_unstable();
+    assert_eq!(events, vec![(0, 1), (1, 1)]);
+
+    publisher.tell_anonymously(Publish(2)).unwrap();
+    assert!(wait_until(|| received.lock().unwrap().len() >= 3));
+    // Leave time for an event wrongly sent to the unsubscribed child.
+    thread::sleep(Duration::from_millis(100));
+    let mut events = received.lock().unwrap().clone();
+    events.sort_unstable();
+    assert_eq!(events, vec![(0, 1), (0, 2), (1, 1)]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
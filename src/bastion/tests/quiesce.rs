@@ -0,0 +1,107 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_quiesce() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_quiesce() {
+        super::run()
+    }
+}
+
+const MESSAGES: usize = 5;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let started = Arc::new(AtomicBool::new(false));
+    let released = Arc::new(AtomicBool::new(false));
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let started_ref = started.clone();
+    let released_ref = released.clone();
+    let received_ref = received.clone();
+    let parent = Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
+    let group = parent
+        .children(move |children| {
+            let started = started_ref.clone();
+            let released = released_ref.clone();
+            let received = received_ref.clone();
+            children.with_exec(move |ctx: BastionContext| {
+                let started = started.clone();
+                let released = released.clone();
+                let received = received.clone();
+                async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            msg: usize => {
+                                // The first message is still being handled
+                                // when the system is quiesced.
+                                if msg == 0 {
+                                    started.store(true, Ordering::SeqCst);
+                                    while !released.load(Ordering::SeqCst) {
+                                        Delay::new(Duration::from_millis(10)).await;
+                                    }
+                                }
+                                received.lock().unwrap().push(msg);
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+        })
+        .expect("Couldn't create the children group.");
+    let child = group.elems()[0].clone();
+
+    child.tell_anonymously(0usize).unwrap();
+    assert!(wait_until(|| started.load(Ordering::SeqCst)));
+
+    let guard = Bastion::quiesce();
+    for msg in 1..=MESSAGES {
+        child.tell_anonymously(msg).unwrap();
+    }
+
+    // The message being handled is handled until the end...
+    released.store(true, Ordering::SeqCst);
+    assert!(wait_until(|| !received.lock().unwrap().is_empty()));
+    // ...but none of the messages sent meanwhile are received.
+    thread::sleep(Duration::from_millis(200));
+    assert_eq!(*received.lock().unwrap(), vec![0]);
+
+    let snapshot = Bastion::topology();
+    let supervisor = snapshot
+        .supervisor(parent.id())
+        .expect("The supervisor is missing from the snapshot.");
+    assert_eq!(supervisor.children.len(), 1);
+    assert_eq!(supervisor.children[0].id, group.id().to_string());
+    assert_eq!(supervisor.children[0].live, 1);
+
+    // The messages held meanwhile are received in order once the guard
+    // is dropped.
+    drop(guard);
+    assert!(wait_until(|| received.lock().unwrap().len() == MESSAGES + 1));
+    assert_eq!(
+        *received.lock().unwrap(),
+        (0..=MESSAGES).collect::<Vec<_>>()
+    );
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,97 @@
+#![cfg(all(feature = "distributed", feature = "testkit"))]
+
+mod common;
+
+use bastion::prelude::*;
+use bastion::transport::{MockNetwork, MockTransport};
+use common::{wait_for, wait_until};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_quorum() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_quorum() {
+        super::run()
+    }
+}
+
+const NODES: usize = 3;
+
+type Contexts = Arc<Mutex<HashMap<Uuid, Arc<DistributedContext>>>>;
+
+fn node(transport: MockTransport, contexts: Contexts) {
+    Bastion::distributed(transport, move |dctx| {
+        let contexts = contexts.clone();
+        async move {
+            contexts
+                .lock()
+                .unwrap()
+                .insert(dctx.current(), dctx.clone());
+            loop {
+                dctx.recv().await?;
+            }
+        }
+    })
+    .expect("Couldn't start a cluster node.");
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let network = MockNetwork::new();
+    let transports: Vec<MockTransport> = (0..NODES).map(|_| network.join()).collect();
+    let ids: Vec<Uuid> = transports.iter().map(MockTransport::node_id).collect();
+
+    let contexts: Contexts = Arc::new(Mutex::new(HashMap::new()));
+    for transport in transports {
+        node(transport, contexts.clone());
+    }
+
+    let quorums = || {
+        let contexts = contexts.lock().unwrap();
+        ids.iter()
+            .map(|id| contexts.get(id).map(|dctx| dctx.has_quorum()))
+            .collect::<Vec<_>>()
+    };
+    let formed = || {
+        let contexts = contexts.lock().unwrap();
+        contexts.len() == NODES
+            && contexts
+                .values()
+                .all(|dctx| dctx.members().len() == NODES - 1)
+    };
+    assert!(wait_until(formed), "the cluster didn't form");
+    assert_eq!(quorums(), vec![Some(true); NODES]);
+
+    let mut events = Bastion::event_stream();
+    let lone = ids[0];
+    network.partition(&[lone]);
+
+    let expected = vec![Some(false), Some(true), Some(true)];
+    assert!(wait_until(|| quorums() == expected), "{:?}", quorums());
+    assert!(wait_for(&mut events, |event| match event {
+        SystemEvent::QuorumLost { node } => *node == lone,
+        _ => false,
+    }));
+
+    network.heal();
+    assert!(wait_until(|| quorums() == vec![Some(true); NODES]));
+    assert!(wait_for(&mut events, |event| match event {
+        SystemEvent::QuorumRegained { node } => *node == lone,
+        _ => false,
+    }));
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,127 @@
+#![cfg(all(feature = "distributed", feature = "testkit"))]
+
+mod common;
+
+use bastion::prelude::*;
+use bastion::transport::{MockNetwork, MockTransport};
+use common::wait_until;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_remote_dispatcher() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_remote_dispatcher() {
+        super::run()
+    }
+}
+
+const WEIGHTS: [u32; 3] = [1, 2, 4];
+const MESSAGES: usize = 700;
+
+// The amount of routed payloads received by every weighted node.
+type Counts = Arc<Mutex<HashMap<Uuid, usize>>>;
+
+fn weighted_node(transport: MockTransport, weight: u32, counts: Counts) {
+    let config = ClusterConfig::from(transport).with_tag("weight", weight.to_string());
+    Bastion::distributed(config, move |dctx| {
+        let counts = counts.clone();
+        async move {
+            loop {
+                let msg = dctx.recv().await?;
+                let payload: String = msg.extract().downcast().unwrap();
+                if payload == "work" {
+                    *counts.lock().unwrap().entry(dctx.current()).or_insert(0) += 1;
+                }
+            }
+        }
+    })
+    .expect("Couldn't start a weighted node.");
+}
+
+fn router(transport: MockTransport, routed: Arc<AtomicBool>) {
+    Bastion::distributed(transport, move |dctx| {
+        let routed = routed.clone();
+        async move {
+            // The members which didn't announce a weight, like the
+            // driver, don't get any work.
+            let dispatcher = RemoteDispatcher::new().with_default_weight(0);
+            loop {
+                // The driver asks the router to route the work once
+                // it knows the weight of every node.
+                dctx.recv().await?;
+                let weighted = dctx.members_where(|metadata| metadata.contains_key("weight"));
+                if routed.load(Ordering::SeqCst) || weighted.len() < WEIGHTS.len() {
+                    continue;
+                }
+
+                for _ in 0..MESSAGES {
+                    dispatcher.tell(&dctx, "work".to_string()).unwrap();
+                }
+                routed.store(true, Ordering::SeqCst);
+            }
+        }
+    })
+    .expect("Couldn't start the router.");
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let network =
+        MockNetwork::with_seed(3).with_delay(Duration::from_millis(1), Duration::from_millis(5));
+    let transports: Vec<MockTransport> = WEIGHTS.iter().map(|_| network.join()).collect();
+    let ids: Vec<Uuid> = transports.iter().map(MockTransport::node_id).collect();
+    let router_transport = network.join();
+    let router_id = router_transport.node_id();
+    let driver = network.join();
+
+    let counts: Counts = Arc::new(Mutex::new(HashMap::new()));
+    for (transport, weight) in transports.into_iter().zip(WEIGHTS.iter()) {
+        weighted_node(transport, *weight, counts.clone());
+    }
+    let routed = Arc::new(AtomicBool::new(false));
+    router(router_transport, routed.clone());
+
+    let poked = wait_until(|| {
+        let poked = routed.load(Ordering::SeqCst);
+        if !poked {
+            driver.send_payload(router_id, "route".to_string());
+        }
+        poked
+    });
+    assert!(poked, "The work was never routed.");
+
+    let received = || counts.lock().unwrap().values().sum::<usize>();
+    assert!(wait_until(|| received() >= MESSAGES));
+    assert_eq!(received(), MESSAGES);
+
+    let total: u32 = WEIGHTS.iter().sum();
+    let counts = counts.lock().unwrap();
+    for (id, weight) in ids.iter().zip(WEIGHTS.iter()) {
+        let expected = MESSAGES as f64 * f64::from(*weight) / f64::from(total);
+        let count = counts.get(id).copied().unwrap_or(0) as f64;
+        assert!(
+            (count - expected).abs() <= expected * 0.05,
+            "node of weight {} received {} payloads instead of {}",
+            weight,
+            count,
+            expected
+        );
+    }
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
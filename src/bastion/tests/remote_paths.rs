@@ -0,0 +1,87 @@
+#![cfg(all(feature = "distributed", feature = "testkit"))]
+
+mod common;
+
+use bastion::prelude::*;
+use bastion::transport::MockNetwork;
+use common::wait_until;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_remote_paths() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_remote_paths() {
+        super::run()
+    }
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let network = MockNetwork::new();
+    // The nodes of a mock network get the address `127.0.0.1:<n>`,
+    // where `n` is the order they joined in.
+    let sender = network.join();
+    let sender_addr = SocketAddr::from(([127, 0, 0, 1], 1));
+    let receiver = network.join();
+    let receiver_id = receiver.node_id();
+    let driver = network.join();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_ref = received.clone();
+    Bastion::distributed(receiver, move |dctx| {
+        let received = received_ref.clone();
+        async move {
+            loop {
+                let msg = dctx.recv().await?;
+                received.lock().unwrap().push(msg.path().clone());
+            }
+        }
+    })
+    .expect("Couldn't start the receiver.");
+
+    let sender_id = sender.node_id();
+    let config = ClusterConfig::from(sender).with_node_name("sender");
+    Bastion::distributed(config, move |dctx| async move {
+        loop {
+            // The driver asks the sender to send a message once it
+            // handled the membership events, announcing its name.
+            dctx.recv().await?;
+            dctx.tell(&receiver_id, "hello".to_string()).unwrap();
+        }
+    })
+    .expect("Couldn't start the sender.");
+
+    assert!(wait_until(|| {
+        let sent = !received.lock().unwrap().is_empty();
+        if !sent {
+            driver.send_payload(sender_id, "send".to_string());
+        }
+        sent
+    }));
+
+    let path = received
+        .lock()
+        .unwrap()
+        .first()
+        .cloned()
+        .expect("no message was received");
+    assert!(path.is_remote());
+    let node = path.node().unwrap();
+    assert_eq!(node.name(), "sender");
+    assert_eq!(node.addr(), Some(sender_addr));
+    assert!(path.to_string().starts_with("sender@127.0.0.1:1"));
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
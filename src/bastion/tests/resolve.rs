@@ -0,0 +1,74 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_resolve() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_resolve() {
+        super::run()
+    }
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // The path the child reports for itself, and the messages it received.
+    let path = Arc::new(Mutex::new(None));
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let path_ref = path.clone();
+    let received_ref = received.clone();
+    let children = Bastion::children(move |children| {
+        let path = path_ref.clone();
+        let received = received_ref.clone();
+        children
+            .with_name("resolved")
+            .with_exec(move |ctx: BastionContext| {
+                let path = path.clone();
+                let received = received.clone();
+                async move {
+                    *path.lock().unwrap() = Some(ctx.current().path().as_ref().clone());
+                    loop {
+                        msg! { ctx.recv().await?,
+                            msg: &'static str => {
+                                received.lock().unwrap().push(msg);
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    assert!(wait_until(|| path.lock().unwrap().is_some()));
+    let path: BastionPath = path.lock().unwrap().clone().unwrap();
+
+    let child_ref = Bastion::resolve(&path).expect("Couldn't resolve the child.");
+    assert_eq!(child_ref.id(), children.elems()[0].id());
+    assert_eq!(child_ref.name(), "resolved");
+    child_ref.tell_anonymously("hello").unwrap();
+    assert!(wait_until(|| *received.lock().unwrap() == vec!["hello"]));
+
+    // Paths which don't lead to a child aren't resolved.
+    assert!(Bastion::resolve(children.path()).is_none());
+
+    // Stopped children aren't resolved anymore.
+    children.stop().unwrap();
+    assert!(wait_until(|| Bastion::resolve(&path).is_none()));
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
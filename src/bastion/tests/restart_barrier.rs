@@ -0,0 +1,87 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_restart_barrier() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_restart_barrier() {
+        super::run()
+    }
+}
+
+const REDUNDANCY: usize = 3;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // The amount of times the elements started...
+    let starts = Arc::new(AtomicUsize::new(0));
+    // ...and entered their message loop.
+    let entered = Arc::new(AtomicUsize::new(0));
+
+    let starts_ref = starts.clone();
+    let entered_ref = entered.clone();
+    let supervisor = Bastion::supervisor(|sp| sp.with_strategy(SupervisionStrategy::OneForAll))
+        .expect("Couldn't create the supervisor.");
+    let children = supervisor
+        .children(move |children| {
+            let starts = starts_ref.clone();
+            let entered = entered_ref.clone();
+            children
+                .with_redundancy(REDUNDANCY)
+                .with_exec(move |ctx: BastionContext| {
+                    let starts = starts.clone();
+                    let entered = entered.clone();
+                    async move {
+                        starts.fetch_add(1, Ordering::SeqCst);
+                        // Slow enough for the barrier to be awaited
+                        // while the elements are being restarted.
+                        Delay::new(Duration::from_millis(100)).await;
+                        entered.fetch_add(1, Ordering::SeqCst);
+
+                        loop {
+                            msg! { ctx.recv().await?,
+                                msg: &'static str => {
+                                    if msg == "fault" {
+                                        return Err(());
+                                    }
+                                };
+                                _: _ => ();
+                            }
+                        }
+                    }
+                })
+        })
+        .expect("Couldn't create the children group.");
+
+    run!(children.await_ready());
+    assert_eq!(entered.load(Ordering::SeqCst), REDUNDANCY);
+
+    children.elems()[0].tell_anonymously("fault").unwrap();
+    assert!(wait_until(|| starts.load(Ordering::SeqCst) > REDUNDANCY));
+    assert!(starts.load(Ordering::SeqCst) > REDUNDANCY);
+
+    // Every element was restarted and entered its message loop again
+    // once the barrier resolved.
+    run!(children.restart_completed());
+    assert_eq!(entered.load(Ordering::SeqCst), 2 * REDUNDANCY);
+    assert_eq!(starts.load(Ordering::SeqCst), 2 * REDUNDANCY);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until_within;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_restart_jitter() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_restart_jitter() {
+        super::run()
+    }
+}
+
+const REDUNDANCY: usize = 20;
+const BACKOFF: Duration = Duration::from_millis(100);
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // The instants every element started at.
+    let started = Arc::new(Mutex::new(HashMap::new()));
+
+    let started_inner = started.clone();
+    let supervisor = Bastion::supervisor(|sp| {
+        sp.with_restart_strategy(
+            RestartStrategy::default()
+                .with_actor_restart_strategy(ActorRestartStrategy::LinearBackOff {
+                    timeout: BACKOFF,
+                })
+                .with_jitter(BackoffJitter::Full),
+        )
+    })
+    .expect("Couldn't create the supervisor.");
+    let children = supervisor
+        .children(move |children| {
+            let started = started_inner.clone();
+            children
+                .with_redundancy(REDUNDANCY)
+                .with_exec(move |ctx: BastionContext| {
+                    let started = started.clone();
+                    async move {
+                        let id = ctx.current().id().clone();
+                        started
+                            .lock()
+                            .unwrap()
+                            .entry(id)
+                            .or_insert_with(Vec::new)
+                            .push(Instant::now());
+
+                        msg! { ctx.recv().await?,
+                            _: &'static str => ();
+                            _: _ => ();
+                        }
+
+                        // Every element faults at the same time.
+                        Err(())
+                    }
+                })
+        })
+        .expect("Couldn't create the children group.");
+
+    assert!(wait_until_within(Duration::from_secs(10), || {
+        started.lock().unwrap().len() == REDUNDANCY
+    }));
+    children.broadcast("fault").unwrap();
+
+    assert!(wait_until_within(Duration::from_secs(10), || {
+        let started = started.lock().unwrap();
+        started.len() == REDUNDANCY && started.values().all(|starts| starts.len() >= 2)
+    }));
+
+    let mut restarted = started
+        .lock()
+        .unwrap()
+        .values()
+        .filter_map(|starts| starts.get(1).copied())
+        .collect::<Vec<_>>();
+    assert_eq!(restarted.len(), REDUNDANCY);
+    restarted.sort();
+
+    // Without jitter, every restart would be delayed by exactly the
+    // backoff, whereas the jittered delays are spread between zero
+    // and the backoff.
+    let gaps = restarted
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .collect::<Vec<_>>();
+    let shortest = *gaps.iter().min().unwrap();
+    let longest = *gaps.iter().max().unwrap();
+    assert!(
+        longest - shortest > BACKOFF / 5,
+        "The restarts weren't spread: {:?}",
+        gaps
+    );
+    assert!(
+        longest < BACKOFF * 2,
+        "A restart was delayed past the backoff: {:?}",
+        gaps
+    );
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_retry() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_retry() {
+        super::run()
+    }
+}
+
+static STARTS: AtomicUsize = AtomicUsize::new(0);
+static ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+static PROCESSED: AtomicUsize = AtomicUsize::new(0);
+
+// Fails the first two times it is called.
+async fn flaky(_request: &str) -> Result<(), ()> {
+    if ATTEMPTS.fetch_add(1, Ordering::SeqCst) < 2 {
+        Err(())
+    } else {
+        Ok(())
+    }
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let policy = RetryPolicy::new(3).with_backoff(ActorRestartStrategy::LinearBackOff {
+        timeout: Duration::from_millis(10),
+    });
+
+    let children = Bastion::children(|children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let policy = policy.clone();
+            async move {
+                STARTS.fetch_add(1, Ordering::SeqCst);
+                loop {
+                    msg! { ctx.recv().await?,
+                        ref request: &'static str => {
+                            ctx.retry(&policy, || flaky(request)).await?;
+                            PROCESSED.fetch_add(1, Ordering::SeqCst);
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    children.elems()[0]
+        .tell_anonymously("request")
+        .expect("Couldn't send the message.");
+
+    assert!(wait_until(|| PROCESSED.load(Ordering::SeqCst) == 1));
+    // Leaves time for the child to be (wrongly) restarted.
+    thread::sleep(Duration::from_millis(100));
+
+    assert_eq!(ATTEMPTS.load(Ordering::SeqCst), 3);
+    assert_eq!(PROCESSED.load(Ordering::SeqCst), 1);
+    // The failed attempts didn't fault the child.
+    assert_eq!(STARTS.load(Ordering::SeqCst), 1);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
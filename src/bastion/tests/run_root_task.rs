@@ -0,0 +1,49 @@
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_run_root_task() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_run_root_task() {
+        super::run()
+    }
+}
+
+fn run() {
+    Bastion::init();
+
+    // A children group living next to the root task, which tells
+    // whether the system was stopped once the task resolved.
+    let stopped = Arc::new(AtomicBool::new(false));
+    let stopped_ref = stopped.clone();
+    Bastion::children(move |children| {
+        let stopped = stopped_ref.clone();
+        children
+            .with_callbacks(Callbacks::new().with_after_stop(move || {
+                stopped.store(true, Ordering::SeqCst);
+            }))
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let output = Bastion::run(|| async {
+        let numbers: Vec<u64> = (1..=10).collect();
+        numbers.iter().sum::<u64>()
+    });
+
+    assert_eq!(output, Ok(55));
+    assert!(stopped.load(Ordering::SeqCst));
+}
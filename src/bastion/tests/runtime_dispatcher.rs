@@ -0,0 +1,75 @@
+#![cfg(feature = "testkit")]
+mod common;
+
+use bastion::prelude::*;
+use bastion::testkit::TestProbe;
+use common::{wait_for, wait_until};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_runtime_dispatcher() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_runtime_dispatcher() {
+        super::run()
+    }
+}
+
+const GROUP: &str = "runtime";
+
+// Broadcasts `value` to the group, returning the identifier of the sender.
+fn broadcast(value: usize) -> BastionId {
+    let sender = Bastion::spawn(move |ctx: BastionContext| async move {
+        ctx.broadcast_message(BroadcastTarget::Group(GROUP.to_string()), value);
+        Ok(())
+    })
+    .expect("Couldn't create the sender.");
+
+    sender.elems()[0].id().clone()
+}
+
+fn run() {
+    Bastion::init();
+    let mut events = Bastion::event_stream();
+    Bastion::start();
+
+    let dispatcher_type = DispatcherType::Named(GROUP.to_string());
+    Bastion::register_dispatcher(Dispatcher::with_type(dispatcher_type.clone()))
+        .expect("Couldn't register the dispatcher.");
+    // It can't be registered twice.
+    assert!(Bastion::register_dispatcher(Dispatcher::with_type(dispatcher_type.clone())).is_err());
+
+    let mut probe = TestProbe::new();
+    probe
+        .join(Dispatcher::with_type(dispatcher_type.clone()))
+        .unwrap();
+
+    broadcast(1);
+    let mut received = None;
+    assert!(wait_until(|| {
+        received = probe.try_recv();
+        received.is_some()
+    }));
+    let (msg, _) = received.expect("The message wasn't routed.").extract();
+    assert_eq!(msg.downcast_ref::<usize>().map(|value| *value), Some(1));
+
+    Bastion::remove_dispatcher(&dispatcher_type).expect("Couldn't remove the dispatcher.");
+    assert!(Bastion::remove_dispatcher(&dispatcher_type).is_err());
+
+    let sender = broadcast(2);
+    let dead_lettered = wait_for(&mut events, |event| match event {
+        SystemEvent::DeadLetter { sender: from } => from.id() == &sender,
+        _ => false,
+    });
+    assert!(dead_lettered, "the message wasn't dead-lettered");
+    probe.expect_no_msg();
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
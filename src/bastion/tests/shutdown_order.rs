@@ -0,0 +1,71 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_shutdown_order() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_shutdown_order() {
+        super::run()
+    }
+}
+
+const GROUPS: usize = 3;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let started = Arc::new(Mutex::new(Vec::new()));
+    let stopped = Arc::new(Mutex::new(Vec::new()));
+
+    // Each group is only created once the previous one started, so
+    // that they are started in a known order.
+    for group in 0..GROUPS {
+        let started_ref = started.clone();
+        let stopped_ref = stopped.clone();
+        Bastion::children(move |children| {
+            let callbacks = Callbacks::new().with_after_stop(move || {
+                // Both the child and its group call this callback.
+                let mut stopped = stopped_ref.lock().unwrap();
+                if !stopped.contains(&group) {
+                    stopped.push(group);
+                }
+            });
+
+            children
+                .with_callbacks(callbacks)
+                .with_exec(move |ctx: BastionContext| {
+                    let started = started_ref.clone();
+                    async move {
+                        started.lock().unwrap().push(group);
+                        loop {
+                            ctx.recv().await?;
+                        }
+                    }
+                })
+        })
+        .expect("Couldn't create a children group.");
+
+        assert!(wait_until(|| started.lock().unwrap().len() > group));
+    }
+
+    assert_eq!(*started.lock().unwrap(), (0..GROUPS).collect::<Vec<_>>());
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+
+    let mut expected = started.lock().unwrap().clone();
+    expected.reverse();
+    assert_eq!(*stopped.lock().unwrap(), expected);
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_shutdown_report() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_shutdown_report() {
+        super::run()
+    }
+}
+
+const GROUPS: usize = 2;
+const REDUNDANCY: usize = 2;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let started = Arc::new(AtomicUsize::new(0));
+    let blocking = Arc::new(AtomicBool::new(false));
+
+    // NOTE: the groups are stopped in the reverse of the order they
+    //      were created in, so the stubborn one is created first for
+    //      the others not to wait for it.
+    let blocking_ref = blocking.clone();
+    let stubborn = Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let blocking = blocking_ref.clone();
+            async move {
+                msg! { ctx.recv().await?,
+                    _: &'static str => {
+                        blocking.store(true, Ordering::SeqCst);
+                        // Blocks its thread, so that it can't be told
+                        // to stop meanwhile.
+                        thread::sleep(Duration::from_secs(2));
+                    };
+                    _: _ => ();
+                }
+
+                Ok(())
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    let mut others = Vec::new();
+    for _ in 0..GROUPS {
+        let started = started.clone();
+        let children = Bastion::children(move |children| {
+            let started = started.clone();
+            children
+                .with_redundancy(REDUNDANCY)
+                .with_exec(move |ctx: BastionContext| {
+                    started.fetch_add(1, Ordering::SeqCst);
+                    async move {
+                        loop {
+                            ctx.recv().await?;
+                        }
+                    }
+                })
+        })
+        .expect("Couldn't create the children group.");
+        others.push(children);
+    }
+
+    assert!(wait_until(
+        || started.load(Ordering::SeqCst) == GROUPS * REDUNDANCY
+    ));
+    let stubborn_path = stubborn.elems()[0].path().clone();
+    stubborn.elems()[0]
+        .tell_anonymously("block")
+        .expect("Couldn't send the message.");
+    assert!(wait_until(|| blocking.load(Ordering::SeqCst)));
+
+    let report = Bastion::stop_with_timeout(Duration::from_millis(500));
+
+    // Only the element blocking its thread didn't stop in time...
+    assert!(!report.is_clean());
+    assert_eq!(report.timed_out(), vec![&*stubborn_path]);
+    assert!(report.errored().is_empty());
+
+    // ...while the other ones stopped cleanly.
+    for children in &others {
+        for child in children.elems() {
+            assert_eq!(report.status(child.path()), Some(ShutdownStatus::Stopped));
+        }
+    }
+
+    Bastion::block_until_stopped();
+}
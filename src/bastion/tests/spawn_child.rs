@@ -0,0 +1,102 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_spawn_child() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_spawn_child() {
+        super::run()
+    }
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    // The amount of groups (the spawning one and its helper) which
+    // stopped.
+    let stopped = Arc::new(AtomicUsize::new(0));
+
+    let received_ref = received.clone();
+    let stopped_ref = stopped.clone();
+    let supervisor = Bastion::supervisor(move |sp| {
+        sp.children(move |children| {
+            let received = received_ref.clone();
+            let stopped = stopped_ref.clone();
+            let on_stop = stopped.clone();
+            children
+                .with_callbacks(Callbacks::new().with_after_stop(move || {
+                    on_stop.fetch_add(1, Ordering::SeqCst);
+                }))
+                .with_exec(move |ctx: BastionContext| {
+                    let received = received.clone();
+                    let stopped = stopped.clone();
+                    async move {
+                        let helper = ctx
+                            .spawn_child(move |children| {
+                                children
+                                    .with_callbacks(Callbacks::new().with_after_stop(move || {
+                                        stopped.fetch_add(1, Ordering::SeqCst);
+                                    }))
+                                    .with_exec(move |ctx: BastionContext| {
+                                        let received = received.clone();
+                                        async move {
+                                            loop {
+                                                msg! { ctx.recv().await?,
+                                                    msg: &'static str => {
+                                                        received.lock().unwrap().push(msg);
+                                                    };
+                                                    _: _ => ();
+                                                }
+                                            }
+                                        }
+                                    })
+                            })
+                            .expect("Couldn't spawn the helper.");
+
+                        helper.broadcast("help").unwrap();
+
+                        loop {
+                            ctx.recv().await?;
+                        }
+                    }
+                })
+        })
+    })
+    .expect("Couldn't create the supervisor.");
+
+    // The helper runs under the same supervisor...
+    assert!(wait_until(|| !received.lock().unwrap().is_empty()));
+    assert_eq!(*received.lock().unwrap(), vec!["help"]);
+    let groups = || {
+        Bastion::topology()
+            .supervisor(supervisor.id())
+            .map_or(0, |supervisor| supervisor.children.len())
+    };
+    assert!(wait_until(|| groups() == 2));
+    assert_eq!(groups(), 2);
+
+    // ...and is stopped along with the group which spawned it.
+    supervisor.stop().unwrap();
+    assert!(wait_until(|| stopped.load(Ordering::SeqCst) >= 2));
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(stopped.load(Ordering::SeqCst), 2);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,50 @@
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_stopped() {
+        super::run().await
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    use bastion::prelude::*;
+
+    #[test]
+    fn test_stopped() {
+        run!(super::run())
+    }
+}
+
+async fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let stopped = Arc::new(AtomicBool::new(false));
+    let stopped_ref = stopped.clone();
+    Bastion::children(move |children| {
+        let stopped = stopped_ref.clone();
+        children
+            .with_callbacks(Callbacks::new().with_after_stop(move || {
+                stopped.store(true, Ordering::SeqCst);
+            }))
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    Bastion::stop();
+    Bastion::stopped().await;
+
+    // The system's elements were torn down before it resolved...
+    assert!(stopped.load(Ordering::SeqCst));
+    // ...and it resolves right away once the system is stopped.
+    Bastion::stopped().await;
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_supervise_future() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_supervise_future() {
+        super::run()
+    }
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let runs = Arc::new(AtomicUsize::new(0));
+    let completed = Arc::new(AtomicBool::new(false));
+
+    let runs_ref = runs.clone();
+    let completed_ref = completed.clone();
+    Bastion::supervisor(move |sp| {
+        sp.supervise_future(move || {
+            let runs = runs_ref.clone();
+            let completed = completed_ref.clone();
+            async move {
+                if runs.fetch_add(1, Ordering::SeqCst) == 0 {
+                    panic!("Failing on the first run.");
+                }
+
+                completed.store(true, Ordering::SeqCst);
+                Ok(())
+            }
+        })
+    })
+    .expect("Couldn't create the supervisor.");
+
+    assert!(wait_until(|| completed.load(Ordering::SeqCst)));
+
+    assert!(completed.load(Ordering::SeqCst));
+    // It was restarted once, and not after completing.
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
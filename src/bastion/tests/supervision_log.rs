@@ -0,0 +1,99 @@
+#![cfg(feature = "json-logs")]
+
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use serde_json::Value;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_supervision_log() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_supervision_log() {
+        super::run()
+    }
+}
+
+// Captures the logs written by the subscriber.
+struct Capture(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// Returns the logged supervision events of the given kind.
+fn events(logs: &Mutex<Vec<u8>>, kind: &str) -> Vec<Value> {
+    let logs = String::from_utf8(logs.lock().unwrap().clone()).unwrap();
+    logs.lines()
+        .filter_map(|line| line.find('{').map(|start| &line[start..]))
+        .filter_map(|entry| serde_json::from_str::<Value>(entry).ok())
+        .filter(|entry| entry["event"] == kind)
+        .collect()
+}
+
+fn run() {
+    let logs = Arc::new(Mutex::new(Vec::new()));
+    let logs_ref = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || Capture(logs_ref.clone()))
+        .with_ansi(false)
+        .without_time()
+        .with_level(false)
+        .with_target(false)
+        .finish();
+    tracing::subscriber::set_global_default(subscriber).unwrap();
+
+    Bastion::init();
+    Bastion::start();
+
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    msg: &'static str => {
+                        panic!("{}", msg);
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    let child = &children.elems()[0];
+    child.tell_anonymously("boom").unwrap();
+    assert!(wait_until(|| !events(&logs, "child_restarted").is_empty()));
+
+    // The fault and the restart are both logged, with the path of the
+    // element and why it faulted.
+    let path = child.path().to_string();
+    for kind in &["child_faulted", "child_restarted"] {
+        let events = events(&logs, kind);
+        assert_eq!(events.len(), 1, "{}: {:?}", kind, events);
+        let event = &events[0];
+        assert_eq!(event["path"], path.as_str());
+        assert_eq!(event["reason"], "panicked");
+        assert_eq!(event["message"], "boom");
+        assert!(event["timestamp"].is_u64(), "{:?}", event);
+    }
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
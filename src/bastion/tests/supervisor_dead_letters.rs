@@ -0,0 +1,94 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use futures_timer::Delay;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_supervisor_dead_letters() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_supervisor_dead_letters() {
+        super::run()
+    }
+}
+
+type Received = Arc<Mutex<Vec<&'static str>>>;
+
+// Creates a children group keeping the dead letters it receives.
+fn handler(received: Received) -> ChildrenRef {
+    Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let received = received.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        msg: &'static str => {
+                            received.lock().unwrap().push(msg);
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the dead letter handler.")
+}
+
+// Creates a supervisor routing its dead letters to the given handler,
+// with a child which lets a message it sent to itself expire.
+fn subtree(handler: ChildrenRef, stale: &'static str) -> SupervisorRef {
+    Bastion::supervisor(move |sp| {
+        sp.with_dead_letter_handler(handler)
+            .children(move |children| {
+                children.with_exec(move |ctx: BastionContext| async move {
+                    let addr = ctx.current().addr();
+                    ctx.tell_ttl(&addr, stale, Duration::from_millis(10))
+                        .unwrap();
+                    // The message expires while the child is busy.
+                    Delay::new(Duration::from_millis(200)).await;
+
+                    loop {
+                        msg! { ctx.recv().await?,
+                            _: _ => ();
+                        }
+                    }
+                })
+            })
+    })
+    .expect("Couldn't create the supervisor.")
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let first = Received::default();
+    let second = Received::default();
+    subtree(handler(first.clone()), "first");
+    subtree(handler(second.clone()), "second");
+
+    assert!(wait_until(
+        || !first.lock().unwrap().is_empty() && !second.lock().unwrap().is_empty()
+    ));
+    thread::sleep(Duration::from_millis(50));
+
+    // Each dead letter only reached the handler of its subtree...
+    assert_eq!(*first.lock().unwrap(), vec!["first"]);
+    assert_eq!(*second.lock().unwrap(), vec!["second"]);
+    // ...instead of the global dead letters.
+    assert_eq!(Bastion::dead_letters_count(), 0);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
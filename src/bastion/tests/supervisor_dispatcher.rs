@@ -0,0 +1,116 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{unwrap, wait_until};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_supervisor_dispatcher() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_supervisor_dispatcher() {
+        super::run()
+    }
+}
+
+const SHARED: &str = "supervisor-shared";
+const REDUNDANCY: usize = 2;
+const MESSAGES: usize = 20;
+
+// The messages received by the elements of both groups, with the
+// name of the group of their receiver.
+type Received = Arc<Mutex<Vec<(&'static str, usize)>>>;
+
+fn group(
+    supervisor: &SupervisorRef,
+    name: &'static str,
+    started: Arc<AtomicUsize>,
+    received: Received,
+) -> ChildrenRef {
+    supervisor
+        .children(move |children| {
+            children
+                .with_redundancy(REDUNDANCY)
+                .with_exec(move |ctx: BastionContext| {
+                    let started = started.clone();
+                    let received = received.clone();
+                    async move {
+                        started.fetch_add(1, Ordering::SeqCst);
+
+                        loop {
+                            msg! { ctx.recv().await?,
+                                raw_message: Arc<SignedMessage> => {
+                                    msg! { unwrap(raw_message).await,
+                                        value: usize => {
+                                            received.lock().unwrap().push((name, value));
+                                        };
+                                        _: _ => ();
+                                    }
+                                };
+                                _: _ => ();
+                            }
+                        }
+                    }
+                })
+        })
+        .expect("Couldn't create the children group.")
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let started = Arc::new(AtomicUsize::new(0));
+    let received: Received = Arc::new(Mutex::new(Vec::new()));
+
+    let supervisor = Bastion::supervisor(|sp| {
+        sp.with_dispatcher(Dispatcher::with_type(DispatcherType::Named(
+            SHARED.to_string(),
+        )))
+    })
+    .expect("Couldn't create the supervisor.");
+
+    // Neither group declares the dispatcher itself.
+    group(&supervisor, "first", started.clone(), received.clone());
+    group(&supervisor, "second", started.clone(), received.clone());
+    assert!(wait_until(
+        || started.load(Ordering::SeqCst) == 2 * REDUNDANCY
+    ));
+
+    Bastion::spawn(|ctx: BastionContext| async move {
+        for value in 0..MESSAGES {
+            ctx.broadcast_message(BroadcastTarget::Group(SHARED.to_string()), value);
+        }
+
+        Ok(())
+    })
+    .expect("Couldn't create the sender.");
+
+    assert!(wait_until(|| received.lock().unwrap().len() == MESSAGES));
+
+    // Every message was delivered once, and the messages were
+    // distributed across the elements of both groups.
+    let received = received.lock().unwrap();
+    let mut values = received.iter().map(|(_, value)| *value).collect::<Vec<_>>();
+    values.sort_unstable();
+    assert_eq!(values, (0..MESSAGES).collect::<Vec<_>>());
+
+    let first = received.iter().filter(|(name, _)| *name == "first").count();
+    let second = received
+        .iter()
+        .filter(|(name, _)| *name == "second")
+        .count();
+    assert_eq!(first, MESSAGES / 2);
+    assert_eq!(second, MESSAGES / 2);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
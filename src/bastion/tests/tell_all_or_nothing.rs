@@ -0,0 +1,119 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_tell_all_or_nothing() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_tell_all_or_nothing() {
+        super::run()
+    }
+}
+
+const HIGH_WATERMARK: usize = 4;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // The consumer only receives its messages once the gate opens, so
+    // that they pile up in its mailbox until then.
+    let gate = Arc::new(AtomicBool::new(false));
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let gate_ref = gate.clone();
+    let received_ref = received.clone();
+    let consumers = Bastion::children(move |children| {
+        let gate = gate_ref.clone();
+        let received = received_ref.clone();
+        children
+            .with_mailbox_high_watermark(HIGH_WATERMARK)
+            .with_exec(move |ctx: BastionContext| {
+                let gate = gate.clone();
+                let received = received.clone();
+                async move {
+                    while !gate.load(Ordering::SeqCst) {
+                        Delay::new(Duration::from_millis(10)).await;
+                    }
+
+                    loop {
+                        msg! { ctx.recv().await?,
+                            i: i32 => received.lock().unwrap().push(i);
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    let consumer = consumers.elems()[0].clone();
+
+    // The mailbox is half-full...
+    for i in 0..2 {
+        consumer.tell_anonymously(i).unwrap();
+    }
+    thread::sleep(Duration::from_millis(200));
+
+    let results = Arc::new(Mutex::new(Vec::new()));
+    let results_ref = results.clone();
+    Bastion::children(move |children| {
+        let consumer = consumer.clone();
+        let results = results_ref.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let consumer = consumer.clone();
+            let results = results.clone();
+            async move {
+                // ...so a batch of three messages is fully rejected...
+                let batch = (2..5).map(|i| (consumer.clone(), i)).collect();
+                let over_capacity = ctx.tell_all_or_nothing(batch);
+                // ...while a batch of two messages is fully enqueued.
+                let batch = (5..7).map(|i| (consumer.clone(), i)).collect();
+                let in_capacity = ctx.tell_all_or_nothing(batch);
+
+                results.lock().unwrap().push((over_capacity, in_capacity));
+                Ok(())
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    assert!(wait_until(|| !results.lock().unwrap().is_empty()));
+    let (over_capacity, in_capacity) = results.lock().unwrap().remove(0);
+
+    let rejected = over_capacity.expect_err("the batch wasn't rejected");
+    assert_eq!(
+        rejected
+            .iter()
+            .map(|rejected| rejected.msg)
+            .collect::<Vec<i32>>(),
+        vec![2, 3, 4]
+    );
+    assert!(rejected
+        .iter()
+        .all(|rejected| rejected.reason == Some(RejectionReason::Overloaded)));
+    assert!(in_capacity.is_ok());
+
+    // Only the messages of the accepted batch were enqueued.
+    gate.store(true, Ordering::SeqCst);
+    assert!(wait_until(
+        || received.lock().unwrap().len() == HIGH_WATERMARK
+    ));
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(*received.lock().unwrap(), vec![0, 1, 5, 6]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_tell_async() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_tell_async() {
+        super::run()
+    }
+}
+
+const HIGH_WATERMARK: usize = 4;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let released = Arc::new(AtomicBool::new(false));
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let released_ref = released.clone();
+    let received_ref = received.clone();
+    let children = Bastion::children(move |children| {
+        let released = released_ref.clone();
+        let received = received_ref.clone();
+        children
+            .with_mailbox_high_watermark(HIGH_WATERMARK)
+            .with_exec(move |ctx: BastionContext| {
+                let released = released.clone();
+                let received = received.clone();
+                async move {
+                    // The element doesn't handle its messages until
+                    // it is released, letting its mailbox fill up.
+                    while !released.load(Ordering::SeqCst) {
+                        Delay::new(Duration::from_millis(10)).await;
+                    }
+
+                    loop {
+                        msg! { ctx.recv().await?,
+                            msg: &'static str => {
+                                received.lock().unwrap().push(msg);
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    let child = children.elems()[0].clone();
+
+    for _ in 0..HIGH_WATERMARK * 2 {
+        child.tell_anonymously("backlog").unwrap();
+    }
+
+    let sent = Arc::new(Mutex::new(None));
+    let sent_ref = sent.clone();
+    Bastion::spawn(move |ctx: BastionContext| {
+        let child = child.clone();
+        let sent = sent_ref.clone();
+        async move {
+            let result = ctx.tell_async(&child, "last").await;
+            *sent.lock().unwrap() = Some(result.is_ok());
+
+            Ok(())
+        }
+    })
+    .expect("Couldn't create the sender.");
+
+    // The message isn't sent while the mailbox is full...
+    thread::sleep(Duration::from_millis(200));
+    assert!(sent.lock().unwrap().is_none());
+
+    // ...and is once the element drained it.
+    released.store(true, Ordering::SeqCst);
+    assert!(wait_until(|| sent.lock().unwrap().is_some()));
+    assert_eq!(*sent.lock().unwrap(), Some(true));
+
+    assert!(wait_until(|| received.lock().unwrap().contains(&"last")));
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), HIGH_WATERMARK * 2 + 1);
+    assert_eq!(received.last(), Some(&"last"));
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
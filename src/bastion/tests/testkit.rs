@@ -0,0 +1,127 @@
+#![cfg(feature = "testkit")]
+use bastion::prelude::*;
+use bastion::testkit::{TestActor, TestProbe};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_probe_replies() {
+        super::probe_replies()
+    }
+
+    #[tokio::test]
+    async fn test_probe_round_robin() {
+        super::probe_round_robin()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_probe_replies() {
+        super::probe_replies()
+    }
+
+    #[test]
+    fn test_probe_round_robin() {
+        super::probe_round_robin()
+    }
+}
+
+const WORKERS: usize = 3;
+
+// Replies to every told `&'static str` with its length, and answers
+// every asked one with its uppercase version.
+fn echo() -> TestActor {
+    TestActor::new(|ctx: BastionContext| async move {
+        loop {
+            msg! { ctx.recv().await?,
+                msg: &'static str =!> {
+                    answer!(ctx, msg.to_uppercase()).unwrap();
+                };
+                msg: &'static str => {
+                    let sender = signature!();
+                    ctx.tell(&sender, msg.len()).unwrap();
+                };
+                _: _ => ();
+            }
+        }
+    })
+}
+
+fn probe_replies() {
+    let mut actor = echo();
+    let mut probe = TestProbe::new();
+
+    // Nothing happens until the actor is stepped.
+    probe.tell(actor.child_ref(), "hello").unwrap();
+    probe.tell(actor.child_ref(), "bastion").unwrap();
+    probe.expect_no_msg();
+
+    assert!(actor.step());
+    assert_eq!(probe.expect_msg_of::<usize>(), 5);
+    probe.expect_no_msg();
+
+    actor.run_until_idle();
+    assert!(actor.is_idle());
+    assert_eq!(probe.expect_msg_of::<usize>(), 7);
+    probe.expect_no_msg();
+
+    let answer = probe.ask(actor.child_ref(), "hello").unwrap();
+    actor.run_until_idle();
+    msg! { run!(answer).unwrap(),
+        msg: String => assert_eq!(msg, "HELLO");
+        _: _ => panic!("Unexpected answer.");
+    }
+    assert!(actor.result().is_none());
+}
+
+fn probe_round_robin() {
+    let group = "testkit-round-robin".to_string();
+    let mut workers: Vec<TestProbe> = (0..WORKERS).map(|_| TestProbe::new()).collect();
+    for worker in &workers {
+        worker
+            .join(Dispatcher::with_type(DispatcherType::Named(group.clone())))
+            .unwrap();
+    }
+
+    // Broadcasts to the group as many messages as it is told to.
+    let mut producer = {
+        let group = group.clone();
+        TestActor::new(move |ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    count: usize => {
+                        for i in 0..count {
+                            ctx.broadcast_message(BroadcastTarget::Group(group.clone()), i);
+                        }
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    };
+
+    let driver = TestProbe::new();
+    driver.tell(producer.child_ref(), 2 * WORKERS).unwrap();
+    producer.run_until_idle();
+
+    // Each worker got one message out of every `WORKERS`, in order.
+    let mut received = Vec::new();
+    for worker in &mut workers {
+        let first = worker.expect_msg_of::<usize>();
+        let second = worker.expect_msg_of::<usize>();
+        worker.expect_no_msg();
+
+        assert_eq!(second, first + WORKERS);
+        received.push(first);
+        received.push(second);
+    }
+
+    received.sort_unstable();
+    assert_eq!(received, (0..2 * WORKERS).collect::<Vec<_>>());
+
+    for worker in &workers {
+        worker.leave(DispatcherType::Named(group.clone()));
+    }
+}
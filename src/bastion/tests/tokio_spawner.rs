@@ -0,0 +1,48 @@
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    use bastion::executor::TokioSpawner;
+    use bastion::prelude::*;
+    use tokio::runtime::Handle;
+
+    // NOTE: the test blocks one of the runtime's threads, which a
+    //      current-thread runtime couldn't survive.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_full_cycle_on_tokio_runtime() {
+        let spawner = TokioSpawner::new(Handle::current());
+        Bastion::init_with(Config::new().with_tokio_spawner(spawner));
+        Bastion::start();
+
+        let workers = Bastion::children(|children| {
+            children.with_exec(|ctx| async move {
+                // Bastion's tasks are spawned onto the test's runtime.
+                assert!(tokio::runtime::Handle::try_current().is_ok());
+
+                msg! {
+                    ctx.recv().await?,
+                    question: &'static str =!> {
+                        assert_eq!(question, "marco");
+                        answer!(ctx, "polo").expect("couldn't send answer");
+                    };
+                    _: _ => panic!("didn't receive &str");
+                }
+
+                Ok(())
+            })
+        })
+        .expect("Couldn't create the children group.");
+
+        let answer = workers.elems()[0]
+            .ask_anonymously("marco")
+            .expect("Couldn't send the message.");
+
+        msg! { answer.await.expect("couldn't receive answer"),
+            reply: &'static str => assert_eq!(reply, "polo");
+            _: _ => panic!("didn't receive &str");
+        }
+
+        Bastion::stop();
+        tokio::task::spawn_blocking(Bastion::block_until_stopped)
+            .await
+            .expect("Couldn't wait for the system to stop.");
+    }
+}
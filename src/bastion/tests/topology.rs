@@ -0,0 +1,99 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_topology_snapshot() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_topology_snapshot() {
+        super::run()
+    }
+}
+
+fn idle_group(children: Children, name: &str, redundancy: usize) -> Children {
+    children
+        .with_name(name)
+        .with_redundancy(redundancy)
+        .with_exec(|ctx: BastionContext| async move {
+            loop {
+                ctx.recv().await?;
+            }
+        })
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let parent = Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
+    let parent_group = parent
+        .children(|children| {
+            idle_group(children, "parent_group", 2).with_dispatcher(Dispatcher::with_type(
+                DispatcherType::Named("topology".to_string()),
+            ))
+        })
+        .expect("Couldn't create the children group.");
+
+    let child = parent
+        .supervisor(|sp| sp)
+        .expect("Couldn't create the supervisor.");
+    let child_group = child
+        .children(|children| idle_group(children, "child_group", 3))
+        .expect("Couldn't create the children group.");
+
+    let expected = SupervisorNode {
+        id: parent.id().to_string(),
+        strategy: SupervisionStrategy::OneForOne,
+        supervisors: vec![SupervisorNode {
+            id: child.id().to_string(),
+            strategy: SupervisionStrategy::OneForOne,
+            supervisors: vec![],
+            children: vec![ChildrenNode {
+                id: child_group.id().to_string(),
+                name: "child_group".to_string(),
+                exec: None,
+                redundancy: 3,
+                live: 3,
+                dispatchers: vec![],
+            }],
+        }],
+        children: vec![ChildrenNode {
+            id: parent_group.id().to_string(),
+            name: "parent_group".to_string(),
+            exec: None,
+            redundancy: 2,
+            live: 2,
+            dispatchers: vec!["topology".to_string()],
+        }],
+    };
+
+    // The tree is launched asynchronously, so we wait for it to settle.
+    let mut snapshot = Bastion::topology();
+    assert!(wait_until(|| {
+        snapshot = Bastion::topology();
+        snapshot.supervisor(parent.id()) == Some(&expected)
+    }));
+
+    assert_eq!(snapshot.supervisor(parent.id()), Some(&expected));
+    assert!(snapshot
+        .supervisors
+        .iter()
+        .any(|supervisor| supervisor.id == parent.id().to_string()));
+
+    let json = serde_json::to_string(&snapshot).expect("Couldn't serialize the snapshot.");
+    let deserialized: TopologySnapshot =
+        serde_json::from_str(&json).expect("Couldn't deserialize the snapshot.");
+    assert_eq!(deserialized, snapshot);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
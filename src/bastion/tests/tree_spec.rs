@@ -0,0 +1,129 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_tree_spec() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_tree_spec() {
+        super::run()
+    }
+}
+
+const SPEC: &str = r#"{
+    "supervisors": [
+        {
+            "strategy": "OneForAll",
+            "children": [
+                { "name": "workers", "exec": "idle", "redundancy": 2, "dispatchers": ["spec"] }
+            ],
+            "supervisors": [
+                { "children": [{ "name": "nested", "exec": "idle" }] }
+            ]
+        },
+        {
+            "strategy": "RestForOne",
+            "children": [{ "name": "loggers", "exec": "idle", "redundancy": 3 }]
+        }
+    ]
+}"#;
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    Bastion::register_exec("idle", |ctx: BastionContext| async move {
+        loop {
+            ctx.recv().await?;
+        }
+    });
+
+    // A spec referencing an unregistered exec closure isn't built.
+    let missing = TreeSpec {
+        supervisors: vec![SupervisorSpec {
+            children: vec![ChildrenSpec {
+                name: None,
+                exec: "missing".to_string(),
+                redundancy: 1,
+                dispatchers: vec![],
+            }],
+            ..SupervisorSpec::default()
+        }],
+    };
+    assert_eq!(
+        Bastion::from_spec(missing).unwrap_err(),
+        SpecError::UnknownExec("missing".to_string())
+    );
+
+    let spec: TreeSpec = serde_json::from_str(SPEC).expect("Couldn't parse the spec.");
+    let supervisors = Bastion::from_spec(spec.clone()).expect("Couldn't build the spec.");
+    assert_eq!(supervisors.len(), 2);
+
+    // The tree is launched asynchronously, so we wait for every
+    // element to be launched.
+    let launched = |snapshot: &TopologySnapshot| {
+        let mut stack = snapshot.supervisors.iter().collect::<Vec<_>>();
+        while let Some(node) = stack.pop() {
+            if node
+                .children
+                .iter()
+                .any(|children| children.live != children.redundancy)
+            {
+                return false;
+            }
+            stack.extend(node.supervisors.iter());
+        }
+        true
+    };
+    let mut snapshot = Bastion::topology();
+    assert!(wait_until(|| {
+        snapshot = Bastion::topology();
+        launched(&snapshot) && Ok(&spec) == snapshot.to_spec().as_ref()
+    }));
+
+    // The built tree matches the spec, which it round-trips to.
+    assert!(launched(&snapshot));
+    assert_eq!(snapshot.to_spec(), Ok(spec.clone()));
+    for (supervisor, expected) in supervisors.iter().zip(spec.supervisors.iter()) {
+        let node = snapshot
+            .supervisor(supervisor.id())
+            .expect("The supervisor isn't part of the topology.");
+        assert_eq!(node.strategy, expected.strategy);
+        assert_eq!(node.to_spec().as_ref(), Ok(expected));
+    }
+
+    let workers = &snapshot.supervisor(supervisors[0].id()).unwrap().children[0];
+    assert_eq!(workers.name, "workers");
+    assert_eq!(workers.exec.as_deref(), Some("idle"));
+    assert_eq!(workers.live, 2);
+    assert_eq!(workers.dispatchers, vec!["spec".to_string()]);
+
+    // A group that wasn't built from a spec can't be described by one.
+    let group = supervisors[1]
+        .children(|children| {
+            children.with_exec(|ctx: BastionContext| async move {
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+        })
+        .expect("Couldn't create the children group.");
+
+    assert!(wait_until(|| Bastion::topology().to_spec().is_err()));
+    assert_eq!(
+        Bastion::topology().to_spec(),
+        Err(SpecError::UnregisteredExec(group.id().to_string()))
+    );
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
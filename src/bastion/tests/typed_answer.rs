@@ -0,0 +1,100 @@
+use bastion::prelude::*;
+use futures_timer::Delay;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_typed_answer() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_typed_answer() {
+        super::run()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum LookupError {
+    NotFound(&'static str),
+    Forbidden,
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    key: &'static str =!> {
+                        let answer: Result<u64, LookupError> = match key {
+                            "known" => Ok(42),
+                            "secret" => Err(LookupError::Forbidden),
+                            // The question is dropped without being answered.
+                            "ignored" => continue,
+                            "slow" => {
+                                Delay::new(Duration::from_secs(2)).await;
+                                Ok(0)
+                            }
+                            _ => Err(LookupError::NotFound(key)),
+                        };
+                        answer!(ctx, answer).ok();
+                    };
+                    key: usize =!> {
+                        // An answer of an unexpected type.
+                        answer!(ctx, key).unwrap();
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+    let child = &children.elems()[0];
+
+    let lookup = |key: &'static str| {
+        child
+            .ask_anonymously(key)
+            .unwrap()
+            .typed::<u64, LookupError>()
+            .with_timeout(Duration::from_millis(500))
+    };
+
+    assert_eq!(run!(lookup("known")).unwrap(), Ok(42));
+    assert_eq!(
+        run!(lookup("missing")).unwrap(),
+        Err(LookupError::NotFound("missing"))
+    );
+    assert_eq!(run!(lookup("secret")).unwrap(), Err(LookupError::Forbidden));
+
+    // The errors of the question itself are kept apart from the
+    // ones answered by the child.
+    match run!(lookup("ignored")) {
+        Err(AnswerError::Dropped) => (),
+        other => panic!("Unexpected answer: {:?}", other),
+    }
+    match run!(lookup("slow")) {
+        Err(AnswerError::Timeout(timeout)) => assert_eq!(timeout, Duration::from_millis(500)),
+        other => panic!("Unexpected answer: {:?}", other),
+    }
+    let answer = child
+        .ask_anonymously(7usize)
+        .unwrap()
+        .typed::<u64, LookupError>();
+    match run!(answer) {
+        Err(AnswerError::UnexpectedType(msg)) => msg! { msg,
+            answer: usize => assert_eq!(answer, 7);
+            _: _ => panic!("Unexpected message.");
+        },
+        other => panic!("Unexpected answer: {:?}", other),
+    }
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
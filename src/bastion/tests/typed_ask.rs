@@ -0,0 +1,66 @@
+use bastion::prelude::*;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_typed_ask() {
+        super::run().await
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    use bastion::prelude::*;
+
+    #[test]
+    fn test_typed_ask() {
+        run!(super::run())
+    }
+}
+
+async fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // The child answers the numbers it's asked with their double, the
+    // strings with a string (instead of a number) and drops the other
+    // questions.
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    number: u64 =!> {
+                        answer!(ctx, number * 2).unwrap();
+                    };
+                    text: &'static str =!> {
+                        answer!(ctx, text.to_string()).unwrap();
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+    let child = &children.elems()[0];
+
+    // The answer of the expected type is decoded...
+    let doubled = child.ask::<u64, u64>(21).await;
+    assert_eq!(doubled.unwrap(), 42);
+
+    // ...while an answer of another type is an error holding it...
+    match child.ask::<&'static str, u64>("not a number").await {
+        Err(AskError::Decode(msg)) => {
+            assert_eq!(msg.downcast::<String>().unwrap(), "not a number");
+        }
+        other => panic!("unexpected answer: {:?}", other),
+    }
+
+    // ...as is a question dropped without being answered.
+    match child.ask::<bool, u64>(true).await {
+        Err(AskError::Dropped) => (),
+        other => panic!("unexpected answer: {:?}", other),
+    }
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_typed_handlers() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_typed_handlers() {
+        super::run()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Resize {
+    width: u32,
+    height: u32,
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    // Which handler handled every message, and what it received.
+    let handled = Arc::new(Mutex::new(Vec::new()));
+    let handled_ref = handled.clone();
+    let children = Bastion::children(move |children| {
+        let on_str = handled_ref.clone();
+        let on_resize = handled_ref.clone();
+        let on_fallback = handled_ref.clone();
+        children
+            .on(move |_ctx, msg: &'static str| {
+                let handled = on_str.clone();
+                async move {
+                    handled.lock().unwrap().push(format!("str: {}", msg));
+                    Ok(())
+                }
+            })
+            .on(move |_ctx, resize: Resize| {
+                let handled = on_resize.clone();
+                async move {
+                    let resize = format!("resize: {}x{}", resize.width, resize.height);
+                    handled.lock().unwrap().push(resize);
+                    Ok(())
+                }
+            })
+            .on_question(|_ctx, n: u64, sender| async move { sender.reply(n * 2).map_err(|_| ()) })
+            .on_fallback(move |_ctx, msg: SignedMessage| {
+                let handled = on_fallback.clone();
+                async move {
+                    let (msg, _) = msg.extract();
+                    let msg = msg.downcast::<bool>().expect("unexpected message");
+                    handled.lock().unwrap().push(format!("fallback: {}", msg));
+                    Ok(())
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    let child = children.elems()[0].clone();
+
+    child.tell_anonymously("hello").unwrap();
+    child
+        .tell_anonymously(Resize {
+            width: 640,
+            height: 480,
+        })
+        .unwrap();
+    // Nothing handles a `bool` but the fallback handler...
+    child.tell_anonymously(true).unwrap();
+    child.tell_anonymously("world").unwrap();
+
+    assert!(wait_until(|| handled.lock().unwrap().len() == 4));
+    assert_eq!(
+        *handled.lock().unwrap(),
+        vec![
+            "str: hello".to_string(),
+            "resize: 640x480".to_string(),
+            "fallback: true".to_string(),
+            "str: world".to_string(),
+        ]
+    );
+
+    // ...while questions are answered by their own handler.
+    let answer = child.ask_anonymously(21u64).unwrap();
+    let answered = run!(async {
+        msg! { answer.await.expect("Couldn't receive the answer."),
+            n: u64 => n;
+            _: _ => 0;
+        }
+    });
+    assert_eq!(answered, 42);
+
+    // Broadcasted messages are handed to the handler of their type by
+    // every element, even though they share them.
+    let broadcasted = Arc::new(Mutex::new(Vec::new()));
+    let broadcasted_ref = broadcasted.clone();
+    let group = Bastion::children(move |children| {
+        let on_resize = broadcasted_ref.clone();
+        children
+            .with_redundancy(3)
+            .on(move |ctx, resize: Resize| {
+                let broadcasted = on_resize.clone();
+                async move {
+                    let id = ctx.current().id().clone();
+                    broadcasted.lock().unwrap().push((id, resize.width));
+                    Ok(())
+                }
+            })
+            // NOTE: the elements stop if the broadcast reaches this handler.
+            .on_fallback(|_ctx, _msg: SignedMessage| async move { Err(()) })
+    })
+    .expect("Couldn't create the children group.");
+
+    group
+        .broadcast(Resize {
+            width: 800,
+            height: 600,
+        })
+        .unwrap();
+
+    assert!(wait_until(|| broadcasted.lock().unwrap().len() == 3));
+    let received = broadcasted.lock().unwrap().clone();
+    assert!(received.iter().all(|(_, width)| *width == 800));
+    let receivers = received
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect::<HashSet<_>>();
+    let elems = group.elems().iter().map(|elem| elem.id().clone()).collect();
+    assert_eq!(receivers, elems);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
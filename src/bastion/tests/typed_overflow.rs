@@ -0,0 +1,103 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_typed_overflow() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_typed_overflow() {
+        super::run()
+    }
+}
+
+const CAPACITY: usize = 4;
+
+#[derive(Debug)]
+struct Ping(usize);
+
+#[derive(Debug)]
+struct Command(usize);
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let handled = Arc::new(Mutex::new(Vec::new()));
+    let released = Arc::new(AtomicBool::new(false));
+    let handled_ref = handled.clone();
+    let released_ref = released.clone();
+    let children = Bastion::children(move |children| {
+        children
+            .with_typed_overflow(
+                CAPACITY,
+                DropPriorities::new()
+                    .with_priority::<Ping>(0)
+                    .with_priority::<Command>(10),
+            )
+            .with_exec(move |ctx: BastionContext| {
+                let handled = handled_ref.clone();
+                let released = released_ref.clone();
+                async move {
+                    // The element doesn't handle its messages until
+                    // it is released, letting its mailbox fill up.
+                    while !released.load(Ordering::SeqCst) {
+                        Delay::new(Duration::from_millis(10)).await;
+                    }
+
+                    loop {
+                        msg! { ctx.recv().await?,
+                            command: Command => {
+                                handled.lock().unwrap().push(command.0);
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    // Twice as many messages as the mailbox holds are sent, half of
+    // them being pings and the other half commands.
+    let child = &children.elems()[0];
+    for i in 0..CAPACITY {
+        child.tell_anonymously(Command(i)).unwrap();
+        child.tell_anonymously(Ping(i)).unwrap();
+    }
+
+    // The pings are evicted to make room for the commands, and the
+    // last one is evicted right away since the mailbox is full of
+    // commands.
+    assert!(wait_until(|| Bastion::dead_letters_count() == CAPACITY));
+    let dead_letters = Bastion::drain_dead_letters();
+    assert_eq!(dead_letters.len(), CAPACITY);
+    let evicted = dead_letters
+        .iter()
+        .filter_map(|letter| letter.peek::<Ping>().map(|ping| ping.0))
+        .collect::<Vec<_>>();
+    assert_eq!(evicted, (0..CAPACITY).collect::<Vec<_>>());
+
+    // The commands were all kept.
+    released.store(true, Ordering::SeqCst);
+    assert!(wait_until(|| handled.lock().unwrap().len() == CAPACITY));
+    // Leaves the time for an evicted message to be (wrongly) handled.
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(*handled.lock().unwrap(), (0..CAPACITY).collect::<Vec<_>>());
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
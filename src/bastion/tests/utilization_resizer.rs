@@ -0,0 +1,88 @@
+#![cfg(feature = "scaling")]
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use futures_timer::Delay;
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_utilization_resizer() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_utilization_resizer() {
+        super::run()
+    }
+}
+
+const MIN: usize = 1;
+const MAX: usize = 4;
+
+fn live(supervisor: &SupervisorRef) -> usize {
+    Bastion::topology()
+        .supervisor(supervisor.id())
+        .and_then(|supervisor| supervisor.children.first().map(|group| group.live))
+        .unwrap_or(0)
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let supervisor = Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
+    let workers = supervisor
+        .children(|children| {
+            children
+                .with_resizer(
+                    UtilizationResizer::new(MIN, MAX)
+                        .with_target(1.0, 2.0)
+                        .with_cooldown(Duration::from_millis(100))
+                        .with_interval(Duration::from_millis(20)),
+                )
+                .with_exec(|ctx: BastionContext| async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            ref _job: usize => {
+                                // Every job takes a while to handle.
+                                Delay::new(Duration::from_millis(5)).await;
+                            };
+                            _: _ => ();
+                        }
+                    }
+                })
+        })
+        .expect("Couldn't create the children group.");
+
+    assert!(wait_until(|| live(&supervisor) == MIN));
+
+    // Sustained load makes the group grow up to `max`...
+    let mut job = 0usize;
+    assert!(wait_until(|| {
+        let grown = live(&supervisor) >= MAX;
+        if !grown {
+            for _ in 0..10 {
+                workers.broadcast(job).unwrap();
+                job += 1;
+            }
+        }
+        grown
+    }));
+    assert_eq!(live(&supervisor), MAX);
+
+    // ...and once the jobs are drained, it shrinks back to `min`
+    // after the cooldowns.
+    assert!(wait_until(|| live(&supervisor) == MIN));
+    thread::sleep(Duration::from_millis(300));
+    assert_eq!(live(&supervisor), MIN);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio-runtime")]
+mod tokio_tests {
+    #[tokio::test]
+    async fn test_watch() {
+        super::run()
+    }
+}
+
+#[cfg(not(feature = "tokio-runtime"))]
+mod not_tokio_tests {
+    #[test]
+    fn test_watch() {
+        super::run()
+    }
+}
+
+// Creates a group whose element watches (or only watches then unwatches)
+// the given target, returning whether it's watching and the `Down`
+// messages it received.
+fn watcher(target: ChildRef, unwatch: bool) -> (Arc<AtomicBool>, Arc<Mutex<Vec<Down>>>) {
+    let watching = Arc::new(AtomicBool::new(false));
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let watching_ref = watching.clone();
+    let received_ref = received.clone();
+    Bastion::children(move |children| {
+        let target = target.clone();
+        let watching = watching_ref.clone();
+        let received = received_ref.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let target = target.clone();
+            let watching = watching.clone();
+            let received = received.clone();
+            async move {
+                ctx.watch(&target);
+                if unwatch {
+                    ctx.unwatch(&target);
+                }
+                watching.store(true, Ordering::SeqCst);
+
+                loop {
+                    msg! { ctx.recv().await?,
+                        down: Down => {
+                            received.lock().unwrap().push(down);
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    (watching, received)
+}
+
+fn run() {
+    Bastion::init();
+    Bastion::start();
+
+    let target = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                ctx.recv().await?;
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+    let target = target.elems()[0].clone();
+
+    let (watching, received) = watcher(target.clone(), false);
+    let (unwatched, not_received) = watcher(target.clone(), true);
+    assert!(wait_until(
+        || watching.load(Ordering::SeqCst) && unwatched.load(Ordering::SeqCst)
+    ));
+
+    target.kill().unwrap();
+
+    // The watcher is told which element terminated and why...
+    assert!(wait_until(|| !received.lock().unwrap().is_empty()));
+    {
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(*received[0].path, *target.path());
+        assert_eq!(received[0].reason, DownReason::Killed);
+    }
+
+    // ...unlike the one which stopped watching it.
+    thread::sleep(Duration::from_millis(100));
+    assert!(not_received.lock().unwrap().is_empty());
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}